@@ -109,6 +109,12 @@ async fn timed_search(
     let url = format!("{}/search", target.base_url.trim_end_matches('/'));
     let body = SearchRequest {
         query: query.to_string(),
+        max_results: None,
+        format: None,
+        highlights: false,
+        snippet_max_chars: None,
+        include_fields: None,
+        max_response_bytes: None,
     };
 
     let start = Instant::now();
@@ -0,0 +1,388 @@
+//! Optional API key authentication for the HTTP server.
+//!
+//! When `CORTEX_SCOUT_API_KEYS` (or `SHADOWCRAWL_API_KEYS`) holds a comma-separated key
+//! list, every HTTP route except `/health` requires one of them, presented as
+//! `Authorization: Bearer <key>` or `X-Api-Key: <key>`. Keys can carry a requests/minute
+//! budget from the JSON file named by `CORTEX_SCOUT_API_KEY_LIMITS_FILE`:
+//!
+//! ```json
+//! { "default_requests_per_minute": 60, "keys": { "ci-runner": 600 } }
+//! ```
+//!
+//! Keys without an entry use the default; with no default they are unlimited. The MCP
+//! stdio transport never goes through this layer.
+
+use crate::core::budget;
+use crate::core::config::env_var;
+use crate::core::error::ScoutError;
+use anyhow::Context;
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tower_http::cors::{Any, CorsLayer};
+use tracing::warn;
+
+/// Routes reachable without a key, so load balancers can probe the server.
+pub const EXEMPT_PATHS: &[&str] = &["/health", "/health/live", "/health/ready"];
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+type KeyDigest = [u8; 32];
+
+#[derive(Debug, Default, Deserialize)]
+struct LimitsFile {
+    #[serde(default)]
+    default_requests_per_minute: Option<u32>,
+    #[serde(default)]
+    keys: HashMap<String, u32>,
+}
+
+#[derive(Debug)]
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+/// The configured keys and their per-minute request counters.
+///
+/// Keys are held as SHA-256 digests so a lookup never compares secrets byte by byte.
+#[derive(Debug)]
+pub struct ApiKeyAuth {
+    limits: HashMap<KeyDigest, Option<u32>>,
+    windows: Mutex<HashMap<KeyDigest, Window>>,
+}
+
+fn digest(key: &str) -> KeyDigest {
+    Sha256::digest(key.as_bytes()).into()
+}
+
+impl ApiKeyAuth {
+    /// `keys` is the comma-separated key list; `limits_json` the optional limits file body.
+    /// Returns `None` when the list has no keys.
+    pub fn new(keys: &str, limits_json: Option<&str>) -> anyhow::Result<Option<Self>> {
+        let limits_file: LimitsFile = match limits_json {
+            Some(raw) => serde_json::from_str(raw).context("invalid API key limits file")?,
+            None => LimitsFile::default(),
+        };
+        let mut limits = HashMap::new();
+        for key in keys.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+            let per_minute = limits_file
+                .keys
+                .get(key)
+                .copied()
+                .or(limits_file.default_requests_per_minute)
+                .filter(|n| *n > 0);
+            limits.insert(digest(key), per_minute);
+        }
+        if limits.is_empty() {
+            return Ok(None);
+        }
+        let unknown = limits_file
+            .keys
+            .keys()
+            .filter(|key| !limits.contains_key(&digest(key)))
+            .count();
+        if unknown > 0 {
+            warn!(
+                "API key limits file lists {} key(s) not in the key list; ignoring them",
+                unknown
+            );
+        }
+        Ok(Some(Self {
+            limits,
+            windows: Mutex::new(HashMap::new()),
+        }))
+    }
+
+    /// Reads `CORTEX_SCOUT_API_KEYS` and `CORTEX_SCOUT_API_KEY_LIMITS_FILE` (each also
+    /// under its `SHADOWCRAWL_` name). A limits file that cannot be read is an error:
+    /// starting without the budgets an operator asked for would be worse than not starting.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Some(keys) = env_var(&["CORTEX_SCOUT_API_KEYS", "SHADOWCRAWL_API_KEYS"]) else {
+            return Ok(None);
+        };
+        let limits = match env_var(&[
+            "CORTEX_SCOUT_API_KEY_LIMITS_FILE",
+            "SHADOWCRAWL_API_KEY_LIMITS_FILE",
+        ]) {
+            Some(path) => Some(
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read API key limits file {}", path))?,
+            ),
+            None => None,
+        };
+        Self::new(&keys, limits.as_deref())
+    }
+
+    pub fn key_count(&self) -> usize {
+        self.limits.len()
+    }
+
+    /// Check the request's key and book one request against its budget.
+    pub fn authorize(&self, headers: &HeaderMap) -> Result<(), ScoutError> {
+        self.authorize_at(headers, Instant::now())
+    }
+
+    fn authorize_at(&self, headers: &HeaderMap, now: Instant) -> Result<(), ScoutError> {
+        let presented = presented_key(headers).ok_or_else(|| {
+            ScoutError::unauthorized(
+                "Missing API key: send `Authorization: Bearer <key>` or `X-Api-Key: <key>`",
+            )
+        })?;
+        let key = digest(presented);
+        let Some(limit) = self.limits.get(&key).copied() else {
+            return Err(ScoutError::unauthorized("Invalid API key"));
+        };
+        let Some(limit) = limit else {
+            return Ok(());
+        };
+
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let window = windows.entry(key).or_insert(Window {
+            started: now,
+            count: 0,
+        });
+        if now.duration_since(window.started) >= WINDOW {
+            window.started = now;
+            window.count = 0;
+        }
+        if window.count >= limit {
+            let retry_after = WINDOW
+                .saturating_sub(now.duration_since(window.started))
+                .as_secs()
+                .max(1);
+            return Err(ScoutError::rate_limited(format!(
+                "API key rate limit of {} requests/minute exceeded",
+                limit
+            ))
+            .with_details(serde_json::json!({
+                "limit_per_minute": limit,
+                "retry_after_secs": retry_after,
+            })));
+        }
+        window.count += 1;
+        Ok(())
+    }
+}
+
+/// The budget caller id for a key: `key:` and a prefix of its SHA-256, so the id can be
+/// logged and put in `CORTEX_SCOUT_BUDGET_OVERRIDE` without revealing the key.
+pub fn key_caller_id(key: &str) -> String {
+    let hex: String = digest(key)[..6]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("key:{}", hex)
+}
+
+fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| {
+            let (scheme, token) = v.trim().split_once(' ')?;
+            scheme.eq_ignore_ascii_case("bearer").then(|| token.trim())
+        });
+    bearer
+        .or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+}
+
+/// Axum middleware enforcing [`ApiKeyAuth`]. Rejections use the usual error body, with
+/// `WWW-Authenticate` on 401 and `Retry-After` on 429. Accepted requests run as the key's
+/// budget caller ([`key_caller_id`]).
+pub async fn require_api_key(
+    State(auth): State<Arc<ApiKeyAuth>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    // CORS preflights never carry credentials.
+    if req.method() == Method::OPTIONS || EXEMPT_PATHS.contains(&req.uri().path()) {
+        return next.run(req).await;
+    }
+    match auth.authorize(req.headers()) {
+        Ok(()) => {
+            let caller = presented_key(req.headers())
+                .map_or_else(|| budget::GLOBAL_CALLER.to_string(), key_caller_id);
+            budget::scope(caller, next.run(req)).await
+        }
+        Err(err) => {
+            let retry_after = err
+                .details
+                .as_ref()
+                .and_then(|d| d.get("retry_after_secs"))
+                .and_then(|v| v.as_u64());
+            let unauthorized = err.kind == crate::core::error::ErrorKind::Unauthorized;
+            let mut response = err.into_http().into_response();
+            if unauthorized {
+                response
+                    .headers_mut()
+                    .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            }
+            if let Some(secs) = retry_after {
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+            }
+            response
+        }
+    }
+}
+
+/// CORS for the HTTP server. `origins` is a comma-separated allow list (`*` allows any).
+/// Unset, the server stays fully permissive until API keys are configured, after which
+/// cross-origin browser access has to be opted into.
+pub fn cors_layer(origins: Option<&str>, auth_enabled: bool) -> CorsLayer {
+    let Some(origins) = origins.map(str::trim).filter(|o| !o.is_empty()) else {
+        return if auth_enabled {
+            CorsLayer::new()
+        } else {
+            CorsLayer::permissive()
+        };
+    };
+    if origins.split(',').any(|o| o.trim() == "*") {
+        return CorsLayer::permissive();
+    }
+    let allowed: Vec<HeaderValue> = origins
+        .split(',')
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+        .filter_map(|o| match HeaderValue::from_str(o) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                warn!("Ignoring invalid CORS origin {:?}", o);
+                None
+            }
+        })
+        .collect();
+    CorsLayer::new()
+        .allow_origin(allowed)
+        .allow_methods(Any)
+        .allow_headers(Any)
+        .expose_headers([crate::core::request_id::HEADER.clone()])
+}
+
+/// [`cors_layer`] with origins from `CORTEX_SCOUT_CORS_ORIGINS` / `SHADOWCRAWL_CORS_ORIGINS`.
+pub fn cors_layer_from_env(auth_enabled: bool) -> CorsLayer {
+    let origins = env_var(&["CORTEX_SCOUT_CORS_ORIGINS", "SHADOWCRAWL_CORS_ORIGINS"]);
+    cors_layer(origins.as_deref(), auth_enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    fn app(limits: Option<&str>) -> Router {
+        let auth = ApiKeyAuth::new("alpha, beta", limits).unwrap().unwrap();
+        Router::new()
+            .route("/health", get(|| async { "ok" }))
+            .route("/search", get(|| async { "results" }))
+            .route("/whoami", get(|| async { budget::current_caller() }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(auth),
+                require_api_key,
+            ))
+    }
+
+    async fn call(app: &Router, path: &str, headers: &[(&str, &str)]) -> Response {
+        let mut req = Request::builder().uri(path);
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        app.clone()
+            .oneshot(req.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn error_code(response: Response) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        json["code"].as_str().unwrap_or_default().to_string()
+    }
+
+    #[tokio::test]
+    async fn missing_and_wrong_keys_are_rejected() {
+        let app = app(None);
+
+        let missing = call(&app, "/search", &[]).await;
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(missing.headers()[header::WWW_AUTHENTICATE], "Bearer");
+        assert_eq!(error_code(missing).await, "unauthorized");
+
+        let wrong = call(&app, "/search", &[("authorization", "Bearer gamma")]).await;
+        assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+
+        let health = call(&app, "/health", &[]).await;
+        assert_eq!(health.status(), StatusCode::OK, "/health stays open");
+
+        let bearer = call(&app, "/search", &[("authorization", "bearer alpha")]).await;
+        assert_eq!(bearer.status(), StatusCode::OK);
+        let header_key = call(&app, "/search", &[("x-api-key", "beta")]).await;
+        assert_eq!(header_key.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn per_key_budget_is_enforced() {
+        let app = app(Some(
+            r#"{ "default_requests_per_minute": 2, "keys": { "beta": 5 } }"#,
+        ));
+        let alpha = [("x-api-key", "alpha")];
+        for _ in 0..2 {
+            assert_eq!(call(&app, "/search", &alpha).await.status(), StatusCode::OK);
+        }
+        let limited = call(&app, "/search", &alpha).await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(limited.headers().contains_key(header::RETRY_AFTER));
+        assert_eq!(error_code(limited).await, "rate_limited");
+
+        // Budgets are per key.
+        let beta = call(&app, "/search", &[("x-api-key", "beta")]).await;
+        assert_eq!(beta.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn requests_run_as_the_key_budget_caller() {
+        let app = app(None);
+        let whoami = call(&app, "/whoami", &[("x-api-key", "alpha")]).await;
+        let body = axum::body::to_bytes(whoami.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let caller = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(caller, key_caller_id("alpha"));
+        assert!(caller.starts_with("key:") && !caller.contains("alpha"));
+        assert_ne!(caller, key_caller_id("beta"));
+    }
+
+    #[test]
+    fn budget_resets_after_a_minute() {
+        let auth = ApiKeyAuth::new("alpha", Some(r#"{ "keys": { "alpha": 1 } }"#))
+            .unwrap()
+            .unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("alpha"));
+        let start = Instant::now();
+        assert!(auth.authorize_at(&headers, start).is_ok());
+        let err = auth
+            .authorize_at(&headers, start + Duration::from_secs(45))
+            .unwrap_err();
+        assert_eq!(err.details.unwrap()["retry_after_secs"], 15);
+        assert!(auth.authorize_at(&headers, start + WINDOW).is_ok());
+
+        assert!(ApiKeyAuth::new(" , ", None).unwrap().is_none());
+    }
+}
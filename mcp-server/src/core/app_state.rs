@@ -14,15 +14,37 @@ pub struct AppState {
     pub tool_registry: std::sync::Arc<crate::core::tools_registry::ToolRegistry>,
     pub search_service: std::sync::Arc<dyn crate::tools::search::SearchService>,
     // Caches for performance
-    pub search_cache: moka::future::Cache<String, Vec<super::types::SearchResult>>, // key: query
-    pub scrape_cache: moka::future::Cache<String, super::types::ScrapeResponse>,    // key: url
+    pub search_cache: moka::future::Cache<String, crate::tools::search::CachedSearch>, // key: query
+    pub scrape_cache: moka::future::Cache<String, super::types::ScrapeResponse>,       // key: url
+    pub setup_cache: moka::future::Cache<String, crate::setup::SetupStatus>, // key: check ids
+    // ETags served by `/search` and `/scrape`, for `If-None-Match` (key: endpoint + request)
+    pub http_validators: moka::future::Cache<String, crate::core::http_cache::Validator>,
     // Concurrency control for external calls
     pub outbound_limit: std::sync::Arc<tokio::sync::Semaphore>,
+    // Permits `outbound_limit` was created with (`OUTBOUND_LIMIT`), for saturation reporting
+    pub outbound_capacity: usize,
+    // Per-origin robots.txt cache (only consulted when a scrape sets `respect_robots`)
+    pub robots_policy: std::sync::Arc<crate::scraping::robots::RobotsPolicy>,
+    // Per-domain token buckets paced before every scrape request
+    pub domain_limiter: std::sync::Arc<crate::core::rate_limit::DomainRateLimiter>,
+    // Per-site cookie jars for scrapes that set `persist_session`
+    pub cookie_jars: std::sync::Arc<crate::scraping::cookie_jar::CookieJars>,
+    // Tool/engine/cache counters and latency histograms served at `GET /metrics`
+    pub metrics: std::sync::Arc<crate::core::metrics::Metrics>,
+    // Rolling per-engine health and block-rate benching, served at `GET /engines/status`
+    pub engine_stats: std::sync::Arc<crate::tools::search::engine_stats::EngineStats>,
+    // Per-engine rate-limit cool-downs (429 / captcha), optionally persisted across restarts
+    pub engine_backoff: std::sync::Arc<crate::tools::search::engine_backoff::EngineBackoff>,
     // Memory manager for research history — late-initialized in background to avoid
     // blocking MCP startup. Access via `.read().unwrap().clone()`.
     pub memory: std::sync::Arc<std::sync::RwLock<Option<std::sync::Arc<crate::history::MemoryManager>>>>,
     memory_state: std::sync::Arc<std::sync::RwLock<MemoryInitState>>,
     memory_ready: std::sync::Arc<tokio::sync::Notify>,
+    // Circuit breaker and write queue for history logging, so a failing LanceDB degrades
+    // quietly instead of failing or slowing searches and scrapes
+    pub memory_guard: std::sync::Arc<crate::memory_guard::MemoryGuard>,
+    // Hourly search / scrape / browser budgets per caller, charged in `handlers::run_call`
+    pub budgets: std::sync::Arc<crate::core::budget::Budgets>,
     // Proxy manager for dynamic IP rotation (optional)
     pub proxy_manager: Option<std::sync::Arc<crate::proxy_manager::ProxyManager>>,
 
@@ -32,8 +54,17 @@ pub struct AppState {
     // Shared persistent browser instance (tab reuse — avoids launch overhead per request).
     pub browser_pool: Option<std::sync::Arc<crate::scraping::browser_manager::BrowserPool>>,
 
+    /// Tool calls in flight on `POST /mcp`, by JSON-RPC id, for `notifications/cancelled`.
+    pub mcp_calls: std::sync::Arc<crate::mcp::cancellation::CallRegistry>,
+
+    /// Scheduled change monitors, checked in the background by `monitor::spawn_scheduler`.
+    pub monitors: std::sync::Arc<crate::tools::monitor::Monitors>,
+
     /// File-based config loaded from `cortex-scout.json` (env-var fallback for all fields).
     pub shadow_config: std::sync::Arc<crate::core::config::ShadowConfig>,
+
+    /// Per-component startup timing, served at `GET /debug/startup`.
+    pub startup: std::sync::Arc<crate::core::startup::StartupTracker>,
 }
 
 impl std::fmt::Debug for AppState {
@@ -67,20 +98,65 @@ impl AppState {
             search_service,
             search_cache: moka::future::Cache::builder()
                 .max_capacity(10_000)
-                .time_to_live(std::time::Duration::from_secs(60 * 10))
+                .time_to_live(std::time::Duration::from_secs(
+                    crate::core::config::SEARCH_CACHE_TTL_SECS,
+                ))
                 .build(),
+            // Weighed by approximate result size so a few huge pages cannot pin gigabytes.
+            // Entries outlive their freshness TTL by the revalidation window; stale ones are
+            // only served again after a conditional re-fetch answers 304.
             scrape_cache: moka::future::Cache::builder()
+                .max_capacity(crate::core::config::scrape_cache_max_bytes())
+                .weigher(|key: &String, value: &super::types::ScrapeResponse| {
+                    crate::scrape::cached_scrape_weight(key, value)
+                })
+                .time_to_live(std::time::Duration::from_secs(
+                    crate::core::config::scrape_cache_ttl_secs()
+                        + crate::core::config::scrape_cache_revalidate_window_secs(),
+                ))
+                .build(),
+            setup_cache: moka::future::Cache::builder()
+                .max_capacity(64)
+                .time_to_live(std::time::Duration::from_secs(
+                    crate::core::config::setup_cache_ttl_secs().max(1),
+                ))
+                .build(),
+            http_validators: moka::future::Cache::builder()
                 .max_capacity(10_000)
-                .time_to_live(std::time::Duration::from_secs(60 * 30))
+                .time_to_live(std::time::Duration::from_secs(
+                    crate::core::config::scrape_cache_ttl_secs()
+                        .max(crate::core::config::SEARCH_CACHE_TTL_SECS),
+                ))
                 .build(),
             outbound_limit: std::sync::Arc::new(tokio::sync::Semaphore::new(outbound_limit)),
+            outbound_capacity: outbound_limit,
+            robots_policy: std::sync::Arc::new(crate::scraping::robots::RobotsPolicy::new(
+                std::time::Duration::from_secs(crate::core::config::robots_cache_ttl_secs()),
+            )),
+            domain_limiter: std::sync::Arc::new(crate::core::rate_limit::DomainRateLimiter::new(
+                crate::core::config::domain_rate_limit(),
+                crate::core::config::domain_rate_burst(),
+            )),
+            cookie_jars: std::sync::Arc::new(crate::scraping::cookie_jar::CookieJars::from_env()),
+            metrics: std::sync::Arc::new(crate::core::metrics::Metrics::new()),
+            engine_stats: std::sync::Arc::new(
+                crate::tools::search::engine_stats::EngineStats::from_env(),
+            ),
+            engine_backoff: std::sync::Arc::new(
+                crate::tools::search::engine_backoff::EngineBackoff::from_env(),
+            ),
             memory: std::sync::Arc::new(std::sync::RwLock::new(None)), // Late-initialized in background
             memory_state: std::sync::Arc::new(std::sync::RwLock::new(memory_state)),
             memory_ready: std::sync::Arc::new(tokio::sync::Notify::new()),
+            memory_guard: std::sync::Arc::new(crate::memory_guard::MemoryGuard::from_env()),
+            budgets: std::sync::Arc::new(crate::core::budget::Budgets::from_env()),
             proxy_manager: None, // Will be initialized if IP_LIST_PATH exists
             non_robot_search_lock: std::sync::Arc::new(tokio::sync::Mutex::new(())),
             browser_pool: crate::scraping::browser_manager::BrowserPool::new_auto(),
+            mcp_calls: std::sync::Arc::new(crate::mcp::cancellation::CallRegistry::default()),
+            monitors: std::sync::Arc::new(crate::tools::monitor::Monitors::from_env()),
             shadow_config: std::sync::Arc::new(crate::core::config::load_shadow_config()),
+            startup: std::sync::Arc::default(),
         }
     }
 
@@ -90,8 +166,9 @@ impl AppState {
     }
 
     pub fn install_memory(&self, memory: std::sync::Arc<crate::history::MemoryManager>) {
-        *self.memory.write().unwrap() = Some(memory);
+        *self.memory.write().unwrap() = Some(memory.clone());
         *self.memory_state.write().unwrap() = MemoryInitState::Ready;
+        self.memory_guard.attach(memory);
         self.memory_ready.notify_waiters();
     }
 
@@ -132,6 +209,28 @@ impl AppState {
         self.get_memory()
     }
 
+    /// Memory health for `GET /health` and the `memory_status` tool: the breaker's report,
+    /// with `status` saying why memory is off when it never came up.
+    pub fn memory_health(&self) -> crate::memory_guard::MemoryHealthReport {
+        let mut report = self.memory_guard.report();
+        match *self.memory_state.read().unwrap() {
+            MemoryInitState::Disabled => report.status = "disabled".to_string(),
+            MemoryInitState::Pending => report.status = "initializing".to_string(),
+            MemoryInitState::Failed => report.status = "failed".to_string(),
+            MemoryInitState::Ready => {}
+        }
+        report
+    }
+
+    /// Replace the default registry, e.g. with one that applies the deployment's tool policy.
+    pub fn with_tool_registry(
+        mut self,
+        tool_registry: crate::core::tools_registry::ToolRegistry,
+    ) -> Self {
+        self.tool_registry = std::sync::Arc::new(tool_registry);
+        self
+    }
+
     pub fn with_proxy_manager(
         mut self,
         proxy_manager: std::sync::Arc<crate::proxy_manager::ProxyManager>,
@@ -139,4 +238,12 @@ impl AppState {
         self.proxy_manager = Some(proxy_manager);
         self
     }
+
+    pub fn with_startup(
+        mut self,
+        startup: std::sync::Arc<crate::core::startup::StartupTracker>,
+    ) -> Self {
+        self.startup = startup;
+        self
+    }
 }
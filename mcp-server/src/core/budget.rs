@@ -0,0 +1,651 @@
+//! Hourly search / scrape / browser budgets per caller, charged before a tool call runs.
+//!
+//! The caller is the API key when HTTP auth is on (`key:<hash prefix>`, see
+//! [`crate::core::api_auth`]), the MCP client on stdio (`stdio:<client name>`), and
+//! [`GLOBAL_CALLER`] otherwise. Transports set it with [`scope`]; `mcp::handlers::run_call`
+//! charges the call's [`Cost`] with [`Budgets::charge`]. A call is admitted only if every
+//! budget it touches has room, and all of them are booked under one lock, so parallel
+//! calls cannot overshoot a limit. Refusals are `rate_limited` errors naming the budget and
+//! when it resets.
+//!
+//! Each caller's window starts with its first charged call and lasts an hour. Limits come
+//! from `CORTEX_SCOUT_BUDGET_SEARCHES_PER_HOUR`, `..._SCRAPES_PER_HOUR` and
+//! `..._BROWSER_PER_HOUR` (unset or `0`: unlimited). With any limit set the counters are
+//! saved to [`crate::core::config::budget_file`] so a restart does not hand a runaway client
+//! a fresh hour (written on the blocking pool, one write per burst of calls); `CORTEX_SCOUT_BUDGET_RESET=1` discards them on start instead. For
+//! emergencies `CORTEX_SCOUT_BUDGET_OVERRIDE` lists caller ids exempt from enforcement
+//! (`*` for all); their calls are still counted.
+
+use crate::core::error::ScoutError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// Caller for calls that carry no identity, e.g. over HTTP without API keys.
+pub const GLOBAL_CALLER: &str = "global";
+
+pub const WINDOW_SECS: u64 = 60 * 60;
+
+tokio::task_local! {
+    static CALLER: String;
+}
+
+/// Run `fut` with [`current_caller`] returning `caller`.
+pub async fn scope<F: Future>(caller: String, fut: F) -> F::Output {
+    CALLER.scope(caller, fut).await
+}
+
+/// The caller this task is serving, or [`GLOBAL_CALLER`] outside a [`scope`].
+pub fn current_caller() -> String {
+    CALLER
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| GLOBAL_CALLER.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetKind {
+    Searches,
+    Scrapes,
+    /// Browser-rendered scrapes and browser tools; also counted as scrapes where they are.
+    Browser,
+}
+
+impl BudgetKind {
+    pub const ALL: [BudgetKind; 3] = [
+        BudgetKind::Searches,
+        BudgetKind::Scrapes,
+        BudgetKind::Browser,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BudgetKind::Searches => "searches",
+            BudgetKind::Scrapes => "scrapes",
+            BudgetKind::Browser => "browser",
+        }
+    }
+}
+
+/// Units a tool call books against each budget.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cost {
+    pub searches: u64,
+    pub scrapes: u64,
+    pub browser: u64,
+}
+
+impl Cost {
+    fn get(&self, kind: BudgetKind) -> u64 {
+        match kind {
+            BudgetKind::Searches => self.searches,
+            BudgetKind::Scrapes => self.scrapes,
+            BudgetKind::Browser => self.browser,
+        }
+    }
+
+    pub fn is_free(&self) -> bool {
+        *self == Cost::default()
+    }
+}
+
+/// Whether a scrape's arguments ask for browser rendering: `quality_mode` `aggressive` /
+/// `high` (browser first) or a `screenshot`.
+fn renders_in_browser(arguments: &Value) -> bool {
+    let mode = arguments.get("quality_mode").and_then(Value::as_str);
+    matches!(
+        mode.map(|m| m.trim().to_ascii_lowercase()).as_deref(),
+        Some("aggressive" | "high")
+    ) || arguments
+        .get("screenshot")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// What `tool` (internal name) costs with `arguments`. Batches cost one scrape per URL and
+/// a crawl its page cap (`max_pages`, default 50), charged up front. Research tools are
+/// charged their whole fan-out: `research` its `top_n` pages, `deep_research` every
+/// sub-query and `depth` × `max_sources` × the URL discovery multiplier pages. A static
+/// scrape that escalates to the browser is charged that browser use when it happens (see
+/// `tools::scrape`). Status, history and monitor tools are free.
+pub fn cost(tool: &str, arguments: &Value) -> Cost {
+    let scrapes = |n: u64| Cost {
+        scrapes: n,
+        browser: if renders_in_browser(arguments) { n } else { 0 },
+        ..Cost::default()
+    };
+    let urls = || {
+        arguments
+            .get("urls")
+            .and_then(Value::as_array)
+            .map_or(1, |urls| urls.len().max(1) as u64)
+    };
+    // An integer argument as its handler reads it: `default` when absent, within 1..=max.
+    let count = |key: &str, default: u64, max: u64| {
+        arguments
+            .get(key)
+            .and_then(Value::as_u64)
+            .unwrap_or(default)
+            .clamp(1, max)
+    };
+    match tool {
+        "search_web" | "search_structured" => Cost {
+            searches: 1,
+            ..Cost::default()
+        },
+        "research" => Cost {
+            searches: 1,
+            ..scrapes(count("top_n", 5, 10))
+        },
+        "deep_research" => {
+            let per_hop = count("max_sources", 10, 20)
+                * crate::tools::deep_research::url_discovery_multiplier() as u64;
+            Cost {
+                searches: crate::tools::deep_research::query_cap() as u64,
+                ..scrapes(count("depth", 1, 3) * per_hop)
+            }
+        }
+        "non_robot_search" => Cost {
+            searches: 1,
+            browser: 1,
+            ..Cost::default()
+        },
+        "scrape_url" | "fetch_then_extract" | "extract_structured" | "scrape_diff"
+        | "scrape_compare" => scrapes(1),
+        "scrape_batch" | "extract_batch" => scrapes(urls()),
+        "crawl_website" => scrapes(
+            arguments
+                .get("max_pages")
+                .and_then(Value::as_u64)
+                .unwrap_or(50)
+                .max(1),
+        ),
+        "visual_scout" | "human_auth_session" | "browser_automate" | "agent_profile_auth" => Cost {
+            browser: 1,
+            ..Cost::default()
+        },
+        _ => Cost::default(),
+    }
+}
+
+/// Per-hour limits; `None` is unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BudgetPolicy {
+    pub searches_per_hour: Option<u64>,
+    pub scrapes_per_hour: Option<u64>,
+    pub browser_per_hour: Option<u64>,
+    /// Callers exempt from enforcement (`*` matches all).
+    pub override_callers: Vec<String>,
+}
+
+fn env_limit(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|n| *n > 0)
+}
+
+impl BudgetPolicy {
+    pub fn from_env() -> Self {
+        Self {
+            searches_per_hour: env_limit("CORTEX_SCOUT_BUDGET_SEARCHES_PER_HOUR"),
+            scrapes_per_hour: env_limit("CORTEX_SCOUT_BUDGET_SCRAPES_PER_HOUR"),
+            browser_per_hour: env_limit("CORTEX_SCOUT_BUDGET_BROWSER_PER_HOUR"),
+            override_callers: std::env::var("CORTEX_SCOUT_BUDGET_OVERRIDE")
+                .unwrap_or_default()
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn limit(&self, kind: BudgetKind) -> Option<u64> {
+        match kind {
+            BudgetKind::Searches => self.searches_per_hour,
+            BudgetKind::Scrapes => self.scrapes_per_hour,
+            BudgetKind::Browser => self.browser_per_hour,
+        }
+    }
+
+    /// Whether any budget is limited.
+    pub fn is_limited(&self) -> bool {
+        BudgetKind::ALL
+            .iter()
+            .any(|kind| self.limit(*kind).is_some())
+    }
+
+    pub fn is_overridden(&self, caller: &str) -> bool {
+        self.override_callers
+            .iter()
+            .any(|c| c == "*" || c == caller)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Usage {
+    /// Unix seconds the caller's current window started.
+    window_start: u64,
+    searches: u64,
+    scrapes: u64,
+    browser: u64,
+}
+
+impl Usage {
+    fn used(&self, kind: BudgetKind) -> u64 {
+        match kind {
+            BudgetKind::Searches => self.searches,
+            BudgetKind::Scrapes => self.scrapes,
+            BudgetKind::Browser => self.browser,
+        }
+    }
+
+    fn add(&mut self, cost: &Cost) {
+        self.searches = self.searches.saturating_add(cost.searches);
+        self.scrapes = self.scrapes.saturating_add(cost.scrapes);
+        self.browser = self.browser.saturating_add(cost.browser);
+    }
+
+    fn resets_at(&self) -> u64 {
+        self.window_start + WINDOW_SECS
+    }
+}
+
+/// One budget in a [`BudgetStatus`].
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetLine {
+    pub budget: BudgetKind,
+    /// `None` when unlimited.
+    pub limit_per_hour: Option<u64>,
+    pub used: u64,
+    pub remaining: Option<u64>,
+}
+
+/// A caller's budgets, as reported by the `budget_status` tool.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetStatus {
+    pub caller: String,
+    /// `false` when no limit is set or the caller is in `CORTEX_SCOUT_BUDGET_OVERRIDE`.
+    pub enforced: bool,
+    /// When the current window ends (RFC 3339); `None` before the first charged call.
+    pub resets_at: Option<String>,
+    pub budgets: Vec<BudgetLine>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn rfc3339(unix_secs: u64) -> String {
+    chrono::DateTime::from_timestamp(unix_secs as i64, 0)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+pub struct Budgets {
+    policy: BudgetPolicy,
+    path: Option<PathBuf>,
+    usage: Arc<Mutex<BTreeMap<String, Usage>>>,
+    /// Set while a save is queued; charges booked meanwhile are written by that save.
+    save_queued: Arc<AtomicBool>,
+    /// Held while writing, so a save never overwrites a newer one.
+    writer: Arc<Mutex<()>>,
+}
+
+impl Default for Budgets {
+    fn default() -> Self {
+        Self::new(BudgetPolicy::default(), None)
+    }
+}
+
+impl Budgets {
+    /// Budgets under `policy`, saved to `path` when given (loaded from it if present).
+    pub fn new(policy: BudgetPolicy, path: Option<PathBuf>) -> Self {
+        let usage = path
+            .as_ref()
+            .and_then(|p| std::fs::read(p).ok())
+            .and_then(|raw| match serde_json::from_slice(&raw) {
+                Ok(usage) => Some(usage),
+                Err(e) => {
+                    warn!("ignoring unreadable budget state: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            policy,
+            path,
+            usage: Arc::new(Mutex::new(usage)),
+            save_queued: Arc::default(),
+            writer: Arc::default(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let policy = BudgetPolicy::from_env();
+        if !policy.is_limited() {
+            return Self::new(policy, None);
+        }
+        let path = crate::core::config::budget_file();
+        let reset = std::env::var("CORTEX_SCOUT_BUDGET_RESET")
+            .map(|v| {
+                matches!(
+                    v.trim().to_ascii_lowercase().as_str(),
+                    "1" | "true" | "yes" | "on"
+                )
+            })
+            .unwrap_or(false);
+        if let (true, Some(path)) = (reset, path.as_ref()) {
+            if std::fs::remove_file(path).is_ok() {
+                info!("budget counters in {} reset", path.display());
+            }
+        }
+        Self::new(policy, path)
+    }
+
+    pub fn policy(&self) -> &BudgetPolicy {
+        &self.policy
+    }
+
+    /// Book `cost` for `caller`, or refuse it without booking anything when a budget it
+    /// touches would go over its limit.
+    pub fn charge(&self, caller: &str, cost: Cost) -> Result<(), ScoutError> {
+        self.charge_at(caller, cost, now_secs())
+    }
+
+    fn charge_at(&self, caller: &str, cost: Cost, now: u64) -> Result<(), ScoutError> {
+        if cost.is_free() || !self.policy.is_limited() {
+            return Ok(());
+        }
+        let mut usage = self.usage.lock().expect("budget mutex poisoned");
+        let entry = usage.entry(caller.to_string()).or_default();
+        if now >= entry.resets_at() {
+            *entry = Usage {
+                window_start: now,
+                ..Usage::default()
+            };
+        }
+        if !self.policy.is_overridden(caller) {
+            for kind in BudgetKind::ALL {
+                let (Some(limit), wanted) = (self.policy.limit(kind), cost.get(kind)) else {
+                    continue;
+                };
+                let used = entry.used(kind);
+                if wanted > 0 && used.saturating_add(wanted) > limit {
+                    let resets_at = entry.resets_at();
+                    return Err(ScoutError::rate_limited(format!(
+                        "Budget exceeded: caller '{}' used {} of {} {}/hour ({} more requested); resets at {}",
+                        caller,
+                        used,
+                        limit,
+                        kind.as_str(),
+                        wanted,
+                        rfc3339(resets_at)
+                    ))
+                    .with_details(serde_json::json!({
+                        "budget": kind,
+                        "caller": caller,
+                        "limit_per_hour": limit,
+                        "used": used,
+                        "requested": wanted,
+                        "resets_at": rfc3339(resets_at),
+                        "retry_after_secs": resets_at.saturating_sub(now).max(1),
+                    })));
+                }
+            }
+        }
+        entry.add(&cost);
+        drop(usage);
+        self.queue_save();
+        Ok(())
+    }
+
+    /// `caller`'s usage and what is left in the current window.
+    pub fn status(&self, caller: &str) -> BudgetStatus {
+        self.status_at(caller, now_secs())
+    }
+
+    fn status_at(&self, caller: &str, now: u64) -> BudgetStatus {
+        let usage = self.usage.lock().expect("budget mutex poisoned");
+        let current = usage
+            .get(caller)
+            .filter(|u| now < u.resets_at())
+            .cloned()
+            .unwrap_or_default();
+        let budgets = BudgetKind::ALL
+            .iter()
+            .map(|kind| {
+                let limit = self.policy.limit(*kind);
+                let used = current.used(*kind);
+                BudgetLine {
+                    budget: *kind,
+                    limit_per_hour: limit,
+                    used,
+                    remaining: limit.map(|l| l.saturating_sub(used)),
+                }
+            })
+            .collect();
+        BudgetStatus {
+            caller: caller.to_string(),
+            enforced: self.policy.is_limited() && !self.policy.is_overridden(caller),
+            resets_at: (current.window_start > 0).then(|| rfc3339(current.resets_at())),
+            budgets,
+        }
+    }
+
+    /// Write the counters to disk off the async executor; a no-op unless persistence is
+    /// enabled. Charges arriving while a save is queued ride along with it, so a burst of
+    /// calls costs one write. Outside a Tokio runtime the write happens inline.
+    fn queue_save(&self) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        if self.save_queued.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let (usage, queued, writer) = (
+            self.usage.clone(),
+            self.save_queued.clone(),
+            self.writer.clone(),
+        );
+        let save = move || {
+            let _writing = writer.lock().expect("budget writer poisoned");
+            // Cleared before the snapshot: a later charge either is in it or queues another.
+            queued.store(false, Ordering::SeqCst);
+            let snapshot = usage.lock().expect("budget mutex poisoned").clone();
+            write_usage(&path, &snapshot);
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => drop(runtime.spawn_blocking(save)),
+            Err(_) => save(),
+        }
+    }
+}
+
+/// Failures are logged rather than surfaced, since the in-memory counters still apply.
+fn write_usage(path: &std::path::Path, usage: &BTreeMap<String, Usage>) {
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+        std::fs::write(&tmp, serde_json::to_vec_pretty(usage)?)?;
+        std::fs::rename(&tmp, path)
+    };
+    if let Err(e) = write() {
+        warn!("failed to save budget state to {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::error::ErrorKind;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn policy(searches: u64, scrapes: u64, browser: u64) -> BudgetPolicy {
+        BudgetPolicy {
+            searches_per_hour: Some(searches).filter(|n| *n > 0),
+            scrapes_per_hour: Some(scrapes).filter(|n| *n > 0),
+            browser_per_hour: Some(browser).filter(|n| *n > 0),
+            override_callers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn tool_costs_follow_their_arguments() {
+        assert_eq!(cost("search_web", &json!({"query": "q"})).searches, 1);
+        assert_eq!(
+            cost("scrape_url", &json!({"url": "u", "quality_mode": "high"})),
+            Cost {
+                scrapes: 1,
+                browser: 1,
+                ..Cost::default()
+            }
+        );
+        assert_eq!(
+            cost("scrape_batch", &json!({"urls": ["a", "b", "c"]})).scrapes,
+            3
+        );
+        assert_eq!(cost("crawl_website", &json!({"url": "u"})).scrapes, 50);
+        // Research tools pay for everything they fan out to.
+        assert_eq!(
+            cost(
+                "research",
+                &json!({"query": "q", "top_n": 50, "quality_mode": "high"})
+            ),
+            Cost {
+                searches: 1,
+                scrapes: 10,
+                browser: 10,
+            }
+        );
+        let deep = cost("deep_research", &json!({"query": "q", "depth": 2}));
+        assert_eq!(
+            deep.searches,
+            crate::tools::deep_research::query_cap() as u64
+        );
+        assert_eq!(
+            deep.scrapes,
+            2 * 10 * crate::tools::deep_research::url_discovery_multiplier() as u64
+        );
+        assert!(cost("budget_status", &json!({})).is_free());
+        assert!(cost("history_search", &json!({})).is_free());
+    }
+
+    #[test]
+    fn refusals_name_the_budget_and_book_nothing() {
+        let budgets = Budgets::new(policy(0, 5, 1), None);
+        let now = 1_000_000;
+        let browser_scrape = Cost {
+            scrapes: 1,
+            browser: 1,
+            ..Cost::default()
+        };
+        budgets.charge_at("key:a", browser_scrape, now).unwrap();
+        let err = budgets
+            .charge_at("key:a", browser_scrape, now + 10)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::RateLimited);
+        let details = err.details.clone().unwrap();
+        assert_eq!(details["budget"], "browser");
+        assert_eq!(details["retry_after_secs"], WINDOW_SECS - 10);
+        assert!(err.to_string().contains("resets at"), "{}", err);
+        // The refused call did not consume a scrape; plain scrapes still fit.
+        let status = budgets.status_at("key:a", now + 10);
+        assert_eq!(status.budgets[1].used, 1);
+        for _ in 0..4 {
+            budgets
+                .charge_at("key:a", cost("scrape_url", &json!({})), now + 20)
+                .unwrap();
+        }
+        assert!(budgets
+            .charge_at("key:a", cost("scrape_url", &json!({})), now + 30)
+            .is_err());
+        // Another caller and unlimited budgets are unaffected.
+        budgets
+            .charge_at("key:b", browser_scrape, now + 30)
+            .unwrap();
+        budgets
+            .charge_at("key:a", cost("search_web", &json!({})), now + 30)
+            .unwrap();
+        // A new window starts an hour after the first call.
+        budgets
+            .charge_at("key:a", browser_scrape, now + WINDOW_SECS)
+            .unwrap();
+    }
+
+    #[test]
+    fn overridden_callers_are_counted_but_not_refused() {
+        let mut limits = policy(1, 0, 0);
+        limits.override_callers = vec!["stdio:ops".to_string()];
+        let budgets = Budgets::new(limits, None);
+        let search = cost("search_web", &json!({}));
+        for _ in 0..3 {
+            budgets.charge_at("stdio:ops", search, 100).unwrap();
+        }
+        let status = budgets.status_at("stdio:ops", 100);
+        assert!(!status.enforced);
+        assert_eq!(status.budgets[0].used, 3);
+        budgets.charge_at(GLOBAL_CALLER, search, 100).unwrap();
+        assert!(budgets.charge_at(GLOBAL_CALLER, search, 100).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn parallel_calls_never_overshoot_the_limit() {
+        let budgets = Arc::new(Budgets::new(policy(0, 25, 0), None));
+        let barrier = Arc::new(tokio::sync::Barrier::new(200));
+        let calls: Vec<_> = (0..200)
+            .map(|i| {
+                let budgets = Arc::clone(&budgets);
+                let barrier = Arc::clone(&barrier);
+                tokio::spawn(scope("key:racer".to_string(), async move {
+                    barrier.wait().await;
+                    // Mixed sizes: single scrapes and three-URL batches.
+                    let urls: Vec<String> = (0..(1 + (i % 2) * 2)).map(|n| n.to_string()).collect();
+                    let batch = cost("scrape_batch", &json!({ "urls": urls }));
+                    budgets
+                        .charge(&current_caller(), batch)
+                        .map(|_| batch.scrapes)
+                        .unwrap_or(0)
+                }))
+            })
+            .collect();
+        let mut admitted = 0;
+        for call in calls {
+            admitted += call.await.unwrap();
+        }
+        let used = budgets.status("key:racer").budgets[1].used;
+        assert_eq!(admitted, used);
+        assert!(used <= 25 && used >= 23, "{}", used);
+        assert_eq!(current_caller(), GLOBAL_CALLER);
+    }
+
+    #[test]
+    fn counters_survive_a_restart_when_persisted() {
+        let path = std::env::temp_dir()
+            .join(format!("cortex-scout-budget-{}", uuid::Uuid::new_v4()))
+            .join("budgets.json");
+        let budgets = Budgets::new(policy(2, 0, 0), Some(path.clone()));
+        let search = cost("search_web", &json!({}));
+        budgets.charge("key:a", search).unwrap();
+        budgets.charge("key:a", search).unwrap();
+
+        // Without a runtime the save is written inline, so it is on disk already.
+        let reloaded = Budgets::new(policy(2, 0, 0), Some(path.clone()));
+        assert!(reloaded.charge("key:a", search).is_err());
+        assert_eq!(reloaded.status("key:a").budgets[0].remaining, Some(0));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
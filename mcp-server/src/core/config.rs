@@ -115,10 +115,35 @@ impl ShadowDeepResearchConfig {
     }
 }
 
+/// HITL browser sub-config (mirrors the `non_robot_search` key in cortex-scout.json).
+#[derive(serde::Deserialize, Default, Clone, Debug)]
+pub struct ShadowNonRobotSearchConfig {
+    /// Seconds `hitl_web_fetch` keeps its browser alive after a successful extraction so
+    /// the next call reuses it without a consent prompt or cold launch. `0` disables reuse.
+    pub session_ttl_secs: Option<u64>,
+}
+
+impl ShadowNonRobotSearchConfig {
+    /// Warm-session TTL: JSON field → `CORTEX_SCOUT_NON_ROBOT_SESSION_TTL_SECS` env var →
+    /// disabled.
+    pub fn resolve_session_ttl(&self) -> Option<std::time::Duration> {
+        self.session_ttl_secs
+            .or_else(|| {
+                std::env::var("CORTEX_SCOUT_NON_ROBOT_SESSION_TTL_SECS")
+                    .ok()
+                    .and_then(|v| v.trim().parse().ok())
+            })
+            .filter(|secs| *secs > 0)
+            .map(std::time::Duration::from_secs)
+    }
+}
+
 /// Top-level config loaded from `cortex-scout.json`.
 #[derive(serde::Deserialize, Default, Clone, Debug)]
 pub struct ShadowConfig {
     pub deep_research: ShadowDeepResearchConfig,
+    #[serde(default)]
+    pub non_robot_search: ShadowNonRobotSearchConfig,
 }
 
 /// Load `cortex-scout.json` from standard locations.
@@ -246,6 +271,16 @@ pub fn neurosiphon_enabled() -> bool {
     !matches!(v.as_str(), "0" | "false" | "no" | "off" | "disabled")
 }
 
+/// The first of `names` set to a non-blank value, trimmed. Callers list the
+/// `CORTEX_SCOUT_` name before its older `SHADOWCRAWL_` alias so the former wins.
+pub(crate) fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|v| v.trim().to_string())
+        .find(|v| !v.is_empty())
+}
+
 fn env_duration_secs(key: &str) -> Option<u64> {
     std::env::var(key)
         .ok()
@@ -262,10 +297,13 @@ pub fn mcp_tool_timeout_secs(tool_name: &str) -> u64 {
 
     env_duration_secs(&specific_key)
         .or_else(|| env_duration_secs("CORTEX_SCOUT_TOOL_TIMEOUT_SECS"))
-        .unwrap_or_else(|| match normalized.as_str() {
-            "search_web" | "proxy_manager" | "research_history" => 30,
-            "search_structured" | "scrape_url" | "extract_structured" | "fetch_then_extract" => 75,
-            "scrape_batch" | "crawl_website" => 120,
+        .unwrap_or(match normalized.as_str() {
+            "search_web" | "proxy_manager" | "proxy_status" | "engine_status" | "memory_status"
+            | "budget_status" | "research_history" | "history_search" | "monitor_add"
+            | "monitor_list" | "monitor_remove" | "monitor_events" => 30,
+            "search_structured" | "scrape_url" | "extract_structured" | "fetch_then_extract"
+            | "scrape_diff" | "scrape_compare" => 75,
+            "scrape_batch" | "extract_batch" | "crawl_website" | "research" => 120,
             "deep_research" => 180,
             "visual_scout" => 45,
             "browser_automate" | "scout_browser_automate" => 120,
@@ -276,6 +314,12 @@ pub fn mcp_tool_timeout_secs(tool_name: &str) -> u64 {
         })
 }
 
+/// Default wall-clock budget of one `research` call: `CORTEX_SCOUT_RESEARCH_BUDGET_SECS`,
+/// else 60 seconds. Calls can ask for less (or more, up to the tool's hard timeout).
+pub fn research_budget_secs() -> u64 {
+    env_duration_secs("CORTEX_SCOUT_RESEARCH_BUDGET_SECS").unwrap_or(60)
+}
+
 pub fn scrape_stage_timeout_secs(stage_name: &str) -> u64 {
     let normalized = stage_name.trim().to_ascii_lowercase().replace('-', "_");
     let specific_key = format!(
@@ -285,7 +329,7 @@ pub fn scrape_stage_timeout_secs(stage_name: &str) -> u64 {
 
     env_duration_secs(&specific_key)
         .or_else(|| env_duration_secs("CORTEX_SCOUT_SCRAPE_STAGE_TIMEOUT_SECS"))
-        .unwrap_or_else(|| match normalized.as_str() {
+        .unwrap_or(match normalized.as_str() {
             "preflight_check" => 8,
             "cdp_initial_attempt" | "cdp_retry_attempt" | "forced_cdp_attempt" | "native_cdp_fallback" => 25,
             "cdp_process_html" | "cdp_retry_process_html" => 20,
@@ -305,3 +349,324 @@ pub fn browser_launch_timeout_secs() -> u64 {
 pub fn browser_tab_probe_timeout_secs() -> u64 {
     env_duration_secs("CORTEX_SCOUT_BROWSER_TAB_PROBE_TIMEOUT_SECS").unwrap_or(4)
 }
+
+/// Browser pages (or dedicated browser launches) the shared pool runs at once. Default: 4.
+pub fn browser_pool_size() -> usize {
+    std::env::var("BROWSER_POOL_SIZE")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(4)
+}
+
+/// How long a browser fetch waits for a pool slot before failing as saturated.
+pub fn browser_pool_queue_timeout_secs() -> u64 {
+    env_duration_secs("BROWSER_POOL_QUEUE_TIMEOUT_SECS").unwrap_or(30)
+}
+
+/// Pages a warm pooled browser serves before it is replaced by a fresh process.
+pub fn browser_pool_recycle_pages() -> u64 {
+    std::env::var("BROWSER_POOL_RECYCLE_PAGES")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(100)
+}
+
+/// Largest PDF (or other binary-sniffed body) the scraper will buffer. Default: 20MB.
+pub fn pdf_max_bytes() -> usize {
+    std::env::var("CORTEX_SCOUT_PDF_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(20 * 1024 * 1024)
+}
+
+/// Largest HTML/text body a scrape will buffer, streamed from the network or read back
+/// from a rendered page; bigger pages fail instead of being parsed. Default: 10MB.
+pub fn scrape_max_response_bytes() -> usize {
+    std::env::var("SCRAPE_MAX_RESPONSE_BYTES")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(10 * 1024 * 1024)
+}
+
+/// Character budget for page text handed back to the client when a call does not set
+/// `max_chars`. Default: 10000.
+pub fn max_content_chars() -> usize {
+    std::env::var("MAX_CONTENT_CHARS")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(10000)
+}
+
+/// Combined size of the results the in-memory scrape cache holds before evicting the
+/// least used ones. Default: 256MB.
+pub fn scrape_cache_max_bytes() -> u64 {
+    std::env::var("SCRAPE_CACHE_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(256 * 1024 * 1024)
+}
+
+/// How long the in-memory search cache serves a result.
+pub const SEARCH_CACHE_TTL_SECS: u64 = 10 * 60;
+
+/// How long a cached scrape is served without contacting the site. Default: 30 minutes.
+pub fn scrape_cache_ttl_secs() -> u64 {
+    env_duration_secs("SCRAPE_CACHE_TTL_SECS").unwrap_or(30 * 60)
+}
+
+/// How long past its TTL a cached scrape with an `ETag` or `Last-Modified` is kept for a
+/// conditional re-fetch. Default: 24 hours.
+pub fn scrape_cache_revalidate_window_secs() -> u64 {
+    env_duration_secs("SCRAPE_CACHE_REVALIDATE_WINDOW_SECS").unwrap_or(24 * 60 * 60)
+}
+
+/// `Cache-Control: max-age` of `/search` or `/scrape` responses, from
+/// `CORTEX_SCOUT_HTTP_CACHE_SEARCH_MAX_AGE_SECS` / `CORTEX_SCOUT_HTTP_CACHE_SCRAPE_MAX_AGE_SECS`.
+/// Default: `0`, sent as `no-store`.
+pub fn http_cache_max_age_secs(endpoint: &str) -> u64 {
+    let endpoint = endpoint.to_ascii_uppercase();
+    ["CORTEX_SCOUT", "SHADOWCRAWL"]
+        .iter()
+        .find_map(|prefix| {
+            env_duration_secs(&format!("{}_HTTP_CACHE_{}_MAX_AGE_SECS", prefix, endpoint))
+        })
+        .unwrap_or(0)
+}
+
+pub fn robots_cache_ttl_secs() -> u64 {
+    env_duration_secs("CORTEX_SCOUT_ROBOTS_CACHE_TTL_SECS").unwrap_or(60 * 60)
+}
+
+/// How long `GET /setup` reuses a report for the same check selection. `0` re-runs the
+/// checks on every request.
+pub fn setup_cache_ttl_secs() -> u64 {
+    std::env::var("CORTEX_SCOUT_SETUP_CACHE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(30)
+}
+
+/// How long the HTTP server waits for an optional startup component (setup checks,
+/// memory, proxies) before continuing without it. `CORTEX_SCOUT_STARTUP_TIMEOUT_SECS`
+/// (or `SHADOWCRAWL_STARTUP_TIMEOUT_SECS`); default 60, `0` waits indefinitely.
+pub fn startup_timeout() -> Option<std::time::Duration> {
+    let secs = [
+        "CORTEX_SCOUT_STARTUP_TIMEOUT_SECS",
+        "SHADOWCRAWL_STARTUP_TIMEOUT_SECS",
+    ]
+    .iter()
+    .find_map(|key| std::env::var(key).ok()?.trim().parse::<u64>().ok())
+    .unwrap_or(60);
+    (secs > 0).then(|| std::time::Duration::from_secs(secs))
+}
+
+/// Whether `GET /metrics` serves the Prometheus exposition. Default: disabled;
+/// `CORTEX_SCOUT_METRICS=1` (or `true`/`yes`/`on`) enables it.
+pub fn metrics_enabled() -> bool {
+    std::env::var("CORTEX_SCOUT_METRICS")
+        .map(|v| {
+            matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        })
+        .unwrap_or(false)
+}
+
+/// Whether `GET /docs` serves a Swagger UI page for `/openapi.json`. Default: disabled;
+/// `CORTEX_SCOUT_SWAGGER_UI=1` (or `true`/`yes`/`on`) enables it.
+pub fn swagger_ui_enabled() -> bool {
+    std::env::var("CORTEX_SCOUT_SWAGGER_UI")
+        .map(|v| {
+            matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        })
+        .unwrap_or(false)
+}
+
+/// Whether `hitl_web_fetch` runs in its scripted-consent test mode: no consent prompt,
+/// notifications, tones or kill switch, a `--headless=new` browser, and a challenge fails
+/// with `HumanUnavailable` instead of waiting. `CORTEX_SCOUT_NON_ROBOT_HEADLESS_TEST=1`
+/// (or the older `SHADOWCRAWL_NON_ROBOT_HEADLESS_TEST`) enables it. Default: disabled.
+pub fn non_robot_headless_test() -> bool {
+    [
+        "CORTEX_SCOUT_NON_ROBOT_HEADLESS_TEST",
+        "SHADOWCRAWL_NON_ROBOT_HEADLESS_TEST",
+    ]
+    .iter()
+    .filter_map(|key| std::env::var(key).ok())
+    .any(|v| {
+        matches!(
+            v.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        )
+    })
+}
+
+/// Requests per second allowed per registered domain. `0` disables per-domain pacing.
+pub fn domain_rate_limit() -> f64 {
+    std::env::var("CORTEX_SCOUT_DOMAIN_RATE_LIMIT")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|r| r.is_finite() && *r >= 0.0)
+        .unwrap_or(crate::core::rate_limit::DEFAULT_DOMAIN_RATE)
+}
+
+pub fn domain_rate_burst() -> u32 {
+    std::env::var("CORTEX_SCOUT_DOMAIN_RATE_BURST")
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(1)
+}
+
+/// Whether a low-quality static scrape is retried in the browser when the caller does not
+/// say. Default: enabled; `CORTEX_SCOUT_AUTO_ESCALATE=0` (or `false`/`no`/`off`) disables.
+pub fn auto_escalate_enabled() -> bool {
+    std::env::var("CORTEX_SCOUT_AUTO_ESCALATE")
+        .map(|v| {
+            !matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "0" | "false" | "no" | "off" | "disabled"
+            )
+        })
+        .unwrap_or(true)
+}
+
+/// Static results scoring below this are escalated. Default: 0.35.
+pub fn auto_escalate_min_score() -> f64 {
+    std::env::var("CORTEX_SCOUT_AUTO_ESCALATE_MIN_SCORE")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|s| s.is_finite() && *s >= 0.0)
+        .unwrap_or(0.35)
+}
+
+/// Static results with fewer words than this are escalated. Default: 50.
+pub fn auto_escalate_min_words() -> usize {
+    std::env::var("CORTEX_SCOUT_AUTO_ESCALATE_MIN_WORDS")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(50)
+}
+
+/// `reuse_recent: auto` serves a history match logged at most this many minutes ago.
+/// Default: 30.
+pub fn search_history_reuse_max_age_mins() -> u64 {
+    std::env::var("SEARCH_HISTORY_REUSE_MAX_AGE_MINS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(30)
+}
+
+/// Lowest history similarity (0.0–1.0) a search is served from history at. Default: 0.9.
+pub fn search_history_reuse_min_similarity() -> f32 {
+    std::env::var("SEARCH_HISTORY_REUSE_MIN_SIMILARITY")
+        .ok()
+        .and_then(|v| v.trim().parse::<f32>().ok())
+        .filter(|s| s.is_finite())
+        .map(|s| s.clamp(0.0, 1.0))
+        .unwrap_or(0.9)
+}
+
+/// Whether search queries get a typo-correction pass. Only consulted when NeuroSiphon is
+/// on. Default: enabled; `CORTEX_SCOUT_SPELL_CORRECTION=0` (or `false`/`no`/`off`) keeps
+/// queries exactly as typed.
+pub fn spell_correction_enabled() -> bool {
+    std::env::var("CORTEX_SCOUT_SPELL_CORRECTION")
+        .map(|v| {
+            !matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "0" | "false" | "no" | "off" | "disabled"
+            )
+        })
+        .unwrap_or(true)
+}
+
+/// A corrected query is searched as well when the original returns fewer results than
+/// this. Default: 3; `0` only reports the correction.
+pub fn spell_retry_min_results() -> usize {
+    std::env::var("CORTEX_SCOUT_SPELL_RETRY_MIN_RESULTS")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(3)
+}
+
+/// Saved crawl state untouched for longer than this is removed by the `storage_dirs` setup
+/// check. Default: 7 days; `CORTEX_SCOUT_CRAWL_MAX_AGE_DAYS=0` keeps it forever.
+pub fn crawl_state_max_age() -> Option<std::time::Duration> {
+    let days = std::env::var("CORTEX_SCOUT_CRAWL_MAX_AGE_DAYS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(7);
+    (days > 0).then(|| std::time::Duration::from_secs(days * 24 * 60 * 60))
+}
+
+/// Monitor checks (see `tools::monitor`) run at once. Default: 2, so a large monitor set
+/// does not crowd out interactive scrapes.
+pub fn monitor_concurrency() -> usize {
+    std::env::var("CORTEX_SCOUT_MONITOR_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(2)
+}
+
+/// Where per-engine rate-limit backoff is persisted, or `None` to keep it in memory only
+/// (the default). `CORTEX_SCOUT_ENGINE_BACKOFF_PERSIST=1` enables writing to
+/// `CORTEX_SCOUT_ENGINE_BACKOFF_FILE`, else `~/.cortex-scout/engine_backoff.json`.
+pub fn engine_backoff_file() -> Option<std::path::PathBuf> {
+    let enabled = std::env::var("CORTEX_SCOUT_ENGINE_BACKOFF_PERSIST")
+        .map(|v| {
+            matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        })
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    match std::env::var("CORTEX_SCOUT_ENGINE_BACKOFF_FILE") {
+        Ok(path) if !path.trim().is_empty() => Some(std::path::PathBuf::from(path.trim())),
+        _ => dirs::home_dir().map(|home| home.join(".cortex-scout").join("engine_backoff.json")),
+    }
+}
+
+/// Where per-caller tool budget counters are saved when a budget limit is set (see
+/// `core::budget`): `CORTEX_SCOUT_BUDGET_FILE`, else `~/.cortex-scout/budgets.json`.
+pub fn budget_file() -> Option<std::path::PathBuf> {
+    match std::env::var("CORTEX_SCOUT_BUDGET_FILE") {
+        Ok(path) if !path.trim().is_empty() => Some(std::path::PathBuf::from(path.trim())),
+        _ => dirs::home_dir().map(|home| home.join(".cortex-scout").join("budgets.json")),
+    }
+}
+
+/// Where `persist_session` cookie jars are written, or `None` to keep them in memory only
+/// (the default). `CORTEX_SCOUT_COOKIE_PERSIST=1` enables writing to
+/// `CORTEX_SCOUT_COOKIE_DIR`, else `~/.cortex-scout/cookies`.
+pub fn cookie_persist_dir() -> Option<std::path::PathBuf> {
+    let enabled = std::env::var("CORTEX_SCOUT_COOKIE_PERSIST")
+        .map(|v| {
+            matches!(
+                v.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        })
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    match std::env::var("CORTEX_SCOUT_COOKIE_DIR") {
+        Ok(dir) if !dir.trim().is_empty() => Some(std::path::PathBuf::from(dir.trim())),
+        _ => dirs::home_dir().map(|home| home.join(".cortex-scout").join("cookies")),
+    }
+}
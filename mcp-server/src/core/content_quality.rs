@@ -18,7 +18,12 @@ pub fn apply_scrape_content_limit(
     if content.actual_chars > max_chars {
         content.truncated = true;
         if truncate_clean_content {
-            content.clean_content = content.clean_content.chars().take(max_chars).collect();
+            if let Some(cut) = truncate_markdown(&content.clean_content, max_chars) {
+                if cut.code_block_dropped {
+                    push_warning_unique(&mut content.warnings, "code_block_dropped_by_truncation");
+                }
+                content.clean_content = cut.text;
+            }
         }
         push_warning_unique(&mut content.warnings, "content_truncated");
     } else {
@@ -26,6 +31,146 @@ pub fn apply_scrape_content_limit(
     }
 }
 
+/// Result of [`truncate_markdown`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownTruncation {
+    /// Kept prefix followed by the `…[truncated, N more words]` marker.
+    pub text: String,
+    /// Words left out after the cut.
+    pub omitted_words: usize,
+    /// A fenced code block crossed the limit and was left out whole.
+    pub code_block_dropped: bool,
+}
+
+/// Cut markdown to at most `max_chars` characters (marker excluded) without breaking its
+/// structure, or `None` when it already fits.
+///
+/// The cut lands on the last block boundary under the limit: a blank line, a heading, a
+/// top-level list item (nested items stay with their parent) or either end of a fenced
+/// code block. Fences are never split. When no boundary fits (a limit smaller than the
+/// first paragraph) the first block is cut at a word boundary instead, unless that block
+/// is a fence.
+pub fn truncate_markdown(text: &str, max_chars: usize) -> Option<MarkdownTruncation> {
+    let limit = text.char_indices().nth(max_chars).map(|(i, _)| i)?;
+
+    let mut boundaries = Vec::new();
+    let mut fences: Vec<(usize, usize)> = Vec::new();
+    let mut open_fence: Option<(char, usize, usize)> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        match open_fence {
+            Some((marker, len, start)) => {
+                if fence_marker(trimmed).is_some_and(|(m, l)| m == marker && l >= len)
+                    && trimmed.trim_start_matches(marker).trim().is_empty()
+                {
+                    open_fence = None;
+                    fences.push((start, offset + line.len()));
+                    boundaries.push(offset + line.len());
+                }
+            }
+            None => {
+                if let Some((marker, len)) = fence_marker(trimmed) {
+                    open_fence = Some((marker, len, offset));
+                    boundaries.push(offset);
+                } else if line.trim().is_empty()
+                    || is_markdown_heading(trimmed)
+                    || is_top_level_list_item(line)
+                {
+                    boundaries.push(offset);
+                }
+            }
+        }
+        offset += line.len();
+    }
+    if let Some((_, _, start)) = open_fence {
+        fences.push((start, text.len()));
+    }
+
+    let mut cut = boundaries
+        .into_iter()
+        .filter(|b| *b <= limit && !text[..*b].trim().is_empty())
+        .max()
+        .unwrap_or(0);
+    if cut == 0 {
+        // Limit inside the first block: fall back to the last word boundary.
+        cut = text[..limit].rfind(char::is_whitespace).unwrap_or(limit);
+        if let Some((start, _)) = fences.iter().find(|(s, e)| *s < cut && cut < *e) {
+            cut = *start;
+        }
+    }
+    let code_block_dropped = fences
+        .iter()
+        .any(|(s, e)| *s >= cut && *s < limit && *e > limit);
+
+    let kept = text[..cut].trim_end();
+    let omitted_words = text[cut..].split_whitespace().count();
+    let mut truncated = String::with_capacity(kept.len() + 40);
+    truncated.push_str(kept);
+    if !kept.is_empty() {
+        truncated.push_str("\n\n");
+    }
+    truncated.push_str(&format!("…[truncated, {} more words]", omitted_words));
+    Some(MarkdownTruncation {
+        text: truncated,
+        omitted_words,
+        code_block_dropped,
+    })
+}
+
+/// Opening/closing fence character and run length for a line starting with ``` or ~~~.
+fn fence_marker(line: &str) -> Option<(char, usize)> {
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|c| *c == marker).count();
+    (len >= 3).then_some((marker, len))
+}
+
+fn is_markdown_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+fn is_top_level_list_item(line: &str) -> bool {
+    if line.starts_with("- ") || line.starts_with("* ") || line.starts_with("+ ") {
+        return true;
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && (line[digits..].starts_with(". ") || line[digits..].starts_with(") "))
+}
+
+/// Default chunk size for streamed `clean_content` (SSE and other chunked transports).
+pub const CONTENT_CHUNK_BYTES: usize = 16 * 1024;
+
+/// Split `text` into chunks of at most `max_bytes` bytes (UTF-8 safe).
+///
+/// Prefers breaking after the last newline in a window when one falls in its second
+/// half, so chunks tend to end on paragraph boundaries. Concatenating the chunks
+/// reproduces `text` exactly.
+pub fn split_content_chunks(text: &str, max_bytes: usize) -> Vec<&str> {
+    let max_bytes = max_bytes.max(4);
+    let mut chunks = Vec::new();
+    let mut rest = text;
+
+    while rest.len() > max_bytes {
+        let mut end = max_bytes;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if let Some(newline) = rest[..end].rfind('\n') {
+            if newline + 1 > end / 2 {
+                end = newline + 1;
+            }
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        chunks.push(rest);
+    }
+    chunks
+}
+
 pub fn dedupe_search_result_indexes(
     results: &[SearchResult],
     snippet_chars: usize,
@@ -83,3 +228,108 @@ pub fn build_image_markdown_hints(
         format!("\n\nImage Markdown Hints:\n{}", hints.join("\n"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_content_chunks_round_trips_and_respects_limit() {
+        let text = "line one\nline two is longer\nünïcödé ✓ text without breaks".repeat(50);
+        let chunks = split_content_chunks(&text, 64);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| !c.is_empty() && c.len() <= 64));
+        assert_eq!(chunks.concat(), text);
+    }
+
+    #[test]
+    fn split_content_chunks_prefers_newlines() {
+        let text = format!("{}\n{}", "a".repeat(40), "b".repeat(40));
+        let chunks = split_content_chunks(&text, 64);
+        assert_eq!(chunks[0], format!("{}\n", "a".repeat(40)));
+        assert!(split_content_chunks("", 64).is_empty());
+    }
+
+    #[test]
+    fn truncate_markdown_cuts_at_paragraph_and_heading_boundaries() {
+        assert_eq!(truncate_markdown("short text", 100), None);
+
+        let text = "Intro paragraph here.\n\n## Details\nSome details follow in this section.";
+        let cut = truncate_markdown(text, 40).unwrap();
+        assert_eq!(
+            cut.text,
+            "Intro paragraph here.\n\n…[truncated, 8 more words]"
+        );
+        assert_eq!(cut.omitted_words, 8);
+        assert!(!cut.code_block_dropped);
+
+        let text = "Intro.\n# One\nalpha beta\n# Two\ngamma delta epsilon";
+        let cut = truncate_markdown(text, 30).unwrap();
+        assert!(cut.text.starts_with("Intro.\n# One\nalpha beta\n\n…"));
+    }
+
+    #[test]
+    fn truncate_markdown_keeps_or_drops_whole_fences() {
+        let text = "Setup:\n\n```rust\nfn main() {}\n```\n\nThen run:\n\n```sh\ncargo run --release\n```\n";
+        let limit = text.find("cargo").unwrap();
+        let cut = truncate_markdown(text, limit).unwrap();
+        assert!(cut.code_block_dropped);
+        assert_eq!(
+            cut.text,
+            "Setup:\n\n```rust\nfn main() {}\n```\n\nThen run:\n\n…[truncated, 5 more words]"
+        );
+
+        // A blank line inside a fence is not a boundary.
+        let text = "```\nline one\n\nline two\n```\nafter the block";
+        let cut = truncate_markdown(text, text.find("line two").unwrap()).unwrap();
+        assert!(cut.code_block_dropped);
+        assert_eq!(cut.text, "…[truncated, 9 more words]");
+
+        // Fence closed before the limit stays whole; ~~~ fences count too.
+        let text = "~~~\nkept\n~~~\n\ntrailing words that do not fit";
+        let cut = truncate_markdown(text, 20).unwrap();
+        assert!(!cut.code_block_dropped);
+        assert!(cut.text.starts_with("~~~\nkept\n~~~\n\n…"));
+    }
+
+    #[test]
+    fn truncate_markdown_keeps_nested_list_items_with_their_parent() {
+        let text =
+            "- parent a\n  - child a1\n  - child a2\n- parent b\n  - child b1\n  - child b2\n";
+        let limit = text.find("child b2").unwrap();
+        let cut = truncate_markdown(text, limit).unwrap();
+        assert_eq!(
+            cut.text,
+            "- parent a\n  - child a1\n  - child a2\n\n…[truncated, 9 more words]"
+        );
+
+        let numbered = "1. first\n   1. sub\n2. second item with more words";
+        let cut = truncate_markdown(numbered, 24).unwrap();
+        assert!(cut.text.starts_with("1. first\n   1. sub\n\n…"));
+    }
+
+    #[test]
+    fn truncate_markdown_limit_inside_first_paragraph_cuts_on_a_word() {
+        let text = "The quick brown fox jumps over the lazy dog.\n\nSecond paragraph.";
+        let cut = truncate_markdown(text, 12).unwrap();
+        assert_eq!(cut.text, "The quick\n\n…[truncated, 9 more words]");
+
+        let cut = truncate_markdown("Supercalifragilistic", 5).unwrap();
+        assert_eq!(cut.text, "Super\n\n…[truncated, 1 more words]");
+    }
+
+    #[test]
+    fn scrape_content_limit_reports_dropped_code_block() {
+        let clean = "Intro.\n\n```\nlet x = 1;\nlet y = 2;\n```\n";
+        let mut page = ScrapeResponse::for_test("https://example.com", "", clean);
+        apply_scrape_content_limit(&mut page, 20, true);
+
+        assert!(page.truncated);
+        assert_eq!(page.actual_chars, clean.len());
+        assert!(page.clean_content.starts_with("Intro.\n\n…[truncated"));
+        assert!(page.warnings.contains(&"content_truncated".to_string()));
+        assert!(page
+            .warnings
+            .contains(&"code_block_dropped_by_truncation".to_string()));
+    }
+}
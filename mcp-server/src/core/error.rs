@@ -0,0 +1,303 @@
+//! Error taxonomy shared by the HTTP API and the MCP transports.
+//!
+//! Handlers surface failures as a [`ScoutError`]: a stable [`ErrorKind`] that picks the
+//! HTTP status and JSON-RPC code, a human-readable message, and optional structured
+//! `details`. Errors raised deep inside the scrape/search pipelines stay `anyhow::Error`;
+//! [`ScoutError::classify`] recovers the kind at the handler boundary.
+
+use crate::scraping::robots::RobotsError;
+use crate::search::engines::EngineError;
+use crate::types::ErrorResponse;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The request itself is wrong (missing/invalid parameter, malformed URL).
+    InvalidInput,
+    /// The target refused us: robots.txt, a captcha or an anti-bot block page.
+    Blocked,
+    /// A stage or upstream request ran out of time.
+    Timeout,
+    /// The upstream site or search engine failed or could not be reached.
+    UpstreamUnavailable,
+    /// A referenced resource (e.g. a proxy URL) does not exist.
+    NotFound,
+    /// A server-side feature this request needs is disabled or has nothing to offer.
+    Unavailable,
+    /// The HTTP API requires a key and none (or an unknown one) was presented.
+    Unauthorized,
+    /// The caller's API key has used up its request budget for the current minute, or the
+    /// caller its hourly search / scrape / browser budget (see `core::budget`).
+    RateLimited,
+    #[default]
+    Internal,
+}
+
+impl ErrorKind {
+    /// Stable machine-readable code, identical to the serialized form.
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorKind::InvalidInput => "invalid_input",
+            ErrorKind::Blocked => "blocked",
+            ErrorKind::Timeout => "timeout",
+            ErrorKind::UpstreamUnavailable => "upstream_unavailable",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::Unavailable => "unavailable",
+            ErrorKind::Unauthorized => "unauthorized",
+            ErrorKind::RateLimited => "rate_limited",
+            ErrorKind::Internal => "internal",
+        }
+    }
+
+    pub fn http_status(self) -> StatusCode {
+        match self {
+            ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+            ErrorKind::Blocked => StatusCode::FORBIDDEN,
+            ErrorKind::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            ErrorKind::UpstreamUnavailable => StatusCode::BAD_GATEWAY,
+            ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            ErrorKind::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorKind::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorKind::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorKind::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// JSON-RPC error code. Invalid params, internal errors and MCP's resource-not-found use
+    /// their standard codes; the rest sit in the server-defined `-32010..` range.
+    pub fn jsonrpc_code(self) -> i32 {
+        match self {
+            ErrorKind::InvalidInput => -32602,
+            ErrorKind::Internal => -32603,
+            ErrorKind::NotFound => -32002,
+            ErrorKind::Blocked => -32010,
+            ErrorKind::Timeout => -32011,
+            ErrorKind::UpstreamUnavailable => -32012,
+            ErrorKind::Unavailable => -32013,
+            ErrorKind::Unauthorized => -32014,
+            ErrorKind::RateLimited => -32015,
+        }
+    }
+}
+
+/// The error statuses of the HTTP API for its OpenAPI document. Every one carries an
+/// [`ErrorResponse`] whose `code` is the matching [`ErrorKind`].
+#[derive(utoipa::IntoResponses)]
+pub enum ErrorResponses {
+    /// `invalid_input`: a missing or malformed parameter.
+    #[response(status = 400)]
+    InvalidInput(ErrorResponse),
+    /// `unauthorized`: API keys are configured and none (or an unknown one) was sent.
+    #[response(status = 401)]
+    Unauthorized(ErrorResponse),
+    /// `blocked`: robots.txt, a captcha or an anti-bot page refused the request.
+    #[response(status = 403)]
+    Blocked(ErrorResponse),
+    /// `not_found`: the referenced resource does not exist.
+    #[response(status = 404)]
+    NotFound(ErrorResponse),
+    /// `rate_limited`: the API key used up its requests for the current minute, or the
+    /// caller its hourly tool budget.
+    #[response(status = 429)]
+    RateLimited(ErrorResponse),
+    /// `internal`: an unexpected server-side failure.
+    #[response(status = 500)]
+    Internal(ErrorResponse),
+    /// `upstream_unavailable`: the site or search engine failed or could not be reached.
+    #[response(status = 502)]
+    UpstreamUnavailable(ErrorResponse),
+    /// `unavailable`: a server-side feature this request needs is disabled.
+    #[response(status = 503)]
+    Unavailable(ErrorResponse),
+    /// `timeout`: a stage or upstream request ran out of time.
+    #[response(status = 504)]
+    Timeout(ErrorResponse),
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct ScoutError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub details: Option<Value>,
+}
+
+impl ScoutError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    pub fn blocked(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Blocked, message)
+    }
+
+    pub fn timeout(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Timeout, message)
+    }
+
+    pub fn upstream_unavailable(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::UpstreamUnavailable, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Unavailable, message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Unauthorized, message)
+    }
+
+    pub fn rate_limited(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::RateLimited, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Prefix the message, keeping kind and details (`"Search failed: <message>"`).
+    pub fn context(mut self, context: &str) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+
+    /// Recover a kind from a pipeline error. Anything not recognised is `Internal`;
+    /// the message is always the error's own `Display`.
+    pub fn classify(err: &anyhow::Error) -> Self {
+        if let Some(scout) = err.downcast_ref::<ScoutError>() {
+            return scout.clone();
+        }
+        if let Some(robots) = err.downcast_ref::<RobotsError>() {
+            let kind = match robots {
+                RobotsError::Disallowed { .. } => ErrorKind::Blocked,
+                RobotsError::Unavailable { .. } => ErrorKind::UpstreamUnavailable,
+            };
+            return Self::new(kind, format!("{}: {}", robots.code(), robots))
+                .with_details(serde_json::json!({ "reason": robots.code() }));
+        }
+        if let Some(engine) = err.downcast_ref::<EngineError>() {
+            let kind = match engine {
+                EngineError::Blocked { .. } => ErrorKind::Blocked,
+                EngineError::Transient { .. } => ErrorKind::UpstreamUnavailable,
+                EngineError::Fatal(_) => ErrorKind::Internal,
+            };
+            return Self::new(kind, engine.to_string())
+                .with_details(serde_json::json!({ "attempts": engine.attempts() }));
+        }
+        if err.downcast_ref::<tokio::time::error::Elapsed>().is_some() {
+            return Self::timeout(err.to_string());
+        }
+        if let Some(req) = err.chain().find_map(|e| e.downcast_ref::<reqwest::Error>()) {
+            if req.is_timeout() {
+                return Self::timeout(err.to_string());
+            }
+            if req.is_connect() {
+                return Self::upstream_unavailable(err.to_string());
+            }
+        }
+        Self::internal(err.to_string())
+    }
+
+    pub fn to_response(&self) -> ErrorResponse {
+        ErrorResponse {
+            error: self.message.clone(),
+            code: self.kind,
+            details: self.details.clone(),
+        }
+    }
+
+    /// The `Err` half of every axum handler in this crate.
+    pub fn into_http(self) -> (StatusCode, Json<ErrorResponse>) {
+        (self.kind.http_status(), Json(self.to_response()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_map_to_status_and_jsonrpc_codes() {
+        let (status, Json(body)) = ScoutError::invalid_input("bad url").into_http();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.code, ErrorKind::InvalidInput);
+        assert_eq!(ErrorKind::InvalidInput.jsonrpc_code(), -32602);
+
+        assert_eq!(ErrorKind::Blocked.http_status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            ErrorKind::Timeout.http_status(),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+        assert_eq!(
+            ErrorKind::UpstreamUnavailable.http_status(),
+            StatusCode::BAD_GATEWAY
+        );
+        assert_eq!(ErrorKind::Internal.jsonrpc_code(), -32603);
+        assert_eq!(
+            ErrorKind::RateLimited.http_status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
+    #[test]
+    fn serializes_code_and_optional_details() {
+        let body = ScoutError::blocked("Captcha")
+            .with_details(serde_json::json!({ "reason": "Captcha" }))
+            .to_response();
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["code"], "blocked");
+        assert_eq!(json["details"]["reason"], "Captcha");
+
+        let plain = serde_json::to_value(ScoutError::internal("boom").to_response()).unwrap();
+        assert_eq!(plain["code"], ErrorKind::Internal.code());
+        assert!(plain.get("details").is_none());
+    }
+
+    #[test]
+    fn classifies_wrapped_errors() {
+        let err: anyhow::Error = ScoutError::invalid_input("Invalid URL").into();
+        assert_eq!(ScoutError::classify(&err).kind, ErrorKind::InvalidInput);
+
+        let err = anyhow::Error::new(RobotsError::Disallowed {
+            url: "https://example.com/private".to_string(),
+            crawl_delay: None,
+        });
+        let classified = ScoutError::classify(&err);
+        assert_eq!(classified.kind, ErrorKind::Blocked);
+        assert!(classified.message.starts_with("robots_disallowed: "));
+
+        let err = anyhow::Error::new(EngineError::Transient {
+            message: "connection reset".to_string(),
+            attempts: 3,
+        });
+        assert_eq!(
+            ScoutError::classify(&err).kind,
+            ErrorKind::UpstreamUnavailable
+        );
+        assert_eq!(
+            ScoutError::classify(&anyhow::anyhow!("boom")).kind,
+            ErrorKind::Internal
+        );
+    }
+}
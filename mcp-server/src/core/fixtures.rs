@@ -0,0 +1,252 @@
+//! Offline fixture mode for the fetch layer.
+//!
+//! With `CORTEX_SCOUT_FIXTURE_DIR` (or `SHADOWCRAWL_FIXTURE_DIR`) set, SERP fetches ([`crate::tools::search::engines::fetch_html`])
+//! and native scraper fetches ([`crate::scraping::rust_scraper::RustScraper`]) are served
+//! from files in that directory instead of the network, one `<key>.json` per URL where
+//! `key` is the first 32 hex digits of the URL's SHA-256. Replay never touches the
+//! network; a URL without a recording fails with an error naming the file it expected.
+//!
+//! `CORTEX_SCOUT_FIXTURE_MODE=record` fetches normally and writes every response into the
+//! directory, which is how new fixtures are captured.
+
+use anyhow::{anyhow, Context, Result};
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use tracing::debug;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// Serve recorded responses; never go to the network.
+    Replay,
+    /// Fetch from the network and save each response.
+    Record,
+}
+
+impl FixtureMode {
+    pub fn parse_str(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "replay" => Some(FixtureMode::Replay),
+            "record" => Some(FixtureMode::Record),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FixtureMode::Replay => "replay",
+            FixtureMode::Record => "record",
+        }
+    }
+}
+
+/// One recorded HTTP response. Text bodies are stored as-is so fixtures stay
+/// reviewable; anything else goes in `body_base64`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub url: String,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_base64: Option<String>,
+}
+
+impl RecordedResponse {
+    pub fn new(url: &str, status: u16, headers: Vec<(String, String)>, body: &[u8]) -> Self {
+        let (body, body_base64) = match std::str::from_utf8(body) {
+            Ok(text) => (Some(text.to_string()), None),
+            Err(_) => (
+                None,
+                Some(base64::engine::general_purpose::STANDARD.encode(body)),
+            ),
+        };
+        Self {
+            url: url.to_string(),
+            status,
+            headers,
+            body,
+            body_base64,
+        }
+    }
+
+    pub fn body_bytes(&self) -> Result<Vec<u8>> {
+        if let Some(encoded) = &self.body_base64 {
+            return base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .context("fixture body_base64 is not valid base64");
+        }
+        Ok(self.body.clone().unwrap_or_default().into_bytes())
+    }
+
+    pub fn body_text(&self) -> Result<String> {
+        Ok(String::from_utf8_lossy(&self.body_bytes()?).into_owned())
+    }
+
+    /// Rebuild a `reqwest::Response` so replayed fetches go through the same code as live ones.
+    pub fn to_response(&self) -> Result<reqwest::Response> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let response = builder
+            .body(self.body_bytes()?)
+            .map_err(|e| anyhow!("invalid fixture for {}: {}", self.url, e))?;
+        Ok(reqwest::Response::from(response))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Fixtures {
+    dir: PathBuf,
+    mode: FixtureMode,
+}
+
+impl Fixtures {
+    pub fn new(dir: impl Into<PathBuf>, mode: FixtureMode) -> Self {
+        Self {
+            dir: dir.into(),
+            mode,
+        }
+    }
+
+    /// `None` unless `CORTEX_SCOUT_FIXTURE_DIR` is set (`SHADOWCRAWL_` names are read as
+    /// well). An unknown `CORTEX_SCOUT_FIXTURE_MODE` falls back to replay so a typo never
+    /// hits the network.
+    pub fn from_env() -> Option<Self> {
+        let var = |names: [&str; 2]| {
+            names
+                .iter()
+                .filter_map(|name| std::env::var(name).ok())
+                .find(|v| !v.trim().is_empty())
+        };
+        let dir = var(["CORTEX_SCOUT_FIXTURE_DIR", "SHADOWCRAWL_FIXTURE_DIR"])?;
+        let mode = var(["CORTEX_SCOUT_FIXTURE_MODE", "SHADOWCRAWL_FIXTURE_MODE"])
+            .and_then(|v| FixtureMode::parse_str(&v))
+            .unwrap_or(FixtureMode::Replay);
+        Some(Self::new(dir.trim(), mode))
+    }
+
+    pub fn mode(&self) -> FixtureMode {
+        self.mode
+    }
+
+    pub fn replays(&self) -> bool {
+        self.mode == FixtureMode::Replay
+    }
+
+    pub fn key(url: &str) -> String {
+        hex::encode(Sha256::digest(url.as_bytes()))[..32].to_string()
+    }
+
+    pub fn path_for(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", Self::key(url)))
+    }
+
+    pub fn load(&self, url: &str) -> Result<RecordedResponse> {
+        let path = self.path_for(url);
+        let raw = std::fs::read_to_string(&path).map_err(|e| {
+            anyhow!(
+                "no fixture for {} (expected {}): {}",
+                url,
+                path.display(),
+                e
+            )
+        })?;
+        let recorded: RecordedResponse = serde_json::from_str(&raw)
+            .with_context(|| format!("invalid fixture file {}", path.display()))?;
+        debug!("replaying {} from {}", url, path.display());
+        Ok(recorded)
+    }
+
+    pub fn save(&self, recorded: &RecordedResponse) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("cannot create fixture dir {}", self.dir.display()))?;
+        let path = self.path_for(&recorded.url);
+        std::fs::write(&path, serde_json::to_string_pretty(recorded)?)
+            .with_context(|| format!("cannot write fixture {}", path.display()))?;
+        debug!("recorded {} to {}", recorded.url, path.display());
+        Ok(path)
+    }
+
+    /// Save a live response and hand back an equivalent one for the caller to consume.
+    pub async fn record(
+        &self,
+        url: &str,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response> {
+        let status = response.status().as_u16();
+        let headers = header_pairs(response.headers());
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| anyhow!("failed to read response body: {}", e))?;
+        let recorded = RecordedResponse::new(url, status, headers, &body);
+        self.save(&recorded)?;
+        recorded.to_response()
+    }
+}
+
+pub fn header_pairs(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.as_str().to_string(), v.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn recorded_responses_replay_status_headers_and_body() {
+        let dir =
+            std::env::temp_dir().join(format!("cortex-scout-fixtures-{}", uuid::Uuid::new_v4()));
+        let fixtures = Fixtures::new(&dir, FixtureMode::Record);
+        let url = "https://example.com/page?q=1";
+
+        let live = RecordedResponse::new(
+            url,
+            404,
+            vec![
+                ("content-type".to_string(), "text/html".to_string()),
+                ("x-robots-tag".to_string(), "noindex".to_string()),
+            ],
+            b"<html>missing</html>",
+        )
+        .to_response()
+        .unwrap();
+        let passed_on = fixtures.record(url, live).await.unwrap();
+        assert_eq!(passed_on.status().as_u16(), 404);
+        assert_eq!(passed_on.text().await.unwrap(), "<html>missing</html>");
+
+        let path = fixtures.path_for(url);
+        assert_eq!(path.file_name().unwrap().len(), 32 + ".json".len());
+        assert!(path.exists());
+
+        let replay = Fixtures::new(&dir, FixtureMode::Replay);
+        let response = replay.load(url).unwrap().to_response().unwrap();
+        assert_eq!(response.status().as_u16(), 404);
+        assert_eq!(response.headers()["x-robots-tag"], "noindex");
+        assert_eq!(response.text().await.unwrap(), "<html>missing</html>");
+
+        let binary = RecordedResponse::new(url, 200, Vec::new(), &[0xff, 0x00, 0x25]);
+        assert!(binary.body.is_none());
+        assert_eq!(binary.body_bytes().unwrap(), vec![0xff, 0x00, 0x25]);
+
+        let err = replay.load("https://example.com/other").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("no fixture for https://example.com/other"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,198 @@
+//! HTTP caching headers for `/search` and `/scrape`.
+//!
+//! Every 200 carries an `ETag` (a hash of the body) and a `Cache-Control` of
+//! `max-age=<secs>` from `CORTEX_SCOUT_HTTP_CACHE_SEARCH_MAX_AGE_SECS` /
+//! `CORTEX_SCOUT_HTTP_CACHE_SCRAPE_MAX_AGE_SECS`, or `no-store` while those are unset.
+//! The ETag is remembered per request (endpoint plus request body or query) for as long as
+//! the internal search or scrape cache would serve the result as fresh. A repeat of that
+//! request whose `If-None-Match` names it is answered `304 Not Modified` before any engine
+//! or scraper runs.
+//!
+//! A POST body cannot be named in `Vary`, so shared caches should use the `GET` forms
+//! (`GET /search?query=...`, `GET /scrape?url=...`), whose URL is the whole request.
+
+use crate::AppState;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+    Search,
+    Scrape,
+}
+
+impl Endpoint {
+    fn as_str(self) -> &'static str {
+        match self {
+            Endpoint::Search => "search",
+            Endpoint::Scrape => "scrape",
+        }
+    }
+
+    fn max_age_secs(self) -> u64 {
+        crate::core::config::http_cache_max_age_secs(self.as_str())
+    }
+
+    fn cache_control(self) -> String {
+        match self.max_age_secs() {
+            0 => "no-store".to_string(),
+            secs => format!("max-age={}", secs),
+        }
+    }
+}
+
+/// The ETag last served for a request, and until when the result behind it is fresh.
+#[derive(Clone, Debug)]
+pub struct Validator {
+    etag: String,
+    fresh_until: Instant,
+}
+
+/// Cache key of a request: the endpoint and the request's canonical JSON.
+pub fn request_key(endpoint: Endpoint, request: &impl Serialize) -> String {
+    let json = serde_json::to_vec(request).unwrap_or_default();
+    format!(
+        "{}:{}",
+        endpoint.as_str(),
+        hex::encode(Sha256::digest(&json))
+    )
+}
+
+/// Strong ETag of a response body.
+pub fn etag_for(body: &[u8]) -> String {
+    format!("\"{}\"", &hex::encode(Sha256::digest(body))[..32])
+}
+
+/// How much longer the scrape cache serves a page fetched at `timestamp` (RFC 3339)
+/// without contacting the site.
+pub fn scrape_freshness(timestamp: &str) -> Duration {
+    let ttl = Duration::from_secs(crate::core::config::scrape_cache_ttl_secs());
+    let Ok(fetched) = chrono::DateTime::parse_from_rfc3339(timestamp) else {
+        return Duration::ZERO;
+    };
+    let age = (chrono::Utc::now() - fetched.with_timezone(&chrono::Utc))
+        .to_std()
+        .unwrap_or_default();
+    ttl.saturating_sub(age)
+}
+
+/// Whether an `If-None-Match` header value names `etag` (weak comparison, `*` included).
+pub fn if_none_match(header_value: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    header_value
+        .split(',')
+        .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+}
+
+/// `304 Not Modified` when the request's `If-None-Match` names the ETag remembered for
+/// `key` and that result is still fresh; `None` means the request has to run.
+pub async fn not_modified(
+    state: &AppState,
+    endpoint: Endpoint,
+    key: &str,
+    headers: &HeaderMap,
+) -> Option<Response> {
+    let requested = headers.get(header::IF_NONE_MATCH)?.to_str().ok()?;
+    let validator = state.http_validators.get(key).await?;
+    if validator.fresh_until <= Instant::now() {
+        state.http_validators.invalidate(key).await;
+        return None;
+    }
+    if !if_none_match(requested, &validator.etag) {
+        return None;
+    }
+    Some(
+        (
+            StatusCode::NOT_MODIFIED,
+            cache_headers(endpoint, &validator.etag),
+        )
+            .into_response(),
+    )
+}
+
+/// A 200 with `body` and the caching headers. With `fresh_for`, the ETag is remembered for
+/// `key` that long so a repeat of the request can be answered by [`not_modified`].
+pub async fn respond(
+    state: &AppState,
+    endpoint: Endpoint,
+    key: &str,
+    content_type: &str,
+    body: Vec<u8>,
+    fresh_for: Option<Duration>,
+) -> Response {
+    let etag = etag_for(&body);
+    if let Some(fresh_for) = fresh_for.filter(|d| !d.is_zero()) {
+        remember(state, key, &etag, fresh_for).await;
+    }
+    let mut headers = cache_headers(endpoint, &etag);
+    if let Ok(value) = HeaderValue::from_str(content_type) {
+        headers.insert(header::CONTENT_TYPE, value);
+    }
+    (StatusCode::OK, headers, body).into_response()
+}
+
+/// Remember `etag` for `key` without serving a response.
+pub async fn remember(state: &AppState, key: &str, etag: &str, fresh_for: Duration) {
+    let validator = Validator {
+        etag: etag.to_string(),
+        fresh_until: Instant::now() + fresh_for,
+    };
+    state
+        .http_validators
+        .insert(key.to_string(), validator)
+        .await;
+}
+
+fn cache_headers(endpoint: Endpoint, etag: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&endpoint.cache_control()) {
+        headers.insert(header::CACHE_CONTROL, value);
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_none_match_compares_weakly_and_accepts_lists() {
+        let etag = etag_for(b"{\"results\":[]}");
+        assert_eq!(etag.len(), 34);
+        assert!(if_none_match(&etag, &etag));
+        assert!(if_none_match(&format!("W/{}", etag), &etag));
+        assert!(if_none_match(&format!("\"other\", {}", etag), &etag));
+        assert!(if_none_match("*", &etag));
+        assert!(!if_none_match("\"other\"", &etag));
+    }
+
+    #[tokio::test]
+    async fn remembered_etags_expire_with_the_result() {
+        let state = AppState::new(reqwest::Client::new());
+        let key = request_key(Endpoint::Search, &serde_json::json!({"query": "rust"}));
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"abc\""));
+
+        assert!(not_modified(&state, Endpoint::Search, &key, &headers)
+            .await
+            .is_none());
+        remember(&state, &key, "\"abc\"", Duration::from_secs(60)).await;
+        let response = not_modified(&state, Endpoint::Search, &key, &headers)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], "\"abc\"");
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+
+        remember(&state, &key, "\"abc\"", Duration::ZERO).await;
+        assert!(not_modified(&state, Endpoint::Search, &key, &headers)
+            .await
+            .is_none());
+    }
+}
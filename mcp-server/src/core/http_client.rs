@@ -0,0 +1,334 @@
+//! The process-wide HTTP stack: one tuned connection pool policy and one DNS cache shared
+//! by every client the server builds.
+//!
+//! High-volume scraping used to pay a DNS lookup and a fresh TLS handshake per page because
+//! `RustScraper` built its own client for every instance. Clients now come from
+//! [`builder`], which applies the pool settings and plugs in the shared [`CachingResolver`],
+//! and plain page fetches share the single [`scrape_client`]. Cookie-jar and proxy clients
+//! still need their own pool but keep the tuning and the DNS cache.
+//!
+//! Tuning: `CORTEX_SCOUT_HTTP_POOL_MAX_IDLE_PER_HOST`, `CORTEX_SCOUT_HTTP_POOL_IDLE_TIMEOUT_SECS`
+//! and `CORTEX_SCOUT_DNS_TTL_FLOOR_SECS`. Counters are exported through
+//! [`Metrics::render`](crate::core::metrics::Metrics::render).
+
+use hickory_resolver::config::ResolverConfig;
+use hickory_resolver::name_server::TokioConnectionProvider;
+use hickory_resolver::TokioResolver;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Connection pool and DNS cache settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpTuning {
+    /// Idle connections kept open per host for reuse.
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle pooled connection is kept before it is closed.
+    pub pool_idle_timeout: Duration,
+    /// Cached DNS answers live at least this long, even when the record's TTL is shorter.
+    pub dns_ttl_floor: Duration,
+}
+
+impl Default for HttpTuning {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_host: 32,
+            pool_idle_timeout: Duration::from_secs(90),
+            dns_ttl_floor: Duration::from_secs(60),
+        }
+    }
+}
+
+impl HttpTuning {
+    /// Defaults overridden by `CORTEX_SCOUT_HTTP_POOL_MAX_IDLE_PER_HOST`,
+    /// `CORTEX_SCOUT_HTTP_POOL_IDLE_TIMEOUT_SECS` and `CORTEX_SCOUT_DNS_TTL_FLOOR_SECS`
+    /// (`0` keeps each record's own TTL).
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_u64 = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        Self {
+            pool_max_idle_per_host: env_u64("CORTEX_SCOUT_HTTP_POOL_MAX_IDLE_PER_HOST")
+                .map(|n| n as usize)
+                .unwrap_or(defaults.pool_max_idle_per_host),
+            pool_idle_timeout: env_u64("CORTEX_SCOUT_HTTP_POOL_IDLE_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.pool_idle_timeout),
+            dns_ttl_floor: env_u64("CORTEX_SCOUT_DNS_TTL_FLOOR_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.dns_ttl_floor),
+        }
+    }
+}
+
+/// Hostname → addresses, each entry kept until the later of its record TTL and the floor.
+#[derive(Debug, Default)]
+struct DnsCache {
+    entries: HashMap<String, (Vec<IpAddr>, Instant)>,
+}
+
+impl DnsCache {
+    fn get(&self, host: &str, now: Instant) -> Option<Vec<IpAddr>> {
+        self.entries
+            .get(host)
+            .filter(|(_, expires)| *expires > now)
+            .map(|(addrs, _)| addrs.clone())
+    }
+
+    fn insert(
+        &mut self,
+        host: &str,
+        addrs: Vec<IpAddr>,
+        valid_until: Instant,
+        floor: Duration,
+        now: Instant,
+    ) {
+        self.entries.retain(|_, (_, expires)| *expires > now);
+        self.entries
+            .insert(host.to_string(), (addrs, valid_until.max(now + floor)));
+    }
+}
+
+/// `reqwest` resolver answering from [`DnsCache`] and falling back to hickory.
+///
+/// reqwest only resolves a host when it opens a new connection, so every call here is
+/// also counted as a created connection.
+pub struct CachingResolver {
+    resolver: TokioResolver,
+    cache: Arc<Mutex<DnsCache>>,
+    ttl_floor: Duration,
+}
+
+impl CachingResolver {
+    fn new(ttl_floor: Duration) -> Self {
+        // The system configuration (resolv.conf) when it can be read, public resolvers otherwise.
+        let resolver = match TokioResolver::builder_tokio() {
+            Ok(builder) => builder.build(),
+            Err(e) => {
+                debug!("system DNS config unavailable ({}); using defaults", e);
+                TokioResolver::builder_with_config(
+                    ResolverConfig::default(),
+                    TokioConnectionProvider::default(),
+                )
+                .build()
+            }
+        };
+        Self {
+            resolver,
+            cache: Arc::default(),
+            ttl_floor,
+        }
+    }
+}
+
+impl Resolve for CachingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_ascii_lowercase();
+        STATS.connections_created.fetch_add(1, Ordering::Relaxed);
+        if let Some(addrs) = self.cache.lock().unwrap().get(&host, Instant::now()) {
+            STATS.dns_hits.fetch_add(1, Ordering::Relaxed);
+            return Box::pin(async move { Ok(socket_addrs(addrs)) });
+        }
+        STATS.dns_misses.fetch_add(1, Ordering::Relaxed);
+
+        let resolver = self.resolver.clone();
+        let cache = self.cache.clone();
+        let floor = self.ttl_floor;
+        Box::pin(async move {
+            let lookup = resolver.lookup_ip(host.as_str()).await?;
+            let addrs: Vec<IpAddr> = lookup.iter().collect();
+            cache.lock().unwrap().insert(
+                &host,
+                addrs.clone(),
+                lookup.valid_until(),
+                floor,
+                Instant::now(),
+            );
+            Ok(socket_addrs(addrs))
+        })
+    }
+}
+
+/// reqwest fills in the port, so resolved addresses carry port 0.
+fn socket_addrs(addrs: Vec<IpAddr>) -> Addrs {
+    Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)))
+}
+
+/// Process-wide HTTP counters, exported as Prometheus series by the metrics registry.
+#[derive(Debug, Default)]
+pub struct HttpStats {
+    dns_hits: AtomicU64,
+    dns_misses: AtomicU64,
+    connections_created: AtomicU64,
+    /// Page and SERP requests sent (each retry and redirect hop counts).
+    requests: AtomicU64,
+}
+
+impl HttpStats {
+    /// `(metric, labels, value)` for every series; connections reused are the counted
+    /// requests that did not need a new connection.
+    pub fn series(&self) -> Vec<(&'static str, Vec<(&'static str, String)>, u64)> {
+        use crate::core::metrics::{DNS_LOOKUPS, HTTP_CONNECTIONS};
+        let created = self.connections_created.load(Ordering::Relaxed);
+        let requests = self.requests.load(Ordering::Relaxed);
+        vec![
+            (
+                DNS_LOOKUPS,
+                vec![("result", "hit".to_string())],
+                self.dns_hits.load(Ordering::Relaxed),
+            ),
+            (
+                DNS_LOOKUPS,
+                vec![("result", "miss".to_string())],
+                self.dns_misses.load(Ordering::Relaxed),
+            ),
+            (
+                HTTP_CONNECTIONS,
+                vec![("kind", "created".to_string())],
+                created,
+            ),
+            (
+                HTTP_CONNECTIONS,
+                vec![("kind", "reused".to_string())],
+                requests.saturating_sub(created),
+            ),
+        ]
+    }
+}
+
+static STATS: HttpStats = HttpStats {
+    dns_hits: AtomicU64::new(0),
+    dns_misses: AtomicU64::new(0),
+    connections_created: AtomicU64::new(0),
+    requests: AtomicU64::new(0),
+};
+
+struct HttpStack {
+    tuning: HttpTuning,
+    resolver: Arc<CachingResolver>,
+    scrape_client: OnceLock<reqwest::Client>,
+}
+
+static STACK: OnceLock<HttpStack> = OnceLock::new();
+
+fn stack() -> &'static HttpStack {
+    STACK.get_or_init(|| {
+        let tuning = HttpTuning::from_env();
+        HttpStack {
+            resolver: Arc::new(CachingResolver::new(tuning.dns_ttl_floor)),
+            tuning,
+            scrape_client: OnceLock::new(),
+        }
+    })
+}
+
+/// The process-wide counters.
+pub fn stats() -> &'static HttpStats {
+    &STATS
+}
+
+/// Count one outbound page or SERP request towards `connections{kind="reused"}`.
+pub fn note_request() {
+    STATS.requests.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A client builder carrying the shared pool settings and DNS cache; callers add
+/// timeouts, redirect policy, cookies or a proxy.
+pub fn builder() -> reqwest::ClientBuilder {
+    let stack = stack();
+    reqwest::Client::builder()
+        .pool_max_idle_per_host(stack.tuning.pool_max_idle_per_host)
+        .pool_idle_timeout(stack.tuning.pool_idle_timeout)
+        .tcp_keepalive(Duration::from_secs(60))
+        .http2_adaptive_window(true)
+        .dns_resolver(stack.resolver.clone())
+}
+
+/// The shared client for native page fetches: 30s timeout and no automatic redirects,
+/// since `RustScraper` follows them itself to check each hop against the domain policy.
+pub fn scrape_client() -> reqwest::Client {
+    stack()
+        .scrape_client
+        .get_or_init(|| {
+            scrape_builder()
+                .build()
+                .expect("Failed to create HTTP client")
+        })
+        .clone()
+}
+
+/// [`builder`] with the page-fetch timeout and redirect policy of [`scrape_client`].
+pub fn scrape_builder() -> reqwest::ClientBuilder {
+    builder()
+        .timeout(Duration::from_secs(30))
+        .redirect(reqwest::redirect::Policy::none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_answers_outlive_short_ttls_up_to_the_floor() {
+        let now = Instant::now();
+        let addrs = vec!["93.184.216.34".parse::<IpAddr>().unwrap()];
+        let mut cache = DnsCache::default();
+        cache.insert(
+            "example.com",
+            addrs.clone(),
+            now + Duration::from_secs(5),
+            Duration::from_secs(60),
+            now,
+        );
+        cache.insert(
+            "long.example",
+            addrs.clone(),
+            now + Duration::from_secs(600),
+            Duration::from_secs(60),
+            now,
+        );
+
+        let later = now + Duration::from_secs(30);
+        assert_eq!(cache.get("example.com", later), Some(addrs.clone()));
+        assert_eq!(
+            cache.get("example.com", now + Duration::from_secs(61)),
+            None
+        );
+        assert_eq!(
+            cache.get("long.example", now + Duration::from_secs(300)),
+            Some(addrs)
+        );
+        assert_eq!(cache.get("other.example", now), None);
+
+        // Expired entries are dropped on the next insert.
+        cache.insert(
+            "fresh.example",
+            vec![],
+            now,
+            Duration::ZERO,
+            now + Duration::from_secs(120),
+        );
+        assert!(!cache.entries.contains_key("example.com"));
+        assert!(cache.entries.contains_key("long.example"));
+    }
+
+    #[test]
+    fn reused_connections_never_go_negative() {
+        let stats = HttpStats::default();
+        stats.connections_created.store(3, Ordering::Relaxed);
+        stats.requests.store(2, Ordering::Relaxed);
+        let reused = stats
+            .series()
+            .into_iter()
+            .find(|(_, labels, _)| labels[0].1 == "reused")
+            .unwrap();
+        assert_eq!(reused.2, 0);
+    }
+}
@@ -0,0 +1,293 @@
+//! In-process counters and histograms rendered in the Prometheus text format.
+//!
+//! Every `AppState` owns one [`Metrics`] registry. Instrumented code records into it
+//! unconditionally (a short mutex hold per event); `GET /metrics` only exposes it when
+//! `CORTEX_SCOUT_METRICS` is enabled. Metric names use the stable `shadowcrawl_` prefix
+//! and are listed in [`FAMILIES`]; renaming one breaks dashboards built on it.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Tool executions by `tool` and `outcome` (`ok`/`error`). `scrape_url` counts every page
+/// scrape, including those run by `scrape_batch` and `crawl_website`.
+pub const TOOL_CALLS: &str = "shadowcrawl_tool_calls_total";
+pub const TOOL_DURATION: &str = "shadowcrawl_tool_duration_seconds";
+/// Search engine requests by `engine` and `outcome`
+/// (`success`/`blocked`/`timeout`/`transient`/`fatal`).
+pub const ENGINE_REQUESTS: &str = "shadowcrawl_engine_requests_total";
+pub const ENGINE_DURATION: &str = "shadowcrawl_engine_duration_seconds";
+/// Cache lookups by `cache` (`search`/`scrape`) and `result` (`hit`/`miss`).
+pub const CACHE_LOOKUPS: &str = "shadowcrawl_cache_lookups_total";
+/// Headless browser (CDP) page fetches by `outcome`.
+pub const BROWSER_FETCHES: &str = "shadowcrawl_browser_fetches_total";
+pub const BROWSER_DURATION: &str = "shadowcrawl_browser_fetch_duration_seconds";
+/// DNS cache lookups by `result` (`hit`/`miss`), process-wide (see [`crate::core::http_client`]).
+pub const DNS_LOOKUPS: &str = "shadowcrawl_dns_cache_lookups_total";
+/// Outbound connections by `kind` (`created`/`reused`), process-wide. `reused` counts page
+/// and SERP requests served over an already-open pooled connection.
+pub const HTTP_CONNECTIONS: &str = "shadowcrawl_http_connections_total";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Counter,
+    Histogram,
+}
+
+/// Exposition order, type and help text of every metric family.
+const FAMILIES: &[(&str, Kind, &str)] = &[
+    (
+        TOOL_CALLS,
+        Kind::Counter,
+        "Tool executions by tool and outcome.",
+    ),
+    (
+        TOOL_DURATION,
+        Kind::Histogram,
+        "Tool execution time in seconds.",
+    ),
+    (
+        ENGINE_REQUESTS,
+        Kind::Counter,
+        "Search engine requests by engine and outcome.",
+    ),
+    (
+        ENGINE_DURATION,
+        Kind::Histogram,
+        "Search engine request time in seconds.",
+    ),
+    (
+        CACHE_LOOKUPS,
+        Kind::Counter,
+        "Cache lookups by cache and result.",
+    ),
+    (
+        BROWSER_FETCHES,
+        Kind::Counter,
+        "Headless browser page fetches by outcome.",
+    ),
+    (
+        BROWSER_DURATION,
+        Kind::Histogram,
+        "Headless browser page fetch time in seconds.",
+    ),
+    (DNS_LOOKUPS, Kind::Counter, "DNS cache lookups by result."),
+    (
+        HTTP_CONNECTIONS,
+        Kind::Counter,
+        "Outbound HTTP connections created or reused.",
+    ),
+];
+
+/// Upper bounds (seconds) of the histogram buckets; `+Inf` is implicit.
+const BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+type SeriesKey = (&'static str, Vec<(&'static str, String)>);
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Non-cumulative count per bucket in [`BUCKETS`].
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    counters: Mutex<BTreeMap<SeriesKey, u64>>,
+    histograms: Mutex<BTreeMap<SeriesKey, Histogram>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn inc(&self, name: &'static str, labels: &[(&'static str, &str)]) {
+        let mut counters = self.counters.lock().unwrap();
+        *counters.entry(series_key(name, labels)).or_insert(0) += 1;
+    }
+
+    pub fn observe(&self, name: &'static str, labels: &[(&'static str, &str)], elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let mut histograms = self.histograms.lock().unwrap();
+        let histogram = histograms
+            .entry(series_key(name, labels))
+            .or_insert_with(|| Histogram {
+                buckets: vec![0; BUCKETS.len()],
+                ..Default::default()
+            });
+        if let Some(i) = BUCKETS.iter().position(|le| seconds <= *le) {
+            histogram.buckets[i] += 1;
+        }
+        histogram.sum += seconds;
+        histogram.count += 1;
+    }
+
+    pub fn record_tool(&self, tool: &str, ok: bool, elapsed: Duration) {
+        let outcome = if ok { "ok" } else { "error" };
+        self.inc(TOOL_CALLS, &[("tool", tool), ("outcome", outcome)]);
+        self.observe(TOOL_DURATION, &[("tool", tool)], elapsed);
+    }
+
+    pub fn record_engine(&self, engine: &str, outcome: &str, elapsed: Duration) {
+        self.inc(ENGINE_REQUESTS, &[("engine", engine), ("outcome", outcome)]);
+        self.observe(ENGINE_DURATION, &[("engine", engine)], elapsed);
+    }
+
+    pub fn record_cache(&self, cache: &str, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.inc(CACHE_LOOKUPS, &[("cache", cache), ("result", result)]);
+    }
+
+    pub fn record_browser_fetch(&self, ok: bool, elapsed: Duration) {
+        let outcome = if ok { "ok" } else { "error" };
+        self.inc(BROWSER_FETCHES, &[("outcome", outcome)]);
+        self.observe(BROWSER_DURATION, &[], elapsed);
+    }
+
+    /// Current value of one counter series (labels in recording order); for tests and
+    /// diagnostics.
+    pub fn counter(&self, name: &'static str, labels: &[(&'static str, &str)]) -> u64 {
+        let counters = self.counters.lock().unwrap();
+        counters
+            .get(&series_key(name, labels))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Prometheus text exposition (format 0.0.4) of every family, including empty ones.
+    pub fn render(&self) -> String {
+        let mut counters = self.counters.lock().unwrap().clone();
+        for (name, labels, value) in crate::core::http_client::stats().series() {
+            counters.insert((name, labels), value);
+        }
+        let histograms = self.histograms.lock().unwrap().clone();
+        let mut out = String::new();
+        for (name, kind, help) in FAMILIES {
+            out.push_str(&format!("# HELP {} {}\n", name, help));
+            match kind {
+                Kind::Counter => {
+                    out.push_str(&format!("# TYPE {} counter\n", name));
+                    for ((_, labels), value) in counters.iter().filter(|((n, _), _)| n == name) {
+                        out.push_str(&format!("{}{} {}\n", name, label_set(labels, None), value));
+                    }
+                }
+                Kind::Histogram => {
+                    out.push_str(&format!("# TYPE {} histogram\n", name));
+                    for ((_, labels), histogram) in
+                        histograms.iter().filter(|((n, _), _)| n == name)
+                    {
+                        let mut cumulative = 0;
+                        for (le, n) in BUCKETS.iter().zip(&histogram.buckets) {
+                            cumulative += n;
+                            out.push_str(&format!(
+                                "{}_bucket{} {}\n",
+                                name,
+                                label_set(labels, Some(&le.to_string())),
+                                cumulative
+                            ));
+                        }
+                        out.push_str(&format!(
+                            "{}_bucket{} {}\n",
+                            name,
+                            label_set(labels, Some("+Inf")),
+                            histogram.count
+                        ));
+                        out.push_str(&format!(
+                            "{}_sum{} {}\n",
+                            name,
+                            label_set(labels, None),
+                            histogram.sum
+                        ));
+                        out.push_str(&format!(
+                            "{}_count{} {}\n",
+                            name,
+                            label_set(labels, None),
+                            histogram.count
+                        ));
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn series_key(name: &'static str, labels: &[(&'static str, &str)]) -> SeriesKey {
+    (
+        name,
+        labels
+            .iter()
+            .map(|(key, value)| (*key, value.to_string()))
+            .collect(),
+    )
+}
+
+fn label_set(labels: &[(&'static str, String)], le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_cumulative_histograms() {
+        let metrics = Metrics::new();
+        metrics.record_cache("search", true);
+        metrics.record_cache("search", true);
+        metrics.record_cache("scrape", false);
+        metrics.record_engine("bing", "blocked", Duration::from_millis(80));
+        metrics.record_engine("bing", "success", Duration::from_millis(300));
+        metrics.inc(TOOL_CALLS, &[("tool", "a\"b"), ("outcome", "ok")]);
+
+        assert_eq!(
+            metrics.counter(CACHE_LOOKUPS, &[("cache", "search"), ("result", "hit")]),
+            2
+        );
+        let text = metrics.render();
+        assert!(text.contains("# TYPE shadowcrawl_cache_lookups_total counter\n"));
+        assert!(
+            text.contains("shadowcrawl_cache_lookups_total{cache=\"search\",result=\"hit\"} 2\n")
+        );
+        assert!(text.contains(
+            "shadowcrawl_engine_requests_total{engine=\"bing\",outcome=\"blocked\"} 1\n"
+        ));
+        assert!(text.contains(
+            "shadowcrawl_engine_duration_seconds_bucket{engine=\"bing\",le=\"0.1\"} 1\n"
+        ));
+        assert!(text.contains(
+            "shadowcrawl_engine_duration_seconds_bucket{engine=\"bing\",le=\"0.5\"} 2\n"
+        ));
+        assert!(text.contains(
+            "shadowcrawl_engine_duration_seconds_bucket{engine=\"bing\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(text.contains("shadowcrawl_engine_duration_seconds_count{engine=\"bing\"} 2\n"));
+        assert!(text.contains("tool=\"a\\\"b\""));
+        // Families with no samples still announce themselves.
+        assert!(text.contains("# TYPE shadowcrawl_browser_fetches_total counter\n"));
+        assert!(!text.contains("shadowcrawl_browser_fetches_total{"));
+        // The shared HTTP stack's counters are always present.
+        assert!(text.contains("shadowcrawl_dns_cache_lookups_total{result=\"hit\"} "));
+        assert!(text.contains("shadowcrawl_http_connections_total{kind=\"reused\"} "));
+    }
+}
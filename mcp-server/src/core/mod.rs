@@ -1,6 +1,20 @@
+pub mod api_auth;
 pub mod app_state;
+pub mod budget;
 pub mod config;
 pub mod content_quality;
+pub mod error;
+pub mod fixtures;
+pub mod http_cache;
+pub mod http_client;
+pub mod metrics;
+pub mod progress;
+pub mod rate_limit;
+pub mod readiness;
+pub mod request_id;
+pub mod retry;
+pub mod startup;
+pub mod tool_policy;
 pub mod tools_registry;
 pub mod types;
 
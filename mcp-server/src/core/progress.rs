@@ -0,0 +1,161 @@
+//! Progress updates from long-running tools (`crawl_website`, `scrape_batch`).
+//!
+//! A transport that can push notifications (stdio, WebSocket) runs the tool call inside
+//! [`forward_throttled`], which hands the reports on at most once per [`INTERVAL`]; the
+//! tools call [`report`] / [`report_url`] as work completes. Outside a scope (HTTP, direct
+//! library use) reporting is a no-op.
+
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::time::Instant;
+
+/// Minimum spacing of progress notifications for one call.
+pub const INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub progress: u64,
+    pub total: Option<u64>,
+    pub message: String,
+}
+
+tokio::task_local! {
+    static SINK: UnboundedSender<Progress>;
+}
+
+/// Run `fut` with [`report`] calls made inside it delivered to `sink`.
+pub async fn scope<F: Future>(sink: UnboundedSender<Progress>, fut: F) -> F::Output {
+    SINK.scope(sink, fut).await
+}
+
+pub fn report(progress: usize, total: Option<usize>, message: &str) {
+    let _ = SINK.try_with(|sink| {
+        let _ = sink.send(Progress {
+            progress: progress as u64,
+            total: total.map(|t| t as u64),
+            message: message.to_string(),
+        });
+    });
+}
+
+/// `notifications/progress` params for `update` under the caller's `progressToken`.
+pub fn notification_params(token: &serde_json::Value, update: &Progress) -> serde_json::Value {
+    let mut params = serde_json::json!({
+        "progressToken": token,
+        "progress": update.progress,
+        "message": update.message,
+    });
+    if let Some(total) = update.total {
+        params["total"] = serde_json::json!(total);
+    }
+    params
+}
+
+/// [`report`] for a run over URLs: `completed` of `total` known so far are done, `failed`
+/// of them unsuccessfully, and `url` is the one that just finished.
+pub fn report_url(completed: usize, failed: usize, total: Option<usize>, url: &str) {
+    report(completed, total, &format!("{} ({} failed)", url, failed));
+}
+
+/// Run `call` in a [`scope`] whose reports are passed to `emit` at most once per
+/// `interval`. The first report goes out at once; later ones inside the interval are
+/// coalesced into the newest. Whatever is still pending when `call` finishes is emitted
+/// before this returns, so the final count always precedes the result.
+pub async fn forward_throttled<F, E, Fut>(call: F, interval: Duration, mut emit: E) -> F::Output
+where
+    F: Future,
+    E: FnMut(Progress) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let (tx, mut rx) = mpsc::unbounded_channel::<Progress>();
+    let forward = async {
+        let mut pending = None;
+        let mut next_at = Instant::now();
+        loop {
+            tokio::select! {
+                update = rx.recv() => match update {
+                    Some(update) => pending = Some(update),
+                    None => break,
+                },
+                _ = tokio::time::sleep_until(next_at), if pending.is_some() => {}
+            }
+            if Instant::now() >= next_at {
+                if let Some(update) = pending.take() {
+                    emit(update).await;
+                    next_at = Instant::now() + interval;
+                }
+            }
+        }
+        if let Some(update) = pending {
+            emit(update).await;
+        }
+    };
+    let (output, ()) = tokio::join!(scope(tx, call), forward);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reports_reach_the_scope_sink_only() {
+        report(1, None, "nobody listening");
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let value = scope(tx, async {
+            report(1, Some(2), "https://example.com/a");
+            tokio::task::yield_now().await;
+            report(2, Some(2), "https://example.com/b");
+            42
+        })
+        .await;
+
+        assert_eq!(value, 42);
+        let first = rx.recv().await.unwrap();
+        assert_eq!(first.progress, 1);
+        assert_eq!(first.total, Some(2));
+        assert_eq!(first.message, "https://example.com/a");
+        assert_eq!(rx.recv().await.unwrap().progress, 2);
+        assert!(rx.recv().await.is_none(), "sink closes with the scope");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn forwarding_is_throttled_to_the_newest_report() {
+        let mut emitted = Vec::new();
+        let value = forward_throttled(
+            async {
+                for done in 1..=4 {
+                    report_url(
+                        done,
+                        done / 2,
+                        Some(4),
+                        &format!("https://example.com/{}", done),
+                    );
+                    tokio::time::sleep(Duration::from_millis(400)).await;
+                }
+                report_url(5, 2, Some(5), "https://example.com/5");
+                "done"
+            },
+            INTERVAL,
+            |update| {
+                emitted.push((update.progress, update.message));
+                async {}
+            },
+        )
+        .await;
+
+        assert_eq!(value, "done");
+        // Reports land at 0, 0.4, 0.8, 1.2 and 1.6s: the first goes out at once, the newest
+        // at the 1s mark, and the last (superseding 1.2s) when the call ends.
+        assert_eq!(
+            emitted,
+            [
+                (1, "https://example.com/1 (0 failed)".to_string()),
+                (3, "https://example.com/3 (1 failed)".to_string()),
+                (5, "https://example.com/5 (2 failed)".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Per-domain request pacing shared by every scraping path.
+//!
+//! Each registered domain (`news.example.co.uk` → `example.co.uk`) gets its own token
+//! bucket. Callers reserve a token before issuing a request and sleep until it is due,
+//! so concurrent scrapes of one site queue up behind each other while other sites
+//! proceed at full speed. The lock is only held to book the reservation, never while
+//! sleeping.
+//!
+//! Unlike `features::host_guard` (cross-process, randomized gaps, penalties after
+//! blocks) this limiter is in-process and deterministic.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Requests per second per domain when neither env nor the tool call overrides it.
+pub const DEFAULT_DOMAIN_RATE: f64 = 1.0;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+    acquired: u64,
+    delayed: u64,
+    total_wait: Duration,
+    max_wait: Duration,
+}
+
+/// Counters for one domain, exposed for diagnostics.
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainRateStats {
+    pub domain: String,
+    pub acquired: u64,
+    pub delayed: u64,
+    pub total_wait_ms: u64,
+    pub max_wait_ms: u64,
+}
+
+#[derive(Debug)]
+pub struct DomainRateLimiter {
+    rate: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl DomainRateLimiter {
+    /// `rate` is requests/second per domain (`<= 0` disables pacing); `burst` is how many
+    /// requests an idle domain may issue back-to-back.
+    pub fn new(rate: f64, burst: u32) -> Self {
+        Self {
+            rate,
+            burst: burst.max(1) as f64,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait for a request slot on `url`'s domain and return how long we waited.
+    ///
+    /// `rate_override` replaces the configured rate for this call only (a tool argument).
+    /// URLs without a host are never delayed.
+    pub async fn acquire(&self, url: &str, rate_override: Option<f64>) -> Duration {
+        let Some(domain) = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(registered_domain))
+        else {
+            return Duration::ZERO;
+        };
+        let wait = self.reserve(&domain, rate_override.unwrap_or(self.rate), Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        wait
+    }
+
+    /// Book one token for `domain` at `now`, returning the delay until it is usable.
+    fn reserve(&self, domain: &str, rate: f64, now: Instant) -> Duration {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(domain.to_string()).or_insert_with(|| Bucket {
+            tokens: self.burst,
+            last_refill: now,
+            acquired: 0,
+            delayed: 0,
+            total_wait: Duration::ZERO,
+            max_wait: Duration::ZERO,
+        });
+        bucket.acquired += 1;
+        if rate.is_nan() || rate <= 0.0 {
+            return Duration::ZERO;
+        }
+
+        // Reservations already handed out may have pushed `last_refill` into the future.
+        if now > bucket.last_refill {
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rate).min(self.burst);
+            bucket.last_refill = now;
+        }
+
+        // Going negative books a slot behind the callers already waiting.
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            return Duration::ZERO;
+        }
+        let wait = Duration::from_secs_f64(-bucket.tokens / rate);
+        bucket.delayed += 1;
+        bucket.total_wait += wait;
+        bucket.max_wait = bucket.max_wait.max(wait);
+        wait
+    }
+
+    /// Per-domain counters, busiest domain first.
+    pub fn stats(&self) -> Vec<DomainRateStats> {
+        let buckets = self.buckets.lock().unwrap();
+        let mut stats: Vec<DomainRateStats> = buckets
+            .iter()
+            .map(|(domain, b)| DomainRateStats {
+                domain: domain.clone(),
+                acquired: b.acquired,
+                delayed: b.delayed,
+                total_wait_ms: b.total_wait.as_millis() as u64,
+                max_wait_ms: b.max_wait.as_millis() as u64,
+            })
+            .collect();
+        stats.sort_by(|a, b| b.acquired.cmp(&a.acquired).then(a.domain.cmp(&b.domain)));
+        stats
+    }
+}
+
+/// Second-level labels that act as public suffixes under a ccTLD (`co.uk`, `com.au`, ...).
+const SECOND_LEVEL_SUFFIXES: &[&str] = &["co", "com", "net", "org", "gov", "edu", "ac", "ne", "or"];
+
+/// Approximate registrable domain without a public-suffix list. IP literals and
+/// single-label hosts are returned unchanged.
+pub fn registered_domain(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.parse::<std::net::IpAddr>().is_ok() || host.starts_with('[') {
+        return host;
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    if labels.len() <= 2 {
+        return host;
+    }
+    let n = labels.len();
+    let keep = if labels[n - 1].len() == 2 && SECOND_LEVEL_SUFFIXES.contains(&labels[n - 2]) {
+        3
+    } else {
+        2
+    };
+    labels[n - keep..].join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_domain_collapses_subdomains() {
+        assert_eq!(registered_domain("www.example.com"), "example.com");
+        assert_eq!(registered_domain("a.b.example.com"), "example.com");
+        assert_eq!(registered_domain("news.bbc.co.uk"), "bbc.co.uk");
+        assert_eq!(registered_domain("example.io"), "example.io");
+        assert_eq!(registered_domain("127.0.0.1"), "127.0.0.1");
+        assert_eq!(registered_domain("localhost"), "localhost");
+    }
+
+    #[test]
+    fn reservations_queue_behind_each_other() {
+        let limiter = DomainRateLimiter::new(2.0, 1);
+        let now = Instant::now();
+        assert_eq!(limiter.reserve("example.com", 2.0, now), Duration::ZERO);
+        assert_eq!(
+            limiter.reserve("example.com", 2.0, now),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            limiter.reserve("example.com", 2.0, now),
+            Duration::from_millis(1000)
+        );
+        // Other domains have their own bucket.
+        assert_eq!(limiter.reserve("example.org", 2.0, now), Duration::ZERO);
+
+        let stats = limiter.stats();
+        assert_eq!(stats[0].domain, "example.com");
+        assert_eq!(stats[0].acquired, 3);
+        assert_eq!(stats[0].delayed, 2);
+        assert_eq!(stats[0].max_wait_ms, 1000);
+    }
+
+    #[test]
+    fn tokens_refill_over_time_up_to_burst() {
+        let limiter = DomainRateLimiter::new(1.0, 2);
+        let start = Instant::now();
+        assert_eq!(limiter.reserve("example.com", 1.0, start), Duration::ZERO);
+        assert_eq!(limiter.reserve("example.com", 1.0, start), Duration::ZERO);
+        assert_eq!(
+            limiter.reserve("example.com", 1.0, start),
+            Duration::from_secs(1)
+        );
+
+        // A long idle period only restores `burst` tokens.
+        let later = start + Duration::from_secs(60);
+        assert_eq!(limiter.reserve("example.com", 1.0, later), Duration::ZERO);
+        assert_eq!(limiter.reserve("example.com", 1.0, later), Duration::ZERO);
+        assert_eq!(
+            limiter.reserve("example.com", 1.0, later),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn non_positive_rate_disables_pacing() {
+        let limiter = DomainRateLimiter::new(0.0, 1);
+        let now = Instant::now();
+        for _ in 0..5 {
+            assert_eq!(limiter.reserve("example.com", 0.0, now), Duration::ZERO);
+        }
+        assert_eq!(limiter.stats()[0].delayed, 0);
+    }
+}
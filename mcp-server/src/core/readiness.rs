@@ -0,0 +1,381 @@
+//! Runtime readiness for `GET /health/ready`.
+//!
+//! `/health/live` only says the process is up. Readiness looks at the subsystems a request
+//! depends on: whether any configured search engine is outside its bench / cool-down, the
+//! browser when the CDP search fallback is on, the proxy pool when one is loaded, the
+//! semantic memory breaker and how many outbound permits are free. Each one is reported
+//! as a [`SetupCheck`], so the breakdown reads like `GET /setup`.
+//!
+//! Only "hard" subsystems decide readiness (503 when one fails); the rest are reported but
+//! informational. `CORTEX_SCOUT_READY_HARD` sets the hard list as comma-separated ids
+//! (`none` for none); the default is [`DEFAULT_HARD`].
+
+use crate::core::AppState;
+use crate::setup::{CheckStatus, SetupCheck, SetupReport};
+use crate::tools::search::{engines, DEFAULT_SEARCH_ENGINES};
+use serde::Serialize;
+use std::sync::OnceLock;
+use tracing::warn;
+
+pub const SEARCH_ENGINES: &str = "search_engines";
+pub const BROWSER: &str = "browser";
+pub const PROXIES: &str = "proxies";
+pub const MEMORY: &str = "memory";
+pub const OUTBOUND: &str = "outbound";
+
+/// Every subsystem id, in report order.
+pub const SUBSYSTEMS: &[&str] = &[SEARCH_ENGINES, BROWSER, PROXIES, MEMORY, OUTBOUND];
+
+/// Subsystems that make the server unready by default. The browser is informational
+/// because the CDP fallback is on by default and many deployments have no Chrome; memory
+/// degrades quietly and a saturated semaphore only queues requests.
+pub const DEFAULT_HARD: &[&str] = &[SEARCH_ENGINES, PROXIES];
+
+/// Free outbound permits below this share of `OUTBOUND_LIMIT` are reported as a warning.
+const OUTBOUND_WARN_RATIO: f64 = 0.1;
+
+/// Which subsystems are hard dependencies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReadinessPolicy {
+    pub hard: Vec<String>,
+}
+
+impl Default for ReadinessPolicy {
+    fn default() -> Self {
+        Self {
+            hard: DEFAULT_HARD.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+}
+
+impl ReadinessPolicy {
+    pub fn from_env() -> Self {
+        match std::env::var("CORTEX_SCOUT_READY_HARD") {
+            Ok(raw) => Self::parse(&raw),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// A comma-separated id list; `none` (or an empty list) makes every subsystem
+    /// informational. Unknown ids are logged and ignored.
+    pub fn parse(raw: &str) -> Self {
+        let mut hard: Vec<String> = Vec::new();
+        for id in raw
+            .split(',')
+            .map(|id| id.trim().to_ascii_lowercase())
+            .filter(|id| !id.is_empty() && id != "none")
+        {
+            if !SUBSYSTEMS.contains(&id.as_str()) {
+                warn!(
+                    "unknown readiness subsystem '{}' ignored (known: {})",
+                    id,
+                    SUBSYSTEMS.join(", ")
+                );
+            } else if !hard.contains(&id) {
+                hard.push(id);
+            }
+        }
+        Self { hard }
+    }
+
+    pub fn is_hard(&self, id: &str) -> bool {
+        self.hard.iter().any(|h| h == id)
+    }
+}
+
+/// The process-wide policy, read from the environment on first use.
+pub fn global() -> &'static ReadinessPolicy {
+    static POLICY: OnceLock<ReadinessPolicy> = OnceLock::new();
+    POLICY.get_or_init(ReadinessPolicy::from_env)
+}
+
+/// The `GET /health/ready` body.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessReport {
+    /// `false` when a hard subsystem failed; answered with 503.
+    pub ready: bool,
+    /// `fail` when not ready, else `warn` if any check failed or warned, else `pass`.
+    pub status: CheckStatus,
+    /// Subsystem ids that decide `ready`.
+    pub hard: Vec<String>,
+    pub checked_at: String,
+    #[serde(flatten)]
+    pub report: SetupReport,
+}
+
+impl ReadinessReport {
+    fn from_checks(policy: &ReadinessPolicy, checks: Vec<SetupCheck>) -> Self {
+        let ready = !checks
+            .iter()
+            .any(|c| c.status.is_fail() && policy.is_hard(&c.id));
+        let report = SetupReport { checks };
+        let status = match report.overall_status() {
+            _ if !ready => CheckStatus::Fail,
+            CheckStatus::Fail => CheckStatus::Warn,
+            status => status,
+        };
+        Self {
+            ready,
+            status,
+            hard: policy.hard.clone(),
+            checked_at: chrono::Utc::now().to_rfc3339(),
+            report,
+        }
+    }
+}
+
+/// Check every subsystem against `policy`.
+pub async fn check(state: &AppState, policy: &ReadinessPolicy) -> ReadinessReport {
+    let checks = vec![
+        search_engines_check(state),
+        browser_check(state),
+        proxies_check(state).await,
+        memory_check(state),
+        outbound_check(state),
+    ];
+    ReadinessReport::from_checks(policy, checks)
+}
+
+fn check_result(id: &str, title: &str, status: CheckStatus, details: String) -> SetupCheck {
+    SetupCheck {
+        id: id.to_string(),
+        title: title.to_string(),
+        status,
+        details,
+        actions: Vec::new(),
+    }
+}
+
+/// Fails when every engine in `SEARCH_ENGINES` is benched or cooling down.
+fn search_engines_check(state: &AppState) -> SetupCheck {
+    let raw =
+        std::env::var("SEARCH_ENGINES").unwrap_or_else(|_| DEFAULT_SEARCH_ENGINES.to_string());
+    let mut configured: Vec<&str> = Vec::new();
+    for entry in raw.split(',').map(|s| s.trim().to_ascii_lowercase()) {
+        if let Some(engine) = engines::lookup(&entry) {
+            if !configured.contains(&engine.name()) {
+                configured.push(engine.name());
+            }
+        }
+    }
+    let title = "Search engines";
+    if configured.is_empty() {
+        return check_result(
+            SEARCH_ENGINES,
+            title,
+            CheckStatus::Fail,
+            format!("SEARCH_ENGINES names no known engine: '{}'", raw),
+        );
+    }
+
+    let mut unavailable = Vec::new();
+    for name in &configured {
+        if let Some((remaining, reason)) = state.engine_stats.bench_remaining(name) {
+            unavailable.push(format!(
+                "{} benched for {}s ({})",
+                name,
+                remaining.as_secs(),
+                reason
+            ));
+        } else if let Some(remaining) = state.engine_backoff.remaining(name) {
+            unavailable.push(format!(
+                "{} cooling down for {}s",
+                name,
+                remaining.as_secs()
+            ));
+        }
+    }
+    let available = configured.len() - unavailable.len();
+    let status = if available == 0 {
+        CheckStatus::Fail
+    } else if unavailable.is_empty() {
+        CheckStatus::Pass
+    } else {
+        CheckStatus::Warn
+    };
+    let mut details = format!("{} of {} engine(s) available", available, configured.len());
+    if !unavailable.is_empty() {
+        details.push_str(&format!(": {}", unavailable.join("; ")));
+    }
+    check_result(SEARCH_ENGINES, title, status, details)
+}
+
+/// Skipped unless the CDP search fallback is on; then fails without a local browser.
+fn browser_check(state: &AppState) -> SetupCheck {
+    let title = "Browser (CDP search fallback)";
+    if !engines::cdp_fallback_configured() {
+        return check_result(
+            BROWSER,
+            title,
+            CheckStatus::Skip,
+            "SEARCH_CDP_FALLBACK is off".to_string(),
+        );
+    }
+    if !crate::scraping::browser_manager::native_browser_available() {
+        return check_result(
+            BROWSER,
+            title,
+            CheckStatus::Fail,
+            "No Chrome/Chromium found; blocked searches cannot fall back to a browser".to_string(),
+        );
+    }
+    let details = match state.browser_pool.as_ref().map(|pool| pool.stats()) {
+        Some(stats) => format!(
+            "Browser available; pool {}/{} in use, {} queued",
+            stats.in_use, stats.size, stats.queued
+        ),
+        None => "Browser available".to_string(),
+    };
+    check_result(BROWSER, title, CheckStatus::Pass, details)
+}
+
+/// Skipped without a proxy pool; fails when no proxy in it is enabled.
+async fn proxies_check(state: &AppState) -> SetupCheck {
+    let title = "Proxy pool";
+    let Some(manager) = state.proxy_manager.as_ref() else {
+        return check_result(
+            PROXIES,
+            title,
+            CheckStatus::Skip,
+            "No proxy pool configured".to_string(),
+        );
+    };
+    match manager.get_status().await {
+        Ok(status) if status.enabled_proxies == 0 => check_result(
+            PROXIES,
+            title,
+            CheckStatus::Fail,
+            format!("All {} proxies are disabled", status.total_proxies),
+        ),
+        Ok(status) => check_result(
+            PROXIES,
+            title,
+            CheckStatus::Pass,
+            format!(
+                "{} of {} proxies enabled, {} with recent failures",
+                status.enabled_proxies, status.total_proxies, status.failed_proxies
+            ),
+        ),
+        Err(e) => check_result(
+            PROXIES,
+            title,
+            CheckStatus::Fail,
+            format!("Proxy status unavailable: {}", e),
+        ),
+    }
+}
+
+/// The semantic memory breaker: skipped when memory is off, failing while degraded.
+fn memory_check(state: &AppState) -> SetupCheck {
+    let health = state.memory_health();
+    let status = match health.status.as_str() {
+        "healthy" => CheckStatus::Pass,
+        "disabled" => CheckStatus::Skip,
+        "initializing" => CheckStatus::Warn,
+        _ => CheckStatus::Fail,
+    };
+    let mut details = format!("Semantic memory {}", health.status);
+    if let Some(error) = health.last_error.as_deref().filter(|_| status.is_fail()) {
+        details.push_str(&format!(": {}", error));
+    }
+    check_result(MEMORY, "Semantic memory", status, details)
+}
+
+/// Warns when few outbound permits are free and fails when none are.
+fn outbound_check(state: &AppState) -> SetupCheck {
+    let capacity = state.outbound_capacity;
+    let free = state.outbound_limit.available_permits();
+    let status = if free == 0 {
+        CheckStatus::Fail
+    } else if (free as f64) < capacity as f64 * OUTBOUND_WARN_RATIO {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    check_result(
+        OUTBOUND,
+        "Outbound requests",
+        status,
+        format!("{} of {} outbound permits free", free, capacity),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::search::engine_backoff::EngineBackoff;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn state() -> AppState {
+        let mut state = AppState::new(reqwest::Client::new());
+        state.engine_backoff = Arc::new(EngineBackoff::new(None));
+        state
+    }
+
+    fn find<'a>(report: &'a ReadinessReport, id: &str) -> &'a SetupCheck {
+        report.report.checks.iter().find(|c| c.id == id).unwrap()
+    }
+
+    #[test]
+    fn policy_parses_known_ids_and_none() {
+        assert_eq!(ReadinessPolicy::default().hard, DEFAULT_HARD);
+        let policy = ReadinessPolicy::parse(" Memory, outbound,bogus,memory ");
+        assert_eq!(policy.hard, [MEMORY, OUTBOUND]);
+        assert!(policy.is_hard(MEMORY) && !policy.is_hard(SEARCH_ENGINES));
+        assert!(ReadinessPolicy::parse("none").hard.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cooling_engines_fail_readiness_only_when_hard() {
+        let state = state();
+        let outbound = state.outbound_limit.clone();
+        let _held = outbound
+            .acquire_many(state.outbound_capacity as u32)
+            .await
+            .unwrap();
+        let configured =
+            std::env::var("SEARCH_ENGINES").unwrap_or_else(|_| DEFAULT_SEARCH_ENGINES.to_string());
+        for engine in configured
+            .split(',')
+            .filter_map(|e| engines::lookup(e.trim()))
+        {
+            state.engine_backoff.record_block(
+                engine.name(),
+                "http_429",
+                Some(Duration::from_secs(120)),
+            );
+        }
+
+        let report = check(&state, &ReadinessPolicy::default()).await;
+        assert!(!report.ready);
+        assert_eq!(report.status, CheckStatus::Fail);
+        let engines = find(&report, SEARCH_ENGINES);
+        assert_eq!(engines.status, CheckStatus::Fail);
+        assert!(
+            engines.details.contains("cooling down"),
+            "{}",
+            engines.details
+        );
+        assert_eq!(find(&report, PROXIES).status, CheckStatus::Skip);
+        assert_eq!(find(&report, OUTBOUND).status, CheckStatus::Fail);
+
+        // The same failures are informational under an empty hard list.
+        let report = check(&state, &ReadinessPolicy::parse("none")).await;
+        assert!(report.ready);
+        assert_eq!(report.status, CheckStatus::Warn);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["checks"][0]["id"], SEARCH_ENGINES);
+        assert_eq!(json["checks"][0]["status"], "fail");
+    }
+
+    #[tokio::test]
+    async fn idle_server_with_engines_available_is_ready() {
+        let state = state();
+        let report = check(&state, &ReadinessPolicy::default()).await;
+        assert!(report.ready, "{:?}", report.report.checks);
+        assert_eq!(find(&report, SEARCH_ENGINES).status, CheckStatus::Pass);
+        assert_eq!(find(&report, OUTBOUND).status, CheckStatus::Pass);
+        assert_eq!(report.report.checks.len(), SUBSYSTEMS.len());
+    }
+}
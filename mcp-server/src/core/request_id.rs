@@ -0,0 +1,163 @@
+//! Request IDs that tie together every log line of one HTTP request or tool call.
+//!
+//! Each transport picks an ID at its boundary and runs the work inside [`scope`], which
+//! makes the ID available through [`current`] and opens a `request{request_id=…}` span
+//! around everything the call logs:
+//!
+//! - HTTP: an incoming `X-Request-Id` header is honoured, otherwise one is generated; the
+//!   ID is echoed in the response's `X-Request-Id` header ([`propagate`]).
+//! - JSON-RPC (stdio, WebSocket, `POST /mcp`): `params._meta.requestId` is honoured,
+//!   otherwise the ID is derived from the JSON-RPC id ([`for_jsonrpc_id`]).
+//!
+//! Tool results carry the ID as `request_id` (see `mcp::http::instrument_tool_response`).
+//! Work spawned onto other tasks leaves the scope, like progress reporting does.
+
+use axum::extract::Request;
+use axum::http::{HeaderName, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use serde_json::Value;
+use std::future::Future;
+use tracing::Instrument;
+
+pub static HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied ID accepted; longer ones are replaced by a generated ID.
+const MAX_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT: String;
+}
+
+/// Run `fut` with [`current`] returning `id` and its logs under a `request` span.
+pub async fn scope<F: Future>(id: String, fut: F) -> F::Output {
+    let span = tracing::info_span!("request", request_id = %id);
+    CURRENT.scope(id, fut.instrument(span)).await
+}
+
+/// The ID of the request this task is serving, if it runs inside a [`scope`].
+pub fn current() -> Option<String> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// A fresh ID for a request that did not bring one.
+pub fn generate() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// A client-supplied ID, if it is short printable ASCII that is safe to log and echo.
+pub fn accept(candidate: &str) -> Option<String> {
+    let candidate = candidate.trim();
+    let valid = !candidate.is_empty()
+        && candidate.len() <= MAX_LEN
+        && candidate
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-_.:/@+=".contains(&b));
+    valid.then(|| candidate.to_string())
+}
+
+/// `requestId` from a JSON-RPC request's `_meta`, if the client set a usable one.
+pub fn from_meta(meta: Option<&Value>) -> Option<String> {
+    meta.and_then(|m| m.get("requestId"))
+        .and_then(Value::as_str)
+        .and_then(accept)
+}
+
+/// The ID for JSON-RPC request `id` on a connection identified by `session`. JSON-RPC ids
+/// only distinguish requests within one session, so the session keeps them apart in logs.
+pub fn for_jsonrpc_id(session: &str, id: &Value) -> String {
+    let id = match id {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    accept(&format!("{}-{}", session, id)).unwrap_or_else(generate)
+}
+
+/// A short random session name, for [`for_jsonrpc_id`].
+pub fn new_session(transport: &str) -> String {
+    format!("{}-{}", transport, &generate()[..8])
+}
+
+/// Axum middleware: serve the request inside a [`scope`] and echo its ID back in the
+/// `X-Request-Id` response header.
+pub async fn propagate(req: Request, next: Next) -> Response {
+    let id = req
+        .headers()
+        .get(&HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(accept)
+        .unwrap_or_else(generate);
+    let mut response = scope(id.clone(), next.run(req)).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(HEADER.clone(), value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::Router;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn scope_sets_the_current_id() {
+        assert_eq!(current(), None);
+        let seen = scope("abc-1".to_string(), async {
+            tokio::task::yield_now().await;
+            current()
+        })
+        .await;
+        assert_eq!(seen.as_deref(), Some("abc-1"));
+        assert_eq!(current(), None);
+    }
+
+    #[test]
+    fn client_ids_are_validated() {
+        assert_eq!(accept(" req-42 ").as_deref(), Some("req-42"));
+        assert_eq!(accept(""), None);
+        assert_eq!(accept("two words"), None);
+        assert_eq!(accept("line\nbreak"), None);
+        assert_eq!(accept(&"x".repeat(MAX_LEN + 1)), None);
+
+        let meta = json!({ "requestId": "trace-7", "progressToken": 1 });
+        assert_eq!(from_meta(Some(&meta)).as_deref(), Some("trace-7"));
+        assert_eq!(from_meta(Some(&json!({ "requestId": 7 }))), None);
+        assert_eq!(from_meta(None), None);
+    }
+
+    #[test]
+    fn jsonrpc_ids_are_qualified_by_session() {
+        assert_eq!(for_jsonrpc_id("stdio-1a2b", &json!(7)), "stdio-1a2b-7");
+        assert_eq!(for_jsonrpc_id("ws-9f", &json!("call-3")), "ws-9f-call-3");
+        let session = new_session("stdio");
+        assert!(session.starts_with("stdio-") && session.len() == "stdio-".len() + 8);
+    }
+
+    #[tokio::test]
+    async fn http_requests_echo_their_id() {
+        let app = Router::new()
+            .route("/", get(|| async { current().unwrap_or_default() }))
+            .layer(axum::middleware::from_fn(propagate));
+
+        let req = Request::builder()
+            .uri("/")
+            .header("X-Request-Id", "client-supplied")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.headers()[&HEADER], "client-supplied");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"client-supplied");
+
+        let req = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(req).await.unwrap();
+        let generated = response.headers()[&HEADER].to_str().unwrap();
+        assert_eq!(generated.len(), 32);
+    }
+}
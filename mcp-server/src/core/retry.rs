@@ -0,0 +1,279 @@
+//! Retry policy for transient HTTP failures (connection resets, timeouts, 5xx gateways, 429).
+//!
+//! Every outbound fetch here is a GET, so replaying a request is always safe. Attempts are
+//! spaced with exponential backoff plus jitter; a 429 carrying `Retry-After` waits exactly
+//! as long as the server asked. Callers pass a deadline so retries never outlive the
+//! stage or engine budget they run under.
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::debug;
+
+/// Status codes retried when `CORTEX_SCOUT_RETRY_STATUSES` is unset.
+pub const DEFAULT_RETRYABLE_STATUSES: &[u16] = &[408, 429, 502, 503, 504];
+
+/// A `Retry-After` longer than this is treated as "not worth waiting for".
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one; `1` disables retrying.
+    pub max_attempts: u32,
+    /// Delay before the second attempt; doubles for every further attempt.
+    pub base_delay: Duration,
+    /// Upper bound for a single backoff delay (before jitter).
+    pub max_delay: Duration,
+    /// Fraction of the delay randomly added or removed (`0.0..=1.0`).
+    pub jitter: f64,
+    pub retryable_statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(4),
+            jitter: 0.2,
+            retryable_statuses: DEFAULT_RETRYABLE_STATUSES.to_vec(),
+        }
+    }
+}
+
+/// Final response (or error) of a retried request and how many attempts it took.
+pub struct RetryOutcome {
+    pub result: Result<reqwest::Response, reqwest::Error>,
+    pub attempts: u32,
+}
+
+impl RetryPolicy {
+    /// Defaults overridden by `CORTEX_SCOUT_RETRY_MAX_ATTEMPTS`, `CORTEX_SCOUT_RETRY_BASE_DELAY_MS`,
+    /// `CORTEX_SCOUT_RETRY_MAX_DELAY_MS`, `CORTEX_SCOUT_RETRY_JITTER` and
+    /// `CORTEX_SCOUT_RETRY_STATUSES` (comma-separated status codes).
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_u64 = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+
+        Self {
+            max_attempts: env_u64("CORTEX_SCOUT_RETRY_MAX_ATTEMPTS")
+                .map(|n| n.clamp(1, 10) as u32)
+                .unwrap_or(defaults.max_attempts),
+            base_delay: env_u64("CORTEX_SCOUT_RETRY_BASE_DELAY_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.base_delay),
+            max_delay: env_u64("CORTEX_SCOUT_RETRY_MAX_DELAY_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.max_delay),
+            jitter: std::env::var("CORTEX_SCOUT_RETRY_JITTER")
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|j| j.is_finite())
+                .map(|j| j.clamp(0.0, 1.0))
+                .unwrap_or(defaults.jitter),
+            retryable_statuses: std::env::var("CORTEX_SCOUT_RETRY_STATUSES")
+                .ok()
+                .and_then(|v| parse_status_list(&v))
+                .unwrap_or(defaults.retryable_statuses),
+        }
+    }
+
+    /// Builder: per-call override of the attempt count (clamped to `1..=10`).
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.clamp(1, 10);
+        self
+    }
+
+    pub fn is_retryable_status(&self, status: u16) -> bool {
+        self.retryable_statuses.contains(&status)
+    }
+
+    /// Backoff before attempt `attempt + 1`, without jitter.
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    fn jittered(&self, delay: Duration) -> Duration {
+        if self.jitter <= 0.0 || delay.is_zero() {
+            return delay;
+        }
+        use rand::prelude::*;
+        let spread = rand::rng().random_range(-self.jitter..=self.jitter);
+        delay.mul_f64((1.0 + spread).max(0.0))
+    }
+
+    /// How long to wait before retrying a response with `status`, or `None` if it should
+    /// not be retried. A 429 with a usable `Retry-After` uses the server's value.
+    fn delay_for_status(&self, attempt: u32, status: u16, headers: &HeaderMap) -> Option<Duration> {
+        if !self.is_retryable_status(status) {
+            return None;
+        }
+        if status == 429 {
+            if let Some(wait) = headers
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| parse_retry_after(v, SystemTime::now()))
+            {
+                return (wait <= MAX_RETRY_AFTER).then_some(wait);
+            }
+        }
+        Some(self.jittered(self.backoff_delay(attempt)))
+    }
+
+    /// Send the request built by `build` until it succeeds, fails permanently, runs out of
+    /// attempts, or the next wait would end past `deadline`. The last response is returned
+    /// as-is, so a request that keeps answering 503 still yields that 503.
+    pub async fn send<F>(&self, deadline: Option<Instant>, mut build: F) -> RetryOutcome
+    where
+        F: FnMut() -> reqwest::RequestBuilder,
+    {
+        let max_attempts = self.max_attempts.max(1);
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let result = build().send().await;
+            let delay = match &result {
+                Ok(resp) => self.delay_for_status(attempt, resp.status().as_u16(), resp.headers()),
+                Err(e) if is_transient_error(e) => Some(self.jittered(self.backoff_delay(attempt))),
+                Err(_) => None,
+            };
+
+            let Some(delay) = delay.filter(|_| attempt < max_attempts) else {
+                return RetryOutcome {
+                    result,
+                    attempts: attempt,
+                };
+            };
+            if deadline.is_some_and(|d| Instant::now() + delay >= d) {
+                debug!("retry budget exhausted after {} attempt(s)", attempt);
+                return RetryOutcome {
+                    result,
+                    attempts: attempt,
+                };
+            }
+
+            match &result {
+                Ok(resp) => debug!(
+                    "HTTP {} from {}; retrying in {}ms (attempt {}/{})",
+                    resp.status().as_u16(),
+                    resp.url(),
+                    delay.as_millis(),
+                    attempt + 1,
+                    max_attempts
+                ),
+                Err(e) => debug!(
+                    "request failed ({}); retrying in {}ms (attempt {}/{})",
+                    e,
+                    delay.as_millis(),
+                    attempt + 1,
+                    max_attempts
+                ),
+            }
+            drop(result);
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// Connection-level failures worth replaying; redirect loops, bad URLs and body
+/// decoding errors are not.
+fn is_transient_error(err: &reqwest::Error) -> bool {
+    err.is_connect() || err.is_timeout() || (err.is_request() && !err.is_builder())
+}
+
+/// `Retry-After` is either delta-seconds or an HTTP-date.
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at: SystemTime = chrono::DateTime::parse_from_rfc2822(value).ok()?.into();
+    Some(at.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+fn parse_status_list(raw: &str) -> Option<Vec<u16>> {
+    let codes: Vec<u16> = raw
+        .split(',')
+        .filter_map(|s| s.trim().parse::<u16>().ok())
+        .filter(|c| (100..=599).contains(c))
+        .collect();
+    (!codes.is_empty()).then_some(codes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn no_jitter() -> RetryPolicy {
+        RetryPolicy {
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let policy = no_jitter();
+        assert_eq!(policy.backoff_delay(1), Duration::from_millis(250));
+        assert_eq!(policy.backoff_delay(2), Duration::from_millis(500));
+        assert_eq!(policy.backoff_delay(3), Duration::from_millis(1000));
+        assert_eq!(policy.backoff_delay(40), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn jitter_stays_within_fraction() {
+        let policy = RetryPolicy::default();
+        for _ in 0..100 {
+            let d = policy.jittered(Duration::from_millis(1000));
+            assert!(d >= Duration::from_millis(800) && d <= Duration::from_millis(1200));
+        }
+    }
+
+    #[test]
+    fn retry_after_seconds_and_http_date() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_445_412_480);
+        assert_eq!(parse_retry_after(" 7 ", now), Some(Duration::from_secs(7)));
+        // 2015-10-21T07:28:00Z is 1_445_412_480.
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:10 GMT", now),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn status_delay_honors_retry_after_on_429() {
+        let policy = no_jitter();
+        let mut headers = HeaderMap::new();
+        assert_eq!(policy.delay_for_status(1, 404, &headers), None);
+        assert_eq!(
+            policy.delay_for_status(2, 503, &headers),
+            Some(Duration::from_millis(500))
+        );
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        assert_eq!(
+            policy.delay_for_status(1, 429, &headers),
+            Some(Duration::from_secs(3))
+        );
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        assert_eq!(policy.delay_for_status(1, 429, &headers), None);
+    }
+
+    #[test]
+    fn status_list_parsing() {
+        assert_eq!(parse_status_list("500, 503,abc"), Some(vec![500, 503]));
+        assert_eq!(parse_status_list("  "), None);
+        assert_eq!(parse_status_list("42"), None);
+        assert_eq!(RetryPolicy::default().with_max_attempts(0).max_attempts, 1);
+    }
+}
@@ -0,0 +1,341 @@
+//! HTTP server startup.
+//!
+//! The listener binds before the slow optional components (the setup checklist, semantic
+//! memory, the proxy pool) initialize, so orchestrators see `/health/live` answer at once.
+//! Until [`StartupGate::open`] installs the full router, the gate answers liveness itself,
+//! reports `/health/ready` as 503 with the startup progress, and turns every other request
+//! away as `unavailable`.
+//!
+//! [`StartupTracker::run`] times each component into the [`StartupReport`], logged once
+//! when startup finishes and served at `GET /debug/startup`. A component that fails is
+//! reported and the server continues without it; one still running after
+//! `CORTEX_SCOUT_STARTUP_TIMEOUT_SECS` is abandoned and reported as `degraded`.
+
+use crate::core::error::ScoutError;
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::Router;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tower::ServiceExt;
+use tracing::{info, warn};
+
+pub const SETUP_CHECKS: &str = "setup_checks";
+pub const MEMORY: &str = "memory";
+pub const PROXIES: &str = "proxies";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupPhase {
+    Starting,
+    Ready,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Ready,
+    /// Not configured (memory disabled, no proxy list).
+    Skipped,
+    /// Initialization failed; the server runs without the component.
+    Failed,
+    /// Still initializing at the startup timeout; abandoned.
+    Degraded,
+}
+
+/// What one component's initialization produced.
+pub enum Init<T> {
+    Ready(T, String),
+    Skipped(String),
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentTiming {
+    pub id: String,
+    pub status: ComponentStatus,
+    pub duration_ms: u64,
+    pub details: String,
+}
+
+/// The `GET /debug/startup` body.
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupReport {
+    pub phase: StartupPhase,
+    pub started_at: String,
+    /// Time from the start of startup until the listener accepted connections.
+    pub listener_bound_ms: Option<u64>,
+    /// Time until every component finished or was abandoned.
+    pub total_ms: Option<u64>,
+    /// In completion order.
+    pub components: Vec<ComponentTiming>,
+}
+
+impl StartupReport {
+    fn summary(&self) -> String {
+        let components: Vec<String> = self
+            .components
+            .iter()
+            .map(|c| {
+                let status = serde_json::to_value(c.status).unwrap_or_default();
+                format!(
+                    "{} {} in {}ms",
+                    c.id,
+                    status.as_str().unwrap_or(""),
+                    c.duration_ms
+                )
+            })
+            .collect();
+        format!(
+            "Startup finished in {}ms (listener bound after {}ms): {}",
+            self.total_ms.unwrap_or_default(),
+            self.listener_bound_ms.unwrap_or_default(),
+            components.join(", ")
+        )
+    }
+}
+
+/// Startup progress, shared by the gate, `main` and `AppState`.
+#[derive(Debug)]
+pub struct StartupTracker {
+    started: Instant,
+    report: RwLock<StartupReport>,
+}
+
+impl Default for StartupTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StartupTracker {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            report: RwLock::new(StartupReport {
+                phase: StartupPhase::Starting,
+                started_at: chrono::Utc::now().to_rfc3339(),
+                listener_bound_ms: None,
+                total_ms: None,
+                components: Vec::new(),
+            }),
+        }
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    pub fn listener_bound(&self) {
+        self.report.write().unwrap().listener_bound_ms = Some(self.elapsed_ms());
+    }
+
+    /// Run one component's initialization and record how long it took. `None` unless it
+    /// came up; with a `timeout`, a component still running then is dropped as degraded.
+    pub async fn run<T>(
+        &self,
+        id: &str,
+        timeout: Option<Duration>,
+        init: impl Future<Output = Init<T>>,
+    ) -> Option<T> {
+        let started = Instant::now();
+        let outcome = match timeout {
+            Some(limit) => tokio::time::timeout(limit, init).await.ok(),
+            None => Some(init.await),
+        };
+        let (status, details, value) = match outcome {
+            Some(Init::Ready(value, details)) => (ComponentStatus::Ready, details, Some(value)),
+            Some(Init::Skipped(details)) => (ComponentStatus::Skipped, details, None),
+            Some(Init::Failed(details)) => (ComponentStatus::Failed, details, None),
+            None => {
+                let details = format!(
+                    "Still initializing after {}s; continuing without it",
+                    timeout.unwrap_or_default().as_secs()
+                );
+                warn!("startup: {}: {}", id, details);
+                (ComponentStatus::Degraded, details, None)
+            }
+        };
+        self.report
+            .write()
+            .unwrap()
+            .components
+            .push(ComponentTiming {
+                id: id.to_string(),
+                status,
+                duration_ms: started.elapsed().as_millis() as u64,
+                details,
+            });
+        value
+    }
+
+    /// Mark startup complete and log the report.
+    pub fn finish(&self) {
+        let mut report = self.report.write().unwrap();
+        report.phase = StartupPhase::Ready;
+        report.total_ms = Some(self.elapsed_ms());
+        info!("{}", report.summary());
+    }
+
+    pub fn report(&self) -> StartupReport {
+        self.report.read().unwrap().clone()
+    }
+}
+
+/// The server's router: answers for the server until startup finishes, then passes
+/// every request to the router given to [`StartupGate::open`].
+#[derive(Clone)]
+pub struct StartupGate {
+    tracker: Arc<StartupTracker>,
+    app: Arc<OnceLock<Router>>,
+}
+
+impl StartupGate {
+    pub fn new(tracker: Arc<StartupTracker>) -> Self {
+        Self {
+            tracker,
+            app: Arc::default(),
+        }
+    }
+
+    /// Serve `app` from now on.
+    pub fn open(&self, app: Router) {
+        if self.app.set(app).is_err() {
+            warn!("startup gate opened twice; keeping the first router");
+        }
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new().fallback(gate).with_state(self.clone())
+    }
+}
+
+async fn gate(State(gate): State<StartupGate>, req: Request) -> Response {
+    if let Some(app) = gate.app.get() {
+        return match app.clone().oneshot(req).await {
+            Ok(response) => response,
+            Err(never) => match never {},
+        };
+    }
+    match req.uri().path() {
+        "/" | "/health" | "/health/live" => Json(serde_json::json!({
+            "status": "starting",
+            "service": "cortex-scout",
+            "version": env!("CARGO_PKG_VERSION"),
+        }))
+        .into_response(),
+        "/health/ready" => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({
+                "ready": false,
+                "status": "starting",
+                "startup": gate.tracker.report(),
+            })),
+        )
+            .into_response(),
+        _ => ScoutError::unavailable("Server is starting; retry shortly")
+            .into_http()
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+
+    async fn slow<T>(delay: Duration, outcome: Init<T>) -> Init<T> {
+        tokio::time::sleep(delay).await;
+        outcome
+    }
+
+    #[tokio::test]
+    async fn listener_answers_while_components_initialize() {
+        let tracker = Arc::new(StartupTracker::new());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tracker.listener_bound();
+        let gate = StartupGate::new(tracker.clone());
+        let app = gate.router();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let init = {
+            let tracker = tracker.clone();
+            tokio::spawn(async move {
+                let timeout = Some(Duration::from_millis(300));
+                tokio::join!(
+                    tracker.run(
+                        SETUP_CHECKS,
+                        timeout,
+                        slow(Duration::from_millis(50), Init::Ready((), "ok".into()))
+                    ),
+                    tracker.run(
+                        MEMORY,
+                        timeout,
+                        slow(Duration::from_millis(150), Init::Ready(7, "ok".into()))
+                    ),
+                    tracker.run(
+                        PROXIES,
+                        timeout,
+                        slow(Duration::from_secs(60), Init::Ready((), "hung".into()))
+                    ),
+                )
+            })
+        };
+
+        let client = reqwest::Client::new();
+        let fetch = |path: &str| client.get(format!("http://{}{}", addr, path)).send();
+        let live = fetch("/health/live").await.unwrap();
+        assert_eq!(live.status(), 200);
+        assert_eq!(
+            live.json::<serde_json::Value>().await.unwrap()["status"],
+            "starting"
+        );
+        let ready = fetch("/health/ready").await.unwrap();
+        assert_eq!(ready.status(), 503);
+        assert_eq!(fetch("/search").await.unwrap().status(), 503);
+
+        let (setup, memory, proxies) = init.await.unwrap();
+        assert_eq!((setup, memory, proxies), (Some(()), Some(7), None));
+        gate.open(Router::new().route("/health/ready", get(|| async { "ready" })));
+        tracker.finish();
+        let ready = fetch("/health/ready").await.unwrap();
+        assert_eq!(ready.status(), 200);
+
+        let report = tracker.report();
+        assert_eq!(report.phase, StartupPhase::Ready);
+        assert!(report.listener_bound_ms.unwrap() < 50);
+        let timing = |id: &str| report.components.iter().find(|c| c.id == id).unwrap();
+        assert_eq!(timing(SETUP_CHECKS).status, ComponentStatus::Ready);
+        assert!(timing(SETUP_CHECKS).duration_ms >= 50);
+        assert!(timing(MEMORY).duration_ms >= 150);
+        assert_eq!(timing(PROXIES).status, ComponentStatus::Degraded);
+        assert!(timing(PROXIES).duration_ms >= 300);
+        // The three ran concurrently, not one after another.
+        assert!(report.total_ms.unwrap() < 50 + 150 + 300);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap()["components"][0]["id"],
+            SETUP_CHECKS
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_and_skipped_components_are_reported() {
+        let tracker = StartupTracker::new();
+        let none: Option<()> = tracker
+            .run(MEMORY, None, async { Init::Failed("lance: lock".into()) })
+            .await;
+        assert!(none.is_none());
+        tracker
+            .run::<()>(PROXIES, None, async { Init::Skipped("no ip.txt".into()) })
+            .await;
+        tracker.finish();
+        let report = tracker.report();
+        assert_eq!(report.components[0].status, ComponentStatus::Failed);
+        assert_eq!(report.components[1].status, ComponentStatus::Skipped);
+        assert!(report.summary().contains("proxies skipped in"));
+    }
+}
@@ -0,0 +1,232 @@
+//! Per-deployment tool policy: tools removed from this server and public names changed.
+//!
+//! Read at startup from `CORTEX_SCOUT_TOOLS_DISABLED` (comma-separated tool names),
+//! `CORTEX_SCOUT_TOOL_ALIASES` (`tool=alias,...`) and the TOML file named by
+//! `CORTEX_SCOUT_TOOLS_CONFIG` (each also as `SHADOWCRAWL_*`):
+//!
+//! ```toml
+//! disabled = ["web_crawl", "hitl_web_fetch"]
+//!
+//! [aliases]
+//! web_search = "search"
+//! ```
+//!
+//! Names may be internal, public or legacy tool names. The file and the variables are
+//! merged; [`ToolRegistry::with_policy`](super::tools_registry::ToolRegistry::with_policy)
+//! validates the result against the tool catalog. Only the subset of TOML above (string
+//! and string-array values, an `[aliases]` table, comments) is understood.
+
+use crate::core::config::env_var;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ToolPolicy {
+    /// Tool names that must not be listed or callable.
+    pub disabled: Vec<String>,
+    /// Tool name -> the public name it is listed under instead.
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl ToolPolicy {
+    /// The policy configured for this process; empty when nothing is set.
+    pub fn from_env() -> Result<Self> {
+        let mut policy = match env_var(&["CORTEX_SCOUT_TOOLS_CONFIG", "SHADOWCRAWL_TOOLS_CONFIG"]) {
+            Some(path) => {
+                let text = std::fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read tools config {}", path))?;
+                Self::from_toml(&text).with_context(|| format!("invalid tools config {}", path))?
+            }
+            None => Self::default(),
+        };
+        if let Some(list) = env_var(&["CORTEX_SCOUT_TOOLS_DISABLED", "SHADOWCRAWL_TOOLS_DISABLED"])
+        {
+            policy.disabled.extend(
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        if let Some(list) = env_var(&["CORTEX_SCOUT_TOOL_ALIASES", "SHADOWCRAWL_TOOL_ALIASES"]) {
+            for pair in list.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let (tool, alias) = pair.split_once('=').ok_or_else(|| {
+                    anyhow!(
+                        "CORTEX_SCOUT_TOOL_ALIASES entry '{}' is not tool=alias",
+                        pair
+                    )
+                })?;
+                policy.add_alias(tool.trim(), alias.trim())?;
+            }
+        }
+        Ok(policy)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.disabled.is_empty() && self.aliases.is_empty()
+    }
+
+    fn add_alias(&mut self, tool: &str, alias: &str) -> Result<()> {
+        if tool.is_empty() || alias.is_empty() {
+            bail!("tool alias '{}={}' needs both names", tool, alias);
+        }
+        match self.aliases.get(tool) {
+            Some(existing) if existing != alias => bail!(
+                "tool '{}' is aliased twice ('{}' and '{}')",
+                tool,
+                existing,
+                alias
+            ),
+            _ => {
+                self.aliases.insert(tool.to_string(), alias.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let mut policy = Self::default();
+        let mut table: Option<String> = None;
+        for (line_no, statement) in statements(text)? {
+            let fail = |message: String| anyhow!("line {}: {}", line_no, message);
+            if let Some(name) = statement
+                .strip_prefix('[')
+                .and_then(|s| s.strip_suffix(']'))
+            {
+                let name = name.trim();
+                if name != "aliases" {
+                    return Err(fail(format!("unknown table [{}]", name)));
+                }
+                table = Some(name.to_string());
+                continue;
+            }
+            let (key, value) = statement
+                .split_once('=')
+                .ok_or_else(|| fail(format!("expected key = value, found '{}'", statement)))?;
+            let key = unquote(key.trim()).unwrap_or_else(|| key.trim().to_string());
+            let value = value.trim();
+            match (table.as_deref(), key.as_str()) {
+                (None, "disabled") => policy.disabled.extend(
+                    string_array(value)
+                        .ok_or_else(|| fail("disabled must be an array of strings".to_string()))?,
+                ),
+                (None, other) => return Err(fail(format!("unknown key '{}'", other))),
+                (Some(_), tool) => {
+                    let alias = unquote(value)
+                        .ok_or_else(|| fail(format!("alias for '{}' must be a string", tool)))?;
+                    policy
+                        .add_alias(tool, &alias)
+                        .map_err(|e| fail(e.to_string()))?;
+                }
+            }
+        }
+        Ok(policy)
+    }
+}
+
+/// Non-empty statements with comments removed, arrays joined across lines, and the line
+/// each starts on.
+fn statements(text: &str) -> Result<Vec<(usize, String)>> {
+    let mut statements = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+    for (i, raw) in text.lines().enumerate() {
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (start, mut statement) = pending.take().unwrap_or((i + 1, String::new()));
+        statement.push_str(line);
+        statement.push(' ');
+        let opened = statement.matches('[').count();
+        let closed = statement.matches(']').count();
+        if statement.contains('=') && opened > closed {
+            pending = Some((start, statement));
+        } else {
+            statements.push((start, statement.trim().to_string()));
+        }
+    }
+    if let Some((line_no, _)) = pending {
+        bail!("line {}: unterminated array", line_no);
+    }
+    Ok(statements)
+}
+
+/// `line` up to a `#` that is not inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// The contents of a `"basic"` or `'literal'` string (no escapes).
+fn unquote(value: &str) -> Option<String> {
+    let value = value.trim();
+    ['"', '\'']
+        .into_iter()
+        .find_map(|q| value.strip_prefix(q)?.strip_suffix(q))
+        .filter(|inner| !inner.contains(['"', '\'', '\\']))
+        .map(str::to_string)
+}
+
+fn string_array(value: &str) -> Option<Vec<String>> {
+    let inner = value.trim().strip_prefix('[')?.strip_suffix(']')?;
+    inner
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(unquote)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_config_is_parsed() {
+        let policy = ToolPolicy::from_toml(
+            r#"
+            # Security review 2026-10: no crawling or human-in-the-loop browsing.
+            disabled = [
+                "web_crawl",   # and its legacy names
+                'hitl_web_fetch',
+            ]
+
+            [aliases]
+            web_search = "search"
+            "web_fetch" = 'fetch_page' # quoted keys work too
+            "#,
+        )
+        .unwrap();
+        assert_eq!(policy.disabled, vec!["web_crawl", "hitl_web_fetch"]);
+        assert_eq!(
+            policy.aliases,
+            BTreeMap::from([
+                ("web_search".to_string(), "search".to_string()),
+                ("web_fetch".to_string(), "fetch_page".to_string()),
+            ])
+        );
+        assert!(ToolPolicy::from_toml("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_toml_is_rejected() {
+        for bad in [
+            "disabled = \"web_crawl\"",
+            "disabled = [\"web_crawl\"",
+            "enabled = []",
+            "[tools]\nweb_search = \"search\"",
+            "[aliases]\nweb_search = search",
+            "[aliases]\nweb_search = \"a\"\nweb_search = \"b\"",
+        ] {
+            assert!(ToolPolicy::from_toml(bad).is_err(), "{}", bad);
+        }
+    }
+}
@@ -1,6 +1,9 @@
+use crate::core::tool_policy::ToolPolicy;
 use crate::mcp::tooling::tool_catalog;
+use anyhow::{bail, Result};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tracing::warn;
 
 #[derive(Clone, Debug)]
 pub struct PublicToolSpec {
@@ -19,10 +22,20 @@ pub struct ToolRegistry {
     arg_aliases: HashMap<String, HashMap<String, String>>,
     // Per-internal-tool enum value aliases: tool -> field -> (public_value -> internal_value)
     enum_aliases: HashMap<String, HashMap<String, HashMap<String, String>>>,
+    // Internal names of tools this deployment removed (see `with_policy`).
+    disabled: BTreeSet<String>,
+    // Policy entries that named no known tool, for the setup checklist.
+    policy_warnings: Vec<String>,
 }
 
 impl ToolRegistry {
     pub fn load() -> Self {
+        Self::load_with_memory(crate::core::config::lancedb_uri().is_some())
+    }
+
+    /// Memory-backed tools stay listed when semantic memory is disabled, but their
+    /// description says so up front; calls fail with an `unavailable` error.
+    fn load_with_memory(memory_enabled: bool) -> Self {
         let internal_catalog = tool_catalog();
         let mut registry = ToolRegistry::default();
 
@@ -143,7 +156,18 @@ impl ToolRegistry {
                 _ => internal_name.clone(),
             };
             let public_title = internal.title.to_string();
-            let public_description = internal.description.to_string();
+            let mut public_description = internal.description.to_string();
+            if !memory_enabled
+                && matches!(
+                    internal_name.as_str(),
+                    "research_history" | "history_search"
+                )
+            {
+                public_description = format!(
+                    "[Unavailable: semantic memory is disabled on this server] {}",
+                    public_description
+                );
+            }
 
             let public_input_schema =
                 registry.sanitize_schema_for_public(&internal_name, internal.input_schema);
@@ -175,6 +199,86 @@ impl ToolRegistry {
         registry
     }
 
+    /// [`Self::load`] with the [`ToolPolicy`] configured for this process applied.
+    pub fn load_for_deployment() -> Result<Self> {
+        Self::load().with_policy(&ToolPolicy::from_env()?)
+    }
+
+    /// Remove the tools `policy` disables from the listing and list the ones it aliases
+    /// under their alias. Disabled tools still resolve, so a call by name can be answered
+    /// with [`Self::is_disabled`] instead of "unknown tool"; aliased tools stay callable by
+    /// their previous names. Names that match no tool are logged and ignored; an alias
+    /// that is already another tool's name is an error.
+    pub fn with_policy(mut self, policy: &ToolPolicy) -> Result<Self> {
+        for name in &policy.disabled {
+            match self.public_to_internal.get(name) {
+                Some(internal) => {
+                    self.internal_to_public.remove(internal);
+                    self.disabled.insert(internal.clone());
+                }
+                None => self
+                    .policy_warnings
+                    .push(format!("cannot disable unknown tool '{}'", name)),
+            }
+        }
+
+        let mut assigned: BTreeMap<&str, &str> = BTreeMap::new();
+        for (name, alias) in &policy.aliases {
+            let Some(internal) = self.public_to_internal.get(name).cloned() else {
+                self.policy_warnings
+                    .push(format!("cannot alias unknown tool '{}'", name));
+                continue;
+            };
+            if let Some(previous) = assigned.insert(alias, name) {
+                bail!(
+                    "tool alias '{}' is given to both '{}' and '{}'",
+                    alias,
+                    previous,
+                    name
+                );
+            }
+            if let Some(owner) = self.public_to_internal.get(alias) {
+                if *owner != internal {
+                    bail!(
+                        "tool alias '{}' for '{}' is already the name of '{}'",
+                        alias,
+                        name,
+                        owner
+                    );
+                }
+            }
+            let Some(spec) = self.internal_to_public.get_mut(&internal) else {
+                self.policy_warnings.push(format!(
+                    "alias '{}' ignored: '{}' is not listed in this deployment",
+                    alias, name
+                ));
+                continue;
+            };
+            spec.public_name = alias.clone();
+            self.public_to_internal.insert(alias.clone(), internal);
+        }
+
+        for warning in &self.policy_warnings {
+            warn!("tool policy: {}", warning);
+        }
+        Ok(self)
+    }
+
+    /// Whether this deployment removed `internal_tool_name`.
+    pub fn is_disabled(&self, internal_tool_name: &str) -> bool {
+        self.disabled.contains(internal_tool_name)
+    }
+
+    /// Internal names of the tools removed by the deployment's policy.
+    pub fn disabled_tools(&self) -> impl Iterator<Item = &str> {
+        self.disabled.iter().map(String::as_str)
+    }
+
+    /// Policy entries that were ignored because they named no (listed) tool.
+    pub fn policy_warnings(&self) -> &[String] {
+        &self.policy_warnings
+    }
+
     pub fn public_specs(&self) -> Vec<PublicToolSpec> {
         let mut tools: Vec<_> = self.internal_to_public.values().cloned().collect();
         tools.sort_by(|a, b| a.public_name.cmp(&b.public_name));
@@ -304,6 +408,26 @@ fn rewrite_schema_enum_values(
 #[cfg(test)]
 mod tests {
     use super::ToolRegistry;
+    use crate::core::tool_policy::ToolPolicy;
+    use std::collections::BTreeMap;
+
+    fn policy(disabled: &[&str], aliases: &[(&str, &str)]) -> ToolPolicy {
+        ToolPolicy {
+            disabled: disabled.iter().map(|s| s.to_string()).collect(),
+            aliases: aliases
+                .iter()
+                .map(|(tool, alias)| (tool.to_string(), alias.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    fn public_names(registry: &ToolRegistry) -> Vec<String> {
+        registry
+            .public_specs()
+            .into_iter()
+            .map(|s| s.public_name)
+            .collect()
+    }
 
     #[test]
     fn unified_search_aliases_resolve_to_search_web() {
@@ -334,4 +458,87 @@ mod tests {
         assert!(!names.iter().any(|n| n == "human_auth_session"));
         assert!(!names.iter().any(|n| n == "fetch_then_extract"));
     }
+
+    #[test]
+    fn proxy_status_is_listed_under_its_own_name() {
+        let registry = ToolRegistry::load();
+        assert_eq!(
+            registry
+                .resolve_incoming_tool_name("proxy_status")
+                .as_deref(),
+            Some("proxy_status")
+        );
+        assert_eq!(
+            registry.public_tool_name_for_internal("proxy_status"),
+            Some("proxy_status")
+        );
+    }
+
+    #[test]
+    fn memory_tools_flagged_when_memory_disabled() {
+        let disabled = ToolRegistry::load_with_memory(false);
+        assert!(disabled
+            .public_description_for_internal("history_search")
+            .unwrap()
+            .starts_with("[Unavailable"));
+        assert!(disabled
+            .public_description_for_internal("research_history")
+            .unwrap()
+            .starts_with("[Unavailable"));
+
+        let enabled = ToolRegistry::load_with_memory(true);
+        assert!(!enabled
+            .public_description_for_internal("history_search")
+            .unwrap()
+            .starts_with("[Unavailable"));
+    }
+
+    #[test]
+    fn disabled_tools_are_unlisted_but_recognised() {
+        let registry = ToolRegistry::load()
+            .with_policy(&policy(
+                &["hitl_web_fetch", "proxy_status", "no_such_tool"],
+                &[],
+            ))
+            .unwrap();
+        let names = public_names(&registry);
+        assert!(!names.iter().any(|n| n == "hitl_web_fetch"));
+        assert!(!names.iter().any(|n| n == "proxy_status"));
+        assert!(names.iter().any(|n| n == "web_fetch"));
+        assert_eq!(
+            registry
+                .resolve_incoming_tool_name("hitl_web_fetch")
+                .as_deref(),
+            Some("non_robot_search")
+        );
+        assert!(registry.is_disabled("non_robot_search"));
+        assert!(!registry.is_disabled("scrape_url"));
+        assert_eq!(registry.policy_warnings().len(), 1);
+    }
+
+    #[test]
+    fn aliased_tools_are_listed_under_the_alias() {
+        let registry = ToolRegistry::load()
+            .with_policy(&policy(&[], &[("web_search", "search")]))
+            .unwrap();
+        let names = public_names(&registry);
+        assert!(names.iter().any(|n| n == "search"));
+        assert!(!names.iter().any(|n| n == "web_search"));
+        for name in ["search", "web_search", "search_web"] {
+            assert_eq!(
+                registry.resolve_incoming_tool_name(name).as_deref(),
+                Some("search_web"),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn conflicting_aliases_are_rejected() {
+        let shared = policy(&[], &[("web_search", "lookup"), ("web_fetch", "lookup")]);
+        assert!(ToolRegistry::load().with_policy(&shared).is_err());
+        let taken = policy(&[], &[("web_search", "web_fetch")]);
+        assert!(ToolRegistry::load().with_policy(&taken).is_err());
+    }
 }
@@ -1,29 +1,71 @@
+use crate::core::error::ErrorKind;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchRequest {
     pub query: String,
+    /// Cap on the merged result list, applied after reranking. `0` yields an empty list.
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    /// `json` (default), `opensearch` or `csv`; see `search::SearchOutputFormat`.
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Add `title_highlights` / `content_highlights` query-term offsets to each result.
+    #[serde(default)]
+    pub highlights: bool,
+    /// Trim each result's `content` to this many characters (after reranking).
+    #[serde(default)]
+    pub snippet_max_chars: Option<usize>,
+    /// Comma-separated result fields to keep in `json` output, e.g. `title,url,score`.
+    #[serde(default)]
+    pub include_fields: Option<String>,
+    /// Drop trailing results until the body fits; the count is reported as `truncated_results`.
+    #[serde(default)]
+    pub max_response_bytes: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SearchStructuredRequest {
     pub query: String,
     #[serde(default)]
     pub top_n: Option<usize>,
+    /// Per-page cap on `clean_content`, applied after the (cached) scrape.
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+    /// `balanced` (default), `aggressive`, or `high`.
+    #[serde(default)]
+    pub quality_mode: Option<String>,
+    #[serde(default)]
+    pub use_proxy: bool,
+    /// Scrapes in flight at once (default 3, max 8).
+    #[serde(default)]
+    pub concurrency: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SearchResponse {
     pub results: Vec<SearchResult>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SearchStructuredResponse {
     pub results: Vec<SearchResult>,
     pub scraped_content: Vec<ScrapeResponse>,
+    /// Top results whose scrape failed, with the reason.
+    #[serde(default)]
+    pub failed_urls: Vec<FailedUrl>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct FailedUrl {
+    pub url: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
 pub struct SearchResult {
     pub url: String,
     pub title: String,
@@ -52,20 +94,178 @@ pub struct SearchResult {
     pub domain: Option<String>,
     #[serde(default)]
     pub source_type: Option<String>, // docs, repo, blog, news, other
+    /// Language detected from title + snippet; only set when a search `language` is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+    /// Byte ranges of query-term matches in `title`; only set when `highlights` is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_highlights: Option<Vec<HighlightSpan>>,
+    /// Byte ranges of query-term matches in `content`; only set when `highlights` is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_highlights: Option<Vec<HighlightSpan>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A half-open `[start, end)` byte range, always on UTF-8 char boundaries.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub struct HighlightSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScrapeRequest {
     pub url: String,
     #[serde(default)]
     pub content_links_only: Option<bool>,
+    /// Links kept in `links`, in page order (all by default).
     #[serde(default)]
     pub max_links: Option<usize>,
     #[serde(default)]
     pub max_images: Option<usize>,
+    #[serde(default)]
+    pub respect_robots: bool,
+    /// Fetch and parse the page's advertised RSS/Atom feed into `feed_items`.
+    #[serde(default)]
+    pub parse_feeds: bool,
+    /// Total HTTP attempts for transient failures (overrides `CORTEX_SCOUT_RETRY_MAX_ATTEMPTS`).
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+    /// Re-render low-quality static HTML in the browser (overrides `CORTEX_SCOUT_AUTO_ESCALATE`).
+    #[serde(default)]
+    pub auto_escalate: Option<bool>,
+    /// Send and store cookies through the site's shared jar across scrapes.
+    #[serde(default)]
+    pub persist_session: bool,
+    /// Forget the site's cookies before fetching.
+    #[serde(default)]
+    pub clear_session: bool,
+    /// HTTP Basic/Bearer credentials, sent as an `Authorization` header.
+    #[serde(default)]
+    pub auth: Option<crate::scraping::request_auth::RequestAuth>,
+    /// Extra request headers, applied after the stealth defaults. Hop-by-hop headers are
+    /// rejected; secret-looking values are never logged.
+    #[serde(default)]
+    pub headers: Option<BTreeMap<String, String>>,
+    /// User-Agent sent instead of the rotating browser one.
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Return only title, canonical URL, headings, links and images (no content extraction).
+    #[serde(default)]
+    pub links_only: bool,
+    /// Give each link a `detail`: its enclosing block, page-chrome flag, surrounding
+    /// sentence and `rel` qualifiers.
+    #[serde(default)]
+    pub links_detail: bool,
+    /// Render in the browser and save a PNG of the page (see `ScrapeResponse.screenshot`).
+    #[serde(default)]
+    pub screenshot: bool,
+    /// Capture the whole scrollable page instead of the viewport.
+    #[serde(default)]
+    pub screenshot_full_page: bool,
+    /// Also return the PNG as base64 when it is under the inline size cap.
+    #[serde(default)]
+    pub screenshot_inline: bool,
+    /// Keep the fetched HTML in `content` (omitted by default).
+    #[serde(default)]
+    pub include_raw_html: bool,
+    /// Follow the page's `hreflang` alternate in this language when the page is in another.
+    #[serde(default)]
+    pub prefer_language: Option<String>,
+    /// Return `sections`: `clean_content` split along its headings.
+    #[serde(default)]
+    pub include_sections: bool,
+    /// Words per paragraph window for pages without headings (default 300).
+    #[serde(default)]
+    pub section_target_words: Option<usize>,
+    /// Words of trailing paragraphs repeated in the next window (default 50).
+    #[serde(default)]
+    pub section_overlap_words: Option<usize>,
+    /// Follow "next page" links and merge the series into one result.
+    #[serde(default)]
+    pub follow_pagination: bool,
+    /// Pages merged with `follow_pagination`, the first included (default 5, at most 20).
+    #[serde(default)]
+    pub max_pages: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Query string for `GET /scrape`: the [`ScrapeRequest`] options without credentials,
+/// custom headers, screenshots or cookie-jar side effects.
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScrapeQuery {
+    pub url: String,
+    #[serde(default)]
+    pub respect_robots: bool,
+    #[serde(default)]
+    pub parse_feeds: bool,
+    #[serde(default)]
+    pub max_attempts: Option<u32>,
+    #[serde(default)]
+    pub auto_escalate: Option<bool>,
+    #[serde(default)]
+    pub links_only: bool,
+    #[serde(default)]
+    pub links_detail: bool,
+    #[serde(default)]
+    pub max_links: Option<usize>,
+    #[serde(default)]
+    pub include_raw_html: bool,
+    #[serde(default)]
+    pub prefer_language: Option<String>,
+    #[serde(default)]
+    pub include_sections: bool,
+    #[serde(default)]
+    pub section_target_words: Option<usize>,
+    #[serde(default)]
+    pub section_overlap_words: Option<usize>,
+    #[serde(default)]
+    pub follow_pagination: bool,
+    #[serde(default)]
+    pub max_pages: Option<usize>,
+}
+
+impl From<ScrapeQuery> for ScrapeRequest {
+    fn from(query: ScrapeQuery) -> Self {
+        Self {
+            url: query.url,
+            content_links_only: None,
+            max_links: query.max_links,
+            max_images: None,
+            respect_robots: query.respect_robots,
+            parse_feeds: query.parse_feeds,
+            max_attempts: query.max_attempts,
+            auto_escalate: query.auto_escalate,
+            persist_session: false,
+            clear_session: false,
+            auth: None,
+            headers: None,
+            user_agent: None,
+            links_only: query.links_only,
+            links_detail: query.links_detail,
+            screenshot: false,
+            screenshot_full_page: false,
+            screenshot_inline: false,
+            include_raw_html: query.include_raw_html,
+            prefer_language: query.prefer_language,
+            include_sections: query.include_sections,
+            section_target_words: query.section_target_words,
+            section_overlap_words: query.section_overlap_words,
+            follow_pagination: query.follow_pagination,
+            max_pages: query.max_pages,
+        }
+    }
+}
+
+/// Query string for `GET /scrape/stream`.
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScrapeStreamQuery {
+    pub url: String,
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct ScrapeResponse {
     pub url: String,
     pub title: String,
@@ -136,12 +336,296 @@ pub struct ScrapeResponse {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub final_url: Option<String>,
 
+    /// Redirect responses followed on the way to `final_url`, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redirect_chain: Vec<RedirectHop>,
+
     /// Execution timing metrics for the scrape pipeline.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub metrics: Option<ToolExecutionMetrics>,
+
+    /// Source format when the body was not HTML (e.g. `"pdf"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_format: Option<String>,
+
+    /// Page count for paginated documents such as PDFs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_count: Option<usize>,
+
+    /// RSS/Atom feeds advertised by the page via `<link rel="alternate">`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feed_urls: Vec<String>,
+
+    /// Parsed feed entries (newest first, at most 50): the page's first advertised feed
+    /// when `parse_feeds` is set, or the document itself when the URL is a feed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feed_items: Vec<FeedItem>,
+
+    /// HTTP attempts the native fetch needed (more than 1 means transient failures were retried).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetch_attempts: Option<u32>,
+
+    /// Set when the fetched page is an anti-bot/captcha/denial page rather than content
+    /// (e.g. `Captcha`, `Cloudflare`); `/scrape` answers such results with a `blocked` error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_reason: Option<String>,
+
+    /// Manifest written by `download_assets` (original URL → local path, sha256, content type).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_manifest: Option<String>,
+
+    /// Encoding the native fetch decoded the HTML body from (e.g. `UTF-8`, `GBK`, `Shift_JIS`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charset: Option<String>,
+
+    /// `ETag` of the native fetch's response, kept so a stale cache entry can be revalidated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+
+    /// `Last-Modified` of the native fetch's response, used like `etag`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+
+    /// Served from the cache after the site answered a conditional request with
+    /// `304 Not Modified`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub revalidated: bool,
+
+    /// JSON-LD objects of allowlisted Schema.org types (Article, Product, Recipe, JobPosting,
+    /// Event, Organization, FAQPage), as published by the page. `clean_content` still carries
+    /// the markdown rendering.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub structured_data: Vec<serde_json::Value>,
+
+    /// PNG of the rendered page, captured when `screenshot` was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot: Option<PageScreenshot>,
+
+    /// Lowercased directives from `<meta name="robots">` and the `X-Robots-Tag` header
+    /// (e.g. `noindex`, `nofollow`); `none` is recorded as both.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub robots_directives: Vec<String>,
+
+    /// Matches for the caller's named CSS `selectors`, keyed by name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector_results: Option<BTreeMap<String, SelectorResult>>,
+
+    /// Language variants advertised via `<link rel="alternate" hreflang>`, including
+    /// `x-default`, with URLs resolved against the canonical URL.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternate_languages: Vec<AlternateLanguage>,
+
+    /// Every fetch attempted for this page, in order, with the one that produced the
+    /// content marked `selected`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub provenance: Vec<ScrapeAttempt>,
+
+    /// `clean_content` split along its headings (or into paragraph windows when it has
+    /// none), when `include_sections` was requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<ContentSection>,
+
+    /// Where `title` came from when the page has no usable `<title>`: `og:title`,
+    /// `twitter:title`, `heading`, `json_ld:headline` or `url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_source: Option<String>,
+
+    /// Where `meta_description` came from when the page has no usable
+    /// `<meta name="description">`: `og:description`, `twitter:description`,
+    /// `json_ld:description` or `paragraph`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta_description_source: Option<String>,
+
+    /// Reading level and content-quality signals of `clean_content`, for HTML pages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<QualitySignals>,
+}
+
+#[cfg(test)]
+impl ScrapeResponse {
+    /// A 200 `text/html` page with `url`, `title` and `clean_content` (its words counted)
+    /// and nothing else, for tests to fill in what they need.
+    pub(crate) fn for_test(url: &str, title: &str, clean_content: &str) -> Self {
+        ScrapeResponse {
+            url: url.to_string(),
+            title: title.to_string(),
+            content: String::new(),
+            clean_content: clean_content.to_string(),
+            embedded_state_json: None,
+            embedded_data_sources: vec![],
+            hydration_status: HydrationStatus {
+                json_found: false,
+                settle_time_ms: None,
+                noise_reduction_ratio: 0.0,
+                consent_nodes_removed: 0,
+            },
+            meta_description: String::new(),
+            meta_keywords: String::new(),
+            headings: vec![],
+            links: vec![],
+            images: vec![],
+            timestamp: "2026-03-01T00:00:00Z".to_string(),
+            status_code: 200,
+            content_type: "text/html".to_string(),
+            word_count: clean_content.split_whitespace().count(),
+            language: "en".to_string(),
+            canonical_url: None,
+            site_name: None,
+            author: None,
+            published_at: None,
+            og_title: None,
+            og_description: None,
+            og_image: None,
+            reading_time_minutes: None,
+            code_blocks: vec![],
+            truncated: false,
+            actual_chars: clean_content.len(),
+            max_chars_limit: None,
+            extraction_score: None,
+            warnings: vec![],
+            domain: None,
+            auth_wall_reason: None,
+            auth_risk_score: None,
+            detection_factors: vec![],
+            final_url: None,
+            redirect_chain: Vec::new(),
+            metrics: None,
+            content_format: None,
+            page_count: None,
+            feed_urls: Vec::new(),
+            feed_items: Vec::new(),
+            fetch_attempts: None,
+            block_reason: None,
+            asset_manifest: None,
+            charset: None,
+            etag: None,
+            last_modified: None,
+            revalidated: false,
+            structured_data: Vec::new(),
+            screenshot: None,
+            robots_directives: Vec::new(),
+            selector_results: None,
+            alternate_languages: Vec::new(),
+            provenance: Vec::new(),
+            sections: Vec::new(),
+            title_source: None,
+            meta_description_source: None,
+            quality: None,
+        }
+    }
+}
+
+/// Content-quality signals of a scraped page, used by `min_quality` to drop thin pages.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct QualitySignals {
+    /// Flesch–Kincaid grade level of `clean_content`; `None` for short or non-Latin text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_level: Option<f64>,
+    /// Characters of `clean_content` per byte of fetched HTML (0-1).
+    pub text_to_markup_ratio: f64,
+    /// Links per 100 words of `clean_content`.
+    pub link_density: f64,
+    /// Share of the document's text dropped as navigation, ads and other boilerplate (0-1).
+    pub boilerplate_ratio: f64,
+    /// The page names both an author and a publication date.
+    pub has_provenance: bool,
+    /// Weighted blend of the signals above and the word count (0 = thin, 1 = substantial).
+    pub composite: f64,
+}
+
+/// A page left out of a batch or crawl because its `quality.composite` was below
+/// `min_quality`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct LowQualityPage {
+    pub url: String,
+    pub quality: f64,
+}
+
+/// A page `semantic_dedup` collapsed into a near-identical one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct SemanticDuplicate {
+    pub url: String,
+    /// The page kept in its place: earlier in the same job, or a past scrape.
+    pub duplicate_of: String,
+    /// Cosine similarity of the two pages' title and opening text.
+    pub similarity: f32,
+    /// `duplicate_of` is a past scrape from history rather than a page of this job.
+    #[serde(default)]
+    pub from_history: bool,
+}
+
+/// One section of a page's `clean_content`, sized for embedding.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct ContentSection {
+    /// Headings from the outermost down to this section's own. Empty for text before the
+    /// first heading and for paragraph windows of pages without headings.
+    pub heading_path: Vec<String>,
+    /// 1-6 for a heading section, 0 otherwise.
+    pub level: u8,
+    /// Markdown from below the heading to the next heading of the same or a higher level,
+    /// subsections included.
+    pub content: String,
+    pub word_count: usize,
+    /// Indices into the page's `code_blocks` that fall within `content`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub code_blocks: Vec<usize>,
+}
+
+/// One `hreflang` alternate of a page.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct AlternateLanguage {
+    /// Language tag as published (e.g. `de`, `pt-BR`), or `x-default`.
+    pub lang: String,
+    pub url: String,
+}
+
+/// One element matched by a caller-supplied CSS selector.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct SelectorMatch {
+    /// Text content with whitespace collapsed.
+    pub text: String,
+    /// Absolute `href` of an `<a>` element.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub href: Option<String>,
+    /// Absolute `src` of an `<img>` element.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub src: Option<String>,
+}
+
+/// Outcome of one named selector: its matches in document order, or why it could not run.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(untagged)]
+pub enum SelectorResult {
+    Matches(Vec<SelectorMatch>),
+    Error { error: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct PageScreenshot {
+    /// Absolute path of the PNG under the screenshot directory.
+    pub path: String,
+    pub bytes: usize,
+    /// Whole scrollable page rather than the 1920×1080 viewport.
+    pub full_page: bool,
+    /// The PNG, base64-encoded, when `screenshot_inline` was set and it fit the size cap.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base64: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct FeedItem {
+    pub title: String,
+    #[serde(default)]
+    pub link: Option<String>,
+    /// RFC 3339 publish (or last update) time.
+    #[serde(default)]
+    pub published: Option<String>,
+    /// Plain-text summary with markup stripped.
+    #[serde(default)]
+    pub summary: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
 pub struct ToolExecutionMetrics {
     pub total_duration_ms: u64,
     pub total_duration_seconds: f64,
@@ -153,7 +637,7 @@ pub struct ToolExecutionMetrics {
     pub phases: Vec<ToolExecutionPhase>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
 pub struct ToolExecutionPhase {
     pub name: String,
     pub duration_ms: u64,
@@ -185,13 +669,17 @@ pub struct AuthWallBlocked {
     pub github_raw_url: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct EmbeddedDataSource {
     pub source_type: String,
     pub content: String,
+    /// `nextjs`, `nuxt`, `remix` or `apollo` when `content` is the data unwrapped from that
+    /// framework's hydration container rather than a raw script blob.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub framework: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ToSchema)]
 pub struct HydrationStatus {
     #[serde(default)]
     pub json_found: bool,
@@ -199,9 +687,12 @@ pub struct HydrationStatus {
     pub settle_time_ms: Option<u64>,
     #[serde(default)]
     pub noise_reduction_ratio: f64,
+    /// Consent-manager / cookie-wall containers removed before extraction.
+    #[serde(default)]
+    pub consent_nodes_removed: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct CodeBlock {
     pub language: Option<String>,
     pub code: String,
@@ -211,55 +702,182 @@ pub struct CodeBlock {
     pub end_char: Option<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
 pub struct Heading {
     pub level: String,
     pub text: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Link {
     pub url: String,
     pub text: String,
+    /// The anchor carries `rel="nofollow"`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub nofollow: bool,
+    /// Where the link sits and how it is referenced; only with `links_detail`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<LinkDetail>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Context for one outbound link, for weighing citations.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct LinkDetail {
+    /// The nearest enclosing block element: `p`, `li`, `td`, `figcaption`, ...
+    pub block: String,
+    /// The link sits in page chrome (`nav`, `header`, `footer`, `aside` or a container
+    /// named like one), which content extraction drops.
+    pub in_chrome: bool,
+    /// The sentence around the link, at most 140 characters.
+    pub context: String,
+    /// The anchor carries `rel="sponsored"`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sponsored: bool,
+    /// The anchor carries `rel="ugc"` (user-generated content).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ugc: bool,
+}
+
+/// One redirect answered while fetching a page: the URL requested and its status (3xx,
+/// or that of the page itself when it redirected in-page).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, ToSchema)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
+    /// Set when the page redirected itself rather than answering 3xx.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_redirect: Option<ClientRedirectKind>,
+}
+
+/// How a page redirected in its markup.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientRedirectKind {
+    /// `<meta http-equiv="refresh">` with a zero or near-zero delay.
+    MetaRefresh,
+    /// An inline script that only assigns a literal URL to `location`.
+    Script,
+}
+
+/// One fetch a scrape made on its way to the returned content, in the order tried.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct ScrapeAttempt {
+    pub method: ScrapeMethod,
+    pub user_agent: UserAgentClass,
+    /// Host (and port) of the proxy the attempt went through; credentials are never kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    pub duration_ms: u64,
+    /// HTTP status of the page, when one was received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    /// Anti-bot/captcha page the attempt ran into (see `ScrapeResponse.block_reason`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_reason: Option<String>,
+    /// Why the attempt produced no page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Whether the returned content came from this attempt.
+    #[serde(default)]
+    pub selected: bool,
+}
+
+/// How a [`ScrapeAttempt`] fetched the page.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrapeMethod {
+    /// Native HTTP GET.
+    Static,
+    /// Rendered in the headless browser over CDP (also behind the `browserless` entry points).
+    Cdp,
+    /// Rendered in a visible browser by `non_robot_search`, with a human on hand.
+    NonRobot,
+}
+
+/// Device class of the User-Agent a [`ScrapeAttempt`] presented.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserAgentClass {
+    Desktop,
+    Mobile,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Image {
     pub src: String,
     pub alt: String,
     pub title: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ChatRequest {
     pub query: String,
+    /// Sentences in the extractive answer (default 8).
+    #[serde(default)]
+    pub max_sentences: Option<usize>,
+    /// Cite each sentence with `[n]` and append the numbered source list (default true).
+    #[serde(default)]
+    pub include_sources: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ChatResponse {
     pub response: String,
     pub search_results: Vec<SearchResult>,
     pub scraped_content: Vec<ScrapeResponse>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: String,
+    /// Machine-readable category; see [`ErrorKind`] for the HTTP/JSON-RPC mapping.
+    #[serde(default)]
+    pub code: ErrorKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
 }
 
 // Batch scraping types
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScrapeBatchRequest {
     pub urls: Vec<String>,
-    #[serde(default)]
+    #[serde(default, alias = "concurrency")]
     pub max_concurrent: Option<usize>,
     #[serde(default)]
     pub max_chars: Option<usize>,
     #[serde(default)]
     pub output_format: Option<String>,
+    /// Total wall-clock budget for the whole batch; unfinished URLs are reported inline.
+    #[serde(default)]
+    pub budget_secs: Option<u64>,
+    /// Per-domain requests/second for this batch; defaults to the server-wide rate.
+    #[serde(default)]
+    pub domain_rate_limit: Option<f64>,
+    /// Share each site's cookie jar across the batch (and later scrapes).
+    #[serde(default)]
+    pub persist_session: bool,
+    /// Forget the cookies of every site in `urls` before the batch starts.
+    #[serde(default)]
+    pub clear_session: bool,
+    /// HTTP Basic/Bearer credentials sent to every URL in the batch.
+    #[serde(default)]
+    pub auth: Option<crate::scraping::request_auth::RequestAuth>,
+    /// Drop pages whose `quality.composite` is below this (0-1); they are listed in
+    /// `dropped_low_quality` instead of `results`.
+    #[serde(default)]
+    pub min_quality: Option<f64>,
+    /// Collapse near-identical pages into the first of them (needs semantic memory).
+    #[serde(default)]
+    pub semantic_dedup: bool,
+    /// Similarity (0-1] at which two pages count as the same; default 0.9.
+    #[serde(default)]
+    pub dedup_threshold: Option<f64>,
+    /// `job` (default) compares the batch's pages; `history` also past scrapes.
+    #[serde(default)]
+    pub dedup_scope: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct ScrapeBatchResult {
     pub url: String,
     pub success: bool,
@@ -269,16 +887,50 @@ pub struct ScrapeBatchResult {
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub failure_reason: Option<String>,
+    /// Earlier URL in the batch that reached the same page (after redirects); this
+    /// entry carries no `data` of its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
     pub duration_ms: u64,
+    /// Near-identical pages `semantic_dedup` collapsed into this one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates_of: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScrapeBatchResponse {
     pub total: usize,
     pub successful: usize,
     pub failed: usize,
     pub total_duration_ms: u64,
     pub results: Vec<ScrapeBatchResult>,
+    /// Pages scraped but left out of `results` by `min_quality`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped_low_quality: Vec<LowQualityPage>,
+    /// Pages left out of `results` by `semantic_dedup`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<SemanticDuplicate>,
+}
+
+// Proxy control types
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxyToggleRequest {
+    /// Proxy URL exactly as listed in the registry (e.g. `http://10.0.0.1:8080`).
+    pub proxy_url: String,
+}
+
+// Search engine control types
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct EngineResetRequest {
+    /// Engine to release from the bench (e.g. `google`); every engine when omitted.
+    #[serde(default)]
+    pub engine: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxyRotateResponse {
+    /// Newly selected proxy, credentials masked.
+    pub current_proxy: String,
 }
 
 // Website crawling types
@@ -299,6 +951,12 @@ pub struct CrawlRequest {
     pub same_domain_only: Option<bool>,
     #[serde(default)]
     pub max_chars_per_page: Option<usize>,
+    #[serde(default)]
+    pub use_sitemap: Option<bool>,
+    #[serde(default)]
+    pub sitemap_url: Option<String>,
+    #[serde(default)]
+    pub max_sitemap_entries: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -317,19 +975,191 @@ pub struct CrawlPageResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     pub duration_ms: u64,
+    /// Link score the page was queued with (`strategy: "priority"` only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<f64>,
+    /// Page whose link queued this one; absent for the start URL and sitemap entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    /// In-scope links on the page, one per target. Saved with the crawl state for the
+    /// link graph; left out of `results` in the crawl response.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outlinks: Vec<CrawlOutlink>,
+    /// Score against the crawl's `content_filter` query, when one was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relevance: Option<f32>,
+    /// `quality.composite` of the scraped page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<f64>,
+    /// Near-identical pages `semantic_dedup` collapsed into this one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates_of: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlOutlink {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor_text: Option<String>,
+}
+
+/// The crawled pages and the links between them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrawlGraph {
+    pub nodes: Vec<CrawlGraphNode>,
+    pub edges: Vec<CrawlGraphEdge>,
+    /// Pages beyond the node cap (`max_graph_nodes`) were left out, with their edges.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlGraphNode {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// HTTP status; absent when the fetch failed outright.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_count: Option<usize>,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrawlGraphEdge {
+    pub from: String,
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor_text: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CrawlResponse {
+    /// Pass as `resume` to continue this crawl later; absent when its state could not be
+    /// saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crawl_id: Option<String>,
     pub start_url: String,
     pub pages_crawled: usize,
     pub pages_failed: usize,
     pub max_depth_reached: usize,
     pub total_duration_ms: u64,
     pub unique_domains: Vec<String>,
+    /// Entries read from the sitemap (0 when `use_sitemap` was off or it was unreachable).
+    #[serde(default)]
+    pub sitemap_urls_found: usize,
+    /// Scraped pages that were seeded from the sitemap vs. found by following links.
+    #[serde(default)]
+    pub pages_from_sitemap: usize,
+    #[serde(default)]
+    pub pages_from_links: usize,
+    /// Pages fetched but left out of `results` because they are `noindex`
+    /// (`respect_robots_meta` only).
+    #[serde(default)]
+    pub pages_excluded_noindex: usize,
     pub results: Vec<CrawlPageResult>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sitemap: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph: Option<CrawlGraph>,
+    /// What `content_filter` left out of `results`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_filter: Option<CrawlFilterSummary>,
+    /// Scope estimate of a `dry_run` crawl, which fetched only the seed page (and sitemap).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<CrawlPlan>,
+    /// Pages crawled but left out of `results` by `min_quality`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped_low_quality: Vec<LowQualityPage>,
+    /// Pages left out of `results` by `semantic_dedup`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<SemanticDuplicate>,
+    /// Pages fetched but left out of `results` because their canonical URL had already
+    /// been crawled (`respect_canonical`). They do not count towards `max_pages`.
+    #[serde(default)]
+    pub canonical_duplicates_skipped: usize,
+    /// Up to 20 of those pages with the canonical URL each declared, to check that the
+    /// site's canonical tags are trustworthy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub canonical_collapses: Vec<CanonicalCollapse>,
+}
+
+/// A crawled page collapsed into the canonical URL it declares.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanonicalCollapse {
+    pub url: String,
+    pub canonical_url: String,
+}
+
+/// The would-be frontier of a crawl: links on the seed page and sitemap entries, run
+/// through the same scope, include/exclude and depth rules as a real crawl.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlPlan {
+    /// Pages the crawl would fetch from what is known so far: the seed plus every
+    /// matched candidate, capped at `max_pages`.
+    pub estimated_pages: usize,
+    /// Only the first level was explored, so a crawl with `max_depth` above 1 that is
+    /// not already capped by `max_pages` may find more.
+    pub estimate_is_lower_bound: bool,
+    /// Distinct candidate URLs that pass the filters.
+    pub candidates_matched: usize,
+    pub candidates_excluded: usize,
+    /// Excluded candidates by the rule that dropped them: `exclude:<pattern>`,
+    /// `no_include_match`, `other_domain`, `domain_policy`, `nofollow`, `max_depth` or
+    /// `invalid_url`.
+    pub excluded_by_rule: BTreeMap<String, usize>,
+    /// Up to 50 matched URLs, in the order the crawl would fetch them.
+    pub sample_urls: Vec<String>,
+    pub structure: CrawlStructure,
+}
+
+/// How the matched candidates are laid out, to spot listings that would dominate a crawl.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrawlStructure {
+    /// Matched URLs grouped by first path segment (`/` for the site root), largest first.
+    pub sections: Vec<CrawlSection>,
+    /// Pagination schemes seen, e.g. `path:/page/{n}` or `query:page`.
+    pub pagination_patterns: Vec<String>,
+    /// Matched URLs that are pages of a paginated listing.
+    pub pagination_urls: usize,
+    /// Matched URLs under category, tag, topic or archive listings.
+    pub category_urls: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlSection {
+    pub prefix: String,
+    pub urls: usize,
+}
+
+/// Outcome of a crawl's `content_filter`: `results` holds only the matching pages, best
+/// first; the sitemap and graph still cover every crawled page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlFilterSummary {
+    pub query: String,
+    pub min_score: f32,
+    /// Successful pages scoring at least `min_score`.
+    pub pages_matched: usize,
+    pub pages_returned: usize,
+    /// Pages below `min_score` or past `max_pages_returned`, best first.
+    pub filtered_out: Vec<CrawlFilteredPage>,
+    /// Further filtered-out pages not listed, to keep the response small.
+    #[serde(default)]
+    pub filtered_out_unlisted: usize,
+    /// Failed pages left out of `results` (still counted in `pages_failed`).
+    #[serde(default)]
+    pub failed_pages_omitted: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlFilteredPage {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub score: f32,
 }
 
 // Structured extraction types
@@ -366,6 +1196,90 @@ pub struct ExtractResponse {
     pub duration_ms: u64,
     #[serde(default)]
     pub warnings: Vec<String>,
+    /// Matches for the caller's named CSS `selectors`, keyed by name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector_results: Option<BTreeMap<String, SelectorResult>>,
+    /// Where each schema field's value came from, keyed by field name. Empty without a
+    /// schema. Kept apart from `extracted_data`, so strict output keeps its shape.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub field_provenance: BTreeMap<String, FieldProvenance>,
+}
+
+/// How one schema field of an [`ExtractResponse`] was filled.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldProvenance {
+    pub source: ExtractSource,
+    /// Where on the page the value was found: a JSON-LD property, a metadata name, a
+    /// heading or a short excerpt around the match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// How far the value sat from the field's keyword in the text, in characters; only
+    /// for `keyword_proximity`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance: Option<usize>,
+    /// 0.0–1.0, from the source, the match distance and the grounding and type checks.
+    pub confidence: f64,
+}
+
+/// Which extraction path produced a field's value.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtractSource {
+    /// A typed property of the page's JSON-LD.
+    JsonLd,
+    /// Page metadata: title, meta description, author, dates, links, images, code blocks.
+    Meta,
+    /// The page's h2/h3 headings.
+    Heading,
+    /// Symbol links of a rustdoc (docs.rs) page.
+    RustdocFastPath,
+    /// Text following the field's name (or a variant of it) in the page content.
+    KeywordProximity,
+    /// A pattern match anywhere in the content, or the first long paragraph.
+    FallbackRegex,
+    /// Nothing was found.
+    Null,
+}
+
+/// One URL of an `extract_batch` call.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtractBatchItem {
+    pub url: String,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction: Option<ExtractResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Set when this URL reached the same page as an earlier one in the batch, which
+    /// carries the extraction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// How often one field came back null or empty across a batch's extractions.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExtractFieldNulls {
+    pub field: String,
+    pub null_count: usize,
+    /// `null_count` over the number of pages extracted.
+    pub null_ratio: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtractBatchResponse {
+    pub total: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub total_duration_ms: u64,
+    /// In input order.
+    pub results: Vec<ExtractBatchItem>,
+    /// Table view: one row per extracted page, its fields plus a `_url` column.
+    pub rows: Vec<serde_json::Value>,
+    /// Every extracted field, most often null first.
+    pub field_nulls: Vec<ExtractFieldNulls>,
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -391,6 +1305,9 @@ pub struct SniperOutput {
     pub key_code_blocks: Vec<SniperCodeBlock>,
     /// Minimal provenance metadata.
     pub metadata: SniperMetadata,
+    /// Matches for the caller's named CSS `selectors`, keyed by name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector_results: Option<BTreeMap<String, SelectorResult>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -417,6 +1334,13 @@ pub struct SniperMetadata {
     pub extraction_score: Option<f64>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub warnings: Vec<String>,
+    /// Asset manifest path when `download_assets` was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_manifest: Option<String>,
+    /// Saved PNG when `screenshot` was requested (path only; use `output_format: json` for
+    /// inline base64).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot_path: Option<String>,
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -506,3 +1430,216 @@ pub struct DeepResearchResult {
     /// Total wall-clock time for the full research pipeline.
     pub total_duration_ms: u64,
 }
+
+// ─────────────────────────────────────────────────────────────────────────────
+// History search types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Query string for `GET /history`.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    /// Free-text query; empty lists the most recent entries instead of ranking.
+    #[serde(default)]
+    pub q: Option<String>,
+    /// `search` or `scrape`.
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// `day` | `week` | `month` | `year`, counted back from now.
+    #[serde(default)]
+    pub time_range: Option<String>,
+    /// RFC 3339 lower bound; overrides `time_range`.
+    #[serde(default)]
+    pub since: Option<String>,
+    /// RFC 3339 upper bound.
+    #[serde(default)]
+    pub until: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub threshold: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct HistoryEntrySummary {
+    pub id: String,
+    /// `search` or `scrape`.
+    pub kind: String,
+    /// The search query, or the URL for scrapes.
+    pub query: String,
+    pub timestamp: String,
+    /// Results the search returned; absent for scrapes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_count: Option<usize>,
+    /// Cosine similarity with keyword boost; absent when listing without a query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    pub summary: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct HistorySearchResponse {
+    pub query: String,
+    pub total: usize,
+    pub entries: Vec<HistoryEntrySummary>,
+}
+
+/// Query string for `GET /setup`.
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SetupQuery {
+    /// Comma-separated check ids (e.g. `chrome_installed,network_ping`); all checks when absent.
+    #[serde(default)]
+    pub check: Option<String>,
+}
+
+/// Query string for `DELETE /history`.
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryPurgeQuery {
+    /// Delete entries recorded more than this many days ago; `0` deletes everything.
+    pub older_than_days: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub struct HistoryPurgeResponse {
+    pub older_than_days: u32,
+    /// Entries recorded before this instant were deleted.
+    pub cutoff: String,
+    pub deleted: usize,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Scrape diff types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A page field compared between two scrapes.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FieldChange {
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScrapeDiffResponse {
+    pub url: String,
+    /// `baseline_created`, `unchanged` or `changed`.
+    pub status: String,
+    /// Where the previous version came from: `snapshot`, `cache` or `memory`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_timestamp: Option<String>,
+    pub current_timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_count_before: Option<usize>,
+    pub word_count_after: usize,
+    pub word_count_delta: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headings_added: Vec<Heading>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headings_removed: Vec<Heading>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at_change: Option<FieldChange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_url_change: Option<FieldChange>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Unified diff of whitespace-normalized `clean_content`, capped at `max_diff_chars`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_diff: Option<String>,
+    #[serde(default)]
+    pub diff_truncated: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Scrape compare types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Extraction metrics for one quality mode over the shared HTML.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QualityModeSummary {
+    pub quality_mode: String,
+    pub word_count: usize,
+    #[serde(default)]
+    pub extraction_score: Option<f64>,
+    pub noise_reduction_ratio: f64,
+    pub headings: usize,
+    /// The first 500 characters of `clean_content`.
+    pub preview: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScrapeCompareResponse {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>,
+    /// One entry per mode, in `balanced`, `aggressive`, `high` order.
+    pub modes: Vec<QualityModeSummary>,
+    /// The mode with the highest `extraction_score`; earlier modes win ties.
+    pub recommended_mode: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Research types
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// One scraped source of a `research` call.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResearchSource {
+    /// Position after reranking and domain diversification, starting at 1.
+    pub rank: usize,
+    pub url: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    /// Reranker relevance of the search result, between 0.0 and 1.0.
+    pub score: f32,
+    /// The page's sentences most relevant to the query, or its meta description.
+    pub summary: String,
+    pub word_count: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction_score: Option<f64>,
+    /// Fields extracted with the call's `schema` (or `prompt`), when one was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extracted: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction_confidence: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// A selected source that produced no content.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResearchFailure {
+    pub rank: usize,
+    pub url: String,
+    /// Short machine-readable cause, e.g. `budget_exceeded`, `blocked`, `extraction_failed`.
+    pub reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Output of the `research` tool.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResearchResponse {
+    pub query: String,
+    /// Search results considered before selection.
+    pub results_considered: usize,
+    /// Results passed over because their host already had its share of sources.
+    pub skipped_same_domain: usize,
+    /// Successfully scraped sources, in rank order.
+    pub sources: Vec<ResearchSource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<ResearchFailure>,
+    pub budget_ms: u64,
+    pub total_duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
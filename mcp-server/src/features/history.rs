@@ -26,6 +26,56 @@ pub enum EntryType {
     Scrape,
 }
 
+impl EntryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryType::Search => "search",
+            EntryType::Scrape => "scrape",
+        }
+    }
+
+    pub fn parse_str(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "search" => Some(EntryType::Search),
+            "scrape" => Some(EntryType::Scrape),
+            _ => None,
+        }
+    }
+}
+
+/// Restrictions pushed down to LanceDB as a SQL predicate before ranking.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub entry_type: Option<EntryType>,
+    /// Inclusive lower bound on the entry timestamp.
+    pub since: Option<DateTime<Utc>>,
+    /// Inclusive upper bound on the entry timestamp.
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl HistoryFilter {
+    fn predicate(&self) -> Option<String> {
+        let mut clauses = Vec::new();
+        if let Some(entry_type) = &self.entry_type {
+            clauses.push(format!("entry_type = '{}'", entry_type.as_str()));
+        }
+        if let Some(since) = self.since {
+            clauses.push(format!("timestamp_ms >= {}", since.timestamp_millis()));
+        }
+        if let Some(until) = self.until {
+            clauses.push(format!("timestamp_ms <= {}", until.timestamp_millis()));
+        }
+        (!clauses.is_empty()).then(|| clauses.join(" AND "))
+    }
+}
+
+/// LanceDB predicate selecting the entry `id`. Ids are UUIDs; anything else (which could
+/// not be quoted safely) gets `None`.
+fn id_predicate(id: &str) -> Option<String> {
+    let is_uuid_like = !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    is_uuid_like.then(|| format!("id = '{}'", id))
+}
+
 /// History entry stored in semantic memory
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -40,6 +90,24 @@ pub struct HistoryEntry {
     pub source_type: Option<String>,
 }
 
+impl HistoryEntry {
+    /// Number of results a search returned, or `None` for scrapes.
+    ///
+    /// Read from the stored result array when it survived chunking, otherwise from the
+    /// `"(N results)"` suffix `log_search` writes into the summary.
+    pub fn result_count(&self) -> Option<usize> {
+        if !matches!(self.entry_type, EntryType::Search) {
+            return None;
+        }
+        if let Some(results) = self.full_result.as_array() {
+            return Some(results.len());
+        }
+        let inner = self.summary.strip_suffix(" results)")?;
+        let (_, count) = inner.rsplit_once('(')?;
+        count.trim().parse().ok()
+    }
+}
+
 /// Memory manager for research history
 pub struct MemoryManager {
     table: Table,
@@ -341,15 +409,29 @@ impl MemoryManager {
         min_similarity: f32,
         entry_type_filter: Option<EntryType>,
     ) -> Result<Vec<(HistoryEntry, f32)>> {
+        let filter = HistoryFilter {
+            entry_type: entry_type_filter,
+            ..Default::default()
+        };
+        self.search_history_filtered(query, max_results, min_similarity, &filter)
+            .await
+    }
+
+    /// `search_history` with an entry-type and time-range filter applied in LanceDB.
+    pub async fn search_history_filtered(
+        &self,
+        query: &str,
+        max_results: usize,
+        min_similarity: f32,
+        filter: &HistoryFilter,
+    ) -> Result<Vec<(HistoryEntry, f32)>> {
+        let predicate = filter.predicate();
+
         // Special case: empty query means "scan" (used by analytics helpers like get_top_domains)
         if query.trim().is_empty() {
             let mut scan = self.table.query().limit(max_results);
-            if let Some(entry_type) = entry_type_filter {
-                let filter_value = match entry_type {
-                    EntryType::Search => "search",
-                    EntryType::Scrape => "scrape",
-                };
-                scan = scan.only_if(format!("entry_type = '{}'", filter_value));
+            if let Some(predicate) = &predicate {
+                scan = scan.only_if(predicate.clone());
             }
 
             let stream = scan.execute().await.context("Failed to scan LanceDB")?;
@@ -374,12 +456,8 @@ impl MemoryManager {
             .distance_type(lancedb::DistanceType::Cosine)
             .limit(max_results);
 
-        if let Some(entry_type) = entry_type_filter {
-            let filter_value = match entry_type {
-                EntryType::Search => "search",
-                EntryType::Scrape => "scrape",
-            };
-            vector_query = vector_query.only_if(format!("entry_type = '{}'", filter_value));
+        if let Some(predicate) = predicate {
+            vector_query = vector_query.only_if(predicate);
         }
 
         let stream = vector_query
@@ -441,6 +519,52 @@ impl MemoryManager {
         Ok(entries)
     }
 
+    /// Embed `texts` with the memory's model in one blocking task.
+    pub async fn embed_texts(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let model = self.get_embedding_model().await?;
+        tokio::task::spawn_blocking(move || texts.iter().map(|t| model.encode_single(t)).collect())
+            .await
+            .context("Failed to generate embeddings")
+    }
+
+    /// Past scrapes closest to `embedding`, best first, scored as cosine similarity.
+    /// Unlike `search_history` there is no keyword boost: callers compare pages, not queries.
+    pub async fn nearest_scrapes(
+        &self,
+        embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<(HistoryEntry, f32)>> {
+        let filter = HistoryFilter {
+            entry_type: Some(EntryType::Scrape),
+            ..Default::default()
+        };
+        let mut vector_query = self
+            .table
+            .query()
+            .nearest_to(embedding)
+            .context("Failed to build vector query")?
+            .distance_type(lancedb::DistanceType::Cosine)
+            .limit(limit);
+        if let Some(predicate) = filter.predicate() {
+            vector_query = vector_query.only_if(predicate);
+        }
+
+        let stream = vector_query
+            .execute()
+            .await
+            .context("Failed to search LanceDB")?;
+        let batches: Vec<RecordBatch> = stream
+            .try_collect()
+            .await
+            .context("Failed to read search results")?;
+        let mut entries = Vec::new();
+        for batch in batches {
+            entries.extend(Self::batches_to_entries(&batch, Some("_distance"))?);
+        }
+        entries.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(entries)
+    }
+
     /// Log a search operation
     pub async fn log_search(
         &self,
@@ -507,6 +631,54 @@ impl MemoryManager {
         Ok((total as u64, total as u64))
     }
 
+    /// Delete the entry stored under `id`, if any.
+    pub async fn delete_entry(&self, id: &str) -> Result<()> {
+        let Some(predicate) = id_predicate(id) else {
+            return Ok(());
+        };
+        self.table
+            .delete(&predicate)
+            .await
+            .context("Failed to delete history entry")?;
+        Ok(())
+    }
+
+    /// Store and delete a throwaway entry, to tell whether the table accepts writes.
+    pub async fn probe_write(&self) -> Result<()> {
+        let id = Uuid::new_v4().to_string();
+        self.store_entry(HistoryEntry {
+            id: id.clone(),
+            entry_type: EntryType::Search,
+            query: "__memory_probe__".to_string(),
+            topic: "memory probe".to_string(),
+            summary: "memory probe".to_string(),
+            full_result: serde_json::Value::Null,
+            timestamp: Utc::now(),
+            domain: None,
+            source_type: None,
+        })
+        .await?;
+        self.delete_entry(&id).await
+    }
+
+    /// Delete every entry recorded before `cutoff`; returns how many were removed.
+    pub async fn delete_older_than(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        let predicate = format!("timestamp_ms < {}", cutoff.timestamp_millis());
+        let matching = self
+            .table
+            .count_rows(Some(predicate.clone()))
+            .await
+            .context("Failed to count expired history entries")?;
+        if matching > 0 {
+            self.table
+                .delete(&predicate)
+                .await
+                .context("Failed to delete expired history entries")?;
+            tracing::info!("Purged {} history entries older than {}", matching, cutoff);
+        }
+        Ok(matching)
+    }
+
     fn batches_to_entries(
         batch: &RecordBatch,
         distance_column: Option<&str>,
@@ -626,17 +798,66 @@ impl MemoryManager {
         Ok(out)
     }
 
-    /// Check for recent duplicate searches (within last N hours)
+    /// The newest `limit` entries of `entry_type`, newest first. Only the first 1000
+    /// entries of that type LanceDB returns are considered.
+    pub async fn recent_entries(
+        &self,
+        entry_type: EntryType,
+        limit: usize,
+    ) -> Result<Vec<HistoryEntry>> {
+        let filter = HistoryFilter {
+            entry_type: Some(entry_type),
+            ..Default::default()
+        };
+        let mut entries: Vec<HistoryEntry> = self
+            .search_history_filtered("", 1000, 0.0, &filter)
+            .await?
+            .into_iter()
+            .map(|(entry, _)| entry)
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.timestamp));
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    /// The entry stored under `id`.
+    pub async fn entry_by_id(&self, id: &str) -> Result<Option<HistoryEntry>> {
+        let Some(predicate) = id_predicate(id) else {
+            return Ok(None);
+        };
+        let stream = self
+            .table
+            .query()
+            .only_if(predicate)
+            .limit(1)
+            .execute()
+            .await
+            .context("Failed to look up history entry")?;
+        let batches: Vec<RecordBatch> = stream
+            .try_collect()
+            .await
+            .context("Failed to read history entry")?;
+        for batch in batches {
+            if let Some((entry, _)) = Self::batches_to_entries(&batch, None)?.into_iter().next() {
+                return Ok(Some(entry));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Check for recent duplicate searches (within last N hours) scoring at least
+    /// `min_similarity`
     pub async fn find_recent_duplicate(
         &self,
         query: &str,
         hours_back: u64,
+        min_similarity: f32,
     ) -> Result<Option<(HistoryEntry, f32)>> {
         use chrono::Duration;
 
         // Search for very similar queries (high threshold)
         let results = self
-            .search_history(query, 5, 0.9, Some(EntryType::Search))
+            .search_history(query, 5, min_similarity, Some(EntryType::Search))
             .await?;
 
         // Filter to only recent entries
@@ -695,3 +916,60 @@ impl MemoryManager {
         Ok(sorted)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(entry_type: EntryType, summary: &str, full_result: serde_json::Value) -> HistoryEntry {
+        HistoryEntry {
+            id: "id".to_string(),
+            entry_type,
+            query: "q".to_string(),
+            topic: "q".to_string(),
+            summary: summary.to_string(),
+            full_result,
+            timestamp: Utc::now(),
+            domain: None,
+            source_type: None,
+        }
+    }
+
+    #[test]
+    fn filter_predicate_combines_clauses() {
+        assert_eq!(HistoryFilter::default().predicate(), None);
+
+        let since = DateTime::<Utc>::from_timestamp_millis(1_000).unwrap();
+        let until = DateTime::<Utc>::from_timestamp_millis(2_000).unwrap();
+        let filter = HistoryFilter {
+            entry_type: Some(EntryType::Scrape),
+            since: Some(since),
+            until: Some(until),
+        };
+        assert_eq!(
+            filter.predicate().as_deref(),
+            Some("entry_type = 'scrape' AND timestamp_ms >= 1000 AND timestamp_ms <= 2000")
+        );
+    }
+
+    #[test]
+    fn result_count_from_results_or_summary() {
+        let listed = entry(
+            EntryType::Search,
+            "Search: rust (2 results)",
+            serde_json::json!([{}, {}]),
+        );
+        assert_eq!(listed.result_count(), Some(2));
+
+        // Chunked results are no longer an array; fall back to the summary.
+        let chunked = entry(
+            EntryType::Search,
+            "Search: tokio (result) (12 results)",
+            serde_json::json!({"content": "[..."}),
+        );
+        assert_eq!(chunked.result_count(), Some(12));
+
+        let scrape = entry(EntryType::Scrape, "Scraped: x", serde_json::json!({}));
+        assert_eq!(scrape.result_count(), None);
+    }
+}
@@ -0,0 +1,657 @@
+//! Circuit breaker and write queue in front of semantic memory.
+//!
+//! History writes (`log_search`, `log_scrape`) go through a bounded queue
+//! (`CORTEX_SCOUT_MEMORY_QUEUE`, default 256) drained by a background task, so a slow
+//! LanceDB never adds latency to a search or scrape; a write that finds the queue full is
+//! dropped. After `CORTEX_SCOUT_MEMORY_FAILURE_THRESHOLD` (default 5) consecutive failed
+//! calls memory is marked degraded: history writes and duplicate checks are skipped at
+//! once, with a single warning, and a background probe tries a throwaway write every
+//! `CORTEX_SCOUT_MEMORY_PROBE_SECS` (default 30). The first probe that succeeds marks
+//! memory healthy again.
+//!
+//! Reads that need the store itself (history search, semantic shaving) still use the
+//! [`MemoryManager`] directly and fail on their own.
+
+use crate::history::{HistoryEntry, MemoryManager};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+pub const DEFAULT_PROBE_SECS: u64 = 30;
+pub const DEFAULT_QUEUE_CAPACITY: usize = 256;
+
+/// When memory is marked degraded and how it is probed.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct BreakerPolicy {
+    /// Consecutive failed calls after which memory is degraded.
+    pub failure_threshold: u32,
+    /// Seconds between recovery probes while degraded.
+    pub probe_secs: u64,
+    /// History writes waiting for the store before new ones are dropped.
+    pub queue_capacity: usize,
+    /// Longest a single memory call may take before it counts as failed.
+    pub call_timeout_secs: u64,
+}
+
+impl Default for BreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            probe_secs: DEFAULT_PROBE_SECS,
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            call_timeout_secs: crate::core::config::scrape_stage_timeout_secs("history_log"),
+        }
+    }
+}
+
+impl BreakerPolicy {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            failure_threshold: std::env::var("CORTEX_SCOUT_MEMORY_FAILURE_THRESHOLD")
+                .ok()
+                .and_then(|v| v.trim().parse::<u32>().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.failure_threshold),
+            probe_secs: std::env::var("CORTEX_SCOUT_MEMORY_PROBE_SECS")
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.probe_secs),
+            queue_capacity: std::env::var("CORTEX_SCOUT_MEMORY_QUEUE")
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.queue_capacity),
+            ..defaults
+        }
+    }
+}
+
+/// The memory operations on the search and scrape paths. Implemented by
+/// [`MemoryManager`]; tests substitute a store that fails on demand.
+#[async_trait]
+pub trait HistoryStore: Send + Sync {
+    async fn log_search(&self, query: String, results: &Value, result_count: usize) -> Result<()>;
+
+    async fn log_scrape(
+        &self,
+        url: String,
+        title: Option<String>,
+        content_preview: String,
+        domain: Option<String>,
+        full_result: &Value,
+    ) -> Result<()>;
+
+    async fn delete_entry(&self, id: &str) -> Result<()>;
+
+    async fn find_recent_duplicate(
+        &self,
+        query: &str,
+        hours_back: u64,
+        min_similarity: f32,
+    ) -> Result<Option<(HistoryEntry, f32)>>;
+
+    async fn is_rapid_testing(&self, url: &str) -> Result<bool>;
+
+    /// A write that leaves nothing behind, to tell whether a degraded store recovered.
+    async fn probe(&self) -> Result<()>;
+}
+
+#[async_trait]
+impl HistoryStore for MemoryManager {
+    async fn log_search(&self, query: String, results: &Value, result_count: usize) -> Result<()> {
+        MemoryManager::log_search(self, query, results, result_count).await
+    }
+
+    async fn log_scrape(
+        &self,
+        url: String,
+        title: Option<String>,
+        content_preview: String,
+        domain: Option<String>,
+        full_result: &Value,
+    ) -> Result<()> {
+        MemoryManager::log_scrape(self, url, title, content_preview, domain, full_result).await
+    }
+
+    async fn delete_entry(&self, id: &str) -> Result<()> {
+        MemoryManager::delete_entry(self, id).await
+    }
+
+    async fn find_recent_duplicate(
+        &self,
+        query: &str,
+        hours_back: u64,
+        min_similarity: f32,
+    ) -> Result<Option<(HistoryEntry, f32)>> {
+        MemoryManager::find_recent_duplicate(self, query, hours_back, min_similarity).await
+    }
+
+    async fn is_rapid_testing(&self, url: &str) -> Result<bool> {
+        MemoryManager::is_rapid_testing(self, url).await
+    }
+
+    async fn probe(&self) -> Result<()> {
+        self.probe_write().await
+    }
+}
+
+/// A queued history write.
+enum HistoryWrite {
+    Search {
+        query: String,
+        results: Value,
+        result_count: usize,
+        /// Entry the new one supersedes, deleted once the write succeeds.
+        replaces: Option<String>,
+    },
+    Scrape {
+        url: String,
+        title: Option<String>,
+        content_preview: String,
+        domain: Option<String>,
+        full_result: Value,
+    },
+}
+
+impl HistoryWrite {
+    async fn apply(self, store: &dyn HistoryStore) -> Result<()> {
+        match self {
+            HistoryWrite::Search {
+                query,
+                results,
+                result_count,
+                replaces,
+            } => {
+                store.log_search(query, &results, result_count).await?;
+                if let Some(stale_id) = replaces {
+                    if let Err(e) = store.delete_entry(&stale_id).await {
+                        warn!("Failed to drop stale history entry {}: {}", stale_id, e);
+                    }
+                }
+                Ok(())
+            }
+            HistoryWrite::Scrape {
+                url,
+                title,
+                content_preview,
+                domain,
+                full_result,
+            } => {
+                store
+                    .log_scrape(url, title, content_preview, domain, &full_result)
+                    .await
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    total_failures: u64,
+    last_error: Option<String>,
+    last_error_timestamp: Option<u64>,
+    degraded_since: Option<u64>,
+    skipped_operations: u64,
+    dropped_writes: u64,
+    /// Set when a write was dropped on a full queue; cleared once one fits again.
+    queue_full: bool,
+}
+
+/// Payload of the `memory` section of `GET /health` and the `memory_status` MCP tool.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryHealthReport {
+    /// `disabled`, `initializing`, `failed` (never came up), `healthy` or `degraded`.
+    pub status: String,
+    pub consecutive_failures: u32,
+    pub total_failures: u64,
+    pub last_error: Option<String>,
+    pub last_error_timestamp: Option<u64>,
+    /// Unix time memory was marked degraded; `None` while healthy.
+    pub degraded_since: Option<u64>,
+    /// History writes and duplicate checks skipped while degraded.
+    pub skipped_operations: u64,
+    pub queued_writes: usize,
+    /// History writes dropped because the queue was full.
+    pub dropped_writes: u64,
+    pub policy: BreakerPolicy,
+}
+
+fn unix_now() -> Option<u64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+pub struct MemoryGuard {
+    policy: BreakerPolicy,
+    state: Mutex<BreakerState>,
+    store: OnceLock<Arc<dyn HistoryStore>>,
+    queue: OnceLock<mpsc::Sender<HistoryWrite>>,
+}
+
+impl Default for MemoryGuard {
+    fn default() -> Self {
+        Self::new(BreakerPolicy::default())
+    }
+}
+
+impl MemoryGuard {
+    pub fn new(policy: BreakerPolicy) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(BreakerState::default()),
+            store: OnceLock::new(),
+            queue: OnceLock::new(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(BreakerPolicy::from_env())
+    }
+
+    /// Start guarding `store`: spawns the history writer and the recovery probe. Only the
+    /// first store attached is used.
+    pub fn attach(self: &Arc<Self>, store: Arc<dyn HistoryStore>) {
+        if self.store.set(store.clone()).is_err() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("Semantic memory attached outside a Tokio runtime; history writes are off");
+            return;
+        };
+        let (tx, rx) = mpsc::channel(self.policy.queue_capacity.max(1));
+        let _ = self.queue.set(tx);
+        runtime.spawn(Arc::clone(self).write_history(store.clone(), rx));
+        runtime.spawn(Arc::clone(self).probe_while_degraded(store));
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.lock().degraded_since.is_some()
+    }
+
+    /// Queue a search for history. Returns at once; skipped while degraded.
+    pub fn log_search(
+        &self,
+        query: String,
+        results: Value,
+        result_count: usize,
+        replaces: Option<String>,
+    ) {
+        self.enqueue(HistoryWrite::Search {
+            query,
+            results,
+            result_count,
+            replaces,
+        });
+    }
+
+    /// Queue a scrape for history. Returns at once; skipped while degraded.
+    pub fn log_scrape(
+        &self,
+        url: String,
+        title: Option<String>,
+        content_preview: String,
+        domain: Option<String>,
+        full_result: Value,
+    ) {
+        self.enqueue(HistoryWrite::Scrape {
+            url,
+            title,
+            content_preview,
+            domain,
+            full_result,
+        });
+    }
+
+    /// `MemoryManager::find_recent_duplicate`, or `None` at once without memory or while
+    /// degraded.
+    pub async fn find_recent_duplicate(
+        &self,
+        query: &str,
+        hours_back: u64,
+        min_similarity: f32,
+    ) -> Result<Option<(HistoryEntry, f32)>> {
+        match self.available_store() {
+            Some(store) => {
+                self.call(store.find_recent_duplicate(query, hours_back, min_similarity))
+                    .await
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// `MemoryManager::is_rapid_testing`, or `false` at once without memory or while
+    /// degraded.
+    pub async fn is_rapid_testing(&self, url: &str) -> Result<bool> {
+        match self.available_store() {
+            Some(store) => self.call(store.is_rapid_testing(url)).await,
+            None => Ok(false),
+        }
+    }
+
+    pub fn report(&self) -> MemoryHealthReport {
+        let queued_writes = self
+            .queue
+            .get()
+            .map(|tx| tx.max_capacity() - tx.capacity())
+            .unwrap_or(0);
+        let state = self.lock();
+        MemoryHealthReport {
+            status: if state.degraded_since.is_some() {
+                "degraded"
+            } else {
+                "healthy"
+            }
+            .to_string(),
+            consecutive_failures: state.consecutive_failures,
+            total_failures: state.total_failures,
+            last_error: state.last_error.clone(),
+            last_error_timestamp: state.last_error_timestamp,
+            degraded_since: state.degraded_since,
+            skipped_operations: state.skipped_operations,
+            queued_writes,
+            dropped_writes: state.dropped_writes,
+            policy: self.policy,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state.lock().expect("memory guard mutex poisoned")
+    }
+
+    /// The store, unless memory is degraded (which counts the skipped operation).
+    fn available_store(&self) -> Option<&Arc<dyn HistoryStore>> {
+        let store = self.store.get()?;
+        let mut state = self.lock();
+        if state.degraded_since.is_some() {
+            state.skipped_operations += 1;
+            return None;
+        }
+        Some(store)
+    }
+
+    fn enqueue(&self, write: HistoryWrite) {
+        let Some(tx) = self.queue.get() else {
+            return;
+        };
+        if self.available_store().is_none() {
+            return;
+        }
+        match tx.try_send(write) {
+            Ok(()) => self.lock().queue_full = false,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                let mut state = self.lock();
+                state.dropped_writes += 1;
+                if !std::mem::replace(&mut state.queue_full, true) {
+                    warn!(
+                        "History write queue full ({} pending); dropping writes until it drains",
+                        self.policy.queue_capacity
+                    );
+                }
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {}
+        }
+    }
+
+    /// Run one memory call under the call timeout and record how it went.
+    async fn call<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        let timeout = Duration::from_secs(self.policy.call_timeout_secs);
+        let outcome = match tokio::time::timeout(timeout, future).await {
+            Ok(outcome) => outcome,
+            Err(_) => Err(anyhow!(
+                "memory call timed out after {} seconds",
+                timeout.as_secs()
+            )),
+        };
+        match &outcome {
+            Ok(_) => self.record_success(),
+            Err(e) => self.record_failure(e),
+        }
+        outcome
+    }
+
+    fn record_success(&self) {
+        let mut state = self.lock();
+        state.consecutive_failures = 0;
+        if state.degraded_since.take().is_some() {
+            info!("Semantic memory recovered; history writes and duplicate checks resume");
+        }
+    }
+
+    fn record_failure(&self, error: &anyhow::Error) {
+        let mut state = self.lock();
+        state.consecutive_failures += 1;
+        state.total_failures += 1;
+        state.last_error = Some(error.to_string());
+        state.last_error_timestamp = unix_now();
+        if state.degraded_since.is_some() {
+            debug!("Semantic memory still unavailable: {}", error);
+        } else if state.consecutive_failures >= self.policy.failure_threshold {
+            state.degraded_since = unix_now().or(Some(0));
+            warn!(
+                "Semantic memory degraded after {} consecutive failures (last: {}); skipping history writes and duplicate checks, probing every {}s",
+                state.consecutive_failures, error, self.policy.probe_secs
+            );
+        } else {
+            warn!("Semantic memory call failed: {}", error);
+        }
+    }
+
+    async fn write_history(
+        self: Arc<Self>,
+        store: Arc<dyn HistoryStore>,
+        mut rx: mpsc::Receiver<HistoryWrite>,
+    ) {
+        while let Some(write) = rx.recv().await {
+            // Writes queued before memory degraded are skipped too.
+            if self.is_degraded() {
+                self.lock().skipped_operations += 1;
+                continue;
+            }
+            let _ = self.call(write.apply(store.as_ref())).await;
+        }
+    }
+
+    async fn probe_while_degraded(self: Arc<Self>, store: Arc<dyn HistoryStore>) {
+        let period = Duration::from_secs(self.policy.probe_secs.max(1));
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if self.is_degraded() {
+                let _ = self.call(store.probe()).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Fails every call while `failing` is set; counts the calls that reach it.
+    #[derive(Default)]
+    struct FlakyStore {
+        failing: AtomicBool,
+        calls: AtomicUsize,
+        probes: AtomicUsize,
+        /// Scrape writes hang until the test lets them through.
+        gate: Option<tokio::sync::Semaphore>,
+    }
+
+    impl FlakyStore {
+        fn outcome(&self) -> Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.failing.load(Ordering::SeqCst) {
+                Err(anyhow!("No space left on device"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[async_trait]
+    impl HistoryStore for FlakyStore {
+        async fn log_search(&self, _: String, _: &Value, _: usize) -> Result<()> {
+            self.outcome()
+        }
+
+        async fn log_scrape(
+            &self,
+            _: String,
+            _: Option<String>,
+            _: String,
+            _: Option<String>,
+            _: &Value,
+        ) -> Result<()> {
+            if let Some(gate) = &self.gate {
+                gate.acquire().await.unwrap().forget();
+            }
+            self.outcome()
+        }
+
+        async fn delete_entry(&self, _: &str) -> Result<()> {
+            self.outcome()
+        }
+
+        async fn find_recent_duplicate(
+            &self,
+            _: &str,
+            _: u64,
+            _: f32,
+        ) -> Result<Option<(HistoryEntry, f32)>> {
+            self.outcome().map(|()| None)
+        }
+
+        async fn is_rapid_testing(&self, _: &str) -> Result<bool> {
+            self.outcome().map(|()| false)
+        }
+
+        async fn probe(&self) -> Result<()> {
+            self.probes.fetch_add(1, Ordering::SeqCst);
+            self.outcome()
+        }
+    }
+
+    fn policy() -> BreakerPolicy {
+        BreakerPolicy {
+            failure_threshold: 3,
+            probe_secs: 30,
+            queue_capacity: 4,
+            call_timeout_secs: 5,
+        }
+    }
+
+    fn guarded(store: FlakyStore) -> (Arc<MemoryGuard>, Arc<FlakyStore>) {
+        let store = Arc::new(store);
+        let guard = Arc::new(MemoryGuard::new(policy()));
+        guard.attach(store.clone());
+        (guard, store)
+    }
+
+    /// Let the writer task drain the queue.
+    async fn settle() {
+        for _ in 0..20 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    fn log(guard: &MemoryGuard, n: usize) {
+        guard.log_search(format!("query {}", n), Value::Null, 0, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn repeated_failures_degrade_and_a_probe_recovers() {
+        let (guard, store) = guarded(FlakyStore::default());
+        store.failing.store(true, Ordering::SeqCst);
+
+        for n in 0..3 {
+            log(&guard, n);
+            settle().await;
+        }
+        assert!(guard.is_degraded());
+        let report = guard.report();
+        assert_eq!(report.status, "degraded");
+        assert_eq!(report.consecutive_failures, 3);
+        assert_eq!(
+            report.last_error.as_deref(),
+            Some("No space left on device")
+        );
+
+        // Degraded: nothing reaches the store, and duplicate checks answer at once.
+        let calls = store.calls.load(Ordering::SeqCst);
+        log(&guard, 3);
+        settle().await;
+        assert!(guard
+            .find_recent_duplicate("query", 6, 0.9)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(!guard
+            .is_rapid_testing("https://example.com/")
+            .await
+            .unwrap());
+        assert_eq!(store.calls.load(Ordering::SeqCst), calls);
+        assert_eq!(guard.report().skipped_operations, 3);
+
+        // A failing probe keeps it degraded; the first one that succeeds recovers.
+        tokio::time::sleep(Duration::from_secs(31)).await;
+        settle().await;
+        assert_eq!(store.probes.load(Ordering::SeqCst), 1);
+        assert!(guard.is_degraded());
+        store.failing.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        settle().await;
+        assert_eq!(store.probes.load(Ordering::SeqCst), 2);
+        assert!(!guard.is_degraded());
+        assert_eq!(guard.report().consecutive_failures, 0);
+
+        log(&guard, 4);
+        settle().await;
+        assert_eq!(store.calls.load(Ordering::SeqCst), calls + 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_slow_store_never_blocks_the_caller() {
+        let (guard, store) = guarded(FlakyStore {
+            gate: Some(tokio::sync::Semaphore::new(0)),
+            ..Default::default()
+        });
+        let scrape = |n: usize| {
+            guard.log_scrape(
+                format!("https://example.com/{}", n),
+                None,
+                String::new(),
+                None,
+                Value::Null,
+            )
+        };
+
+        // One write is held by the store and four wait in the queue; the rest are dropped.
+        for n in 0..8 {
+            scrape(n);
+            settle().await;
+        }
+        let report = guard.report();
+        assert_eq!(report.queued_writes, 4);
+        assert_eq!(report.dropped_writes, 3);
+        assert!(!guard.is_degraded());
+
+        store.gate.as_ref().unwrap().add_permits(5);
+        settle().await;
+        assert_eq!(store.calls.load(Ordering::SeqCst), 5);
+        assert_eq!(guard.report().queued_writes, 0);
+    }
+}
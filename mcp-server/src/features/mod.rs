@@ -2,6 +2,7 @@ pub mod antibot;
 pub mod auth_registry;
 pub mod history;
 pub mod host_guard;
+pub mod memory_guard;
 pub mod non_robot_search;
 pub mod proxy_grabber;
 pub mod proxy_manager;
@@ -4,6 +4,8 @@
 //! - Requires explicit user consent before launching a visible browser.
 //! - Includes a **Safety Kill Switch** (hold `ESC` ~3 seconds) to abort and clean up.
 //! - Times out (`human_timeout`) if the user doesn't respond.
+//! - `CORTEX_SCOUT_NON_ROBOT_HEADLESS_TEST=1` runs the same state machine headlessly for
+//!   CI, without any human interaction (see [`NonRobotSearchConfig::headless_test`]).
 //!
 //! Stealth model:
 //! - `non_robot_search` runs a *real, visible* user browser (Brave/Chrome/Chromium) and lets
@@ -18,7 +20,9 @@ use std::time::Duration;
 use thiserror::Error;
 
 #[cfg(feature = "non_robot_search")]
-use crate::types::EmbeddedDataSource;
+use crate::scraping::provenance;
+#[cfg(feature = "non_robot_search")]
+use crate::types::{EmbeddedDataSource, ScrapeMethod, UserAgentClass};
 
 #[cfg(feature = "non_robot_search")]
 use crate::rust_scraper::RustScraper;
@@ -27,7 +31,7 @@ use anyhow::anyhow;
 #[cfg(feature = "non_robot_search")]
 use rand::distr::{Distribution, Uniform};
 #[cfg(feature = "non_robot_search")]
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 #[cfg(feature = "non_robot_search")]
 use std::time::Instant;
 #[cfg(feature = "non_robot_search")]
@@ -40,6 +44,7 @@ use tracing::{info, warn};
 enum NonRobotState {
     Initial,
     VisibleBrowserLaunch,
+    SessionReused,
     Interaction,
     ChallengeDetection,
     HitlTrigger,
@@ -102,6 +107,18 @@ pub struct NonRobotSearchConfig {
     /// Use this to tell the user exactly what to log in to and why.
     /// Example: "Please log in to GitHub so I can read the Discussions in this repo."
     pub instruction_message: Option<String>,
+    /// Scripted-consent mode for automated tests: skips consent, notifications, tones and
+    /// the kill switch, launches the browser with `--headless=new`, and fails with
+    /// [`NonRobotSearchError::HumanUnavailable`] as soon as a challenge needs a human.
+    /// Callers set it from [`crate::core::config::non_robot_headless_test`].
+    pub headless_test: bool,
+    /// Keep the browser warm this long after a successful extraction so the next call
+    /// within the TTL skips consent and the cold launch. `None` closes it as soon as the
+    /// call ends. Callers resolve it from
+    /// [`crate::core::config::ShadowNonRobotSearchConfig::resolve_session_ttl`].
+    pub session_ttl: Option<Duration>,
+    /// Close the browser when this call ends even if `session_ttl` would keep it warm.
+    pub close_session: bool,
 }
 
 #[derive(Debug, Error)]
@@ -129,6 +146,9 @@ pub enum NonRobotSearchError {
 
     #[error("automation failed: {0}")]
     AutomationFailed(String),
+
+    #[error("{0}")]
+    DomainPolicy(crate::core::error::ScoutError),
 }
 
 /// `execute_manual_auth_flow` — strict manual-only HITL flow.
@@ -141,6 +161,10 @@ pub async fn execute_manual_auth_flow(
     state: &Arc<AppState>,
     cfg: NonRobotSearchConfig,
 ) -> Result<ScrapeResponse, NonRobotSearchError> {
+    crate::scraping::domain_policy::check(&cfg.url)
+        .await
+        .map_err(NonRobotSearchError::DomainPolicy)?;
+
     #[cfg(feature = "non_robot_search")]
     {
         execute_manual_auth_flow_inner(state, cfg).await
@@ -165,10 +189,16 @@ async fn execute_manual_auth_flow_inner(
     // Ensure sequential execution of non-robot tool calls.
     let _serial_guard = state.non_robot_search_lock.lock().await;
 
+    // The login flow always starts a fresh browser on the shared debugging port.
+    discard_warm_session().await;
+
     notify_and_prompt_user(&cfg)?;
 
     let (abort_tx, mut abort_rx) = watch::channel(false);
-    let killswitch = KillSwitch::start(abort_tx);
+    // A client cancelling the tool call takes the same path as the emergency abort.
+    let _cancel_forward = crate::mcp::cancellation::forward_to(abort_tx.clone());
+    // The rdev listener needs a desktop session; headless test runs have none.
+    let killswitch = (!cfg.headless_test).then(|| KillSwitch::start(abort_tx));
 
     let input_controller: Box<dyn InputController> = Box::new(NoopInputController);
 
@@ -193,10 +223,13 @@ async fn execute_manual_auth_flow_inner(
     input_controller.lock().ok();
     let lock_guard = InputLockGuard::new(&*input_controller);
 
-    let mut session =
-        BrowserSession::launch(proxy_arg.as_deref(), cfg.user_profile_path.as_deref())
-            .await
-            .map_err(|e| NonRobotSearchError::BrowserLaunchFailed(e.to_string()))?;
+    let mut session = BrowserSession::launch(
+        proxy_arg.as_deref(),
+        cfg.user_profile_path.as_deref(),
+        cfg.headless_test,
+    )
+    .await
+    .map_err(|e| NonRobotSearchError::BrowserLaunchFailed(e.to_string()))?;
 
     log_state(NonRobotState::VisibleBrowserLaunch);
 
@@ -257,7 +290,9 @@ async fn execute_manual_auth_flow_inner(
         session.close().await;
     }
 
-    killswitch.stop();
+    if let Some(killswitch) = &killswitch {
+        killswitch.stop();
+    }
     log_state(NonRobotState::Done);
 
     // Record proxy result.
@@ -386,6 +421,22 @@ pub async fn execute_non_robot_search(
     }
 }
 
+/// Close the browser a previous call kept warm (see [`NonRobotSearchConfig::session_ttl`]),
+/// waiting for any call in flight. `true` when there was one to close.
+pub async fn close_warm_session(state: &Arc<AppState>) -> bool {
+    #[cfg(feature = "non_robot_search")]
+    {
+        let _serial_guard = state.non_robot_search_lock.lock().await;
+        discard_warm_session().await
+    }
+
+    #[cfg(not(feature = "non_robot_search"))]
+    {
+        let _ = state;
+        false
+    }
+}
+
 #[cfg(feature = "non_robot_search")]
 async fn execute_non_robot_search_impl(
     state: &Arc<AppState>,
@@ -405,23 +456,34 @@ async fn execute_non_robot_search_inner(
     cfg: NonRobotSearchConfig,
 ) -> Result<ScrapeResponse, NonRobotSearchError> {
     log_state(NonRobotState::Initial);
+    let started = Instant::now();
 
     // Ensure sequential execution of non-robot tool calls.
     // This avoids Chromium profile lock conflicts (e.g., SingletonLock) when callers reuse a live profile.
     let _serial_guard = state.non_robot_search_lock.lock().await;
 
-    notify_and_prompt_user(&cfg)?;
+    // One consent covers a warm session for its whole lifetime.
+    let warm = take_warm_session(&cfg).await;
+    if warm.is_none() {
+        notify_and_prompt_user(&cfg)?;
+    }
 
     let (abort_tx, mut abort_rx) = watch::channel(false);
-    let killswitch = KillSwitch::start(abort_tx);
+    // A client cancelling the tool call takes the same path as the emergency abort.
+    let _cancel_forward = crate::mcp::cancellation::forward_to(abort_tx.clone());
+    // The rdev listener needs a desktop session; headless test runs have none.
+    let killswitch = (!cfg.headless_test).then(|| KillSwitch::start(abort_tx));
 
     // Best-effort input controller. Full OS-level input blocking is platform-specific and requires
     // elevated permissions (e.g., Accessibility on macOS). We keep the interface so a stricter
     // implementation can be plugged in later.
     let input_controller: Box<dyn InputController> = Box::new(NoopInputController);
 
-    // Acquire proxy (if requested) before input locking.
-    let proxy_arg = if cfg.use_proxy {
+    // Acquire proxy (if requested) before input locking. A warm session keeps the proxy it
+    // was launched with rather than rotating mid-session.
+    let proxy_arg = if let Some(session) = &warm {
+        session.proxy.clone()
+    } else if cfg.use_proxy {
         if let Some(manager) = &state.proxy_manager {
             match manager.switch_to_best_proxy().await {
                 Ok(proxy_url) => Some(proxy_url),
@@ -441,12 +503,23 @@ async fn execute_non_robot_search_inner(
     input_controller.lock().ok();
     let lock_guard = InputLockGuard::new(&*input_controller);
 
-    let mut session =
-        BrowserSession::launch(proxy_arg.as_deref(), cfg.user_profile_path.as_deref())
+    let mut session = match warm {
+        Some(session) => {
+            log_state(NonRobotState::SessionReused);
+            session
+        }
+        None => {
+            let session = BrowserSession::launch(
+                proxy_arg.as_deref(),
+                cfg.user_profile_path.as_deref(),
+                cfg.headless_test,
+            )
             .await
             .map_err(|e| NonRobotSearchError::BrowserLaunchFailed(e.to_string()))?;
-
-    log_state(NonRobotState::VisibleBrowserLaunch);
+            log_state(NonRobotState::VisibleBrowserLaunch);
+            session
+        }
+    };
 
     // Drive main flow; if the browser transport drops mid-run (common during manual interaction),
     // relaunch once using the same profile directory and retry.
@@ -506,11 +579,15 @@ async fn execute_non_robot_search_inner(
         ).await;
         // Detach handler task so the browser process continues independently.
         session.handler_task.abort();
+    } else if let (Ok(_), Some(ttl), false) = (&result, cfg.session_ttl, cfg.close_session) {
+        park_warm_session(state, session, &cfg, ttl);
     } else {
         session.close().await;
     }
 
-    killswitch.stop();
+    if let Some(killswitch) = &killswitch {
+        killswitch.stop();
+    }
 
     log_state(NonRobotState::Done);
 
@@ -520,9 +597,137 @@ async fn execute_non_robot_search_inner(
         let _ = manager.record_proxy_result(proxy_url, success, None).await;
     }
 
+    if let Ok(page) = &mut result {
+        page.provenance = vec![provenance::attempt(
+            ScrapeMethod::NonRobot,
+            UserAgentClass::Desktop,
+            proxy_arg.as_deref(),
+            started.elapsed(),
+            page.status_code,
+            None,
+        )];
+    }
+
     result
 }
 
+/// A browser kept alive between calls. Only touched while holding
+/// `AppState::non_robot_search_lock`; `generation` tells an idle-timeout task whether the
+/// session it was started for is still the one parked.
+#[cfg(feature = "non_robot_search")]
+struct WarmSession {
+    session: BrowserSession,
+    user_profile_path: Option<String>,
+    use_proxy: bool,
+    headless: bool,
+    expires_at: Instant,
+    generation: u64,
+}
+
+#[cfg(feature = "non_robot_search")]
+static WARM_SESSION: std::sync::Mutex<Option<WarmSession>> = std::sync::Mutex::new(None);
+
+#[cfg(feature = "non_robot_search")]
+static WARM_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(feature = "non_robot_search")]
+fn warm_slot() -> std::sync::MutexGuard<'static, Option<WarmSession>> {
+    WARM_SESSION.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Take the parked session if it can serve `cfg`: same profile, proxy choice and headless
+/// mode, not yet expired, and its window still open. A session that can't is closed.
+#[cfg(feature = "non_robot_search")]
+async fn take_warm_session(cfg: &NonRobotSearchConfig) -> Option<BrowserSession> {
+    let warm = warm_slot().take()?;
+    let reusable = warm.user_profile_path == cfg.user_profile_path
+        && warm.use_proxy == cfg.use_proxy
+        && warm.headless == cfg.headless_test
+        && warm.expires_at > Instant::now()
+        && !warm.session.is_closed();
+    if reusable {
+        info!(
+            "non_robot_search: reusing warm browser session {} (port {})",
+            warm.generation, warm.session.debugging_port
+        );
+        return Some(warm.session);
+    }
+
+    info!(
+        "non_robot_search: warm browser session {} cannot serve this call; closing it",
+        warm.generation
+    );
+    let mut session = warm.session;
+    session.close().await;
+    None
+}
+
+/// Close the parked session, if any. Callers hold `non_robot_search_lock`.
+#[cfg(feature = "non_robot_search")]
+async fn discard_warm_session() -> bool {
+    let Some(warm) = warm_slot().take() else {
+        return false;
+    };
+    info!(
+        "non_robot_search: closing warm browser session {}",
+        warm.generation
+    );
+    let mut session = warm.session;
+    session.close().await;
+    true
+}
+
+/// Park `session` for `ttl` and start the idle-timeout task that closes it unless a later
+/// call has taken it (and re-parked it under a new generation) by then.
+#[cfg(feature = "non_robot_search")]
+fn park_warm_session(
+    state: &Arc<AppState>,
+    session: BrowserSession,
+    cfg: &NonRobotSearchConfig,
+    ttl: Duration,
+) {
+    let generation = WARM_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    info!(
+        "non_robot_search: keeping browser warm for {}s as session {}",
+        ttl.as_secs(),
+        generation
+    );
+    *warm_slot() = Some(WarmSession {
+        session,
+        user_profile_path: cfg.user_profile_path.clone(),
+        use_proxy: cfg.use_proxy,
+        headless: cfg.headless_test,
+        expires_at: Instant::now() + ttl,
+        generation,
+    });
+
+    let serial = Arc::clone(&state.non_robot_search_lock);
+    tokio::spawn(async move {
+        tokio::time::sleep(ttl).await;
+        let _serial_guard = serial.lock().await;
+        let expired = {
+            let mut slot = warm_slot();
+            if slot
+                .as_ref()
+                .is_some_and(|warm| warm.generation == generation)
+            {
+                slot.take()
+            } else {
+                None
+            }
+        };
+        if let Some(warm) = expired {
+            info!(
+                "non_robot_search: warm browser session {} idle for {}s; closing it",
+                generation,
+                ttl.as_secs()
+            );
+            let mut session = warm.session;
+            session.close().await;
+        }
+    });
+}
+
 #[cfg(feature = "non_robot_search")]
 async fn wait_for_network_idle_heuristic(page: &chromiumoxide::Page, timeout: Duration) {
     let timeout_ms = timeout.as_millis().min(u128::from(u64::MAX)) as u64;
@@ -915,12 +1120,7 @@ async fn run_flow(
                 break;
             }
         } else if start.elapsed() >= cfg.captcha_grace {
-            log_state(NonRobotState::HitlTrigger);
-            request_human_help(session, input_controller).await?;
-            log_state(NonRobotState::UserActionCompletionDetection);
-            wait_for_human_resolution(session, cfg.human_timeout, abort_rx).await?;
-            // After resolved, re-lock input and continue.
-            input_controller.lock().ok();
+            hand_over_to_human(session, cfg, input_controller, abort_rx).await?;
             hitl_triggered = true;
             break;
         }
@@ -943,11 +1143,7 @@ async fn run_flow(
         };
 
         if challenged_now {
-            log_state(NonRobotState::HitlTrigger);
-            request_human_help(session, input_controller).await?;
-            log_state(NonRobotState::UserActionCompletionDetection);
-            wait_for_human_resolution(session, cfg.human_timeout, abort_rx).await?;
-            input_controller.lock().ok();
+            hand_over_to_human(session, cfg, input_controller, abort_rx).await?;
         }
     }
 
@@ -966,7 +1162,9 @@ async fn run_flow(
 
     if manual_triggered {
         info!("non_robot_search: 🚀 MANUAL RETURN BUTTON CLICKED - Extracting immediately");
-        play_tone(Tone::Success);
+        if !cfg.headless_test {
+            play_tone(Tone::Success);
+        }
         // Skip wait steps, extract immediately
     } else {
         // Default auto-extraction flow
@@ -1080,67 +1278,55 @@ async fn run_flow(
     let mut settle_time_ms: Option<u64> = None;
     if !matches!(cfg.quality_mode, crate::rust_scraper::QualityMode::Balanced) {
         info!("non_robot_search: waiting for innerText length to settle...");
-        let settle_script = r#"
-            (async () => {
-                const maxChecks = 60;   // 15s max
-                const interval = 250;   // ms
-                const requiredStableMs = 1500;
-
-                let lastLen = (document.body && document.body.innerText) ? document.body.innerText.length : 0;
-                let stableMs = 0;
-
-                for (let i = 0; i < maxChecks; i++) {
-                    await new Promise(r => setTimeout(r, interval));
-                    const curLen = (document.body && document.body.innerText) ? document.body.innerText.length : 0;
-                    if (curLen === lastLen) {
-                        stableMs += interval;
-                        if (stableMs >= requiredStableMs) {
-                            return { settled: true, checks: i + 1, len: curLen, elapsedMs: (i + 1) * interval };
-                        }
-                    } else {
-                        stableMs = 0;
-                    }
-                    lastLen = curLen;
+        match crate::scraping::browser_manager::wait_for_text_settle(&session.page).await {
+            Ok(settle) => {
+                if settle.elapsed_ms > 0 {
+                    settle_time_ms = Some(settle.elapsed_ms);
                 }
-                return { settled: false, checks: maxChecks, len: lastLen, elapsedMs: maxChecks * interval };
-            })()
-        "#;
-
-        match session.page.evaluate(settle_script).await {
-            Ok(result) => {
-                if let Ok(info) = result.into_value::<serde_json::Value>() {
-                    let settled = info
-                        .get("settled")
-                        .and_then(|v| v.as_bool())
-                        .unwrap_or(false);
-                    let checks = info.get("checks").and_then(|v| v.as_i64()).unwrap_or(0);
-                    let len = info.get("len").and_then(|v| v.as_i64()).unwrap_or(0);
-                    let elapsed_ms = info.get("elapsedMs").and_then(|v| v.as_u64()).unwrap_or(0);
-                    if elapsed_ms > 0 {
-                        settle_time_ms = Some(elapsed_ms);
-                    }
-                    if settled {
-                        info!(
-                            "non_robot_search: innerText settled after {} checks (~{}s), len={}",
-                            checks,
-                            checks as f64 * 0.25,
-                            len
-                        );
-                    } else {
-                        warn!(
-                            "non_robot_search: innerText did not fully settle after {} checks (len={})",
-                            checks, len
-                        );
-                    }
+                if settle.settled {
+                    info!(
+                        "non_robot_search: innerText settled after {} checks (~{}s), len={}",
+                        settle.checks,
+                        settle.checks as f64 * 0.25,
+                        settle.len
+                    );
+                } else {
+                    warn!(
+                        "non_robot_search: innerText did not fully settle after {} checks (len={})",
+                        settle.checks, settle.len
+                    );
                 }
             }
-            Err(e) => warn!("non_robot_search: innerText settle check failed: {}", e),
+            Err(e) => warn!("non_robot_search: {}", e),
         }
     }
 
     extract_current_page(state, cfg, session, settle_time_ms).await
 }
 
+/// Challenge still present after the grace period: ask the human to solve it, wait for
+/// FINISH & RETURN, then re-lock input. Headless test runs have nobody to ask.
+#[cfg(feature = "non_robot_search")]
+async fn hand_over_to_human(
+    session: &BrowserSession,
+    cfg: &NonRobotSearchConfig,
+    input_controller: &dyn InputController,
+    abort_rx: &mut watch::Receiver<bool>,
+) -> Result<(), NonRobotSearchError> {
+    log_state(NonRobotState::HitlTrigger);
+    if cfg.headless_test {
+        warn!(
+            "non_robot_search: challenge detected in headless test mode; no human to hand over to"
+        );
+        return Err(NonRobotSearchError::HumanUnavailable);
+    }
+    request_human_help(session, input_controller).await?;
+    log_state(NonRobotState::UserActionCompletionDetection);
+    wait_for_human_resolution(session, cfg.human_timeout, abort_rx).await?;
+    input_controller.lock().ok();
+    Ok(())
+}
+
 #[cfg(feature = "non_robot_search")]
 async fn run_manual_auth_flow(
     state: &Arc<AppState>,
@@ -1188,6 +1374,10 @@ async fn run_manual_auth_flow(
 
     // Enter HITL immediately. No challenge heuristics.
     log_state(NonRobotState::HitlTrigger);
+    if cfg.headless_test {
+        warn!("non_robot_search: manual_auth_flow needs a human; failing in headless test mode");
+        return Err(NonRobotSearchError::HumanUnavailable);
+    }
     request_human_help(session, input_controller).await?;
     log_state(NonRobotState::UserActionCompletionDetection);
 
@@ -1281,21 +1471,18 @@ async fn extract_current_page(
         .scrape_cache
         .insert(cfg.url.clone(), scraped.clone())
         .await;
-    if let Some(memory) = state.get_memory() {
+    if state.get_memory().is_some() {
         let summary = format!("{} words (non_robot_search)", scraped.word_count);
         let domain = url::Url::parse(&cfg.url)
             .ok()
             .and_then(|u| u.host_str().map(|s| s.to_string()));
-        let result_json = serde_json::to_value(&scraped).unwrap_or_default();
-        let _ = memory
-            .log_scrape(
-                cfg.url.clone(),
-                Some(scraped.title.clone()),
-                summary,
-                domain,
-                &result_json,
-            )
-            .await;
+        state.memory_guard.log_scrape(
+            cfg.url.clone(),
+            Some(scraped.title.clone()),
+            summary,
+            domain,
+            serde_json::to_value(&scraped).unwrap_or_default(),
+        );
     }
 
     Ok(scraped)
@@ -1401,6 +1588,7 @@ fn deep_metadata_hunt_jobposting_scripts(html: &str) -> Vec<EmbeddedDataSource>
         out.push(EmbeddedDataSource {
             source_type,
             content,
+            framework: None,
         });
 
         if out.len() >= 8 {
@@ -1465,6 +1653,7 @@ fn deep_metadata_hunt_linkedin_job_evidence(html: &str) -> Vec<EmbeddedDataSourc
     vec![EmbeddedDataSource {
         source_type: "linkedin:html-evidence:fallback".to_string(),
         content: evidence.to_string(),
+        framework: None,
     }]
 }
 
@@ -1502,6 +1691,11 @@ async fn get_html_snapshot(page: &chromiumoxide::Page, closed: bool) -> anyhow::
 
 #[cfg(feature = "non_robot_search")]
 fn notify_and_prompt_user(cfg: &NonRobotSearchConfig) -> Result<(), NonRobotSearchError> {
+    if cfg.headless_test {
+        info!("non_robot_search: headless test mode — skipping consent, notification and tones");
+        return Ok(());
+    }
+
     let auto_allow = std::env::var("CORTEX_SCOUT_NON_ROBOT_AUTO_ALLOW")
         .ok()
         .as_deref()
@@ -1568,7 +1762,7 @@ fn notify_and_prompt_user(cfg: &NonRobotSearchConfig) -> Result<(), NonRobotSear
 
     // If we have an interactive TTY and consent mode allows it, use strict Enter/Esc flow.
     if use_tty_prompt {
-        println!(
+        eprintln!(
             "Cortex Scout needs screen access for high-fidelity rendering. Press [Enter] to allow or [Esc] to cancel."
         );
 
@@ -1633,7 +1827,7 @@ fn notify_and_prompt_user(cfg: &NonRobotSearchConfig) -> Result<(), NonRobotSear
 
 #[cfg(feature = "non_robot_search")]
 fn notify_and_prompt_user_tty() -> Result<(), NonRobotSearchError> {
-    println!(
+    eprintln!(
         "Cortex Scout needs screen access for high-fidelity rendering. Press [Enter] to allow or [Esc] to cancel."
     );
 
@@ -2420,6 +2614,7 @@ struct BrowserSession {
     profile_name: Option<String>,
     created_profile_dir: bool,
     debugging_port: u16,
+    headless: bool,
 }
 
 #[cfg(feature = "non_robot_search")]
@@ -2443,7 +2638,11 @@ impl Drop for BrowserSession {
 
 #[cfg(feature = "non_robot_search")]
 impl BrowserSession {
-    async fn launch(proxy: Option<&str>, user_profile_path: Option<&str>) -> anyhow::Result<Self> {
+    async fn launch(
+        proxy: Option<&str>,
+        user_profile_path: Option<&str>,
+        headless: bool,
+    ) -> anyhow::Result<Self> {
         let (profile_dir, profile_name, created_profile_dir) =
             resolve_profile_dir(user_profile_path)?;
 
@@ -2472,9 +2671,13 @@ impl BrowserSession {
             "--no-first-run".to_string(),
             "--no-default-browser-check".to_string(),
         ];
+        if headless {
+            args.push("--headless=new".to_string());
+        }
 
         if let Some(proxy_url) = proxy {
-            args.push(format!("--proxy-server={}", proxy_url));
+            let server = crate::features::proxy_manager::browser_proxy_server(proxy_url)?;
+            args.push(format!("--proxy-server={}", server));
         }
 
         if let Some(dir) = profile_dir.as_ref() {
@@ -2607,6 +2810,7 @@ impl BrowserSession {
             profile_name,
             created_profile_dir,
             debugging_port,
+            headless,
         })
     }
 
@@ -2686,9 +2890,13 @@ impl BrowserSession {
             "--no-first-run".to_string(),
             "--no-default-browser-check".to_string(),
         ];
+        if self.headless {
+            args.push("--headless=new".to_string());
+        }
 
         if let Some(proxy_url) = self.proxy.as_ref() {
-            args.push(format!("--proxy-server={}", proxy_url));
+            let server = crate::features::proxy_manager::browser_proxy_server(proxy_url)?;
+            args.push(format!("--proxy-server={}", server));
         }
 
         if let Some(dir) = self.profile_dir.as_ref() {
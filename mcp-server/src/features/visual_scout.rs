@@ -53,6 +53,7 @@ pub async fn take_screenshot(
     width: Option<u32>,
     height: Option<u32>,
 ) -> Result<VisualScoutResult> {
+    crate::scraping::domain_policy::check(url).await?;
     let total_start = Instant::now();
     let mut phases = Vec::new();
     let exe = browser_manager::find_chrome_executable().ok_or_else(|| {
@@ -30,12 +30,16 @@ pub use core::AppState;
 
 // --- Backwards-compatible module paths ---
 pub use features::{
-    antibot, history, host_guard, non_robot_search, proxy_grabber, proxy_manager, visual_scout,
+    antibot, history, host_guard, memory_guard, non_robot_search, proxy_grabber, proxy_manager,
+    visual_scout,
 };
 pub use mcp::handlers as mcp_handlers;
 pub use mcp::stdio as stdio_service;
 pub use mcp::tooling as mcp_tooling;
-pub use nlp::{query_rewriter, rerank};
+pub use nlp::{extractive, query_rewriter, rerank};
 pub use scraping::rust_scraper;
 pub use setup as shadow_setup;
-pub use tools::{batch_scrape, crawl, deep_research, extract, scrape, search};
+pub use tools::{
+    batch_scrape, crawl, crawl_graph, crawl_state, deep_research, extract, history_search,
+    monitor, research, scrape, scrape_compare, scrape_diff, search, semantic_dedup,
+};
@@ -1,17 +1,36 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{IntoResponse, Json, Response},
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, Sse},
+        IntoResponse, Json, Response,
+    },
     routing::{get, post},
     Router,
 };
+use std::convert::Infallible;
 use std::env;
-use std::sync::Arc;
-use tower_http::cors::CorsLayer;
+use std::sync::{Arc, OnceLock};
 use tower_http::trace::TraceLayer;
 use tracing::{error, info, warn};
+use utoipa::OpenApi;
 
-use cortex_scout::{mcp, scrape, search, types::*, AppState};
+use cortex_scout::core::api_auth::{self, ApiKeyAuth};
+use cortex_scout::core::error::{ErrorKind, ErrorResponses, ScoutError};
+use cortex_scout::core::http_cache::{self, Endpoint};
+use cortex_scout::core::readiness;
+use cortex_scout::core::request_id;
+use cortex_scout::core::startup::{self, Init, StartupGate, StartupReport, StartupTracker};
+use cortex_scout::proxy_manager::{ProxyManager, ProxyPoolReport};
+use cortex_scout::rust_scraper::QualityMode;
+use cortex_scout::scraping::pagination;
+use cortex_scout::scraping::request_headers::RequestHeaders;
+use cortex_scout::scraping::sections::SectionOptions;
+use cortex_scout::search::engine_stats::EngineStatusReport;
+use cortex_scout::{
+    batch_scrape, crawl_state, extractive, history_search, mcp, scrape, search, semantic_dedup,
+    setup, types::*, AppState,
+};
 
 fn parse_port_from_args() -> Option<u16> {
     let mut args = std::env::args().peekable();
@@ -42,14 +61,40 @@ fn port_from_env() -> Option<u16> {
     None
 }
 
+/// `--stdio` or `MCP_TRANSPORT=stdio`: serve MCP over stdin/stdout instead of HTTP.
+fn stdio_transport_requested(args: &[String]) -> bool {
+    args.iter().any(|a| a == "--stdio")
+        || std::env::var("MCP_TRANSPORT")
+            .map(|v| v.trim().eq_ignore_ascii_case("stdio"))
+            .unwrap_or(false)
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+
+    // stdio mode must run before the stdout tracing subscriber below is installed:
+    // stdout carries the JSON-RPC stream, so the stdio service logs to stderr only.
+    if stdio_transport_requested(&args) && !args.iter().any(|a| a == "--setup") {
+        return cortex_scout::stdio_service::run().await;
+    }
+
+    // `quality-check <html-file> <url> [--write]` regenerates a golden scrape fixture.
+    // Runs before tracing is installed so stdout carries only the expectation JSON.
+    if args.get(1).map(String::as_str) == Some("quality-check") {
+        return cortex_scout::rust_scraper::golden::run_quality_check(&args[2..]).await;
+    }
+
+    // `replay <capture_id>` re-extracts a debug capture from CORTEX_SCOUT_CAPTURE_DIR.
+    if args.get(1).map(String::as_str) == Some("replay") {
+        return cortex_scout::scraping::capture::run_replay(&args[2..]).await;
+    }
+
     // Initialize tracing
     let env_filter = cortex_scout::build_env_filter("info,tower_http=warn");
     tracing_subscriber::fmt().with_env_filter(env_filter).init();
 
     // Handle setup-only mode
-    let args: Vec<String> = std::env::args().collect();
     if args.iter().any(|a| a == "--setup") {
         let opts = cortex_scout::setup::SetupOptions {
             mode: cortex_scout::setup::SetupRunMode::SetupFlag,
@@ -65,14 +110,7 @@ async fn main() -> anyhow::Result<()> {
     }
 
     info!("Starting MCP Server");
-
-    // Pre-flight checklist (non-interactive) at startup
-    let report = cortex_scout::setup::check_all(cortex_scout::setup::SetupOptions::default()).await;
-    info!("{}", report.summarize_for_logs());
-    if report.has_failures() {
-        warn!("shadow-setup: startup checklist found failures; run with --setup for guided remediation");
-        report.print_action_required_blocks();
-    }
+    let tracker = Arc::new(StartupTracker::new());
 
     // Create HTTP client
     let http_timeout = env::var("HTTP_TIMEOUT_SECS")
@@ -83,110 +121,321 @@ async fn main() -> anyhow::Result<()> {
         .ok()
         .and_then(|v| v.parse::<u64>().ok())
         .unwrap_or(10);
-    let http_client = reqwest::Client::builder()
+    let http_client = cortex_scout::core::http_client::builder()
         .timeout(std::time::Duration::from_secs(http_timeout))
         .connect_timeout(std::time::Duration::from_secs(connect_timeout))
+        .redirect(cortex_scout::scraping::domain_policy::redirect_policy())
         .build()?;
 
-    // Create application state
-    let mut state = AppState::new(http_client);
+    // Create application state; an invalid tool policy stops the server here.
+    let state = AppState::new(http_client)
+        .with_tool_registry(
+            cortex_scout::core::tools_registry::ToolRegistry::load_for_deployment()?,
+        )
+        .with_startup(tracker.clone());
 
-    // Initialize semantic memory (persistent by default; disable via CORTEX_SCOUT_MEMORY_DISABLED=1)
-    if let Some(lancedb_uri) = cortex_scout::core::config::lancedb_uri() {
-        if !lancedb_uri.contains("://") {
-            // Best-effort create the directory for local-path URIs.
-            let _ = tokio::fs::create_dir_all(&lancedb_uri).await;
-        }
-        info!("Initializing memory with LanceDB at: {}", lancedb_uri);
-        match cortex_scout::history::MemoryManager::new(&lancedb_uri).await {
-            Ok(memory) => {
-                state = state.with_memory(Arc::new(memory));
-                info!("Memory initialized successfully");
-            }
-            Err(e) => {
-                state.mark_memory_failed();
-                warn!(
-                    "Failed to initialize memory: {}. Continuing without memory feature.",
-                    e
-                );
-            }
+    let api_auth = ApiKeyAuth::from_env()?.map(Arc::new);
+
+    // Bind before the optional components initialize so liveness probes answer at once;
+    // the startup gate holds back everything else until `finish_startup` opens it.
+    let port: u16 = parse_port_from_args()
+        .or_else(port_from_env)
+        .unwrap_or(5000);
+    let bind_addr = format!("0.0.0.0:{}", port);
+    let listener = match tokio::net::TcpListener::bind(&bind_addr).await {
+        Ok(l) => l,
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+            anyhow::bail!(
+                "Address already in use: {}. Stop the existing process or run with --port {} (or set PORT/CORTEX_SCOUT_PORT).",
+                bind_addr,
+                port.saturating_add(1)
+            )
         }
-    } else {
-        info!("Semantic memory disabled (CORTEX_SCOUT_MEMORY_DISABLED=1)");
+        Err(e) => return Err(e.into()),
+    };
+    tracker.listener_bound();
+    info!("MCP Server listening on http://{}", bind_addr);
+
+    let gate = StartupGate::new(tracker);
+    let running: Arc<OnceLock<Arc<AppState>>> = Arc::default();
+    tokio::spawn(finish_startup(
+        state,
+        api_auth,
+        gate.clone(),
+        running.clone(),
+    ));
+
+    axum::serve(listener, gate.router())
+        .with_graceful_shutdown(shutdown_signal(running))
+        .await?;
+
+    Ok(())
+}
+
+/// Initialize the setup checklist, semantic memory and the proxy pool concurrently, then
+/// open the startup gate to the full router. Each of them is optional: one that fails or
+/// outlasts `CORTEX_SCOUT_STARTUP_TIMEOUT_SECS` leaves the server running without it.
+async fn finish_startup(
+    mut state: AppState,
+    api_auth: Option<Arc<ApiKeyAuth>>,
+    gate: StartupGate,
+    running: Arc<OnceLock<Arc<AppState>>>,
+) {
+    let tracker = state.startup.clone();
+    let timeout = cortex_scout::core::config::startup_timeout();
+    let (_, memory, proxy_manager) = tokio::join!(
+        tracker.run(startup::SETUP_CHECKS, timeout, run_setup_checks()),
+        tracker.run(startup::MEMORY, timeout, init_memory()),
+        tracker.run(startup::PROXIES, timeout, init_proxy_manager()),
+    );
+
+    match memory {
+        Some(memory) => state.install_memory(memory),
+        None if state.is_memory_pending() => state.mark_memory_failed(),
+        None => {}
+    }
+    if let Some(proxy_manager) = proxy_manager {
+        state = state.with_proxy_manager(proxy_manager);
     }
 
-    // Initialize proxy manager if ip.txt exists
-    let ip_list_path = env::var("IP_LIST_PATH").unwrap_or_else(|_| "ip.txt".to_string());
+    let state = Arc::new(state);
+    let _ = running.set(Arc::clone(&state));
+    cortex_scout::monitor::spawn_scheduler(Arc::clone(&state));
+    gate.open(build_router(state, api_auth));
+    tracker.finish();
+}
 
-    if tokio::fs::metadata(&ip_list_path).await.is_ok() {
-        info!("Loading proxy manager from IP list: {}", ip_list_path);
-        match cortex_scout::proxy_manager::ProxyManager::new(&ip_list_path).await {
-            Ok(proxy_manager) => {
-                let status = proxy_manager.get_status().await?;
-                state = state.with_proxy_manager(Arc::new(proxy_manager));
-                info!(
-                    "Proxy manager initialized: {} total proxies, {} enabled",
-                    status.total_proxies, status.enabled_proxies
-                );
-            }
-            Err(e) => {
-                warn!(
-                    "Failed to initialize proxy manager: {}. Continuing without proxy support.",
-                    e
-                );
-            }
+/// Pre-flight checklist (non-interactive).
+async fn run_setup_checks() -> Init<()> {
+    // The server already holds its port, so the conflict check would only find itself.
+    let options = cortex_scout::setup::SetupOptions {
+        skip: vec!["port_conflict".to_string()],
+        ..Default::default()
+    };
+    let report = cortex_scout::setup::check_all(options).await;
+    let summary = report.summarize_for_logs();
+    info!("{}", summary);
+    if report.has_failures() {
+        warn!("shadow-setup: startup checklist found failures; run with --setup for guided remediation");
+        report.print_action_required_blocks();
+        return Init::Failed(summary);
+    }
+    Init::Ready((), summary)
+}
+
+/// Semantic memory (persistent by default; disable via CORTEX_SCOUT_MEMORY_DISABLED=1).
+async fn init_memory() -> Init<Arc<cortex_scout::history::MemoryManager>> {
+    let Some(lancedb_uri) = cortex_scout::core::config::lancedb_uri() else {
+        info!("Semantic memory disabled (CORTEX_SCOUT_MEMORY_DISABLED=1)");
+        return Init::Skipped("Disabled (CORTEX_SCOUT_MEMORY_DISABLED=1)".to_string());
+    };
+    if !lancedb_uri.contains("://") {
+        // Best-effort create the directory for local-path URIs.
+        let _ = tokio::fs::create_dir_all(&lancedb_uri).await;
+    }
+    info!("Initializing memory with LanceDB at: {}", lancedb_uri);
+    match cortex_scout::history::MemoryManager::new(&lancedb_uri).await {
+        Ok(memory) => {
+            info!("Memory initialized successfully");
+            Init::Ready(Arc::new(memory), format!("LanceDB at {}", lancedb_uri))
         }
-    } else {
+        Err(e) => {
+            warn!(
+                "Failed to initialize memory: {}. Continuing without memory feature.",
+                e
+            );
+            Init::Failed(e.to_string())
+        }
+    }
+}
+
+/// Proxy manager, if the IP list (`IP_LIST_PATH`, default `ip.txt`) exists.
+async fn init_proxy_manager() -> Init<Arc<ProxyManager>> {
+    let ip_list_path = env::var("IP_LIST_PATH").unwrap_or_else(|_| "ip.txt".to_string());
+    if tokio::fs::metadata(&ip_list_path).await.is_err() {
         info!(
             "IP list not found at {}. Proxy feature disabled.",
             ip_list_path
         );
+        return Init::Skipped(format!("No IP list at {}", ip_list_path));
     }
 
-    let state = Arc::new(state);
+    info!("Loading proxy manager from IP list: {}", ip_list_path);
+    let loaded = match ProxyManager::new(&ip_list_path).await {
+        Ok(proxy_manager) => proxy_manager
+            .get_status()
+            .await
+            .map(|status| (proxy_manager, status)),
+        Err(e) => Err(e),
+    };
+    match loaded {
+        Ok((proxy_manager, status)) => {
+            let details = format!(
+                "{} total proxies, {} enabled",
+                status.total_proxies, status.enabled_proxies
+            );
+            info!("Proxy manager initialized: {}", details);
+            Init::Ready(Arc::new(proxy_manager), details)
+        }
+        Err(e) => {
+            warn!(
+                "Failed to initialize proxy manager: {}. Continuing without proxy support.",
+                e
+            );
+            Init::Failed(e.to_string())
+        }
+    }
+}
 
-    // Build router
-    let app = Router::new()
+fn build_router(state: Arc<AppState>, api_auth: Option<Arc<ApiKeyAuth>>) -> Router {
+    let cors = api_auth::cors_layer_from_env(api_auth.is_some());
+    let mut app = Router::new()
         .route("/", get(health_check))
         .route("/health", get(health_check))
+        .route("/health/live", get(health_check))
+        .route("/health/ready", get(readiness_handler))
+        .route("/debug/startup", get(startup_report_handler))
         .route("/.well-known/mcp/server-card.json", get(server_card))
         .route("/mcp", post(mcp_rpc_handler))
-        .route("/search", post(search_web_handler))
+        .route("/mcp/ws", get(mcp::ws::handler))
+        .route(
+            "/search",
+            get(search_web_get_handler).post(search_web_handler),
+        )
         .route("/search_structured", post(search_structured_handler))
-        .route("/scrape", post(scrape_url_handler))
+        .route(
+            "/scrape",
+            get(scrape_url_get_handler).post(scrape_url_handler),
+        )
+        .route("/scrape/stream", get(scrape_stream_handler))
+        .route("/scrape_batch", post(scrape_batch_handler))
+        .route(
+            "/history",
+            get(history_search_handler).delete(history_purge_handler),
+        )
+        .route("/crawl/{id}/status", get(crawl_status_handler))
+        .route("/setup", get(setup_status_handler))
+        .route("/metrics", get(metrics_handler))
+        .route("/engines/status", get(engine_status_handler))
+        .route("/engines/reset", post(engine_reset_handler))
+        .route("/proxy/status", get(proxy_status_handler))
+        .route("/proxy/rotate", post(proxy_rotate_handler))
+        .route("/proxy/enable", post(proxy_enable_handler))
+        .route("/proxy/disable", post(proxy_disable_handler))
         .route("/chat", post(chat_handler))
         .route("/mcp/tools", get(mcp::list_tools))
         .route("/mcp/call", post(mcp::call_tool))
-        .layer(CorsLayer::permissive())
+        .route("/openapi.json", get(openapi_json));
+    if cortex_scout::core::config::swagger_ui_enabled() {
+        app = app.route("/docs", get(swagger_ui));
+    }
+    if let Some(auth) = api_auth {
+        info!(
+            "API key authentication enabled ({} key(s)); {} stays open",
+            auth.key_count(),
+            api_auth::EXEMPT_PATHS.join(", ")
+        );
+        app = app.layer(axum::middleware::from_fn_with_state(
+            auth,
+            api_auth::require_api_key,
+        ));
+    }
+    app.layer(cors)
         .layer(TraceLayer::new_for_http())
-        .with_state(state.clone());
+        .layer(axum::middleware::from_fn(request_id::propagate))
+        .with_state(state)
+}
 
-    // Start server
-    let port: u16 = parse_port_from_args()
-        .or_else(port_from_env)
-        .unwrap_or(5000);
-    let bind_addr = format!("0.0.0.0:{}", port);
-    let listener = match tokio::net::TcpListener::bind(&bind_addr).await {
-        Ok(l) => l,
-        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
-            anyhow::bail!(
-                "Address already in use: {}. Stop the existing process or run with --port {} (or set PORT/CORTEX_SCOUT_PORT).",
-                bind_addr,
-                port.saturating_add(1)
-            )
-        }
-        Err(e) => return Err(e.into()),
-    };
-    info!("MCP Server listening on http://{}", bind_addr);
+/// OpenAPI 3.1 description of the HTTP routes, served at `GET /openapi.json`. Request and
+/// response schemas are derived from `core::types`; reports owned by other modules
+/// (engine, proxy, setup and crawl status) are documented as free-form objects.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Cortex Scout HTTP API",
+        description = "Search, scrape and crawl endpoints of the cortex-scout HTTP server. \
+Errors share one body shape, `ErrorResponse`, whose `code` names the error kind and picks \
+the HTTP status. When API keys are configured every route except `/health`, \
+`/health/live` and `/health/ready` requires one."
+    ),
+    paths(
+        health_check,
+        readiness_handler,
+        startup_report_handler,
+        server_card,
+        mcp_rpc_handler,
+        mcp::ws::handler,
+        search_web_handler,
+        search_web_get_handler,
+        search_structured_handler,
+        scrape_url_handler,
+        scrape_url_get_handler,
+        scrape_stream_handler,
+        scrape_batch_handler,
+        history_search_handler,
+        history_purge_handler,
+        crawl_status_handler,
+        setup_status_handler,
+        metrics_handler,
+        engine_status_handler,
+        engine_reset_handler,
+        proxy_status_handler,
+        proxy_rotate_handler,
+        proxy_enable_handler,
+        proxy_disable_handler,
+        chat_handler,
+        mcp::http::list_tools,
+        mcp::http::call_tool,
+        openapi_json
+    ),
+    components(schemas(ErrorResponse, ErrorKind)),
+    tags(
+        (name = "search", description = "Web search, search + scrape, and extractive answers"),
+        (name = "scrape", description = "Single, streamed and batch page extraction"),
+        (name = "crawl", description = "Saved crawl progress"),
+        (name = "history", description = "Search and scrape history"),
+        (name = "engines", description = "Search engine health"),
+        (name = "proxy", description = "Proxy pool control"),
+        (name = "mcp", description = "MCP over HTTP, WebSocket and the REST tool bridge"),
+        (name = "meta", description = "Health, readiness, metrics and this document")
+    )
+)]
+struct ApiDoc;
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal(state.clone()))
-        .await?;
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    tag = "meta",
+    responses((status = 200, description = "This OpenAPI document", body = serde_json::Value))
+)]
+async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
 
-    Ok(())
+/// Swagger UI for `/openapi.json`, loaded from a CDN. Only routed with
+/// `CORTEX_SCOUT_SWAGGER_UI=1`.
+async fn swagger_ui() -> axum::response::Html<&'static str> {
+    axum::response::Html(SWAGGER_UI_HTML)
 }
 
-async fn shutdown_signal(state: Arc<AppState>) {
+const SWAGGER_UI_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Cortex Scout HTTP API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
+
+async fn shutdown_signal(running: Arc<OnceLock<Arc<AppState>>>) {
     #[cfg(unix)]
     {
         use tokio::signal::unix::{signal, SignalKind};
@@ -217,19 +466,77 @@ async fn shutdown_signal(state: Arc<AppState>) {
         let _ = tokio::signal::ctrl_c().await;
     }
 
+    // Nothing to clean up when startup never finished.
+    let Some(state) = running.get() else {
+        return;
+    };
     if let Some(pool) = state.browser_pool.as_ref() {
         pool.shutdown().await;
     }
+    // A warm hitl_web_fetch browser must not outlive the server; don't wait long on a
+    // call still holding it.
+    let _ = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        cortex_scout::non_robot_search::close_warm_session(state),
+    )
+    .await;
 }
 
-async fn health_check() -> Json<serde_json::Value> {
+/// Liveness: the process is up and serving. Also answered at `/health` and `/`.
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "meta",
+    responses((status = 200, description = "Service name, version and semantic memory health", body = serde_json::Value))
+)]
+async fn health_check(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "status": "healthy",
         "service": "cortex-scout",
-        "version": env!("CARGO_PKG_VERSION")
+        "version": env!("CARGO_PKG_VERSION"),
+        "memory": state.memory_health()
     }))
 }
 
+/// Readiness: per-subsystem checks (search engines, browser, proxies, memory, outbound
+/// permits). Answered with 503 when a subsystem listed in `CORTEX_SCOUT_READY_HARD` fails.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "meta",
+    responses(
+        (status = 200, description = "Every hard subsystem is healthy", body = serde_json::Value),
+        (status = 503, description = "At least one hard subsystem failed", body = serde_json::Value)
+    )
+)]
+async fn readiness_handler(State(state): State<Arc<AppState>>) -> Response {
+    let report = readiness::check(&state, readiness::global()).await;
+    let code = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(report)).into_response()
+}
+
+/// Startup timing: when the listener bound and how long each optional component (setup
+/// checks, semantic memory, proxies) took, with those that failed or timed out.
+#[utoipa::path(
+    get,
+    path = "/debug/startup",
+    tag = "meta",
+    responses((status = 200, description = "Per-component startup timing", body = serde_json::Value))
+)]
+async fn startup_report_handler(State(state): State<Arc<AppState>>) -> Json<StartupReport> {
+    Json(state.startup.report())
+}
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/mcp/server-card.json",
+    tag = "mcp",
+    responses((status = 200, description = "MCP server card: server info, public tools and recent resources", body = serde_json::Value))
+)]
 async fn server_card(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
     let tools: Vec<serde_json::Value> = state
         .tool_registry
@@ -242,6 +549,11 @@ async fn server_card(State(state): State<Arc<AppState>>) -> Json<serde_json::Val
             })
         })
         .collect();
+    // First page of `resources/list`: recent cached scrapes and search history.
+    let resources = mcp::resources::list(&state, None)
+        .await
+        .map(|page| page.resources)
+        .unwrap_or_default();
 
     Json(serde_json::json!({
         "serverInfo": {
@@ -249,135 +561,775 @@ async fn server_card(State(state): State<Arc<AppState>>) -> Json<serde_json::Val
             "version": env!("CARGO_PKG_VERSION")
         },
         "tools": tools,
-        "resources": [],
+        "resources": resources,
         "prompts": []
     }))
 }
 
+/// Streamable HTTP MCP transport. Notifications (no `id`) are acknowledged with
+/// 202 Accepted and no body.
+#[utoipa::path(
+    post,
+    path = "/mcp",
+    tag = "mcp",
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, description = "JSON-RPC 2.0 response", body = serde_json::Value),
+        (status = 202, description = "Notification accepted; no body")
+    )
+)]
 async fn mcp_rpc_handler(
     State(state): State<Arc<AppState>>,
     Json(request): Json<serde_json::Value>,
 ) -> Response {
-    let id = request.get("id").cloned();
-    let method = request
-        .get("method")
-        .and_then(|m| m.as_str())
-        .unwrap_or_default();
+    match mcp::jsonrpc::handle_message(state, request).await {
+        Some(response) => Json(response).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
 
-    // JSON-RPC notifications have no "id" — acknowledge with 202 Accepted, no body.
-    if id.is_none() {
-        return StatusCode::ACCEPTED.into_response();
-    }
-    let id = id.unwrap();
-
-    match method {
-        "initialize" => Json(serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": id,
-            "result": {
-                "protocolVersion": "2024-11-05",
-                "capabilities": {
-                    "tools": {}
-                },
-                "serverInfo": {
-                    "name": "Cortex Scout",
-                    "version": env!("CARGO_PKG_VERSION")
-                }
+#[utoipa::path(
+    post,
+    path = "/search",
+    tag = "search",
+    request_body = SearchRequest,
+    responses(
+        (status = 200, description = "Merged, reranked results. With `format=opensearch` or `format=csv` the body is that rendering instead (`application/x-suggestions+json` / `text/csv`). Carries `ETag` and `Cache-Control`.", body = SearchResponse),
+        (status = 304, description = "`If-None-Match` names the ETag of a result that is still cached"),
+        ErrorResponses
+    )
+)]
+async fn search_web_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<SearchRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    search_web_response(&state, &headers, request).await
+}
+
+/// `POST /search` with the request in the query string, so HTTP caches can key on the URL.
+#[utoipa::path(
+    get,
+    path = "/search",
+    tag = "search",
+    params(SearchRequest),
+    responses(
+        (status = 200, description = "As `POST /search`", body = SearchResponse),
+        (status = 304, description = "`If-None-Match` names the ETag of a result that is still cached"),
+        ErrorResponses
+    )
+)]
+async fn search_web_get_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(request): Query<SearchRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    search_web_response(&state, &headers, request).await
+}
+
+async fn search_web_response(
+    state: &Arc<AppState>,
+    headers: &HeaderMap,
+    request: SearchRequest,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let format = match request.format.as_deref() {
+        None => search::SearchOutputFormat::Json,
+        Some(raw) => search::SearchOutputFormat::parse_str(raw).ok_or_else(|| {
+            ScoutError::invalid_input(format!(
+                "Unknown format '{}': expected json, opensearch or csv",
+                raw
+            ))
+            .into_http()
+        })?,
+    };
+    let include_fields: Vec<String> = request
+        .include_fields
+        .as_deref()
+        .map(|raw| raw.split(',').map(str::to_string).collect())
+        .unwrap_or_default();
+    let include_fields =
+        search::shape::parse_include_fields(&include_fields).map_err(ScoutError::into_http)?;
+    let key = http_cache::request_key(Endpoint::Search, &request);
+    if let Some(response) = http_cache::not_modified(state, Endpoint::Search, &key, headers).await {
+        return Ok(response);
+    }
+    let overrides =
+        (request.max_results.is_some() || request.snippet_max_chars.is_some()).then(|| {
+            search::SearchParamOverrides {
+                max_results: request.max_results,
+                snippet_max_chars: request.snippet_max_chars,
+                ..Default::default()
+            }
+        });
+    match search::search_web_with_params(state, &request.query, overrides).await {
+        Ok((mut results, extras)) => {
+            // Empty result sets are not always cached, so a repeat may well differ.
+            let fresh_for = (!results.is_empty()).then(|| {
+                std::time::Duration::from_secs(cortex_scout::core::config::SEARCH_CACHE_TTL_SECS)
+            });
+            if request.highlights && matches!(format, search::SearchOutputFormat::Json) {
+                let terms = search::highlight_terms(&request.query, extras.query_rewrite.as_ref());
+                search::annotate_highlights(&mut results, &terms);
             }
-        }))
-        .into_response(),
-
-        "tools/list" => {
-            let tools = mcp::http::list_tools_for_state(state.as_ref());
-            Json(serde_json::json!({
-                "jsonrpc": "2.0",
-                "id": id,
-                "result": tools
-            }))
-            .into_response()
+            let shaped = !include_fields.is_empty() || request.max_response_bytes.is_some();
+            let body = match format {
+                search::SearchOutputFormat::Json if !shaped => {
+                    serde_json::to_vec(&SearchResponse { results }).map_err(|e| {
+                        ScoutError::internal(format!("Failed to encode results: {}", e)).into_http()
+                    })?
+                }
+                _ => {
+                    let render = |kept: &[SearchResult], dropped: usize| match format {
+                        search::SearchOutputFormat::Json => {
+                            search::shape::json_body(kept, &include_fields, dropped).to_string()
+                        }
+                        _ => format.render(&request.query, kept),
+                    };
+                    match request.max_response_bytes {
+                        Some(max_bytes) => {
+                            search::shape::fit_to_bytes(&mut results, max_bytes, render).0
+                        }
+                        None => render(&results, 0),
+                    }
+                    .into_bytes()
+                }
+            };
+            Ok(http_cache::respond(
+                state,
+                Endpoint::Search,
+                &key,
+                format.content_type(),
+                body,
+                fresh_for,
+            )
+            .await)
         }
+        Err(e) => {
+            error!("Search error: {}", e);
+            Err(ScoutError::classify(&e).into_http())
+        }
+    }
+}
 
-        "tools/call" => {
-            let params = request.get("params").cloned().unwrap_or_default();
-            let tool_name = params
-                .get("name")
-                .and_then(|n| n.as_str())
-                .unwrap_or_default()
-                .to_string();
-            let arguments = params.get("arguments").cloned().unwrap_or_default();
-
-            let call_req = mcp::http::McpCallRequest {
-                name: tool_name,
-                arguments,
-            };
+#[utoipa::path(
+    post,
+    path = "/scrape",
+    tag = "scrape",
+    request_body = ScrapeRequest,
+    responses(
+        (status = 200, description = "The extracted page. Carries `ETag` and `Cache-Control`.", body = ScrapeResponse),
+        (status = 304, description = "`If-None-Match` names the ETag of a page that is still cached"),
+        ErrorResponses
+    )
+)]
+async fn scrape_url_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ScrapeRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    scrape_url_response(&state, &headers, request).await
+}
 
-            match mcp::http::call_tool_inner(state, call_req).await {
-                Ok(result) => Json(serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "id": id,
-                    "result": result
-                }))
-                .into_response(),
-                Err((_, Json(err))) => Json(serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "id": id,
-                    "error": {
-                        "code": -32000,
-                        "message": err.error
-                    }
-                }))
-                .into_response(),
+/// `POST /scrape` with the request in the query string, so HTTP caches can key on the URL.
+/// Options that carry credentials or have side effects are body-only.
+#[utoipa::path(
+    get,
+    path = "/scrape",
+    tag = "scrape",
+    params(ScrapeQuery),
+    responses(
+        (status = 200, description = "As `POST /scrape`", body = ScrapeResponse),
+        (status = 304, description = "`If-None-Match` names the ETag of a page that is still cached"),
+        ErrorResponses
+    )
+)]
+async fn scrape_url_get_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ScrapeQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    scrape_url_response(&state, &headers, query.into()).await
+}
+
+/// Whether a scrape may be answered from its remembered ETag: requests with credentials,
+/// custom headers, a screenshot or cookie-jar side effects always run.
+fn scrape_is_cacheable(request: &ScrapeRequest) -> bool {
+    request.auth.is_none()
+        && request.headers.as_ref().is_none_or(|h| h.is_empty())
+        && !request.screenshot
+        && !request.persist_session
+        && !request.clear_session
+}
+
+async fn scrape_url_response(
+    state: &Arc<AppState>,
+    headers: &HeaderMap,
+    request: ScrapeRequest,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let cacheable = scrape_is_cacheable(&request);
+    let key = http_cache::request_key(Endpoint::Scrape, &request);
+    if cacheable {
+        if let Some(response) =
+            http_cache::not_modified(state, Endpoint::Scrape, &key, headers).await
+        {
+            return Ok(response);
+        }
+    }
+    let request_headers =
+        RequestHeaders::new(request.headers.unwrap_or_default(), request.user_agent).map_err(
+            |e| ScoutError::invalid_input(format!("Invalid headers: {}", e)).into_http(),
+        )?;
+    let follow_pagination = if request.follow_pagination {
+        let max_pages = request.max_pages.map(|n| n as u64);
+        Some(pagination::max_pages(max_pages).map_err(ScoutError::into_http)?)
+    } else {
+        None
+    };
+    let options = scrape::ScrapeUrlOptions {
+        respect_robots: request.respect_robots,
+        parse_feeds: request.parse_feeds,
+        max_attempts: request.max_attempts,
+        auto_escalate: request.auto_escalate,
+        persist_session: request.persist_session,
+        clear_session: request.clear_session,
+        auth: request.auth,
+        headers: request_headers,
+        links_only: request.links_only,
+        links_detail: request.links_detail,
+        max_links: request.max_links,
+        screenshot: request.screenshot,
+        screenshot_full_page: request.screenshot_full_page,
+        screenshot_inline: request.screenshot_inline,
+        include_raw_html: request.include_raw_html,
+        prefer_language: request.prefer_language,
+        sections: request.include_sections.then(|| {
+            SectionOptions::new(request.section_target_words, request.section_overlap_words)
+        }),
+        follow_pagination,
+        ..Default::default()
+    };
+    match scrape::scrape_url_full(state, &request.url, options).await {
+        Ok(content) => match scrape::block_error(&content) {
+            Some(blocked) => {
+                warn!("Scrape blocked: {}", blocked);
+                Err(blocked.into_http())
+            }
+            None => {
+                let fresh_for = cacheable.then(|| http_cache::scrape_freshness(&content.timestamp));
+                let body = serde_json::to_vec(&content).map_err(|e| {
+                    ScoutError::internal(format!("Failed to encode page: {}", e)).into_http()
+                })?;
+                Ok(http_cache::respond(
+                    state,
+                    Endpoint::Scrape,
+                    &key,
+                    "application/json",
+                    body,
+                    fresh_for,
+                )
+                .await)
             }
+        },
+        Err(e) => {
+            error!("Scrape error: {}", e);
+            Err(ScoutError::classify(&e).into_http())
+        }
+    }
+}
+
+/// SSE variant of `/scrape` for clients with small message limits: one `meta` event,
+/// `content` events carrying ~16KB slices of `clean_content`, then `done`.
+#[utoipa::path(
+    get,
+    path = "/scrape/stream",
+    tag = "scrape",
+    params(ScrapeStreamQuery),
+    responses(
+        (status = 200, description = "Server-sent events: one `meta`, `content` slices of `clean_content`, then `done`", content_type = "text/event-stream", body = String),
+        ErrorResponses
+    )
+)]
+async fn scrape_stream_handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ScrapeStreamQuery>,
+) -> Result<
+    Sse<impl futures::Stream<Item = Result<Event, Infallible>>>,
+    (StatusCode, Json<ErrorResponse>),
+> {
+    let mut content = scrape::scrape_url(&state, &query.url).await.map_err(|e| {
+        error!("Scrape stream error: {}", e);
+        ScoutError::classify(&e).into_http()
+    })?;
+
+    match query.max_chars {
+        Some(max_chars) => {
+            cortex_scout::content_quality::apply_scrape_content_limit(&mut content, max_chars, true)
         }
+        None => content.actual_chars = content.clean_content.len(),
+    }
+
+    let chunks = cortex_scout::content_quality::split_content_chunks(
+        &content.clean_content,
+        cortex_scout::content_quality::CONTENT_CHUNK_BYTES,
+    );
+    let mut events = Vec::with_capacity(chunks.len() + 2);
+    events.push(
+        Event::default().event("meta").data(
+            serde_json::json!({
+                "url": content.url,
+                "final_url": content.final_url,
+                "redirect_chain": content.redirect_chain,
+                "provenance": content.provenance,
+                "title": content.title,
+                "status_code": content.status_code,
+                "word_count": content.word_count,
+                "warnings": content.warnings,
+                "truncated": content.truncated,
+                "actual_chars": content.actual_chars,
+                "max_chars_limit": content.max_chars_limit,
+                "chunks": chunks.len(),
+            })
+            .to_string(),
+        ),
+    );
+    for (index, chunk) in chunks.iter().enumerate() {
+        events.push(
+            Event::default()
+                .event("content")
+                .data(serde_json::json!({ "index": index, "text": chunk }).to_string()),
+        );
+    }
+    events.push(
+        Event::default()
+            .event("done")
+            .data(serde_json::json!({ "chunks": chunks.len() }).to_string()),
+    );
 
-        _ => Json(serde_json::json!({
-            "jsonrpc": "2.0",
-            "id": id,
-            "error": {
-                "code": -32601,
-                "message": format!("Method not found: {}", method)
+    Ok(Sse::new(futures::stream::iter(
+        events.into_iter().map(Ok::<_, Infallible>),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/scrape_batch",
+    tag = "scrape",
+    request_body = ScrapeBatchRequest,
+    responses(
+        (status = 200, description = "Per-URL outcomes; failures are reported inline", body = ScrapeBatchResponse),
+        ErrorResponses
+    )
+)]
+async fn scrape_batch_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ScrapeBatchRequest>,
+) -> Result<Json<ScrapeBatchResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if request.urls.is_empty() {
+        return Err(ScoutError::invalid_input("urls array cannot be empty").into_http());
+    }
+    if request
+        .min_quality
+        .is_some_and(|q| !(0.0..=1.0).contains(&q))
+    {
+        return Err(ScoutError::invalid_input("min_quality must be between 0 and 1").into_http());
+    }
+    let semantic_dedup = if request.semantic_dedup {
+        let dedup = semantic_dedup::SemanticDedup::new(
+            request.dedup_threshold,
+            request.dedup_scope.as_deref(),
+        )
+        .map_err(ScoutError::into_http)?;
+        let memory = semantic_dedup::memory(&state)
+            .await
+            .map_err(ScoutError::into_http)?;
+        Some((dedup, memory))
+    } else {
+        None
+    };
+
+    if request.clear_session {
+        for url in &request.urls {
+            state.cookie_jars.clear(url);
+        }
+    }
+
+    // Per-URL failures (including budget exhaustion) are reported inline in `results`;
+    // every scrape still goes through the shared outbound semaphore and scrape cache.
+    match batch_scrape::scrape_batch_with_budget(
+        &state,
+        request.urls,
+        request.max_concurrent.unwrap_or(4),
+        request.max_chars,
+        scrape::ScrapeUrlOptions {
+            domain_rate_limit: request.domain_rate_limit,
+            persist_session: request.persist_session,
+            auth: request.auth,
+            ..Default::default()
+        },
+        request.budget_secs.map(std::time::Duration::from_secs),
+    )
+    .await
+    {
+        Ok(mut response) => {
+            if let Some(min_quality) = request.min_quality {
+                batch_scrape::apply_min_quality(&mut response, min_quality);
+            }
+            if let Some((dedup, memory)) = &semantic_dedup {
+                dedup
+                    .apply_to_batch(memory, &mut response)
+                    .await
+                    .map_err(|e| {
+                        ScoutError::classify(&e)
+                            .context("Semantic dedup failed")
+                            .into_http()
+                    })?;
             }
-        }))
-        .into_response(),
+            Ok(Json(response))
+        }
+        Err(e) => {
+            error!("Batch scrape error: {}", e);
+            Err(ScoutError::classify(&e).into_http())
+        }
     }
 }
 
-async fn search_web_handler(
+#[utoipa::path(
+    get,
+    path = "/history",
+    tag = "history",
+    params(HistoryQuery),
+    responses(
+        (status = 200, description = "Matching history entries", body = HistorySearchResponse),
+        ErrorResponses
+    )
+)]
+async fn history_search_handler(
     State(state): State<Arc<AppState>>,
-    Json(request): Json<SearchRequest>,
-) -> Result<Json<SearchResponse>, (StatusCode, Json<ErrorResponse>)> {
-    match search::search_web(&state, &request.query).await {
-        Ok((results, _extras)) => Ok(Json(SearchResponse { results })),
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<HistorySearchResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let params = history_search::HistorySearchParams::from_query(&query, chrono::Utc::now())
+        .map_err(ScoutError::into_http)?;
+    match history_search::search(&state, params).await {
+        Ok(response) => Ok(Json(response)),
         Err(e) => {
-            error!("Search error: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                }),
-            ))
+            error!("History search error: {}", e);
+            Err(ScoutError::classify(&e).into_http())
         }
     }
 }
 
-async fn scrape_url_handler(
+#[utoipa::path(
+    delete,
+    path = "/history",
+    tag = "history",
+    params(HistoryPurgeQuery),
+    responses(
+        (status = 200, description = "How many entries were deleted", body = HistoryPurgeResponse),
+        ErrorResponses
+    )
+)]
+async fn history_purge_handler(
     State(state): State<Arc<AppState>>,
-    Json(request): Json<ScrapeRequest>,
-) -> Result<Json<ScrapeResponse>, (StatusCode, Json<ErrorResponse>)> {
-    match scrape::scrape_url(&state, &request.url).await {
-        Ok(content) => Ok(Json(content)),
+    Query(query): Query<HistoryPurgeQuery>,
+) -> Result<Json<HistoryPurgeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    match history_search::purge(&state, query.older_than_days).await {
+        Ok(response) => Ok(Json(response)),
         Err(e) => {
-            error!("Scrape error: {}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: e.to_string(),
-                }),
+            error!("History purge error: {}", e);
+            Err(ScoutError::classify(&e).into_http())
+        }
+    }
+}
+
+/// Progress of a crawl saved under `id` (running, completed or failed).
+#[utoipa::path(
+    get,
+    path = "/crawl/{id}/status",
+    tag = "crawl",
+    params(("id" = String, Path, description = "Crawl id returned by `crawl_website`")),
+    responses(
+        (status = 200, description = "Progress of the saved crawl", body = serde_json::Value),
+        ErrorResponses
+    )
+)]
+async fn crawl_status_handler(
+    Path(id): Path<String>,
+) -> Result<Json<crawl_state::CrawlStatusReport>, (StatusCode, Json<ErrorResponse>)> {
+    let root = crawl_state::default_crawl_dir().ok_or_else(|| {
+        ScoutError::unavailable("No crawl state directory (set CORTEX_SCOUT_CRAWL_DIR or HOME)")
+            .into_http()
+    })?;
+    match crawl_state::crawl_status(&root, &id).await {
+        Ok(report) => Ok(Json(report)),
+        Err(e) => Err(ScoutError::classify(&e).into_http()),
+    }
+}
+
+/// Pre-flight checklist as JSON for readiness probes. Reports with `status: fail` are
+/// answered with 503. Results are cached per check selection for
+/// `CORTEX_SCOUT_SETUP_CACHE_SECS` so frequent probes do not repeat the ping/HTTPS checks.
+#[utoipa::path(
+    get,
+    path = "/setup",
+    tag = "meta",
+    params(SetupQuery),
+    responses(
+        (status = 200, description = "Pre-flight checklist; no check failed", body = serde_json::Value),
+        (status = 503, description = "Pre-flight checklist with at least one failed check", body = serde_json::Value),
+        ErrorResponses
+    )
+)]
+async fn setup_status_handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SetupQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let only = query.check.as_deref().map(|raw| {
+        let mut ids: Vec<String> = raw
+            .split(',')
+            .map(|id| id.trim().to_ascii_lowercase())
+            .filter(|id| !id.is_empty())
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    });
+    let key = only
+        .as_ref()
+        .map(|ids| ids.join(","))
+        .unwrap_or_else(|| "*".to_string());
+    let options = setup::SetupOptions {
+        // The server itself holds its port, so the conflict check only runs when asked for.
+        skip: if only.is_none() {
+            vec!["port_conflict".to_string()]
+        } else {
+            Vec::new()
+        },
+        only: only.clone(),
+        ..Default::default()
+    };
+    let run = async move { setup::SetupStatus::from_report(setup::check_all(options).await) };
+    let status = if cortex_scout::core::config::setup_cache_ttl_secs() == 0 {
+        run.await
+    } else {
+        state.setup_cache.get_with(key, run).await
+    };
+
+    if let Some(only) = &only {
+        let unknown: Vec<&str> = only
+            .iter()
+            .filter(|id| !status.report.checks.iter().any(|c| &c.id == *id))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(ScoutError::invalid_input(format!(
+                "Unknown setup check(s): {}",
+                unknown.join(", ")
             ))
+            .into_http());
         }
     }
+
+    let code = if status.status == setup::CheckStatus::Fail {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    Ok((code, Json(status)).into_response())
 }
 
+/// Prometheus text exposition of the tool, engine, cache and browser metrics. Disabled
+/// (404) unless `CORTEX_SCOUT_METRICS` is set, since it reveals traffic patterns.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "meta",
+    responses(
+        (status = 200, description = "Prometheus text exposition (only with `CORTEX_SCOUT_METRICS=1`)", content_type = "text/plain", body = String),
+        ErrorResponses
+    )
+)]
+async fn metrics_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    if !cortex_scout::core::config::metrics_enabled() {
+        return Err(ScoutError::not_found(
+            "Metrics are disabled. Set CORTEX_SCOUT_METRICS=1 to enable GET /metrics.",
+        )
+        .into_http());
+    }
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        state.metrics.render(),
+    )
+        .into_response())
+}
+
+fn engine_status_report(state: &AppState) -> EngineStatusReport {
+    let known: Vec<&str> = search::engines::REGISTRY.iter().map(|e| e.name()).collect();
+    state.engine_stats.report(&known)
+}
+
+#[utoipa::path(
+    get,
+    path = "/engines/status",
+    tag = "engines",
+    responses((status = 200, description = "Per-engine health and statistics", body = serde_json::Value))
+)]
+async fn engine_status_handler(State(state): State<Arc<AppState>>) -> Json<EngineStatusReport> {
+    Json(engine_status_report(&state))
+}
+
+#[utoipa::path(
+    post,
+    path = "/engines/reset",
+    tag = "engines",
+    request_body = EngineResetRequest,
+    responses(
+        (status = 200, description = "Engine status after the reset", body = serde_json::Value),
+        ErrorResponses
+    )
+)]
+async fn engine_reset_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<EngineResetRequest>,
+) -> Result<Json<EngineStatusReport>, (StatusCode, Json<ErrorResponse>)> {
+    let target = match request.engine.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => match search::engines::lookup(name) {
+            Some(adapter) => Some(adapter.name()),
+            None => {
+                return Err(
+                    ScoutError::not_found(format!("Unknown search engine: {}", name)).into_http(),
+                )
+            }
+        },
+        _ => None,
+    };
+    state.engine_stats.reset(target);
+    Ok(Json(engine_status_report(&state)))
+}
+
+fn require_proxy_manager(
+    state: &AppState,
+) -> Result<&Arc<ProxyManager>, (StatusCode, Json<ErrorResponse>)> {
+    state.proxy_manager.as_ref().ok_or_else(|| {
+        ScoutError::unavailable("Proxy manager not available. Provide IP_LIST_PATH (default: ip.txt) to enable proxy support.").into_http()
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/proxy/status",
+    tag = "proxy",
+    responses(
+        (status = 200, description = "Proxy pool report", body = serde_json::Value),
+        ErrorResponses
+    )
+)]
+async fn proxy_status_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ProxyPoolReport>, (StatusCode, Json<ErrorResponse>)> {
+    let manager = require_proxy_manager(&state)?;
+    manager
+        .pool_report()
+        .await
+        .map(Json)
+        .map_err(|e| ScoutError::classify(&e).into_http())
+}
+
+#[utoipa::path(
+    post,
+    path = "/proxy/rotate",
+    tag = "proxy",
+    responses(
+        (status = 200, description = "The newly selected proxy", body = ProxyRotateResponse),
+        ErrorResponses
+    )
+)]
+async fn proxy_rotate_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ProxyRotateResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let manager = require_proxy_manager(&state)?;
+    match manager.switch_to_best_proxy().await {
+        Ok(proxy_url) => Ok(Json(ProxyRotateResponse {
+            current_proxy: cortex_scout::mcp_tooling::format_proxy_display(&proxy_url),
+        })),
+        // Every proxy is disabled or cooling down after failures.
+        Err(e) => Err(ScoutError::unavailable(e.to_string()).into_http()),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/proxy/enable",
+    tag = "proxy",
+    request_body = ProxyToggleRequest,
+    responses(
+        (status = 200, description = "Proxy pool report after the change", body = serde_json::Value),
+        ErrorResponses
+    )
+)]
+async fn proxy_enable_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ProxyToggleRequest>,
+) -> Result<Json<ProxyPoolReport>, (StatusCode, Json<ErrorResponse>)> {
+    set_proxy_enabled(&state, &request.proxy_url, true).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/proxy/disable",
+    tag = "proxy",
+    request_body = ProxyToggleRequest,
+    responses(
+        (status = 200, description = "Proxy pool report after the change", body = serde_json::Value),
+        ErrorResponses
+    )
+)]
+async fn proxy_disable_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ProxyToggleRequest>,
+) -> Result<Json<ProxyPoolReport>, (StatusCode, Json<ErrorResponse>)> {
+    set_proxy_enabled(&state, &request.proxy_url, false).await
+}
+
+async fn set_proxy_enabled(
+    state: &AppState,
+    proxy_url: &str,
+    enabled: bool,
+) -> Result<Json<ProxyPoolReport>, (StatusCode, Json<ErrorResponse>)> {
+    let manager = require_proxy_manager(state)?;
+    let internal_error = |e: anyhow::Error| ScoutError::classify(&e).into_http();
+    if !manager.has_proxy(proxy_url).await {
+        return Err(ScoutError::not_found(format!(
+            "Proxy URL not found in registry: {}",
+            proxy_url
+        ))
+        .into_http());
+    }
+    manager
+        .set_proxy_enabled(proxy_url, enabled)
+        .await
+        .map_err(internal_error)?;
+    manager
+        .pool_report()
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+#[utoipa::path(
+    post,
+    path = "/search_structured",
+    tag = "search",
+    request_body = SearchStructuredRequest,
+    responses(
+        (status = 200, description = "Search results plus the scraped top pages", body = SearchStructuredResponse),
+        ErrorResponses
+    )
+)]
 async fn search_structured_handler(
     State(state): State<Arc<AppState>>,
     Json(request): Json<SearchStructuredRequest>,
@@ -385,40 +1337,81 @@ async fn search_structured_handler(
     let (results, _extras) = search::search_web(&state, &request.query)
         .await
         .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Search failed: {}", e),
-                }),
-            )
+            ScoutError::classify(&e)
+                .context("Search failed")
+                .into_http()
         })?;
 
+    let quality_mode = match request.quality_mode.as_deref() {
+        None => None,
+        Some(raw) => Some(QualityMode::parse_str(raw).ok_or_else(|| {
+            ScoutError::invalid_input(
+                "Invalid quality_mode. Allowed values: balanced, aggressive, high",
+            )
+            .into_http()
+        })?),
+    };
+
     let top_n = request.top_n.unwrap_or(3);
     let to_scrape: Vec<String> = results.iter().take(top_n).map(|r| r.url.clone()).collect();
 
-    let mut scraped_content = Vec::new();
+    // Bound concurrent scrapes; each still goes through the scrape cache.
+    let limit = Arc::new(tokio::sync::Semaphore::new(
+        request.concurrency.unwrap_or(3).clamp(1, 8),
+    ));
+    let use_proxy = request.use_proxy;
     let mut tasks = Vec::new();
     for url in to_scrape {
         let state_cloned = Arc::clone(&state);
-        tasks.push(tokio::spawn(async move {
-            (url.clone(), scrape::scrape_url(&state_cloned, &url).await)
-        }));
+        let limit = Arc::clone(&limit);
+        tasks.push((
+            url.clone(),
+            tokio::spawn(async move {
+                let _permit = limit.acquire_owned().await.expect("semaphore closed");
+                scrape::scrape_url_with_options(&state_cloned, &url, use_proxy, quality_mode).await
+            }),
+        ));
     }
 
-    for task in tasks {
-        match task.await {
-            Ok((_, Ok(content))) => scraped_content.push(content),
-            Ok((url, Err(e))) => warn!("Structured scrape failed for {}: {}", url, e),
-            Err(e) => warn!("Structured scrape task join error: {}", e),
-        }
+    let mut scraped_content = Vec::new();
+    let mut failed_urls = Vec::new();
+    for (url, task) in tasks {
+        let error = match task.await {
+            Ok(Ok(mut content)) => {
+                if let Some(max_chars) = request.max_chars {
+                    cortex_scout::content_quality::apply_scrape_content_limit(
+                        &mut content,
+                        max_chars,
+                        true,
+                    );
+                }
+                scraped_content.push(content);
+                continue;
+            }
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => format!("scrape task failed: {}", e),
+        };
+        warn!("Structured scrape failed for {}: {}", url, error);
+        failed_urls.push(FailedUrl { url, error });
     }
 
     Ok(Json(SearchStructuredResponse {
         results,
         scraped_content,
+        failed_urls,
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/chat",
+    tag = "search",
+    request_body = ChatRequest,
+    responses(
+        (status = 200, description = "Extractive answer with the results and pages it was built from", body = ChatResponse),
+        ErrorResponses
+    )
+)]
 async fn chat_handler(
     State(state): State<Arc<AppState>>,
     Json(request): Json<ChatRequest>,
@@ -429,12 +1422,9 @@ async fn chat_handler(
         Ok((results, _extras)) => results,
         Err(e) => {
             error!("Search failed: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Search failed: {}", e),
-                }),
-            ));
+            return Err(ScoutError::classify(&e)
+                .context("Search failed")
+                .into_http());
         }
     };
 
@@ -471,8 +1461,20 @@ async fn chat_handler(
         }
     }
 
-    // Step 3: Generate response based on scraped content
-    let response_text = if scraped_content.is_empty() {
+    // Step 3: Answer from the scraped pages' most relevant sentences
+    let max_sentences = request
+        .max_sentences
+        .unwrap_or(extractive::DEFAULT_MAX_SENTENCES)
+        .clamp(1, 50);
+    let answer = extractive::extractive_answer(
+        &request.query,
+        &scraped_content,
+        max_sentences,
+        request.include_sources.unwrap_or(true),
+    );
+    let response_text = if let Some(answer) = answer {
+        answer
+    } else if scraped_content.is_empty() {
         format!("I found {} search results for '{}', but couldn't scrape any content. Here are the URLs:\n{}", 
             search_results.len(),
             request.query,
@@ -507,3 +1509,175 @@ async fn chat_handler(
         scraped_content,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn openapi_document_describes_search_and_scrape() {
+        let json = serde_json::to_string(&ApiDoc::openapi()).expect("document serializes");
+        let doc: serde_json::Value = serde_json::from_str(&json).expect("document parses");
+
+        for (path, schema) in [("/search", "SearchRequest"), ("/scrape", "ScrapeRequest")] {
+            let body = &doc["paths"][path]["post"]["requestBody"];
+            assert_eq!(
+                body["content"]["application/json"]["schema"]["$ref"],
+                format!("#/components/schemas/{}", schema),
+                "{} request body",
+                path
+            );
+            assert!(
+                doc["paths"][path]["post"]["responses"]["400"].is_object(),
+                "{} documents invalid_input",
+                path
+            );
+        }
+
+        let schemas = &doc["components"]["schemas"];
+        let required = |name: &str| -> Vec<String> {
+            serde_json::from_value(schemas[name]["required"].clone()).unwrap_or_default()
+        };
+        assert_eq!(required("SearchRequest"), vec!["query".to_string()]);
+        assert_eq!(required("ScrapeRequest"), vec!["url".to_string()]);
+        for field in ["max_chars", "quality_mode", "use_proxy"] {
+            assert!(
+                schemas["SearchStructuredRequest"]["properties"][field].is_object(),
+                "SearchStructuredRequest.{}",
+                field
+            );
+        }
+        assert!(!required("SearchStructuredRequest").contains(&"max_chars".to_string()));
+        assert!(schemas["ErrorKind"]["enum"]
+            .as_array()
+            .is_some_and(|codes| codes.iter().any(|c| c == "rate_limited")));
+        assert!(doc["paths"]["/crawl/{id}/status"]["get"].is_object());
+    }
+
+    #[derive(Default)]
+    struct CountingSearch {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl search::SearchService for CountingSearch {
+        async fn search(
+            &self,
+            _state: &Arc<AppState>,
+            _query: &str,
+            _overrides: Option<search::SearchParamOverrides>,
+        ) -> anyhow::Result<search::SearchExecutionOutcome> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(search::SearchExecutionOutcome {
+                results: vec![SearchResult {
+                    url: "https://doc.rust-lang.org/book/".to_string(),
+                    title: "The Rust Programming Language".to_string(),
+                    content: "An introductory book about the Rust programming language."
+                        .to_string(),
+                    ..Default::default()
+                }],
+                extras: Default::default(),
+            })
+        }
+    }
+
+    fn if_none_match(etag: HeaderValue) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, etag);
+        headers
+    }
+
+    #[tokio::test]
+    async fn repeated_search_with_etag_skips_the_engines() {
+        let engine = Arc::new(CountingSearch::default());
+        let mut state = AppState::new(reqwest::Client::new());
+        state.search_service = engine.clone();
+        let state = Arc::new(state);
+        let request = || SearchRequest {
+            query: "rust book".to_string(),
+            max_results: None,
+            format: None,
+            highlights: false,
+            snippet_max_chars: None,
+            include_fields: None,
+            max_response_bytes: None,
+        };
+
+        let first = search_web_handler(State(state.clone()), HeaderMap::new(), Json(request()))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(first.headers()[header::CACHE_CONTROL], "no-store");
+        let etag = first.headers()[header::ETAG].clone();
+        let calls = engine.calls.load(Ordering::SeqCst);
+        assert!(calls > 0);
+
+        // With the result evicted, only the remembered ETag can answer without the engines.
+        state.search_cache.invalidate_all();
+        let second = search_web_handler(
+            State(state.clone()),
+            if_none_match(etag.clone()),
+            Json(request()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(second.headers()[header::ETAG], etag);
+        assert_eq!(engine.calls.load(Ordering::SeqCst), calls);
+
+        let third = search_web_handler(
+            State(state.clone()),
+            if_none_match(HeaderValue::from_static("\"other\"")),
+            Json(request()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(third.status(), StatusCode::OK);
+        assert_eq!(third.headers()[header::ETAG], etag);
+        assert!(engine.calls.load(Ordering::SeqCst) > calls);
+    }
+
+    #[tokio::test]
+    async fn repeated_scrape_with_etag_skips_the_scraper() {
+        let state = Arc::new(AppState::new(reqwest::Client::new()));
+        // Nothing listens on the discard port: any scrape that runs fails.
+        let request = |extra: serde_json::Value| -> ScrapeRequest {
+            let mut body = serde_json::json!({"url": "http://127.0.0.1:9/page", "max_attempts": 1});
+            body.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().cloned().unwrap_or_default());
+            serde_json::from_value(body).unwrap()
+        };
+        let etag = HeaderValue::from_static("\"page\"");
+        for extra in [
+            serde_json::json!({}),
+            serde_json::json!({"auth": {"type": "bearer", "token": "secret"}}),
+        ] {
+            let key = http_cache::request_key(Endpoint::Scrape, &request(extra.clone()));
+            http_cache::remember(&state, &key, "\"page\"", std::time::Duration::from_secs(60))
+                .await;
+        }
+
+        let cached = scrape_url_handler(
+            State(state.clone()),
+            if_none_match(etag.clone()),
+            Json(request(serde_json::json!({}))),
+        )
+        .await
+        .unwrap();
+        assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(cached.headers()[header::ETAG], etag);
+
+        let with_credentials = scrape_url_handler(
+            State(state.clone()),
+            if_none_match(etag),
+            Json(request(
+                serde_json::json!({"auth": {"type": "bearer", "token": "secret"}}),
+            )),
+        )
+        .await;
+        assert!(with_credentials.is_err());
+    }
+}
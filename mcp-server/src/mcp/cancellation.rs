@@ -0,0 +1,215 @@
+//! Cancelling in-flight tool calls.
+//!
+//! Every dispatched call runs under a [`CancellationToken`]. The stdio transport gets it
+//! from rmcp, which cancels it on `notifications/cancelled`; the HTTP and WebSocket
+//! transports register it in a [`CallRegistry`] under the JSON-RPC request id so a later
+//! `notifications/cancelled` (or `$/cancelRequest`) can find it.
+//!
+//! A cancelled call is dropped, which releases whatever permits and guards it held. Tools
+//! that must clean up first (the visible-browser flows) hook the token with
+//! [`forward_to`] and get [`CANCEL_GRACE`] to wind down before they are dropped.
+
+use super::{McpCallResponse, McpContent};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+pub use tokio_util::sync::CancellationToken;
+
+/// How long a call that forwarded its cancellation gets to finish its own cleanup.
+pub const CANCEL_GRACE: Duration = Duration::from_secs(15);
+
+#[derive(Clone)]
+struct CallScope {
+    token: CancellationToken,
+    cooperative: Arc<AtomicBool>,
+}
+
+tokio::task_local! {
+    static CURRENT_CALL: CallScope;
+}
+
+/// How a bounded call ended.
+#[derive(Debug)]
+pub enum CallOutcome<T> {
+    Finished(T),
+    TimedOut,
+    Cancelled,
+}
+
+/// Run `call` until it finishes, `timeout` elapses or `token` is cancelled.
+pub async fn run_bounded<F: Future>(
+    call: F,
+    timeout: Duration,
+    token: CancellationToken,
+) -> CallOutcome<F::Output> {
+    let scope = CallScope {
+        token: token.clone(),
+        cooperative: Arc::new(AtomicBool::new(false)),
+    };
+    let cooperative = Arc::clone(&scope.cooperative);
+    let call = CURRENT_CALL.scope(scope, tokio::time::timeout(timeout, call));
+    tokio::pin!(call);
+
+    // Cancellation first: a cooperative call may finish because it was cancelled.
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => {
+            if cooperative.load(Ordering::SeqCst) {
+                let _ = tokio::time::timeout(CANCEL_GRACE, &mut call).await;
+            }
+            CallOutcome::Cancelled
+        }
+        outcome = &mut call => match outcome {
+            Ok(output) => CallOutcome::Finished(output),
+            Err(_) => CallOutcome::TimedOut,
+        },
+    }
+}
+
+/// Aborts the forwarding task when the call that started it ends.
+pub struct CancelForward(tokio::task::JoinHandle<()>);
+
+impl Drop for CancelForward {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Send `true` on `abort_tx` when the current call is cancelled, and let the call finish
+/// its cleanup instead of being dropped straight away. `None` outside a dispatched call.
+pub fn forward_to(abort_tx: watch::Sender<bool>) -> Option<CancelForward> {
+    let scope = CURRENT_CALL.try_with(CallScope::clone).ok()?;
+    scope.cooperative.store(true, Ordering::SeqCst);
+    Some(CancelForward(tokio::spawn(async move {
+        scope.token.cancelled().await;
+        let _ = abort_tx.send(true);
+    })))
+}
+
+/// In-flight calls by JSON-RPC request id.
+#[derive(Debug, Default)]
+pub struct CallRegistry {
+    calls: Mutex<HashMap<String, CancellationToken>>,
+}
+
+/// Removes its call from the registry when dropped.
+pub struct RegisteredCall<'a> {
+    registry: &'a CallRegistry,
+    key: String,
+    pub token: CancellationToken,
+}
+
+impl Drop for RegisteredCall<'_> {
+    fn drop(&mut self) {
+        self.registry.calls.lock().unwrap().remove(&self.key);
+    }
+}
+
+impl CallRegistry {
+    pub fn register(&self, request_id: &Value) -> RegisteredCall<'_> {
+        let key = request_id.to_string();
+        let token = CancellationToken::new();
+        self.calls
+            .lock()
+            .unwrap()
+            .insert(key.clone(), token.clone());
+        RegisteredCall {
+            registry: self,
+            key,
+            token,
+        }
+    }
+
+    /// Cancel the call registered under `request_id`; `false` if none is in flight.
+    pub fn cancel(&self, request_id: &Value) -> bool {
+        let token = self.calls.lock().unwrap().remove(&request_id.to_string());
+        token.map(|token| token.cancel()).is_some()
+    }
+}
+
+pub fn cancelled_call_response(tool_name: &str) -> McpCallResponse {
+    let body = json!({
+        "status": "cancelled",
+        "tool_name": tool_name,
+        "message": "Tool execution was cancelled by the client.",
+        "retryable": true
+    });
+
+    McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text: serde_json::to_string_pretty(&body).unwrap_or_else(|_| body.to_string()),
+        }],
+        is_error: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Semaphore;
+
+    #[tokio::test]
+    async fn cancelling_drops_the_call_and_its_permits() {
+        let permits = Arc::new(Semaphore::new(1));
+        let registry = CallRegistry::default();
+        let call = registry.register(&json!(7));
+        let held = Arc::clone(&permits);
+        let stuck = async move {
+            let _permit = held.acquire().await.unwrap();
+            std::future::pending::<()>().await
+        };
+
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(registry.cancel(&json!(7)));
+        };
+        let (outcome, ()) = tokio::join!(
+            run_bounded(stuck, Duration::from_secs(30), call.token.clone()),
+            cancel
+        );
+
+        assert!(matches!(outcome, CallOutcome::Cancelled));
+        assert_eq!(permits.available_permits(), 1);
+        assert!(!registry.cancel(&json!(7)), "cancel removes the entry");
+        assert!(!registry.cancel(&json!("7")), "ids keep their JSON type");
+    }
+
+    #[tokio::test]
+    async fn forwarded_cancellation_lets_the_call_clean_up() {
+        let token = CancellationToken::new();
+        let (cleaned_tx, mut cleaned_rx) = watch::channel(false);
+        let call = async move {
+            let (abort_tx, mut abort_rx) = watch::channel(false);
+            let _forward = forward_to(abort_tx).expect("inside a call");
+            abort_rx.changed().await.unwrap();
+            let _ = cleaned_tx.send(true);
+        };
+
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        let outcome = run_bounded(call, Duration::from_secs(30), token).await;
+
+        assert!(matches!(outcome, CallOutcome::Cancelled));
+        assert!(*cleaned_rx.borrow_and_update());
+        assert!(forward_to(watch::channel(false).0).is_none());
+    }
+
+    #[tokio::test]
+    async fn timeout_wins_when_nobody_cancels() {
+        let outcome = run_bounded(
+            std::future::pending::<()>(),
+            Duration::from_millis(10),
+            CancellationToken::new(),
+        )
+        .await;
+        assert!(matches!(outcome, CallOutcome::TimedOut));
+    }
+}
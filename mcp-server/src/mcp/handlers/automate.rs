@@ -5,6 +5,7 @@
 //! Phase 21: assert (fail-fast DOM assertions), mock_api (fetch+XHR network mocking)
 
 use crate::cdp::state;
+use crate::core::error::ScoutError;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::scraping::browser_manager;
 use crate::types::ErrorResponse;
@@ -758,6 +759,7 @@ async fn run_navigate(page: &chromiumoxide::Page, url: &str) -> Result<Value> {
     if url.is_empty() {
         return Err(anyhow!("navigate: 'target' (URL) is required"));
     }
+    crate::scraping::domain_policy::check(url).await?;
     info!("🌐 navigate → {}", url);
     page.goto(url)
         .await
@@ -1909,6 +1911,7 @@ async fn run_tabs(
         }
         "new" => {
             let url = step.get("target").and_then(|v| v.as_str()).filter(|s| s.starts_with("http") || *s == "about:blank").unwrap_or("about:blank");
+            crate::scraping::domain_policy::check(url).await?;
             let page = browser.new_page(url).await.map_err(|e| anyhow!("tabs new failed: {}", e))?;
             page.activate().await.map_err(|e| anyhow!("tabs new activate failed: {}", e))?;
             *current_page = page.clone();
@@ -3063,12 +3066,10 @@ pub async fn handle(
     let steps = match arguments.get("steps").and_then(|v| v.as_array()) {
         Some(s) if !s.is_empty() => s.clone(),
         _ => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "'steps' must be a non-empty array of action objects".to_string(),
-                }),
-            ));
+            return Err(ScoutError::invalid_input(
+                "'steps' must be a non-empty array of action objects",
+            )
+            .into_http());
         }
     };
 
@@ -3197,6 +3198,10 @@ pub async fn handle_profile_auth(
         .unwrap_or(120)
         .clamp(10, 600); // clamp: 10s–10min
 
+    crate::scraping::domain_policy::check(url)
+        .await
+        .map_err(|e| e.into_http())?;
+
     // Step 1: release the headless SingletonLock on the profile.
     if let Err(e) = state::close_session().await {
         return Ok(Json(McpCallResponse {
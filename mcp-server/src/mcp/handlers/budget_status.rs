@@ -0,0 +1,26 @@
+use crate::core::budget;
+use crate::mcp::{McpCallResponse, McpContent};
+use crate::types::ErrorResponse;
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// The calling client's hourly search / scrape / browser budgets: limits, usage, what is
+/// left and when the window resets.
+pub async fn handle(
+    state: Arc<AppState>,
+    _arguments: &Value,
+) -> Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let status = state.budgets.status(&budget::current_caller());
+    let text = serde_json::to_string_pretty(&status)
+        .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e));
+    Ok(Json(McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text,
+        }],
+        is_error: false,
+    }))
+}
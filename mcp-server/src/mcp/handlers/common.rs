@@ -1,8 +1,16 @@
+use crate::core::error::ScoutError;
+use crate::history::MemoryManager;
 use crate::rust_scraper::QualityMode;
+use crate::scraping::request_auth::RequestAuth;
+use crate::scraping::request_headers::RequestHeaders;
+use crate::semantic_dedup::{self, SemanticDedup};
 use crate::types::ErrorResponse;
+use crate::AppState;
 use axum::http::StatusCode;
 use axum::response::Json;
 use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 pub fn parse_quality_mode(
     arguments: &Value,
@@ -14,11 +22,119 @@ pub fn parse_quality_mode(
 
     match QualityMode::parse_str(raw) {
         Some(mode) => Ok(mode),
-        None => Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Invalid quality_mode. Allowed values: balanced, aggressive, high".to_string(),
-            }),
-        )),
+        None => Err(ScoutError::invalid_input(
+            "Invalid quality_mode. Allowed values: balanced, aggressive, high",
+        )
+        .into_http()),
     }
 }
+
+/// Optional `domain_rate_limit` (requests/second per domain; `0` disables pacing).
+pub fn parse_domain_rate_limit(arguments: &Value) -> Option<f64> {
+    arguments
+        .get("domain_rate_limit")
+        .and_then(|v| v.as_f64())
+        .filter(|r| r.is_finite() && *r >= 0.0)
+}
+
+/// Optional `auth` object (`{"type": "basic"|"bearer", ...}`), validated up front so a
+/// malformed credential fails as `invalid_input` before anything is fetched.
+pub fn parse_auth(
+    arguments: &Value,
+) -> Result<Option<RequestAuth>, (StatusCode, Json<ErrorResponse>)> {
+    let Some(raw) = arguments.get("auth").filter(|v| !v.is_null()) else {
+        return Ok(None);
+    };
+    let auth: RequestAuth = serde_json::from_value(raw.clone()).map_err(|_| {
+        ScoutError::invalid_input(
+            "Invalid auth: expected {\"type\": \"basic\", \"username\", \"password\"} or {\"type\": \"bearer\", \"token\"}",
+        )
+        .into_http()
+    })?;
+    auth.header_value()
+        .map_err(|e| ScoutError::invalid_input(format!("Invalid auth: {}", e)).into_http())?;
+    Ok(Some(auth))
+}
+
+/// Optional `headers` object (`{"name": "value"}`) and `user_agent` string, validated up
+/// front like `auth` so a hop-by-hop or malformed header fails before anything is fetched.
+pub fn parse_request_headers(
+    arguments: &Value,
+) -> Result<Option<RequestHeaders>, (StatusCode, Json<ErrorResponse>)> {
+    let invalid = || {
+        ScoutError::invalid_input("Invalid headers: expected an object mapping names to strings")
+            .into_http()
+    };
+    let mut headers = Vec::new();
+    if let Some(raw) = arguments.get("headers").filter(|v| !v.is_null()) {
+        for (name, value) in raw.as_object().ok_or_else(invalid)? {
+            let value = value.as_str().ok_or_else(invalid)?;
+            headers.push((name.clone(), value.to_string()));
+        }
+    }
+    let user_agent = match arguments.get("user_agent").filter(|v| !v.is_null()) {
+        Some(Value::String(ua)) => Some(ua.clone()),
+        Some(_) => {
+            return Err(
+                ScoutError::invalid_input("Invalid user_agent: expected a string").into_http(),
+            )
+        }
+        None => None,
+    };
+    RequestHeaders::new(headers, user_agent)
+        .map_err(|e| ScoutError::invalid_input(format!("Invalid headers: {}", e)).into_http())
+}
+
+/// Optional `max_selector_matches` (matches kept per named selector).
+pub fn parse_max_selector_matches(arguments: &Value) -> Option<usize> {
+    arguments
+        .get("max_selector_matches")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+}
+
+/// Optional `selectors` object (`{"name": "css selector"}`). Selector syntax is checked
+/// per name when the page is evaluated; only the shape is validated here.
+pub fn parse_selectors(
+    arguments: &Value,
+) -> Result<BTreeMap<String, String>, (StatusCode, Json<ErrorResponse>)> {
+    let Some(raw) = arguments.get("selectors").filter(|v| !v.is_null()) else {
+        return Ok(BTreeMap::new());
+    };
+    let invalid = || {
+        ScoutError::invalid_input(
+            "Invalid selectors: expected an object mapping names to CSS selector strings",
+        )
+        .into_http()
+    };
+    let mut selectors = BTreeMap::new();
+    for (name, css) in raw.as_object().ok_or_else(invalid)? {
+        let css = css.as_str().ok_or_else(invalid)?;
+        selectors.insert(name.clone(), css.to_string());
+    }
+    Ok(selectors)
+}
+
+/// `semantic_dedup` with its `dedup_threshold` and `dedup_scope`, plus the memory it embeds
+/// with. Rejected before any page is fetched when the server runs without semantic memory.
+pub async fn parse_semantic_dedup(
+    state: &AppState,
+    arguments: &Value,
+) -> Result<Option<(SemanticDedup, Arc<MemoryManager>)>, (StatusCode, Json<ErrorResponse>)> {
+    if !arguments
+        .get("semantic_dedup")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        return Ok(None);
+    }
+    let dedup = SemanticDedup::new(
+        arguments.get("dedup_threshold").and_then(|v| v.as_f64()),
+        arguments.get("dedup_scope").and_then(|v| v.as_str()),
+    )
+    .map_err(ScoutError::into_http)?;
+    let memory = semantic_dedup::memory(state)
+        .await
+        .map_err(ScoutError::into_http)?;
+    Ok(Some((dedup, memory)))
+}
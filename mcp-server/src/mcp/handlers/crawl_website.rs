@@ -1,5 +1,9 @@
-use super::common::parse_quality_mode;
-use crate::crawl::CrawlConfig;
+use super::common::{
+    parse_auth, parse_domain_rate_limit, parse_quality_mode, parse_semantic_dedup,
+};
+use crate::core::error::ScoutError;
+use crate::crawl::{CrawlConfig, CrawlContentFilter, CrawlResume, CrawlStrategy};
+use crate::crawl_graph::{self, GraphFormat};
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::types::{AuthWallBlocked, ErrorResponse};
 use crate::{crawl, AppState};
@@ -13,19 +17,44 @@ pub async fn handle(
     state: Arc<AppState>,
     arguments: &Value,
 ) -> Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let url = arguments
-        .get("url")
+    // `resume` continues a saved crawl with its saved settings, so `url` is optional then.
+    let resume = arguments
+        .get("resume")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: url".to_string(),
-                }),
+        .map(str::trim)
+        .filter(|id| !id.is_empty());
+    let url = match (arguments.get("url").and_then(|v| v.as_str()), resume) {
+        (Some(url), _) => url,
+        (None, Some(_)) => "",
+        (None, None) => {
+            return Err(
+                ScoutError::invalid_input("Missing required parameter: url (or resume)")
+                    .into_http(),
             )
-        })?;
+        }
+    };
 
     let quality_mode = parse_quality_mode(arguments)?;
+    let strategy = match arguments.get("strategy").and_then(|v| v.as_str()) {
+        None => CrawlStrategy::default(),
+        Some(raw) => CrawlStrategy::parse_str(raw).ok_or_else(|| {
+            ScoutError::invalid_input(format!(
+                "Unknown strategy '{}': expected 'breadth' or 'priority'",
+                raw
+            ))
+            .into_http()
+        })?,
+    };
+    let output = match arguments.get("output").and_then(|v| v.as_str()) {
+        None => GraphFormat::default(),
+        Some(raw) => GraphFormat::parse_str(raw).ok_or_else(|| {
+            ScoutError::invalid_input(format!(
+                "Unknown output '{}': expected 'json', 'graphml' or 'dot'",
+                raw
+            ))
+            .into_http()
+        })?,
+    };
 
     let config = CrawlConfig {
         max_depth: arguments
@@ -71,7 +100,79 @@ pub async fn handle(
             .map(|n| n as usize)
             .unwrap_or(5000),
         quality_mode: quality_mode.as_str().to_string(),
+        use_sitemap: arguments
+            .get("use_sitemap")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        sitemap_url: arguments
+            .get("sitemap_url")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        max_sitemap_entries: arguments
+            .get("max_sitemap_entries")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(1000),
+        domain_rate_limit: parse_domain_rate_limit(arguments),
+        persist_session: arguments
+            .get("persist_session")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        strategy,
+        auth: parse_auth(arguments)?,
+        links_only: arguments
+            .get("links_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        respect_robots_meta: arguments
+            .get("respect_robots_meta")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        max_graph_nodes: arguments
+            .get("max_graph_nodes")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(500),
+        respect_canonical: arguments
+            .get("respect_canonical")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true),
     };
+    if !url.is_empty()
+        && arguments
+            .get("clear_session")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    {
+        state.cookie_jars.clear(url);
+    }
+
+    // Only pages matching this query come back; the whole crawl still runs.
+    let content_filter = arguments
+        .get("content_filter")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(|query| CrawlContentFilter {
+            query: query.to_string(),
+            min_score: arguments
+                .get("min_score")
+                .and_then(|v| v.as_f64())
+                .map(|n| n as f32)
+                .unwrap_or(CrawlContentFilter::DEFAULT_MIN_SCORE),
+            max_pages_returned: arguments
+                .get("max_pages_returned")
+                .and_then(|v| v.as_u64())
+                .map(|n| n as usize),
+        });
+
+    // Pages below this quality composite are listed in `dropped_low_quality` instead.
+    let min_quality = arguments.get("min_quality").and_then(|v| v.as_f64());
+    if min_quality.is_some_and(|q| !(0.0..=1.0).contains(&q)) {
+        return Err(ScoutError::invalid_input("min_quality must be between 0 and 1").into_http());
+    }
+    // Near-identical pages collapse into the first; see `semantic_dedup`.
+    let semantic_dedup = parse_semantic_dedup(&state, arguments).await?;
 
     let use_proxy = arguments
         .get("use_proxy")
@@ -86,8 +187,66 @@ pub async fn handle(
         .map(|n| n as usize)
         .unwrap_or(10_000);
 
-    match crawl::crawl_website(&state, url, config, use_proxy).await {
-        Ok(response) => {
+    let dry_run = arguments
+        .get("dry_run")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if dry_run && resume.is_some() {
+        return Err(ScoutError::invalid_input(
+            "dry_run plans a new crawl and cannot be combined with resume",
+        )
+        .into_http());
+    }
+
+    let outcome = match resume {
+        Some(crawl_id) => {
+            let resume = CrawlResume {
+                crawl_id: crawl_id.to_string(),
+                max_pages: arguments
+                    .get("max_pages")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as usize),
+                auth: config.auth,
+            };
+            crawl::resume_crawl(&state, resume, use_proxy).await
+        }
+        None if dry_run => crawl::plan_crawl(&state, url, config, use_proxy).await,
+        None => crawl::crawl_website(&state, url, config, use_proxy).await,
+    };
+    match outcome {
+        // Graph exports are bounded by `max_graph_nodes`; cutting them at `max_chars`
+        // would leave a file Gephi/Graphviz cannot open.
+        Ok(response) if output != GraphFormat::Json => {
+            let graph = response.graph.unwrap_or_default();
+            let text = match output {
+                GraphFormat::Dot => crawl_graph::to_dot(&graph),
+                _ => crawl_graph::to_graphml(&graph),
+            };
+            Ok(Json(McpCallResponse {
+                content: vec![McpContent {
+                    content_type: "text".to_string(),
+                    text,
+                }],
+                is_error: false,
+            }))
+        }
+        Ok(mut response) => {
+            if let Some(min_quality) = min_quality {
+                crawl::apply_min_quality(&mut response, min_quality);
+            }
+            if let Some((dedup, memory)) = &semantic_dedup {
+                dedup
+                    .apply_to_crawl(memory, &mut response)
+                    .await
+                    .map_err(|e| {
+                        ScoutError::classify(&e)
+                            .context("Semantic dedup failed")
+                            .into_http()
+                    })?;
+            }
+            if let Some(filter) = &content_filter {
+                filter.apply(&mut response);
+            }
             let mut json_str = serde_json::to_string_pretty(&response)
                 .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e));
             if json_str.len() > max_chars {
@@ -1,4 +1,5 @@
 use super::common::parse_quality_mode;
+use crate::core::error::ScoutError;
 use crate::deep_research::{deep_research, DeepResearchConfig};
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::mcp::tooling::deep_research_enabled;
@@ -16,23 +17,11 @@ fn parse_request(
     let query = arguments
         .get("query")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: query".to_string(),
-                }),
-            )
-        })?
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: query").into_http())?
         .to_string();
 
     if query.trim().is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "query must not be empty".to_string(),
-            }),
-        ));
+        return Err(ScoutError::invalid_input("query must not be empty").into_http());
     }
 
     let depth = arguments
@@ -92,15 +81,12 @@ pub async fn handle(
     // Runtime gate (belt-and-suspenders: catalog already filters this entry when disabled,
     // but direct HTTP calls bypass tool discovery).
     if !deep_research_enabled() {
-        return Err((
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: "deep_research is disabled. \
+        return Err(ScoutError::unavailable(
+            "deep_research is disabled. \
                     Set DEEP_RESEARCH_ENABLED=1 (or unset) to enable at runtime. \
-                    For a build without this tool: cargo build --no-default-features."
-                    .to_string(),
-            }),
-        ));
+                    For a build without this tool: cargo build --no-default-features.",
+        )
+        .into_http());
     }
 
     let (query, config) = parse_request(arguments)?;
@@ -0,0 +1,73 @@
+use crate::mcp::{McpCallResponse, McpContent};
+use crate::tools::search::engines;
+use crate::types::ErrorResponse;
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde_json::Value;
+use std::sync::Arc;
+
+fn text_response(text: String, is_error: bool) -> Json<McpCallResponse> {
+    Json(McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text,
+        }],
+        is_error,
+    })
+}
+
+/// Per-engine search health. `action` defaults to `status`; `reset` lifts the
+/// block-rate bench on `engine`, or on every engine when it is omitted.
+pub async fn handle(
+    state: Arc<AppState>,
+    arguments: &Value,
+) -> Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let action = arguments
+        .get("action")
+        .and_then(|v| v.as_str())
+        .unwrap_or("status");
+    let engine = arguments
+        .get("engine")
+        .and_then(|v| v.as_str())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .filter(|v| !v.is_empty());
+
+    let mut released = None;
+    match action {
+        "status" => {}
+        "reset" => {
+            let target = match engine.as_deref() {
+                Some(name) => match engines::lookup(name) {
+                    Some(adapter) => Some(adapter.name()),
+                    None => {
+                        return Ok(text_response(
+                            format!("Unknown search engine: {}", name),
+                            true,
+                        ))
+                    }
+                },
+                None => None,
+            };
+            released = Some(state.engine_stats.reset(target));
+        }
+        other => {
+            return Ok(text_response(
+                format!("Invalid action: {}. Use status or reset.", other),
+                true,
+            ))
+        }
+    }
+
+    let known: Vec<&str> = engines::REGISTRY.iter().map(|e| e.name()).collect();
+    let mut report = serde_json::to_value(state.engine_stats.report(&known))
+        .unwrap_or_else(|e| serde_json::json!({"error": format!("Failed to serialize: {}", e)}));
+    if let (Some(released), Some(obj)) = (released, report.as_object_mut()) {
+        obj.insert("released_engines".to_string(), serde_json::json!(released));
+    }
+    Ok(text_response(
+        serde_json::to_string_pretty(&report)
+            .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e)),
+        false,
+    ))
+}
@@ -0,0 +1,102 @@
+use super::common::{parse_max_selector_matches, parse_quality_mode, parse_selectors};
+use super::extract_structured::parse_extract_schema;
+use crate::core::error::ScoutError;
+use crate::extract;
+use crate::mcp::{McpCallResponse, McpContent};
+use crate::types::ErrorResponse;
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::error;
+
+pub async fn handle(
+    state: Arc<AppState>,
+    arguments: &Value,
+) -> Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let urls = arguments
+        .get("urls")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| {
+            ScoutError::invalid_input("Missing required parameter: urls (must be array)")
+                .into_http()
+        })?
+        .iter()
+        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+        .collect::<Vec<_>>();
+
+    if urls.is_empty() {
+        return Err(ScoutError::invalid_input("urls array cannot be empty").into_http());
+    }
+
+    let schema = parse_extract_schema(arguments.get("schema"));
+    let prompt = arguments
+        .get("prompt")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let (schema, prompt) = match (schema, prompt) {
+        (None, Some(text)) => match extract::parse_schema_from_prompt(&text) {
+            Some(parsed) => (Some(parsed), None),
+            None => (None, Some(text)),
+        },
+        other => other,
+    };
+
+    let max_concurrent = arguments
+        .get("max_concurrent")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+        .unwrap_or(5);
+
+    let options = extract::ExtractStructuredOptions {
+        schema,
+        prompt,
+        strict: arguments
+            .get("strict")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true),
+        max_chars: arguments
+            .get("max_chars")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize),
+        use_proxy: arguments
+            .get("use_proxy")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        quality_mode: Some(parse_quality_mode(arguments)?.as_str().to_string()),
+        placeholder_word_threshold: arguments
+            .get("placeholder_word_threshold")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize),
+        placeholder_empty_ratio: arguments
+            .get("placeholder_empty_ratio")
+            .and_then(|v| v.as_f64()),
+        selectors: parse_selectors(arguments)?,
+        max_selector_matches: parse_max_selector_matches(arguments),
+    };
+
+    match extract::extract_batch(&state, urls, max_concurrent, options).await {
+        Ok(response) => {
+            let json_str = serde_json::to_string_pretty(&response)
+                .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e));
+            Ok(Json(McpCallResponse {
+                content: vec![McpContent {
+                    content_type: "text".to_string(),
+                    text: json_str,
+                }],
+                is_error: false,
+            }))
+        }
+        Err(e) => {
+            error!("Batch extract tool error: {}", e);
+            Ok(Json(McpCallResponse {
+                content: vec![McpContent {
+                    content_type: "text".to_string(),
+                    text: format!("Batch extract failed: {}", e),
+                }],
+                is_error: true,
+            }))
+        }
+    }
+}
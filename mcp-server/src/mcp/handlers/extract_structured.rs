@@ -1,4 +1,5 @@
-use super::common::parse_quality_mode;
+use super::common::{parse_max_selector_matches, parse_quality_mode, parse_selectors};
+use crate::core::error::ScoutError;
 use crate::extract;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::types::{ErrorResponse, ExtractField};
@@ -9,7 +10,9 @@ use serde_json::Value;
 use std::sync::Arc;
 use tracing::error;
 
-fn parse_extract_schema(schema_value: Option<&serde_json::Value>) -> Option<Vec<ExtractField>> {
+pub(super) fn parse_extract_schema(
+    schema_value: Option<&serde_json::Value>,
+) -> Option<Vec<ExtractField>> {
     fn parse_field(obj: &serde_json::Map<String, serde_json::Value>) -> Option<ExtractField> {
         let name = obj
             .get("name")
@@ -126,14 +129,7 @@ pub async fn handle(
     let url = arguments
         .get("url")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: url".to_string(),
-                }),
-            )
-        })?;
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: url").into_http())?;
 
     let schema_value = arguments.get("schema");
     let mut schema = parse_extract_schema(schema_value);
@@ -192,6 +188,8 @@ pub async fn handle(
             quality_mode: Some(quality_mode.as_str().to_string()),
             placeholder_word_threshold,
             placeholder_empty_ratio,
+            selectors: parse_selectors(arguments)?,
+            max_selector_matches: parse_max_selector_matches(arguments),
         },
     )
     .await
@@ -1,4 +1,8 @@
-use super::common::parse_quality_mode;
+use super::common::{
+    parse_auth, parse_max_selector_matches, parse_quality_mode, parse_request_headers,
+    parse_selectors,
+};
+use crate::core::error::ScoutError;
 use crate::extract;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::types::{ErrorResponse, ExtractField};
@@ -121,14 +125,7 @@ pub async fn handle(
     let url = arguments
         .get("url")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: url".to_string(),
-                }),
-            )
-        })?;
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: url").into_http())?;
 
     let schema_value = arguments.get("schema");
     let schema = parse_extract_schema(schema_value);
@@ -180,6 +177,30 @@ pub async fn handle(
         extract_relevant_sections: false,
         section_limit: None,
         section_threshold: None,
+        respect_robots: false,
+        domain_rate_limit: None,
+        parse_feeds: false,
+        max_attempts: None,
+        auto_escalate: None,
+        download_assets: None,
+        bypass_cache: false,
+        revalidate: false,
+        persist_session: false,
+        clear_session: false,
+        auth: parse_auth(arguments)?,
+        headers: parse_request_headers(arguments)?,
+        links_only: false,
+        links_detail: false,
+        max_links: None,
+        screenshot: false,
+        screenshot_full_page: false,
+        screenshot_inline: false,
+        include_raw_html: false,
+        selectors: parse_selectors(arguments)?,
+        max_selector_matches: parse_max_selector_matches(arguments),
+        prefer_language: None,
+        sections: None,
+        follow_pagination: None,
     };
 
     match scrape::scrape_url_full(&state, url, options).await {
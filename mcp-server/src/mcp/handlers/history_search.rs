@@ -0,0 +1,88 @@
+use crate::core::error::ScoutError;
+use crate::history_search::{self, HistorySearchParams};
+use crate::mcp::{McpCallResponse, McpContent};
+use crate::types::{ErrorResponse, HistoryQuery};
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::error;
+
+fn str_arg(arguments: &Value, key: &str) -> Option<String> {
+    arguments
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+pub async fn handle(
+    state: Arc<AppState>,
+    arguments: &Value,
+) -> Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let action = arguments
+        .get("action")
+        .and_then(|v| v.as_str())
+        .unwrap_or("search");
+
+    let json = match action {
+        "search" => {
+            let query = HistoryQuery {
+                q: str_arg(arguments, "query"),
+                kind: str_arg(arguments, "kind"),
+                time_range: str_arg(arguments, "time_range"),
+                since: str_arg(arguments, "since"),
+                until: str_arg(arguments, "until"),
+                limit: arguments
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as usize),
+                threshold: arguments
+                    .get("threshold")
+                    .and_then(|v| v.as_f64())
+                    .map(|n| n as f32),
+            };
+            let params = HistorySearchParams::from_query(&query, chrono::Utc::now())
+                .map_err(ScoutError::into_http)?;
+            let response = history_search::search(&state, params).await.map_err(|e| {
+                error!("history_search error: {}", e);
+                ScoutError::classify(&e).into_http()
+            })?;
+            serde_json::to_string_pretty(&response)
+        }
+        "delete" => {
+            let days = arguments
+                .get("older_than_days")
+                .and_then(|v| v.as_u64())
+                .ok_or_else(|| {
+                    ScoutError::invalid_input(
+                        "action=delete requires older_than_days (0 deletes everything)",
+                    )
+                    .into_http()
+                })?;
+            let days = u32::try_from(days).map_err(|_| {
+                ScoutError::invalid_input("older_than_days is out of range").into_http()
+            })?;
+            let response = history_search::purge(&state, days).await.map_err(|e| {
+                error!("history_search delete error: {}", e);
+                ScoutError::classify(&e).into_http()
+            })?;
+            serde_json::to_string_pretty(&response)
+        }
+        other => {
+            return Err(ScoutError::invalid_input(format!(
+                "Unknown action '{}': expected 'search' or 'delete'",
+                other
+            ))
+            .into_http())
+        }
+    };
+
+    Ok(Json(McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text: json.unwrap_or_else(|e| format!(r#"{{"error": "Serialization failed: {}"}}"#, e)),
+        }],
+        is_error: false,
+    }))
+}
@@ -1,3 +1,4 @@
+use super::common::parse_quality_mode;
 /// `human_auth_session` — the Auth Specialist.
 ///
 /// This is the Auth-Gatekeeper's escalation endpoint.  It extends the core HITL
@@ -14,7 +15,7 @@
 /// In the **Autonomous Auth-Handling Protocol** this tool is invoked in Step 3
 /// (HITL Phase) only after `web_fetch` returned `auth_risk_score >= 0.4` and
 /// `visual_scout` confirmed the presence of a login page.
-use super::common::parse_quality_mode;
+use crate::core::error::ScoutError;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::types::ErrorResponse;
 use axum::http::StatusCode;
@@ -31,14 +32,7 @@ pub async fn handle(
     let url = arguments
         .get("url")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: url".to_string(),
-                }),
-            )
-        })?;
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: url").into_http())?;
 
     let output_format = arguments
         .get("output_format")
@@ -108,6 +102,9 @@ pub async fn handle(
             wait_for_selector,
             keep_open,
             instruction_message,
+            headless_test: crate::core::config::non_robot_headless_test(),
+            session_ttl: None,
+            close_session: false,
         };
 
         match crate::features::non_robot_search::execute_manual_auth_flow(&state, cfg).await {
@@ -0,0 +1,24 @@
+use crate::mcp::{McpCallResponse, McpContent};
+use crate::types::ErrorResponse;
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Semantic memory health: whether history writes and duplicate checks run or are
+/// skipped, recent failures and the write queue.
+pub async fn handle(
+    state: Arc<AppState>,
+    _arguments: &Value,
+) -> Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let text = serde_json::to_string_pretty(&state.memory_health())
+        .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e));
+    Ok(Json(McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text,
+        }],
+        is_error: false,
+    }))
+}
@@ -1,15 +1,133 @@
 pub mod automate;
+pub mod budget_status;
 pub mod common;
 pub mod crawl_website;
 pub mod deep_research;
+pub mod engine_status;
+pub mod extract_batch;
 pub mod extract_structured;
 pub mod fetch_then_extract;
+pub mod history_search;
 pub mod human_auth_session;
+pub mod memory_status;
+pub mod monitor;
 pub mod non_robot_search;
 pub mod proxy_manager;
+pub mod proxy_status;
+pub mod research;
 pub mod research_history;
 pub mod scrape_batch;
+pub mod scrape_compare;
+pub mod scrape_diff;
 pub mod scrape_url;
 pub mod search_structured;
 pub mod search_web;
 pub mod visual_scout;
+
+use crate::core::budget;
+use crate::core::error::ScoutError;
+use crate::mcp::cancellation::{self, CallOutcome, CancellationToken};
+use crate::mcp::McpCallResponse;
+use crate::types::ErrorResponse;
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub type HandlerResult = Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)>;
+
+/// Route a call to the handler for `internal_name`; `request_name` is the name the client
+/// used, for error messages.
+pub async fn dispatch(
+    state: Arc<AppState>,
+    internal_name: &str,
+    request_name: &str,
+    arguments: &Value,
+) -> HandlerResult {
+    match internal_name {
+        "search_web" => search_web::handle(state, arguments).await,
+        "search_structured" => search_structured::handle(state, arguments).await,
+        "scrape_url" => scrape_url::handle(state, arguments).await,
+        "crawl_website" => crawl_website::handle(state, arguments).await,
+        "scrape_batch" => scrape_batch::handle(state, arguments).await,
+        "scrape_diff" => scrape_diff::handle(state, arguments).await,
+        "scrape_compare" => scrape_compare::handle(state, arguments).await,
+        "deep_research" => deep_research::handle(state, arguments).await,
+        "research" => research::handle(state, arguments).await,
+        "extract_structured" => extract_structured::handle(state, arguments).await,
+        "extract_batch" => extract_batch::handle(state, arguments).await,
+        "fetch_then_extract" => fetch_then_extract::handle(state, arguments).await,
+        "research_history" => research_history::handle(state, arguments).await,
+        "monitor_add" => monitor::handle_add(state, arguments).await,
+        "monitor_list" => monitor::handle_list(state, arguments).await,
+        "monitor_remove" => monitor::handle_remove(state, arguments).await,
+        "monitor_events" => monitor::handle_events(state, arguments).await,
+        "history_search" => history_search::handle(state, arguments).await,
+        "proxy_manager" => proxy_manager::handle(state, arguments).await,
+        "proxy_status" => proxy_status::handle(state, arguments).await,
+        "engine_status" => engine_status::handle(state, arguments).await,
+        "memory_status" => memory_status::handle(state, arguments).await,
+        "budget_status" => budget_status::handle(state, arguments).await,
+        "non_robot_search" => non_robot_search::handle(state, arguments).await,
+        "visual_scout" => visual_scout::handle(state, arguments).await,
+        "human_auth_session" => human_auth_session::handle(state, arguments).await,
+        "browser_automate" | "scout_browser_automate" => automate::handle(state, arguments).await,
+        "browser_close" | "scout_browser_close" => automate::handle_close(state, arguments).await,
+        "agent_profile_auth" | "scout_agent_profile_auth" => {
+            automate::handle_profile_auth(state, arguments).await
+        }
+        _ => Err(ScoutError::invalid_input(format!("Unknown tool: {}", request_name)).into_http()),
+    }
+}
+
+/// The tool's configured hard timeout, shortened (never extended) by a positive
+/// `timeout_ms` argument.
+pub fn call_timeout(internal_name: &str, arguments: &Value) -> Duration {
+    let configured = Duration::from_secs(crate::core::config::mcp_tool_timeout_secs(internal_name));
+    arguments
+        .get("timeout_ms")
+        .and_then(Value::as_u64)
+        .filter(|ms| *ms > 0)
+        .map_or(configured, |ms| Duration::from_millis(ms).min(configured))
+}
+
+/// [`dispatch`] bounded by [`call_timeout`] and `cancel`. A timed-out or cancelled call is
+/// dropped and answered with a `timeout` / `cancelled` error result. Tools the deployment
+/// disabled fail with `unavailable`, and calls over the caller's budget (see
+/// [`budget`]) with `rate_limited`, before anything runs.
+#[tracing::instrument(skip_all, fields(tool = %request_name))]
+pub async fn run_call(
+    state: Arc<AppState>,
+    internal_name: &str,
+    request_name: &str,
+    arguments: &Value,
+    cancel: CancellationToken,
+) -> HandlerResult {
+    if state.tool_registry.is_disabled(internal_name) {
+        return Err(ScoutError::unavailable(format!(
+            "Tool '{}' is not available in this deployment",
+            request_name
+        ))
+        .into_http());
+    }
+    state
+        .budgets
+        .charge(
+            &budget::current_caller(),
+            budget::cost(internal_name, arguments),
+        )
+        .map_err(ScoutError::into_http)?;
+    let timeout = call_timeout(internal_name, arguments);
+    // Boxed: inlining every handler's future here overflows the compiler's layout depth.
+    let call = Box::pin(dispatch(state, internal_name, request_name, arguments));
+    match cancellation::run_bounded(call, timeout, cancel).await {
+        CallOutcome::Finished(result) => result,
+        CallOutcome::TimedOut => Ok(Json(crate::mcp::timeout::timeout_call_response(
+            request_name,
+            timeout,
+        ))),
+        CallOutcome::Cancelled => Ok(Json(cancellation::cancelled_call_response(request_name))),
+    }
+}
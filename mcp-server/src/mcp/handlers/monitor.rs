@@ -0,0 +1,113 @@
+use crate::core::error::ScoutError;
+use crate::mcp::{McpCallResponse, McpContent};
+use crate::monitor::{MonitorSpec, DEFAULT_EVENT_LIMIT};
+use crate::types::ErrorResponse;
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::error;
+
+type HandlerResult = Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)>;
+
+fn json_response(value: &impl Serialize) -> HandlerResult {
+    Ok(Json(McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text: serde_json::to_string_pretty(value)
+                .unwrap_or_else(|e| format!(r#"{{"error": "Serialization failed: {}"}}"#, e)),
+        }],
+        is_error: false,
+    }))
+}
+
+fn monitor_error(action: &str, e: anyhow::Error) -> (StatusCode, Json<ErrorResponse>) {
+    error!("{} error: {}", action, e);
+    ScoutError::classify(&e).into_http()
+}
+
+pub async fn handle_add(state: Arc<AppState>, arguments: &Value) -> HandlerResult {
+    let urls = arguments
+        .get("urls")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: urls").into_http())?
+        .iter()
+        .map(|v| {
+            v.as_str().map(str::to_string).ok_or_else(|| {
+                ScoutError::invalid_input("urls must be an array of strings").into_http()
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let spec = MonitorSpec {
+        urls,
+        interval_secs: arguments.get("interval_secs").and_then(|v| v.as_u64()),
+        use_proxy: arguments
+            .get("use_proxy")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        label: arguments
+            .get("label")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+    };
+    let monitor = state
+        .monitors
+        .add(spec, chrono::Utc::now())
+        .await
+        .map_err(|e| monitor_error("monitor_add", e))?;
+    json_response(&monitor)
+}
+
+pub async fn handle_list(state: Arc<AppState>, _arguments: &Value) -> HandlerResult {
+    let monitors = state.monitors.list().await;
+    json_response(&serde_json::json!({ "monitors": monitors }))
+}
+
+pub async fn handle_remove(state: Arc<AppState>, arguments: &Value) -> HandlerResult {
+    let id = arguments
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: id").into_http())?;
+    let removed = state
+        .monitors
+        .remove(id)
+        .await
+        .map_err(|e| monitor_error("monitor_remove", e))?;
+    if !removed {
+        return Err(ScoutError::not_found(format!("Unknown monitor '{}'", id)).into_http());
+    }
+    json_response(&serde_json::json!({ "id": id, "removed": true }))
+}
+
+pub async fn handle_events(state: Arc<AppState>, arguments: &Value) -> HandlerResult {
+    let since = match arguments.get("since").and_then(|v| v.as_str()) {
+        Some(raw) => Some(
+            chrono::DateTime::parse_from_rfc3339(raw.trim())
+                .map_err(|_| {
+                    ScoutError::invalid_input(format!(
+                        "Invalid since '{}': expected an RFC 3339 timestamp",
+                        raw
+                    ))
+                    .into_http()
+                })?
+                .with_timezone(&chrono::Utc),
+        ),
+        None => None,
+    };
+    let limit = arguments
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_EVENT_LIMIT, |n| (n as usize).max(1));
+    let events = state
+        .monitors
+        .events(
+            since,
+            arguments.get("monitor_id").and_then(|v| v.as_str()),
+            limit,
+        )
+        .await
+        .map_err(|e| monitor_error("monitor_events", e))?;
+    json_response(&serde_json::json!({ "events": events }))
+}
@@ -1,4 +1,5 @@
 use super::common::parse_quality_mode;
+use crate::core::error::ScoutError;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::types::ErrorResponse;
 use axum::http::StatusCode;
@@ -24,17 +25,32 @@ pub async fn handle(
         return super::human_auth_session::handle(state, arguments).await;
     }
 
+    let close_session = arguments
+        .get("close_session")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    // `close_session` without a URL just ends the warm session.
+    if close_session && arguments.get("url").is_none() {
+        let closed = crate::features::non_robot_search::close_warm_session(&state).await;
+        let text = if closed {
+            "hitl_web_fetch: warm browser session closed"
+        } else {
+            "hitl_web_fetch: no warm browser session to close"
+        };
+        return Ok(Json(McpCallResponse {
+            content: vec![McpContent {
+                content_type: "text".to_string(),
+                text: text.to_string(),
+            }],
+            is_error: false,
+        }));
+    }
+
     let url = arguments
         .get("url")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: url".to_string(),
-                }),
-            )
-        })?;
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: url").into_http())?;
 
     let output_format = arguments
         .get("output_format")
@@ -96,6 +112,12 @@ pub async fn handle(
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
+        // `0` turns reuse off for this call even when cortex-scout.json or the env enable it.
+        let session_ttl = match arguments.get("session_ttl_secs").and_then(|v| v.as_u64()) {
+            Some(secs) => (secs > 0).then(|| std::time::Duration::from_secs(secs)),
+            None => state.shadow_config.non_robot_search.resolve_session_ttl(),
+        };
+
         let cfg = crate::features::non_robot_search::NonRobotSearchConfig {
             url: url.to_string(),
             max_chars,
@@ -108,6 +130,9 @@ pub async fn handle(
             wait_for_selector,
             keep_open,
             instruction_message,
+            headless_test: crate::core::config::non_robot_headless_test(),
+            session_ttl,
+            close_session,
         };
 
         match crate::features::non_robot_search::execute_non_robot_search(&state, cfg).await {
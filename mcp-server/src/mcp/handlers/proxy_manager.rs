@@ -1,3 +1,4 @@
+use crate::core::error::ScoutError;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::mcp_tooling::format_proxy_display;
 use crate::proxy_grabber;
@@ -18,12 +19,7 @@ pub async fn handle(
         .get("action")
         .and_then(|v| v.as_str())
         .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: action".to_string(),
-                }),
-            )
+            ScoutError::invalid_input("Missing required parameter: action").into_http()
         })?;
 
     match action {
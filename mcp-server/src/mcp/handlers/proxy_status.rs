@@ -0,0 +1,106 @@
+use crate::core::error::ScoutError;
+use crate::mcp::{McpCallResponse, McpContent};
+use crate::mcp_tooling::format_proxy_display;
+use crate::types::ErrorResponse;
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::error;
+
+fn text_response(text: String, is_error: bool) -> Json<McpCallResponse> {
+    Json(McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text,
+        }],
+        is_error,
+    })
+}
+
+/// Pool health plus manual control. `action` defaults to `status`; `rotate` forces
+/// `switch_to_best_proxy`, and `enable` / `disable` bench or restore `proxy_url`, given in
+/// full or in the masked form the status report lists.
+pub async fn handle(
+    state: Arc<AppState>,
+    arguments: &Value,
+) -> Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let action = arguments
+        .get("action")
+        .and_then(|v| v.as_str())
+        .unwrap_or("status");
+
+    let Some(proxy_manager) = &state.proxy_manager else {
+        return Ok(text_response(
+            "Proxy manager not available. Provide IP_LIST_PATH (default: ip.txt) to enable proxy support."
+                .to_string(),
+            true,
+        ));
+    };
+
+    match action {
+        "status" => {}
+        "rotate" => match proxy_manager.switch_to_best_proxy().await {
+            Ok(proxy_url) => {
+                return Ok(text_response(
+                    format!(
+                        "Rotated to proxy: {}\n\nPass use_proxy=true on the next fetch to use it.",
+                        format_proxy_display(&proxy_url)
+                    ),
+                    false,
+                ))
+            }
+            Err(e) => {
+                error!("Proxy rotate error: {}", e);
+                return Ok(text_response(
+                    format!("proxy_status rotate failed: {}", e),
+                    true,
+                ));
+            }
+        },
+        "enable" | "disable" => {
+            let Some(proxy_url) = arguments
+                .get("proxy_url")
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.trim().is_empty())
+            else {
+                return Err(ScoutError::invalid_input(format!(
+                    "Missing required parameter for {}: proxy_url",
+                    action
+                ))
+                .into_http());
+            };
+            if let Err(e) = proxy_manager
+                .set_proxy_enabled(proxy_url, action == "enable")
+                .await
+            {
+                return Ok(text_response(
+                    format!("proxy_status {} failed: {}", action, e),
+                    true,
+                ));
+            }
+        }
+        other => {
+            return Ok(text_response(
+                format!(
+                    "Invalid action: {}. Use status, rotate, enable, or disable.",
+                    other
+                ),
+                true,
+            ))
+        }
+    }
+
+    match proxy_manager.pool_report().await {
+        Ok(report) => Ok(text_response(
+            serde_json::to_string_pretty(&report)
+                .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e)),
+            false,
+        )),
+        Err(e) => {
+            error!("Proxy status error: {}", e);
+            Ok(text_response(format!("proxy_status failed: {}", e), true))
+        }
+    }
+}
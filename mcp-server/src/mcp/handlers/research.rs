@@ -0,0 +1,132 @@
+use super::common::parse_quality_mode;
+use super::extract_structured::parse_extract_schema;
+use crate::core::error::ScoutError;
+use crate::mcp::{McpCallResponse, McpContent};
+use crate::research::{self, ResearchOptions};
+use crate::types::ErrorResponse;
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::error;
+
+/// Seconds of the tool's hard timeout kept back so the partial report still gets out.
+const BUDGET_HEADROOM_SECS: u64 = 5;
+const MIN_BUDGET_SECS: u64 = 5;
+
+fn parse_request(
+    arguments: &Value,
+) -> Result<(String, ResearchOptions), (StatusCode, Json<ErrorResponse>)> {
+    let query = arguments
+        .get("query")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: query").into_http())?
+        .trim()
+        .to_string();
+    if query.is_empty() {
+        return Err(ScoutError::invalid_input("query must not be empty").into_http());
+    }
+
+    let defaults = ResearchOptions::default();
+    let max_budget = crate::core::config::mcp_tool_timeout_secs("research")
+        .saturating_sub(BUDGET_HEADROOM_SECS)
+        .max(MIN_BUDGET_SECS);
+    let budget_secs = arguments
+        .get("budget_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(defaults.budget.as_secs())
+        .clamp(MIN_BUDGET_SECS, max_budget);
+
+    let options = ResearchOptions {
+        top_n: arguments
+            .get("top_n")
+            .and_then(|v| v.as_u64())
+            .map_or(defaults.top_n, |n| n.clamp(1, 10) as usize),
+        max_chars: arguments
+            .get("max_chars")
+            .and_then(|v| v.as_u64())
+            .map_or(defaults.max_chars, |n| n.max(200) as usize),
+        quality_mode: Some(parse_quality_mode(arguments)?),
+        use_proxy: arguments
+            .get("use_proxy")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        schema: parse_extract_schema(arguments.get("schema")),
+        prompt: arguments
+            .get("prompt")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.trim().is_empty())
+            .map(str::to_string),
+        budget: Duration::from_secs(budget_secs),
+    };
+    Ok((query, options))
+}
+
+pub async fn handle(
+    state: Arc<AppState>,
+    arguments: &Value,
+) -> Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (query, options) = parse_request(arguments)?;
+
+    let response = research::research(&state, &query, options)
+        .await
+        .map_err(|e| {
+            error!("research error for {:?}: {}", query, e);
+            ScoutError::classify(&e)
+                .context("Research failed")
+                .into_http()
+        })?;
+
+    Ok(Json(McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text: serde_json::to_string_pretty(&response)
+                .unwrap_or_else(|e| format!(r#"{{"error": "Serialization failed: {}"}}"#, e)),
+        }],
+        is_error: false,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_request_clamps_knobs_and_reads_the_schema() {
+        let (query, options) = parse_request(&json!({
+            "query": "  tokio runtime  ",
+            "top_n": 50,
+            "max_chars": 10,
+            "budget_secs": 100000,
+            "quality_mode": "aggressive",
+            "schema": { "version": "string", "license": "string" }
+        }))
+        .expect("request should parse");
+
+        assert_eq!(query, "tokio runtime");
+        assert_eq!(options.top_n, 10);
+        assert_eq!(options.max_chars, 200);
+        assert_eq!(
+            options.budget.as_secs(),
+            crate::core::config::mcp_tool_timeout_secs("research") - BUDGET_HEADROOM_SECS
+        );
+        assert_eq!(options.quality_mode.map(|m| m.as_str()), Some("aggressive"));
+        let fields: Vec<String> = options
+            .schema
+            .unwrap()
+            .into_iter()
+            .map(|f| f.name)
+            .collect();
+        assert_eq!(fields.len(), 2);
+        assert!(fields.contains(&"license".to_string()));
+    }
+
+    #[test]
+    fn parse_request_rejects_a_blank_query() {
+        let err = parse_request(&json!({ "query": "  " })).err().unwrap();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::core::error::ScoutError;
 use crate::history::EntryType;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::types::ErrorResponse;
@@ -17,12 +18,7 @@ pub async fn handle(
         .get("query")
         .and_then(|v| v.as_str())
         .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: query".to_string(),
-                }),
-            )
+            ScoutError::invalid_input("Missing required parameter: query").into_http()
         })?;
 
     let limit = arguments
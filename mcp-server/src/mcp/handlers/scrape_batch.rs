@@ -1,7 +1,11 @@
-use super::common::parse_quality_mode;
+use super::common::{
+    parse_auth, parse_domain_rate_limit, parse_quality_mode, parse_request_headers,
+    parse_semantic_dedup,
+};
+use crate::core::error::ScoutError;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::types::ErrorResponse;
-use crate::{batch_scrape, AppState};
+use crate::{batch_scrape, scrape, AppState};
 use axum::http::StatusCode;
 use axum::response::Json;
 use serde_json::Value;
@@ -16,24 +20,15 @@ pub async fn handle(
         .get("urls")
         .and_then(|v| v.as_array())
         .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: urls (must be array)".to_string(),
-                }),
-            )
+            ScoutError::invalid_input("Missing required parameter: urls (must be array)")
+                .into_http()
         })?
         .iter()
         .filter_map(|v| v.as_str().map(|s| s.to_string()))
         .collect::<Vec<_>>();
 
     if urls.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "urls array cannot be empty".to_string(),
-            }),
-        ));
+        return Err(ScoutError::invalid_input("urls array cannot be empty").into_http());
     }
 
     let max_concurrent = arguments
@@ -59,17 +54,59 @@ pub async fn handle(
 
     let quality_mode = parse_quality_mode(arguments)?;
 
-    match batch_scrape::scrape_batch(
+    let min_quality = arguments.get("min_quality").and_then(|v| v.as_f64());
+    if min_quality.is_some_and(|q| !(0.0..=1.0).contains(&q)) {
+        return Err(ScoutError::invalid_input("min_quality must be between 0 and 1").into_http());
+    }
+    let semantic_dedup = parse_semantic_dedup(&state, arguments).await?;
+
+    let options = scrape::ScrapeUrlOptions {
+        use_proxy,
+        quality_mode: Some(quality_mode),
+        domain_rate_limit: parse_domain_rate_limit(arguments),
+        persist_session: arguments
+            .get("persist_session")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        auth: parse_auth(arguments)?,
+        headers: parse_request_headers(arguments)?,
+        ..Default::default()
+    };
+    // Cleared once up front; per-URL clearing would wipe cookies set earlier in the batch.
+    if arguments
+        .get("clear_session")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        for url in &urls {
+            state.cookie_jars.clear(url);
+        }
+    }
+
+    match batch_scrape::scrape_batch_with_budget(
         &state,
         urls,
         max_concurrent,
         max_chars,
-        use_proxy,
-        Some(quality_mode),
+        options,
+        None,
     )
     .await
     {
-        Ok(response) => {
+        Ok(mut response) => {
+            if let Some(min_quality) = min_quality {
+                batch_scrape::apply_min_quality(&mut response, min_quality);
+            }
+            if let Some((dedup, memory)) = &semantic_dedup {
+                dedup
+                    .apply_to_batch(memory, &mut response)
+                    .await
+                    .map_err(|e| {
+                        ScoutError::classify(&e)
+                            .context("Semantic dedup failed")
+                            .into_http()
+                    })?;
+            }
             if output_format == "text" {
                 let mut text = format!(
                     "Batch scrape summary\nTotal: {}\nSuccessful: {}\nFailed: {}\nDuration: {}ms\n\n",
@@ -100,6 +137,18 @@ pub async fn handle(
                         ));
                     }
                 }
+                for dropped in &response.dropped_low_quality {
+                    text.push_str(&format!(
+                        "⏭ {} (quality {:.2} below min_quality)\n",
+                        dropped.url, dropped.quality
+                    ));
+                }
+                for duplicate in &response.duplicates {
+                    text.push_str(&format!(
+                        "⧉ {} (duplicate of {}, similarity {:.2})\n",
+                        duplicate.url, duplicate.duplicate_of, duplicate.similarity
+                    ));
+                }
 
                 return Ok(Json(McpCallResponse {
                     content: vec![McpContent {
@@ -119,10 +168,12 @@ pub async fn handle(
                             "url": item.url,
                             "success": item.success,
                             "duration_ms": item.duration_ms,
+                            "duplicates_of": item.duplicates_of,
                             "data": {
                                 "metadata": {
                                     "title": data.title,
                                     "canonical_url": data.canonical_url,
+                                    "final_url": data.final_url,
                                     "site_name": data.site_name,
                                     "author": data.author,
                                     "published_at": data.published_at,
@@ -132,6 +183,7 @@ pub async fn handle(
                                     "word_count": data.word_count,
                                     "reading_time_minutes": data.reading_time_minutes,
                                     "extraction_score": data.extraction_score,
+                                    "quality": data.quality,
                                     "warnings": data.warnings,
                                     "truncated": data.truncated,
                                     "actual_chars": data.actual_chars,
@@ -151,7 +203,8 @@ pub async fn handle(
                             "url": item.url,
                             "success": item.success,
                             "duration_ms": item.duration_ms,
-                            "error": item.error
+                            "error": item.error,
+                            "duplicate_of": item.duplicate_of
                         })
                     }
                 })
@@ -162,7 +215,9 @@ pub async fn handle(
                 "successful": response.successful,
                 "failed": response.failed,
                 "total_duration_ms": response.total_duration_ms,
-                "results": normalized_results
+                "results": normalized_results,
+                "dropped_low_quality": response.dropped_low_quality,
+                "duplicates": response.duplicates
             });
 
             let json_str = serde_json::to_string_pretty(&normalized)
@@ -0,0 +1,43 @@
+use crate::core::error::ScoutError;
+use crate::mcp::{McpCallResponse, McpContent};
+use crate::scrape_compare::{self, ScrapeCompareOptions};
+use crate::types::ErrorResponse;
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::error;
+
+pub async fn handle(
+    state: Arc<AppState>,
+    arguments: &Value,
+) -> Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let url = arguments
+        .get("url")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: url").into_http())?;
+
+    let options = ScrapeCompareOptions {
+        use_proxy: arguments
+            .get("use_proxy")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+    };
+
+    let response = scrape_compare::scrape_compare(&state, url, options)
+        .await
+        .map_err(|e| {
+            error!("scrape_compare error for {}: {}", url, e);
+            ScoutError::classify(&e).into_http()
+        })?;
+
+    Ok(Json(McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text: serde_json::to_string_pretty(&response)
+                .unwrap_or_else(|e| format!(r#"{{"error": "Serialization failed: {}"}}"#, e)),
+        }],
+        is_error: false,
+    }))
+}
@@ -0,0 +1,48 @@
+use crate::core::error::ScoutError;
+use crate::mcp::{McpCallResponse, McpContent};
+use crate::scrape_diff::{self, ScrapeDiffOptions};
+use crate::types::ErrorResponse;
+use crate::AppState;
+use axum::http::StatusCode;
+use axum::response::Json;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::error;
+
+pub async fn handle(
+    state: Arc<AppState>,
+    arguments: &Value,
+) -> Result<Json<McpCallResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let url = arguments
+        .get("url")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: url").into_http())?;
+
+    let options = ScrapeDiffOptions {
+        use_proxy: arguments
+            .get("use_proxy")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        max_diff_chars: arguments
+            .get("max_diff_chars")
+            .and_then(|v| v.as_u64())
+            .map(|n| (n as usize).max(200)),
+        snapshot_dir: None,
+    };
+
+    let response = scrape_diff::scrape_diff(&state, url, options)
+        .await
+        .map_err(|e| {
+            error!("scrape_diff error for {}: {}", url, e);
+            ScoutError::classify(&e).into_http()
+        })?;
+
+    Ok(Json(McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text: serde_json::to_string_pretty(&response)
+                .unwrap_or_else(|e| format!(r#"{{"error": "Serialization failed: {}"}}"#, e)),
+        }],
+        is_error: false,
+    }))
+}
@@ -1,15 +1,24 @@
-use super::common::parse_quality_mode;
+use super::common::{
+    parse_auth, parse_domain_rate_limit, parse_max_selector_matches, parse_quality_mode,
+    parse_request_headers, parse_selectors,
+};
+use crate::core::error::ScoutError;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::rust_scraper::QualityMode;
+use crate::scraping::assets::AssetOptions;
+use crate::scraping::pagination;
+use crate::scraping::robots::RobotsError;
+use crate::scraping::sections::SectionOptions;
 use crate::types::{
-    AuthWallBlocked, CodeBlock, ErrorResponse, SniperCodeBlock, SniperMetadata, SniperOutput,
+    AuthWallBlocked, CodeBlock, ErrorResponse, ScrapeResponse, SniperCodeBlock, SniperMetadata,
+    SniperOutput,
 };
 use crate::{scrape, AppState};
 use axum::http::StatusCode;
 use axum::response::Json;
 use serde_json::Value;
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, warn};
 
 pub async fn handle(
     state: Arc<AppState>,
@@ -24,34 +33,24 @@ pub async fn handle(
     // - single (default): original scrape_url behavior
     // - batch: delegates to scrape_batch handler (requires `urls`)
     // - crawl: delegates to crawl_website handler (requires `url`)
+    // - links_only: single URL, returning only its links/images/headings
     match mode {
         "batch" => return super::scrape_batch::handle(state, arguments).await,
         "crawl" => return super::crawl_website::handle(state, arguments).await,
-        "single" => {}
+        "single" | "links_only" => {}
         other => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!(
-                        "Invalid mode '{}'. Valid values: single, batch, crawl",
-                        other
-                    ),
-                }),
-            ));
+            return Err(ScoutError::invalid_input(format!(
+                "Invalid mode '{}'. Valid values: single, batch, crawl, links_only",
+                other
+            ))
+            .into_http());
         }
     }
 
     let url = arguments
         .get("url")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: url".to_string(),
-                }),
-            )
-        })?;
+        .ok_or_else(|| ScoutError::invalid_input("Missing required parameter: url").into_http())?;
 
     let use_proxy = arguments
         .get("use_proxy")
@@ -95,6 +94,94 @@ pub async fn handle(
         .get("extract_app_state")
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
+    let respect_robots = arguments
+        .get("respect_robots")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let parse_feeds = arguments
+        .get("parse_feeds")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let max_attempts = arguments
+        .get("max_attempts")
+        .and_then(|v| v.as_u64())
+        .map(|n| n.min(10) as u32);
+    let auto_escalate = arguments.get("auto_escalate").and_then(|v| v.as_bool());
+    let persist_session = arguments
+        .get("persist_session")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let clear_session = arguments
+        .get("clear_session")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let screenshot = arguments
+        .get("screenshot")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let screenshot_full_page = arguments
+        .get("screenshot_full_page")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let screenshot_inline = arguments
+        .get("screenshot_inline")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let download_assets = arguments
+        .get("download_assets")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+        .then(|| AssetOptions {
+            dir: arguments
+                .get("asset_dir")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.trim().is_empty())
+                .map(std::path::PathBuf::from),
+            max_assets: arguments
+                .get("max_assets")
+                .and_then(|v| v.as_u64())
+                .map(|n| n.clamp(1, 100) as usize),
+            max_bytes: None,
+        });
+    let sections = arguments
+        .get("include_sections")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+        .then(|| {
+            let words = |key: &str| {
+                arguments
+                    .get(key)
+                    .and_then(|v| v.as_u64())
+                    .map(|n| n as usize)
+            };
+            SectionOptions::new(
+                words("section_target_words"),
+                words("section_overlap_words"),
+            )
+        });
+
+    let follow_pagination = if arguments
+        .get("follow_pagination")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        let max_pages = arguments.get("max_pages").and_then(|v| v.as_u64());
+        Some(pagination::max_pages(max_pages).map_err(ScoutError::into_http)?)
+    } else {
+        None
+    };
+
+    // Raw HTML only ever reaches `output_format: json`.
+    // 🧬 Task 1: Force-override — never return raw HTML when NeuroSiphon is
+    // active or quality_mode is aggressive.  Returning raw HTML under these
+    // modes is a massive token leak that defeats the token-saving architecture.
+    let include_raw_html = arguments
+        .get("include_raw_html")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+        && arguments.get("output_format").and_then(|v| v.as_str()) == Some("json")
+        && !crate::core::config::neurosiphon_enabled()
+        && quality_mode != QualityMode::Aggressive;
 
     let options = scrape::ScrapeUrlOptions {
         use_proxy,
@@ -106,9 +193,51 @@ pub async fn handle(
         extract_relevant_sections,
         section_limit,
         section_threshold,
+        respect_robots,
+        domain_rate_limit: parse_domain_rate_limit(arguments),
+        parse_feeds,
+        max_attempts,
+        auto_escalate,
+        download_assets,
+        bypass_cache: false,
+        revalidate: false,
+        persist_session,
+        clear_session,
+        auth: parse_auth(arguments)?,
+        headers: parse_request_headers(arguments)?,
+        links_only: mode == "links_only",
+        links_detail: arguments
+            .get("links_detail")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        max_links: arguments
+            .get("max_links")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize),
+        screenshot,
+        screenshot_full_page,
+        screenshot_inline,
+        include_raw_html,
+        selectors: parse_selectors(arguments)?,
+        max_selector_matches: parse_max_selector_matches(arguments),
+        prefer_language: arguments
+            .get("prefer_language")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string),
+        sections,
+        follow_pagination,
     };
 
-    match scrape::scrape_url_full(&state, url, options).await {
+    let scraped = scrape::scrape_url_full(&state, url, options).await;
+    // A scrape that still ends on a block page fails as `blocked`, as over HTTP.
+    if let Some(blocked) = scraped.as_ref().ok().and_then(scrape::block_error) {
+        warn!("Scrape blocked: {}", blocked);
+        return Err(blocked.into_http());
+    }
+    match scraped {
+        Ok(content) if mode == "links_only" => Ok(Json(links_only_response(&content))),
         Ok(mut content) => {
             let max_chars = arguments
                 .get("max_chars")
@@ -196,8 +325,8 @@ pub async fn handle(
                     .to_string();
 
                 // Auto-compute GitHub raw URL hint for blob pages and repo root pages.
-                // NOTE: rewrite_url_for_clean_content in scrape.rs already rewrites
-                // /blob/ and repo root → raw before the scrape, so this is an informational hint.
+                // NOTE: github_raw_candidates in scrape.rs already fetches /blob/ and repo
+                // root pages as raw text before the scrape, so this is an informational hint.
                 let github_raw_url = if url.contains("github.com") && url.contains("/blob/") {
                     let raw = url
                         .replace("github.com/", "raw.githubusercontent.com/")
@@ -277,30 +406,7 @@ pub async fn handle(
             }
 
             if output_format == "json" {
-                let mut include_raw_html = arguments
-                    .get("include_raw_html")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(false);
-
-                // 🧬 Task 1: Force-override — never return raw HTML when NeuroSiphon is
-                // active or quality_mode is aggressive.  Returning raw HTML under these
-                // modes is a massive token leak that defeats the token-saving architecture.
-                if crate::core::config::neurosiphon_enabled()
-                    || quality_mode == QualityMode::Aggressive
-                {
-                    include_raw_html = false;
-                }
-
-                let mut json_content = content.clone();
-                if !include_raw_html {
-                    json_content.content.clear();
-                    crate::content_quality::push_warning_unique(
-                        &mut json_content.warnings,
-                        "raw_html_omitted_in_scrape_url_json",
-                    );
-                }
-
-                let mut json_str = serde_json::to_string_pretty(&json_content)
+                let mut json_str = serde_json::to_string_pretty(&content)
                     .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e));
                 // FIX #1 — max_chars caps the TOTAL serialized JSON payload, not just the text
                 // extraction field. A CDP-rendered page can balloon to 93KB even with a 3000-char
@@ -330,7 +436,9 @@ pub async fn handle(
                 // FIX #2 — Media-Aware Auto-detection for raw .md / .mdx / .rst / .txt URLs.
                 // HTML extraction on raw text produces duplicate frontmatter appearing in BOTH
                 // key_paragraphs AND key_code_blocks. Skip the noisy HTML pipeline entirely.
-                if is_raw_content_url(url) {
+                if is_raw_content_url(url)
+                    || matches!(content.content_format.as_deref(), Some("markdown" | "text"))
+                {
                     let para_budget = max_chars.saturating_sub(1000).max(500);
                     let mut raw_para_total = 0usize;
                     let key_paragraphs: Vec<String> = content
@@ -367,7 +475,10 @@ pub async fn handle(
                             word_count: content.word_count,
                             extraction_score: content.extraction_score,
                             warnings: raw_warnings,
+                            asset_manifest: content.asset_manifest.clone(),
+                            screenshot_path: content.screenshot.as_ref().map(|s| s.path.clone()),
                         },
+                        selector_results: content.selector_results.clone(),
                     };
                     let json_str = serde_json::to_string_pretty(&sniper)
                         .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e));
@@ -488,7 +599,10 @@ pub async fn handle(
                         word_count: content.word_count,
                         extraction_score: content.extraction_score,
                         warnings: sniper_warnings,
+                        asset_manifest: content.asset_manifest.clone(),
+                        screenshot_path: content.screenshot.as_ref().map(|s| s.path.clone()),
                     },
+                    selector_results: content.selector_results.clone(),
                 };
 
                 let mut json_str = serde_json::to_string_pretty(&sniper)
@@ -527,20 +641,14 @@ pub async fn handle(
                         content.word_count
                     )
                 } else {
-                    let preview = content
-                        .clean_content
-                        .chars()
-                        .take(max_chars)
-                        .collect::<String>();
-                    if content.clean_content.len() > max_chars {
-                        format!(
+                    match crate::content_quality::truncate_markdown(&content.clean_content, max_chars) {
+                        Some(cut) => format!(
                             "{}\n\n[Content truncated: {}/{} chars shown. Increase max_chars parameter to see more]",
-                            preview,
+                            cut.text,
                             max_chars,
                             content.clean_content.len()
-                        )
-                    } else {
-                        preview
+                        ),
+                        None => content.clean_content.clone(),
                     }
                 };
 
@@ -586,8 +694,40 @@ pub async fn handle(
                     sources
                 };
 
+                let feed_section = if content.feed_items.is_empty() {
+                    String::new()
+                } else {
+                    let mut feed = format!("\n\nFeed items ({}):\n", content.feed_items.len());
+                    for item in &content.feed_items {
+                        feed.push_str(&format!(
+                            "- {} ({}) {}\n",
+                            item.title,
+                            item.published.as_deref().unwrap_or("-"),
+                            item.link.as_deref().unwrap_or("-")
+                        ));
+                    }
+                    feed
+                };
+                let mut asset_section = content
+                    .asset_manifest
+                    .as_deref()
+                    .map(|path| format!("\n\nAsset manifest: {}", path))
+                    .unwrap_or_default();
+                if let Some(shot) = &content.screenshot {
+                    asset_section.push_str(&format!(
+                        "\n\nScreenshot: {} ({} bytes)",
+                        shot.path, shot.bytes
+                    ));
+                }
+                if let Some(results) = &content.selector_results {
+                    asset_section.push_str(&format!(
+                        "\n\nSelector results:\n{}",
+                        serde_json::to_string_pretty(results).unwrap_or_default()
+                    ));
+                }
+
                 format!(
-                    "{}\nURL: {}\nCanonical: {}\nWord Count: {} ({}m)\nLanguage: {}\nSite: {}\nAuthor: {}\nPublished: {}\n\nDescription: {}\nOG Image: {}\n\nHeadings:\n{}\n\nLinks: {}  Images: {}\n\nPreview:\n{}{}{}",
+                    "{}\nURL: {}\nCanonical: {}\nWord Count: {} ({}m)\nLanguage: {}\nSite: {}\nAuthor: {}\nPublished: {}\n\nDescription: {}\nOG Image: {}\n\nHeadings:\n{}\n\nLinks: {}  Images: {}\n\nPreview:\n{}{}{}{}{}",
                     content.title,
                     content.url,
                     content.canonical_url.as_deref().unwrap_or("-"),
@@ -606,7 +746,9 @@ pub async fn handle(
                     content.images.len(),
                     content_preview,
                     image_preview_section,
-                    sources_section
+                    sources_section,
+                    feed_section,
+                    asset_section
                 )
             };
 
@@ -619,6 +761,18 @@ pub async fn handle(
             }))
         }
         Err(e) => {
+            if let Some(robots) = e.downcast_ref::<RobotsError>() {
+                return Ok(Json(McpCallResponse {
+                    content: vec![McpContent {
+                        content_type: "text".to_string(),
+                        text: format!("Scraping refused [{}]: {}", robots.code(), robots),
+                    }],
+                    is_error: true,
+                }));
+            }
+            if let Some(typed) = e.downcast_ref::<ScoutError>() {
+                return Err(typed.clone().into_http());
+            }
             error!("Scrape tool error: {}", e);
             Ok(Json(McpCallResponse {
                 content: vec![McpContent {
@@ -631,6 +785,38 @@ pub async fn handle(
     }
 }
 
+/// `mode: "links_only"` output: the page's link graph and outline as JSON, uncapped by
+/// `max_chars` since the links are the whole point of the call.
+fn links_only_response(content: &ScrapeResponse) -> McpCallResponse {
+    let mut body = serde_json::json!({
+        "url": content.url,
+        "title": content.title,
+        "canonical_url": content.canonical_url,
+        "status_code": content.status_code,
+        "links": content.links,
+        "images": content.images,
+        "headings": content.headings,
+        "warnings": content.warnings,
+    });
+    if let Some(reason) = &content.block_reason {
+        body["block_reason"] = serde_json::json!(reason);
+    }
+    if let Some(metrics) = &content.metrics {
+        body["metrics"] = serde_json::json!(metrics);
+    }
+    if !content.provenance.is_empty() {
+        body["provenance"] = serde_json::json!(content.provenance);
+    }
+    McpCallResponse {
+        content: vec![McpContent {
+            content_type: "text".to_string(),
+            text: serde_json::to_string_pretty(&body)
+                .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e)),
+        }],
+        is_error: false,
+    }
+}
+
 /// Returns `true` when the URL points to a raw text/data file where HTML extraction
 /// is unhelpful and produces noisy/duplicate content (Fix #2 — Media-Aware Auto-detection).
 fn is_raw_content_url(url: &str) -> bool {
@@ -1,4 +1,5 @@
 use super::common::parse_quality_mode;
+use crate::core::error::ScoutError;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::types::ErrorResponse;
 use crate::{scrape, search, AppState};
@@ -17,12 +18,7 @@ pub async fn handle(
         .get("query")
         .and_then(|v| v.as_str())
         .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: query".to_string(),
-                }),
-            )
+            ScoutError::invalid_input("Missing required parameter: query").into_http()
         })?;
 
     let top_n = arguments
@@ -39,12 +35,9 @@ pub async fn handle(
     let quality_mode = parse_quality_mode(arguments)?;
 
     let (results, extras) = search::search_web(&state, query).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Search failed: {}", e),
-            }),
-        )
+        ScoutError::classify(&e)
+            .context("Search failed")
+            .into_http()
     })?;
 
     let to_scrape: Vec<String> = results.iter().take(top_n).map(|r| r.url.clone()).collect();
@@ -1,3 +1,4 @@
+use crate::core::error::ScoutError;
 use crate::mcp::{McpCallResponse, McpContent};
 use crate::types::ErrorResponse;
 use crate::{search, AppState};
@@ -25,19 +26,16 @@ pub async fn handle(
         .get("query")
         .and_then(|v| v.as_str())
         .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing required parameter: query".to_string(),
-                }),
-            )
+            ScoutError::invalid_input("Missing required parameter: query").into_http()
         })?;
 
-    let max_results = arguments
+    // An explicit max_results also caps the merged result list server-side
+    // (applied after reranking), so it participates in the cache key.
+    let requested_max_results = arguments
         .get("max_results")
         .and_then(|v| v.as_u64())
-        .map(|n| n as usize)
-        .unwrap_or(10);
+        .map(|n| n as usize);
+    let max_results = requested_max_results.unwrap_or(10);
     // Dynamic snippet length — controls how many chars of each result's content snippet
     // are displayed. Default: 120 (NeuroSiphon mode) / 200 (standard), but agents can
     // override for deep research (bigger snippets) or token-constrained tasks (smaller).
@@ -46,6 +44,60 @@ pub async fn handle(
         .and_then(|v| v.as_u64())
         .map(|n| n as usize);
 
+    // Machine-readable output (json / opensearch / csv) instead of the agent-oriented text.
+    let format = match arguments.get("format").and_then(|v| v.as_str()) {
+        None => None,
+        Some(raw) => Some(search::SearchOutputFormat::parse_str(raw).ok_or_else(|| {
+            ScoutError::invalid_input(format!(
+                "Unknown format '{}': expected json, opensearch or csv",
+                raw
+            ))
+            .into_http()
+        })?),
+    };
+
+    // Query-term offsets on each result; only the structured formats carry them.
+    let highlights = arguments
+        .get("highlights")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let reuse_recent = match arguments.get("reuse_recent").and_then(|v| v.as_str()) {
+        None => None,
+        Some(raw) => Some(search::ReuseRecent::parse_str(raw).ok_or_else(|| {
+            ScoutError::invalid_input(format!(
+                "Unknown reuse_recent '{}': expected auto, always or never",
+                raw
+            ))
+            .into_http()
+        })?),
+    };
+
+    let string_list = |key: &str| -> Vec<String> {
+        arguments
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|s| s.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    // Response shaping: trimmed snippets, a field whitelist (JSON) and a byte cap that
+    // drops trailing results instead of overflowing the client's message limit.
+    let snippet_max_chars = arguments
+        .get("snippet_max_chars")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize);
+    let include_fields = search::shape::parse_include_fields(&string_list("include_fields"))
+        .map_err(ScoutError::into_http)?;
+    let max_response_bytes = arguments
+        .get("max_response_bytes")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize);
+
     let overrides = search::SearchParamOverrides {
         engines: arguments
             .get("engines")
@@ -59,6 +111,10 @@ pub async fn handle(
             .get("language")
             .and_then(|v| v.as_str())
             .map(|s| s.to_string()),
+        language_mode: arguments
+            .get("language_mode")
+            .and_then(|v| v.as_str())
+            .and_then(search::LanguageFilterMode::parse_str),
         safesearch: arguments
             .get("safesearch")
             .and_then(|v| v.as_i64())
@@ -77,7 +133,13 @@ pub async fn handle(
             .get("pageno")
             .and_then(|v| v.as_u64())
             .and_then(|n| if n >= 1 { Some(n as u32) } else { None }),
+        max_results: requested_max_results,
         disable_recovery: false,
+        reuse_recent,
+        include_domains: string_list("include_domains"),
+        exclude_domains: string_list("exclude_domains"),
+        exclude_url_patterns: string_list("exclude_url_patterns"),
+        snippet_max_chars,
     };
 
     let has_overrides = overrides.engines.is_some()
@@ -85,30 +147,79 @@ pub async fn handle(
         || overrides.language.is_some()
         || overrides.safesearch.is_some()
         || overrides.time_range.is_some()
-        || overrides.pageno.is_some();
+        || overrides.pageno.is_some()
+        || overrides.max_results.is_some()
+        || overrides.reuse_recent.is_some()
+        || !overrides.include_domains.is_empty()
+        || !overrides.exclude_domains.is_empty()
+        || !overrides.exclude_url_patterns.is_empty()
+        || overrides.snippet_max_chars.is_some();
 
-    let (results, extras) = search::search_web_with_params(
+    let (results, mut extras) = search::search_web_with_params(
         &state,
         query,
         if has_overrides { Some(overrides) } else { None },
     )
     .await
     .map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Search failed: {}", e),
-            }),
-        )
+        ScoutError::classify(&e)
+            .context("Search failed")
+            .into_http()
     })?;
 
+    if let Some(format) = format {
+        let (deduped_indexes, _) =
+            crate::content_quality::dedupe_search_result_indexes(&results, 140);
+        let mut selected: Vec<_> = deduped_indexes
+            .iter()
+            .take(max_results)
+            .map(|&i| results[i].clone())
+            .collect();
+        if highlights {
+            let terms = search::highlight_terms(query, extras.query_rewrite.as_ref());
+            search::annotate_highlights(&mut selected, &terms);
+        }
+        let text = match max_response_bytes {
+            Some(max_bytes) => {
+                search::shape::fit_to_bytes(&mut selected, max_bytes, |kept, dropped| {
+                    search::shape::render(format, query, kept, &include_fields, dropped)
+                })
+                .0
+            }
+            None => search::shape::render(format, query, &selected, &include_fields, 0),
+        };
+        return Ok(Json(McpCallResponse {
+            content: vec![McpContent {
+                content_type: "text".to_string(),
+                text,
+            }],
+            is_error: false,
+        }));
+    }
+
     let search_id = Uuid::new_v4().to_string();
 
+    // Instant answers (definitions, conversions) lead the output, before any result list.
+    let mut answers_text = String::new();
+    if extras.from_history {
+        answers_text.push_str(&format!(
+            "♻️ from_history: results of a similar search {} min ago (reuse_recent=never searches live).\n\n",
+            extras.history_age_secs.unwrap_or(0) / 60
+        ));
+    }
+    if !extras.answers.is_empty() {
+        answers_text.push_str("**Instant Answers:**\n");
+        for answer in &extras.answers {
+            answers_text.push_str(&format!("📌 {}\n\n", answer));
+        }
+    }
+
     let content_text = if results.is_empty() {
-        let mut text = format!(
-            "Search ID: {}\nNo search results found for query: '{}'\n\n",
-            search_id, query
-        );
+        let mut text = format!("Search ID: {}\n{}", search_id, answers_text);
+        text.push_str(&format!(
+            "No search results found for query: '{}'\n\n",
+            query
+        ));
 
         if !extras.suggestions.is_empty() {
             text.push_str(&format!(
@@ -122,11 +233,8 @@ pub async fn handle(
                 extras.corrections.join(", ")
             ));
         }
-        if !extras.degraded_engines.is_empty() {
-            text.push_str(&format!(
-                "\n**Degraded engines:** {}\n",
-                extras.degraded_engines.join(", ")
-            ));
+        if let Some(diagnostics) = extras.engine_diagnostics() {
+            text.push_str(&format!("\n**Engine diagnostics:** {}\n", diagnostics));
         }
         if !extras.skipped_engines.is_empty() {
             text.push_str(&format!(
@@ -134,30 +242,28 @@ pub async fn handle(
                 extras.skipped_engines.join(", ")
             ));
         }
+        if let Some(summary) = filtered_summary(&extras.filtered_out) {
+            text.push_str(&format!("**Filtered out:** {}\n", summary));
+        }
         text
     } else {
         let (deduped_indexes, duplicate_removed) =
             crate::content_quality::dedupe_search_result_indexes(&results, 140);
         let result_count = deduped_indexes.len();
-        let limited_results = deduped_indexes.iter().take(max_results);
+        let limited_results: Vec<_> = deduped_indexes.iter().take(max_results).collect();
+        let shown = limited_results.len();
 
-        let mut text = format!(
-            "Search ID: {}\nFound {} search results for '{}':",
-            search_id, result_count, query
-        );
+        let mut text = format!("Search ID: {}\n{}", search_id, answers_text);
+        text.push_str(&format!(
+            "Found {} search results for '{}':",
+            result_count, query
+        ));
         if result_count > max_results {
             text.push_str(&format!(" (showing top {})\n", max_results));
         }
         text.push_str("\n\n");
 
-        if !extras.answers.is_empty() {
-            text.push_str("**Instant Answers:**\n");
-            for answer in &extras.answers {
-                text.push_str(&format!("📌 {}\n\n", answer));
-            }
-        }
-
-        for (i, result_index) in limited_results.enumerate() {
+        for (i, result_index) in limited_results.into_iter().enumerate() {
             let result = &results[*result_index];
             let engine = result.engine.as_deref().unwrap_or("-");
             let domain = result.domain.as_deref().unwrap_or("-");
@@ -167,7 +273,7 @@ pub async fn handle(
                 .score
                 .map(|s| format!("{:.3}", s))
                 .unwrap_or_else(|| "-".to_string());
-            text.push_str(&format!(
+            let entry = format!(
                 "{}. **{}**\n   URL: {}\n   Engine: {} | Domain: {} | Type: {} | Published: {} | Score: {}\n   Snippet: {}\n\n",
                 i + 1,
                 result.title,
@@ -185,6 +291,18 @@ pub async fn handle(
                     });
                     result.content.chars().take(limit).collect::<String>()
                 }
+            );
+            // The byte cap keeps whole entries only; at least the first one is always shown.
+            if max_response_bytes.is_some_and(|max| i > 0 && text.len() + entry.len() > max) {
+                extras.truncated_results = shown - i;
+                break;
+            }
+            text.push_str(&entry);
+        }
+        if extras.truncated_results > 0 {
+            text.push_str(&format!(
+                "✂️ truncated_results: {} (dropped to fit max_response_bytes)\n",
+                extras.truncated_results
             ));
         }
 
@@ -195,17 +313,20 @@ pub async fn handle(
             ));
         }
 
+        if !extras.corrections.is_empty() {
+            text.push_str(&format!(
+                "\n**Did you mean:** {}\n",
+                extras.corrections.join(", ")
+            ));
+        }
         if !extras.suggestions.is_empty() {
             text.push_str(&format!(
                 "\n**Related searches:** {}\n",
                 extras.suggestions.join(", ")
             ));
         }
-        if !extras.degraded_engines.is_empty() {
-            text.push_str(&format!(
-                "\n⚠️ **Degraded engines:** {}\n",
-                extras.degraded_engines.join(", ")
-            ));
+        if let Some(diagnostics) = extras.engine_diagnostics() {
+            text.push_str(&format!("\n⚠️ **Engine diagnostics:** {}\n", diagnostics));
         }
         if !extras.skipped_engines.is_empty() {
             text.push_str(&format!(
@@ -213,6 +334,15 @@ pub async fn handle(
                 extras.skipped_engines.join(", ")
             ));
         }
+        if !extras.time_filtered_engines.is_empty() {
+            text.push_str(&format!(
+                "**Time-filtered engines:** {}\n",
+                extras.time_filtered_engines.join(", ")
+            ));
+        }
+        if let Some(summary) = filtered_summary(&extras.filtered_out) {
+            text.push_str(&format!("**Filtered out:** {}\n", summary));
+        }
 
         text
     };
@@ -225,3 +355,26 @@ pub async fn handle(
         is_error: false,
     }))
 }
+
+/// e.g. `3 result(s) (2 outside include_domains, 1 by exclude_url_patterns)`.
+fn filtered_summary(counts: &search::FilteredCounts) -> Option<String> {
+    if counts.total() == 0 {
+        return None;
+    }
+    let parts: Vec<String> = [
+        (counts.not_included, "outside include_domains"),
+        (counts.excluded_domains, "by exclude_domains"),
+        (counts.excluded_patterns, "by exclude_url_patterns"),
+        (counts.wrong_filetype, "not matching filetype:"),
+        (counts.outside_date_window, "outside after:/before:"),
+    ]
+    .iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, label)| format!("{} {}", n, label))
+    .collect();
+    Some(format!(
+        "{} result(s) ({})",
+        counts.total(),
+        parts.join(", ")
+    ))
+}
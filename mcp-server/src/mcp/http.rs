@@ -1,14 +1,18 @@
 use super::handlers;
+use crate::core::error::{ErrorResponses, ScoutError};
+use crate::core::request_id;
 use crate::types::*;
+use crate::mcp::cancellation::CancellationToken;
 use crate::AppState;
 use axum::{extract::State, http::StatusCode, response::Json};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use tracing::info;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct McpTool {
     pub name: String,
     pub title: String,
@@ -19,25 +23,25 @@ pub struct McpTool {
     pub icons: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct McpToolsResponse {
     pub tools: Vec<McpTool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct McpCallRequest {
     pub name: String,
     pub arguments: serde_json::Value,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct McpCallResponse {
     pub content: Vec<McpContent>,
     #[serde(rename = "isError")]
     pub is_error: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct McpContent {
     #[serde(rename = "type")]
     pub content_type: String,
@@ -50,6 +54,7 @@ pub(crate) fn instrument_tool_response(
     started_at: Instant,
 ) -> McpCallResponse {
     let elapsed = started_at.elapsed();
+    let request_id = request_id::current();
     let metrics_json = json!({
         "tool_name": tool_name,
         "total_duration_ms": elapsed.as_millis() as u64,
@@ -64,6 +69,9 @@ pub(crate) fn instrument_tool_response(
         if let Ok(mut value) = serde_json::from_str::<serde_json::Value>(&item.text) {
             if let Some(obj) = value.as_object_mut() {
                 obj.insert("_tool_metrics".to_string(), metrics_json.clone());
+                if let Some(id) = &request_id {
+                    obj.insert("request_id".to_string(), json!(id));
+                }
                 item.text = serde_json::to_string_pretty(&value).unwrap_or_else(|_| item.text.clone());
                 continue;
             }
@@ -75,6 +83,9 @@ pub(crate) fn instrument_tool_response(
                 elapsed.as_secs_f64(),
                 elapsed.as_millis() as u64
             ));
+            if let Some(id) = &request_id {
+                item.text.push_str(&format!("\nRequest ID: {}", id));
+            }
         }
     }
 
@@ -98,6 +109,12 @@ pub fn list_tools_for_state(state: &AppState) -> McpToolsResponse {
     McpToolsResponse { tools }
 }
 
+#[utoipa::path(
+    get,
+    path = "/mcp/tools",
+    tag = "mcp",
+    responses((status = 200, description = "Tools this server exposes, with their input schemas", body = McpToolsResponse))
+)]
 pub async fn list_tools(State(state): State<Arc<AppState>>) -> Json<McpToolsResponse> {
     Json(list_tools_for_state(state.as_ref()))
 }
@@ -106,6 +123,15 @@ pub async fn list_tools(State(state): State<Arc<AppState>>) -> Json<McpToolsResp
 pub async fn call_tool_inner(
     state: Arc<AppState>,
     request: McpCallRequest,
+) -> Result<McpCallResponse, (StatusCode, Json<ErrorResponse>)> {
+    call_tool_cancellable(state, request, CancellationToken::new()).await
+}
+
+/// [`call_tool_inner`] for a call the client can cancel through `cancel`.
+pub async fn call_tool_cancellable(
+    state: Arc<AppState>,
+    request: McpCallRequest,
+    cancel: CancellationToken,
 ) -> Result<McpCallResponse, (StatusCode, Json<ErrorResponse>)> {
     let tool_start = Instant::now();
     let request_name = request.name.clone();
@@ -124,67 +150,29 @@ pub async fn call_tool_inner(
             _ => None,
         })
         .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Unknown tool: {}", request_name),
-                }),
-            )
+            ScoutError::invalid_input(format!("Unknown tool: {}", request_name)).into_http()
         })?;
 
     let internal_args = state
         .tool_registry
         .map_public_arguments_to_internal(&internal_name, request.arguments);
 
-    let tool_timeout = Duration::from_secs(crate::core::config::mcp_tool_timeout_secs(&internal_name));
-    let dispatch_name = internal_name.clone();
-    let state_for_dispatch = Arc::clone(&state);
-    let request_name_for_dispatch = request_name.clone();
-
-    let dispatch = async move {
-        match dispatch_name.as_str() {
-            "search_web" => handlers::search_web::handle(state_for_dispatch, &internal_args).await,
-            "search_structured" => handlers::search_structured::handle(state_for_dispatch, &internal_args).await,
-            "scrape_url" => handlers::scrape_url::handle(state_for_dispatch, &internal_args).await,
-            "crawl_website" => handlers::crawl_website::handle(state_for_dispatch, &internal_args).await,
-            "scrape_batch" => handlers::scrape_batch::handle(state_for_dispatch, &internal_args).await,
-            "deep_research" => handlers::deep_research::handle(state_for_dispatch, &internal_args).await,
-            "extract_structured" => handlers::extract_structured::handle(state_for_dispatch, &internal_args).await,
-            "fetch_then_extract" => handlers::fetch_then_extract::handle(state_for_dispatch, &internal_args).await,
-            "research_history" => handlers::research_history::handle(state_for_dispatch, &internal_args).await,
-            "proxy_manager" => handlers::proxy_manager::handle(state_for_dispatch, &internal_args).await,
-            "non_robot_search" => handlers::non_robot_search::handle(state_for_dispatch, &internal_args).await,
-            "visual_scout" => handlers::visual_scout::handle(state_for_dispatch, &internal_args).await,
-            "human_auth_session" => handlers::human_auth_session::handle(state_for_dispatch, &internal_args).await,
-            "browser_automate" | "scout_browser_automate" => {
-                handlers::automate::handle(state_for_dispatch, &internal_args).await
-            }
-            "browser_close" | "scout_browser_close" => {
-                handlers::automate::handle_close(state_for_dispatch, &internal_args).await
-            }
-            "agent_profile_auth" | "scout_agent_profile_auth" => {
-                handlers::automate::handle_profile_auth(state_for_dispatch, &internal_args).await
-            }
-            _ => Err((
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: format!("Unknown tool: {}", request_name_for_dispatch),
-                }),
-            )),
-        }
-    };
-
-    match tokio::time::timeout(tool_timeout, dispatch).await {
-        Ok(result) => result.map(|Json(r)| instrument_tool_response(r, &request_name, tool_start)),
-        Err(_) => Ok(instrument_tool_response(
-            super::timeout::timeout_call_response(&request_name, tool_timeout),
-            &request_name,
-            tool_start,
-        )),
-    }
+    handlers::run_call(state, &internal_name, &request_name, &internal_args, cancel)
+        .await
+        .map(|Json(r)| instrument_tool_response(r, &request_name, tool_start))
 }
 
 /// Axum route handler: `POST /mcp/call`
+#[utoipa::path(
+    post,
+    path = "/mcp/call",
+    tag = "mcp",
+    request_body = McpCallRequest,
+    responses(
+        (status = 200, description = "Tool output; `isError` marks a tool-level failure", body = McpCallResponse),
+        ErrorResponses
+    )
+)]
 pub async fn call_tool(
     State(state): State<Arc<AppState>>,
     Json(request): Json<McpCallRequest>,
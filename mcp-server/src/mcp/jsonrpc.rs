@@ -0,0 +1,382 @@
+//! JSON-RPC 2.0 message handling for the streamable HTTP (`POST /mcp`) and WebSocket
+//! (`GET /mcp/ws`) transports.
+//!
+//! Tool calls go through the same [`call_tool_inner`] dispatch as `POST /mcp/call`, and
+//! results/errors are converted with the stdio transport's helpers, so an MCP client sees
+//! identical content blocks and error codes whichever transport it speaks.
+//!
+//! `resources/list` and `resources/read` are served by [`super::resources`], like on stdio.
+//!
+//! Tool calls run under the request ID of the transport (the HTTP request's, or one per
+//! WebSocket message) unless `params._meta.requestId` names one; results echo it as
+//! `request_id`.
+//!
+//! `tools/call` requests are tracked by id in a [`CallRegistry`] (the server-wide
+//! `AppState::mcp_calls` for `POST /mcp`, one per socket for WebSockets) so that
+//! `notifications/cancelled` — or the older `$/cancelRequest` — can cancel them.
+
+use super::cancellation::CallRegistry;
+use super::http::{call_tool_cancellable, list_tools_for_state, McpCallRequest};
+use super::resources;
+use super::stdio::{error_response_to_error_data, mcp_call_response_to_stdio_result};
+use crate::core::request_id;
+use crate::AppState;
+use axum::response::Json;
+use rmcp::model::{ErrorCode, ErrorData};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::debug;
+
+fn success(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn failure(id: Value, error: ErrorData) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+fn invalid_params(message: impl Into<String>) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_PARAMS, message.into(), None)
+}
+
+/// `tools/call` params: `name` is required, `arguments` may be omitted or `null`.
+fn parse_call_params(params: Option<&Value>) -> Result<McpCallRequest, ErrorData> {
+    let Some(params) = params.and_then(Value::as_object) else {
+        return Err(invalid_params("tools/call params must be an object"));
+    };
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .filter(|name| !name.trim().is_empty())
+        .ok_or_else(|| invalid_params("tools/call params.name must be a non-empty string"))?;
+    let arguments = match params.get("arguments") {
+        None | Some(Value::Null) => json!({}),
+        Some(args @ Value::Object(_)) => args.clone(),
+        Some(_) => {
+            return Err(invalid_params(
+                "tools/call params.arguments must be an object",
+            ))
+        }
+    };
+    Ok(McpCallRequest {
+        name: name.to_string(),
+        arguments,
+    })
+}
+
+fn to_result<T: serde::Serialize>(value: T) -> Result<Value, ErrorData> {
+    serde_json::to_value(value).map_err(|e| ErrorData::internal_error(e.to_string(), None))
+}
+
+async fn list_resources(state: &AppState, params: Option<&Value>) -> Result<Value, ErrorData> {
+    let cursor = match params.and_then(|p| p.get("cursor")) {
+        None | Some(Value::Null) => None,
+        Some(Value::String(cursor)) => Some(cursor.as_str()),
+        Some(_) => {
+            return Err(invalid_params(
+                "resources/list params.cursor must be a string",
+            ))
+        }
+    };
+    to_result(resources::list(state, cursor).await?)
+}
+
+async fn read_resource(state: &AppState, params: Option<&Value>) -> Result<Value, ErrorData> {
+    let uri = params
+        .and_then(|p| p.get("uri"))
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid_params("resources/read params.uri must be a string"))?;
+    to_result(resources::read(state, uri).await?)
+}
+
+async fn call_tool(
+    state: Arc<AppState>,
+    calls: &CallRegistry,
+    id: &Value,
+    params: Option<&Value>,
+) -> Result<Value, ErrorData> {
+    let request = parse_call_params(params)?;
+    let call = calls.register(id);
+    let run = call_tool_cancellable(state, request, call.token.clone());
+    // A client-chosen `_meta.requestId` replaces the one the transport assigned.
+    let outcome = match request_id::from_meta(params.and_then(|p| p.get("_meta"))) {
+        Some(chosen) => request_id::scope(chosen, run).await,
+        None => run.await,
+    };
+    match outcome {
+        Ok(response) => to_result(mcp_call_response_to_stdio_result(response)),
+        Err((_, Json(err))) => Err(error_response_to_error_data(err)),
+    }
+}
+
+/// Cancel the call a `notifications/cancelled` (`params.requestId`) or `$/cancelRequest`
+/// (`params.id`) message points at. Unknown or finished ids are ignored.
+fn cancel_call(calls: &CallRegistry, message: &Value) {
+    let request_id = message
+        .pointer("/params/requestId")
+        .or_else(|| message.pointer("/params/id"));
+    if let Some(request_id) = request_id {
+        if calls.cancel(request_id) {
+            debug!("MCP call {} cancelled by client", request_id);
+        }
+    }
+}
+
+/// Handle one JSON-RPC message. Notifications (no `id`, e.g. `notifications/initialized`)
+/// are accepted silently and yield `None`; every request yields a response object.
+pub async fn handle_message(state: Arc<AppState>, message: Value) -> Option<Value> {
+    let calls = Arc::clone(&state.mcp_calls);
+    handle_message_in(state, &calls, message).await
+}
+
+/// [`handle_message`] with tool calls tracked in `calls`.
+pub async fn handle_message_in(
+    state: Arc<AppState>,
+    calls: &CallRegistry,
+    message: Value,
+) -> Option<Value> {
+    let method = message.get("method").and_then(Value::as_str);
+    if matches!(method, Some("notifications/cancelled" | "$/cancelRequest")) {
+        cancel_call(calls, &message);
+        return None;
+    }
+    let id = message.get("id").cloned()?;
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(failure(
+            id,
+            ErrorData::new(ErrorCode::INVALID_REQUEST, "Missing method", None),
+        ));
+    };
+
+    let outcome = match method {
+        "initialize" => {
+            let mut capabilities = json!({ "tools": {} });
+            if resources::available(&state) {
+                capabilities["resources"] = json!({});
+            }
+            Ok(json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": capabilities,
+                "serverInfo": {
+                    "name": "Cortex Scout",
+                    "version": env!("CARGO_PKG_VERSION")
+                }
+            }))
+        }
+        "ping" => Ok(json!({})),
+        "tools/list" => to_result(list_tools_for_state(state.as_ref())),
+        "resources/list" => list_resources(&state, message.get("params")).await,
+        "resources/read" => read_resource(&state, message.get("params")).await,
+        "tools/call" => call_tool(state, calls, &id, message.get("params")).await,
+        _ => Err(ErrorData::new(
+            ErrorCode::METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+            None,
+        )),
+    };
+
+    Some(match outcome {
+        Ok(result) => success(id, result),
+        Err(error) => failure(id, error),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> Arc<AppState> {
+        Arc::new(AppState::new(reqwest::Client::new()))
+    }
+
+    async fn rpc(message: Value) -> Value {
+        handle_message(state(), message)
+            .await
+            .expect("requests always get a response")
+    }
+
+    #[tokio::test]
+    async fn initialized_notification_gets_no_response() {
+        let note = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(handle_message(state(), note).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn search_call_returns_stdio_shaped_content() {
+        let response = rpc(json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "tools/call",
+            "params": {
+                "name": "web_search",
+                "arguments": { "query": "rust async runtimes", "max_results": 0 }
+            }
+        }))
+        .await;
+
+        assert_eq!(response["id"], 7);
+        let result = &response["result"];
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["content"][0]["type"], "text");
+        assert!(result["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("No search results found"));
+        serde_json::from_value::<rmcp::model::CallToolResult>(result.clone())
+            .expect("result deserializes as an MCP CallToolResult");
+    }
+
+    #[tokio::test]
+    async fn meta_request_id_is_echoed_in_the_result() {
+        let call = |meta: Value| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 8,
+                "method": "tools/call",
+                "params": {
+                    "name": "web_search",
+                    "arguments": { "query": "rust async runtimes", "max_results": 0 },
+                    "_meta": meta
+                }
+            })
+        };
+        let text = |response: &Value| response["result"]["content"][0]["text"].to_string();
+
+        let response = rpc(call(json!({ "requestId": "trace-42" }))).await;
+        assert!(text(&response).contains("Request ID: trace-42"));
+
+        // Without one, the call keeps the transport's ID.
+        let response = request_id::scope(
+            "ws-abc-8".to_string(),
+            rpc(call(json!({ "progressToken": 1 }))),
+        )
+        .await;
+        assert!(text(&response).contains("Request ID: ws-abc-8"));
+    }
+
+    #[tokio::test]
+    async fn malformed_params_are_invalid_params() {
+        for params in [
+            json!(null),
+            json!({ "arguments": {} }),
+            json!({ "name": "web_search", "arguments": "query=rust" }),
+        ] {
+            let response = rpc(json!({
+                "jsonrpc": "2.0",
+                "id": "a",
+                "method": "tools/call",
+                "params": params
+            }))
+            .await;
+            assert_eq!(response["error"]["code"], -32602, "params: {}", params);
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_notifications_cancel_the_registered_call() {
+        let calls = CallRegistry::default();
+        let numbered = calls.register(&json!(3));
+        let named = calls.register(&json!("req-9"));
+
+        for (message, token) in [
+            (
+                json!({ "jsonrpc": "2.0", "method": "notifications/cancelled", "params": { "requestId": 3, "reason": "user" } }),
+                &numbered.token,
+            ),
+            (
+                json!({ "jsonrpc": "2.0", "method": "$/cancelRequest", "params": { "id": "req-9" } }),
+                &named.token,
+            ),
+        ] {
+            assert!(handle_message_in(state(), &calls, message).await.is_none());
+            assert!(token.is_cancelled());
+        }
+
+        // A stray cancel for an unknown id is ignored.
+        let note = json!({ "jsonrpc": "2.0", "method": "notifications/cancelled", "params": { "requestId": 99 } });
+        assert!(handle_message_in(state(), &calls, note).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn timeout_ms_bounds_the_call() {
+        let response = rpc(json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "tools/call",
+            "params": {
+                "name": "web_fetch",
+                "arguments": { "url": "https://slow.invalid/page", "timeout_ms": 1 }
+            }
+        }))
+        .await;
+        let result = &response["result"];
+        assert_eq!(result["isError"], true);
+        let body: Value =
+            serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body["status"], "timeout");
+        assert_eq!(body["timeout_ms"], 1);
+    }
+
+    #[tokio::test]
+    async fn tool_errors_keep_taxonomy_codes() {
+        let response = rpc(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": "web_search", "arguments": {} }
+        }))
+        .await;
+        assert_eq!(response["error"]["code"], -32602);
+        assert_eq!(response["error"]["data"]["code"], "invalid_input");
+
+        let response = rpc(json!({ "jsonrpc": "2.0", "id": 2, "method": "prompts/list" })).await;
+        assert_eq!(response["error"]["code"], -32601);
+    }
+
+    #[tokio::test]
+    async fn resources_are_advertised_once_there_is_something_to_serve() {
+        let state = state();
+        state.mark_memory_failed();
+        let initialize = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize" });
+        let response = handle_message(Arc::clone(&state), initialize.clone())
+            .await
+            .unwrap();
+        assert!(response["result"]["capabilities"]
+            .get("resources")
+            .is_none());
+
+        let page = crate::types::ScrapeResponse::for_test(
+            "https://example.com/",
+            "Example",
+            "# Example\n\nHello.",
+        );
+        state.scrape_cache.insert("key".to_string(), page).await;
+        state.scrape_cache.run_pending_tasks().await;
+
+        let response = handle_message(Arc::clone(&state), initialize)
+            .await
+            .unwrap();
+        assert_eq!(response["result"]["capabilities"]["resources"], json!({}));
+
+        let list = json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/list", "params": {} });
+        let response = handle_message(Arc::clone(&state), list).await.unwrap();
+        let resource = &response["result"]["resources"][0];
+        assert_eq!(resource["name"], "Example");
+        assert_eq!(resource["mimeType"], "text/markdown");
+
+        let read = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "resources/read",
+            "params": { "uri": resource["uri"] }
+        });
+        let response = handle_message(Arc::clone(&state), read).await.unwrap();
+        let contents = &response["result"]["contents"][0];
+        assert_eq!(contents["text"], "# Example\n\nHello.");
+        assert_eq!(contents["_meta"]["url"], "https://example.com/");
+
+        let read = json!({ "jsonrpc": "2.0", "id": 4, "method": "resources/read", "params": {} });
+        let response = handle_message(state, read).await.unwrap();
+        assert_eq!(response["error"]["code"], -32602);
+    }
+}
@@ -1,11 +1,15 @@
+pub mod cancellation;
 pub mod handlers;
 pub mod http;
+pub mod jsonrpc;
+pub mod resources;
 pub mod stdio;
 pub mod timeout;
 pub mod tooling;
+pub mod ws;
 
 // Preserve existing call sites: cortex_scout::mcp::{list_tools, call_tool, call_tool_inner}
 pub use http::{
-    call_tool, call_tool_inner, list_tools, McpCallRequest, McpCallResponse, McpContent, McpTool,
-    McpToolsResponse,
+    call_tool, call_tool_cancellable, call_tool_inner, list_tools, McpCallRequest, McpCallResponse,
+    McpContent, McpTool, McpToolsResponse,
 };
@@ -0,0 +1,384 @@
+//! MCP resources: what the server already has, browsable without another fetch.
+//!
+//! Recent scrapes in `scrape_cache` are listed as `shadowcrawl://scrape/<sha256 of url>`
+//! and read back as `text/markdown` (the clean content, metadata under `_meta`). When
+//! semantic memory is up, recent `web_search` history is listed as
+//! `shadowcrawl://search/<entry id>` and read back as JSON. Both are capped, the list is
+//! paged with an offset cursor, and contents are cut to `MAX_CONTENT_CHARS` like tool
+//! output. stdio and the JSON-RPC transports serve the same results from here.
+
+use crate::content_quality::truncate_markdown;
+use crate::history::{EntryType, HistoryEntry};
+use crate::types::ScrapeResponse;
+use crate::AppState;
+use rmcp::model::{
+    AnnotateAble, ErrorData, ListResourcesResult, Meta, RawResource, ReadResourceResult, Resource,
+    ResourceContents,
+};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+pub const SCRAPE_URI_PREFIX: &str = "shadowcrawl://scrape/";
+pub const SEARCH_URI_PREFIX: &str = "shadowcrawl://search/";
+/// Resources per `resources/list` page.
+pub const PAGE_SIZE: usize = 50;
+/// Most recent scrapes listed.
+pub const MAX_LISTED_SCRAPES: usize = 100;
+/// Most recent searches listed.
+pub const MAX_LISTED_SEARCHES: usize = 100;
+
+/// Whether `initialize` should advertise resources: something is cached, or memory is
+/// (or will shortly be) available to list search history from.
+pub fn available(state: &AppState) -> bool {
+    state.scrape_cache.iter().next().is_some()
+        || state.get_memory().is_some()
+        || state.is_memory_pending()
+}
+
+fn url_hash(url: &str) -> String {
+    hex::encode(Sha256::digest(url.as_bytes()))
+}
+
+/// The newest cached result per URL that has text to serve, newest first.
+fn recent_scrapes(state: &AppState) -> Vec<ScrapeResponse> {
+    let mut by_url: HashMap<String, ScrapeResponse> = HashMap::new();
+    for (_, page) in state.scrape_cache.iter() {
+        if page.clean_content.trim().is_empty() {
+            continue;
+        }
+        match by_url.get(&page.url) {
+            Some(kept) if kept.timestamp >= page.timestamp => {}
+            _ => {
+                by_url.insert(page.url.clone(), page);
+            }
+        }
+    }
+    let mut pages: Vec<ScrapeResponse> = by_url.into_values().collect();
+    pages.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then(a.url.cmp(&b.url)));
+    pages
+}
+
+async fn recent_searches(state: &AppState, limit: usize) -> Vec<HistoryEntry> {
+    let Some(memory) = state.get_memory() else {
+        return Vec::new();
+    };
+    match memory.recent_entries(EntryType::Search, limit).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("resources/list: search history unavailable: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn scrape_resource(page: &ScrapeResponse) -> Resource {
+    let name = if page.title.trim().is_empty() {
+        page.url.clone()
+    } else {
+        page.title.trim().to_string()
+    };
+    let mut raw = RawResource::new(
+        format!("{}{}", SCRAPE_URI_PREFIX, url_hash(&page.url)),
+        name,
+    );
+    raw.description = Some(format!("Scraped {} at {}", page.url, page.timestamp));
+    raw.mime_type = Some("text/markdown".to_string());
+    raw.size = u32::try_from(page.clean_content.len()).ok();
+    raw.no_annotation()
+}
+
+fn search_resource(entry: &HistoryEntry) -> Resource {
+    let mut raw = RawResource::new(
+        format!("{}{}", SEARCH_URI_PREFIX, entry.id),
+        format!("Search: {}", entry.query),
+    );
+    raw.description = Some(format!(
+        "{} at {}",
+        entry.summary,
+        entry.timestamp.to_rfc3339()
+    ));
+    raw.mime_type = Some("application/json".to_string());
+    raw.no_annotation()
+}
+
+/// `resources/list`: cached scrapes, then search history, `PAGE_SIZE` at a time.
+/// `cursor` is the `nextCursor` of the previous page.
+pub async fn list(
+    state: &AppState,
+    cursor: Option<&str>,
+) -> Result<ListResourcesResult, ErrorData> {
+    let offset = match cursor {
+        None => 0,
+        Some(cursor) => cursor.parse::<usize>().map_err(|_| {
+            ErrorData::invalid_params(format!("Invalid resources/list cursor: {}", cursor), None)
+        })?,
+    };
+
+    let mut resources: Vec<Resource> = recent_scrapes(state)
+        .iter()
+        .take(MAX_LISTED_SCRAPES)
+        .map(scrape_resource)
+        .collect();
+    resources.extend(
+        recent_searches(state, MAX_LISTED_SEARCHES)
+            .await
+            .iter()
+            .map(search_resource),
+    );
+
+    let total = resources.len();
+    let page: Vec<Resource> = resources.into_iter().skip(offset).take(PAGE_SIZE).collect();
+    let next = offset + page.len();
+    let mut result = ListResourcesResult::with_all_items(page);
+    result.next_cursor = (next < total).then(|| next.to_string());
+    Ok(result)
+}
+
+fn not_found(uri: &str) -> ErrorData {
+    ErrorData::resource_not_found(
+        format!("Resource not found: {}", uri),
+        Some(json!({ "uri": uri })),
+    )
+}
+
+fn read_scrape(
+    state: &AppState,
+    uri: &str,
+    hash: &str,
+    max_chars: usize,
+) -> Option<ResourceContents> {
+    let page = recent_scrapes(state)
+        .into_iter()
+        .find(|page| url_hash(&page.url) == hash)?;
+    let (text, truncated) = match truncate_markdown(&page.clean_content, max_chars) {
+        Some(cut) => (cut.text, true),
+        None => (page.clean_content.clone(), false),
+    };
+    let mut meta = serde_json::Map::new();
+    for (key, value) in [
+        ("url", json!(page.url)),
+        ("title", json!(page.title)),
+        ("timestamp", json!(page.timestamp)),
+        ("status_code", json!(page.status_code)),
+        ("word_count", json!(page.word_count)),
+        ("language", json!(page.language)),
+        ("truncated", json!(truncated)),
+    ] {
+        meta.insert(key.to_string(), value);
+    }
+    if let Some(final_url) = &page.final_url {
+        meta.insert("final_url".to_string(), json!(final_url));
+    }
+    Some(ResourceContents::TextResourceContents {
+        uri: uri.to_string(),
+        mime_type: Some("text/markdown".to_string()),
+        text,
+        meta: Some(Meta(meta)),
+    })
+}
+
+/// A search entry as JSON, dropping results from the end until it fits `max_chars`.
+fn search_json(entry: &HistoryEntry, max_chars: usize) -> String {
+    let mut results = entry.full_result.clone();
+    let mut truncated = false;
+    loop {
+        let body = json!({
+            "id": entry.id,
+            "query": entry.query,
+            "summary": entry.summary,
+            "timestamp": entry.timestamp.to_rfc3339(),
+            "result_count": entry.result_count(),
+            "truncated": truncated,
+            "results": results,
+        });
+        let text = serde_json::to_string_pretty(&body).unwrap_or_else(|_| body.to_string());
+        if text.chars().count() <= max_chars || results.is_null() {
+            return text;
+        }
+        truncated = true;
+        match results.as_array_mut() {
+            Some(items) if !items.is_empty() => {
+                items.pop();
+            }
+            _ => results = Value::Null,
+        }
+    }
+}
+
+/// `resources/read` for a `shadowcrawl://scrape/...` or `shadowcrawl://search/...` URI.
+pub async fn read(state: &AppState, uri: &str) -> Result<ReadResourceResult, ErrorData> {
+    let max_chars = crate::core::config::max_content_chars();
+    if let Some(hash) = uri.strip_prefix(SCRAPE_URI_PREFIX) {
+        let contents = read_scrape(state, uri, hash, max_chars).ok_or_else(|| not_found(uri))?;
+        return Ok(ReadResourceResult::new(vec![contents]));
+    }
+    if let Some(id) = uri.strip_prefix(SEARCH_URI_PREFIX) {
+        let memory = state.get_memory().ok_or_else(|| not_found(uri))?;
+        let entry = memory
+            .entry_by_id(id)
+            .await
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?
+            .filter(|entry| matches!(entry.entry_type, EntryType::Search))
+            .ok_or_else(|| not_found(uri))?;
+        return Ok(ReadResourceResult::new(vec![
+            ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some("application/json".to_string()),
+                text: search_json(&entry, max_chars),
+                meta: None,
+            },
+        ]));
+    }
+    Err(not_found(uri))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> AppState {
+        AppState::new(reqwest::Client::new())
+    }
+
+    fn page(url: &str, clean_content: &str, timestamp: &str) -> ScrapeResponse {
+        ScrapeResponse {
+            timestamp: timestamp.to_string(),
+            ..ScrapeResponse::for_test(url, &format!("Title of {}", url), clean_content)
+        }
+    }
+
+    async fn cache(state: &AppState, key: &str, page: ScrapeResponse) {
+        state.scrape_cache.insert(key.to_string(), page).await;
+        state.scrape_cache.run_pending_tasks().await;
+    }
+
+    #[tokio::test]
+    async fn cached_scrapes_list_newest_first_once_per_url() {
+        let state = state();
+        assert!(list(&state, None).await.unwrap().resources.is_empty());
+
+        let a = "https://example.com/a";
+        cache(
+            &state,
+            "a|plain",
+            page(a, "old text", "2026-01-01T00:00:00Z"),
+        )
+        .await;
+        cache(
+            &state,
+            "a|selectors",
+            page(a, "new text", "2026-01-02T00:00:00Z"),
+        )
+        .await;
+        cache(
+            &state,
+            "b",
+            page("https://example.com/b", "b text", "2026-01-03T00:00:00Z"),
+        )
+        .await;
+        cache(
+            &state,
+            "c|links_only",
+            page("https://example.com/c", "", "2026-01-04T00:00:00Z"),
+        )
+        .await;
+
+        let listed = list(&state, None).await.unwrap();
+        assert_eq!(listed.next_cursor, None);
+        let names: Vec<_> = listed
+            .resources
+            .iter()
+            .map(|r| r.raw.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Title of https://example.com/b",
+                "Title of https://example.com/a"
+            ]
+        );
+        assert!(available(&state));
+
+        let uri = &listed.resources[1].raw.uri;
+        assert!(uri.starts_with(SCRAPE_URI_PREFIX));
+        let read = read(&state, uri).await.unwrap();
+        match &read.contents[0] {
+            ResourceContents::TextResourceContents {
+                text,
+                mime_type,
+                meta,
+                ..
+            } => {
+                assert_eq!(text, "new text");
+                assert_eq!(mime_type.as_deref(), Some("text/markdown"));
+                assert_eq!(meta.as_ref().unwrap().get("url"), Some(&json!(a)));
+            }
+            other => panic!("expected text contents, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn pages_through_the_list_and_rejects_unknown_uris() {
+        let state = state();
+        for i in 0..(PAGE_SIZE + 5) {
+            let url = format!("https://example.com/{}", i);
+            let timestamp = format!("2026-01-01T00:{:02}:00Z", i);
+            cache(&state, &url, page(&url, "text", &timestamp)).await;
+        }
+
+        let first = list(&state, None).await.unwrap();
+        assert_eq!(first.resources.len(), PAGE_SIZE);
+        let cursor = first.next_cursor.expect("a second page");
+        let second = list(&state, Some(&cursor)).await.unwrap();
+        assert_eq!(second.resources.len(), 5);
+        assert_eq!(second.next_cursor, None);
+        assert_eq!(
+            list(&state, Some("later")).await.unwrap_err().code.0,
+            -32602
+        );
+
+        for uri in [
+            "shadowcrawl://scrape/0000",
+            "shadowcrawl://search/1234",
+            "file:///etc/passwd",
+        ] {
+            assert_eq!(
+                read(&state, uri).await.unwrap_err().code.0,
+                -32002,
+                "{}",
+                uri
+            );
+        }
+    }
+
+    #[test]
+    fn search_json_drops_results_to_fit() {
+        let entry = HistoryEntry {
+            id: "5f0c".to_string(),
+            entry_type: EntryType::Search,
+            query: "rust".to_string(),
+            topic: "rust".to_string(),
+            summary: "Search: rust (3 results)".to_string(),
+            full_result: json!([
+                { "url": "https://a.example", "content": "x".repeat(300) },
+                { "url": "https://b.example", "content": "x".repeat(300) },
+                { "url": "https://c.example", "content": "x".repeat(300) },
+            ]),
+            timestamp: chrono::Utc::now(),
+            domain: None,
+            source_type: None,
+        };
+
+        let full: Value = serde_json::from_str(&search_json(&entry, 10_000)).unwrap();
+        assert_eq!(full["results"].as_array().unwrap().len(), 3);
+        assert_eq!(full["truncated"], false);
+
+        let text = search_json(&entry, 800);
+        assert!(text.chars().count() <= 800);
+        let cut: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(cut["results"].as_array().unwrap().len(), 1);
+        assert_eq!(cut["truncated"], true);
+        assert_eq!(cut["result_count"], 3);
+    }
+}
@@ -1,5 +1,6 @@
 use super::handlers;
 use super::tooling::schema_to_object_map;
+use crate::core::{budget, progress, request_id};
 use crate::mcp::McpCallResponse;
 use crate::types::ErrorResponse;
 use crate::{history, AppState};
@@ -10,18 +11,15 @@ use serde_json::Value;
 use std::borrow::Cow;
 use std::env;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use tracing::{info, warn};
 
-fn status_code_to_error_code(status: StatusCode) -> ErrorCode {
-    match status {
-        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::INVALID_PARAMS,
-        StatusCode::NOT_FOUND => ErrorCode::METHOD_NOT_FOUND,
-        _ => ErrorCode::INTERNAL_ERROR,
-    }
+pub(crate) fn error_response_to_error_data(err: ErrorResponse) -> ErrorData {
+    let data = serde_json::json!({ "code": err.code, "details": err.details });
+    ErrorData::new(ErrorCode(err.code.jsonrpc_code()), err.error, Some(data))
 }
 
-fn mcp_call_response_to_stdio_result(response: McpCallResponse) -> CallToolResult {
+pub(crate) fn mcp_call_response_to_stdio_result(response: McpCallResponse) -> CallToolResult {
     let content = response
         .content
         .into_iter()
@@ -44,17 +42,15 @@ fn convert_http_handler_result_with_metrics(
         Ok(Json(response)) => Ok(mcp_call_response_to_stdio_result(
             super::http::instrument_tool_response(response, tool_name, started_at),
         )),
-        Err((status, Json(err))) => Err(ErrorData::new(
-            status_code_to_error_code(status),
-            err.error,
-            None,
-        )),
+        Err((_, Json(err))) => Err(error_response_to_error_data(err)),
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct McpService {
     pub state: Arc<AppState>,
+    /// Qualifies JSON-RPC ids into request IDs, see [`request_id::for_jsonrpc_id`].
+    session: String,
 }
 
 impl McpService {
@@ -84,12 +80,14 @@ impl McpService {
             .and_then(|v| v.parse::<u64>().ok())
             .unwrap_or(10);
 
-        let http_client = reqwest::Client::builder()
+        let http_client = crate::core::http_client::builder()
             .timeout(std::time::Duration::from_secs(http_timeout))
             .connect_timeout(std::time::Duration::from_secs(connect_timeout))
+            .redirect(crate::scraping::domain_policy::redirect_policy())
             .build()?;
 
-        let mut state = AppState::new(http_client);
+        let mut state = AppState::new(http_client)
+            .with_tool_registry(crate::core::tools_registry::ToolRegistry::load_for_deployment()?);
 
         // Proxy manager — synchronous file read, fast.
         let ip_list_path = env::var("IP_LIST_PATH").unwrap_or_else(|_| "ip.txt".to_string());
@@ -115,6 +113,7 @@ impl McpService {
 
         // Wrap state in Arc now — MCP transport can start immediately.
         let state = Arc::new(state);
+        crate::monitor::spawn_scheduler(Arc::clone(&state));
 
         // LanceDB / semantic memory — may trigger IVF index rebuild on large datasets
         // (several seconds). Spawn in background so MCP handshake is not delayed.
@@ -143,7 +142,10 @@ impl McpService {
             info!("Semantic memory disabled (CORTEX_SCOUT_MEMORY_DISABLED=1)");
         }
 
-        Ok(Self { state })
+        Ok(Self {
+            state,
+            session: request_id::new_session("stdio"),
+        })
     }
 }
 
@@ -154,7 +156,15 @@ impl rmcp::ServerHandler for McpService {
             .with_description(
                 "A pure Rust web research service using federated search plus high-integrity content synchronization for consistent downstream analysis."
             );
-        let mut info = ServerInfo::new(ServerCapabilities::builder().enable_tools().build());
+        let capabilities = if super::resources::available(&self.state) {
+            ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build()
+        } else {
+            ServerCapabilities::builder().enable_tools().build()
+        };
+        let mut info = ServerInfo::new(capabilities);
         info.protocol_version = ProtocolVersion::LATEST;
         info.server_info = server_info;
         info.instructions = Some(
@@ -190,10 +200,27 @@ impl rmcp::ServerHandler for McpService {
         })
     }
 
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParams>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        let cursor = request.and_then(|page| page.cursor);
+        super::resources::list(&self.state, cursor.as_deref()).await
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        super::resources::read(&self.state, &request.uri).await
+    }
+
     async fn call_tool(
         &self,
         request: CallToolRequestParams,
-        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        context: rmcp::service::RequestContext<rmcp::RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
         let tool_start = Instant::now();
         info!(
@@ -235,65 +262,162 @@ impl rmcp::ServerHandler for McpService {
             .tool_registry
             .map_public_arguments_to_internal(&internal_name, public_args);
 
-        let tool_timeout =
-            Duration::from_secs(crate::core::config::mcp_tool_timeout_secs(&internal_name));
-        let request_name = request.name.to_string();
-        let state = Arc::clone(&self.state);
-        let dispatch_name = internal_name.clone();
-
-        let dispatch = async move {
-            match dispatch_name.as_str() {
-                "search_web" => handlers::search_web::handle(Arc::clone(&state), &internal_args).await,
-                "search_structured" => handlers::search_structured::handle(Arc::clone(&state), &internal_args).await,
-                "scrape_url" => handlers::scrape_url::handle(Arc::clone(&state), &internal_args).await,
-                "crawl_website" => handlers::crawl_website::handle(Arc::clone(&state), &internal_args).await,
-                "scrape_batch" => handlers::scrape_batch::handle(Arc::clone(&state), &internal_args).await,
-                "deep_research" => handlers::deep_research::handle(Arc::clone(&state), &internal_args).await,
-                "extract_structured" => handlers::extract_structured::handle(Arc::clone(&state), &internal_args).await,
-                "fetch_then_extract" => handlers::fetch_then_extract::handle(Arc::clone(&state), &internal_args).await,
-                "research_history" => handlers::research_history::handle(Arc::clone(&state), &internal_args).await,
-                "proxy_manager" => handlers::proxy_manager::handle(Arc::clone(&state), &internal_args).await,
-                "non_robot_search" => handlers::non_robot_search::handle(Arc::clone(&state), &internal_args).await,
-                "visual_scout" => handlers::visual_scout::handle(Arc::clone(&state), &internal_args).await,
-                "human_auth_session" => handlers::human_auth_session::handle(Arc::clone(&state), &internal_args).await,
-                "browser_automate" | "scout_browser_automate" => {
-                    handlers::automate::handle(Arc::clone(&state), &internal_args).await
-                }
-                "browser_close" | "scout_browser_close" => {
-                    handlers::automate::handle_close(Arc::clone(&state), &internal_args).await
-                }
-                "agent_profile_auth" | "scout_agent_profile_auth" => {
-                    handlers::automate::handle_profile_auth(Arc::clone(&state), &internal_args).await
-                }
-                _ => Err((
-                    StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse {
-                        error: format!("Unknown tool: {}", request_name),
-                    }),
-                )),
-            }
-        };
-
-        match tokio::time::timeout(tool_timeout, dispatch).await {
-            Ok(result) => {
+        // Same precedence as the other transports: `_meta.requestId`, else the JSON-RPC id.
+        let meta = serde_json::to_value(&context.meta).ok();
+        let call_id = request_id::from_meta(meta.as_ref()).unwrap_or_else(|| {
+            let id = serde_json::to_value(&context.id).unwrap_or(Value::Null);
+            request_id::for_jsonrpc_id(&self.session, &id)
+        });
+        // One stdio process serves one client; its name keeps the budget caller stable
+        // across restarts.
+        let caller = context
+            .peer
+            .peer_info()
+            .map(|info| format!("stdio:{}", info.client_info.name))
+            .unwrap_or_else(|| "stdio".to_string());
+        let call = request_id::scope(
+            call_id,
+            budget::scope(caller, async {
+                let result = handlers::run_call(
+                    Arc::clone(&self.state),
+                    &internal_name,
+                    request.name.as_ref(),
+                    &internal_args,
+                    context.ct.clone(),
+                )
+                .await;
                 convert_http_handler_result_with_metrics(request.name.as_ref(), tool_start, result)
+            }),
+        );
+
+        // Tools that report progress (`crawl_website`, `scrape_batch`) notify a caller that
+        // sent `_meta.progressToken`, through the session's peer.
+        let Some(token) = meta.as_ref().and_then(|m| m.get("progressToken")).cloned() else {
+            return call.await;
+        };
+        let peer = context.peer.clone();
+        progress::forward_throttled(call, progress::INTERVAL, |update| {
+            let params = serde_json::from_value::<ProgressNotificationParam>(
+                progress::notification_params(&token, &update),
+            );
+            let peer = peer.clone();
+            async move {
+                let sent = match params {
+                    Ok(params) => peer
+                        .notify_progress(params)
+                        .await
+                        .map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                if let Err(e) = sent {
+                    warn!("Dropped MCP progress notification: {}", e);
+                }
             }
-            Err(_) => Ok(mcp_call_response_to_stdio_result(
-                super::http::instrument_tool_response(
-                    super::timeout::timeout_call_response(request.name.as_ref(), tool_timeout),
-                    request.name.as_ref(),
-                    tool_start,
-                ),
-            )),
-        }
+        })
+        .await
     }
 }
 
 pub async fn run() -> anyhow::Result<()> {
     let service = McpService::new().await?;
+    let state = Arc::clone(&service.state);
     let running = service.serve(rmcp::transport::stdio()).await?;
     info!("MCP stdio server initialized; waiting for client session");
-    let quit_reason = running.waiting().await?;
-    warn!("MCP stdio server stopped: {:?}", quit_reason);
+    let quit_reason = running.waiting().await;
+    // A warm hitl_web_fetch browser must not outlive the session.
+    crate::features::non_robot_search::close_warm_session(&state).await;
+    warn!("MCP stdio server stopped: {:?}", quit_reason?);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+    /// One newline-delimited JSON-RPC message, as the stdio transport frames them.
+    async fn send(writer: &mut (impl AsyncWrite + Unpin), message: Value) {
+        let line = format!("{}\n", message);
+        writer.write_all(line.as_bytes()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn batch_progress_reaches_the_client_before_the_result() {
+        let service = McpService {
+            state: Arc::new(AppState::new(reqwest::Client::new())),
+            session: request_id::new_session("stdio"),
+        };
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            if let Ok(running) = service.serve(tokio::io::split(server)).await {
+                let _ = running.waiting().await;
+            }
+        });
+        let (client_read, mut client_write) = tokio::io::split(client);
+        let mut lines = BufReader::new(client_read).lines();
+
+        send(
+            &mut client_write,
+            json!({
+                "jsonrpc": "2.0", "id": 1, "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test", "version": "0" }
+                }
+            }),
+        )
+        .await;
+        let initialized: Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(initialized["id"], 1);
+        send(
+            &mut client_write,
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        )
+        .await;
+
+        // Loopback targets are refused by the domain policy, so each URL settles at once.
+        let urls: Vec<_> = (1..=5)
+            .map(|n| format!("http://127.0.0.1/mock/{}", n))
+            .collect();
+        send(json!({
+            "jsonrpc": "2.0", "id": 2, "method": "tools/call",
+            "params": {
+                "name": "scrape_batch",
+                "arguments": { "urls": urls },
+                "_meta": { "progressToken": "batch-1" }
+            }
+        }))
+        .await;
+
+        let mut progress = Vec::new();
+        loop {
+            let line = lines.next_line().await.unwrap().expect("server closed");
+            let message: Value = serde_json::from_str(&line).unwrap();
+            if message["method"] == "notifications/progress" {
+                assert_eq!(message["params"]["progressToken"], "batch-1");
+                assert_eq!(message["params"]["total"], 5);
+                progress.push(message["params"].clone());
+            } else if message["id"] == 2 {
+                assert!(message.get("result").is_some(), "{}", message);
+                break;
+            }
+        }
+
+        assert!(!progress.is_empty());
+        let counts: Vec<_> = progress
+            .iter()
+            .map(|p| p["progress"].as_u64().unwrap())
+            .collect();
+        assert!(counts.windows(2).all(|w| w[0] < w[1]), "{:?}", counts);
+        assert_eq!(counts.last(), Some(&5));
+        let last = progress.last().unwrap()["message"].as_str().unwrap();
+        assert!(
+            last.starts_with("http://127.0.0.1/mock/") && last.ends_with("(5 failed)"),
+            "{}",
+            last
+        );
+    }
+}
@@ -7,12 +7,13 @@ pub fn timeout_call_response(tool_name: &str, timeout: Duration) -> McpCallRespo
         "status": "timeout",
         "tool_name": tool_name,
         "timeout_seconds": timeout.as_secs(),
+        "timeout_ms": timeout.as_millis() as u64,
         "message": format!(
             "Tool execution exceeded the hard timeout of {} seconds and was cancelled to prevent a stuck MCP session.",
-            timeout.as_secs()
+            timeout.as_secs_f64()
         ),
         "retryable": true,
-        "suggested_action": "Retry with a narrower scope or raise CORTEX_SCOUT_TOOL_TIMEOUT_SECS[_TOOL] if this workload legitimately needs more time."
+        "suggested_action": "Retry with a narrower scope, a larger timeout_ms, or raise CORTEX_SCOUT_TOOL_TIMEOUT_SECS[_TOOL] if this workload legitimately needs more time."
     });
 
     McpCallResponse {
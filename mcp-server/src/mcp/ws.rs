@@ -0,0 +1,219 @@
+//! WebSocket transport (`GET /mcp/ws`) for MCP clients that cannot spawn a stdio process.
+//!
+//! Every text frame carries one JSON-RPC message, answered by [`handle_message`] exactly as
+//! `POST /mcp` would. Requests run as separate tasks, so tool calls on one connection
+//! proceed concurrently and clients match responses by `id`. A call whose params carry
+//! `_meta.progressToken` also receives `notifications/progress` from tools that report
+//! progress. `notifications/cancelled` cancels one call, which then answers with a
+//! `cancelled` error; closing the socket aborts all of them. Each message is handled under
+//! a request ID derived from the socket and its JSON-RPC id, and charged to the budget
+//! caller of the upgrade request (see `core::budget`).
+
+use super::cancellation::CallRegistry;
+use super::jsonrpc::handle_message_in;
+use crate::core::progress::{self, Progress};
+use crate::core::{budget, request_id};
+use crate::AppState;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::Response;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::task::JoinSet;
+use tracing::info;
+
+/// Axum route handler: `GET /mcp/ws`
+#[utoipa::path(
+    get,
+    path = "/mcp/ws",
+    tag = "mcp",
+    responses((status = 101, description = "Switches to a WebSocket carrying MCP JSON-RPC messages"))
+)]
+pub async fn handler(ws: WebSocketUpgrade, State(state): State<Arc<AppState>>) -> Response {
+    // The upgraded socket runs on its own task, outside the request's budget scope.
+    let caller = budget::current_caller();
+    ws.on_upgrade(move |socket| serve(state, socket, caller))
+}
+
+async fn serve(state: Arc<AppState>, socket: WebSocket, caller: String) {
+    let (mut sink, mut stream) = socket.split();
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        while let Some(message) = out_rx.recv().await {
+            if sink
+                .send(Message::Text(message.to_string().into()))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    let mut calls = JoinSet::new();
+    // Per socket, so ids from different clients never collide.
+    let registry = Arc::new(CallRegistry::default());
+    let session = request_id::new_session("ws");
+    loop {
+        tokio::select! {
+            frame = stream.next() => {
+                let text = match frame {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+
+                let message = match serde_json::from_str::<Value>(&text) {
+                    Ok(message) => message,
+                    Err(e) => {
+                        let _ = out_tx.send(json!({
+                            "jsonrpc": "2.0",
+                            "id": null,
+                            "error": { "code": -32700, "message": format!("Parse error: {}", e) }
+                        }));
+                        continue;
+                    }
+                };
+                let frame_id = match message.get("id") {
+                    Some(id) => request_id::for_jsonrpc_id(&session, id),
+                    None => request_id::generate(),
+                };
+                calls.spawn(request_id::scope(
+                    frame_id,
+                    budget::scope(
+                        caller.clone(),
+                        handle_frame(
+                            Arc::clone(&state),
+                            Arc::clone(&registry),
+                            message,
+                            out_tx.clone(),
+                        ),
+                    ),
+                ));
+            }
+            Some(_) = calls.join_next(), if !calls.is_empty() => {}
+        }
+    }
+
+    if !calls.is_empty() {
+        info!(
+            "MCP websocket closed; aborting {} unfinished call(s)",
+            calls.len()
+        );
+    }
+    calls.shutdown().await;
+    drop(out_tx);
+    let _ = writer.await;
+}
+
+async fn handle_frame(
+    state: Arc<AppState>,
+    calls: Arc<CallRegistry>,
+    message: Value,
+    out: UnboundedSender<Value>,
+) {
+    let token = message.pointer("/params/_meta/progressToken").cloned();
+    let response = with_progress(token, &out, handle_message_in(state, &calls, message)).await;
+    if let Some(response) = response {
+        let _ = out.send(response);
+    }
+}
+
+/// Run `call`, forwarding its progress reports as `notifications/progress` for `token`
+/// (throttled, see [`progress::forward_throttled`]). All notifications are queued on `out`
+/// before this returns, so they precede the response.
+async fn with_progress<F: Future>(
+    token: Option<Value>,
+    out: &UnboundedSender<Value>,
+    call: F,
+) -> F::Output {
+    let Some(token) = token else {
+        return call.await;
+    };
+    progress::forward_throttled(call, progress::INTERVAL, |update| {
+        let _ = out.send(progress_notification(&token, update));
+        async {}
+    })
+    .await
+}
+
+fn progress_notification(token: &Value, update: Progress) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/progress",
+        "params": progress::notification_params(token, &update),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+    #[tokio::test]
+    async fn progress_is_forwarded_before_the_result() {
+        let (out, mut rx) = mpsc::unbounded_channel();
+        let output = with_progress(Some(json!("tok-1")), &out, async {
+            progress::report(1, Some(2), "https://example.com/a");
+            progress::report(2, Some(2), "https://example.com/b");
+            "done"
+        })
+        .await;
+        assert_eq!(output, "done");
+
+        let first = rx.recv().await.unwrap();
+        assert_eq!(first["method"], "notifications/progress");
+        assert_eq!(first["params"]["progressToken"], "tok-1");
+        assert_eq!(first["params"]["progress"], 1);
+        assert_eq!(first["params"]["total"], 2);
+        assert_eq!(rx.recv().await.unwrap()["params"]["progress"], 2);
+        assert!(rx.try_recv().is_err());
+
+        // Without a token the reports go nowhere.
+        with_progress(None, &out, async { progress::report(1, None, "x") }).await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn socket_multiplexes_requests_by_id() {
+        let state = Arc::new(AppState::new(reqwest::Client::new()));
+        let app = axum::Router::new()
+            .route("/mcp/ws", axum::routing::get(handler))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/mcp/ws", addr))
+            .await
+            .unwrap();
+        for frame in [
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            json!({ "jsonrpc": "2.0", "id": "b", "method": "ping" }),
+        ] {
+            socket
+                .send(ClientMessage::Text(frame.to_string().into()))
+                .await
+                .unwrap();
+        }
+        socket
+            .send(ClientMessage::Text("{not json".into()))
+            .await
+            .unwrap();
+
+        let mut responses = HashMap::new();
+        while responses.len() < 3 {
+            let frame = socket.next().await.unwrap().unwrap();
+            let message: Value = serde_json::from_str(frame.to_text().unwrap()).unwrap();
+            responses.insert(message["id"].to_string(), message);
+        }
+        assert!(responses["1"]["result"]["tools"].as_array().unwrap().len() > 1);
+        assert_eq!(responses["\"b\""]["result"], json!({}));
+        assert_eq!(responses["null"]["error"]["code"], -32700);
+    }
+}
@@ -0,0 +1,221 @@
+//! Extractive answers for `/chat`: the scraped pages' own sentences, ranked against the
+//! query with [`Reranker`] and stitched together with `[n]` source citations.
+
+use crate::rerank::Reranker;
+use crate::types::{ScrapeResponse, SearchResult};
+use std::collections::HashSet;
+
+pub const DEFAULT_MAX_SENTENCES: usize = 8;
+
+/// Shorter fragments are usually navigation or captions; longer ones are run-on lists.
+const MIN_SENTENCE_CHARS: usize = 40;
+const MAX_SENTENCE_CHARS: usize = 400;
+const MIN_SENTENCE_WORDS: usize = 6;
+/// Sentences sharing at least this share of their words count as the same sentence.
+const DUPLICATE_OVERLAP: f64 = 0.8;
+
+/// Split markdown-ish `clean_content` into prose sentences, skipping headings, tables,
+/// code fences and fragments too short or long to read as a sentence.
+pub fn split_sentences(content: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut in_fence = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || line.is_empty() || line.starts_with('#') || line.starts_with('|') {
+            continue;
+        }
+        let line = line.trim_start_matches(['-', '*', '>', ' ']);
+
+        let mut current = String::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            current.push(c);
+            let boundary = matches!(c, '.' | '!' | '?')
+                && chars.peek().is_some_and(|next| next.is_whitespace());
+            if boundary {
+                push_sentence(&mut sentences, &current);
+                current.clear();
+            }
+        }
+        push_sentence(&mut sentences, &current);
+    }
+    sentences
+}
+
+fn push_sentence(sentences: &mut Vec<String>, candidate: &str) {
+    let sentence = candidate.trim();
+    let chars = sentence.chars().count();
+    if !(MIN_SENTENCE_CHARS..=MAX_SENTENCE_CHARS).contains(&chars)
+        || sentence.split_whitespace().count() < MIN_SENTENCE_WORDS
+    {
+        return;
+    }
+    sentences.push(sentence.to_string());
+}
+
+fn word_set(sentence: &str) -> HashSet<String> {
+    sentence
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_near_duplicate(a: &HashSet<String>, b: &HashSet<String>) -> bool {
+    let smaller = a.len().min(b.len());
+    if smaller == 0 {
+        return false;
+    }
+    a.intersection(b).count() as f64 / smaller as f64 >= DUPLICATE_OVERLAP
+}
+
+/// Answer `query` from the top `max_sentences` sentences of `pages`. With
+/// `include_sources`, each sentence carries a `[n]` marker and a numbered source list
+/// follows. `None` when no page has a usable sentence.
+pub fn extractive_answer(
+    query: &str,
+    pages: &[ScrapeResponse],
+    max_sentences: usize,
+    include_sources: bool,
+) -> Option<String> {
+    let candidates: Vec<SearchResult> = pages
+        .iter()
+        .enumerate()
+        .flat_map(|(page, scraped)| {
+            split_sentences(&scraped.clean_content)
+                .into_iter()
+                .map(move |sentence| SearchResult {
+                    url: page.to_string(),
+                    content: sentence,
+                    ..Default::default()
+                })
+        })
+        .collect();
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let mut picked: Vec<(usize, String)> = Vec::new();
+    let mut picked_words: Vec<HashSet<String>> = Vec::new();
+    for candidate in Reranker::new(query).rerank(candidates, None) {
+        if picked.len() >= max_sentences.max(1) {
+            break;
+        }
+        let words = word_set(&candidate.content);
+        if picked_words
+            .iter()
+            .any(|seen| is_near_duplicate(seen, &words))
+        {
+            continue;
+        }
+        let page = candidate.url.parse::<usize>().unwrap_or(0);
+        picked.push((page, candidate.content));
+        picked_words.push(words);
+    }
+
+    // Number sources in order of first citation.
+    let mut cited: Vec<usize> = Vec::new();
+    let mut body = Vec::with_capacity(picked.len());
+    for (page, sentence) in picked {
+        if !include_sources {
+            body.push(sentence);
+            continue;
+        }
+        let number = match cited.iter().position(|p| *p == page) {
+            Some(ix) => ix + 1,
+            None => {
+                cited.push(page);
+                cited.len()
+            }
+        };
+        body.push(format!("{} [{}]", sentence, number));
+    }
+
+    let mut answer = body.join(" ");
+    if include_sources {
+        answer.push_str("\n\nSources:");
+        for (ix, page) in cited.iter().enumerate() {
+            let scraped = &pages[*page];
+            let url = scraped.canonical_url.as_ref().unwrap_or(&scraped.url);
+            let title = if scraped.title.trim().is_empty() {
+                url.as_str()
+            } else {
+                scraped.title.trim()
+            };
+            answer.push_str(&format!("\n[{}] {} - {}", ix + 1, title, url));
+        }
+    }
+    Some(answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(url: &str, title: &str, clean_content: &str) -> ScrapeResponse {
+        ScrapeResponse::for_test(url, title, clean_content)
+    }
+
+    #[test]
+    fn splits_prose_and_skips_markdown_structure() {
+        let content = "# Tokio runtime\n\
+            Tokio is an asynchronous runtime for the Rust programming language. It provides \
+            the building blocks needed for writing network applications!\n\
+            ```rust\nlet rt = tokio::runtime::Runtime::new().unwrap(); // a long code line here\n```\n\
+            | col | col |\n\
+            - Short item.\n\
+            > The scheduler uses work stealing to balance tasks across worker threads.";
+        assert_eq!(
+            split_sentences(content),
+            vec![
+                "Tokio is an asynchronous runtime for the Rust programming language.",
+                "It provides the building blocks needed for writing network applications!",
+                "The scheduler uses work stealing to balance tasks across worker threads.",
+            ]
+        );
+    }
+
+    #[test]
+    fn cites_sources_and_drops_near_duplicate_sentences() {
+        let pages = vec![
+            page(
+                "https://a.example/tokio",
+                "Tokio guide",
+                "Tokio is the most popular async runtime for Rust applications today. \
+                 Unrelated footer text about cookies and privacy settings here.",
+            ),
+            page(
+                "https://b.example/tokio",
+                "",
+                "Tokio is the most popular async runtime for Rust applications today! \
+                 The Tokio runtime schedules async tasks on a pool of worker threads.",
+            ),
+        ];
+
+        let answer = extractive_answer("tokio async runtime", &pages, 2, true).unwrap();
+        let (body, sources) = answer.split_once("\n\nSources:").unwrap();
+        assert_eq!(body.matches("most popular async runtime").count(), 1);
+        assert!(body.contains("applications today. [1]"));
+        assert!(body.contains("worker threads. [2]"));
+        assert!(!body.contains("cookies"));
+        assert!(sources.contains("[1] Tokio guide - https://a.example/tokio"));
+        assert!(sources.contains("[2] https://b.example/tokio - https://b.example/tokio"));
+
+        let plain = extractive_answer("tokio async runtime", &pages, 1, false).unwrap();
+        assert!(!plain.contains('['));
+        assert!(!plain.contains("Sources:"));
+
+        assert!(extractive_answer(
+            "tokio",
+            &[page("https://c.example", "", "Too short.")],
+            8,
+            true
+        )
+        .is_none());
+    }
+}
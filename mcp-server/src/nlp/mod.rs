@@ -1,3 +1,5 @@
+pub mod extractive;
 pub mod query_rewriter;
 pub mod rerank;
 pub mod semantic_shave;
+pub mod spell;
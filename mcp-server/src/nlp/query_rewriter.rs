@@ -1,3 +1,4 @@
+use super::spell::SpellCorrector;
 use std::collections::HashMap;
 use tracing::debug;
 
@@ -5,6 +6,7 @@ use tracing::debug;
 pub struct QueryRewriter {
     dev_keywords: Vec<&'static str>,
     site_mappings: HashMap<&'static str, Vec<&'static str>>,
+    spell_correction: bool,
 }
 
 impl Default for QueryRewriter {
@@ -159,12 +161,21 @@ impl QueryRewriter {
 
                 map
             },
+            spell_correction: false,
         }
     }
 
+    /// Also look for typos in every query (developer or not) and report the corrected
+    /// query in [`QueryRewriteResult::corrected`]. The query itself is not replaced.
+    pub fn with_spell_correction(mut self, enabled: bool) -> Self {
+        self.spell_correction = enabled;
+        self
+    }
+
     /// Analyze and potentially rewrite a query for better developer-focused results
     pub fn rewrite_query(&self, query: &str) -> QueryRewriteResult {
         let query_lower = query.to_lowercase();
+        let corrected = self.correct_spelling(query);
 
         // Check if this is a developer query
         let is_dev_query = self.is_developer_query(&query_lower);
@@ -173,6 +184,7 @@ impl QueryRewriter {
             return QueryRewriteResult {
                 original: query.to_string(),
                 rewritten: None,
+                corrected,
                 suggestions: vec![],
                 detected_keywords: vec![],
                 is_developer_query: false,
@@ -210,12 +222,25 @@ impl QueryRewriter {
         QueryRewriteResult {
             original: query.to_string(),
             rewritten,
+            corrected,
             suggestions,
             detected_keywords,
             is_developer_query: true,
         }
     }
 
+    /// Typo-corrected query, never touching the keywords this rewriter recognises.
+    fn correct_spelling(&self, query: &str) -> Option<String> {
+        if !self.spell_correction {
+            return None;
+        }
+        let corrected = SpellCorrector::shared().correct_query(query, &self.dev_keywords);
+        if let Some(corrected) = &corrected {
+            debug!("Spelling corrected: '{}' -> '{}'", query, corrected);
+        }
+        corrected
+    }
+
     /// Check if query is developer-related
     fn is_developer_query(&self, query_lower: &str) -> bool {
         // Check for dev keywords
@@ -355,6 +380,8 @@ impl QueryRewriter {
 pub struct QueryRewriteResult {
     pub original: String,
     pub rewritten: Option<String>,
+    /// The original query with typos fixed, when spell correction found any.
+    pub corrected: Option<String>,
     pub suggestions: Vec<String>,
     pub detected_keywords: Vec<String>,
     pub is_developer_query: bool,
@@ -428,4 +455,23 @@ mod tests {
         assert!(!rewriter.is_similar_query("rust", "python"));
         assert!(!rewriter.is_similar_query("javascript", "java"));
     }
+
+    #[test]
+    fn test_spell_correction() {
+        let result = QueryRewriter::new().rewrite_query("kubernets ingress annotaion");
+        assert_eq!(result.corrected, None);
+
+        let rewriter = QueryRewriter::new().with_spell_correction(true);
+        let result = rewriter.rewrite_query("kubernets ingress annotaion");
+        assert_eq!(
+            result.corrected.as_deref(),
+            Some("kubernetes ingress annotation")
+        );
+        assert_eq!(result.best_query(), "kubernets ingress annotaion");
+
+        // Detected keywords and their variants stay as typed.
+        let result = rewriter.rewrite_query("asyncio tutorial");
+        assert!(result.is_developer_query);
+        assert_eq!(result.corrected, None);
+    }
 }
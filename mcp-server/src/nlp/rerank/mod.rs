@@ -0,0 +1,354 @@
+//! Search result reranking.
+//!
+//! Scores come from a [`RerankBackend`] chosen once per process:
+//!
+//! - `RERANK_BACKEND=lexical`: keyword overlap between query and title/snippet.
+//! - `RERANK_BACKEND=onnx`: cosine similarity of sentence embeddings from the ONNX model at
+//!   `RERANK_MODEL_PATH` (needs the `onnx-rerank` feature). This is the default whenever
+//!   `RERANK_MODEL_PATH` is set.
+//!
+//! A missing model file, a load failure or a build without the feature falls back to the
+//! lexical backend with a warning, as does any scoring error at query time.
+
+#[cfg(feature = "onnx-rerank")]
+mod onnx;
+
+#[cfg(feature = "onnx-rerank")]
+pub use onnx::OnnxEmbeddingBackend;
+
+use crate::types::SearchResult;
+use std::cmp::Ordering;
+use std::path::Path;
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+/// Scores search results against a query; higher means more relevant.
+pub trait RerankBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// One score per result, in input order.
+    fn score(&self, query: &str, results: &[SearchResult]) -> anyhow::Result<Vec<f32>>;
+}
+
+/// Keyword-overlap scoring; needs no model and never fails.
+pub struct LexicalBackend;
+
+impl RerankBackend for LexicalBackend {
+    fn name(&self) -> &'static str {
+        "lexical"
+    }
+
+    fn score(&self, query: &str, results: &[SearchResult]) -> anyhow::Result<Vec<f32>> {
+        let query_tokens = tokenize(query);
+        Ok(results
+            .iter()
+            .map(|r| lexical_score(&query_tokens, r))
+            .collect())
+    }
+}
+
+/// Which backend the environment asks for, before checking that it can load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BackendChoice {
+    Lexical,
+    Onnx(String),
+}
+
+fn backend_choice(backend: Option<&str>, model_path: Option<&str>) -> BackendChoice {
+    let model_path = model_path.map(str::trim).filter(|p| !p.is_empty());
+    let wants_onnx = match backend.map(|b| b.trim().to_ascii_lowercase()) {
+        Some(b) if b == "onnx" => true,
+        Some(b) if b == "lexical" => false,
+        Some(other) => {
+            if !other.is_empty() {
+                warn!("Unknown RERANK_BACKEND '{}'; using lexical", other);
+            }
+            false
+        }
+        None => model_path.is_some(),
+    };
+    match model_path {
+        Some(path) if wants_onnx => {
+            if Path::new(path).is_file() {
+                BackendChoice::Onnx(path.to_string())
+            } else {
+                warn!("Rerank model {} not found; using lexical reranking", path);
+                BackendChoice::Lexical
+            }
+        }
+        None if wants_onnx => {
+            warn!("RERANK_BACKEND=onnx needs RERANK_MODEL_PATH; using lexical reranking");
+            BackendChoice::Lexical
+        }
+        _ => BackendChoice::Lexical,
+    }
+}
+
+fn load_backend(choice: BackendChoice) -> Box<dyn RerankBackend> {
+    match choice {
+        BackendChoice::Lexical => Box::new(LexicalBackend),
+        #[cfg(feature = "onnx-rerank")]
+        BackendChoice::Onnx(path) => match OnnxEmbeddingBackend::load(Path::new(&path)) {
+            Ok(backend) => {
+                info!("Reranking with ONNX embedding model {}", path);
+                Box::new(backend)
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to load rerank model {}: {:#}; using lexical",
+                    path, e
+                );
+                Box::new(LexicalBackend)
+            }
+        },
+        #[cfg(not(feature = "onnx-rerank"))]
+        BackendChoice::Onnx(path) => {
+            warn!(
+                "Rerank model {} ignored: built without the onnx-rerank feature",
+                path
+            );
+            Box::new(LexicalBackend)
+        }
+    }
+}
+
+/// The process-wide backend, chosen from the environment on first use.
+pub fn backend() -> &'static dyn RerankBackend {
+    static BACKEND: OnceLock<Box<dyn RerankBackend>> = OnceLock::new();
+    BACKEND
+        .get_or_init(|| {
+            let backend = std::env::var("RERANK_BACKEND").ok();
+            let model_path = std::env::var("RERANK_MODEL_PATH").ok();
+            load_backend(backend_choice(backend.as_deref(), model_path.as_deref()))
+        })
+        .as_ref()
+}
+
+/// Tokenize text into lowercase words
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty() && s.len() > 2) // Skip short words and empty strings
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Simple text similarity scoring using TF-IDF-like approach
+/// Calculates relevance score between 0.0 and 1.0
+fn lexical_score(query_tokens: &[String], result: &SearchResult) -> f32 {
+    if query_tokens.is_empty() {
+        return 0.5;
+    }
+
+    let mut score = 0.0;
+    let mut matches = 0;
+
+    // Tokenize title and content
+    let title_tokens = tokenize(&result.title);
+    let content_tokens = tokenize(&result.content);
+
+    // Count matching tokens with weights
+    for query_token in query_tokens {
+        // Title matches (higher weight)
+        if title_tokens.contains(query_token) {
+            score += 0.4;
+            matches += 1;
+        }
+        // Content matches (lower weight)
+        else if content_tokens.contains(query_token) {
+            score += 0.2;
+            matches += 1;
+        }
+    }
+
+    // Normalize: consider query complexity
+    let max_score = query_tokens.len() as f32 * 0.4; // Max possible score
+    let normalized = if max_score > 0.0 {
+        (score / max_score).min(1.0)
+    } else {
+        0.5
+    };
+
+    // Boost by match ratio
+    let match_ratio = matches as f32 / query_tokens.len() as f32;
+    let final_score = (normalized + match_ratio) / 2.0;
+
+    final_score.clamp(0.0, 1.0)
+}
+
+/// Reranks results for one query with the process-wide [`backend`].
+pub struct Reranker {
+    query: String,
+    query_tokens: Vec<String>,
+    backend: &'static dyn RerankBackend,
+}
+
+impl Reranker {
+    /// Create a new reranker for a given query
+    pub fn new(query: &str) -> Self {
+        Self::with_backend(query, backend())
+    }
+
+    pub fn with_backend(query: &str, backend: &'static dyn RerankBackend) -> Self {
+        Self {
+            query: query.to_string(),
+            query_tokens: tokenize(query),
+            backend,
+        }
+    }
+
+    /// Lexical relevance score for a single result, between 0.0 and 1.0
+    pub fn score_result(&self, result: &SearchResult) -> f32 {
+        lexical_score(&self.query_tokens, result)
+    }
+
+    /// One score per result, in input order, from the backend; lexical scores stand in
+    /// when the backend fails.
+    pub fn scores(&self, results: &[SearchResult]) -> Vec<f32> {
+        match self.backend.score(&self.query, results) {
+            Ok(scores) if scores.len() == results.len() => scores,
+            Ok(scores) => {
+                warn!(
+                    "{} reranker returned {} scores for {} results; using lexical",
+                    self.backend.name(),
+                    scores.len(),
+                    results.len()
+                );
+                results.iter().map(|r| self.score_result(r)).collect()
+            }
+            Err(e) => {
+                warn!(
+                    "{} reranker failed: {:#}; using lexical",
+                    self.backend.name(),
+                    e
+                );
+                results.iter().map(|r| self.score_result(r)).collect()
+            }
+        }
+    }
+
+    /// Rerank search results and optionally filter by threshold
+    pub fn rerank(&self, results: Vec<SearchResult>, threshold: Option<f32>) -> Vec<SearchResult> {
+        let scores = self.scores(&results);
+
+        // Score all results
+        let mut scored: Vec<(SearchResult, f32)> = results.into_iter().zip(scores).collect();
+
+        // Filter by threshold if provided
+        if let Some(min_score) = threshold {
+            scored.retain(|(_, score)| *score >= min_score);
+        }
+
+        // Sort by score descending
+        scored.sort_by(|(_, a_score), (_, b_score)| {
+            b_score.partial_cmp(a_score).unwrap_or(Ordering::Equal)
+        });
+
+        info!(
+            "Reranked {} results with {} backend (threshold: {:.2})",
+            scored.len(),
+            self.backend.name(),
+            threshold.unwrap_or(0.0)
+        );
+
+        // Return only results, discard scores
+        scored.into_iter().map(|(r, _)| r).collect()
+    }
+
+    /// Get top N reranked results
+    pub fn rerank_top(&self, results: Vec<SearchResult>, top_n: usize) -> Vec<SearchResult> {
+        let mut reranked = self.rerank(results, None);
+        reranked.truncate(top_n);
+        reranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("Hello World! This is a Test-case.");
+        assert!(tokens.contains(&"hello".to_string()));
+        assert!(tokens.contains(&"world".to_string()));
+        assert!(!tokens.contains(&"is".to_string())); // Too short
+    }
+
+    #[test]
+    fn test_reranking() {
+        let query = "rust programming tutorial";
+        let reranker = Reranker::with_backend(query, &LexicalBackend);
+
+        let results = vec![
+            SearchResult {
+                url: "https://rust-lang.org".to_string(),
+                title: "The Rust Programming Language".to_string(),
+                content: "Official Rust tutorial and documentation".to_string(),
+                ..Default::default()
+            },
+            SearchResult {
+                url: "https://python.org".to_string(),
+                title: "Python Programming Language".to_string(),
+                content: "Learn Python online".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let reranked = reranker.rerank(results, None);
+        // Rust result should rank higher than Python result
+        assert_eq!(reranked[0].title, "The Rust Programming Language");
+    }
+
+    #[test]
+    fn backend_choice_falls_back_to_lexical() {
+        let model = std::env::temp_dir().join("cortex-scout-rerank-test.onnx");
+        std::fs::write(&model, b"not a real model").unwrap();
+        let model = model.to_str().unwrap();
+
+        assert_eq!(backend_choice(None, None), BackendChoice::Lexical);
+        assert_eq!(
+            backend_choice(None, Some(model)),
+            BackendChoice::Onnx(model.to_string())
+        );
+        assert_eq!(
+            backend_choice(Some("lexical"), Some(model)),
+            BackendChoice::Lexical
+        );
+        assert_eq!(
+            backend_choice(Some("onnx"), Some("/nonexistent/model.onnx")),
+            BackendChoice::Lexical
+        );
+        assert_eq!(backend_choice(Some("onnx"), None), BackendChoice::Lexical);
+        // An unloadable model degrades to lexical instead of failing.
+        let backend = load_backend(BackendChoice::Onnx(model.to_string()));
+        assert_eq!(backend.name(), "lexical");
+    }
+
+    struct FailingBackend;
+
+    impl RerankBackend for FailingBackend {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn score(&self, _query: &str, _results: &[SearchResult]) -> anyhow::Result<Vec<f32>> {
+            anyhow::bail!("model unavailable")
+        }
+    }
+
+    #[test]
+    fn scoring_errors_use_lexical_scores() {
+        let reranker = Reranker::with_backend("rust async runtime", &FailingBackend);
+        let results = ["Cooking pasta", "Rust async runtime comparison"]
+            .into_iter()
+            .map(|title| SearchResult {
+                title: title.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let top = reranker.rerank_top(results, 1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].title, "Rust async runtime comparison");
+    }
+}
@@ -0,0 +1,170 @@
+//! Sentence-embedding reranker backed by a local ONNX model.
+//!
+//! Expects a BERT-style encoder export (e.g. all-MiniLM-L6-v2) with `input_ids`,
+//! `attention_mask` and optionally `token_type_ids` inputs, plus the matching
+//! `tokenizer.json` in the same directory. Token embeddings are mean-pooled over the
+//! attention mask; models that already output one vector per sentence are used as is.
+
+use super::RerankBackend;
+use crate::types::SearchResult;
+use anyhow::{anyhow, bail, Context};
+use std::path::Path;
+use std::sync::Arc;
+use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+use tract_onnx::prelude::*;
+
+/// Texts embedded per model invocation.
+const BATCH_SIZE: usize = 32;
+/// Longer title+snippet inputs are truncated; snippets rarely need more.
+const MAX_TOKENS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModelInput {
+    InputIds,
+    AttentionMask,
+    TokenTypeIds,
+}
+
+pub struct OnnxEmbeddingBackend {
+    plan: Arc<TypedRunnableModel>,
+    inputs: Vec<ModelInput>,
+    tokenizer: Tokenizer,
+}
+
+impl OnnxEmbeddingBackend {
+    /// Load `model_path` and the `tokenizer.json` beside it.
+    pub fn load(model_path: &Path) -> anyhow::Result<Self> {
+        let tokenizer_path = model_path.with_file_name("tokenizer.json");
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+            .map_err(|e| anyhow!("{}: {}", tokenizer_path.display(), e))?;
+        tokenizer.with_padding(Some(PaddingParams::default()));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: MAX_TOKENS,
+                ..Default::default()
+            }))
+            .map_err(|e| anyhow!("tokenizer truncation: {}", e))?;
+
+        let mut model = tract_onnx::onnx()
+            .model_for_path(model_path)
+            .with_context(|| format!("reading {}", model_path.display()))?;
+        let batch = model.sym("batch");
+        let seq = model.sym("seq");
+        let mut inputs = Vec::new();
+        for (ix, outlet) in model.input_outlets()?.to_vec().into_iter().enumerate() {
+            let input = match model.node(outlet.node).name.as_str() {
+                "input_ids" => ModelInput::InputIds,
+                "attention_mask" => ModelInput::AttentionMask,
+                "token_type_ids" => ModelInput::TokenTypeIds,
+                other => bail!("unsupported model input '{}'", other),
+            };
+            inputs.push(input);
+            model.set_input_fact(
+                ix,
+                InferenceFact::dt_shape(i64::datum_type(), tvec![batch.to_dim(), seq.to_dim()]),
+            )?;
+        }
+        if !inputs.contains(&ModelInput::InputIds) {
+            bail!("model has no input_ids input");
+        }
+        let plan = model.into_optimized()?.into_runnable()?;
+
+        Ok(Self {
+            plan,
+            inputs,
+            tokenizer,
+        })
+    }
+
+    /// L2-normalised embeddings for `texts`, one row per text.
+    fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for chunk in texts.chunks(BATCH_SIZE) {
+            let encodings = self
+                .tokenizer
+                .encode_batch(chunk.to_vec(), true)
+                .map_err(|e| anyhow!("tokenize: {}", e))?;
+            let rows = encodings.len();
+            let seq_len = encodings.first().map_or(0, |e| e.get_ids().len());
+            let flat = |field: fn(&tokenizers::Encoding) -> &[u32]| -> Vec<i64> {
+                encodings
+                    .iter()
+                    .flat_map(|e| field(e).iter().map(|&v| v as i64))
+                    .collect()
+            };
+            let mask = flat(tokenizers::Encoding::get_attention_mask);
+
+            let mut feed = TVec::new();
+            for input in &self.inputs {
+                let data = match input {
+                    ModelInput::InputIds => flat(tokenizers::Encoding::get_ids),
+                    ModelInput::AttentionMask => mask.clone(),
+                    ModelInput::TokenTypeIds => flat(tokenizers::Encoding::get_type_ids),
+                };
+                feed.push(Tensor::from_shape(&[rows, seq_len], &data)?.into_tvalue());
+            }
+            let outputs = self.plan.run(feed)?;
+            let output = outputs[0].to_plain_array_view::<f32>()?;
+
+            for row in 0..rows {
+                let mut vector = match output.ndim() {
+                    // [batch, hidden]: already pooled.
+                    2 => output.slice(tract_ndarray::s![row, ..]).to_vec(),
+                    // [batch, seq, hidden]: mean over unmasked tokens.
+                    3 => {
+                        let hidden = output.shape()[2];
+                        let mut sum = vec![0.0f32; hidden];
+                        let mut count = 0.0f32;
+                        for tok in 0..seq_len {
+                            if mask[row * seq_len + tok] == 0 {
+                                continue;
+                            }
+                            count += 1.0;
+                            let token = output.slice(tract_ndarray::s![row, tok, ..]);
+                            for (acc, v) in sum.iter_mut().zip(token.iter()) {
+                                *acc += v;
+                            }
+                        }
+                        sum.iter_mut().for_each(|v| *v /= count.max(1.0));
+                        sum
+                    }
+                    n => bail!("unexpected embedding output rank {}", n),
+                };
+                let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+                if norm > 0.0 {
+                    vector.iter_mut().for_each(|v| *v /= norm);
+                }
+                embeddings.push(vector);
+            }
+        }
+        Ok(embeddings)
+    }
+}
+
+impl RerankBackend for OnnxEmbeddingBackend {
+    fn name(&self) -> &'static str {
+        "onnx"
+    }
+
+    fn score(&self, query: &str, results: &[SearchResult]) -> anyhow::Result<Vec<f32>> {
+        if results.is_empty() {
+            return Ok(Vec::new());
+        }
+        let query_embedding = self
+            .embed(&[query.to_string()])?
+            .pop()
+            .context("empty query embedding")?;
+        let documents: Vec<String> = results
+            .iter()
+            .map(|r| format!("{}\n{}", r.title, r.content))
+            .collect();
+        Ok(self
+            .embed(&documents)?
+            .iter()
+            .map(|doc| {
+                let cosine: f32 = doc.iter().zip(&query_embedding).map(|(a, b)| a * b).sum();
+                cosine.clamp(0.0, 1.0)
+            })
+            .collect())
+    }
+}
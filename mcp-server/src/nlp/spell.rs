@@ -0,0 +1,295 @@
+//! Per-token typo correction for search queries, SymSpell-style: unknown words are
+//! replaced by the dictionary word at the smallest edit distance, ties going to the more
+//! frequent word. The bundled dictionary (`data/frequency_dictionary.txt`, one
+//! `word count` pair per line) was counted from English technical prose.
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+const DICTIONARY: &str = include_str!("data/frequency_dictionary.txt");
+
+/// Shorter tokens have too many one-edit neighbours to correct safely.
+const MIN_TOKEN_CHARS: usize = 4;
+const MAX_TOKEN_CHARS: usize = 20;
+/// Tokens at least this long may be two edits away from their correction.
+const TWO_EDIT_MIN_CHARS: usize = 8;
+/// Rare dictionary words are never offered as corrections.
+const MIN_SUGGESTION_COUNT: u64 = 20;
+
+/// Crate, tool and product names that are spelled the way they are on purpose.
+const PROTECTED_WORDS: &[&str] = &[
+    "actix",
+    "anyhow",
+    "axum",
+    "chromadb",
+    "deno",
+    "gunicorn",
+    "httpx",
+    "huggingface",
+    "kubectl",
+    "langchain",
+    "lancedb",
+    "leptos",
+    "matplotlib",
+    "minikube",
+    "neurosiphon",
+    "nextjs",
+    "numpy",
+    "ollama",
+    "pgvector",
+    "pnpm",
+    "prost",
+    "pytest",
+    "qdrant",
+    "reqwest",
+    "rustls",
+    "scipy",
+    "serde",
+    "sklearn",
+    "sqlx",
+    "thiserror",
+    "tokio",
+    "uvicorn",
+    "vuejs",
+];
+
+pub struct SpellCorrector {
+    /// `(word, count)` grouped by word length; index is the length in bytes.
+    by_len: Vec<Vec<(String, u64)>>,
+    known: HashSet<String>,
+}
+
+impl SpellCorrector {
+    /// The corrector over the bundled dictionary, parsed on first use.
+    pub fn shared() -> &'static SpellCorrector {
+        static SHARED: OnceLock<SpellCorrector> = OnceLock::new();
+        SHARED.get_or_init(|| SpellCorrector::from_dictionary(DICTIONARY))
+    }
+
+    pub fn from_dictionary(dictionary: &str) -> Self {
+        let mut by_len: Vec<Vec<(String, u64)>> = Vec::new();
+        let mut known = HashSet::new();
+        for line in dictionary.lines() {
+            let mut parts = line.split_whitespace();
+            let (Some(word), Some(count)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            let Ok(count) = count.parse::<u64>() else {
+                continue;
+            };
+            let word = word.to_ascii_lowercase();
+            if by_len.len() <= word.len() {
+                by_len.resize_with(word.len() + 1, Vec::new);
+            }
+            by_len[word.len()].push((word.clone(), count));
+            known.insert(word);
+        }
+        Self { by_len, known }
+    }
+
+    pub fn is_known(&self, word: &str) -> bool {
+        let word = word.to_ascii_lowercase();
+        self.known.contains(&word) || inflection_stems(&word).any(|s| self.known.contains(s))
+    }
+
+    /// Best replacement for a single word, or `None` when it is known, protected or has
+    /// no close enough dictionary neighbour.
+    pub fn correct_word(&self, word: &str, protected: &[&str]) -> Option<String> {
+        if word.len() < MIN_TOKEN_CHARS
+            || word.len() > MAX_TOKEN_CHARS
+            || !word.chars().all(|c| c.is_ascii_alphabetic())
+            // All-caps tokens are acronyms.
+            || word.chars().all(|c| c.is_ascii_uppercase())
+        {
+            return None;
+        }
+        let lower = word.to_ascii_lowercase();
+        if self.is_known(&lower) || is_protected(&lower, protected) {
+            return None;
+        }
+
+        let max_distance = if lower.len() >= TWO_EDIT_MIN_CHARS {
+            2
+        } else {
+            1
+        };
+        let mut best: Option<(usize, u64, &str)> = None;
+        let lengths = lower.len().saturating_sub(max_distance)..=lower.len() + max_distance;
+        for len in lengths {
+            for (candidate, count) in self.by_len.get(len).into_iter().flatten() {
+                if *count < MIN_SUGGESTION_COUNT {
+                    continue;
+                }
+                let limit = best.map_or(max_distance, |(d, _, _)| d);
+                let Some(distance) = edit_distance_within(&lower, candidate, limit) else {
+                    continue;
+                };
+                let better = best.is_none_or(|(best_distance, best_count, _)| {
+                    distance < best_distance || (distance == best_distance && *count > best_count)
+                });
+                if better {
+                    best = Some((distance, *count, candidate.as_str()));
+                }
+            }
+        }
+
+        let (_, _, corrected) = best?;
+        Some(match_case(word, corrected))
+    }
+
+    /// The query with each misspelled word replaced, or `None` when nothing changed.
+    /// Operators (`site:`), quoted phrases and anything that is not a plain word are
+    /// left alone.
+    pub fn correct_query(&self, query: &str, protected: &[&str]) -> Option<String> {
+        let mut corrected = String::with_capacity(query.len());
+        let mut changed = false;
+        let mut in_quotes = false;
+        let mut rest = query;
+        while !rest.is_empty() {
+            let start = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            corrected.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let token = &rest[..end];
+            rest = &rest[end..];
+
+            let quotes = token.matches('"').count();
+            let replacement = if in_quotes || quotes > 0 {
+                None
+            } else {
+                self.correct_word(token, protected)
+            };
+            if quotes % 2 == 1 {
+                in_quotes = !in_quotes;
+            }
+            match replacement {
+                Some(word) => {
+                    corrected.push_str(&word);
+                    changed = true;
+                }
+                None => corrected.push_str(token),
+            }
+        }
+        changed.then_some(corrected)
+    }
+}
+
+/// Protected names also cover their variants (`tokio-console` style names are not plain
+/// words, but `reactjs`, `asyncio` and `rustc` start with a keyword).
+fn is_protected(word: &str, protected: &[&str]) -> bool {
+    PROTECTED_WORDS.contains(&word)
+        || protected.iter().any(|keyword| {
+            *keyword == word
+                || (keyword.len() >= MIN_TOKEN_CHARS
+                    && (word.starts_with(keyword) || word.ends_with(keyword)))
+        })
+}
+
+/// Plural and tense variants of words the dictionary only has in one form.
+fn inflection_stems(word: &str) -> impl Iterator<Item = &str> {
+    ["s", "es", "ed", "d", "ing", "er", "ly"]
+        .into_iter()
+        .filter_map(move |suffix| word.strip_suffix(suffix))
+        .filter(|stem| stem.len() >= 3)
+}
+
+fn match_case(original: &str, corrected: &str) -> String {
+    if original
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_uppercase())
+    {
+        let mut chars = corrected.chars();
+        match chars.next() {
+            Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+            None => String::new(),
+        }
+    } else {
+        corrected.to_string()
+    }
+}
+
+/// Optimal string alignment distance (Levenshtein plus adjacent transpositions), or
+/// `None` once it is known to exceed `max`.
+fn edit_distance_within(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut before_prev: Vec<usize> = Vec::new();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        curr[0] = i;
+        let mut row_min = curr[0];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut value = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                value = value.min(before_prev[j - 2] + 1);
+            }
+            curr[j] = value;
+            row_min = row_min.min(value);
+        }
+        if row_min > max {
+            return None;
+        }
+        before_prev = std::mem::replace(&mut prev, std::mem::take(&mut curr));
+        curr = vec![0; b.len() + 1];
+    }
+    let distance = prev[b.len()];
+    (distance <= max).then_some(distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrects_typos_from_the_bundled_dictionary() {
+        let spell = SpellCorrector::shared();
+        assert_eq!(
+            spell
+                .correct_query("kubernets ingress annotaion", &[])
+                .as_deref(),
+            Some("kubernetes ingress annotation")
+        );
+        assert_eq!(
+            spell.correct_query("Pyhton  tutorial", &[]).as_deref(),
+            Some("Python  tutorial")
+        );
+        assert_eq!(
+            spell.correct_word("recieve", &[]).as_deref(),
+            Some("receive")
+        );
+    }
+
+    #[test]
+    fn leaves_known_protected_and_non_word_tokens_alone() {
+        let spell = SpellCorrector::shared();
+        for query in [
+            "coffee shops near me",
+            "tokio mutex deadlock",
+            "sqlx migrations site:docs.rs",
+            "\"kubernets ingress\" error",
+            "HTTPX AWSX",
+            "rust 1.75 release",
+        ] {
+            assert_eq!(spell.correct_query(query, &[]), None, "{query}");
+        }
+        assert_eq!(spell.correct_query("flaskr app", &["flask"]), None);
+    }
+
+    #[test]
+    fn prefers_smaller_distance_then_higher_frequency() {
+        let spell = SpellCorrector::from_dictionary("alpha 100\nalpine 500\nalphas 30\n");
+        assert_eq!(spell.correct_word("alpah", &[]).as_deref(), Some("alpha"));
+        assert_eq!(spell.correct_word("alpxx", &[]), None);
+        assert_eq!(edit_distance_within("kitten", "sitting", 3), Some(3));
+        assert_eq!(edit_distance_within("kitten", "sitting", 2), None);
+        assert_eq!(edit_distance_within("teh", "the", 1), Some(1));
+    }
+}
@@ -0,0 +1,428 @@
+//! Image downloads for offline archives (`download_assets` on `web_fetch`).
+//!
+//! Files are stored content-addressed as `<asset_dir>/<domain>/<sha256>.<ext>`, so an image
+//! shared by several pages of a site (or seen again during a crawl) is written once. Each
+//! page gets its own manifest under `<asset_dir>/<domain>/manifests/` mapping the original
+//! URLs to those files.
+
+use crate::types::ScrapeResponse;
+use crate::AppState;
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Images downloaded per page when the caller does not say.
+pub const DEFAULT_MAX_ASSETS: usize = 20;
+
+const DOWNLOAD_CONCURRENCY: usize = 4;
+const ASSET_FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+/// SVGs this small are almost always tracking pixels or spacer icons.
+const TINY_SVG_BYTES: usize = 1024;
+
+#[derive(Debug, Clone, Default)]
+pub struct AssetOptions {
+    /// Root directory; `None` uses `~/.cortex-scout/assets`.
+    pub dir: Option<PathBuf>,
+    /// `None` uses [`DEFAULT_MAX_ASSETS`].
+    pub max_assets: Option<usize>,
+    /// Per-file cap; `None` uses `CORTEX_SCOUT_ASSET_MAX_BYTES` (default 5MB).
+    pub max_bytes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetEntry {
+    pub url: String,
+    pub path: String,
+    pub sha256: String,
+    pub content_type: String,
+    pub bytes: usize,
+    /// The file was already stored by an earlier page (same hash), so nothing was written.
+    pub reused: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedAsset {
+    pub url: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetManifest {
+    pub page_url: String,
+    pub generated_at: String,
+    pub assets: Vec<AssetEntry>,
+    pub skipped: Vec<SkippedAsset>,
+}
+
+pub fn default_asset_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".cortex-scout").join("assets"))
+}
+
+fn max_asset_bytes() -> usize {
+    std::env::var("CORTEX_SCOUT_ASSET_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(5 * 1024 * 1024)
+}
+
+/// Absolute, deduplicated image URLs in page order, plus the ones rejected up front.
+fn collect_candidates(page: &ScrapeResponse) -> (Vec<String>, Vec<SkippedAsset>) {
+    let base = url::Url::parse(page.final_url.as_deref().unwrap_or(&page.url)).ok();
+    let mut seen = HashSet::new();
+    let mut candidates = Vec::new();
+    let mut skipped = Vec::new();
+
+    for image in &page.images {
+        let src = image.src.trim();
+        if src.is_empty() {
+            continue;
+        }
+        if src.starts_with("data:") {
+            skipped.push(SkippedAsset {
+                url: src.chars().take(64).collect(),
+                reason: "data_uri".to_string(),
+            });
+            continue;
+        }
+        let resolved = match &base {
+            Some(base) => base.join(src),
+            None => url::Url::parse(src),
+        };
+        let Ok(resolved) = resolved else {
+            skipped.push(SkippedAsset {
+                url: src.to_string(),
+                reason: "invalid_url".to_string(),
+            });
+            continue;
+        };
+        if !matches!(resolved.scheme(), "http" | "https") {
+            skipped.push(SkippedAsset {
+                url: src.to_string(),
+                reason: "unsupported_scheme".to_string(),
+            });
+            continue;
+        }
+        let resolved = resolved.to_string();
+        if seen.insert(resolved.clone()) {
+            candidates.push(resolved);
+        }
+    }
+
+    (candidates, skipped)
+}
+
+fn is_svg(content_type: &str, url: &str) -> bool {
+    content_type.contains("svg")
+        || url
+            .split(['?', '#'])
+            .next()
+            .is_some_and(|path| path.to_ascii_lowercase().ends_with(".svg"))
+}
+
+fn extension_for(content_type: &str, url: &str) -> String {
+    let known = match content_type {
+        "image/png" => Some("png"),
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => Some("jpg"),
+        "image/gif" => Some("gif"),
+        "image/webp" => Some("webp"),
+        "image/avif" => Some("avif"),
+        "image/svg+xml" => Some("svg"),
+        "image/bmp" => Some("bmp"),
+        "image/x-icon" | "image/vnd.microsoft.icon" => Some("ico"),
+        _ => None,
+    };
+    if let Some(ext) = known {
+        return ext.to_string();
+    }
+    url.split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .filter(|ext| {
+            (1..=5).contains(&ext.len()) && ext.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+        .unwrap_or_else(|| "bin".to_string())
+}
+
+/// Download one image, enforcing the size cap while streaming. `Err` is a skip reason.
+async fn fetch_asset(
+    client: &reqwest::Client,
+    url: &str,
+    referer: &str,
+    max_bytes: usize,
+) -> Result<(Vec<u8>, String), String> {
+    let user_agent = crate::antibot::get_random_user_agent();
+    let mut resp = crate::scraping::domain_policy::get_checked(url, |hop| {
+        client
+            .get(hop)
+            .header("User-Agent", user_agent)
+            .header("Accept", "image/avif,image/webp,image/*,*/*;q=0.8")
+            .header("Referer", referer)
+            .timeout(ASSET_FETCH_TIMEOUT)
+    })
+    .await
+    .map_err(
+        |e| match e.downcast_ref::<crate::core::error::ScoutError>() {
+            Some(_) => "domain_policy".to_string(),
+            None => format!("fetch_failed: {}", e),
+        },
+    )?;
+
+    if !resp.status().is_success() {
+        return Err(format!("http_{}", resp.status().as_u16()));
+    }
+    let content_type = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| {
+            v.split(';')
+                .next()
+                .unwrap_or("")
+                .trim()
+                .to_ascii_lowercase()
+        })
+        .unwrap_or_default();
+    if !content_type.is_empty()
+        && !content_type.starts_with("image/")
+        && content_type != "application/octet-stream"
+    {
+        return Err(format!("not_an_image: {}", content_type));
+    }
+    if resp
+        .content_length()
+        .is_some_and(|len| len as usize > max_bytes)
+    {
+        return Err("too_large".to_string());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp
+        .chunk()
+        .await
+        .map_err(|e| format!("fetch_failed: {}", e))?
+    {
+        if body.len() + chunk.len() > max_bytes {
+            return Err("too_large".to_string());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok((body, content_type))
+}
+
+/// Write `body` under its hash unless an identical file is already there.
+async fn store_asset(
+    domain_dir: &Path,
+    url: &str,
+    body: &[u8],
+    content_type: &str,
+) -> Result<AssetEntry> {
+    let sha256 = hex::encode(Sha256::digest(body));
+    let path = domain_dir.join(format!("{}.{}", sha256, extension_for(content_type, url)));
+    let reused = tokio::fs::try_exists(&path).await.unwrap_or(false);
+    if !reused {
+        // Write-then-rename so a concurrent download of the same image never sees a partial file.
+        let tmp = domain_dir.join(format!(".{}.{}.tmp", sha256, uuid::Uuid::new_v4()));
+        tokio::fs::write(&tmp, body).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+    }
+    Ok(AssetEntry {
+        url: url.to_string(),
+        path: path.display().to_string(),
+        sha256,
+        content_type: content_type.to_string(),
+        bytes: body.len(),
+        reused,
+    })
+}
+
+/// Download the page's images and write its manifest. Each download holds an
+/// `outbound_limit` permit, like any other outbound request. Returns the manifest path
+/// and contents.
+pub async fn download_assets(
+    state: &Arc<AppState>,
+    page: &ScrapeResponse,
+    options: &AssetOptions,
+) -> Result<(PathBuf, AssetManifest)> {
+    let root = options
+        .dir
+        .clone()
+        .or_else(default_asset_dir)
+        .context("no asset directory: set asset_dir or HOME")?;
+    let domain = crate::features::session_store::domain_key(&page.url)
+        .unwrap_or_else(|| "unknown".to_string());
+    let domain_dir = root.join(domain);
+    let manifest_dir = domain_dir.join("manifests");
+    tokio::fs::create_dir_all(&manifest_dir)
+        .await
+        .with_context(|| format!("creating {}", manifest_dir.display()))?;
+
+    let max_assets = options.max_assets.unwrap_or(DEFAULT_MAX_ASSETS);
+    let max_bytes = options.max_bytes.unwrap_or_else(max_asset_bytes);
+    let (mut candidates, mut skipped) = collect_candidates(page);
+    for url in candidates.drain(max_assets.min(candidates.len())..) {
+        skipped.push(SkippedAsset {
+            url,
+            reason: "max_assets".to_string(),
+        });
+    }
+
+    let outcomes: Vec<(String, Result<AssetEntry, String>)> = stream::iter(candidates)
+        .map(|url| {
+            let domain_dir = domain_dir.clone();
+            async move {
+                let _permit = state
+                    .outbound_limit
+                    .acquire()
+                    .await
+                    .expect("semaphore closed");
+                let outcome = match fetch_asset(
+                    &crate::core::http_client::scrape_client(),
+                    &url,
+                    &page.url,
+                    max_bytes,
+                )
+                .await
+                {
+                    Ok((body, _)) if body.is_empty() => Err("empty".to_string()),
+                    Ok((body, content_type))
+                        if is_svg(&content_type, &url) && body.len() < TINY_SVG_BYTES =>
+                    {
+                        Err("tiny_svg".to_string())
+                    }
+                    Ok((body, content_type)) => {
+                        store_asset(&domain_dir, &url, &body, &content_type)
+                            .await
+                            .map_err(|e| format!("write_failed: {}", e))
+                    }
+                    Err(reason) => Err(reason),
+                };
+                (url, outcome)
+            }
+        })
+        .buffered(DOWNLOAD_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut assets = Vec::new();
+    for (url, outcome) in outcomes {
+        match outcome {
+            Ok(entry) => assets.push(entry),
+            Err(reason) => {
+                debug!("asset {} skipped: {}", url, reason);
+                skipped.push(SkippedAsset { url, reason });
+            }
+        }
+    }
+
+    let manifest = AssetManifest {
+        page_url: page.url.clone(),
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        assets,
+        skipped,
+    };
+    let page_key = hex::encode(Sha256::digest(page.url.as_bytes()));
+    let manifest_path = manifest_dir.join(format!("{}.json", &page_key[..16]));
+    tokio::fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)
+        .await
+        .with_context(|| format!("writing {}", manifest_path.display()))?;
+
+    info!(
+        "assets: {} stored, {} skipped for {} → {}",
+        manifest.assets.len(),
+        manifest.skipped.len(),
+        page.url,
+        manifest_path.display()
+    );
+    Ok((manifest_path, manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Image;
+
+    fn image(src: &str) -> Image {
+        Image {
+            src: src.to_string(),
+            alt: String::new(),
+            title: String::new(),
+        }
+    }
+
+    #[test]
+    fn candidates_are_resolved_deduplicated_and_filtered() {
+        let page = ScrapeResponse {
+            images: vec![
+                image("/img/a.png"),
+                image("https://example.com/img/a.png"),
+                image("data:image/gif;base64,R0lGOD"),
+                image("ftp://example.com/b.png"),
+                image("c.jpg?w=200"),
+            ],
+            ..ScrapeResponse::for_test("https://example.com/blog/post", "", "")
+        };
+
+        let (candidates, skipped) = collect_candidates(&page);
+        assert_eq!(
+            candidates,
+            [
+                "https://example.com/img/a.png",
+                "https://example.com/blog/c.jpg?w=200"
+            ]
+        );
+        let reasons: Vec<_> = skipped.iter().map(|s| s.reason.as_str()).collect();
+        assert_eq!(reasons, ["data_uri", "unsupported_scheme"]);
+    }
+
+    #[test]
+    fn extensions_and_svg_detection() {
+        assert_eq!(extension_for("image/jpeg", "https://x/y"), "jpg");
+        assert_eq!(extension_for("", "https://x/photo.WEBP?v=2"), "webp");
+        assert_eq!(
+            extension_for("application/octet-stream", "https://x/y"),
+            "bin"
+        );
+        assert!(is_svg("", "https://x/pixel.svg#t"));
+        assert!(is_svg("image/svg+xml", "https://x/p"));
+        assert!(!is_svg("image/png", "https://x/p.png"));
+    }
+
+    #[tokio::test]
+    async fn identical_images_are_stored_once() {
+        let dir =
+            std::env::temp_dir().join(format!("cortex-scout-assets-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let first = store_asset(
+            &dir,
+            "https://a.example/logo.png",
+            b"png-bytes",
+            "image/png",
+        )
+        .await
+        .unwrap();
+        let second = store_asset(
+            &dir,
+            "https://b.example/copy.png",
+            b"png-bytes",
+            "image/png",
+        )
+        .await
+        .unwrap();
+        assert!(!first.reused);
+        assert!(second.reused);
+        assert_eq!(first.path, second.path);
+        assert!(first.path.ends_with(&format!("{}.png", first.sha256)));
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
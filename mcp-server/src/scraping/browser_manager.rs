@@ -2,10 +2,10 @@
 //!
 //! This module is the **single source of truth** for:
 //! * Finding a usable browser executable (Brave → Chrome → Chromium, cross-platform).
-//! * `BrowserPool` — shared persistent browser instance with tab reuse (Step 2).
+//! * `BrowserPool` — bounded, queued pool of tabs in a warm, periodically recycled browser (Step 2).
 //! * Launching a headless browser session.
 //! * Lightweight "fetch HTML" primitives + ad-block network filter (Step 3).
-//! * Smart `wait_until_stable` / `auto_scroll` for SPA / lazy pages (Step 4).
+//! * Smart `wait_until_stable` / `wait_for_text_settle` / `auto_scroll` for SPA / lazy pages (Step 4).
 //!
 //! All other modules (cdp.rs, search engines, scrape tool, etc.) use this
 //! module. No external headless-browser sidecar is required.
@@ -15,19 +15,26 @@
 //! - JS-level stealth injection is applied in the CDP pipeline (see `rust_scraper/stealth.rs`
 //!   and `rust_scraper/cdp.rs`).
 
+use crate::core::error::ScoutError;
 use aho_corasick::AhoCorasick;
 use anyhow::{anyhow, Result};
 use chromiumoxide::browser::BrowserConfig;
+use chromiumoxide::cdp::browser_protocol::browser::BrowserContextId;
+use chromiumoxide::cdp::browser_protocol::target::{
+    CreateBrowserContextParams, CreateTargetParams,
+};
 use chromiumoxide::handler::viewport::Viewport;
 use chromiumoxide::{Browser, Page};
 use futures::StreamExt;
 use rand::seq::IndexedRandom;
+use serde::Serialize;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::OnceLock;
 use std::time::Duration;
 use tokio::task::JoinHandle;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
 use tracing::{info, warn};
 use uuid::Uuid;
 
@@ -339,7 +346,8 @@ pub fn build_headless_config(
         .arg(format!("--user-agent={}", ua));
 
     if let Some(proxy) = proxy_url {
-        builder = builder.arg(format!("--proxy-server={}", proxy));
+        let server = crate::features::proxy_manager::browser_proxy_server(proxy)?;
+        builder = builder.arg(format!("--proxy-server={}", server));
     }
 
     let config = builder
@@ -350,27 +358,211 @@ pub fn build_headless_config(
 
 // ── Browser Pool (Step 2: tab reuse) ─────────────────────────────────────────
 
-/// A shared, long-lived browser instance that reuses tabs per request.
+/// How long [`BrowserPool::shutdown`] waits for in-flight pages before closing anyway.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Point-in-time counters of a [`BrowserPool`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct BrowserPoolStats {
+    /// Maximum pages (or browser launches) in flight at once (`BROWSER_POOL_SIZE`).
+    pub size: usize,
+    pub in_use: usize,
+    /// Callers waiting for a slot.
+    pub queued: usize,
+    /// Pages opened in warm browsers since start.
+    pub pages_served: u64,
+    /// Warm browsers retired after `BROWSER_POOL_RECYCLE_PAGES` pages.
+    pub recycled: u64,
+}
+
+/// Counters shared between the pool and the leases it hands out.
+#[derive(Default)]
+struct PoolCounters {
+    in_use: AtomicUsize,
+    queued: AtomicUsize,
+    pages_served: AtomicU64,
+    recycled: AtomicU64,
+}
+
+/// Counts a caller as queued for as long as it waits, including when the wait is dropped.
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl<'a> QueuedGuard<'a> {
+    fn enter(queued: &'a AtomicUsize) -> Self {
+        queued.fetch_add(1, Ordering::SeqCst);
+        Self(queued)
+    }
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A warm browser process. Once the pool retires it (recycling or restart), it closes
+/// when the last page opened in it is released.
+struct WarmBrowser {
+    browser: Option<Browser>,
+    handler_task: Option<JoinHandle<()>>,
+    data_dir: PathBuf,
+    pages: AtomicU64,
+}
+
+impl WarmBrowser {
+    fn browser(&self) -> &Browser {
+        self.browser.as_ref().expect("warm browser is open")
+    }
+
+    async fn is_alive(&self, probe_timeout: Duration) -> bool {
+        matches!(
+            tokio::time::timeout(probe_timeout, self.browser().version()).await,
+            Ok(Ok(_))
+        )
+    }
+
+    async fn close(mut self) {
+        if let (Some(mut browser), Some(handler_task)) =
+            (self.browser.take(), self.handler_task.take())
+        {
+            let data_dir = self.data_dir.clone();
+            shutdown_browser_session(&mut browser, handler_task, data_dir, "Browser pool").await;
+        }
+    }
+}
+
+impl Drop for WarmBrowser {
+    fn drop(&mut self) {
+        let (Some(mut browser), Some(handler_task)) =
+            (self.browser.take(), self.handler_task.take())
+        else {
+            return;
+        };
+        // Drop cannot await; close from a task so no Chromium process is left behind.
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            handler_task.abort();
+            return;
+        };
+        let data_dir = self.data_dir.clone();
+        handle.spawn(async move {
+            shutdown_browser_session(&mut browser, handler_task, data_dir, "Browser pool").await;
+        });
+    }
+}
+
+/// A slot in the pool, held for the duration of one browser fetch. Dropping it frees the
+/// slot for the next queued caller.
+pub struct PoolLease {
+    _permit: OwnedSemaphorePermit,
+    counters: Arc<PoolCounters>,
+}
+
+impl Drop for PoolLease {
+    fn drop(&mut self) {
+        self.counters.in_use.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A tab in its own browser context (separate cookies and storage) of a warm pooled
+/// browser. Derefs to [`Page`]; call [`PooledPage::release`] when done, otherwise the
+/// tab is closed in the background on drop.
+pub struct PooledPage {
+    page: Option<Page>,
+    context: Option<BrowserContextId>,
+    browser: Arc<WarmBrowser>,
+    _lease: PoolLease,
+}
+
+impl PooledPage {
+    /// Close the tab and its browser context, then give the slot back.
+    pub async fn release(mut self) {
+        close_pooled_page(&self.browser, self.page.take(), self.context.take()).await;
+    }
+}
+
+impl std::ops::Deref for PooledPage {
+    type Target = Page;
+
+    fn deref(&self) -> &Page {
+        self.page.as_ref().expect("pooled page is open")
+    }
+}
+
+impl Drop for PooledPage {
+    fn drop(&mut self) {
+        let (page, context) = (self.page.take(), self.context.take());
+        if page.is_none() && context.is_none() {
+            return;
+        }
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let browser = Arc::clone(&self.browser);
+            handle.spawn(async move { close_pooled_page(&browser, page, context).await });
+        }
+    }
+}
+
+async fn close_pooled_page(
+    browser: &WarmBrowser,
+    page: Option<Page>,
+    context: Option<BrowserContextId>,
+) {
+    if let Some(page) = page {
+        let _ = page.close().await;
+    }
+    if let Some(context) = context {
+        let _ = browser.browser().dispose_browser_context(context).await;
+    }
+}
+
+/// A shared, long-lived browser that serves isolated tabs to concurrent scrapes.
 ///
-/// Instead of launching/destroying a full browser on every scrape (slow),
-/// `BrowserPool` keeps **one** browser alive and opens a fresh **tab** per
-/// request.  If the browser crashes, the next `acquire()` restarts it
-/// transparently.
+/// * At most `BROWSER_POOL_SIZE` pages (or dedicated browser launches, see
+///   [`BrowserPool::lease`]) run at once; further callers wait in FIFO order for up to
+///   `BROWSER_POOL_QUEUE_TIMEOUT_SECS` and then fail with an `unavailable`
+///   "browser pool saturated" error.
+/// * The warm browser is reused across scrapes and recycled after
+///   `BROWSER_POOL_RECYCLE_PAGES` pages so its memory does not creep; a crashed
+///   browser is restarted transparently on the next `acquire()`.
 ///
 /// Store `Arc<BrowserPool>` in `AppState` so all handlers share one instance.
 pub struct BrowserPool {
     exe: String,
-    /// Each (Browser, PathBuf) pair holds the browser and its unique user-data-dir
-    /// so the dir can be cleaned up when the browser is restarted or dropped.
-    inner: Mutex<Option<(Browser, PathBuf)>>,
+    size: usize,
+    queue_timeout: Duration,
+    recycle_after: u64,
+    slots: Arc<Semaphore>,
+    counters: Arc<PoolCounters>,
+    warm: Mutex<Option<Arc<WarmBrowser>>>,
 }
 
 impl BrowserPool {
-    /// Create a pool for the given executable. Browser is lazy-started.
+    /// Create a pool for the given executable, sized from the environment. The browser is
+    /// lazy-started.
     pub fn new(exe: impl Into<String>) -> Arc<Self> {
+        Self::with_limits(
+            exe,
+            crate::core::config::browser_pool_size(),
+            Duration::from_secs(crate::core::config::browser_pool_queue_timeout_secs()),
+            crate::core::config::browser_pool_recycle_pages(),
+        )
+    }
+
+    /// Create a pool with explicit limits instead of the environment's.
+    pub fn with_limits(
+        exe: impl Into<String>,
+        size: usize,
+        queue_timeout: Duration,
+        recycle_after: u64,
+    ) -> Arc<Self> {
+        let size = size.max(1);
         Arc::new(Self {
             exe: exe.into(),
-            inner: Mutex::new(None),
+            size,
+            queue_timeout,
+            recycle_after: recycle_after.max(1),
+            slots: Arc::new(Semaphore::new(size)),
+            counters: Arc::default(),
+            warm: Mutex::new(None),
         })
     }
 
@@ -380,64 +572,146 @@ impl BrowserPool {
         find_chrome_executable().map(Self::new)
     }
 
-    /// Acquire a fresh tab from the persistent browser.
+    pub fn stats(&self) -> BrowserPoolStats {
+        BrowserPoolStats {
+            size: self.size,
+            in_use: self.counters.in_use.load(Ordering::SeqCst),
+            queued: self.counters.queued.load(Ordering::SeqCst),
+            pages_served: self.counters.pages_served.load(Ordering::SeqCst),
+            recycled: self.counters.recycled.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Wait (FIFO, up to the queue timeout) for a free slot. Callers that launch their own
+    /// browser — e.g. to route it through a proxy — hold a lease so they count against
+    /// the pool size too.
+    pub async fn lease(&self) -> Result<PoolLease> {
+        let waited = {
+            let _queued = QueuedGuard::enter(&self.counters.queued);
+            tokio::time::timeout(self.queue_timeout, Arc::clone(&self.slots).acquire_owned()).await
+        };
+        let permit = match waited {
+            Ok(Ok(permit)) => permit,
+            Ok(Err(_)) => return Err(ScoutError::unavailable("browser pool is shut down").into()),
+            Err(_) => {
+                let stats = self.stats();
+                return Err(ScoutError::unavailable(format!(
+                    "browser pool saturated: {} page(s) in use, {} queued; retry later",
+                    stats.in_use, stats.queued
+                ))
+                .with_details(serde_json::json!({
+                    "pool_size": stats.size,
+                    "in_use": stats.in_use,
+                    "queued": stats.queued,
+                    "queue_timeout_secs": self.queue_timeout.as_secs(),
+                }))
+                .into());
+            }
+        };
+        self.counters.in_use.fetch_add(1, Ordering::SeqCst);
+        Ok(PoolLease {
+            _permit: permit,
+            counters: Arc::clone(&self.counters),
+        })
+    }
+
+    /// Acquire a fresh tab, in its own browser context, from the warm browser.
     ///
-    /// * Lazy-starts the browser on first call.
-    /// * Restarts transparently if the process has crashed.
-    /// * Close the returned `Page` when done — the browser stays alive.
-    pub async fn acquire(&self, proxy_url: Option<&str>) -> Result<Page> {
-        let mut guard = self.inner.lock().await;
-        let probe_timeout = Duration::from_secs(crate::core::config::browser_tab_probe_timeout_secs());
-
-        // Probe: try opening a blank tab to test if browser is still alive.
-        let alive = match guard.as_mut() {
-            Some((b, _)) => match tokio::time::timeout(probe_timeout, b.new_page("about:blank")).await {
-                Ok(Ok(page)) => {
-                    drop(page);
-                    true
-                }
-                Ok(Err(_)) | Err(_) => false,
-            },
-            None => false,
+    /// * Waits for a slot like [`Self::lease`].
+    /// * Lazy-starts the browser on first call and restarts it if it has crashed.
+    /// * Release the returned page when done — the browser stays alive.
+    pub async fn acquire(&self) -> Result<PooledPage> {
+        let lease = self.lease().await?;
+        let browser = self.warm_browser().await?;
+        let context = browser
+            .browser()
+            .create_browser_context(CreateBrowserContextParams::default())
+            .await
+            .map_err(|e| anyhow!("Pool: failed to create browser context: {}", e))?;
+        let params = CreateTargetParams::builder()
+            .url("about:blank")
+            .browser_context_id(context.clone())
+            .build()
+            .map_err(|e| anyhow!("Pool: invalid tab parameters: {}", e))?;
+        let page = match browser.browser().new_page(params).await {
+            Ok(page) => page,
+            Err(e) => {
+                close_pooled_page(&browser, None, Some(context)).await;
+                return Err(anyhow!("Pool: failed to open tab: {}", e));
+            }
         };
+        browser.pages.fetch_add(1, Ordering::SeqCst);
+        self.counters.pages_served.fetch_add(1, Ordering::SeqCst);
+        Ok(PooledPage {
+            page: Some(page),
+            context: Some(context),
+            browser,
+            _lease: lease,
+        })
+    }
 
-        if !alive {
-            if guard.is_some() {
+    /// The current warm browser, launching (or recycling/restarting) it first as needed.
+    async fn warm_browser(&self) -> Result<Arc<WarmBrowser>> {
+        let mut guard = self.warm.lock().await;
+        if let Some(current) = guard.as_ref() {
+            let pages = current.pages.load(Ordering::SeqCst);
+            let probe_timeout =
+                Duration::from_secs(crate::core::config::browser_tab_probe_timeout_secs());
+            if pages >= self.recycle_after {
+                info!("♻️ Browser pool: recycling instance after {} pages", pages);
+                self.counters.recycled.fetch_add(1, Ordering::SeqCst);
+                *guard = None;
+            } else if !current.is_alive(probe_timeout).await {
                 warn!("🔄 Browser pool: instance dead, restarting...");
-                if let Some((mut old, old_dir)) = guard.take() {
-                    let _ = old.close().await;
-                    let _ = tokio::fs::remove_dir_all(&old_dir).await;
-                }
+                *guard = None;
             }
-            info!("🚀 Browser pool: launching new instance ({})", self.exe);
-            let (config, data_dir) = build_headless_config(&self.exe, proxy_url, 1920, 1080)?;
-            let (new_browser, mut handler) = launch_browser_serialized(
-                config,
-                &format!("Pool: failed to launch ({})", self.exe),
-            )
-            .await?;
-            tokio::spawn(async move {
-                while let Some(event) = handler.next().await {
-                    if let Err(e) = event {
-                        log_cdp_handler_error("Pool CDP handler error", &e.to_string());
-                    }
-                }
-            });
-            *guard = Some((new_browser, data_dir));
         }
 
-        let (b, _) = guard.as_mut().expect("browser present after init");
-        b.new_page("about:blank")
-            .await
-            .map_err(|e| anyhow!("Pool: failed to open tab: {}", e))
+        if let Some(current) = guard.as_ref() {
+            return Ok(Arc::clone(current));
+        }
+        info!("🚀 Browser pool: launching new instance ({})", self.exe);
+        let (config, data_dir) = build_headless_config(&self.exe, None, 1920, 1080)?;
+        let (browser, mut handler) =
+            launch_browser_serialized(config, &format!("Pool: failed to launch ({})", self.exe))
+                .await?;
+        let handler_task = tokio::spawn(async move {
+            while let Some(event) = handler.next().await {
+                if let Err(e) = event {
+                    log_cdp_handler_error("Pool CDP handler error", &e.to_string());
+                }
+            }
+        });
+        let warm = Arc::new(WarmBrowser {
+            browser: Some(browser),
+            handler_task: Some(handler_task),
+            data_dir,
+            pages: AtomicU64::new(0),
+        });
+        *guard = Some(Arc::clone(&warm));
+        Ok(warm)
     }
 
-    /// Gracefully close the pooled browser instance.
+    /// Stop handing out slots, wait for in-flight pages to be released (up to 30s), then
+    /// close the warm browser. Callers still queued get a "shut down" error.
     pub async fn shutdown(&self) {
-        let mut guard = self.inner.lock().await;
-        if let Some((mut b, data_dir)) = guard.take() {
-            let _ = b.close().await;
-            let _ = tokio::fs::remove_dir_all(&data_dir).await;
+        let drain = Arc::clone(&self.slots).acquire_many_owned(self.size as u32);
+        match tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, drain).await {
+            Ok(_) => {}
+            Err(_) => warn!(
+                "Browser pool: {} page(s) still in use after {}s; closing anyway",
+                self.counters.in_use.load(Ordering::SeqCst),
+                SHUTDOWN_DRAIN_TIMEOUT.as_secs()
+            ),
+        }
+        self.slots.close();
+
+        let warm = self.warm.lock().await.take();
+        if let Some(warm) = warm {
+            // Still shared only if the drain timed out; it then closes on its last release.
+            if let Ok(warm) = Arc::try_unwrap(warm) {
+                warm.close().await;
+            }
             info!("🛑 Browser pool shut down");
         }
     }
@@ -572,23 +846,59 @@ pub async fn wait_until_stable(page: &Page, quiet_ms: u64, timeout_ms: u64) -> R
     Ok(())
 }
 
-impl Drop for BrowserPool {
-    fn drop(&mut self) {
-        // Best-effort cleanup. Drop cannot await; if we're inside a tokio runtime,
-        // spawn a task to close the browser to avoid zombie Chromium processes.
-        let Ok(handle) = tokio::runtime::Handle::try_current() else {
-            return;
-        };
+/// How [`wait_for_text_settle`] ended.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextSettle {
+    pub settled: bool,
+    pub checks: u64,
+    pub len: u64,
+    pub elapsed_ms: u64,
+}
 
-        if let Ok(mut guard) = self.inner.try_lock() {
-            if let Some((mut browser, data_dir)) = guard.take() {
-                handle.spawn(async move {
-                    let _ = browser.close().await;
-                    let _ = tokio::fs::remove_dir_all(&data_dir).await;
-                });
+/// Wait until `document.body.innerText.length` holds still for 1.5s (15s at most), the
+/// point a client-rendered view has finished drawing. Polled every 250 ms in the page.
+pub async fn wait_for_text_settle(page: &Page) -> Result<TextSettle> {
+    let settle_script = r#"
+        (async () => {
+            const maxChecks = 60;   // 15s max
+            const interval = 250;   // ms
+            const requiredStableMs = 1500;
+
+            let lastLen = (document.body && document.body.innerText) ? document.body.innerText.length : 0;
+            let stableMs = 0;
+
+            for (let i = 0; i < maxChecks; i++) {
+                await new Promise(r => setTimeout(r, interval));
+                const curLen = (document.body && document.body.innerText) ? document.body.innerText.length : 0;
+                if (curLen === lastLen) {
+                    stableMs += interval;
+                    if (stableMs >= requiredStableMs) {
+                        return { settled: true, checks: i + 1, len: curLen, elapsedMs: (i + 1) * interval };
+                    }
+                } else {
+                    stableMs = 0;
+                }
+                lastLen = curLen;
             }
-        }
-    }
+            return { settled: false, checks: maxChecks, len: lastLen, elapsedMs: maxChecks * interval };
+        })()
+    "#;
+    let info = page
+        .evaluate(settle_script)
+        .await
+        .map_err(|e| anyhow!("innerText settle check failed: {}", e))?
+        .into_value::<serde_json::Value>()
+        .map_err(|e| anyhow!("innerText settle check returned no result: {}", e))?;
+    let number = |key: &str| info.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    Ok(TextSettle {
+        settled: info
+            .get("settled")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        checks: number("checks"),
+        len: number("len"),
+        elapsed_ms: number("elapsedMs"),
+    })
 }
 
 /// Auto-scroll the full page height to trigger lazy-loaded / intersection-observer
@@ -773,4 +1083,74 @@ mod tests {
         assert!(debug.contains("headless: New"), "{debug}");
         assert!(debug.contains("sandbox: false"), "{debug}");
     }
+
+    fn test_pool(size: usize, queue_timeout: Duration) -> Arc<BrowserPool> {
+        BrowserPool::with_limits("/bin/false", size, queue_timeout, 10)
+    }
+
+    #[tokio::test]
+    async fn saturated_pool_fails_queued_callers_after_the_timeout() {
+        let pool = test_pool(1, Duration::from_millis(50));
+        let held = pool.lease().await.unwrap();
+        let err = pool.lease().await.err().expect("pool is saturated");
+        let err = err.downcast_ref::<ScoutError>().expect("typed error");
+        assert_eq!(err.kind, crate::core::error::ErrorKind::Unavailable);
+        assert!(
+            err.message.contains("browser pool saturated"),
+            "{}",
+            err.message
+        );
+        assert_eq!(err.details.as_ref().unwrap()["in_use"], 1);
+
+        let stats = pool.stats();
+        assert_eq!((stats.size, stats.in_use, stats.queued), (1, 1, 0));
+        drop(held);
+        assert_eq!(pool.stats().in_use, 0);
+        assert!(pool.lease().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn queued_callers_are_served_in_arrival_order() {
+        let pool = test_pool(1, Duration::from_secs(5));
+        let held = pool.lease().await.unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for caller in 1..=3 {
+            let (waiter, tx) = (Arc::clone(&pool), tx.clone());
+            tokio::spawn(async move {
+                let _lease = waiter.lease().await.unwrap();
+                tx.send(caller).unwrap();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            });
+            // Let each caller join the queue before the next one arrives.
+            while pool.stats().queued < caller {
+                tokio::task::yield_now().await;
+            }
+        }
+        drop(held);
+        let mut order = Vec::new();
+        for _ in 0..3 {
+            order.push(rx.recv().await.unwrap());
+        }
+        assert_eq!(order, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn shutdown_drains_in_flight_leases_and_refuses_new_ones() {
+        let pool = test_pool(2, Duration::from_secs(5));
+        let held = pool.lease().await.unwrap();
+        let shutdown = tokio::spawn({
+            let pool = Arc::clone(&pool);
+            async move { pool.shutdown().await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(
+            !shutdown.is_finished(),
+            "shutdown must wait for the page in use"
+        );
+
+        drop(held);
+        shutdown.await.unwrap();
+        let err = pool.lease().await.err().expect("pool is shut down");
+        assert!(err.to_string().contains("shut down"), "{err}");
+    }
 }
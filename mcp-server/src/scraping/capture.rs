@@ -0,0 +1,393 @@
+//! Opt-in debug captures, so "extraction returned garbage for URL X" can be reproduced
+//! from the exact bytes the pipeline saw instead of a page the user has to share.
+//!
+//! With `CORTEX_SCOUT_CAPTURE_DIR` (or `SHADOWCRAWL_CAPTURE_DIR`) set, every HTML page the
+//! scraper extracts is written to `<dir>/<capture_id>/`: `request.json` (URLs, status,
+//! request and response headers), `page.html.gz` (the decoded HTML) and `response.json`
+//! (the `ScrapeResponse` produced from it). The result carries a `capture_id:<id>` warning
+//! to quote in bug reports. Secret-looking headers (`Authorization`, cookies, tokens) and
+//! query parameters (`token`, `api_key`, `sig`, ...) are redacted before anything is
+//! written; the page body itself is kept as served. Once the directory holds more than
+//! `CORTEX_SCOUT_CAPTURE_MAX_MB` (default 256), the oldest bundles are removed.
+//!
+//! `cortex-scout replay <capture_id>` runs the captured HTML through `process_html` again,
+//! so a parser fix can be checked against the page that misbehaved.
+
+use crate::core::config::env_var;
+use crate::rust_scraper::RustScraper;
+use crate::scraping::request_headers::is_secret;
+use crate::types::{ScrapeMethod, ScrapeResponse};
+use anyhow::{anyhow, bail, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+use url::{Position, Url};
+
+/// Capture directory budget when `CORTEX_SCOUT_CAPTURE_MAX_MB` is unset.
+pub const DEFAULT_MAX_MB: u64 = 256;
+
+/// Query parameter names redacted when they contain one of these.
+const SECRET_PARAM_FRAGMENTS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "passwd",
+    "session",
+    "signature",
+    "credential",
+    "apikey",
+];
+
+/// Query parameter names redacted when one of their `_`/`-`/`.`-separated words is one of
+/// these (`api_key`, `X-Amz-Credential`, `sig`), so `keywords` or `design` stay readable.
+const SECRET_PARAM_WORDS: &[&str] = &["key", "sig", "auth", "pwd", "code"];
+
+const REDACTED: &str = "REDACTED";
+
+/// What was asked for and what came back, with secrets redacted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureRequest {
+    pub url: String,
+    /// Where redirects ended, when they did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>,
+    pub method: ScrapeMethod,
+    pub status_code: u16,
+    /// RFC 3339.
+    #[serde(default)]
+    pub captured_at: String,
+    #[serde(default)]
+    pub request_headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub response_headers: Vec<(String, String)>,
+}
+
+impl CaptureRequest {
+    pub fn new(url: &str, method: ScrapeMethod, status_code: u16) -> Self {
+        Self {
+            url: url.to_string(),
+            final_url: None,
+            method,
+            status_code,
+            captured_at: chrono::Utc::now().to_rfc3339(),
+            request_headers: Vec::new(),
+            response_headers: Vec::new(),
+        }
+    }
+
+    fn scrubbed(mut self) -> Self {
+        self.url = scrub_url(&self.url);
+        self.final_url = self.final_url.as_deref().map(scrub_url);
+        self.request_headers = scrub_headers(self.request_headers);
+        self.response_headers = scrub_headers(self.response_headers);
+        self
+    }
+}
+
+/// `url` (absolute, or relative like a `Location` header) with the values of
+/// secret-looking query parameters replaced; unparseable input is returned as is.
+pub fn scrub_url(url: &str) -> String {
+    let (mut parsed, relative) = match Url::parse(url) {
+        Ok(parsed) => (parsed, false),
+        Err(_) => match Url::parse("http://relative.invalid").and_then(|base| base.join(url)) {
+            Ok(parsed) => (parsed, true),
+            Err(_) => return url.to_string(),
+        },
+    };
+    if parsed.query().is_none() {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if is_secret_param(&name) {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    if relative {
+        parsed[Position::BeforePath..].to_string()
+    } else {
+        parsed.to_string()
+    }
+}
+
+fn is_secret_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_PARAM_FRAGMENTS
+        .iter()
+        .any(|fragment| name.contains(fragment))
+        || name
+            .split(['_', '-', '.'])
+            .any(|word| SECRET_PARAM_WORDS.contains(&word))
+}
+
+/// `headers` with the values of secret headers (see [`is_secret`]) replaced.
+pub fn scrub_headers(headers: Vec<(String, String)>) -> Vec<(String, String)> {
+    headers
+        .into_iter()
+        .map(|(name, value)| {
+            let value = if is_secret(&name) {
+                "<redacted>".to_string()
+            } else if name.eq_ignore_ascii_case("location")
+                || name.eq_ignore_ascii_case("content-location")
+            {
+                scrub_url(&value)
+            } else {
+                value
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct Captures {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl Captures {
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+        }
+    }
+
+    /// `None` unless `CORTEX_SCOUT_CAPTURE_DIR` is set.
+    pub fn from_env() -> Option<Self> {
+        let dir = env_var(&["CORTEX_SCOUT_CAPTURE_DIR", "SHADOWCRAWL_CAPTURE_DIR"])?;
+        let max_mb = env_var(&["CORTEX_SCOUT_CAPTURE_MAX_MB", "SHADOWCRAWL_CAPTURE_MAX_MB"])
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_MAX_MB);
+        Some(Self::new(dir, max_mb * 1024 * 1024))
+    }
+
+    /// Write a bundle for `result`, extracted from `html`, and note its id in
+    /// `result.warnings`. Returns the id.
+    pub async fn save(
+        &self,
+        request: CaptureRequest,
+        html: &str,
+        result: &mut ScrapeResponse,
+    ) -> Result<String> {
+        let capture_id = format!(
+            "{}-{}",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S"),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+        let bundle = self.dir.join(&capture_id);
+        tokio::fs::create_dir_all(&bundle)
+            .await
+            .with_context(|| format!("creating {}", bundle.display()))?;
+        result.warnings.push(format!("capture_id:{}", capture_id));
+
+        let mut response = result.clone();
+        response.url = scrub_url(&response.url);
+        response.final_url = response.final_url.as_deref().map(scrub_url);
+        for hop in &mut response.redirect_chain {
+            hop.url = scrub_url(&hop.url);
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(html.as_bytes())?;
+        let files = [
+            (
+                "request.json",
+                serde_json::to_vec_pretty(&request.scrubbed())?,
+            ),
+            ("page.html.gz", encoder.finish()?),
+            ("response.json", serde_json::to_vec_pretty(&response)?),
+        ];
+        for (name, bytes) in files {
+            let path = bundle.join(name);
+            tokio::fs::write(&path, bytes)
+                .await
+                .with_context(|| format!("writing {}", path.display()))?;
+        }
+        debug!("captured {} as {}", response.url, capture_id);
+
+        match prune(&self.dir, self.max_bytes, &capture_id).await {
+            Ok(0) => {}
+            Ok(removed) => debug!(
+                "Removed {} old capture(s) from {}",
+                removed,
+                self.dir.display()
+            ),
+            Err(e) => debug!("Capture pruning failed in {}: {}", self.dir.display(), e),
+        }
+        Ok(capture_id)
+    }
+
+    /// The request metadata and HTML of a capture.
+    pub async fn load(&self, capture_id: &str) -> Result<(CaptureRequest, String)> {
+        let capture_id = capture_id.trim().trim_start_matches("capture_id:");
+        if capture_id.is_empty()
+            || !capture_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            bail!("invalid capture id '{}'", capture_id);
+        }
+        let bundle = self.dir.join(capture_id);
+        let request_path = bundle.join("request.json");
+        let raw = tokio::fs::read(&request_path)
+            .await
+            .with_context(|| format!("no capture {} in {}", capture_id, self.dir.display()))?;
+        let request: CaptureRequest = serde_json::from_slice(&raw)
+            .with_context(|| format!("invalid {}", request_path.display()))?;
+        let gz = tokio::fs::read(bundle.join("page.html.gz"))
+            .await
+            .with_context(|| format!("capture {} has no page.html.gz", capture_id))?;
+        let mut html = String::new();
+        GzDecoder::new(gz.as_slice())
+            .read_to_string(&mut html)
+            .with_context(|| format!("capture {} has a corrupt page.html.gz", capture_id))?;
+        Ok((request, html))
+    }
+
+    /// Extract a capture's HTML again, as the URL it was finally served from.
+    pub async fn replay(&self, capture_id: &str) -> Result<ScrapeResponse> {
+        let (request, html) = self.load(capture_id).await?;
+        let url = request.final_url.as_deref().unwrap_or(&request.url);
+        RustScraper::new()
+            .with_captures(None)
+            .process_html(&html, url)
+            .await
+    }
+}
+
+/// Total size of the bundles in `dir`; the oldest (ids sort by time) are removed until it
+/// fits `max_bytes`, never `keep`. Returns how many were removed.
+async fn prune(dir: &Path, max_bytes: u64, keep: &str) -> Result<usize> {
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    let mut bundles: Vec<(String, u64)> = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        if !entry.file_type().await?.is_dir() {
+            continue;
+        }
+        let mut size = 0;
+        let mut files = tokio::fs::read_dir(entry.path()).await?;
+        while let Some(file) = files.next_entry().await? {
+            size += file.metadata().await.map(|m| m.len()).unwrap_or(0);
+        }
+        bundles.push((entry.file_name().to_string_lossy().into_owned(), size));
+    }
+    bundles.sort();
+
+    let mut total: u64 = bundles.iter().map(|(_, size)| size).sum();
+    let mut removed = 0;
+    for (name, size) in bundles {
+        if total <= max_bytes {
+            break;
+        }
+        if name == keep {
+            continue;
+        }
+        if tokio::fs::remove_dir_all(dir.join(&name)).await.is_ok() {
+            total = total.saturating_sub(size);
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// `replay <capture_id>`: print the `ScrapeResponse` the current pipeline produces from a
+/// capture in `CORTEX_SCOUT_CAPTURE_DIR`.
+pub async fn run_replay(args: &[String]) -> Result<()> {
+    let capture_id = args
+        .first()
+        .ok_or_else(|| anyhow!("usage: cortex-scout replay <capture_id>"))?;
+    let captures = Captures::from_env()
+        .ok_or_else(|| anyhow!("set CORTEX_SCOUT_CAPTURE_DIR to the capture directory"))?;
+    let result = captures.replay(capture_id).await?;
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("cortex-scout-captures-{}", uuid::Uuid::new_v4()))
+    }
+
+    const PAGE: &str = "<html><head><title>Quarterly report</title></head><body>\
+        <p>Revenue grew in every region this quarter.</p></body></html>";
+
+    #[test]
+    fn secret_params_and_headers_are_redacted() {
+        assert_eq!(
+            scrub_url("https://example.com/a?q=rust&access_token=abc&api_key=k&keywords=x&X-Amz-Signature=s"),
+            "https://example.com/a?q=rust&access_token=REDACTED&api_key=REDACTED&keywords=x&X-Amz-Signature=REDACTED"
+        );
+        assert_eq!(scrub_url("https://example.com/a"), "https://example.com/a");
+        let headers = scrub_headers(vec![
+            ("Authorization".to_string(), "Bearer abc".to_string()),
+            ("set-cookie".to_string(), "sid=1".to_string()),
+            ("content-type".to_string(), "text/html".to_string()),
+            ("location".to_string(), "/next?session_id=9".to_string()),
+        ]);
+        assert_eq!(
+            headers,
+            [
+                ("Authorization", "<redacted>"),
+                ("set-cookie", "<redacted>"),
+                ("content-type", "text/html"),
+                ("location", "/next?session_id=REDACTED"),
+            ]
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn captures_are_scrubbed_replayable_and_rotated() {
+        let dir = temp_dir();
+        let captures = Captures::new(&dir, 1);
+        let url = "https://reports.example.com/q3?token=s3cret";
+        let mut result = RustScraper::new()
+            .with_captures(None)
+            .process_html(PAGE, url)
+            .await
+            .unwrap();
+        let mut request = CaptureRequest::new(url, ScrapeMethod::Static, 200);
+        request.request_headers = vec![("cookie".to_string(), "sid=s3cret".to_string())];
+
+        let first = captures
+            .save(request.clone(), PAGE, &mut result)
+            .await
+            .unwrap();
+        assert!(result.warnings.contains(&format!("capture_id:{}", first)));
+        for file in ["request.json", "response.json"] {
+            let written = std::fs::read_to_string(dir.join(&first).join(file)).unwrap();
+            assert!(!written.contains("s3cret"), "{}: {}", file, written);
+        }
+
+        let (loaded, html) = captures
+            .load(&format!("capture_id:{}", first))
+            .await
+            .unwrap();
+        assert_eq!(html, PAGE);
+        assert_eq!(loaded.url, "https://reports.example.com/q3?token=REDACTED");
+        let replayed = captures.replay(&first).await.unwrap();
+        assert_eq!(replayed.title, "Quarterly report");
+        assert_eq!(replayed.word_count, result.word_count);
+        assert!(captures.load("../etc").await.is_err());
+
+        // Over budget: every older bundle goes, the newest one stays.
+        let second = captures.save(request, PAGE, &mut result).await.unwrap();
+        assert!(!dir.join(&first).exists());
+        assert!(dir.join(&second).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
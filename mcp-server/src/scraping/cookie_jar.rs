@@ -0,0 +1,185 @@
+//! Opt-in per-site cookie jars for native (reqwest) fetches.
+//!
+//! `RustScraper` normally builds a client without a cookie store, so every request looks
+//! like a first visit. A scrape with `persist_session: true` builds it on the site's
+//! shared jar instead: consent and session cookies set by one page are sent by the next
+//! scrape, batch item or crawl page of the same site. The anti-bot headers are still
+//! added per request.
+//!
+//! Jars are keyed by host (a leading `www.` is ignored) and live only in memory unless
+//! `CORTEX_SCOUT_COOKIE_PERSIST=1`, in which case unexpired persistent cookies are written
+//! to `<dir>/<site>.json` (see [`crate::core::config::cookie_persist_dir`]).
+
+use anyhow::{Context, Result};
+use cookie_store::CookieStore;
+use reqwest_cookie_store::CookieStoreMutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, warn};
+
+pub type SharedJar = Arc<CookieStoreMutex>;
+
+#[derive(Default)]
+pub struct CookieJars {
+    jars: Mutex<HashMap<String, SharedJar>>,
+    persist_dir: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for CookieJars {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CookieJars")
+            .field("sites", &self.jars.lock().unwrap().len())
+            .field("persist_dir", &self.persist_dir)
+            .finish()
+    }
+}
+
+/// Jar key for a URL: lowercase host without a leading `www.`, filesystem-safe.
+pub fn site_key(url: &str) -> Option<String> {
+    let host = url::Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    Some(host.replace(['.', ':'], "_"))
+}
+
+impl CookieJars {
+    pub fn new(persist_dir: Option<PathBuf>) -> Self {
+        Self {
+            jars: Mutex::new(HashMap::new()),
+            persist_dir,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(crate::core::config::cookie_persist_dir())
+    }
+
+    fn jar_path(dir: &Path, key: &str) -> PathBuf {
+        dir.join(format!("{}.json", key))
+    }
+
+    fn load(&self, key: &str) -> CookieStore {
+        let Some(dir) = &self.persist_dir else {
+            return CookieStore::default();
+        };
+        let path = Self::jar_path(dir, key);
+        let Ok(file) = std::fs::File::open(&path) else {
+            return CookieStore::default();
+        };
+        cookie_store::serde::json::load(std::io::BufReader::new(file)).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable cookie jar {}: {}", path.display(), e);
+            CookieStore::default()
+        })
+    }
+
+    /// The site's jar, created (or loaded from disk when persistence is on) on first use.
+    pub fn jar_for(&self, url: &str) -> Option<SharedJar> {
+        let key = site_key(url)?;
+        let mut jars = self.jars.lock().unwrap();
+        if let Some(jar) = jars.get(&key) {
+            return Some(Arc::clone(jar));
+        }
+        let jar = Arc::new(CookieStoreMutex::new(self.load(&key)));
+        jars.insert(key, Arc::clone(&jar));
+        Some(jar)
+    }
+
+    /// Drop the site's cookies from memory and disk. Returns whether a jar existed.
+    pub fn clear(&self, url: &str) -> bool {
+        let Some(key) = site_key(url) else {
+            return false;
+        };
+        let mut existed = self.jars.lock().unwrap().remove(&key).is_some();
+        if let Some(dir) = &self.persist_dir {
+            existed |= std::fs::remove_file(Self::jar_path(dir, &key)).is_ok();
+        }
+        debug!("Cleared cookie jar for {} (existed: {})", key, existed);
+        existed
+    }
+
+    /// Write the site's jar to disk; a no-op unless persistence is enabled.
+    pub fn save(&self, url: &str) -> Result<()> {
+        let (Some(dir), Some(key)) = (&self.persist_dir, site_key(url)) else {
+            return Ok(());
+        };
+        let Some(jar) = self.jars.lock().unwrap().get(&key).cloned() else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        let mut buf = Vec::new();
+        {
+            let store = jar.lock().unwrap();
+            cookie_store::serde::json::save(&store, &mut buf)
+                .map_err(|e| anyhow::anyhow!("serializing cookies: {}", e))?;
+        }
+        let path = Self::jar_path(dir, &key);
+        let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+        std::fs::write(&tmp, buf)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_cookie(jar: &SharedJar, set_cookie: &str, url: &str) {
+        let url = url::Url::parse(url).unwrap();
+        jar.lock()
+            .unwrap()
+            .parse(set_cookie, &url)
+            .expect("valid cookie");
+    }
+
+    fn cookie_names(jar: &SharedJar, url: &str) -> Vec<String> {
+        let url = url::Url::parse(url).unwrap();
+        jar.lock()
+            .unwrap()
+            .matches(&url)
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn jars_are_shared_per_site_and_clearable() {
+        let jars = CookieJars::new(None);
+        let jar = jars.jar_for("https://www.example.com/a").unwrap();
+        store_cookie(&jar, "consent=yes; Path=/", "https://www.example.com/a");
+
+        let same = jars.jar_for("https://example.com/b").unwrap();
+        assert!(Arc::ptr_eq(&jar, &same));
+        let other = jars.jar_for("https://other.org/").unwrap();
+        assert!(cookie_names(&other, "https://other.org/").is_empty());
+
+        assert!(jars.clear("https://example.com/"));
+        let fresh = jars.jar_for("https://www.example.com/a").unwrap();
+        assert!(cookie_names(&fresh, "https://www.example.com/a").is_empty());
+        assert!(!jars.clear("not a url"));
+    }
+
+    #[test]
+    fn persistence_round_trips_only_when_enabled() {
+        let dir =
+            std::env::temp_dir().join(format!("cortex-scout-cookies-{}", uuid::Uuid::new_v4()));
+        let url = "https://example.com/";
+
+        let jars = CookieJars::new(Some(dir.clone()));
+        let jar = jars.jar_for(url).unwrap();
+        store_cookie(&jar, "sid=1; Max-Age=3600; Path=/", url);
+        jars.save(url).unwrap();
+
+        let reloaded = CookieJars::new(Some(dir.clone()));
+        let jar = reloaded.jar_for(url).unwrap();
+        assert_eq!(cookie_names(&jar, url), ["sid"]);
+
+        assert!(reloaded.clear(url));
+        assert!(!CookieJars::jar_path(&dir, "example_com").exists());
+
+        let memory_only = CookieJars::new(None);
+        memory_only.jar_for(url).unwrap();
+        memory_only.save(url).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,499 @@
+//! Operator-defined limits on which hosts the server may contact.
+//!
+//! - `CORTEX_SCOUT_DOMAIN_ALLOWLIST` (or `SHADOWCRAWL_DOMAIN_ALLOWLIST`): comma-separated
+//!   hosts; when set, every other host is refused.
+//! - `CORTEX_SCOUT_DOMAIN_DENYLIST` (or `SHADOWCRAWL_DOMAIN_DENYLIST`): comma-separated
+//!   hosts that are always refused (deny wins over allow).
+//! - Loopback, private, link-local, CGNAT and unique-local addresses are refused by
+//!   default, whether given literally (including decimal/hex IPv4 and IPv4-in-IPv6 forms)
+//!   or resolved from a hostname, so a scrape cannot reach cloud metadata or local
+//!   services. `CORTEX_SCOUT_ALLOW_PRIVATE_TARGETS=true` (or the older
+//!   `SHADOWCRAWL_ALLOW_PRIVATE_TARGETS`, or `CORTEX_SCOUT_BLOCK_PRIVATE_NETWORKS=0`)
+//!   lifts this for deployments that scrape an intranet.
+//!
+//! An entry `example.com` matches that host only; `*.example.com` matches example.com and
+//! every subdomain. The policy is read once per process. Fetch and navigation paths call
+//! [`check`] before contacting a URL; crawl frontier expansion and redirect following use
+//! the DNS-free [`allows`] so a denied link is skipped rather than failing the caller.
+//! Native scrapes re-run [`check`] on every redirect hop, and browser renders pause every
+//! document request (redirects, refreshes and script navigations alike) to run it. The
+//! shared `AppState` client follows redirects through [`redirect_policy`], which can only
+//! judge a hop by its URL: a hostname that resolves to a private address gets through
+//! there. URLs taken from fetched pages (assets, sitemaps) are therefore fetched with
+//! [`get_checked`], which follows redirects itself and resolves every hop.
+
+use crate::core::error::ScoutError;
+use std::net::IpAddr;
+use std::sync::OnceLock;
+use tracing::warn;
+use url::{Host, Url};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HostPattern {
+    Exact(String),
+    /// `*.example.com`: the domain itself and any subdomain.
+    Domain(String),
+}
+
+impl HostPattern {
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim().trim_end_matches('.').to_ascii_lowercase();
+        match raw.strip_prefix("*.") {
+            Some(domain) if !domain.is_empty() => Some(Self::Domain(domain.to_string())),
+            Some(_) => None,
+            None if raw.is_empty() => None,
+            None => Some(Self::Exact(raw)),
+        }
+    }
+
+    pub(crate) fn matches(&self, host: &str) -> bool {
+        match self {
+            Self::Exact(exact) => host == exact,
+            Self::Domain(domain) => {
+                host == domain
+                    || host
+                        .strip_suffix(domain.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            }
+        }
+    }
+
+    /// The host (or, for a wildcard, the registered domain) without the `*.` prefix.
+    pub(crate) fn host(&self) -> &str {
+        match self {
+            Self::Exact(host) | Self::Domain(host) => host,
+        }
+    }
+
+    fn as_string(&self) -> String {
+        match self {
+            Self::Exact(exact) => exact.clone(),
+            Self::Domain(domain) => format!("*.{}", domain),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DomainPolicy {
+    allow: Vec<HostPattern>,
+    deny: Vec<HostPattern>,
+    block_private_networks: bool,
+}
+
+impl DomainPolicy {
+    /// Build a policy from comma-separated allow/deny lists.
+    pub fn new(allowlist: &str, denylist: &str, block_private_networks: bool) -> Self {
+        let parse = |list: &str| list.split(',').filter_map(HostPattern::parse).collect();
+        Self {
+            allow: parse(allowlist),
+            deny: parse(denylist),
+            block_private_networks,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::from_vars(|key| std::env::var(key).ok())
+    }
+
+    /// [`Self::from_env`] with variables looked up through `lookup`.
+    fn from_vars(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |key: &str| lookup(key).unwrap_or_default();
+        // The first of the names that is set and not blank.
+        let list = |keys: [&str; 2]| {
+            keys.iter()
+                .map(|key| var(key))
+                .find(|value| !value.trim().is_empty())
+                .unwrap_or_default()
+        };
+        let allow_private = [
+            "CORTEX_SCOUT_ALLOW_PRIVATE_TARGETS",
+            "SHADOWCRAWL_ALLOW_PRIVATE_TARGETS",
+        ]
+        .iter()
+        .any(|key| env_flag(&var(key)) == Some(true))
+            || env_flag(&var("CORTEX_SCOUT_BLOCK_PRIVATE_NETWORKS")) == Some(false);
+        Self::new(
+            &list([
+                "CORTEX_SCOUT_DOMAIN_ALLOWLIST",
+                "SHADOWCRAWL_DOMAIN_ALLOWLIST",
+            ]),
+            &list([
+                "CORTEX_SCOUT_DOMAIN_DENYLIST",
+                "SHADOWCRAWL_DOMAIN_DENYLIST",
+            ]),
+            !allow_private,
+        )
+    }
+
+    /// Whether any rule is configured; an inactive policy allows everything.
+    pub fn is_active(&self) -> bool {
+        !self.allow.is_empty() || !self.deny.is_empty() || self.block_private_networks
+    }
+
+    /// The rule `url` breaks, judged from the URL alone. URLs without a network host
+    /// (`about:blank`, `data:`) are always allowed.
+    pub fn static_violation(&self, url: &str) -> Option<String> {
+        if !self.is_active() {
+            return None;
+        }
+        let parsed = Url::parse(url).ok()?;
+        let host = match parsed.host()? {
+            Host::Domain(domain) => domain.trim_end_matches('.').to_ascii_lowercase(),
+            Host::Ipv4(ip) => ip.to_string(),
+            Host::Ipv6(ip) => ip.to_string(),
+        };
+
+        if let Some(pattern) = self.deny.iter().find(|p| p.matches(&host)) {
+            return Some(format!("denylist:{}", pattern.as_string()));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|p| p.matches(&host)) {
+            return Some("not_allowlisted".to_string());
+        }
+        if self.block_private_networks {
+            let private = match parsed.host()? {
+                Host::Domain(_) => host == "localhost" || host.ends_with(".localhost"),
+                Host::Ipv4(ip) => is_private_ip(IpAddr::V4(ip)),
+                Host::Ipv6(ip) => is_private_ip(IpAddr::V6(ip)),
+            };
+            if private {
+                return Some(format!("private_network:{}", host));
+            }
+        }
+        None
+    }
+
+    /// [`Self::static_violation`], plus the addresses a hostname resolves to when private
+    /// networks are blocked. A name that does not resolve is left for the fetch to fail.
+    pub async fn violation(&self, url: &str) -> Option<String> {
+        if let Some(rule) = self.static_violation(url) {
+            return Some(rule);
+        }
+        if !self.block_private_networks {
+            return None;
+        }
+        let parsed = Url::parse(url).ok()?;
+        let Some(Host::Domain(domain)) = parsed.host() else {
+            return None;
+        };
+        let port = parsed.port_or_known_default().unwrap_or(80);
+        let mut addrs = tokio::net::lookup_host((domain, port)).await.ok()?;
+        let private = addrs.find(|addr| is_private_ip(addr.ip()))?;
+        Some(format!("private_network:{}", private.ip()))
+    }
+}
+
+/// `Some(true)`/`Some(false)` for a recognised boolean setting, `None` when unset or garbled.
+fn env_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                // 0.0.0.0/8 "this network"; Linux routes it to the local host
+                || a == 0
+                // 100.64.0.0/10 shared address space (carrier-grade NAT)
+                || (a == 100 && (64..128).contains(&b))
+        }
+        IpAddr::V6(v6) => {
+            if v6.is_loopback() || v6.is_unspecified() {
+                return true;
+            }
+            // IPv4-mapped (`::ffff:a.b.c.d`) and deprecated IPv4-compatible (`::a.b.c.d`).
+            if let Some(v4) = v6.to_ipv4() {
+                return is_private_ip(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// The process-wide policy, read from the environment on first use.
+pub fn global() -> &'static DomainPolicy {
+    static POLICY: OnceLock<DomainPolicy> = OnceLock::new();
+    POLICY.get_or_init(DomainPolicy::from_env)
+}
+
+/// Refuse `url` with a `blocked` error (reason `domain_policy`) when the global policy
+/// denies it.
+pub async fn check(url: &str) -> Result<(), ScoutError> {
+    match global().violation(url).await {
+        Some(rule) => Err(violation_error(url, &rule)),
+        None => Ok(()),
+    }
+}
+
+/// Whether the global policy allows `url`, without DNS resolution.
+pub fn allows(url: &str) -> bool {
+    global().static_violation(url).is_none()
+}
+
+/// Redirect policy for shared clients: follow up to 10 hops, refusing any hop the global
+/// policy rejects from its URL alone. Resolution is not possible inside the policy, so a
+/// redirect to a public-looking name that resolves privately is followed; fetches of
+/// caller-supplied URLs use [`get_checked`] or the follower in `RustScraper` instead.
+pub fn redirect_policy() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() >= 10 {
+            return attempt.error("too many redirects");
+        }
+        match global().static_violation(attempt.url().as_str()) {
+            Some(rule) => {
+                let error = violation_error(attempt.url().as_str(), &rule);
+                attempt.error(error)
+            }
+            None => attempt.follow(),
+        }
+    })
+}
+
+/// GET `url` with requests built by `request` on a client that does not follow redirects
+/// (such as [`scrape_client`](crate::core::http_client::scrape_client)), following up to 10
+/// redirects by hand and running [`check`] on every hop, the first included.
+pub async fn get_checked(
+    url: &str,
+    request: impl Fn(&str) -> reqwest::RequestBuilder,
+) -> anyhow::Result<reqwest::Response> {
+    follow_checked(global(), url, request).await
+}
+
+async fn follow_checked(
+    policy: &DomainPolicy,
+    url: &str,
+    request: impl Fn(&str) -> reqwest::RequestBuilder,
+) -> anyhow::Result<reqwest::Response> {
+    let mut current = url.to_string();
+    for _ in 0..=10 {
+        if let Some(rule) = policy.violation(&current).await {
+            return Err(violation_error(&current, &rule).into());
+        }
+        let response = request(&current).send().await?;
+        if !response.status().is_redirection() {
+            return Ok(response);
+        }
+        let Some(next) = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|location| Url::parse(&current).ok()?.join(location.trim()).ok())
+        else {
+            return Ok(response);
+        };
+        current = next.to_string();
+    }
+    Err(anyhow::anyhow!("too many redirects from {}", url))
+}
+
+fn violation_error(url: &str, rule: &str) -> ScoutError {
+    warn!("Domain policy refused {} ({})", url, rule);
+    ScoutError::blocked(format!("Domain policy does not allow {} ({})", url, rule)).with_details(
+        serde_json::json!({
+            "reason": "domain_policy",
+            "rule": rule,
+            "url": url,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shadowcrawl_list_names_are_honoured() {
+        let vars = |pairs: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                pairs
+                    .iter()
+                    .find(|(name, _)| *name == key)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let policy =
+            DomainPolicy::from_vars(vars(&[("SHADOWCRAWL_DOMAIN_DENYLIST", "*.evil.example")]));
+        assert_eq!(
+            policy.static_violation("https://cdn.evil.example/x"),
+            Some("denylist:*.evil.example".to_string())
+        );
+        assert_eq!(policy.static_violation("https://docs.rs/serde"), None);
+
+        let policy = DomainPolicy::from_vars(vars(&[("SHADOWCRAWL_DOMAIN_ALLOWLIST", "docs.rs")]));
+        assert_eq!(
+            policy.static_violation("https://example.com/"),
+            Some("not_allowlisted".to_string())
+        );
+
+        // The CORTEX_SCOUT_ name wins when both are set.
+        let policy = DomainPolicy::from_vars(vars(&[
+            ("CORTEX_SCOUT_DOMAIN_DENYLIST", "a.example"),
+            ("SHADOWCRAWL_DOMAIN_DENYLIST", "b.example"),
+        ]));
+        assert_eq!(policy.static_violation("https://b.example/"), None);
+        assert!(policy.static_violation("https://a.example/").is_some());
+    }
+
+    #[test]
+    fn lists_match_exact_hosts_and_wildcard_domains() {
+        let policy = DomainPolicy::new(" docs.rs, *.example.com ", "*.internal.example.com", false);
+        assert_eq!(policy.static_violation("https://docs.rs/serde"), None);
+        assert_eq!(policy.static_violation("https://example.com/"), None);
+        assert_eq!(policy.static_violation("https://a.b.example.com/x"), None);
+        assert_eq!(
+            policy.static_violation("https://wiki.internal.example.com/"),
+            Some("denylist:*.internal.example.com".to_string())
+        );
+        assert_eq!(
+            policy.static_violation("https://www.docs.rs/"),
+            Some("not_allowlisted".to_string())
+        );
+        assert_eq!(
+            policy.static_violation("https://badexample.com/"),
+            Some("not_allowlisted".to_string())
+        );
+        assert_eq!(policy.static_violation("about:blank"), None);
+
+        let open = DomainPolicy::new("", "", false);
+        assert!(!open.is_active());
+        assert_eq!(open.static_violation("http://127.0.0.1/"), None);
+    }
+
+    #[tokio::test]
+    async fn private_networks_are_refused_literally_and_after_resolution() {
+        let policy = DomainPolicy::new("", "", true);
+        for url in [
+            "http://127.0.0.1:8080/admin",
+            "http://10.1.2.3/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://100.64.0.1/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[::ffff:192.168.0.1]/",
+            "http://localhost:3000/",
+        ] {
+            assert!(
+                policy
+                    .static_violation(url)
+                    .is_some_and(|rule| rule.starts_with("private_network:")),
+                "{} should be refused",
+                url
+            );
+        }
+        assert_eq!(policy.static_violation("https://93.184.216.34/"), None);
+        assert!(policy.violation("http://app.localhost/").await.is_some());
+    }
+
+    #[test]
+    fn encoded_and_ipv6_loopback_forms_are_refused() {
+        let policy = DomainPolicy::new("", "", true);
+        for url in [
+            // 127.0.0.1 as one decimal number, hex, octal and shorthand.
+            "http://2130706433/",
+            "http://0x7f000001/",
+            "http://0177.0.0.1/",
+            "http://127.1/",
+            // 169.254.169.254 in decimal.
+            "http://2852039166/latest/meta-data/",
+            "http://0.0.0.0:6379/",
+            "http://[0:0:0:0:0:0:0:1]/",
+            "http://[::]/",
+            "http://[::127.0.0.1]/",
+            "http://[::ffff:7f00:1]/",
+            "http://[fe80::1]/",
+        ] {
+            assert!(
+                policy
+                    .static_violation(url)
+                    .is_some_and(|rule| rule.starts_with("private_network:")),
+                "{} should be refused",
+                url
+            );
+        }
+        assert_eq!(policy.static_violation("http://[2606:4700::1111]/"), None);
+        assert_eq!(policy.static_violation("http://1.1.1.1/"), None);
+    }
+
+    #[tokio::test]
+    async fn shared_client_redirects_into_private_networks_are_refused() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/start", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = sock.read(&mut buf).await;
+            let _ = sock
+                .write_all(b"HTTP/1.1 302 Found\r\nLocation: http://2852039166/latest/meta-data/\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .await;
+        });
+
+        let client = reqwest::Client::builder()
+            .redirect(redirect_policy())
+            .build()
+            .unwrap();
+        let err = client.get(&url).send().await.unwrap_err();
+        assert!(err.is_redirect(), "{err}");
+        let refused = std::error::Error::source(&err)
+            .and_then(|e| e.downcast_ref::<ScoutError>())
+            .expect("domain policy error");
+        assert_eq!(refused.kind, crate::core::error::ErrorKind::Blocked);
+    }
+
+    #[tokio::test]
+    async fn checked_gets_hold_every_redirect_hop_to_the_policy() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/sitemap.xml", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = sock.read(&mut buf).await;
+            let _ = sock
+                .write_all(b"HTTP/1.1 301 Moved Permanently\r\nLocation: http://denied.example/sitemap.xml\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .await;
+        });
+
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap();
+        let policy = DomainPolicy::new("", "denied.example", false);
+        let err = follow_checked(&policy, &url, |hop| client.get(hop))
+            .await
+            .unwrap_err();
+        let refused = err
+            .downcast_ref::<ScoutError>()
+            .expect("domain policy error");
+        assert_eq!(refused.kind, crate::core::error::ErrorKind::Blocked);
+        assert!(refused.message.contains("denied.example"));
+    }
+
+    #[test]
+    fn flags_parse_booleans_only() {
+        assert_eq!(env_flag(" TRUE "), Some(true));
+        assert_eq!(env_flag("0"), Some(false));
+        assert_eq!(env_flag(""), None);
+        assert_eq!(env_flag("maybe"), None);
+    }
+
+    #[test]
+    fn violations_become_structured_blocked_errors() {
+        let err = violation_error("https://denied.example/", "denylist:denied.example");
+        assert_eq!(err.kind, crate::core::error::ErrorKind::Blocked);
+        let details = err.details.unwrap();
+        assert_eq!(details["reason"], "domain_policy");
+        assert_eq!(details["rule"], "denylist:denied.example");
+    }
+}
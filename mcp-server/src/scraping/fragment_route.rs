@@ -0,0 +1,114 @@
+//! Fragment-routed single-page apps: `https://app.example.com/#/docs/getting-started`,
+//! `https://mail.example.com/#!/inbox`.
+//!
+//! Browsers never send the fragment to the server, so a static fetch of such a URL gets
+//! the app shell whatever the route; only a browser renders it. Each route is still a
+//! different document, so cache and dedup keys keep a routing fragment, while an in-page
+//! anchor (`#section-3`) is dropped from them as before.
+
+use url::Url;
+
+/// Whether `fragment` (without the `#`) is a client-side route: a path (`/docs`,
+/// `/search?q=rust`) or a hash-bang (`!/inbox`, `!inbox`). Everything else is an anchor:
+/// element ids, text fragments (`:~:text=`) and query-like state (`page=3`,
+/// `access_token=...&state=...`), none of which names a different document.
+pub fn is_route(fragment: &str) -> bool {
+    fragment.starts_with('/') || fragment.starts_with('!')
+}
+
+/// The routing fragment of `url` (without the `#`), if it has one.
+pub fn route(url: &str) -> Option<&str> {
+    let (_, fragment) = url.split_once('#')?;
+    is_route(fragment).then_some(fragment)
+}
+
+/// Drop the fragment from `url` unless it is a route.
+pub fn strip_anchor(url: &mut Url) {
+    if !url.fragment().is_some_and(is_route) {
+        url.set_fragment(None);
+    }
+}
+
+/// `url` without an anchor fragment; a routing fragment is kept.
+pub fn without_anchor(url: &str) -> &str {
+    match url.split_once('#') {
+        Some((document, fragment)) if !is_route(fragment) => document,
+        _ => url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_are_told_apart_from_anchors() {
+        for url in [
+            "https://app.example.com/#/docs/getting-started",
+            "https://app.example.com/#/",
+            "https://mail.example.com/#!/inbox",
+            "https://mail.example.com/#!inbox",
+            "https://app.example.com/#/search?q=rust&page=2",
+        ] {
+            assert!(route(url).is_some(), "{}", url);
+            assert_eq!(without_anchor(url), url);
+        }
+
+        for (url, document) in [
+            (
+                "https://docs.example.com/guide#section-3",
+                "https://docs.example.com/guide",
+            ),
+            (
+                "https://docs.example.com/guide?v=2#install",
+                "https://docs.example.com/guide?v=2",
+            ),
+            (
+                "https://example.com/article#:~:text=fragment%20routes",
+                "https://example.com/article",
+            ),
+            (
+                "https://example.com/report.pdf#page=3",
+                "https://example.com/report.pdf",
+            ),
+            (
+                "https://example.com/callback#access_token=abc&state=xyz",
+                "https://example.com/callback",
+            ),
+            ("https://example.com/#?tab=reviews", "https://example.com/"),
+            ("https://example.com/page#", "https://example.com/page"),
+        ] {
+            assert_eq!(route(url), None, "{}", url);
+            assert_eq!(without_anchor(url), document, "{}", url);
+        }
+        assert_eq!(
+            without_anchor("https://example.com/plain"),
+            "https://example.com/plain"
+        );
+    }
+
+    #[test]
+    fn strip_anchor_keeps_routes_intact() {
+        let stripped = |raw: &str| {
+            let mut url = Url::parse(raw).unwrap();
+            strip_anchor(&mut url);
+            url.to_string()
+        };
+        assert_eq!(
+            stripped("https://app.example.com/#/search?q=rust%20async"),
+            "https://app.example.com/#/search?q=rust%20async"
+        );
+        assert_eq!(
+            stripped("https://mail.example.com/#!/inbox/42"),
+            "https://mail.example.com/#!/inbox/42"
+        );
+        assert_eq!(
+            stripped("https://docs.example.com/guide#section-3"),
+            "https://docs.example.com/guide"
+        );
+        assert_eq!(
+            stripped("https://example.com/?q=1#page=2&sort=new"),
+            "https://example.com/?q=1"
+        );
+    }
+}
@@ -1,2 +1,15 @@
+pub mod assets;
 pub mod browser_manager;
+pub mod capture;
+pub mod cookie_jar;
+pub mod domain_policy;
+pub mod fragment_route;
+pub mod pagination;
+pub mod provenance;
+pub mod request_auth;
+pub mod request_headers;
+pub mod robots;
 pub mod rust_scraper;
+pub mod screenshots;
+pub mod sections;
+pub mod selectors;
@@ -0,0 +1,354 @@
+//! "Next page" discovery for articles, threads and listings split across numbered pages,
+//! and merging of the pages into one result.
+//!
+//! The next page is taken from, in order: `<link rel="next">` / `<a rel="next">`, a next
+//! control (`a[aria-label="Next"]`, or a `.pagination` / `.pager` link reading "Next" or
+//! an arrow), and a link to the same path whose `page`/`p` query parameter is one more
+//! than the current page's. Sites that only paginate from script (infinite scroll) expose
+//! none of these, so their scrape is left as it is.
+
+use crate::core::error::ScoutError;
+use crate::scraping::fragment_route;
+use crate::types::ScrapeResponse;
+use scraper::{Html, Selector};
+use url::Url;
+
+/// Pages merged (the first included) when the caller enables pagination without a cap.
+pub const DEFAULT_MAX_PAGES: usize = 5;
+/// Upper bound on a caller-supplied cap.
+pub const MAX_PAGES_LIMIT: usize = 20;
+
+/// A caller's `max_pages` for pagination, [`DEFAULT_MAX_PAGES`] when not given.
+pub fn max_pages(requested: Option<u64>) -> Result<usize, ScoutError> {
+    match requested {
+        None => Ok(DEFAULT_MAX_PAGES),
+        Some(n) if (1..=MAX_PAGES_LIMIT as u64).contains(&n) => Ok(n as usize),
+        Some(_) => Err(ScoutError::invalid_input(format!(
+            "max_pages must be between 1 and {} with follow_pagination",
+            MAX_PAGES_LIMIT
+        ))),
+    }
+}
+
+/// Query parameters that number pages one by one.
+const PAGE_PARAMS: &[&str] = &["page", "p", "pg", "paged"];
+
+/// Containers whose links are page controls.
+const PAGER_LINKS: &str =
+    ".pagination a[href], .pager a[href], nav[aria-label*=\"agination\"] a[href], ul.pages a[href]";
+
+/// The page after `page_url`, from the fetched `html`. Only links on the same host count,
+/// and a link back to `page_url` itself is never returned.
+pub fn next_page_url(html: &str, page_url: &str) -> Option<String> {
+    let base = Url::parse(page_url).ok()?;
+    let document = Html::parse_document(html);
+    let resolve = |href: &str| -> Option<Url> {
+        let mut url = base.join(href.trim()).ok()?;
+        fragment_route::strip_anchor(&mut url);
+        let same_page = url.path() == base.path()
+            && url.query() == base.query()
+            && url.fragment() == base.fragment().filter(|f| fragment_route::is_route(f));
+        (matches!(url.scheme(), "http" | "https")
+            && url.host_str() == base.host_str()
+            && !same_page)
+            .then_some(url)
+    };
+    let select = |css: &str| {
+        Selector::parse(css)
+            .map(|selector| document.select(&selector).collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+
+    let rel_next = select("link[rel~=\"next\"][href], a[rel~=\"next\"][href]");
+    let labelled = select("a[aria-label][href]")
+        .into_iter()
+        .filter(|element| is_next_label(element.value().attr("aria-label").unwrap_or("")));
+    let pager = select(PAGER_LINKS)
+        .into_iter()
+        .filter(|element| is_next_label(&element.text().collect::<String>()));
+    if let Some(url) = rel_next
+        .into_iter()
+        .chain(labelled)
+        .chain(pager)
+        .find_map(|element| resolve(element.value().attr("href")?))
+    {
+        return Some(url.to_string());
+    }
+
+    let (param, current) = page_number(&base).unwrap_or(("page", 1));
+    select("a[href]")
+        .into_iter()
+        .filter_map(|element| resolve(element.value().attr("href")?))
+        .find(|url| {
+            url.path() == base.path()
+                && url.query_pairs().any(|(key, value)| {
+                    key == param && value.parse::<u32>().ok() == Some(current + 1)
+                })
+        })
+        .map(|url| url.to_string())
+}
+
+/// The page-number parameter of `url` and its value; a URL without one is page 1.
+fn page_number(url: &Url) -> Option<(&'static str, u32)> {
+    url.query_pairs().find_map(|(key, value)| {
+        let param = PAGE_PARAMS
+            .iter()
+            .find(|param| key.eq_ignore_ascii_case(param))?;
+        Some((*param, value.parse().ok()?))
+    })
+}
+
+/// "Next", "Next page", "Next ›", "›", "»" and the like; not "Next article".
+fn is_next_label(label: &str) -> bool {
+    let label = label.trim();
+    let plain = label
+        .trim_matches(|c: char| c.is_whitespace() || matches!(c, '›' | '»' | '→' | '>'))
+        .to_lowercase();
+    // A bare arrow is the next control; "«" / "‹" (previous) are not arrows here.
+    (plain.is_empty() && !label.is_empty())
+        || matches!(
+            plain.as_str(),
+            "next" | "next page" | "nächste" | "suivant" | "siguiente"
+        )
+}
+
+/// URL a page is remembered under when guarding against loops: no anchor fragment, no
+/// trailing slash.
+pub fn page_key(url: &str) -> String {
+    fragment_route::without_anchor(url)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Append `pages` (pages 2, 3, ... in order) to `first`. Each page's `clean_content` is
+/// preceded by a `--- page N: <url> ---` separator; headings, code blocks (offsets shifted
+/// into the merged text), links and images are concatenated, links and images without
+/// repeats; `word_count` and `reading_time_minutes` are summed. The result keeps the first
+/// page's URL as `canonical_url` and gains a `pagination_merged:<n>` warning; a
+/// `pagination_cap_reached:<n>` warning marks a series cut at `n` pages (`cap_reached`).
+pub fn merge(first: &mut ScrapeResponse, pages: Vec<ScrapeResponse>, cap_reached: bool) {
+    let total = pages.len() + 1;
+    let first_url = first
+        .canonical_url
+        .clone()
+        .or_else(|| first.final_url.clone())
+        .unwrap_or_else(|| first.url.clone());
+    for (index, page) in pages.into_iter().enumerate() {
+        let page_url = page.final_url.as_deref().unwrap_or(&page.url);
+        first
+            .clean_content
+            .push_str(&format!("\n\n--- page {}: {} ---\n\n", index + 2, page_url));
+        let offset = first.clean_content.chars().count();
+        first.clean_content.push_str(&page.clean_content);
+        first.headings.extend(page.headings);
+        first
+            .code_blocks
+            .extend(page.code_blocks.into_iter().map(|mut block| {
+                block.start_char = block.start_char.map(|c| c + offset);
+                block.end_char = block.end_char.map(|c| c + offset);
+                block
+            }));
+        for link in page.links {
+            if !first.links.iter().any(|known| known.url == link.url) {
+                first.links.push(link);
+            }
+        }
+        for image in page.images {
+            if !first.images.iter().any(|known| known.src == image.src) {
+                first.images.push(image);
+            }
+        }
+        first.word_count += page.word_count;
+        first.reading_time_minutes = match (first.reading_time_minutes, page.reading_time_minutes) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+    }
+    if total > 1 {
+        first.canonical_url = Some(first_url);
+        crate::content_quality::push_warning_unique(
+            &mut first.warnings,
+            &format!("pagination_merged:{}", total),
+        );
+    }
+    if cap_reached {
+        crate::content_quality::push_warning_unique(
+            &mut first.warnings,
+            &format!("pagination_cap_reached:{}", total),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Heading, Link};
+
+    const THREAD_PAGE1: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/pagination/thread_page1.html"
+    ));
+    const LISTING_PAGE2: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/pagination/listing_page2.html"
+    ));
+    const INFINITE_SCROLL: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/pagination/infinite_scroll.html"
+    ));
+
+    fn page(url: &str, clean_content: &str, word_count: usize) -> ScrapeResponse {
+        ScrapeResponse {
+            headings: vec![Heading {
+                level: "h2".to_string(),
+                text: format!("Posts on {}", url),
+            }],
+            links: vec![Link {
+                url: "https://forum.example.com/latest".to_string(),
+                text: "Latest".to_string(),
+                nofollow: false,
+                detail: None,
+            }],
+            word_count,
+            reading_time_minutes: Some(1),
+            ..ScrapeResponse::for_test(url, "", clean_content)
+        }
+    }
+
+    #[test]
+    fn rel_next_wins_and_resolves_against_the_page() {
+        let html = THREAD_PAGE1;
+        assert_eq!(
+            next_page_url(
+                html,
+                "https://forum.example.com/t/upgrading-the-cluster/4411"
+            )
+            .as_deref(),
+            Some("https://forum.example.com/t/upgrading-the-cluster/4411?page=2")
+        );
+
+        // Without rel=next, the labelled control inside the pager is used.
+        let html = html.replace("<link rel=\"next\"", "<link rel=\"prefetch\"");
+        let html = html.replace("aria-label=\"Next page\"", "");
+        assert_eq!(
+            next_page_url(
+                &html,
+                "https://forum.example.com/t/upgrading-the-cluster/4411"
+            )
+            .as_deref(),
+            Some("https://forum.example.com/t/upgrading-the-cluster/4411?page=2")
+        );
+    }
+
+    #[test]
+    fn numbered_links_are_followed_by_page_parameter() {
+        let html = LISTING_PAGE2;
+        assert_eq!(
+            next_page_url(
+                html,
+                "https://shop.example.com/lamps/desk?page=2&sort=price"
+            )
+            .as_deref(),
+            Some("https://shop.example.com/lamps/desk?page=3&sort=price")
+        );
+        // On the last page nothing is one more, and page 1 is never "next".
+        assert_eq!(
+            next_page_url(
+                html,
+                "https://shop.example.com/lamps/desk?page=7&sort=price"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn script_only_pagination_finds_nothing() {
+        let html = INFINITE_SCROLL;
+        assert_eq!(
+            next_page_url(html, "https://snapshots.example.com/feed"),
+            None
+        );
+        // A next link pointing at the page itself is no next page.
+        let html = r#"<a rel="next" href="/feed#more">More</a>"#;
+        assert_eq!(
+            next_page_url(html, "https://snapshots.example.com/feed"),
+            None
+        );
+        assert!(is_next_label(" Next › ") && is_next_label("»"));
+        assert!(!is_next_label("Next article") && !is_next_label(""));
+    }
+
+    #[test]
+    fn merged_pages_keep_order_and_the_first_url() {
+        let mut first = page(
+            "https://forum.example.com/t/4411",
+            "First post.\n\n```sh\nkubectl version\n```",
+            20,
+        );
+        first.code_blocks = vec![crate::types::CodeBlock {
+            language: Some("sh".to_string()),
+            code: "kubectl version".to_string(),
+            start_char: Some(13),
+            end_char: Some(40),
+        }];
+        let mut second = page(
+            "https://forum.example.com/t/4411?page=2",
+            "```yaml\nkind: Pod\n```",
+            30,
+        );
+        second.code_blocks = vec![crate::types::CodeBlock {
+            language: Some("yaml".to_string()),
+            code: "kind: Pod".to_string(),
+            start_char: Some(0),
+            end_char: Some(21),
+        }];
+        let third = page("https://forum.example.com/t/4411?page=3", "Last post.", 10);
+
+        merge(&mut first, vec![second, third], true);
+
+        assert_eq!(
+            first.clean_content,
+            "First post.\n\n```sh\nkubectl version\n```\
+             \n\n--- page 2: https://forum.example.com/t/4411?page=2 ---\n\n\
+             ```yaml\nkind: Pod\n```\
+             \n\n--- page 3: https://forum.example.com/t/4411?page=3 ---\n\nLast post."
+        );
+        assert_eq!(first.word_count, 60);
+        assert_eq!(first.reading_time_minutes, Some(3));
+        assert_eq!(first.headings.len(), 3);
+        assert_eq!(first.links.len(), 1);
+        let yaml = &first.code_blocks[1];
+        let start = yaml.start_char.unwrap();
+        let merged: String = first.clean_content.chars().skip(start).take(7).collect();
+        assert_eq!(merged, "```yaml");
+        assert_eq!(
+            first.canonical_url.as_deref(),
+            Some("https://forum.example.com/t/4411")
+        );
+        assert_eq!(
+            first.warnings,
+            ["pagination_merged:3", "pagination_cap_reached:3"]
+        );
+
+        // Nothing found, cap not hit: untouched.
+        let mut lone = page("https://forum.example.com/t/9", "Only post.", 2);
+        merge(&mut lone, Vec::new(), false);
+        assert!(lone.warnings.is_empty() && lone.canonical_url.is_none());
+    }
+
+    #[test]
+    fn page_keys_ignore_fragments_and_trailing_slashes() {
+        assert_eq!(
+            page_key("https://forum.example.com/t/4411/#post-3"),
+            page_key("https://forum.example.com/t/4411")
+        );
+        assert_ne!(
+            page_key("https://forum.example.com/t/4411?page=2"),
+            page_key("https://forum.example.com/t/4411")
+        );
+        assert_ne!(
+            page_key("https://app.example.com/#/threads/4411"),
+            page_key("https://app.example.com/#/threads/4412")
+        );
+    }
+}
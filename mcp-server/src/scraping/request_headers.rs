@@ -0,0 +1,230 @@
+//! Per-request headers and User-Agent for sites that expect specific values (a partner
+//! UA allowlist, an `X-Client-Id` in front of an HTML API).
+//!
+//! Explicit values are applied after the stealth defaults, so they win over the random
+//! User-Agent and stealth headers, on native fetches and browser renders alike.
+//! Hop-by-hop headers are refused. Headers whose name looks secret (authorization,
+//! cookies, tokens) are redacted from `Debug` output and, like `RequestAuth`, not sent
+//! to another origin after a redirect. Cache keys use [`RequestHeaders::fingerprint`].
+
+use anyhow::{anyhow, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Connection-level headers the HTTP client owns; setting them per request is refused.
+const HOP_BY_HOP: &[&str] = &[
+    "host",
+    "content-length",
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "te",
+    "trailer",
+    "upgrade",
+];
+
+/// Name fragments that mark a header value as a secret.
+const SECRET_MARKERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "token",
+    "secret",
+    "password",
+    "api-key",
+    "apikey",
+    "session",
+];
+
+/// Whether values of header `name` must never be logged.
+pub fn is_secret(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+/// Validated custom headers (names lowercased) and User-Agent override.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RequestHeaders {
+    headers: BTreeMap<String, String>,
+    user_agent: Option<String>,
+}
+
+impl std::fmt::Debug for RequestHeaders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let headers: BTreeMap<&str, &str> = self
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let shown = if is_secret(name) { "<redacted>" } else { value };
+                (name.as_str(), shown)
+            })
+            .collect();
+        f.debug_struct("RequestHeaders")
+            .field("headers", &headers)
+            .field("user_agent", &self.user_agent)
+            .finish()
+    }
+}
+
+impl RequestHeaders {
+    /// Validate `headers` and `user_agent`; `None` when neither sets anything. A
+    /// `User-Agent` entry in `headers` counts as `user_agent` unless that is given too.
+    pub fn new(
+        headers: impl IntoIterator<Item = (String, String)>,
+        user_agent: Option<String>,
+    ) -> Result<Option<Self>> {
+        let mut user_agent = user_agent
+            .map(|ua| ua.trim().to_string())
+            .filter(|ua| !ua.is_empty());
+        let mut checked = BTreeMap::new();
+        for (name, value) in headers {
+            let name = name.trim().to_ascii_lowercase();
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| anyhow!("'{}' is not a valid header name", name))?;
+            if HOP_BY_HOP.contains(&name.as_str()) {
+                return Err(anyhow!("header '{}' is managed by the HTTP client", name));
+            }
+            HeaderValue::from_str(value.trim())
+                .map_err(|_| anyhow!("header '{}' has characters not allowed in a value", name))?;
+            if name == "user-agent" {
+                user_agent.get_or_insert_with(|| value.trim().to_string());
+                continue;
+            }
+            checked.insert(name, value.trim().to_string());
+        }
+        if let Some(ua) = &user_agent {
+            HeaderValue::from_str(ua)
+                .map_err(|_| anyhow!("user_agent has characters not allowed in a header"))?;
+        }
+        if checked.is_empty() && user_agent.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            headers: checked,
+            user_agent,
+        }))
+    }
+
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
+    /// Headers as name/value pairs, secrets included.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    /// The headers for a request; secret ones only when `same_origin`, and marked sensitive.
+    pub fn header_map(&self, same_origin: bool) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in self.iter() {
+            let secret = is_secret(name);
+            if secret && !same_origin {
+                continue;
+            }
+            let (Ok(name), Ok(mut value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) else {
+                continue;
+            };
+            value.set_sensitive(secret);
+            map.insert(name, value);
+        }
+        map
+    }
+
+    /// Short SHA-256 digest of the headers and User-Agent, safe for cache keys and logs.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        for (name, value) in self.iter() {
+            hasher.update(name.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(value.as_bytes());
+            hasher.update(b"\0");
+        }
+        if let Some(ua) = &self.user_agent {
+            hasher.update(b"ua\0");
+            hasher.update(ua.as_bytes());
+        }
+        hex::encode(&hasher.finalize()[..8])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn validates_names_values_and_hop_by_hop_headers() {
+        let headers = RequestHeaders::new(
+            pairs(&[("X-Client-Id", "abc"), ("User-Agent", "PartnerBot/2.0")]),
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(headers.user_agent(), Some("PartnerBot/2.0"));
+        assert_eq!(
+            headers.iter().collect::<Vec<_>>(),
+            vec![("x-client-id", "abc")]
+        );
+
+        let explicit = RequestHeaders::new(
+            pairs(&[("User-Agent", "ignored")]),
+            Some("Chosen/1.0".to_string()),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(explicit.user_agent(), Some("Chosen/1.0"));
+
+        assert!(RequestHeaders::new(Vec::new(), Some(" ".to_string()))
+            .unwrap()
+            .is_none());
+        for rejected in [
+            ("Host", "evil.test"),
+            ("Content-Length", "0"),
+            ("Connection", "close"),
+        ] {
+            assert!(RequestHeaders::new(pairs(&[rejected]), None).is_err());
+        }
+        assert!(RequestHeaders::new(pairs(&[("bad name", "x")]), None).is_err());
+        assert!(RequestHeaders::new(pairs(&[("X-Evil", "a\r\nHost: b")]), None).is_err());
+    }
+
+    #[test]
+    fn secrets_are_redacted_and_kept_on_origin() {
+        let headers = RequestHeaders::new(
+            pairs(&[
+                ("X-Api-Token", "s3cret"),
+                ("Cookie", "sid=1"),
+                ("X-Client-Id", "abc"),
+            ]),
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        let debug = format!("{:?}", headers);
+        assert!(debug.contains("abc") && !debug.contains("s3cret") && !debug.contains("sid=1"));
+
+        let same_origin = headers.header_map(true);
+        assert_eq!(same_origin.len(), 3);
+        assert!(same_origin["x-api-token"].is_sensitive());
+        let cross_origin = headers.header_map(false);
+        assert_eq!(cross_origin.len(), 1);
+        assert_eq!(cross_origin["x-client-id"], "abc");
+
+        let other = RequestHeaders::new(pairs(&[("X-Client-Id", "xyz")]), None)
+            .unwrap()
+            .unwrap();
+        assert_ne!(headers.fingerprint(), other.fingerprint());
+        assert!(!headers.fingerprint().contains("s3cret"));
+    }
+}
@@ -0,0 +1,351 @@
+//! robots.txt support for compliance-sensitive deployments.
+//!
+//! `RobotsPolicy` fetches `/robots.txt` once per origin and keeps the parsed rules in a
+//! TTL cache (shared through `AppState`), so concurrent scrapes of the same host coalesce
+//! onto a single fetch. Matching follows RFC 9309:
+//! - the most specific (longest) matching rule wins, `Allow` wins ties;
+//! - `*` matches any run of characters and a trailing `$` anchors the end of the path;
+//! - a 4xx robots.txt means "no restrictions", while 5xx / network failures mean
+//!   "disallow everything" (failures are not cached, so the next scrape retries).
+//!
+//! `Crawl-delay` is parsed and reported but not enforced.
+
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+/// Product token used to select a robots.txt group before falling back to `*`.
+pub const ROBOTS_USER_AGENT: &str = "cortex-scout";
+
+/// RFC 9309 asks crawlers to parse at least 500 KiB; anything past that is ignored.
+const MAX_ROBOTS_BYTES: usize = 512 * 1024;
+const ROBOTS_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum RobotsError {
+    #[error("robots.txt disallows fetching {url}")]
+    Disallowed {
+        url: String,
+        crawl_delay: Option<f64>,
+    },
+
+    #[error("robots.txt for {origin} is unavailable ({reason}); treating host as disallowed")]
+    Unavailable { origin: String, reason: String },
+}
+
+impl RobotsError {
+    /// Stable machine-readable code for tool responses and warnings.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RobotsError::Disallowed { .. } => "robots_disallowed",
+            RobotsError::Unavailable { .. } => "robots_unavailable",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RobotsRule {
+    allow: bool,
+    pattern: String,
+}
+
+#[derive(Debug, Clone, Default)]
+struct RobotsGroup {
+    agents: Vec<String>,
+    rules: Vec<RobotsRule>,
+    crawl_delay: Option<f64>,
+}
+
+/// Parsed robots.txt for a single origin.
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    groups: Vec<RobotsGroup>,
+}
+
+impl RobotsRules {
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(body: &str) -> Self {
+        let mut groups: Vec<RobotsGroup> = Vec::new();
+        let mut current: Option<RobotsGroup> = None;
+        // A user-agent line after any rule starts a new group; consecutive
+        // user-agent lines share the group that follows them.
+        let mut in_rules = false;
+
+        for raw_line in body.lines() {
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules || current.is_none() {
+                        if let Some(group) = current.take() {
+                            groups.push(group);
+                        }
+                        current = Some(RobotsGroup::default());
+                        in_rules = false;
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    if value.is_empty() {
+                        continue;
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.rules.push(RobotsRule {
+                            allow: key == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    in_rules = true;
+                    if let Some(group) = current.as_mut() {
+                        group.crawl_delay = value.parse::<f64>().ok().filter(|d| *d >= 0.0);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(group) = current.take() {
+            groups.push(group);
+        }
+
+        Self { groups }
+    }
+
+    fn matching_groups(&self, user_agent: &str) -> Vec<&RobotsGroup> {
+        let ua = user_agent.to_ascii_lowercase();
+        let specific: Vec<&RobotsGroup> = self
+            .groups
+            .iter()
+            .filter(|g| {
+                g.agents
+                    .iter()
+                    .any(|a| a != "*" && ua.starts_with(a.as_str()))
+            })
+            .collect();
+        if !specific.is_empty() {
+            return specific;
+        }
+        self.groups
+            .iter()
+            .filter(|g| g.agents.iter().any(|a| a == "*"))
+            .collect()
+    }
+
+    /// Whether `path` (path plus optional `?query`) may be fetched by `user_agent`.
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+
+        let mut best: Option<(usize, bool)> = None;
+        for group in self.matching_groups(user_agent) {
+            for rule in &group.rules {
+                if !pattern_matches(&rule.pattern, path) {
+                    continue;
+                }
+                let len = rule.pattern.len();
+                best = match best {
+                    Some((best_len, best_allow))
+                        if best_len > len || (best_len == len && best_allow) =>
+                    {
+                        Some((best_len, best_allow))
+                    }
+                    _ => Some((len, rule.allow)),
+                };
+            }
+        }
+        best.map(|(_, allow)| allow).unwrap_or(true)
+    }
+
+    pub fn crawl_delay(&self, user_agent: &str) -> Option<f64> {
+        self.matching_groups(user_agent)
+            .iter()
+            .filter_map(|g| g.crawl_delay)
+            .reduce(f64::max)
+    }
+}
+
+/// Match a robots.txt path pattern (`*` wildcard, optional trailing `$`).
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+
+    let tail: Vec<&str> = parts.collect();
+    if tail.is_empty() {
+        return !anchored || rest.is_empty();
+    }
+    for (i, part) in tail.iter().enumerate() {
+        let last = i == tail.len() - 1;
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Per-origin robots.txt cache shared by all scrapes.
+pub struct RobotsPolicy {
+    cache: moka::future::Cache<String, Arc<RobotsRules>>,
+}
+
+impl RobotsPolicy {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            cache: moka::future::Cache::builder()
+                .max_capacity(5_000)
+                .time_to_live(ttl)
+                .build(),
+        }
+    }
+
+    /// Check `url` against its origin's robots.txt.
+    ///
+    /// Returns the applicable `Crawl-delay` (seconds) when the fetch is allowed, or a
+    /// `RobotsError` when it must not be made. Non-HTTP(S) or unparsable URLs are passed
+    /// through so the caller's own validation can report them.
+    pub async fn check(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+    ) -> Result<Option<f64>, RobotsError> {
+        let Ok(parsed) = url::Url::parse(url) else {
+            return Ok(None);
+        };
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Ok(None);
+        }
+        let origin = parsed.origin().ascii_serialization();
+
+        let rules = self
+            .cache
+            .try_get_with(origin.clone(), fetch_robots(client, &origin))
+            .await
+            .map_err(|e| RobotsError::Unavailable {
+                origin: origin.clone(),
+                reason: e.to_string(),
+            })?;
+
+        let mut path = parsed.path().to_string();
+        if let Some(query) = parsed.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+
+        let crawl_delay = rules.crawl_delay(ROBOTS_USER_AGENT);
+        if rules.is_allowed(ROBOTS_USER_AGENT, &path) {
+            Ok(crawl_delay)
+        } else {
+            Err(RobotsError::Disallowed {
+                url: url.to_string(),
+                crawl_delay,
+            })
+        }
+    }
+}
+
+async fn fetch_robots(client: &reqwest::Client, origin: &str) -> anyhow::Result<Arc<RobotsRules>> {
+    let robots_url = format!("{}/robots.txt", origin);
+    debug!("fetching {}", robots_url);
+
+    let resp = client
+        .get(&robots_url)
+        .timeout(ROBOTS_FETCH_TIMEOUT)
+        .send()
+        .await?;
+    let status = resp.status();
+
+    if status.is_client_error() {
+        return Ok(Arc::new(RobotsRules::allow_all()));
+    }
+    if !status.is_success() {
+        warn!("robots.txt fetch for {} returned HTTP {}", origin, status);
+        anyhow::bail!("http_{}", status.as_u16());
+    }
+
+    let bytes = resp.bytes().await?;
+    let body = String::from_utf8_lossy(&bytes[..bytes.len().min(MAX_ROBOTS_BYTES)]);
+    Ok(Arc::new(RobotsRules::parse(&body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+# comment line
+User-agent: *
+Disallow: /private/
+Allow: /private/public-page
+Disallow: /*.pdf$
+Crawl-delay: 5
+
+User-agent: cortex-scout
+User-agent: other-bot
+Disallow: /no-scout
+";
+
+    #[test]
+    fn falls_back_to_star_group() {
+        let rules = RobotsRules::parse(SAMPLE);
+        assert!(rules.is_allowed("some-bot", "/"));
+        assert!(!rules.is_allowed("some-bot", "/private/secret"));
+        assert_eq!(rules.crawl_delay("some-bot"), Some(5.0));
+    }
+
+    #[test]
+    fn specific_group_replaces_star_group() {
+        let rules = RobotsRules::parse(SAMPLE);
+        assert!(!rules.is_allowed(ROBOTS_USER_AGENT, "/no-scout/page"));
+        assert!(rules.is_allowed(ROBOTS_USER_AGENT, "/private/secret"));
+        assert_eq!(rules.crawl_delay(ROBOTS_USER_AGENT), None);
+    }
+
+    #[test]
+    fn longest_match_wins_and_allow_breaks_ties() {
+        let rules = RobotsRules::parse(SAMPLE);
+        assert!(rules.is_allowed("x", "/private/public-page"));
+
+        let tie = RobotsRules::parse("User-agent: *\nDisallow: /page\nAllow: /page\n");
+        assert!(tie.is_allowed("x", "/page"));
+    }
+
+    #[test]
+    fn wildcards_and_end_anchor() {
+        let rules = RobotsRules::parse(SAMPLE);
+        assert!(!rules.is_allowed("x", "/docs/file.pdf"));
+        assert!(rules.is_allowed("x", "/docs/file.pdf?download=1"));
+        assert!(pattern_matches("/a/*/c", "/a/b/c/d"));
+        assert!(!pattern_matches("/a/*/c$", "/a/b/c/d"));
+    }
+
+    #[test]
+    fn empty_or_missing_rules_allow_everything() {
+        assert!(RobotsRules::parse("").is_allowed("x", "/anything"));
+        assert!(RobotsRules::parse("User-agent: *\nDisallow:\n").is_allowed("x", "/anything"));
+        assert!(RobotsRules::allow_all().is_allowed(ROBOTS_USER_AGENT, "/"));
+    }
+}
@@ -1,6 +1,8 @@
-use super::RustScraper;
+use super::cdp_guard::{check_landing, InjectedHeaders, NavigationGuard};
+use super::{consent, framework_state, reading_time_minutes, word_equivalents, RustScraper};
 use crate::scraping::browser_manager;
-use crate::types::ScrapeResponse;
+use crate::scraping::capture::CaptureRequest;
+use crate::types::{ScrapeMethod, ScrapeResponse};
 use anyhow::{anyhow, Result};
 use chrono::Utc;
 use futures::StreamExt;
@@ -16,8 +18,45 @@ impl RustScraper {
         url: &str,
         proxy_url: Option<String>,
     ) -> Result<(String, u16)> {
+        let started = std::time::Instant::now();
+        let outcome = self.fetch_via_cdp_once(url, proxy_url).await;
+        if let Some(metrics) = &self.metrics {
+            metrics.record_browser_fetch(outcome.is_ok(), started.elapsed());
+        }
+        outcome
+    }
+
+    async fn fetch_via_cdp_once(
+        &self,
+        url: &str,
+        proxy_url: Option<String>,
+    ) -> Result<(String, u16)> {
+        crate::scraping::domain_policy::check(url).await?;
         crate::host_guard::wait_for_url_host(url).await;
 
+        // A shared browser cannot switch proxies, so proxied fetches launch their own and
+        // only take a slot in the pool.
+        match (&self.browser_pool, proxy_url) {
+            (Some(pool), None) => {
+                let page = pool.acquire().await?;
+                let outcome = self.render_page(&page, url).await;
+                page.release().await;
+                outcome.map(|content| (content, 200))
+            }
+            (Some(pool), proxy_url) => {
+                let _lease = pool.lease().await?;
+                self.fetch_via_dedicated_browser(url, proxy_url).await
+            }
+            (None, proxy_url) => self.fetch_via_dedicated_browser(url, proxy_url).await,
+        }
+    }
+
+    /// Launch a browser for this fetch alone and close it afterwards.
+    async fn fetch_via_dedicated_browser(
+        &self,
+        url: &str,
+        proxy_url: Option<String>,
+    ) -> Result<(String, u16)> {
         let exe = browser_manager::find_chrome_executable().ok_or_else(|| {
             anyhow!("No browser found for CDP stealth mode. Install Brave, Chrome, or Chromium.")
         })?;
@@ -41,11 +80,47 @@ impl RustScraper {
             }
         });
 
-        let page = browser
-            .new_page("about:blank")
-            .await
-            .map_err(|e| anyhow!("Failed to create page: {}", e))?;
+        let outcome = match browser.new_page("about:blank").await {
+            Ok(page) => {
+                let outcome = self.render_page(&page, url).await;
+                drop(page);
+                outcome
+            }
+            Err(e) => Err(anyhow!("Failed to create page: {}", e)),
+        };
+        browser_manager::shutdown_browser_session(&mut browser, handle, data_dir, "fetch_via_cdp")
+            .await;
+        outcome.map(|content| (content, 200))
+    }
+
+    /// Stealth-inject, navigate and settle `page`, then return its HTML unless it is
+    /// still a challenge or block page. Every document request on the way, redirect hops
+    /// included, is held to the domain policy.
+    async fn render_page(&self, page: &chromiumoxide::Page, url: &str) -> Result<String> {
+        let mut headers = InjectedHeaders::new(url);
+        if let Some(auth) = self.auth_header.as_ref().and_then(|v| v.to_str().ok()) {
+            headers.scoped("Authorization", auth);
+        }
+        // Like native fetches: secret-looking custom headers stay on the target's origin.
+        for (name, value) in self.custom_headers.iter().flat_map(|custom| custom.iter()) {
+            if crate::scraping::request_headers::is_secret(name) {
+                headers.scoped(name, value);
+            } else {
+                headers.shared(name, value);
+            }
+        }
+        let guard = NavigationGuard::install(page, headers).await?;
+        let outcome = self.render_guarded(page, url, &guard).await;
+        guard.remove().await;
+        outcome
+    }
 
+    async fn render_guarded(
+        &self,
+        page: &chromiumoxide::Page,
+        url: &str,
+        guard: &NavigationGuard,
+    ) -> Result<String> {
         debug!("💉 Injecting Universal Stealth Engine (site-agnostic)");
         let stealth_script = self.get_universal_stealth_script();
         page.execute(
@@ -58,12 +133,25 @@ impl RustScraper {
 
         // Auto-inject any stored session cookies before navigation so the
         // initial HTTP request is sent with a valid auth token.
-        let had_session = crate::features::session_store::auto_inject(&page, url).await;
+        let had_session = crate::features::session_store::auto_inject(page, url).await;
 
-        info!("🌐 Navigating to: {}", url);
-        page.goto(url)
+        if let Some(user_agent) = self.custom_headers.as_ref().and_then(|c| c.user_agent()) {
+            page.execute(
+                chromiumoxide::cdp::browser_protocol::network::SetUserAgentOverrideParams::new(
+                    user_agent,
+                ),
+            )
             .await
-            .map_err(|e| anyhow!("Failed to navigate: {}", e))?;
+            .map_err(|e| anyhow!("Failed to override User-Agent: {}", e))?;
+        }
+
+        info!("🌐 Navigating to: {}", url);
+        if let Err(e) = page.goto(url).await {
+            if let Some(refused) = guard.refused() {
+                return Err(refused.into());
+            }
+            return Err(anyhow!("Failed to navigate: {}", e));
+        }
 
         // Post-session stealth micro-interactions: short jitter + two simulated
         // cursor positions.  Only performed when cookies were actually injected
@@ -151,7 +239,7 @@ impl RustScraper {
             }
         }
 
-        self.simulate_mouse_movement(&page).await?;
+        self.simulate_mouse_movement(page).await?;
 
         let final_wait = {
             let mut rng = rand::rng();
@@ -162,10 +250,26 @@ impl RustScraper {
 
         // Smart dynamic hydration: wait for network to settle, then auto-scroll
         // to trigger lazy-loaded content before capturing HTML.
-        browser_manager::wait_until_stable(&page, 1500, 8000)
+        browser_manager::wait_until_stable(page, 1500, 8000)
             .await
             .ok();
-        browser_manager::auto_scroll(&page).await.ok();
+        browser_manager::auto_scroll(page).await.ok();
+
+        // 🧭 Fragment route (`#/docs`, `#!/inbox`): the app draws it client-side once the
+        // shell has loaded, so wait for the rendered text to stop changing.
+        if crate::scraping::fragment_route::route(url).is_some() {
+            match browser_manager::wait_for_text_settle(page).await {
+                Ok(settle) if settle.settled => info!(
+                    "🧭 Fragment route rendered after ~{}ms ({} chars)",
+                    settle.elapsed_ms, settle.len
+                ),
+                Ok(settle) => warn!(
+                    "🧭 Fragment route still changing after ~{}ms ({} chars)",
+                    settle.elapsed_ms, settle.len
+                ),
+                Err(e) => debug!("Fragment route settle skipped: {}", e),
+            }
+        }
 
         // 🗨️ GitHub threaded content (Discussions / Issues) — React renders comments
         // lazily; the 1.5 s network-idle window above often captures an empty shell.
@@ -173,10 +277,10 @@ impl RustScraper {
         if url.contains("github.com") && (url.contains("/discussions/") || url.contains("/issues/"))
         {
             info!("🗨️ GitHub threaded page detected — extended comment hydration");
-            browser_manager::wait_until_stable(&page, 2500, 12_000)
+            browser_manager::wait_until_stable(page, 2500, 12_000)
                 .await
                 .ok();
-            wait_for_discussion_comments(&page).await.ok();
+            wait_for_discussion_comments(page).await.ok();
         }
 
         // 🧬 Visual Noise Filter (NeuroSiphon DNA)
@@ -190,18 +294,36 @@ impl RustScraper {
             }
         }
 
+        // Taken after settling and noise filtering so it shows what extraction will see.
+        if self.screenshot {
+            self.capture_screenshot(page).await;
+        }
+
+        // A refused hop leaves Chrome on its error page; a script redirect that slipped
+        // past the guard still lands somewhere the policy can judge.
+        if let Some(refused) = guard.refused() {
+            return Err(refused.into());
+        }
+        check_landing(page).await?;
+
         let content = page
             .content()
             .await
             .map_err(|e| anyhow!("Failed to get page content: {}", e))?;
 
+        let max_bytes = crate::core::config::scrape_max_response_bytes();
+        if content.len() > max_bytes {
+            return Err(super::response_too_large(
+                content.len(),
+                max_bytes,
+                "SCRAPE_MAX_RESPONSE_BYTES",
+            ));
+        }
+
         if self.detect_challenge(&content) {
             warn!("❌ CDP fetch hit challenge iframe/content signature");
             crate::host_guard::note_url_host_blocked(url, "challenge_detected").await;
 
-            drop(page);
-            browser_manager::shutdown_browser_session(&mut browser, handle, data_dir, "fetch_via_cdp").await;
-
             return Err(anyhow!("CDP bypass failed: Challenge detected"));
         }
 
@@ -209,18 +331,38 @@ impl RustScraper {
             warn!("❌ CDP fetch still blocked: {}", block_reason);
             crate::host_guard::note_url_host_blocked(url, block_reason).await;
 
-            drop(page);
-            browser_manager::shutdown_browser_session(&mut browser, handle, data_dir, "fetch_via_cdp").await;
-
             return Err(anyhow!("CDP bypass failed: {}", block_reason));
         }
 
         info!("✅ CDP fetch successful ({} chars)", content.len());
 
-        drop(page);
-        browser_manager::shutdown_browser_session(&mut browser, handle, data_dir, "fetch_via_cdp").await;
+        Ok(content)
+    }
 
-        Ok((content, 200))
+    /// Save a PNG of `page` for [`Self::process_html`] to attach. Failures are logged and
+    /// leave the result without a screenshot rather than failing the fetch.
+    async fn capture_screenshot(&self, page: &chromiumoxide::Page) {
+        use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
+        use chromiumoxide::page::ScreenshotParams;
+
+        let params = ScreenshotParams::builder()
+            .format(CaptureScreenshotFormat::Png)
+            .full_page(self.screenshot_full_page)
+            .build();
+        let saved = match page.screenshot(params).await {
+            Ok(png) => crate::scraping::screenshots::save(&png, self.screenshot_full_page).await,
+            Err(e) => Err(anyhow!("capture failed: {}", e)),
+        };
+        match saved {
+            Ok(shot) => {
+                debug!(
+                    "📸 Saved screenshot ({} bytes) to {}",
+                    shot.bytes, shot.path
+                );
+                *self.captured_screenshot.lock().unwrap() = Some(shot);
+            }
+            Err(e) => warn!("Screenshot failed: {}", e),
+        }
     }
 
     async fn simulate_mouse_movement(&self, page: &chromiumoxide::Page) -> Result<()> {
@@ -259,18 +401,32 @@ impl RustScraper {
 
     /// Process raw HTML into ScrapeResponse (for CDP-fetched content)
     pub async fn process_html(&self, html: &str, url: &str) -> Result<ScrapeResponse> {
+        let mut result = self.cdp_html_response(html, url)?;
+        result.screenshot = self.captured_screenshot.lock().unwrap().take();
+        self.attach_feed_items(&mut result).await;
+        if self.captures.is_some() {
+            let request = CaptureRequest::new(url, ScrapeMethod::Cdp, result.status_code);
+            self.capture(request, html, &mut result).await;
+        }
+        Ok(result)
+    }
+
+    fn cdp_html_response(&self, html: &str, url: &str) -> Result<ScrapeResponse> {
         let parsed_url = Url::parse(url).map_err(|e| anyhow!("Invalid URL '{}': {}", url, e))?;
         let document = Html::parse_document(html);
 
-        let title = self.extract_title(&document);
-        let meta_description = self.extract_meta_description(&document);
+        let (title, title_source) = self.extract_title(&document, &parsed_url);
+        let (meta_description, meta_description_source) = self.extract_meta_description(&document);
         let meta_keywords = self.extract_meta_keywords(&document);
         let language = self.detect_language(&document, html);
         let canonical_url = self.extract_canonical(&document, &parsed_url);
+        let alternate_languages =
+            self.extract_alternate_languages(&document, &parsed_url, canonical_url.as_deref());
         let site_name = self.extract_site_name(&document);
         let (og_title, og_description, og_image) = self.extract_open_graph(&document, &parsed_url);
         let author = self.extract_author(&document);
         let published_at = self.extract_published_time(&document);
+        let feed_urls = self.extract_feed_links(&document, &parsed_url);
 
         let code_blocks = {
             // 🧬 Rule B: infer language from URL extension for raw source files
@@ -282,13 +438,24 @@ impl RustScraper {
         // 🔒 HTML-level Auth-Wall check — fast DOM selector scan on raw HTML.
         let auth_wall_html_reason = self.detect_auth_wall_html(html, url);
 
+        // The main entity of a framework payload (a Next.js product, an Apollo-cached
+        // article) beats both the whole-state flattening and JSON-LD below.
+        let framework_payloads = framework_state::unwrap(&document);
+        let framework_entity = if self.extract_app_state {
+            None
+        } else {
+            framework_state::main_entity_markdown(&framework_payloads)
+        };
+
         // JSON-LD can be the cleanest source on modern sites; prefer it when present.
         let json_ld_content = self.extract_json_ld(&document);
+        let (structured_data, structured_data_truncated) = self.extract_structured_data(&document);
 
         // ── 🧬 SPA fast-path (before JSON-LD): prefer embedded state blobs when present.
         // 🧬 Rule C: only commit to the SPA JSON when it yields readable content (≥ 100 words)
         // or `extract_app_state` is explicitly set on the scraper instance.
-        let spa_state_content = if crate::core::config::neurosiphon_enabled()
+        let spa_state_content = if framework_entity.is_none()
+            && crate::core::config::neurosiphon_enabled()
             && crate::scraping::rust_scraper::clean::looks_like_spa(html)
         {
             self.extract_spa_json_state(html)
@@ -297,13 +464,16 @@ impl RustScraper {
             None
         };
 
-        let (mut clean_content, noise_reduction_ratio) =
-            if let Some(spa_content) = spa_state_content.as_ref() {
-                (self.normalize_markdown_fragments(spa_content), 0.0)
+        let (mut clean_content, noise_reduction_ratio, consent_nodes_removed) =
+            if let Some(entity) = framework_entity.as_ref() {
+                (entity.clone(), 0.0, 0)
+            } else if let Some(spa_content) = spa_state_content.as_ref() {
+                (self.normalize_markdown_fragments(spa_content), 0.0, 0)
             } else if let Some(json_content) = json_ld_content.as_ref() {
                 (
                     self.normalize_markdown_fragments(&html2md::parse_html(json_content)),
                     0.0,
+                    0,
                 )
             } else {
                 self.extract_clean_content_with_metrics(html, &parsed_url)
@@ -325,12 +495,17 @@ impl RustScraper {
         let images = if spa_forced { vec![] } else { images };
         let headings = if spa_forced { vec![] } else { headings };
 
-        let mut embedded_data_sources = self.collect_embedded_data_sources(&document);
-        let mut embedded_state_json = embedded_data_sources
+        let raw_data_sources = self.collect_embedded_data_sources(&document);
+        let mut embedded_state_json = raw_data_sources
             .iter()
             .max_by_key(|s| s.content.len())
             .map(|s| s.content.clone())
             .or_else(|| self.extract_embedded_state_json(&document));
+        let mut embedded_data_sources: Vec<_> = framework_payloads
+            .iter()
+            .map(framework_state::FrameworkPayload::to_source)
+            .chain(raw_data_sources)
+            .collect();
 
         let mut warnings = Vec::new();
         const MAX_STATE_JSON_CHARS: usize = 200_000;
@@ -346,17 +521,25 @@ impl RustScraper {
                 warnings.push("embedded_state_json_truncated".to_string());
             }
         }
+        if structured_data_truncated {
+            warnings.push("structured_data_truncated".to_string());
+        }
+        if consent_nodes_removed > consent::CONSENT_REMOVAL_WARN_THRESHOLD {
+            warnings.push("consent_markup_stripped".to_string());
+        }
 
         let hydration_status = crate::types::HydrationStatus {
             json_found: !embedded_data_sources.is_empty() || embedded_state_json.is_some(),
             settle_time_ms: None,
             noise_reduction_ratio,
+            consent_nodes_removed,
         };
 
         // 🔒 Auth-Wall Guard Dog (merged): HTML-level + clean-text detection.
         // Content-length gate: pages with >100 words that have a login modal are not truly
         // blocked. Downgrade to advisory warning rather than hard-blocking content.
-        let preliminary_word_count = self.count_words(&clean_content);
+        let preliminary_word_count =
+            word_equivalents(self.count_words_in(&clean_content, &language), &language);
         let detected_auth_reason =
             auth_wall_html_reason.or_else(|| self.detect_auth_wall(&clean_content, url));
         let auth_wall_reason = if preliminary_word_count > 100 {
@@ -371,11 +554,15 @@ impl RustScraper {
         }
 
         clean_content = self.append_image_context_markdown(clean_content, &images, &title);
-        let word_count = self.count_words(&clean_content);
-        let reading_time_minutes = Some(((word_count as f64 / 200.0).ceil() as u32).max(1));
-
-        let extraction_score =
-            self.calculate_extraction_score(word_count, &published_at, &code_blocks, &headings);
+        let word_count = self.count_words_in(&clean_content, &language);
+        let reading_time_minutes = Some(reading_time_minutes(word_count, &language));
+
+        let extraction_score = self.calculate_extraction_score(
+            word_equivalents(word_count, &language),
+            &published_at,
+            &code_blocks,
+            &headings,
+        );
 
         let domain = parsed_url.host_str().map(|h| h.to_string());
         let (auth_risk_score_val, detection_factors) =
@@ -385,10 +572,14 @@ impl RustScraper {
         } else {
             None
         };
-        Ok(ScrapeResponse {
+        let mut page = ScrapeResponse {
             url: url.to_string(),
             title,
-            content: html.to_string(),
+            content: if self.raw_html {
+                html.to_string()
+            } else {
+                String::new()
+            },
             clean_content,
             embedded_state_json,
             embedded_data_sources,
@@ -422,8 +613,32 @@ impl RustScraper {
             auth_risk_score,
             detection_factors,
             final_url: None,
+            redirect_chain: Vec::new(),
             metrics: None,
-        })
+            content_format: None,
+            page_count: None,
+            feed_urls,
+            feed_items: Vec::new(),
+            fetch_attempts: None,
+            block_reason: None,
+            asset_manifest: None,
+            charset: None,
+            etag: None,
+            last_modified: None,
+            revalidated: false,
+            structured_data,
+            screenshot: None,
+            robots_directives: self.extract_robots_directives(&document),
+            selector_results: None,
+            alternate_languages,
+            provenance: Vec::new(),
+            sections: Vec::new(),
+            title_source,
+            meta_description_source,
+            quality: None,
+        };
+        page.quality = Some(self.quality_signals(&page, html.len()));
+        Ok(page)
     }
 
     // ─────────────────────────────────────────────────────────────────────────
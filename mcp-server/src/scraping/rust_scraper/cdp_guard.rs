@@ -0,0 +1,228 @@
+//! Domain policy for browser navigations.
+//!
+//! Chrome follows 3xx answers, meta refreshes and script redirects on its own, so checking
+//! the first URL is not enough. [`NavigationGuard`] pauses every document request (the
+//! page and its frames, each redirect hop included) through the CDP `Fetch` domain and
+//! fails the ones [`domain_policy::check`] refuses before they leave the browser.
+//!
+//! Credentials cannot go through `Network.setExtraHTTPHeaders`, which Chrome attaches to
+//! every request the page makes, third-party scripts and trackers included. With
+//! [`InjectedHeaders`] the guard pauses all requests instead and adds each header only
+//! where it belongs.
+
+use crate::core::error::ScoutError;
+use crate::scraping::domain_policy;
+use anyhow::{anyhow, Result};
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    ContinueRequestParams, DisableParams, EnableParams, EventRequestPaused, FailRequestParams,
+    HeaderEntry, RequestPattern, RequestStage,
+};
+use chromiumoxide::cdp::browser_protocol::network::{ErrorReason, ResourceType};
+use chromiumoxide::Page;
+use futures::StreamExt;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+use url::{Origin, Url};
+
+/// Headers added to the requests of one render: `scoped` ones only on the target's origin,
+/// `shared` ones everywhere.
+#[derive(Debug, Clone)]
+pub(super) struct InjectedHeaders {
+    origin: Option<Origin>,
+    scoped: Vec<(String, String)>,
+    shared: Vec<(String, String)>,
+}
+
+impl InjectedHeaders {
+    pub(super) fn new(target: &str) -> Self {
+        Self {
+            origin: Url::parse(target).ok().map(|u| u.origin()),
+            scoped: Vec::new(),
+            shared: Vec::new(),
+        }
+    }
+
+    /// Send `name` only to the target's origin.
+    pub(super) fn scoped(&mut self, name: &str, value: &str) {
+        self.scoped.push((name.to_string(), value.to_string()));
+    }
+
+    /// Send `name` with every request the page makes.
+    pub(super) fn shared(&mut self, name: &str, value: &str) {
+        self.shared.push((name.to_string(), value.to_string()));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.scoped.is_empty() && self.shared.is_empty()
+    }
+
+    /// The headers a request to `url` goes out with: `original` plus the injected ones
+    /// that apply, which replace same-named originals. `None` when nothing applies.
+    fn for_request(
+        &self,
+        url: &str,
+        original: &serde_json::Value,
+    ) -> Option<Vec<(String, String)>> {
+        let same_origin =
+            self.origin.is_some() && Url::parse(url).ok().map(|u| u.origin()) == self.origin;
+        let added: Vec<&(String, String)> = self
+            .shared
+            .iter()
+            .chain(self.scoped.iter().filter(|_| same_origin))
+            .collect();
+        if added.is_empty() {
+            return None;
+        }
+        let mut headers: Vec<(String, String)> = original
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(name, _)| !added.iter().any(|(n, _)| n.eq_ignore_ascii_case(name)))
+            .filter_map(|(name, value)| Some((name.clone(), value.as_str()?.to_string())))
+            .collect();
+        headers.extend(added.into_iter().cloned());
+        Some(headers)
+    }
+}
+
+/// Document requests of one page held to the domain policy until dropped.
+pub(super) struct NavigationGuard {
+    page: Page,
+    refused: Arc<Mutex<Option<ScoutError>>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl NavigationGuard {
+    /// Start pausing `page`'s document requests, or all of its requests when `headers`
+    /// has anything to add.
+    pub(super) async fn install(page: &Page, headers: InjectedHeaders) -> Result<Self> {
+        let mut paused = page
+            .event_listener::<EventRequestPaused>()
+            .await
+            .map_err(|e| anyhow!("Failed to listen for paused requests: {}", e))?;
+        let mut pattern = RequestPattern::builder()
+            .url_pattern("*")
+            .request_stage(RequestStage::Request)
+            .build();
+        if headers.is_empty() {
+            pattern.resource_type = Some(ResourceType::Document);
+        }
+        page.execute(EnableParams::builder().pattern(pattern).build())
+            .await
+            .map_err(|e| anyhow!("Failed to enable request interception: {}", e))?;
+
+        let refused = Arc::new(Mutex::new(None));
+        let task = tokio::spawn({
+            let page = page.clone();
+            let refused = refused.clone();
+            async move {
+                while let Some(event) = paused.next().await {
+                    let url = event.request.url.as_str();
+                    let checked = match event.resource_type {
+                        ResourceType::Document => domain_policy::check(url).await,
+                        _ => Ok(()),
+                    };
+                    let outcome = match checked {
+                        Ok(()) => {
+                            let mut params = ContinueRequestParams::new(event.request_id.clone());
+                            params.headers = headers
+                                .for_request(url, event.request.headers.inner())
+                                .map(|pairs| {
+                                    pairs
+                                        .into_iter()
+                                        .map(|(name, value)| HeaderEntry::new(name, value))
+                                        .collect()
+                                });
+                            page.execute(params).await.map(|_| ())
+                        }
+                        Err(error) => {
+                            warn!("Browser navigation to {} refused by domain policy", url);
+                            refused.lock().unwrap().get_or_insert(error);
+                            page.execute(FailRequestParams::new(
+                                event.request_id.clone(),
+                                ErrorReason::BlockedByClient,
+                            ))
+                            .await
+                            .map(|_| ())
+                        }
+                    };
+                    if let Err(e) = outcome {
+                        warn!("Failed to release paused request {}: {}", url, e);
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            page: page.clone(),
+            refused,
+            task,
+        })
+    }
+
+    /// The first navigation the policy refused, as a `blocked` error.
+    pub(super) fn refused(&self) -> Option<ScoutError> {
+        self.refused.lock().unwrap().clone()
+    }
+
+    /// Stop intercepting; the page is usable without the guard afterwards.
+    pub(super) async fn remove(self) {
+        self.task.abort();
+        if let Err(e) = self.page.execute(DisableParams::default()).await {
+            warn!("Failed to disable request interception: {}", e);
+        }
+    }
+}
+
+impl Drop for NavigationGuard {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Where the page ended up, held to the policy as a backstop to [`NavigationGuard`].
+pub(super) async fn check_landing(page: &Page) -> Result<(), ScoutError> {
+    match page.url().await {
+        Ok(Some(landed)) => domain_policy::check(&landed).await,
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_headers_only_reach_the_target_origin() {
+        let mut headers = InjectedHeaders::new("https://wiki.example.com/page");
+        headers.scoped("Authorization", "Bearer s3cret");
+        let original = serde_json::json!({
+            "Accept": "text/html",
+            "authorization": "Basic stale",
+        });
+
+        let same = headers
+            .for_request("https://wiki.example.com/static/app.js", &original)
+            .unwrap();
+        assert!(same.contains(&("Accept".to_string(), "text/html".to_string())));
+        assert!(same.contains(&("Authorization".to_string(), "Bearer s3cret".to_string())));
+        assert!(!same.iter().any(|(_, value)| value == "Basic stale"));
+
+        for other in [
+            "https://cdn.example.net/lib.js",
+            "http://wiki.example.com/page",
+            "https://wiki.example.com:8443/page",
+        ] {
+            assert_eq!(headers.for_request(other, &original), None, "{}", other);
+        }
+
+        headers.shared("x-client-id", "scout");
+        let third_party = headers
+            .for_request("https://cdn.example.net/lib.js", &original)
+            .unwrap();
+        assert!(third_party.contains(&("x-client-id".to_string(), "scout".to_string())));
+        assert!(third_party.contains(&("authorization".to_string(), "Basic stale".to_string())));
+        assert!(!third_party
+            .iter()
+            .any(|(_, value)| value == "Bearer s3cret"));
+    }
+}
@@ -0,0 +1,142 @@
+//! Charset detection for fetched HTML before it reaches the extraction pipeline.
+//!
+//! `Response::text()` only knows the `Content-Type` charset, so legacy pages that
+//! declare GBK/Shift_JIS/... in a `<meta>` tag came out as replacement characters.
+//! Here the encoding is taken from a byte-order mark, then the header, then a
+//! `<meta charset>` / `http-equiv` prescan of the first bytes, defaulting to UTF-8.
+
+use encoding_rs::{Encoding, UTF_8};
+use std::sync::OnceLock;
+
+/// How far into the body the `<meta>` prescan looks (the HTML spec uses 1024 bytes;
+/// a little more tolerates long `<head>` preambles).
+const META_PRESCAN_BYTES: usize = 4096;
+
+static META_CHARSET_RE: OnceLock<regex::bytes::Regex> = OnceLock::new();
+
+/// Where the charset used for decoding came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CharsetSource {
+    Bom,
+    ContentType,
+    Meta,
+    Default,
+}
+
+pub(super) struct DecodedBody {
+    pub text: String,
+    /// Canonical encoding name, e.g. `UTF-8`, `GBK`, `Shift_JIS`.
+    pub charset: &'static str,
+    pub source: CharsetSource,
+    /// The bytes contained sequences invalid in `charset` (replaced with U+FFFD).
+    pub had_errors: bool,
+}
+
+/// Encoding named by the `charset` parameter of a `Content-Type` header value.
+pub(super) fn charset_from_content_type(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Encoding::for_label(
+            value
+                .trim()
+                .trim_matches(|c| c == '"' || c == '\'')
+                .as_bytes(),
+        )
+    })
+}
+
+/// Encoding declared by a `<meta charset>` or `<meta http-equiv content="...; charset=">`
+/// near the start of the document.
+pub(super) fn sniff_meta_charset(body: &[u8]) -> Option<&'static Encoding> {
+    let head = &body[..body.len().min(META_PRESCAN_BYTES)];
+    let re = META_CHARSET_RE.get_or_init(|| {
+        regex::bytes::Regex::new(r#"(?i)<meta\b[^>]*?charset\s*=\s*["']?\s*([a-z0-9_\-:.]+)"#)
+            .expect("valid meta charset regex")
+    });
+    let label = re.captures(head)?.get(1)?.as_bytes();
+    let encoding = Encoding::for_label(label)?;
+    // A document that could be read to find this tag is not UTF-16 (per the HTML spec).
+    Some(if encoding.output_encoding() == UTF_8 {
+        UTF_8
+    } else {
+        encoding
+    })
+}
+
+/// Decode an HTML body to UTF-8 using the first charset signal that is present.
+pub(super) fn decode_html(body: &[u8], content_type: &str) -> DecodedBody {
+    let (encoding, source) = if let Some((encoding, _)) = Encoding::for_bom(body) {
+        (encoding, CharsetSource::Bom)
+    } else if let Some(encoding) = charset_from_content_type(content_type) {
+        (encoding, CharsetSource::ContentType)
+    } else if let Some(encoding) = sniff_meta_charset(body) {
+        (encoding, CharsetSource::Meta)
+    } else {
+        (UTF_8, CharsetSource::Default)
+    };
+    // `decode` strips a matching BOM and would switch to a BOM-declared encoding itself.
+    let (text, actual, had_errors) = encoding.decode(body);
+    DecodedBody {
+        text: text.into_owned(),
+        charset: actual.name(),
+        source,
+        had_errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gbk_meta_declared_page_decodes_to_utf8() {
+        let title = "中文网页标题";
+        let page = format!(
+            "<html><head><meta charset=\"gbk\"><title>{}</title></head><body>正文</body></html>",
+            title
+        );
+        let (encoded, _, _) = encoding_rs::GBK.encode(&page);
+        assert!(std::str::from_utf8(&encoded).is_err());
+
+        let decoded = decode_html(&encoded, "text/html");
+        assert_eq!(decoded.charset, "GBK");
+        assert_eq!(decoded.source, CharsetSource::Meta);
+        assert!(!decoded.had_errors);
+        assert!(decoded.text.contains(&format!("<title>{}</title>", title)));
+
+        let url = url::Url::parse("http://example.cn/").unwrap();
+        let page = super::super::RustScraper::new().html_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/html".to_string(),
+            decoded.text,
+        );
+        assert_eq!(page.title, title);
+    }
+
+    #[test]
+    fn header_charset_wins_over_meta_and_utf8_is_default() {
+        let (encoded, _, _) = encoding_rs::SHIFT_JIS.encode("<meta charset=\"gbk\"><p>日本語</p>");
+        let decoded = decode_html(&encoded, "text/html; charset=\"Shift_JIS\"");
+        assert_eq!(decoded.charset, "Shift_JIS");
+        assert_eq!(decoded.source, CharsetSource::ContentType);
+        assert_eq!(decoded.text, "<meta charset=\"gbk\"><p>日本語</p>");
+
+        let http_equiv =
+            b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1252\">";
+        assert_eq!(
+            sniff_meta_charset(http_equiv),
+            Some(encoding_rs::WINDOWS_1252)
+        );
+        assert_eq!(sniff_meta_charset(b"<meta charset=utf-16le>"), Some(UTF_8));
+
+        let plain = decode_html("<p>héllo</p>".as_bytes(), "text/html");
+        assert_eq!(plain.charset, "UTF-8");
+        assert_eq!(plain.source, CharsetSource::Default);
+        assert_eq!(plain.text, "<p>héllo</p>");
+    }
+}
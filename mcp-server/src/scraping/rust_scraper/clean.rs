@@ -1,4 +1,4 @@
-use super::RustScraper;
+use super::{consent, words, RustScraper};
 use readability::extractor;
 use regex::Regex;
 use scraper::{Html, Selector};
@@ -158,12 +158,15 @@ pub fn determine_content_type(url: &Url, html_peek: &str) -> ContentType {
 }
 
 impl RustScraper {
+    /// Returns the clean content, the noise-reduction ratio and how many consent-manager
+    /// containers were stripped before extraction (see [`consent::strip_consent_markup`]).
     pub(super) fn extract_clean_content_with_metrics(
         &self,
         html: &str,
         base_url: &Url,
-    ) -> (String, f64) {
-        let clean = self.extract_clean_content(html, base_url);
+    ) -> (String, f64, usize) {
+        let (stripped, consent_nodes_removed) = consent::strip_consent_markup(html);
+        let clean = self.extract_clean_content(&stripped, base_url);
         // Approximate "noise reduction" as percentage removed by our cleaning pipeline.
         // This is computed relative to a whitespace-normalized raw text snapshot.
         let raw = self.clean_text(&html2md::parse_html(html));
@@ -172,7 +175,7 @@ impl RustScraper {
         } else {
             (raw.len().saturating_sub(clean.len())) as f64 / raw.len() as f64
         };
-        (clean, ratio.clamp(0.0, 1.0))
+        (clean, ratio.clamp(0.0, 1.0), consent_nodes_removed)
     }
 
     /// Fallback to og:description when main content is missing or too small
@@ -621,34 +624,9 @@ impl RustScraper {
                 let tag_name = child_element.value().name();
                 if matches!(
                     tag_name,
-                    "script"
-                        | "style"
-                        | "noscript"
-                        | "svg"
-                        | "canvas"
-                        | "iframe"
-                        | "form"
-                        | "header"
-                        | "footer"
-                        | "nav"
-                        | "aside"
-                ) {
-                    flush_run(self, &mut run_text, &mut run_nodes, text_parts);
-                    continue;
-                }
-
-                let attrs = child_element.value();
-                let mut skip = false;
-                if let Some(id) = attrs.id() {
-                    skip |= self.is_noise_identifier(id);
-                }
-                for class in attrs.classes() {
-                    if self.is_noise_identifier(class) {
-                        skip = true;
-                        break;
-                    }
-                }
-                if skip {
+                    "script" | "style" | "noscript" | "svg" | "canvas" | "iframe" | "form"
+                ) || self.is_page_chrome(&child_element)
+                {
                     flush_run(self, &mut run_text, &mut run_nodes, text_parts);
                     continue;
                 }
@@ -961,6 +939,16 @@ impl RustScraper {
         s
     }
 
+    /// Page chrome that text extraction skips: `header`, `footer`, `nav` and `aside`
+    /// elements, and elements whose id or a class names boilerplate. Link details flag
+    /// links inside it as `in_chrome`.
+    pub(super) fn is_page_chrome(&self, element: &scraper::ElementRef) -> bool {
+        let el = element.value();
+        matches!(el.name(), "header" | "footer" | "nav" | "aside")
+            || el.id().is_some_and(|id| self.is_noise_identifier(id))
+            || el.classes().any(|class| self.is_noise_identifier(class))
+    }
+
     fn is_noise_identifier(&self, ident: &str) -> bool {
         let ident = ident.to_ascii_lowercase();
         let needles = [
@@ -1045,8 +1033,14 @@ impl RustScraper {
         best_text
     }
 
+    /// Language-agnostic count for comparing extraction candidates; the reported
+    /// `word_count` goes through [`Self::count_words_in`].
     pub(super) fn count_words(&self, text: &str) -> usize {
-        text.split_whitespace().count()
+        words::count_words(text, None)
+    }
+
+    pub(super) fn count_words_in(&self, text: &str, language: &str) -> usize {
+        words::count_words(text, Some(language))
     }
 
     fn is_high_noise_content(&self, text: &str) -> bool {
@@ -0,0 +1,167 @@
+//! In-page redirects: `<meta http-equiv="refresh">` and trivial `location` scripts.
+//!
+//! Link shorteners, old CMSes and "you are leaving" interstitials often answer 200 with a
+//! near-empty page that sends the browser on, which a static fetch would extract as is.
+//! Only unambiguous forms count. A refresh must fire within [`MAX_REFRESH_DELAY_SECS`]: a
+//! page with content and `content="30;url=..."` is a page, not a redirect. A script must
+//! consist of nothing but one `location` assignment, `location.replace()` or
+//! `location.assign()` of a string literal.
+
+use crate::types::ClientRedirectKind;
+use regex::Regex;
+use scraper::{Html, Selector};
+use std::sync::LazyLock;
+use url::Url;
+
+/// In-page redirects followed per fetch, on top of HTTP redirects.
+pub(super) const MAX_CLIENT_REDIRECTS: usize = 3;
+
+/// Longest refresh delay still treated as a redirect.
+const MAX_REFRESH_DELAY_SECS: f64 = 1.0;
+
+static LOCATION_SCRIPT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^\s*(?:(?:window|document|self|top)\s*\.\s*)?location(?:\s*\.\s*href)?\s*=\s*(?:"([^"]*)"|'([^']*)')\s*;?\s*$|^\s*(?:(?:window|document|self|top)\s*\.\s*)?location\s*\.\s*(?:replace|assign)\s*\(\s*(?:"([^"]*)"|'([^']*)')\s*\)\s*;?\s*$"#,
+    )
+    .expect("valid location script regex")
+});
+
+/// The redirect `html` declares, resolved against `base`; `None` when it declares none, or
+/// only one to a non-HTTP(S) target.
+pub(super) fn detect(html: &str, base: &Url) -> Option<(Url, ClientRedirectKind)> {
+    // Parsing is skipped for the vast majority of pages, which mention neither.
+    let lower = html.to_ascii_lowercase();
+    if !lower.contains("refresh") && !lower.contains("location") {
+        return None;
+    }
+    let document = Html::parse_document(html);
+    let (target, kind) = meta_refresh_target(&document)
+        .map(|target| (target, ClientRedirectKind::MetaRefresh))
+        .or_else(|| script_target(&document).map(|target| (target, ClientRedirectKind::Script)))?;
+    let target = base.join(target.trim()).ok()?;
+    matches!(target.scheme(), "http" | "https").then_some((target, kind))
+}
+
+fn meta_refresh_target(document: &Html) -> Option<String> {
+    let selector = Selector::parse("meta[http-equiv][content]").ok()?;
+    document
+        .select(&selector)
+        .filter(|meta| {
+            meta.value()
+                .attr("http-equiv")
+                .is_some_and(|v| v.trim().eq_ignore_ascii_case("refresh"))
+        })
+        // A refresh inside <noscript> only applies to browsers without JavaScript.
+        .filter(|meta| {
+            !meta.ancestors().any(|node| {
+                node.value()
+                    .as_element()
+                    .is_some_and(|el| el.name() == "noscript")
+            })
+        })
+        .find_map(|meta| parse_refresh(meta.value().attr("content")?))
+}
+
+/// The URL of a refresh `content` value (`"0; url=/next"`) when its delay is short enough.
+fn parse_refresh(content: &str) -> Option<String> {
+    let (delay, rest) = content.split_once([';', ','])?;
+    let delay: f64 = delay.trim().parse().ok()?;
+    if !(0.0..=MAX_REFRESH_DELAY_SECS).contains(&delay) {
+        return None;
+    }
+    let rest = rest.trim();
+    let target = match rest.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("url") => {
+            rest[3..].trim_start().strip_prefix('=')?
+        }
+        _ => rest,
+    };
+    let target = target.trim().trim_matches(|c| c == '\'' || c == '"').trim();
+    (!target.is_empty()).then(|| target.to_string())
+}
+
+fn script_target(document: &Html) -> Option<String> {
+    let selector = Selector::parse("script:not([src])").ok()?;
+    document.select(&selector).find_map(|script| {
+        let code = script.text().collect::<String>();
+        let captures = LOCATION_SCRIPT.captures(&code)?;
+        (1..=4)
+            .find_map(|i| captures.get(i))
+            .map(|m| m.as_str().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://go.example.com/out/42").unwrap()
+    }
+
+    fn target(html: &str) -> Option<(String, ClientRedirectKind)> {
+        detect(html, &base()).map(|(url, kind)| (url.to_string(), kind))
+    }
+
+    #[test]
+    fn near_zero_meta_refreshes_are_redirects() {
+        let page = |content: &str| {
+            format!(
+                r#"<html><head><meta http-equiv="Refresh" content="{}"></head><body>Redirecting…</body></html>"#,
+                content
+            )
+        };
+        for content in [
+            "0;url=/docs",
+            "0; URL='/docs'",
+            "0.5 ,url = /docs",
+            "1;/docs",
+        ] {
+            assert_eq!(
+                target(&page(content)),
+                Some((
+                    "https://go.example.com/docs".to_string(),
+                    ClientRedirectKind::MetaRefresh
+                )),
+                "{}",
+                content
+            );
+        }
+        assert_eq!(target(&page("30;url=/docs")), None);
+        assert_eq!(target(&page("0")), None);
+        assert_eq!(target(&page("0;url=javascript:alert(1)")), None);
+        assert_eq!(
+            target(r#"<noscript><meta http-equiv="refresh" content="0;url=/nojs"></noscript>"#),
+            None
+        );
+    }
+
+    #[test]
+    fn only_trivial_location_scripts_are_redirects() {
+        let page = |script: &str| format!("<html><body><script>{}</script></body></html>", script);
+        for script in [
+            r#"window.location = "https://docs.example.com/guide";"#,
+            r#"location.href='https://docs.example.com/guide'"#,
+            r#" document.location.href = "https://docs.example.com/guide" "#,
+            r#"window.location.replace("https://docs.example.com/guide");"#,
+            r#"location.assign('https://docs.example.com/guide')"#,
+        ] {
+            assert_eq!(
+                target(&page(script)),
+                Some((
+                    "https://docs.example.com/guide".to_string(),
+                    ClientRedirectKind::Script
+                )),
+                "{}",
+                script
+            );
+        }
+        for script in [
+            r#"if (old) { location.href = "/legacy"; }"#,
+            r#"setTimeout(function () { location.href = "/next"; }, 5000);"#,
+            r#"location.href = base + "/next";"#,
+        ] {
+            assert_eq!(target(&page(script)), None, "{}", script);
+        }
+    }
+}
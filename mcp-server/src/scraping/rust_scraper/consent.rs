@@ -0,0 +1,254 @@
+//! Consent-banner and cookie-wall removal ahead of readability extraction.
+//!
+//! Many EU sites ship the article in the static HTML but wrap it in a consent manager's
+//! markup, which readability then scores as the main content. Known CMP containers
+//! (OneTrust, Cookiebot, Quantcast, Didomi, …) are dropped outright; generic
+//! `cookie-banner` / `consent` / `gdpr` matches are only dropped when they also look like
+//! an overlay (fixed/sticky, high z-index, dialog role or an accept/reject control) and
+//! are short — the same signals the non_robot_search janitor uses — so a recipe page
+//! about cookies keeps its content.
+
+use scraper::{ElementRef, Html, Selector};
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// Above this many removed containers the scrape carries a `consent_markup_stripped`
+/// warning, since a page that heavy with consent markup may have lost real content too.
+pub(super) const CONSENT_REMOVAL_WARN_THRESHOLD: usize = 3;
+
+/// Generic matches with more words than this are treated as page content, not a banner.
+const MAX_GENERIC_BANNER_WORDS: usize = 250;
+
+const MIN_OVERLAY_Z_INDEX: i64 = 1000;
+
+/// Containers owned by consent-management platforms; removed whenever present.
+const KNOWN_CMP_SELECTORS: &[&str] = &[
+    // OneTrust
+    "#onetrust-consent-sdk",
+    "#onetrust-banner-sdk",
+    "#onetrust-pc-sdk",
+    // Cookiebot
+    "#CybotCookiebotDialog",
+    "#CybotCookiebotDialogBodyUnderlay",
+    // Quantcast Choice
+    "#qc-cmp2-container",
+    ".qc-cmp2-container",
+    // Didomi
+    "#didomi-host",
+    ".didomi-popup",
+    // Usercentrics
+    "#usercentrics-root",
+    // Sourcepoint
+    "[id^=\"sp_message_container\"]",
+    // Google Funding Choices
+    ".fc-consent-root",
+    // Osano / cookieconsent.js
+    ".cc-window",
+    ".osano-cm-window",
+    // CookieYes / GDPR Cookie Consent (WordPress)
+    "#cookie-law-info-bar",
+    ".cky-consent-container",
+    // iubenda
+    "#iubenda-cs-banner",
+    // TrustArc
+    "#truste-consent-track",
+    "#consent_blackbar",
+];
+
+/// Generic naming patterns; only removed when [`looks_like_banner`] agrees.
+const GENERIC_SELECTORS: &[&str] = &[
+    "[class*=\"cookie-banner\"]",
+    "[class*=\"cookie-consent\"]",
+    "[class*=\"cookie-notice\"]",
+    "[class*=\"cookie-bar\"]",
+    "[class*=\"cookie-wall\"]",
+    "[class*=\"consent-banner\"]",
+    "[class*=\"gdpr\"]",
+    "[id*=\"cookie-banner\"]",
+    "[id*=\"cookie-consent\"]",
+    "[id*=\"cookie-notice\"]",
+    "[id*=\"consent\"]",
+    "[id*=\"gdpr\"]",
+];
+
+/// Cheap substring gate so pages without any consent markup skip the DOM parse.
+const PRECHECK_NEEDLES: &[&str] = &[
+    "cookie",
+    "consent",
+    "gdpr",
+    "onetrust",
+    "cybot",
+    "qc-cmp",
+    "didomi",
+    "usercentrics",
+    "sp_message",
+    "cc-window",
+    "iubenda",
+    "truste",
+];
+
+const CONSENT_VOCABULARY: &[&str] = &["cookie", "consent", "gdpr", "privacy", "tracking"];
+
+const CONTROL_LABELS: &[&str] = &[
+    "accept",
+    "agree",
+    "reject",
+    "decline",
+    "allow all",
+    "manage",
+    "got it",
+    "i understand",
+];
+
+struct ConsentSelectors {
+    known: Selector,
+    generic: Selector,
+    controls: Selector,
+    content: Selector,
+}
+
+fn selectors() -> &'static ConsentSelectors {
+    static SELECTORS: OnceLock<ConsentSelectors> = OnceLock::new();
+    SELECTORS.get_or_init(|| ConsentSelectors {
+        known: Selector::parse(&KNOWN_CMP_SELECTORS.join(", ")).unwrap(),
+        generic: Selector::parse(&GENERIC_SELECTORS.join(", ")).unwrap(),
+        controls: Selector::parse(
+            "button, a, [role=\"button\"], input[type=\"button\"], input[type=\"submit\"]",
+        )
+        .unwrap(),
+        content: Selector::parse("main, article, h1").unwrap(),
+    })
+}
+
+/// Remove consent-manager markup from `html`. Returns the (possibly unchanged) document
+/// and the number of top-level containers removed; nested matches are not counted twice.
+pub(super) fn strip_consent_markup(html: &str) -> (Cow<'_, str>, usize) {
+    let lower = html.to_ascii_lowercase();
+    if !PRECHECK_NEEDLES.iter().any(|needle| lower.contains(needle)) {
+        return (Cow::Borrowed(html), 0);
+    }
+
+    let sel = selectors();
+    let mut doc = Html::parse_document(html);
+    let mut removed = Vec::new();
+    for node in doc.root_element().descendants() {
+        let Some(el) = ElementRef::wrap(node) else {
+            continue;
+        };
+        if matches!(el.value().name(), "html" | "head" | "body") {
+            continue;
+        }
+        if node.ancestors().any(|a| removed.contains(&a.id())) {
+            continue;
+        }
+        let is_consent =
+            sel.known.matches(&el) || (sel.generic.matches(&el) && looks_like_banner(el, sel));
+        if is_consent {
+            removed.push(node.id());
+        }
+    }
+
+    if removed.is_empty() {
+        return (Cow::Borrowed(html), 0);
+    }
+    for id in &removed {
+        if let Some(mut node) = doc.tree.get_mut(*id) {
+            node.detach();
+        }
+    }
+    (Cow::Owned(doc.html()), removed.len())
+}
+
+/// Overlay and size checks for a generic `cookie`/`consent`/`gdpr` match.
+fn looks_like_banner(el: ElementRef<'_>, sel: &ConsentSelectors) -> bool {
+    if matches!(el.value().name(), "main" | "article") || el.select(&sel.content).next().is_some() {
+        return false;
+    }
+
+    let text = el.text().collect::<Vec<_>>().join(" ").to_lowercase();
+    if text.split_whitespace().count() > MAX_GENERIC_BANNER_WORDS {
+        return false;
+    }
+    if !CONSENT_VOCABULARY.iter().any(|word| text.contains(word)) {
+        return false;
+    }
+
+    let attrs = el.value();
+    let style: String = attrs
+        .attr("style")
+        .unwrap_or("")
+        .to_ascii_lowercase()
+        .split_whitespace()
+        .collect();
+    let positioned = style.contains("position:fixed") || style.contains("position:sticky");
+    let raised = style
+        .split(';')
+        .filter_map(|decl| decl.strip_prefix("z-index:"))
+        .filter_map(|z| z.trim_end_matches("!important").parse::<i64>().ok())
+        .any(|z| z >= MIN_OVERLAY_Z_INDEX);
+    let dialog = matches!(attrs.attr("role"), Some("dialog" | "alertdialog"))
+        || attrs.attr("aria-modal") == Some("true");
+    let has_control = el.select(&sel.controls).any(|control| {
+        let label = control
+            .text()
+            .collect::<String>()
+            .to_lowercase()
+            .trim()
+            .to_string();
+        let label = if label.is_empty() {
+            control.value().attr("value").unwrap_or("").to_lowercase()
+        } else {
+            label
+        };
+        CONTROL_LABELS.iter().any(|needle| label.contains(needle))
+    });
+
+    positioned || raised || dialog || has_control
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_cmp_containers_are_removed_once() {
+        let html = r#"<html><body>
+            <div id="onetrust-consent-sdk"><div id="onetrust-banner-sdk"><p>We value your privacy</p></div></div>
+            <div id="CybotCookiebotDialog"><p>This website uses cookies</p></div>
+            <article><p>The actual story.</p></article>
+        </body></html>"#;
+        let (out, removed) = strip_consent_markup(html);
+        assert_eq!(removed, 2);
+        assert!(!out.contains("We value your privacy"));
+        assert!(!out.contains("This website uses cookies"));
+        assert!(out.contains("The actual story."));
+    }
+
+    #[test]
+    fn generic_matches_need_an_overlay_signal() {
+        let banner = r#"<html><body>
+            <div class="site-cookie-banner" style="position: fixed; bottom: 0">
+              <p>We use cookies to improve your experience.</p><button>Accept all</button>
+            </div>
+            <p>Body text.</p>
+        </body></html>"#;
+        let (out, removed) = strip_consent_markup(banner);
+        assert_eq!(removed, 1);
+        assert!(!out.contains("improve your experience"));
+
+        let inline = r#"<html><body>
+            <div id="consent-and-storage"><p>Store cookie dough in the fridge for up to three days.</p></div>
+        </body></html>"#;
+        let (out, removed) = strip_consent_markup(inline);
+        assert_eq!(removed, 0);
+        assert!(out.contains("cookie dough"));
+    }
+
+    #[test]
+    fn pages_without_consent_markup_are_returned_untouched() {
+        let html = "<html><body><p>Nothing to see.</p></body></html>";
+        let (out, removed) = strip_consent_markup(html);
+        assert_eq!(removed, 0);
+        assert!(matches!(out, Cow::Borrowed(_)));
+    }
+}
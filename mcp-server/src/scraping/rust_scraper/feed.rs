@@ -0,0 +1,365 @@
+//! RSS/Atom feeds: `<link rel="alternate">` discovery and item parsing.
+
+use super::{reading_time_minutes, word_equivalents, RustScraper};
+use crate::types::*;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use scraper::{Html, Selector};
+use std::time::{Duration, Instant};
+use tracing::warn;
+use url::Url;
+
+/// Items kept per feed; feeds are newest-first, so this keeps the most recent ones.
+pub(super) const MAX_FEED_ITEMS: usize = 50;
+const FEED_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(super) struct ParsedFeed {
+    title: Option<String>,
+    items: Vec<FeedItem>,
+}
+
+/// Content types served for RSS/Atom documents.
+pub(super) fn is_feed_content_type(content_type: &str) -> bool {
+    let ct = content_type.to_ascii_lowercase();
+    ct.contains("application/rss+xml") || ct.contains("application/atom+xml")
+}
+
+pub(super) fn parse_feed(body: &[u8]) -> Result<ParsedFeed> {
+    let feed = feed_rs::parser::parse(body).map_err(|e| anyhow!("Failed to parse feed: {}", e))?;
+    let items = feed
+        .entries
+        .into_iter()
+        .take(MAX_FEED_ITEMS)
+        .map(|entry| {
+            let link = entry
+                .links
+                .iter()
+                .find(|l| l.rel.as_deref().is_none_or(|rel| rel == "alternate"))
+                .or_else(|| entry.links.first())
+                .map(|l| l.href.clone());
+            FeedItem {
+                title: entry
+                    .title
+                    .map(|t| html_to_text(&t.content))
+                    .unwrap_or_default(),
+                link,
+                published: entry.published.or(entry.updated).map(|d| d.to_rfc3339()),
+                summary: entry
+                    .summary
+                    .map(|s| html_to_text(&s.content))
+                    .filter(|s| !s.is_empty()),
+            }
+        })
+        .collect();
+
+    Ok(ParsedFeed {
+        title: feed
+            .title
+            .map(|t| html_to_text(&t.content))
+            .filter(|t| !t.is_empty()),
+        items,
+    })
+}
+
+/// Feed titles and summaries are frequently escaped HTML; keep only the text.
+fn html_to_text(fragment: &str) -> String {
+    let text = Html::parse_fragment(fragment)
+        .root_element()
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn items_markdown(items: &[FeedItem]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            let mut block = format!("## {}", item.title);
+            if let Some(published) = &item.published {
+                block.push_str(&format!("\n\nPublished: {}", published));
+            }
+            if let Some(link) = &item.link {
+                block.push_str(&format!("\n\n{}", link));
+            }
+            if let Some(summary) = &item.summary {
+                block.push_str(&format!("\n\n{}", summary));
+            }
+            block
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+impl RustScraper {
+    /// Feed URLs advertised via `<link rel="alternate" type="application/(rss|atom)+xml">`.
+    pub(super) fn extract_feed_links(&self, document: &Html, base: &Url) -> Vec<String> {
+        let Ok(selector) = Selector::parse("link[rel~=\"alternate\"][href]") else {
+            return Vec::new();
+        };
+        let mut feeds: Vec<String> = Vec::new();
+        for el in document.select(&selector) {
+            let is_feed = el.value().attr("type").is_some_and(is_feed_content_type);
+            let Some(href) = el.value().attr("href").filter(|_| is_feed) else {
+                continue;
+            };
+            if let Ok(resolved) = base.join(href.trim()) {
+                let resolved = resolved.to_string();
+                if !feeds.contains(&resolved) {
+                    feeds.push(resolved);
+                }
+            }
+        }
+        feeds
+    }
+
+    /// When `parse_feeds` is set, fetch the first advertised feed into `feed_items`.
+    /// Fetch or parse failures only add a `feed_fetch_failed` / `feed_parse_failed` warning.
+    pub(super) async fn attach_feed_items(&self, result: &mut ScrapeResponse) {
+        if !self.parse_feeds {
+            return;
+        }
+        let Some(feed_url) = result.feed_urls.first().cloned() else {
+            return;
+        };
+
+        let body = match self.fetch_feed(&feed_url).await {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Feed fetch failed for {}: {}", feed_url, e);
+                result.warnings.push("feed_fetch_failed".to_string());
+                return;
+            }
+        };
+        match parse_feed(&body) {
+            Ok(feed) => result.feed_items = feed.items,
+            Err(e) => {
+                warn!("Feed parse failed for {}: {}", feed_url, e);
+                result.warnings.push("feed_parse_failed".to_string());
+            }
+        }
+    }
+
+    async fn fetch_feed(&self, feed_url: &str) -> Result<Vec<u8>> {
+        let deadline = Instant::now() + FEED_FETCH_TIMEOUT;
+        let (response, _) = self
+            .get_following_redirects(feed_url, deadline, |hop, _| {
+                self.client
+                    .get(hop)
+                    .header("User-Agent", crate::antibot::get_random_user_agent())
+                    .header(
+                        "Accept",
+                        "application/rss+xml, application/atom+xml, application/xml;q=0.9, */*;q=0.8",
+                    )
+                    .timeout(FEED_FETCH_TIMEOUT)
+            })
+            .await;
+        let response = response?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP {}", response.status().as_u16()));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Build a `ScrapeResponse` for a URL that is itself an RSS/Atom feed: items become
+    /// `feed_items`, and `clean_content` lists them as markdown.
+    pub(super) fn feed_response(
+        &self,
+        url: &str,
+        parsed_url: &Url,
+        status_code: u16,
+        content_type: String,
+        raw: String,
+        feed: ParsedFeed,
+    ) -> ScrapeResponse {
+        let clean_content = items_markdown(&feed.items);
+        let language = self.detect_language(&Html::parse_document(""), &clean_content);
+        let word_count = self.count_words_in(&clean_content, &language);
+        let reading_time_minutes = Some(reading_time_minutes(word_count, &language));
+        let extraction_score = self.calculate_extraction_score(
+            word_equivalents(word_count, &language),
+            &None,
+            &[],
+            &[],
+        );
+        let links = feed
+            .items
+            .iter()
+            .filter_map(|item| {
+                item.link.as_ref().map(|link| Link {
+                    url: link.clone(),
+                    text: item.title.clone(),
+                    nofollow: false,
+                    detail: None,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        ScrapeResponse {
+            url: url.to_string(),
+            title: feed.title.unwrap_or_default(),
+            content: raw,
+            clean_content,
+            embedded_state_json: None,
+            embedded_data_sources: Vec::new(),
+            hydration_status: HydrationStatus::default(),
+            meta_description: String::new(),
+            meta_keywords: String::new(),
+            headings: Vec::new(),
+            links,
+            images: Vec::new(),
+            timestamp: Utc::now().to_rfc3339(),
+            status_code,
+            content_type,
+            word_count,
+            language,
+            canonical_url: None,
+            site_name: None,
+            author: None,
+            published_at: None,
+            og_title: None,
+            og_description: None,
+            og_image: None,
+            reading_time_minutes,
+            code_blocks: Vec::new(),
+            truncated: false,
+            actual_chars: 0,
+            max_chars_limit: None,
+            extraction_score: Some(extraction_score),
+            warnings: Vec::new(),
+            domain: parsed_url.host_str().map(|h| h.to_string()),
+            auth_wall_reason: None,
+            auth_risk_score: None,
+            detection_factors: Vec::new(),
+            final_url: None,
+            redirect_chain: Vec::new(),
+            metrics: None,
+            content_format: Some("feed".to_string()),
+            page_count: None,
+            feed_urls: Vec::new(),
+            feed_items: feed.items,
+            fetch_attempts: None,
+            block_reason: None,
+            asset_manifest: None,
+            charset: None,
+            etag: None,
+            last_modified: None,
+            revalidated: false,
+            structured_data: Vec::new(),
+            screenshot: None,
+            robots_directives: Vec::new(),
+            selector_results: None,
+            alternate_languages: Vec::new(),
+            provenance: Vec::new(),
+            sections: Vec::new(),
+            title_source: None,
+            meta_description_source: None,
+            quality: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel>
+  <title>Example Blog</title>
+  <link>https://blog.example.com/</link>
+  <item>
+    <title>First post</title>
+    <link>https://blog.example.com/first</link>
+    <pubDate>Tue, 10 Jun 2025 04:00:00 GMT</pubDate>
+    <description>&lt;p&gt;Hello &lt;b&gt;feed&lt;/b&gt; readers&lt;/p&gt;</description>
+  </item>
+  <item><title>Second post</title><link>https://blog.example.com/second</link></item>
+</channel></rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Atom Example</title>
+  <id>urn:example</id>
+  <updated>2025-06-10T04:00:00Z</updated>
+  <entry>
+    <title>Atom entry</title>
+    <id>urn:example:1</id>
+    <link rel="edit" href="https://example.com/edit/1"/>
+    <link rel="alternate" href="https://example.com/posts/1"/>
+    <updated>2025-06-10T04:00:00Z</updated>
+    <summary>Short summary</summary>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn parses_rss_items() {
+        let feed = parse_feed(RSS.as_bytes()).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Example Blog"));
+        assert_eq!(feed.items.len(), 2);
+        let first = &feed.items[0];
+        assert_eq!(first.title, "First post");
+        assert_eq!(
+            first.link.as_deref(),
+            Some("https://blog.example.com/first")
+        );
+        assert_eq!(
+            first.published.as_deref(),
+            Some("2025-06-10T04:00:00+00:00")
+        );
+        assert_eq!(first.summary.as_deref(), Some("Hello feed readers"));
+        assert!(feed.items[1].summary.is_none());
+    }
+
+    #[test]
+    fn parses_atom_and_prefers_alternate_link() {
+        let feed = parse_feed(ATOM.as_bytes()).unwrap();
+        assert_eq!(feed.items.len(), 1);
+        assert_eq!(
+            feed.items[0].link.as_deref(),
+            Some("https://example.com/posts/1")
+        );
+        assert_eq!(feed.items[0].summary.as_deref(), Some("Short summary"));
+    }
+
+    #[test]
+    fn caps_item_count() {
+        let items: String = (0..80)
+            .map(|i| format!("<item><title>Post {i}</title></item>"))
+            .collect();
+        let xml =
+            format!("<rss version=\"2.0\"><channel><title>Big</title>{items}</channel></rss>");
+        let feed = parse_feed(xml.as_bytes()).unwrap();
+        assert_eq!(feed.items.len(), MAX_FEED_ITEMS);
+    }
+
+    #[test]
+    fn malformed_feed_is_an_error() {
+        assert!(parse_feed(b"<html><body>not a feed</body></html>").is_err());
+    }
+
+    #[test]
+    fn discovers_advertised_feed_links() {
+        let html = r#"<html><head>
+            <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+            <link rel="alternate" type="application/atom+xml" href="https://blog.example.com/atom.xml">
+            <link rel="alternate" hreflang="de" href="/de/">
+            <link rel="stylesheet" type="text/css" href="/site.css">
+        </head><body></body></html>"#;
+        let base = Url::parse("https://blog.example.com/posts/").unwrap();
+        let links = RustScraper::new().extract_feed_links(&Html::parse_document(html), &base);
+        assert_eq!(
+            links,
+            vec![
+                "https://blog.example.com/feed.xml".to_string(),
+                "https://blog.example.com/atom.xml".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn feed_content_types() {
+        assert!(is_feed_content_type("application/rss+xml; charset=utf-8"));
+        assert!(is_feed_content_type("application/atom+xml"));
+        assert!(!is_feed_content_type("text/html"));
+    }
+}
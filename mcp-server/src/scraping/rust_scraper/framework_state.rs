@@ -0,0 +1,394 @@
+//! Page data unwrapped from well-known framework hydration containers.
+//!
+//! The raw blobs in `embedded_data_sources` are routing payloads with the useful records
+//! several levels down. This module extracts the data subtree of each recognised
+//! container:
+//!
+//! - Next.js: `<script id="__NEXT_DATA__">` → `props.pageProps`
+//! - Nuxt 2: `window.__NUXT__ = {...}` → `data` (or `state`)
+//! - Remix: `window.__remixContext = {...}` → `state.loaderData` (or `routeData`)
+//! - Apollo: `window.__APOLLO_STATE__ = {...}`, or an Apollo cache inside `pageProps`
+//!   (`__APOLLO_STATE__`, `apolloState`, `initialApolloState`), with its normalised
+//!   `__ref` links resolved back into nested objects
+//!
+//! Only JSON literals are understood; Nuxt's minified `(function(a,b){...})` form and
+//! Nuxt 3's `__NUXT_DATA__` serialisation are left to the raw blobs.
+
+use crate::types::EmbeddedDataSource;
+use scraper::{Html, Selector};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// How deep `__ref` links are followed when an Apollo cache is flattened.
+const MAX_REF_DEPTH: usize = 8;
+
+/// How deep below a payload's root a main entity is looked for.
+const MAX_ENTITY_DEPTH: usize = 3;
+
+/// Scalar fields listed under a main entity's body.
+const MAX_ENTITY_FIELDS: usize = 20;
+
+const TITLE_KEYS: &[&str] = &["title", "name", "headline"];
+const BODY_KEYS: &[&str] = &[
+    "body",
+    "articleBody",
+    "content",
+    "description",
+    "text",
+    "markdown",
+    "html",
+    "summary",
+];
+const APOLLO_KEYS: &[&str] = &["__APOLLO_STATE__", "apolloState", "initialApolloState"];
+
+/// One unwrapped payload.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct FrameworkPayload {
+    /// `nextjs`, `nuxt`, `remix` or `apollo`.
+    pub framework: &'static str,
+    /// `source_type` of the [`EmbeddedDataSource`], e.g. `next_page_props`.
+    pub source_type: &'static str,
+    pub value: Value,
+}
+
+impl FrameworkPayload {
+    pub(super) fn to_source(&self) -> EmbeddedDataSource {
+        EmbeddedDataSource {
+            source_type: self.source_type.to_string(),
+            content: self.value.to_string(),
+            framework: Some(self.framework.to_string()),
+        }
+    }
+}
+
+/// The payloads of every recognised container in `document`, in the order listed in the
+/// module docs.
+pub(super) fn unwrap(document: &Html) -> Vec<FrameworkPayload> {
+    let mut payloads = Vec::new();
+    if let Some(next) = script_json(document, "script#__NEXT_DATA__") {
+        if let Some(Value::Object(mut page_props)) = next.pointer("/props/pageProps").cloned() {
+            for key in APOLLO_KEYS {
+                if let Some(Value::Object(cache)) = page_props.remove(*key) {
+                    payloads.push(apollo_payload(&cache));
+                }
+            }
+            payloads.insert(
+                0,
+                FrameworkPayload {
+                    framework: "nextjs",
+                    source_type: "next_page_props",
+                    value: Value::Object(page_props),
+                },
+            );
+        }
+    }
+    if let Some(nuxt) = assigned_json(document, "__NUXT__") {
+        let value = match nuxt.get("data") {
+            Some(Value::Array(pages)) if pages.len() == 1 => pages[0].clone(),
+            Some(data) => data.clone(),
+            None => nuxt.get("state").cloned().unwrap_or(nuxt),
+        };
+        payloads.push(FrameworkPayload {
+            framework: "nuxt",
+            source_type: "nuxt_data",
+            value,
+        });
+    }
+    if let Some(remix) = assigned_json(document, "__remixContext") {
+        let value = remix
+            .pointer("/state/loaderData")
+            .or_else(|| remix.get("routeData"))
+            .cloned()
+            .unwrap_or(remix);
+        payloads.push(FrameworkPayload {
+            framework: "remix",
+            source_type: "remix_loader_data",
+            value,
+        });
+    }
+    if let Some(Value::Object(cache)) = assigned_json(document, "__APOLLO_STATE__") {
+        payloads.push(apollo_payload(&cache));
+    }
+    payloads.retain(|payload| match &payload.value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    });
+    payloads
+}
+
+fn script_json(document: &Html, selector: &str) -> Option<Value> {
+    let selector = Selector::parse(selector).ok()?;
+    let script = document.select(&selector).next()?;
+    serde_json::from_str(script.text().collect::<String>().trim()).ok()
+}
+
+/// The JSON literal assigned to `name` (`window.name = {...}`) in an inline script.
+fn assigned_json(document: &Html, name: &str) -> Option<Value> {
+    let selector = Selector::parse("script:not([src])").ok()?;
+    document.select(&selector).find_map(|script| {
+        let code = script.text().collect::<String>();
+        let mut search_from = 0;
+        while let Some(found) = code[search_from..].find(name) {
+            let after = search_from + found + name.len();
+            search_from = after;
+            let rest = code[after..].trim_start();
+            let Some(rest) = rest.strip_prefix('=').filter(|r| !r.starts_with('=')) else {
+                continue;
+            };
+            // Parse the first value and ignore whatever statement follows it.
+            let mut values =
+                serde_json::Deserializer::from_str(rest.trim_start()).into_iter::<Value>();
+            if let Some(Ok(value @ (Value::Object(_) | Value::Array(_)))) = values.next() {
+                return Some(value);
+            }
+        }
+        None
+    })
+}
+
+/// An Apollo normalised cache with `ROOT_QUERY` fields resolved into nested objects. A
+/// cache without `ROOT_QUERY` becomes the list of its resolved entities.
+fn apollo_payload(cache: &Map<String, Value>) -> FrameworkPayload {
+    let value = match cache.get("ROOT_QUERY") {
+        Some(Value::Object(root)) => {
+            let mut out = Map::new();
+            for (field, value) in root {
+                if field == "__typename" {
+                    continue;
+                }
+                let resolved = resolve_refs(value, cache, &mut HashSet::new(), 0);
+                // `product({"id":"1"})` reads as `product` unless two fields collide.
+                let name = field.split('(').next().unwrap_or(field).to_string();
+                let name = if out.contains_key(&name) {
+                    field.clone()
+                } else {
+                    name
+                };
+                out.insert(name, resolved);
+            }
+            Value::Object(out)
+        }
+        _ => Value::Array(
+            cache
+                .iter()
+                .filter(|(key, _)| key.as_str() != "ROOT_MUTATION")
+                .map(|(_, value)| resolve_refs(value, cache, &mut HashSet::new(), 0))
+                .collect(),
+        ),
+    };
+    FrameworkPayload {
+        framework: "apollo",
+        source_type: "apollo_state",
+        value,
+    }
+}
+
+/// `value` with every `{"__ref": id}` replaced by the cached entity. Cycles and links
+/// deeper than [`MAX_REF_DEPTH`] are left as references.
+fn resolve_refs(
+    value: &Value,
+    cache: &Map<String, Value>,
+    resolving: &mut HashSet<String>,
+    depth: usize,
+) -> Value {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(id)) = map.get("__ref") {
+                let Some(entity) = cache.get(id) else {
+                    return value.clone();
+                };
+                if depth >= MAX_REF_DEPTH || !resolving.insert(id.clone()) {
+                    return value.clone();
+                }
+                let resolved = resolve_refs(entity, cache, resolving, depth + 1);
+                resolving.remove(id);
+                return resolved;
+            }
+            Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), resolve_refs(v, cache, resolving, depth)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| resolve_refs(item, cache, resolving, depth))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// Markdown for the page's main entity: the object nearest a payload's root with both a
+/// title-like and a body-like string, when it is the only one at that depth or its body is
+/// at least twice as long as any other's (an `seo` block next to the product). Objects in
+/// arrays, such as the cards of a product grid, are never main entities.
+pub(super) fn main_entity_markdown(payloads: &[FrameworkPayload]) -> Option<String> {
+    payloads.iter().find_map(|payload| {
+        let mut level = vec![&payload.value];
+        for _ in 0..=MAX_ENTITY_DEPTH {
+            let mut candidates: Vec<(&Map<String, Value>, usize)> = level
+                .iter()
+                .filter_map(|value| value.as_object())
+                .filter_map(|map| {
+                    entity_title(map)?;
+                    Some((map, entity_body(map)?.1.len()))
+                })
+                .collect();
+            candidates.sort_by_key(|(_, body_len)| std::cmp::Reverse(*body_len));
+            match candidates.as_slice() {
+                [] => {}
+                [(entity, _)] => return Some(render_entity(entity)),
+                [(entity, longest), (_, next), ..] if *longest >= 2 * next => {
+                    return Some(render_entity(entity))
+                }
+                _ => return None,
+            }
+            level = level
+                .iter()
+                .filter_map(|value| value.as_object())
+                .flat_map(|map| map.values())
+                .filter(|value| value.is_object())
+                .collect();
+        }
+        None
+    })
+}
+
+fn text_field<'a>(map: &'a Map<String, Value>, keys: &[&str]) -> Option<(&'a str, &'a str)> {
+    keys.iter().find_map(|key| {
+        let (key, value) = map.get_key_value(*key)?;
+        let text = value.as_str()?.trim();
+        text.chars()
+            .any(char::is_alphanumeric)
+            .then_some((key.as_str(), text))
+    })
+}
+
+fn entity_title(map: &Map<String, Value>) -> Option<(&str, &str)> {
+    text_field(map, TITLE_KEYS)
+}
+
+fn entity_body(map: &Map<String, Value>) -> Option<(&str, &str)> {
+    text_field(map, BODY_KEYS)
+}
+
+fn render_entity(map: &Map<String, Value>) -> String {
+    let (title_key, title) = entity_title(map).unwrap_or_default();
+    let (body_key, body) = entity_body(map).unwrap_or_default();
+    let body = if body.contains("</") {
+        html2md::parse_html(body)
+    } else {
+        body.to_string()
+    };
+    let mut out = format!("# {}\n\n{}", title, body.trim());
+
+    let fields: Vec<String> = map
+        .iter()
+        .filter(|(key, _)| {
+            *key != title_key && *key != body_key && *key != "id" && !key.starts_with("__")
+        })
+        .filter_map(|(key, value)| {
+            let text = match value {
+                Value::String(s) if !s.trim().is_empty() && s.len() <= 200 => s.trim().to_string(),
+                Value::Number(n) => n.to_string(),
+                Value::Bool(b) => b.to_string(),
+                Value::Object(nested) => entity_title(nested)?.1.to_string(),
+                _ => return None,
+            };
+            Some(format!("- **{}**: {}", key, text))
+        })
+        .take(MAX_ENTITY_FIELDS)
+        .collect();
+    if !fields.is_empty() {
+        out.push_str("\n\n");
+        out.push_str(&fields.join("\n"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> Html {
+        let path = format!(
+            "{}/tests/fixtures/framework_state/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        Html::parse_document(&std::fs::read_to_string(path).unwrap())
+    }
+
+    #[test]
+    fn next_page_props_are_unwrapped() {
+        let payloads = unwrap(&fixture("next_product.html"));
+        assert_eq!(payloads.len(), 1);
+        let props = &payloads[0];
+        assert_eq!(
+            (props.framework, props.source_type),
+            ("nextjs", "next_page_props")
+        );
+        assert_eq!(props.value["product"]["sku"], "TK-2207");
+        // The routing envelope around pageProps is gone.
+        assert!(props.value.get("buildId").is_none());
+        assert!(props.value.get("page").is_none());
+
+        let source = props.to_source();
+        assert_eq!(source.framework.as_deref(), Some("nextjs"));
+        assert!(source.content.len() < 2_000);
+
+        let markdown = main_entity_markdown(&payloads).unwrap();
+        assert!(markdown.starts_with("# Trail Kettle 1.2L\n\n"));
+        assert!(markdown.contains("titanium kettle"));
+        assert!(markdown.contains("- **sku**: TK-2207"));
+        assert!(markdown.contains("- **brand**: Summit Works"));
+        assert!(!markdown.contains("__typename"));
+    }
+
+    #[test]
+    fn apollo_caches_are_flattened() {
+        let payloads = unwrap(&fixture("apollo_article.html"));
+        let apollo: Vec<_> = payloads
+            .iter()
+            .filter(|p| p.framework == "apollo")
+            .collect();
+        assert_eq!(apollo.len(), 1);
+        let article = &apollo[0].value["article"];
+        assert_eq!(article["title"], "Winter tyres, explained");
+        assert_eq!(article["author"]["name"], "Mara Lindqvist");
+        // The author refers back to their articles: the cycle stays a reference.
+        assert_eq!(
+            article["author"]["articles"][0]["__ref"],
+            "Article:winter-tyres"
+        );
+        assert_eq!(apollo[0].value["viewer"], Value::Null);
+
+        let markdown = main_entity_markdown(&payloads).unwrap();
+        assert!(markdown.starts_with("# Winter tyres, explained\n\n"));
+        assert!(markdown.contains("- **author**: Mara Lindqvist"));
+    }
+
+    #[test]
+    fn script_assignments_and_lists_are_handled() {
+        let html = Html::parse_document(
+            r#"<script>
+            window.__remixContext = {"state":{"loaderData":{"routes/deals":{"deals":[
+                {"name":"Kettle","description":"Boils water quickly."},
+                {"name":"Stove","description":"Burns gas efficiently."}]}}}};
+            window.__NUXT__ == null;
+            </script>"#,
+        );
+        let payloads = unwrap(&html);
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(payloads[0].source_type, "remix_loader_data");
+        assert_eq!(
+            payloads[0].value["routes/deals"]["deals"][1]["name"],
+            "Stove"
+        );
+        // Items of a list are not a page's main entity.
+        assert_eq!(main_entity_markdown(&payloads), None);
+    }
+}
@@ -0,0 +1,288 @@
+//! GitHub raw files: READMEs and blobs served as plain text by `raw.githubusercontent.com`.
+//!
+//! Markdown is kept as-is for `clean_content` (it already is the clean form), with headings
+//! and fenced code blocks read from it directly. Any other file is source code and becomes a
+//! single code block in the language its extension suggests.
+
+use super::{reading_time_minutes, word_equivalents, RustScraper};
+use crate::types::*;
+use chrono::Utc;
+use scraper::Html;
+use url::Url;
+
+/// Warning carried by every result built here, so callers can tell the page was not
+/// extracted through the generic HTML pipeline.
+pub(super) const SPECIALIZED_HANDLER_WARNING: &str = "specialized_handler:github";
+
+/// A successful non-HTML response from `raw.githubusercontent.com`.
+pub(super) fn is_raw_github_file(url: &Url, content_type: &str) -> bool {
+    url.host_str()
+        .is_some_and(|h| h.eq_ignore_ascii_case("raw.githubusercontent.com"))
+        && !content_type.to_ascii_lowercase().contains("html")
+}
+
+pub(super) fn is_markdown_path(path: &str) -> bool {
+    let path = path.to_ascii_lowercase();
+    [".md", ".markdown", ".mdown", ".mkd"]
+        .iter()
+        .any(|ext| path.ends_with(ext))
+}
+
+/// Fenced code blocks (```` ``` ```` or `~~~`) with their info-string language and char
+/// offsets into `markdown`. An unclosed fence runs to the end of the document.
+pub(super) fn fenced_code_blocks(markdown: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    // (fence, language, start_char, code lines)
+    let mut open: Option<(String, Option<String>, usize, Vec<&str>)> = None;
+    let mut offset = 0;
+    for line in markdown.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.chars().count();
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        let fence_line = trimmed.trim_start();
+        match open.as_mut() {
+            Some((fence, _, _, lines)) => {
+                let closes = fence_line.starts_with(fence.as_str())
+                    && fence_line.trim_end().chars().all(|c| c == '`' || c == '~');
+                if !closes {
+                    lines.push(trimmed);
+                    continue;
+                }
+                let (_, language, start_char, lines) = open.take().expect("fence is open");
+                blocks.push(CodeBlock {
+                    language,
+                    code: lines.join("\n"),
+                    start_char: Some(start_char),
+                    end_char: Some(offset),
+                });
+            }
+            None => {
+                let marker = fence_line.chars().next().filter(|c| *c == '`' || *c == '~');
+                let Some(marker) = marker else { continue };
+                let width = fence_line.chars().take_while(|c| *c == marker).count();
+                if width < 3 || trimmed.len() - fence_line.len() > 3 {
+                    continue;
+                }
+                let info = fence_line[width..].trim();
+                let language = info
+                    .split(|c: char| c.is_whitespace() || c == ',' || c == '{')
+                    .next()
+                    .filter(|lang| !lang.is_empty())
+                    .map(str::to_ascii_lowercase);
+                open = Some((
+                    marker.to_string().repeat(width),
+                    language,
+                    line_start,
+                    Vec::new(),
+                ));
+            }
+        }
+    }
+    if let Some((_, language, start_char, lines)) = open {
+        blocks.push(CodeBlock {
+            language,
+            code: lines.join("\n"),
+            start_char: Some(start_char),
+            end_char: Some(offset),
+        });
+    }
+    blocks
+}
+
+/// ATX headings (`#` … `######`) outside fenced code blocks.
+pub(super) fn markdown_headings(markdown: &str, code_blocks: &[CodeBlock]) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut offset = 0;
+    for line in markdown.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.chars().count();
+        let in_code = code_blocks.iter().any(|block| {
+            block.start_char.unwrap_or(0) <= line_start && line_start < block.end_char.unwrap_or(0)
+        });
+        if in_code {
+            continue;
+        }
+        let line = line.trim();
+        let level = line.chars().take_while(|c| *c == '#').count();
+        if !(1..=6).contains(&level) || !line[level..].starts_with(' ') {
+            continue;
+        }
+        let text = line[level..].trim().trim_end_matches('#').trim();
+        if !text.is_empty() {
+            headings.push(Heading {
+                level: format!("h{}", level),
+                text: text.to_string(),
+            });
+        }
+    }
+    headings
+}
+
+impl RustScraper {
+    /// Build a `ScrapeResponse` from a raw GitHub file. Markdown keeps its source as
+    /// `clean_content`; other files are returned whole as one code block.
+    pub(super) fn github_raw_response(
+        &self,
+        url: &str,
+        parsed_url: &Url,
+        status_code: u16,
+        content_type: String,
+        text: String,
+    ) -> ScrapeResponse {
+        let file_name = parsed_url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or("")
+            .to_string();
+        let (code_blocks, headings, content_format) = if is_markdown_path(parsed_url.path()) {
+            let code_blocks = fenced_code_blocks(&text);
+            let headings = markdown_headings(&text, &code_blocks);
+            (code_blocks, headings, "markdown")
+        } else {
+            let block = CodeBlock {
+                language: Self::infer_language_from_url(parsed_url),
+                code: text.clone(),
+                start_char: Some(0),
+                end_char: Some(text.chars().count()),
+            };
+            (vec![block], Vec::new(), "source")
+        };
+        let title = headings
+            .iter()
+            .find(|h| h.level == "h1")
+            .map(|h| h.text.clone())
+            .unwrap_or(file_name);
+        let clean_content = text.trim().to_string();
+        let language = self.detect_language(&Html::parse_document(""), &clean_content);
+        let word_count = self.count_words_in(&clean_content, &language);
+        let reading_time_minutes = Some(reading_time_minutes(word_count, &language));
+        let extraction_score = self.calculate_extraction_score(
+            word_equivalents(word_count, &language),
+            &None,
+            &code_blocks,
+            &headings,
+        );
+
+        ScrapeResponse {
+            url: url.to_string(),
+            title,
+            content: if self.raw_html { text } else { String::new() },
+            clean_content,
+            embedded_state_json: None,
+            embedded_data_sources: Vec::new(),
+            hydration_status: HydrationStatus::default(),
+            meta_description: String::new(),
+            meta_keywords: String::new(),
+            headings,
+            links: Vec::new(),
+            images: Vec::new(),
+            timestamp: Utc::now().to_rfc3339(),
+            status_code,
+            content_type,
+            word_count,
+            language,
+            canonical_url: None,
+            site_name: Some("GitHub".to_string()),
+            author: None,
+            published_at: None,
+            og_title: None,
+            og_description: None,
+            og_image: None,
+            reading_time_minutes,
+            code_blocks,
+            truncated: false,
+            actual_chars: 0,
+            max_chars_limit: None,
+            extraction_score: Some(extraction_score),
+            warnings: vec![SPECIALIZED_HANDLER_WARNING.to_string()],
+            domain: parsed_url.host_str().map(|h| h.to_string()),
+            auth_wall_reason: None,
+            auth_risk_score: None,
+            detection_factors: Vec::new(),
+            final_url: None,
+            redirect_chain: Vec::new(),
+            metrics: None,
+            content_format: Some(content_format.to_string()),
+            page_count: None,
+            feed_urls: Vec::new(),
+            feed_items: Vec::new(),
+            fetch_attempts: None,
+            block_reason: None,
+            asset_manifest: None,
+            charset: None,
+            etag: None,
+            last_modified: None,
+            revalidated: false,
+            structured_data: Vec::new(),
+            screenshot: None,
+            robots_directives: Vec::new(),
+            selector_results: None,
+            alternate_languages: Vec::new(),
+            provenance: Vec::new(),
+            sections: Vec::new(),
+            title_source: None,
+            meta_description_source: None,
+            quality: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const README: &str = "# demo-crate\n\nA tiny crate.\n\n## Install\n\n```toml\n[dependencies]\ndemo = \"1\"\n```\n\n## Usage\n\n~~~rust,ignore\n# fn main() {\nlet x = demo::run();\n# }\n~~~\n\n```\nplain\n";
+
+    #[test]
+    fn fences_become_code_blocks_with_languages_and_offsets() {
+        let blocks = fenced_code_blocks(README);
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].language.as_deref(), Some("toml"));
+        assert_eq!(blocks[0].code, "[dependencies]\ndemo = \"1\"");
+        assert_eq!(blocks[1].language.as_deref(), Some("rust"));
+        assert_eq!(blocks[1].code, "# fn main() {\nlet x = demo::run();\n# }");
+        // Unclosed fence runs to the end.
+        assert_eq!(blocks[2].language, None);
+        assert_eq!(blocks[2].code, "plain");
+        let start = blocks[0].start_char.unwrap();
+        assert!(README[start..].starts_with("```toml"));
+        assert!(README[..blocks[0].end_char.unwrap()].ends_with("```\n"));
+    }
+
+    #[test]
+    fn readme_keeps_markdown_and_skips_headings_inside_fences() {
+        let url = Url::parse("https://raw.githubusercontent.com/o/demo/main/README.md").unwrap();
+        let result = RustScraper::new().github_raw_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/plain; charset=utf-8".to_string(),
+            README.to_string(),
+        );
+        assert_eq!(result.title, "demo-crate");
+        assert!(result.clean_content.starts_with("# demo-crate"));
+        let headings: Vec<_> = result.headings.iter().map(|h| h.text.as_str()).collect();
+        assert_eq!(headings, ["demo-crate", "Install", "Usage"]);
+        assert_eq!(result.code_blocks.len(), 3);
+        assert_eq!(result.content_format.as_deref(), Some("markdown"));
+        assert_eq!(result.warnings, [SPECIALIZED_HANDLER_WARNING]);
+    }
+
+    #[test]
+    fn source_files_become_one_code_block() {
+        let url = Url::parse("https://raw.githubusercontent.com/o/demo/main/src/lib.rs").unwrap();
+        assert!(is_raw_github_file(&url, "text/plain"));
+        assert!(!is_raw_github_file(&url, "text/html"));
+        let result = RustScraper::new().github_raw_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/plain".to_string(),
+            "pub fn run() -> u32 {\n    1\n}\n".to_string(),
+        );
+        assert_eq!(result.title, "lib.rs");
+        assert_eq!(result.code_blocks.len(), 1);
+        assert_eq!(result.code_blocks[0].language.as_deref(), Some("rust"));
+        assert_eq!(result.content_format.as_deref(), Some("source"));
+    }
+}
@@ -0,0 +1,370 @@
+//! Golden-snapshot checks for the clean / quality pipeline.
+//!
+//! Each fixture under `tests/fixtures/golden/` is an HTML page plus a `.json` expectation
+//! with the same stem. The page goes through `process_html` once per `QualityMode` and the
+//! result is compared against the expectation: a word-count range, headings that must be
+//! found, an `extraction_score` floor, and strings that must or must not appear in
+//! `clean_content`. `cortex-scout quality-check <html-file> <url> [--write]` prints what
+//! the pipeline produces today together with a regenerated expectation, so fixtures are
+//! updated on purpose rather than by hand-editing JSON.
+
+use super::{QualityMode, RustScraper};
+use crate::types::ScrapeResponse;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const QUALITY_MODES: [QualityMode; 3] = [
+    QualityMode::Balanced,
+    QualityMode::Aggressive,
+    QualityMode::High,
+];
+
+/// Regenerated word-count ranges allow this much drift either way.
+const WORD_COUNT_SLACK: f64 = 0.2;
+/// Regenerated score floors sit this far below the lowest observed score.
+const SCORE_SLACK: f64 = 0.05;
+/// Headings and `must_contain` snippets picked when regenerating an expectation.
+const MAX_GENERATED_HEADINGS: usize = 5;
+const MAX_GENERATED_SNIPPETS: usize = 3;
+/// Words taken from the start of a content line for a generated `must_contain` snippet.
+const SNIPPET_WORDS: usize = 8;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GoldenExpectation {
+    /// URL the page is processed as; host and path steer the content router.
+    pub url: String,
+    /// Inclusive `[min, max]` range for `word_count`.
+    pub word_count: [usize; 2],
+    /// Heading texts that must be extracted (case-insensitive, any level).
+    #[serde(default)]
+    pub required_headings: Vec<String>,
+    #[serde(default)]
+    pub min_extraction_score: f64,
+    #[serde(default)]
+    pub must_contain: Vec<String>,
+    /// Boilerplate that cleaning must remove. Never generated; kept across regenerations.
+    #[serde(default)]
+    pub must_not_contain: Vec<String>,
+}
+
+impl GoldenExpectation {
+    /// Every way `page` misses this expectation; empty when it matches.
+    pub fn mismatches(&self, page: &ScrapeResponse) -> Vec<String> {
+        let mut out = Vec::new();
+        let [min, max] = self.word_count;
+        if page.word_count < min || page.word_count > max {
+            out.push(format!(
+                "word_count {} outside [{}, {}]",
+                page.word_count, min, max
+            ));
+        }
+        for heading in &self.required_headings {
+            if !page
+                .headings
+                .iter()
+                .any(|h| h.text.trim().eq_ignore_ascii_case(heading.trim()))
+            {
+                out.push(format!("missing heading {:?}", heading));
+            }
+        }
+        let score = page.extraction_score.unwrap_or(0.0);
+        if score < self.min_extraction_score {
+            out.push(format!(
+                "extraction_score {:.3} below floor {:.3}",
+                score, self.min_extraction_score
+            ));
+        }
+        for needle in &self.must_contain {
+            if !page.clean_content.contains(needle.as_str()) {
+                out.push(format!("clean_content lacks {:?}", needle));
+            }
+        }
+        for needle in &self.must_not_contain {
+            if page.clean_content.contains(needle.as_str()) {
+                out.push(format!("clean_content still contains {:?}", needle));
+            }
+        }
+        out
+    }
+}
+
+/// Run `html` through the CDP-side pipeline as `url` with the given quality mode.
+pub async fn process_fixture(html: &str, url: &str, mode: QualityMode) -> Result<ScrapeResponse> {
+    RustScraper::new_with_quality_mode(Some(mode.as_str()))
+        .process_html(html, url)
+        .await
+}
+
+/// Check `html` under every quality mode; each problem is prefixed with its mode.
+pub async fn check_fixture(html: &str, expectation: &GoldenExpectation) -> Vec<String> {
+    let mut out = Vec::new();
+    for mode in QUALITY_MODES {
+        match process_fixture(html, &expectation.url, mode).await {
+            Ok(page) => out.extend(
+                expectation
+                    .mismatches(&page)
+                    .into_iter()
+                    .map(|m| format!("{}: {}", mode.as_str(), m)),
+            ),
+            Err(e) => out.push(format!("{}: process_html failed: {}", mode.as_str(), e)),
+        }
+    }
+    out
+}
+
+/// `(html path, expectation)` for every `*.html` in `dir`, sorted by file name.
+pub fn load_fixtures(dir: &Path) -> Result<Vec<(PathBuf, GoldenExpectation)>> {
+    let mut html_paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("reading {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "html"))
+        .collect();
+    html_paths.sort();
+    html_paths
+        .into_iter()
+        .map(|html_path| {
+            let expectation = read_expectation(&expectation_path(&html_path))?
+                .ok_or_else(|| anyhow!("{} has no expectation JSON", html_path.display()))?;
+            Ok((html_path, expectation))
+        })
+        .collect()
+}
+
+fn expectation_path(html_path: &Path) -> PathBuf {
+    html_path.with_extension("json")
+}
+
+fn read_expectation(path: &Path) -> Result<Option<GoldenExpectation>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_str(&raw)
+        .map(Some)
+        .with_context(|| format!("parsing {}", path.display()))
+}
+
+/// An expectation that the pages (one per quality mode) satisfy, with some slack.
+///
+/// `must_not_contain` is carried over from `previous` untouched. Its `must_contain`
+/// entries are kept while every page still has them; otherwise snippets are taken from
+/// the first content lines all pages share.
+pub fn expectation_from(
+    url: &str,
+    pages: &[ScrapeResponse],
+    previous: Option<&GoldenExpectation>,
+) -> GoldenExpectation {
+    let counts = pages.iter().map(|p| p.word_count);
+    let min_words = counts.clone().min().unwrap_or(0);
+    let max_words = counts.max().unwrap_or(0);
+    let word_count = [
+        (min_words as f64 * (1.0 - WORD_COUNT_SLACK)).floor() as usize,
+        (max_words as f64 * (1.0 + WORD_COUNT_SLACK)).ceil() as usize,
+    ];
+
+    let in_every_page = |needle: &str| pages.iter().all(|p| p.clean_content.contains(needle));
+
+    let required_headings = pages
+        .first()
+        .map(|first| {
+            first
+                .headings
+                .iter()
+                .filter(|h| matches!(h.level.as_str(), "h1" | "h2" | "h3"))
+                .map(|h| h.text.trim().to_string())
+                .filter(|text| {
+                    !text.is_empty()
+                        && pages.iter().all(|p| {
+                            p.headings
+                                .iter()
+                                .any(|o| o.text.trim().eq_ignore_ascii_case(text))
+                        })
+                })
+                .take(MAX_GENERATED_HEADINGS)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let lowest_score = pages
+        .iter()
+        .map(|p| p.extraction_score.unwrap_or(0.0))
+        .fold(f64::INFINITY, f64::min);
+    let min_extraction_score = if lowest_score.is_finite() {
+        ((lowest_score - SCORE_SLACK).max(0.0) * 100.0).floor() / 100.0
+    } else {
+        0.0
+    };
+
+    let kept: Vec<String> = previous
+        .map(|p| {
+            p.must_contain
+                .iter()
+                .filter(|n| in_every_page(n))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    let must_contain = if kept.is_empty() {
+        pages
+            .first()
+            .map(|first| {
+                first
+                    .clean_content
+                    .lines()
+                    .filter_map(content_snippet)
+                    .filter(|s| in_every_page(s))
+                    .take(MAX_GENERATED_SNIPPETS)
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        kept
+    };
+
+    GoldenExpectation {
+        url: url.to_string(),
+        word_count,
+        required_headings,
+        min_extraction_score,
+        must_contain,
+        must_not_contain: previous
+            .map(|p| p.must_not_contain.clone())
+            .unwrap_or_default(),
+    }
+}
+
+/// The first words of a prose line, or `None` for short or markup-heavy lines.
+fn content_snippet(line: &str) -> Option<String> {
+    let line = line.trim();
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.len() < SNIPPET_WORDS
+        || line.starts_with(['#', '|', '>', '-', '*', '`', '!', '['])
+        || line.contains(['[', ']', '*', '_', '`'])
+    {
+        return None;
+    }
+    Some(words[..SNIPPET_WORDS].join(" "))
+}
+
+/// `quality-check <html-file> <url> [--write]`: report the pipeline's output for every
+/// quality mode on stderr and print a regenerated expectation on stdout. With `--write`
+/// the expectation replaces the `.json` next to the HTML file.
+pub async fn run_quality_check(args: &[String]) -> Result<()> {
+    let write = args.iter().any(|a| a == "--write");
+    let positional: Vec<&String> = args.iter().filter(|a| !a.starts_with("--")).collect();
+    let (Some(html_file), Some(url)) = (positional.first(), positional.get(1)) else {
+        return Err(anyhow!(
+            "usage: cortex-scout quality-check <html-file> <url> [--write]"
+        ));
+    };
+    let html_path = Path::new(html_file.as_str());
+    let html = std::fs::read_to_string(html_path)
+        .with_context(|| format!("reading {}", html_path.display()))?;
+    let json_path = expectation_path(html_path);
+    let previous = read_expectation(&json_path)?;
+
+    let mut pages = Vec::new();
+    for mode in QUALITY_MODES {
+        let page = process_fixture(&html, url, mode).await?;
+        eprintln!(
+            "{:<10} word_count={} extraction_score={:.3} headings={} warnings={:?}",
+            mode.as_str(),
+            page.word_count,
+            page.extraction_score.unwrap_or(0.0),
+            page.headings.len(),
+            page.warnings
+        );
+        if let Some(previous) = previous.as_ref() {
+            for problem in previous.mismatches(&page) {
+                eprintln!("{:<10} current expectation: {}", "", problem);
+            }
+        }
+        pages.push(page);
+    }
+
+    let expectation = expectation_from(url, &pages, previous.as_ref());
+    let json = serde_json::to_string_pretty(&expectation)?;
+    if write {
+        std::fs::write(&json_path, format!("{}\n", json))
+            .with_context(|| format!("writing {}", json_path.display()))?;
+        eprintln!("wrote {}", json_path.display());
+    }
+    println!("{}", json);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden")
+    }
+
+    #[tokio::test]
+    async fn golden_fixtures_hold_in_every_quality_mode() {
+        let fixtures = load_fixtures(&fixture_dir()).expect("golden fixtures load");
+        assert!(fixtures.len() >= 5, "expected at least 5 golden fixtures");
+
+        let mut report = Vec::new();
+        for (html_path, expectation) in &fixtures {
+            let html = std::fs::read_to_string(html_path).unwrap();
+            let name = html_path.file_name().unwrap().to_string_lossy();
+            for problem in check_fixture(&html, expectation).await {
+                report.push(format!("{}: {}", name, problem));
+            }
+        }
+        assert!(
+            report.is_empty(),
+            "{} golden mismatch(es); if the change is intended, regenerate with \
+             `cortex-scout quality-check <html-file> <url> --write`:\n  {}",
+            report.len(),
+            report.join("\n  ")
+        );
+    }
+
+    #[tokio::test]
+    async fn regenerated_expectations_accept_the_current_output() {
+        let (html_path, previous) = load_fixtures(&fixture_dir())
+            .unwrap()
+            .into_iter()
+            .find(|(p, _)| p.ends_with("news_article.html"))
+            .expect("news article fixture");
+        let html = std::fs::read_to_string(&html_path).unwrap();
+        let mut pages = Vec::new();
+        for mode in QUALITY_MODES {
+            pages.push(process_fixture(&html, &previous.url, mode).await.unwrap());
+        }
+
+        let regenerated = expectation_from(&previous.url, &pages, Some(&previous));
+        assert_eq!(regenerated.must_not_contain, previous.must_not_contain);
+        assert!(!regenerated.must_contain.is_empty());
+        for page in &pages {
+            assert_eq!(regenerated.mismatches(page), Vec::<String>::new());
+        }
+
+        let fresh = expectation_from(&previous.url, &pages, None);
+        assert!(fresh.must_not_contain.is_empty());
+        assert!(fresh.word_count[0] <= pages[0].word_count);
+        assert!(fresh.word_count[1] >= pages[0].word_count);
+    }
+
+    #[test]
+    fn snippets_skip_markup_and_short_lines() {
+        assert_eq!(
+            content_snippet("# Heading with enough words to pass the count"),
+            None
+        );
+        assert_eq!(content_snippet("Too short to be useful"), None);
+        assert_eq!(
+            content_snippet("See [the docs](https://example.com) for every option we support"),
+            None
+        );
+        assert_eq!(
+            content_snippet("  The river rose above its banks for the third time this spring."),
+            Some("The river rose above its banks for the".to_string())
+        );
+    }
+}
@@ -1,7 +1,111 @@
 use super::RustScraper;
 use scraper::{Html, Selector};
 
+/// Schema.org types kept as typed `structured_data` (`NewsArticle` and `BlogPosting` are
+/// treated as `Article`, like the markdown flattening below does).
+const STRUCTURED_DATA_TYPES: &[&str] = &[
+    "Article",
+    "NewsArticle",
+    "BlogPosting",
+    "Product",
+    "Recipe",
+    "JobPosting",
+    "Event",
+    "Organization",
+    "FAQPage",
+];
+
+/// Budget for the serialized size of all `structured_data` objects of one page.
+const MAX_STRUCTURED_DATA_BYTES: usize = 64 * 1024;
+
 impl RustScraper {
+    /// Allowlisted JSON-LD objects as parsed JSON, in document order. Objects that would
+    /// push the total past [`MAX_STRUCTURED_DATA_BYTES`] are dropped and reported by the
+    /// returned flag.
+    pub(super) fn extract_structured_data(
+        &self,
+        document: &Html,
+    ) -> (Vec<serde_json::Value>, bool) {
+        let Ok(selector) = Selector::parse("script[type='application/ld+json']") else {
+            return (Vec::new(), false);
+        };
+
+        let mut candidates = Vec::new();
+        for script in document.select(&selector) {
+            if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&script.inner_html())
+            {
+                Self::collect_structured_data(json_value, &mut candidates);
+            }
+        }
+
+        let mut structured_data = Vec::new();
+        let mut total = 0;
+        let mut truncated = false;
+        for value in candidates {
+            let size = serde_json::to_string(&value).map(|s| s.len()).unwrap_or(0);
+            if total + size > MAX_STRUCTURED_DATA_BYTES {
+                truncated = true;
+                continue;
+            }
+            total += size;
+            structured_data.push(value);
+        }
+        (structured_data, truncated)
+    }
+
+    /// Walk top-level arrays and `@graph` containers; allowlisted objects are kept whole
+    /// (nested entities such as an Article's publisher stay inside their parent).
+    fn collect_structured_data(value: serde_json::Value, out: &mut Vec<serde_json::Value>) {
+        match value {
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    Self::collect_structured_data(item, out);
+                }
+            }
+            serde_json::Value::Object(mut map) => {
+                let allowed = match map.get("@type") {
+                    Some(serde_json::Value::String(t)) => {
+                        STRUCTURED_DATA_TYPES.contains(&t.as_str())
+                    }
+                    Some(serde_json::Value::Array(types)) => types
+                        .iter()
+                        .filter_map(|t| t.as_str())
+                        .any(|t| STRUCTURED_DATA_TYPES.contains(&t)),
+                    _ => false,
+                };
+                if allowed {
+                    out.push(serde_json::Value::Object(map));
+                } else if let Some(graph) = map.remove("@graph") {
+                    Self::collect_structured_data(graph, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The first string `key` of a JSON-LD object at the top level, in a top-level array or
+    /// in an `@graph`, with whitespace collapsed.
+    pub(super) fn json_ld_text(&self, document: &Html, key: &str) -> Option<String> {
+        fn find(value: &serde_json::Value, key: &str) -> Option<String> {
+            match value {
+                serde_json::Value::Array(items) => items.iter().find_map(|item| find(item, key)),
+                serde_json::Value::Object(map) => map
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .filter(|s| s.chars().any(char::is_alphanumeric))
+                    .or_else(|| find(map.get("@graph")?, key)),
+                _ => None,
+            }
+        }
+
+        let selector = Selector::parse("script[type='application/ld+json']").ok()?;
+        document.select(&selector).find_map(|script| {
+            let value = serde_json::from_str::<serde_json::Value>(&script.inner_html()).ok()?;
+            find(&value, key)
+        })
+    }
+
     /// Extract JSON-LD structured data (Schema.org) from <script type="application/ld+json">
     pub(super) fn extract_json_ld(&self, document: &Html) -> Option<String> {
         let selector = Selector::parse("script[type='application/ld+json']").ok()?;
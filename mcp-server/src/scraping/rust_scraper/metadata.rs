@@ -1,46 +1,178 @@
 use super::RustScraper;
-use crate::types::EmbeddedDataSource;
+use crate::types::{AlternateLanguage, EmbeddedDataSource};
 use scraper::{Html, Selector};
 use std::collections::HashSet;
 use url::Url;
 use whatlang::{detect, Lang};
 
-impl RustScraper {
-    /// Extract page title with fallback to h1
-    pub(super) fn extract_title(&self, document: &Html) -> String {
-        // Try title tag first
-        if let Ok(title_selector) = Selector::parse("title") {
-            if let Some(title_element) = document.select(&title_selector).next() {
-                let title = title_element.text().collect::<String>().trim().to_string();
-                if !title.is_empty() {
-                    return title;
-                }
-            }
-        }
+/// `x-default`, or a BCP 47-shaped tag: a 2–8 letter primary subtag followed by
+/// alphanumeric subtags of 1–8 characters (`en`, `pt-BR`, `zh-Hant-TW`).
+fn is_language_tag(tag: &str) -> bool {
+    if tag.eq_ignore_ascii_case("x-default") {
+        return true;
+    }
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or("");
+    (2..=8).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags
+            .all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()))
+}
 
-        // Fallback to h1
-        if let Ok(h1_selector) = Selector::parse("h1") {
-            if let Some(h1_element) = document.select(&h1_selector).next() {
-                let h1_text = h1_element.text().collect::<String>().trim().to_string();
-                if !h1_text.is_empty() {
-                    return h1_text;
-                }
-            }
-        }
+/// Shortest paragraph, in letters and digits, used as a description fallback.
+const MIN_PARAGRAPH_CHARS: usize = 60;
+
+/// Longest description taken from a paragraph.
+const MAX_PARAGRAPH_DESCRIPTION_CHARS: usize = 300;
+
+/// `text` with whitespace collapsed and zero-width characters removed; `None` when nothing
+/// readable is left. Letters and digits of any script count, so a title in Thai or Chinese
+/// is kept while one made of punctuation, `&nbsp;` or a zero-width joiner is not.
+fn visible_text(text: &str) -> Option<String> {
+    let text = text
+        .replace(
+            ['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'],
+            "",
+        )
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    text.chars().any(char::is_alphanumeric).then_some(text)
+}
+
+fn first_text(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .find_map(|el| visible_text(&el.text().collect::<String>()))
+}
+
+fn meta_content(document: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    document
+        .select(&selector)
+        .find_map(|el| visible_text(el.value().attr("content")?))
+}
 
-        "No Title".to_string()
+/// The first `<p>` outside navigation and page chrome with at least
+/// [`MIN_PARAGRAPH_CHARS`] letters or digits, cut to [`MAX_PARAGRAPH_DESCRIPTION_CHARS`].
+fn first_paragraph(document: &Html) -> Option<String> {
+    let selector = Selector::parse("p").ok()?;
+    let text = document
+        .select(&selector)
+        .filter(|p| {
+            !p.ancestors().any(|node| {
+                node.value().as_element().is_some_and(|el| {
+                    matches!(el.name(), "nav" | "header" | "footer" | "aside" | "form")
+                })
+            })
+        })
+        .filter_map(|p| visible_text(&p.text().collect::<String>()))
+        .find(|text| text.chars().filter(|c| c.is_alphanumeric()).count() >= MIN_PARAGRAPH_CHARS)?;
+    if text.chars().count() <= MAX_PARAGRAPH_DESCRIPTION_CHARS {
+        return Some(text);
     }
+    let cut: String = text.chars().take(MAX_PARAGRAPH_DESCRIPTION_CHARS).collect();
+    // Prefer a word boundary; text without spaces (Chinese, Japanese, Thai) is cut as is.
+    let cut = match cut.rfind(' ') {
+        Some(i) if i > cut.len() / 2 => &cut[..i],
+        _ => cut.as_str(),
+    };
+    Some(format!("{}…", cut.trim_end()))
+}
 
-    /// Extract meta description
-    pub(super) fn extract_meta_description(&self, document: &Html) -> String {
-        if let Ok(selector) = Selector::parse("meta[name=\"description\"]") {
-            if let Some(element) = document.select(&selector).next() {
-                if let Some(content) = element.value().attr("content") {
-                    return content.trim().to_string();
+/// The last readable path segment, percent-decoded and de-slugified (`my-article-name.html`
+/// becomes `My article name`), or the host for a URL without one.
+fn title_from_url(url: &Url) -> Option<String> {
+    let from_path = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .rev()
+        .find_map(|segment| {
+            let segment = percent_encoding::percent_decode_str(segment).decode_utf8_lossy();
+            let stem = match segment.rsplit_once('.') {
+                Some((stem, ext))
+                    if !stem.is_empty()
+                        && (1..=5).contains(&ext.len())
+                        && ext.chars().all(|c| c.is_ascii_alphanumeric()) =>
+                {
+                    stem
                 }
+                _ => &segment,
+            };
+            let words = visible_text(&stem.replace(['-', '_', '+'], " "))?;
+            // Bare ids (`/posts/48213`) say nothing about the page.
+            if !words.chars().any(char::is_alphabetic) {
+                return None;
             }
+            let mut chars = words.chars();
+            let first = chars.next()?;
+            Some(first.to_uppercase().chain(chars).collect::<String>())
+        });
+    from_path.or_else(|| url.host_str().map(str::to_string))
+}
+
+impl RustScraper {
+    /// Page title: `<title>`, else the first of og:title, twitter:title, the first h1/h2,
+    /// the JSON-LD `headline` and the de-slugified URL that has visible text. The second
+    /// value names the fallback used, `None` when `<title>` was.
+    pub(super) fn extract_title(
+        &self,
+        document: &Html,
+        page_url: &Url,
+    ) -> (String, Option<String>) {
+        if let Some(title) = first_text(document, "title") {
+            return (title, None);
+        }
+        let fallbacks = [
+            (
+                "og:title",
+                meta_content(document, "meta[property=\"og:title\"]"),
+            ),
+            (
+                "twitter:title",
+                meta_content(document, "meta[name=\"twitter:title\"]"),
+            ),
+            ("heading", first_text(document, "h1, h2")),
+            ("json_ld:headline", self.json_ld_text(document, "headline")),
+            ("url", title_from_url(page_url)),
+        ];
+        match fallbacks
+            .into_iter()
+            .find_map(|(source, value)| Some((value?, source)))
+        {
+            Some((title, source)) => (title, Some(source.to_string())),
+            None => ("No Title".to_string(), None),
         }
-        String::new()
+    }
+
+    /// Meta description: `<meta name="description">`, else the first of og:description,
+    /// twitter:description, the JSON-LD `description` and the first substantive paragraph
+    /// that has visible text. The second value names the fallback used.
+    pub(super) fn extract_meta_description(&self, document: &Html) -> (String, Option<String>) {
+        if let Some(description) = meta_content(document, "meta[name=\"description\"]") {
+            return (description, None);
+        }
+        let fallbacks = [
+            (
+                "og:description",
+                meta_content(document, "meta[property=\"og:description\"]"),
+            ),
+            (
+                "twitter:description",
+                meta_content(document, "meta[name=\"twitter:description\"]"),
+            ),
+            (
+                "json_ld:description",
+                self.json_ld_text(document, "description"),
+            ),
+            ("paragraph", first_paragraph(document)),
+        ];
+        fallbacks
+            .into_iter()
+            .find_map(|(source, value)| Some((value?, Some(source.to_string()))))
+            .unwrap_or_default()
     }
 
     /// Extract meta keywords
@@ -71,6 +203,50 @@ impl RustScraper {
         None
     }
 
+    /// `<link rel="alternate" hreflang>` variants, resolved against the canonical URL when
+    /// there is one. Tags that are not well-formed language tags are skipped.
+    pub(super) fn extract_alternate_languages(
+        &self,
+        document: &Html,
+        base: &Url,
+        canonical_url: Option<&str>,
+    ) -> Vec<AlternateLanguage> {
+        let Ok(selector) = Selector::parse("link[rel~=\"alternate\"][hreflang][href]") else {
+            return Vec::new();
+        };
+        let canonical = canonical_url.and_then(|c| Url::parse(c).ok());
+        let base = canonical.as_ref().unwrap_or(base);
+
+        let mut alternates: Vec<AlternateLanguage> = Vec::new();
+        for el in document.select(&selector) {
+            let lang = el
+                .value()
+                .attr("hreflang")
+                .unwrap_or("")
+                .trim()
+                .replace('_', "-");
+            if !is_language_tag(&lang) {
+                continue;
+            }
+            let href = el.value().attr("href").unwrap_or("").trim();
+            let Some(url) = Some(href)
+                .filter(|h| !h.is_empty())
+                .and_then(|h| base.join(h).ok())
+                .filter(|u| matches!(u.scheme(), "http" | "https"))
+            else {
+                continue;
+            };
+            let alternate = AlternateLanguage {
+                lang,
+                url: url.to_string(),
+            };
+            if !alternates.contains(&alternate) {
+                alternates.push(alternate);
+            }
+        }
+        alternates
+    }
+
     /// Extract site name (OpenGraph fallback)
     pub(super) fn extract_site_name(&self, document: &Html) -> Option<String> {
         if let Ok(selector) = Selector::parse("meta[property=\"og:site_name\"]") {
@@ -295,6 +471,7 @@ impl RustScraper {
                 out.push(EmbeddedDataSource {
                     source_type: "jsonld".to_string(),
                     content: trimmed.to_string(),
+                    framework: None,
                 });
             }
         }
@@ -341,6 +518,7 @@ impl RustScraper {
             out.push(EmbeddedDataSource {
                 source_type: source_type.to_string(),
                 content: trimmed.to_string(),
+                framework: None,
             });
         }
 
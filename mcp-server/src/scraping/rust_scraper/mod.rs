@@ -1,21 +1,46 @@
 mod browserless;
 mod cdp;
+mod cdp_guard;
+mod charset;
 mod clean;
+mod client_redirect;
+mod consent;
+mod feed;
+mod framework_state;
+mod github;
+pub mod golden;
 mod jsonld;
 mod metadata;
 mod parse;
+mod pdf;
 mod quality;
 mod stealth;
+mod text_formats;
+mod words;
+
+pub use parse::{parse_robots_directives, rel_has_nofollow};
+pub use words::{count_words, reading_time_minutes, word_equivalents};
 
 use crate::antibot;
+use crate::core::error::ScoutError;
+use crate::core::fixtures::{header_pairs, Fixtures};
+use crate::core::metrics::Metrics;
+use crate::core::retry::RetryPolicy;
+use crate::scraping::capture::{CaptureRequest, Captures};
+use crate::scraping::cookie_jar::SharedJar;
+use crate::scraping::provenance;
+use crate::scraping::request_auth::RequestAuth;
+use crate::scraping::request_headers::RequestHeaders;
 use crate::types::*;
 use anyhow::{anyhow, Result};
 use chrono::Utc;
+use reqwest::header::HeaderValue;
 use reqwest::Client;
 use scraper::Html;
 use std::collections::HashSet;
-use std::time::Duration;
-use tracing::info;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 use url::Url;
 
 /// Enhanced Rust-native web scraper with anti-bot protection
@@ -27,6 +52,40 @@ pub struct RustScraper {
     /// is only taken if it yields ≥ 100 readable words; otherwise the standard
     /// readability pipeline is used instead.
     pub extract_app_state: bool,
+    /// When `true`, fetch the page's first advertised RSS/Atom feed into `feed_items`.
+    pub parse_feeds: bool,
+    /// When `true`, HTML pages only get title, canonical URL, headings, links and images;
+    /// content extraction is skipped (`mode: "links_only"`).
+    pub links_only: bool,
+    /// When `true`, each extracted link gets a [`LinkDetail`](crate::types::LinkDetail).
+    pub links_detail: bool,
+    /// When `true`, browser (CDP) fetches save a PNG of the settled page, which
+    /// [`Self::process_html`] attaches as `screenshot`.
+    pub screenshot: bool,
+    /// Capture the whole scrollable page rather than the viewport.
+    pub screenshot_full_page: bool,
+    /// When `false`, HTML results leave `content` empty instead of carrying the raw markup.
+    pub raw_html: bool,
+    /// Capture from the last CDP fetch, waiting for `process_html`.
+    captured_screenshot: std::sync::Mutex<Option<PageScreenshot>>,
+    retry_policy: RetryPolicy,
+    /// Sensitive `Authorization` value sent with native fetches and CDP navigations.
+    auth_header: Option<HeaderValue>,
+    /// Caller headers and User-Agent, applied over the stealth defaults.
+    custom_headers: Option<RequestHeaders>,
+    cookie_jar: Option<SharedJar>,
+    proxy: Option<reqwest::Proxy>,
+    /// URL of `proxy`, reported (as its host) in provenance.
+    proxy_url: Option<String>,
+    /// Registry that browser (CDP) fetches report their outcome and latency to.
+    metrics: Option<Arc<Metrics>>,
+    /// Shared pool that bounds and serves browser (CDP) fetches; without one each fetch
+    /// launches its own browser.
+    browser_pool: Option<Arc<crate::scraping::browser_manager::BrowserPool>>,
+    /// Recorded responses native fetches replay from or record to (`CORTEX_SCOUT_FIXTURE_DIR`).
+    fixtures: Option<Fixtures>,
+    /// Debug captures written for every extracted page (`CORTEX_SCOUT_CAPTURE_DIR`).
+    captures: Option<Captures>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -67,23 +126,244 @@ pub struct PreflightCheck {
     pub blocked_reason: Option<String>,
 }
 
+/// Redirects followed before a fetch gives up.
+const MAX_REDIRECTS: usize = 10;
+
+/// How a native GET reached its response.
+struct FetchTrail {
+    /// Requests sent across all hops, retries included.
+    attempts: u32,
+    redirect_chain: Vec<RedirectHop>,
+    /// The URL the response came from; the requested URL when there was no redirect.
+    final_url: String,
+    /// `ETag` and `Last-Modified` of the final response, for later revalidation.
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Outcome of a native fetch made conditional on a cached page's validators.
+pub enum Revalidation {
+    /// The site answered `304 Not Modified`; nothing was downloaded or extracted.
+    NotModified,
+    /// The page was fetched (and extracted) in full.
+    Modified(Box<ScrapeResponse>),
+}
+
+impl FetchTrail {
+    /// `final_url` when the fetch was redirected.
+    fn redirected_to(&self) -> Option<&str> {
+        (!self.redirect_chain.is_empty()).then_some(self.final_url.as_str())
+    }
+
+    fn annotate(&self, result: &mut ScrapeResponse) {
+        result.fetch_attempts = Some(self.attempts);
+        result.final_url = self.redirected_to().map(str::to_string);
+        result.redirect_chain = self.redirect_chain.clone();
+        if self.client_redirects() > 0 {
+            result.warnings.push("client_redirect_followed".to_string());
+        }
+        result.etag = self.etag.clone();
+        result.last_modified = self.last_modified.clone();
+    }
+
+    /// In-page redirects followed so far.
+    fn client_redirects(&self) -> usize {
+        self.redirect_chain
+            .iter()
+            .filter(|hop| hop.client_redirect.is_some())
+            .count()
+    }
+
+    /// Whether `url` was already requested on the way here.
+    fn visited(&self, url: &Url) -> bool {
+        self.redirect_chain
+            .iter()
+            .map(|hop| hop.url.as_str())
+            .chain(std::iter::once(self.final_url.as_str()))
+            .any(|seen| Url::parse(seen).is_ok_and(|seen| &seen == url))
+    }
+
+    fn keep_validators(&mut self, headers: &reqwest::header::HeaderMap) {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v: &HeaderValue| v.to_str().ok())
+                .map(str::to_string)
+        };
+        self.etag = header(reqwest::header::ETAG);
+        self.last_modified = header(reqwest::header::LAST_MODIFIED);
+    }
+}
+
+/// Make `request` conditional on the validators `cached` was fetched with.
+fn conditional_request(
+    mut request: reqwest::RequestBuilder,
+    cached: Option<&ScrapeResponse>,
+) -> reqwest::RequestBuilder {
+    let Some(cached) = cached else {
+        return request;
+    };
+    if let Some(etag) = cached.etag.as_deref() {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = cached.last_modified.as_deref() {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+    request
+}
+
+/// Where a redirect response points, resolved against the URL that returned it.
+fn redirect_target(current: &str, response: &reqwest::Response) -> Option<String> {
+    if !matches!(response.status().as_u16(), 301 | 302 | 303 | 307 | 308) {
+        return None;
+    }
+    let location = response
+        .headers()
+        .get(reqwest::header::LOCATION)?
+        .to_str()
+        .ok()?;
+    let target = Url::parse(current).ok()?.join(location.trim()).ok()?;
+    Some(target.to_string())
+}
+
+/// Read a response body, refusing anything over `max_bytes` up front (via Content-Length)
+/// or as soon as the streamed size crosses the limit. `setting` names the env var that
+/// raises the limit, for the error message.
+pub(crate) async fn read_body_capped(
+    mut response: reqwest::Response,
+    max_bytes: usize,
+    setting: &str,
+) -> Result<Vec<u8>> {
+    if let Some(len) = response.content_length() {
+        if len > max_bytes as u64 {
+            return Err(response_too_large(len as usize, max_bytes, setting));
+        }
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| anyhow!("Failed to read response body: {}", e))?
+    {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(response_too_large(
+                body.len() + chunk.len(),
+                max_bytes,
+                setting,
+            ));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// An HTML/text body read under `SCRAPE_MAX_RESPONSE_BYTES` and decoded with its charset.
+pub(crate) async fn read_html_capped(response: reqwest::Response) -> Result<String> {
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("text/html")
+        .to_string();
+    let body = read_body_capped(
+        response,
+        crate::core::config::scrape_max_response_bytes(),
+        "SCRAPE_MAX_RESPONSE_BYTES",
+    )
+    .await?;
+    Ok(charset::decode_html(&body, &content_type).text)
+}
+
+fn response_too_large(size: usize, max_bytes: usize, setting: &str) -> anyhow::Error {
+    anyhow!(
+        "Response too large: {} bytes exceeds the {} byte limit ({})",
+        size,
+        max_bytes,
+        setting
+    )
+}
+
+/// Merge `X-Robots-Tag` header directives into those read from the page's meta tags.
+fn add_robots_directives(result: &mut ScrapeResponse, header: &str) {
+    for directive in parse_robots_directives(header) {
+        if !result.robots_directives.contains(&directive) {
+            result.robots_directives.push(directive);
+        }
+    }
+}
+
 impl RustScraper {
     pub fn new() -> Self {
         Self::new_with_quality_mode(None)
     }
 
     pub fn new_with_quality_mode(quality_mode: Option<&str>) -> Self {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .redirect(reqwest::redirect::Policy::limited(10))
-            .build()
-            .expect("Failed to create HTTP client");
-
         Self {
-            client,
+            client: crate::core::http_client::scrape_client(),
             quality_mode: QualityMode::from_option(quality_mode),
             extract_app_state: false,
+            parse_feeds: false,
+            links_only: false,
+            links_detail: false,
+            screenshot: false,
+            screenshot_full_page: false,
+            raw_html: true,
+            captured_screenshot: std::sync::Mutex::new(None),
+            retry_policy: RetryPolicy::from_env(),
+            auth_header: None,
+            custom_headers: None,
+            cookie_jar: None,
+            proxy: None,
+            proxy_url: None,
+            metrics: None,
+            browser_pool: None,
+            fixtures: Fixtures::from_env(),
+            captures: Captures::from_env(),
+        }
+    }
+
+    /// A client of its own for a cookie jar or proxy, still on the shared pool tuning and
+    /// DNS cache.
+    fn build_client(cookie_jar: Option<SharedJar>, proxy: Option<reqwest::Proxy>) -> Client {
+        // Redirects are followed by `get_following_redirects`, which records each hop and
+        // holds every target to the domain policy.
+        let mut builder = crate::core::http_client::scrape_builder();
+        if let Some(jar) = cookie_jar {
+            builder = builder.cookie_provider(jar);
+        }
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(proxy);
+        }
+        builder.build().expect("Failed to create HTTP client")
+    }
+
+    /// Builder: fetch with `client` instead of the process-wide
+    /// [`scrape_client`](crate::core::http_client::scrape_client). It must not follow
+    /// redirects itself. A later cookie jar or proxy replaces it with a client of its own.
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Builder: send and store cookies through a shared per-site jar (`persist_session`).
+    pub fn with_cookie_jar(mut self, jar: Option<SharedJar>) -> Self {
+        if jar.is_some() {
+            self.cookie_jar = jar;
+            self.client = Self::build_client(self.cookie_jar.clone(), self.proxy.clone());
+        }
+        self
+    }
+
+    /// Builder: route native fetches through an `http(s)://`, `socks5://` or
+    /// `socks5h://` proxy from the proxy pool. Browser fetches take the proxy per call.
+    pub fn with_proxy(mut self, proxy_url: Option<&str>) -> Result<Self> {
+        if let Some(proxy_url) = proxy_url {
+            self.proxy = Some(crate::features::proxy_manager::reqwest_proxy(proxy_url)?);
+            self.proxy_url = Some(proxy_url.to_string());
+            self.client = Self::build_client(self.cookie_jar.clone(), self.proxy.clone());
         }
+        Ok(self)
     }
 
     /// Builder: override the `extract_app_state` default.
@@ -92,6 +372,255 @@ impl RustScraper {
         self
     }
 
+    /// Builder: opt into feed fetching/parsing for pages that advertise a feed.
+    pub fn with_parse_feeds(mut self, val: bool) -> Self {
+        self.parse_feeds = val;
+        self
+    }
+
+    /// Builder: harvest links instead of extracting content.
+    pub fn with_links_only(mut self, val: bool) -> Self {
+        self.links_only = val;
+        self
+    }
+
+    /// Builder: give each extracted link its enclosing block and surrounding sentence.
+    pub fn with_links_detail(mut self, val: bool) -> Self {
+        self.links_detail = val;
+        self
+    }
+
+    /// Builder: keep (default) or drop the raw HTML in `content`.
+    pub fn with_raw_html(mut self, val: bool) -> Self {
+        self.raw_html = val;
+        self
+    }
+
+    /// Builder: save a PNG of browser-rendered pages, optionally of the full page.
+    pub fn with_screenshot(mut self, enabled: bool, full_page: bool) -> Self {
+        self.screenshot = enabled;
+        self.screenshot_full_page = full_page;
+        self
+    }
+
+    /// Builder: replace the env-derived retry policy for transient fetch failures.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Builder: record browser fetches into the server's metrics registry.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Builder: run browser (CDP) fetches through the shared pool.
+    pub fn with_browser_pool(
+        mut self,
+        pool: Option<Arc<crate::scraping::browser_manager::BrowserPool>>,
+    ) -> Self {
+        self.browser_pool = pool;
+        self
+    }
+
+    /// Builder: send HTTP Basic/Bearer credentials with every fetch of this scraper.
+    pub fn with_auth(mut self, auth: Option<&RequestAuth>) -> Result<Self> {
+        self.auth_header = auth.map(RequestAuth::header_value).transpose()?;
+        Ok(self)
+    }
+
+    /// Builder: send these headers and User-Agent instead of the stealth defaults.
+    pub fn with_headers(mut self, headers: Option<&RequestHeaders>) -> Self {
+        self.custom_headers = headers.cloned();
+        self
+    }
+
+    /// The caller's User-Agent, else a random one from the stealth pool.
+    fn user_agent(&self) -> &str {
+        self.custom_headers
+            .as_ref()
+            .and_then(RequestHeaders::user_agent)
+            .unwrap_or_else(|| antibot::get_random_user_agent())
+    }
+
+    /// Device class of the User-Agent browser renders present.
+    pub fn browser_user_agent_class(&self) -> UserAgentClass {
+        self.custom_headers
+            .as_ref()
+            .and_then(RequestHeaders::user_agent)
+            .map_or(UserAgentClass::Desktop, provenance::user_agent_class)
+    }
+
+    /// Builder: replay native fetches from (or record them to) `fixtures`, overriding
+    /// `CORTEX_SCOUT_FIXTURE_DIR`.
+    pub fn with_fixtures(mut self, fixtures: Option<Fixtures>) -> Self {
+        self.fixtures = fixtures;
+        self
+    }
+
+    /// Builder: write debug captures to `captures`, overriding `CORTEX_SCOUT_CAPTURE_DIR`.
+    pub fn with_captures(mut self, captures: Option<Captures>) -> Self {
+        self.captures = captures;
+        self
+    }
+
+    /// Write a debug capture of `result` when captures are enabled. A capture that cannot
+    /// be written is logged; the scrape goes on without it.
+    async fn capture(&self, request: CaptureRequest, html: &str, result: &mut ScrapeResponse) {
+        let Some(captures) = &self.captures else {
+            return;
+        };
+        if let Err(e) = captures.save(request, html, result).await {
+            warn!("Failed to capture {}: {}", result.url, e);
+        }
+    }
+
+    /// The headers a native fetch sends on top of the stealth defaults, for a capture.
+    fn captured_request_headers(&self, user_agent: &str) -> Vec<(String, String)> {
+        let mut headers = vec![("user-agent".to_string(), user_agent.to_string())];
+        if self.auth_header.is_some() {
+            headers.push(("authorization".to_string(), "<redacted>".to_string()));
+        }
+        if let Some(custom) = &self.custom_headers {
+            headers.extend(custom.iter().map(|(n, v)| (n.to_string(), v.to_string())));
+        }
+        headers
+    }
+
+    /// Whether native fetches are served from fixtures, so the network-facing checks
+    /// (domain policy, host pacing, anti-bot delay) are skipped.
+    fn replaying(&self) -> bool {
+        self.fixtures.as_ref().is_some_and(Fixtures::replays)
+    }
+
+    /// A GET for `url` with a random (or the caller's) User-Agent, stealth headers, the
+    /// caller's headers and, when `with_auth`, credentials, if any. Secret caller headers
+    /// are sent only `with_auth` too.
+    fn stealth_request(
+        &self,
+        url: &str,
+        timeout: Option<Duration>,
+        with_auth: bool,
+    ) -> reqwest::RequestBuilder {
+        self.stealth_request_as(url, self.user_agent(), timeout, with_auth)
+    }
+
+    /// [`Self::stealth_request`] presenting `user_agent`.
+    fn stealth_request_as(
+        &self,
+        url: &str,
+        user_agent: &str,
+        timeout: Option<Duration>,
+        with_auth: bool,
+    ) -> reqwest::RequestBuilder {
+        crate::core::http_client::note_request();
+        let mut request_builder = self.client.get(url).header("User-Agent", user_agent);
+        if let Some(timeout) = timeout {
+            request_builder = request_builder.timeout(timeout);
+        }
+        for (header_name, header_value) in antibot::get_stealth_headers() {
+            request_builder = request_builder.header(header_name, header_value);
+        }
+        if let Some(auth) = self.auth_header.as_ref().filter(|_| with_auth) {
+            request_builder = request_builder.header(reqwest::header::AUTHORIZATION, auth.clone());
+        }
+        // `headers` replaces same-named defaults rather than appending to them.
+        if let Some(custom) = &self.custom_headers {
+            request_builder = request_builder.headers(custom.header_map(with_auth));
+        }
+        request_builder
+    }
+
+    /// GET `url` with [`Self::stealth_request`], following redirects and retrying
+    /// transient failures until `deadline`.
+    async fn send_stealth_get(
+        &self,
+        url: &str,
+        timeout: Option<Duration>,
+        deadline: Instant,
+    ) -> (Result<reqwest::Response>, FetchTrail) {
+        self.get_following_redirects(url, deadline, |hop, same_origin| {
+            self.stealth_request(hop, timeout, same_origin)
+        })
+        .await
+    }
+
+    /// GET `url` with requests built by `request`, following up to [`MAX_REDIRECTS`]
+    /// redirects by hand so each hop is recorded and its target checked against the
+    /// domain policy (including private-network resolution) before it is requested.
+    /// `request` is told whether a hop is on the original origin; credentials must not
+    /// follow a redirect to another one.
+    async fn get_following_redirects(
+        &self,
+        url: &str,
+        deadline: Instant,
+        request: impl Fn(&str, bool) -> reqwest::RequestBuilder,
+    ) -> (Result<reqwest::Response>, FetchTrail) {
+        let origin = Url::parse(url).ok().map(|u| u.origin());
+        let mut trail = FetchTrail {
+            attempts: 0,
+            redirect_chain: Vec::new(),
+            final_url: url.to_string(),
+            etag: None,
+            last_modified: None,
+        };
+        loop {
+            let current = trail.final_url.clone();
+            let same_origin = Url::parse(&current).ok().map(|u| u.origin()) == origin;
+            let (response, attempts) = self
+                .send_get(&current, deadline, || request(&current, same_origin))
+                .await;
+            trail.attempts += attempts;
+            let response = match response {
+                Ok(response) => response,
+                Err(e) => return (Err(e), trail),
+            };
+            let Some(next) = redirect_target(&current, &response) else {
+                return (Ok(response), trail);
+            };
+
+            trail.redirect_chain.push(RedirectHop {
+                url: current,
+                status: response.status().as_u16(),
+                client_redirect: None,
+            });
+            if trail.redirect_chain.len() > MAX_REDIRECTS {
+                return (Err(anyhow!("too many redirects from {}", url)), trail);
+            }
+            if !self.replaying() {
+                if let Err(e) = crate::scraping::domain_policy::check(&next).await {
+                    return (Err(e.into()), trail);
+                }
+            }
+            debug!("following redirect to {}", next);
+            trail.final_url = next;
+        }
+    }
+
+    /// One GET of `url`, retried until `deadline`. Returns the response and the attempts
+    /// it took. With fixtures the response is replayed from, or recorded to, the
+    /// fixture directory.
+    async fn send_get(
+        &self,
+        url: &str,
+        deadline: Instant,
+        request: impl Fn() -> reqwest::RequestBuilder,
+    ) -> (Result<reqwest::Response>, u32) {
+        if let Some(fixtures) = self.fixtures.as_ref().filter(|f| f.replays()) {
+            let replayed = fixtures
+                .load(url)
+                .and_then(|recorded| recorded.to_response());
+            return (replayed, 1);
+        }
+        let outcome = self.retry_policy.send(Some(deadline), request).await;
+        let response = match (outcome.result, &self.fixtures) {
+            (Ok(response), Some(fixtures)) => fixtures.record(url, response).await,
+            (result, _) => result.map_err(anyhow::Error::from),
+        };
+        (response, outcome.attempts)
+    }
+
     pub(super) fn is_aggressive_mode(&self) -> bool {
         matches!(self.quality_mode, QualityMode::Aggressive)
     }
@@ -139,27 +668,28 @@ impl RustScraper {
     }
 
     pub async fn preflight_check(&self, url: &str) -> Result<PreflightCheck> {
-        crate::host_guard::wait_for_url_host(url).await;
-
-        let user_agent = antibot::get_random_user_agent();
-        let mut request_builder = self
-            .client
-            .get(url)
-            .header("User-Agent", user_agent)
-            .timeout(Duration::from_secs(5));
-
-        for (header_name, header_value) in antibot::get_stealth_headers() {
-            request_builder = request_builder.header(header_name, header_value);
+        if !self.replaying() {
+            crate::scraping::domain_policy::check(url).await?;
+            crate::host_guard::wait_for_url_host(url).await;
         }
 
-        let response = request_builder
-            .send()
-            .await
-            .map_err(|e| anyhow!("Preflight request failed: {}", e))?;
+        let deadline = Instant::now()
+            + Duration::from_secs(crate::core::config::scrape_stage_timeout_secs(
+                "preflight_check",
+            ));
+        let (response, trail) = self
+            .send_stealth_get(url, Some(Duration::from_secs(5)), deadline)
+            .await;
+        let response = response.map_err(|e| {
+            anyhow!(
+                "Preflight request failed after {} attempt(s): {}",
+                trail.attempts,
+                e
+            )
+        })?;
 
         let status_code = response.status().as_u16();
-        let html = response
-            .text()
+        let html = read_html_capped(response)
             .await
             .map_err(|e| anyhow!("Preflight read failed: {}", e))?;
 
@@ -176,72 +706,357 @@ impl RustScraper {
 
     /// Scrape a URL with enhanced content extraction and anti-bot protection
     pub async fn scrape_url(&self, url: &str) -> Result<ScrapeResponse> {
+        match self.scrape_url_revalidating(url, None).await? {
+            Revalidation::Modified(result) => Ok(*result),
+            Revalidation::NotModified => {
+                Err(anyhow!("{} answered 304 to an unconditional GET", url))
+            }
+        }
+    }
+
+    /// Like [`Self::scrape_url`], but with `If-None-Match` / `If-Modified-Since` taken from
+    /// `cached` (a previous result of this URL). A `304` returns
+    /// [`Revalidation::NotModified`] before the body is read or anything is extracted.
+    /// A fetched page carries the fetch as its `provenance`.
+    pub async fn scrape_url_revalidating(
+        &self,
+        url: &str,
+        cached: Option<&ScrapeResponse>,
+    ) -> Result<Revalidation> {
+        // One User-Agent for every hop and retry, like a browser would present.
+        let user_agent = self.user_agent();
+        let started = Instant::now();
+        let mut outcome = self.fetch_and_extract(url, cached, user_agent).await?;
+        if let Revalidation::Modified(result) = &mut outcome {
+            let attempt = provenance::attempt(
+                ScrapeMethod::Static,
+                provenance::user_agent_class(user_agent),
+                self.proxy_url.as_deref(),
+                started.elapsed(),
+                result.status_code,
+                result.block_reason.as_deref(),
+            );
+            result.provenance.push(attempt);
+        }
+        Ok(outcome)
+    }
+
+    async fn fetch_and_extract(
+        &self,
+        url: &str,
+        cached: Option<&ScrapeResponse>,
+        user_agent: &str,
+    ) -> Result<Revalidation> {
         info!("Scraping URL with Rust-native scraper: {}", url);
 
         // Validate URL
-        let parsed_url = Url::parse(url).map_err(|e| anyhow!("Invalid URL '{}': {}", url, e))?;
+        let parsed_url = Url::parse(url)
+            .map_err(|e| ScoutError::invalid_input(format!("Invalid URL '{}': {}", url, e)))?;
 
         if parsed_url.scheme() != "http" && parsed_url.scheme() != "https" {
-            return Err(anyhow!("URL must use HTTP or HTTPS protocol"));
+            return Err(ScoutError::invalid_input("URL must use HTTP or HTTPS protocol").into());
         }
 
-        crate::host_guard::wait_for_url_host(url).await;
+        if !self.replaying() {
+            crate::scraping::domain_policy::check(url).await?;
+            crate::host_guard::wait_for_url_host(url).await;
 
-        // Apply anti-bot delay before request
-        antibot::apply_request_delay().await;
+            // Apply anti-bot delay before request
+            antibot::apply_request_delay().await;
+        }
 
-        // Make HTTP request with anti-bot protection (random User-Agent + stealth headers)
-        let user_agent = antibot::get_random_user_agent();
-        let mut request_builder = self.client.get(url).header("User-Agent", user_agent);
+        // Make HTTP request with anti-bot protection (random User-Agent + stealth headers),
+        // retrying transient failures within the native scrape stage budget.
+        let deadline = Instant::now()
+            + Duration::from_secs(crate::core::config::scrape_stage_timeout_secs(
+                "native_http_scrape",
+            ));
+        // Pages that redirect in their markup are followed like 3xx redirects, up to
+        // [`client_redirect::MAX_CLIENT_REDIRECTS`] times; each one restarts the fetch below.
+        // The cached validators belong to the page the cache finally extracted, so they are
+        // only sent when that page is the one requested.
+        let origin = parsed_url.origin();
+        let cached = cached.filter(|c| {
+            !c.redirect_chain
+                .iter()
+                .any(|hop| hop.client_redirect.is_some())
+        });
+        let mut fetch_url = url.to_string();
+        let mut client_trail: Option<(u32, Vec<RedirectHop>)> = None;
+        let mut client_redirect_warning = None;
+        let mut response_headers = Vec::new();
+        let (mut trail, parsed_url, status_code, content_type, x_robots_tag, body, decoded) = loop {
+            let first = client_trail.is_none();
+            let (response, mut trail) = self
+                .get_following_redirects(&fetch_url, deadline, |hop, same_origin| {
+                    // Credentials stay with the origin the caller asked for.
+                    let same_origin =
+                        same_origin && Url::parse(hop).is_ok_and(|u| u.origin() == origin);
+                    let request = self.stealth_request_as(hop, user_agent, None, same_origin);
+                    if first {
+                        conditional_request(request, cached)
+                    } else {
+                        request
+                    }
+                })
+                .await;
+            if let Some((attempts, chain)) = client_trail.take() {
+                trail.attempts += attempts;
+                trail.redirect_chain.splice(0..0, chain);
+            }
+            // A redirect refused by the domain policy keeps its `blocked` classification.
+            let response = response.map_err(|e| match e.downcast::<ScoutError>() {
+                Ok(refused) => refused.into(),
+                Err(e) => anyhow!(
+                    "Failed to fetch URL after {} attempt(s): {}",
+                    trail.attempts,
+                    e
+                ),
+            })?;
+            // Content is extracted against the page that was actually served, so relative
+            // links and `domain` follow the redirect; `url` stays the requested URL.
+            let parsed_url = match trail.redirected_to() {
+                Some(final_url) => Url::parse(final_url)?,
+                None => parsed_url.clone(),
+            };
 
-        // Apply stealth headers to avoid bot detection
-        for (header_name, header_value) in antibot::get_stealth_headers() {
-            request_builder = request_builder.header(header_name, header_value);
+            let status_code = response.status().as_u16();
+            if status_code == 304 && cached.is_some() {
+                debug!("{} not modified since the cached fetch", url);
+                return Ok(Revalidation::NotModified);
+            }
+            trail.keep_validators(response.headers());
+            if self.captures.is_some() {
+                response_headers = header_pairs(response.headers());
+            }
+            let content_type = response
+                .headers()
+                .get("content-type")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("text/html")
+                .to_string();
+            let x_robots_tag = response
+                .headers()
+                .get_all("x-robots-tag")
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            // Get response body. Possible PDFs are read as capped bytes and sniffed first so
+            // binary documents never reach the HTML pipeline.
+            let body = if pdf::may_be_pdf(&content_type, &parsed_url) {
+                let body = read_body_capped(
+                    response,
+                    crate::core::config::pdf_max_bytes(),
+                    "CORTEX_SCOUT_PDF_MAX_BYTES",
+                )
+                .await?;
+                if pdf::is_pdf(&content_type, &body) {
+                    let mut result =
+                        self.pdf_response(url, &parsed_url, status_code, content_type, &body)?;
+                    trail.annotate(&mut result);
+                    add_robots_directives(&mut result, &x_robots_tag);
+                    return Ok(Revalidation::Modified(Box::new(result)));
+                }
+                body
+            } else {
+                read_body_capped(
+                    response,
+                    crate::core::config::scrape_max_response_bytes(),
+                    "SCRAPE_MAX_RESPONSE_BYTES",
+                )
+                .await?
+            };
+            let decoded = charset::decode_html(&body, &content_type);
+            debug!(
+                "Decoded {} as {} ({:?})",
+                trail.final_url, decoded.charset, decoded.source
+            );
+
+            let is_html = content_type.to_ascii_lowercase().contains("html");
+            let redirect = (status_code < 400 && is_html)
+                .then(|| client_redirect::detect(&decoded.text, &parsed_url))
+                .flatten();
+            let redirect = match redirect {
+                Some((next, _)) if trail.visited(&next) => {
+                    debug!("{} redirects back to {}", trail.final_url, next);
+                    client_redirect_warning = Some("client_redirect_loop");
+                    None
+                }
+                Some(_) if trail.client_redirects() >= client_redirect::MAX_CLIENT_REDIRECTS => {
+                    client_redirect_warning = Some("client_redirect_limit");
+                    None
+                }
+                redirect => redirect,
+            };
+            let Some((next, kind)) = redirect else {
+                break (
+                    trail,
+                    parsed_url,
+                    status_code,
+                    content_type,
+                    x_robots_tag,
+                    body,
+                    decoded,
+                );
+            };
+            if !self.replaying() {
+                crate::scraping::domain_policy::check(next.as_str()).await?;
+                crate::host_guard::wait_for_url_host(next.as_str()).await;
+            }
+            debug!("following {:?} redirect to {}", kind, next);
+            trail.redirect_chain.push(RedirectHop {
+                url: trail.final_url.clone(),
+                status: status_code,
+                client_redirect: Some(kind),
+            });
+            fetch_url = next.to_string();
+            client_trail = Some((trail.attempts, trail.redirect_chain));
+        };
+        let html = decoded.text;
+
+        // A feed URL is parsed as a feed; if that fails it still goes through the HTML path.
+        // Feeds are parsed from the raw bytes so their XML encoding declaration still applies.
+        let mut feed_warning = None;
+        if feed::is_feed_content_type(&content_type) {
+            match feed::parse_feed(&body) {
+                Ok(parsed) => {
+                    let mut result = self.feed_response(
+                        url,
+                        &parsed_url,
+                        status_code,
+                        content_type,
+                        html,
+                        parsed,
+                    );
+                    trail.annotate(&mut result);
+                    return Ok(Revalidation::Modified(Box::new(result)));
+                }
+                Err(e) => {
+                    tracing::warn!("{} served a feed content type but {}", url, e);
+                    feed_warning = Some("feed_parse_failed");
+                }
+            }
         }
 
-        let response = request_builder
-            .send()
-            .await
-            .map_err(|e| anyhow!("Failed to fetch URL: {}", e))?;
-
-        let status_code = response.status().as_u16();
-        let content_type = response
-            .headers()
-            .get("content-type")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("text/html")
-            .to_string();
+        // Raw GitHub files are already clean text; a 404 still goes through the HTML path
+        // so callers see the status and can fall back.
+        if !self.links_only
+            && status_code < 400
+            && github::is_raw_github_file(&parsed_url, &content_type)
+        {
+            let mut result =
+                self.github_raw_response(url, &parsed_url, status_code, content_type, html);
+            trail.annotate(&mut result);
+            result.charset = Some(decoded.charset.to_string());
+            return Ok(Revalidation::Modified(Box::new(result)));
+        }
 
-        // Get response body
-        let html = response
-            .text()
-            .await
-            .map_err(|e| anyhow!("Failed to read response body: {}", e))?;
+        // JSON and plain-text bodies are already clean; they never reach the HTML parser.
+        if !self.links_only {
+            if let Some(body) = text_formats::detect(&content_type, &parsed_url, &html) {
+                let mut result = self.text_body_response(
+                    url,
+                    &parsed_url,
+                    status_code,
+                    content_type,
+                    html,
+                    body,
+                );
+                trail.annotate(&mut result);
+                add_robots_directives(&mut result, &x_robots_tag);
+                result.charset = Some(decoded.charset.to_string());
+                return Ok(Revalidation::Modified(Box::new(result)));
+            }
+        }
 
-        if let Some(reason) = self.detect_block_reason(&html) {
+        let block_reason = self.detect_block_reason(&html);
+        if let Some(reason) = block_reason {
             crate::host_guard::note_url_host_blocked(url, reason).await;
         }
 
+        let captured_html = self.captures.is_some().then(|| html.clone());
+        let mut result = self.html_response(url, &parsed_url, status_code, content_type, html);
+        trail.annotate(&mut result);
+        add_robots_directives(&mut result, &x_robots_tag);
+        result.charset = Some(decoded.charset.to_string());
+        if decoded.had_errors {
+            result.warnings.push("charset_decode_errors".to_string());
+        }
+        // A long article that merely mentions "captcha" is content, not a block page.
+        // Links-only results have no word count, so a page without links stands in for it.
+        let sparse = if self.links_only {
+            result.links.is_empty()
+        } else {
+            result.word_count < 50
+        };
+        if status_code >= 400 || sparse {
+            result.block_reason = block_reason.map(str::to_string);
+        }
+        if let Some(warning) = feed_warning {
+            result.warnings.push(warning.to_string());
+        }
+        if let Some(warning) = client_redirect_warning {
+            result.warnings.push(warning.to_string());
+        }
+        self.attach_feed_items(&mut result).await;
+        if let Some(html) = captured_html {
+            let mut request = CaptureRequest::new(url, ScrapeMethod::Static, status_code);
+            request.final_url = result.final_url.clone();
+            request.request_headers = self.captured_request_headers(user_agent);
+            request.response_headers = response_headers;
+            self.capture(request, &html, &mut result).await;
+        }
+
+        info!(
+            "Successfully scraped: {} ({} words, score: {:.2})",
+            result.title,
+            result.word_count,
+            result.extraction_score.unwrap_or_default()
+        );
+        Ok(Revalidation::Modified(Box::new(result)))
+    }
+
+    /// Run the HTML extraction pipeline over a fetched body. Kept synchronous so the
+    /// non-`Send` parsed document never lives across an await in `scrape_url`.
+    fn html_response(
+        &self,
+        url: &str,
+        parsed_url: &Url,
+        status_code: u16,
+        content_type: String,
+        html: String,
+    ) -> ScrapeResponse {
+        if self.links_only {
+            return self.links_only_response(url, parsed_url, status_code, content_type, &html);
+        }
+        #[cfg(test)]
+        tests::FULL_EXTRACTIONS.with(|n| n.set(n.get() + 1));
+
         // Parse HTML
         let document = Html::parse_document(&html);
 
         // Extract basic metadata
-        let title = self.extract_title(&document);
-        let meta_description = self.extract_meta_description(&document);
+        let (title, title_source) = self.extract_title(&document, parsed_url);
+        let (meta_description, meta_description_source) = self.extract_meta_description(&document);
         let meta_keywords = self.extract_meta_keywords(&document);
         let language = self.detect_language(&document, &html);
-        let canonical_url = self.extract_canonical(&document, &parsed_url);
+        let canonical_url = self.extract_canonical(&document, parsed_url);
+        let alternate_languages =
+            self.extract_alternate_languages(&document, parsed_url, canonical_url.as_deref());
         let site_name = self.extract_site_name(&document);
-        let (og_title, og_description, og_image) = self.extract_open_graph(&document, &parsed_url);
+        let (og_title, og_description, og_image) = self.extract_open_graph(&document, parsed_url);
         let author = self.extract_author(&document);
         let published_at = self.extract_published_time(&document);
+        let feed_urls = self.extract_feed_links(&document, parsed_url);
 
         // Extract code blocks BEFORE html2text conversion (Priority 1 fix)
         // 🧬 Rule B: infer language from the URL extension so raw source files
         // (e.g. raw.githubusercontent.com/.../*.rs) receive import nuking even
         // though they carry no HTML code-fence class attributes.
-        let url_lang_hint = Self::infer_language_from_url(&parsed_url);
-        let is_tutorial = Self::is_tutorial_url(&parsed_url);
+        let url_lang_hint = Self::infer_language_from_url(parsed_url);
+        let is_tutorial = Self::is_tutorial_url(parsed_url);
         let code_blocks =
             self.extract_code_blocks(&document, url_lang_hint.as_deref(), is_tutorial);
 
@@ -250,12 +1065,23 @@ impl RustScraper {
         // JS-rendered login pages that would otherwise produce empty clean_content.
         let auth_wall_html_reason = self.detect_auth_wall_html(&html, url);
 
+        // The main entity of a framework payload (a Next.js product, an Apollo-cached
+        // article) beats both the whole-state flattening and JSON-LD below.
+        let framework_payloads = framework_state::unwrap(&document);
+        let framework_entity = if self.extract_app_state {
+            None
+        } else {
+            framework_state::main_entity_markdown(&framework_payloads)
+        };
+
         // JSON-LD can be the cleanest source on modern sites; prefer it when present.
         let json_ld_content = self.extract_json_ld(&document);
+        let (structured_data, structured_data_truncated) = self.extract_structured_data(&document);
 
         // ── 🧬 SPA fast-path (before JSON-LD): prefer embedded state blobs when present.
         // Many SPAs include thin JSON-LD that omits the real page content; embedded state is often richer.
-        let spa_state_content = if crate::core::config::neurosiphon_enabled()
+        let spa_state_content = if framework_entity.is_none()
+            && crate::core::config::neurosiphon_enabled()
             && crate::scraping::rust_scraper::clean::looks_like_spa(&html)
         {
             // 🧬 Rule C: only commit to the SPA JSON fast-path when it yields enough
@@ -268,16 +1094,19 @@ impl RustScraper {
         };
 
         // Extract readable content using readability (fallback)
-        let (mut clean_content, noise_reduction_ratio) =
-            if let Some(spa_content) = spa_state_content.as_ref() {
-                (self.normalize_markdown_fragments(spa_content), 0.0)
+        let (mut clean_content, noise_reduction_ratio, consent_nodes_removed) =
+            if let Some(entity) = framework_entity.as_ref() {
+                (entity.clone(), 0.0, 0)
+            } else if let Some(spa_content) = spa_state_content.as_ref() {
+                (self.normalize_markdown_fragments(spa_content), 0.0, 0)
             } else if let Some(json_content) = json_ld_content.as_ref() {
                 (
                     self.normalize_markdown_fragments(&html2md::parse_html(json_content)),
                     0.0,
+                    0,
                 )
             } else {
-                self.extract_clean_content_with_metrics(&html, &parsed_url)
+                self.extract_clean_content_with_metrics(&html, parsed_url)
             };
         clean_content = self.normalize_markdown_fragments(&clean_content);
         clean_content = self.apply_og_description_fallback(clean_content, &og_description);
@@ -286,8 +1115,8 @@ impl RustScraper {
         // Extract structured data
         let headings = self.extract_headings(&document);
         // Smart link extraction: prefer content links over all document links
-        let links = self.extract_content_links(&document, &parsed_url);
-        let images = self.extract_images(&document, &parsed_url);
+        let links = self.extract_content_links(&document, parsed_url);
+        let images = self.extract_images(&document, parsed_url);
 
         // 🧬 Task 3: When extract_app_state=true and SPA hydration JSON was found, discard
         // all DOM-derived content (code_blocks, links, images, headings).  The hydration
@@ -298,12 +1127,17 @@ impl RustScraper {
         let images = if spa_forced { vec![] } else { images };
         let headings = if spa_forced { vec![] } else { headings };
 
-        let mut embedded_data_sources = self.collect_embedded_data_sources(&document);
-        let mut embedded_state_json = embedded_data_sources
+        let raw_data_sources = self.collect_embedded_data_sources(&document);
+        let mut embedded_state_json = raw_data_sources
             .iter()
             .max_by_key(|s| s.content.len())
             .map(|s| s.content.clone())
             .or_else(|| self.extract_embedded_state_json(&document));
+        let mut embedded_data_sources: Vec<_> = framework_payloads
+            .iter()
+            .map(framework_state::FrameworkPayload::to_source)
+            .chain(raw_data_sources)
+            .collect();
         let mut warnings = Vec::new();
         const MAX_STATE_JSON_CHARS: usize = 200_000;
         for src in embedded_data_sources.iter_mut() {
@@ -318,11 +1152,18 @@ impl RustScraper {
                 warnings.push("embedded_state_json_truncated".to_string());
             }
         }
+        if structured_data_truncated {
+            warnings.push("structured_data_truncated".to_string());
+        }
+        if consent_nodes_removed > consent::CONSENT_REMOVAL_WARN_THRESHOLD {
+            warnings.push("consent_markup_stripped".to_string());
+        }
 
         let hydration_status = crate::types::HydrationStatus {
             json_found: !embedded_data_sources.is_empty() || embedded_state_json.is_some(),
             settle_time_ms: None,
             noise_reduction_ratio,
+            consent_nodes_removed,
         };
 
         // 🔒 Auth-Wall Guard Dog (merged): HTML-level DOM detection + clean-text keyword detection.
@@ -332,7 +1173,8 @@ impl RustScraper {
         // Content-length gate: pages with substantial content (>100 words) that have a password
         // form in the nav/header are NOT auth-walled — the content is publicly accessible.
         // Downgrade to advisory warning only; the handler will prepend a note rather than block.
-        let preliminary_word_count = self.count_words(&clean_content);
+        let preliminary_word_count =
+            word_equivalents(self.count_words_in(&clean_content, &language), &language);
         let detected_auth_reason =
             auth_wall_html_reason.or_else(|| self.detect_auth_wall(&clean_content, url));
         let auth_wall_reason = if preliminary_word_count > 100 {
@@ -359,20 +1201,25 @@ impl RustScraper {
         };
 
         clean_content = self.append_image_context_markdown(clean_content, &images, &title);
-        let word_count = self.count_words(&clean_content);
-        let reading_time_minutes = Some(((word_count as f64 / 200.0).ceil() as u32).max(1));
+        let word_count = self.count_words_in(&clean_content, &language);
+        let reading_time_minutes = Some(reading_time_minutes(word_count, &language));
 
         // Calculate extraction quality score (Priority 1 fix)
-        let extraction_score =
-            self.calculate_extraction_score(word_count, &published_at, &code_blocks, &headings);
+        let extraction_score = self.calculate_extraction_score(
+            word_equivalents(word_count, &language),
+            &published_at,
+            &code_blocks,
+            &headings,
+        );
 
         // Extract domain from URL (Priority 2 enhancement)
         let domain = parsed_url.host_str().map(|h| h.to_string());
 
-        let result = ScrapeResponse {
+        let html_bytes = html.len();
+        let mut page = ScrapeResponse {
             url: url.to_string(),
             title,
-            content: html,
+            content: if self.raw_html { html } else { String::new() },
             clean_content,
             embedded_state_json,
             embedded_data_sources,
@@ -407,14 +1254,113 @@ impl RustScraper {
             auth_risk_score,
             detection_factors,
             final_url: None,
+            redirect_chain: Vec::new(),
             metrics: None,
+            content_format: None,
+            page_count: None,
+            feed_urls,
+            feed_items: Vec::new(),
+            fetch_attempts: None,
+            block_reason: None,
+            asset_manifest: None,
+            charset: None,
+            etag: None,
+            last_modified: None,
+            revalidated: false,
+            structured_data,
+            screenshot: None,
+            robots_directives: self.extract_robots_directives(&document),
+            selector_results: None,
+            alternate_languages,
+            provenance: Vec::new(),
+            sections: Vec::new(),
+            title_source,
+            meta_description_source,
+            quality: None,
         };
+        page.quality = Some(self.quality_signals(&page, html_bytes));
+        page
+    }
 
-        info!(
-            "Successfully scraped: {} ({} words, score: {:.2})",
-            result.title, result.word_count, extraction_score
-        );
-        Ok(result)
+    /// The `links_only` subset of [`Self::html_response`]: the frontier a crawler needs
+    /// (links, plus images and headings for site maps) without readability, JSON-LD, code
+    /// block or embedded state extraction. `clean_content` stays empty and the raw HTML is
+    /// dropped; a `links_only` warning marks the result.
+    fn links_only_response(
+        &self,
+        url: &str,
+        parsed_url: &Url,
+        status_code: u16,
+        content_type: String,
+        html: &str,
+    ) -> ScrapeResponse {
+        let document = Html::parse_document(html);
+        let canonical_url = self.extract_canonical(&document, parsed_url);
+        let alternate_languages =
+            self.extract_alternate_languages(&document, parsed_url, canonical_url.as_deref());
+        let (title, title_source) = self.extract_title(&document, parsed_url);
+
+        ScrapeResponse {
+            url: url.to_string(),
+            title,
+            content: String::new(),
+            clean_content: String::new(),
+            embedded_state_json: None,
+            embedded_data_sources: Vec::new(),
+            hydration_status: crate::types::HydrationStatus::default(),
+            meta_description: String::new(),
+            meta_keywords: String::new(),
+            headings: self.extract_headings(&document),
+            links: self.extract_content_links(&document, parsed_url),
+            images: self.extract_images(&document, parsed_url),
+            timestamp: Utc::now().to_rfc3339(),
+            status_code,
+            content_type,
+            word_count: 0,
+            language: String::new(),
+            canonical_url,
+            site_name: None,
+            author: None,
+            published_at: None,
+            og_title: None,
+            og_description: None,
+            og_image: None,
+            reading_time_minutes: None,
+            code_blocks: Vec::new(),
+            truncated: false,
+            actual_chars: 0,
+            max_chars_limit: None,
+            extraction_score: None,
+            warnings: vec!["links_only".to_string()],
+            domain: parsed_url.host_str().map(|h| h.to_string()),
+            auth_wall_reason: None,
+            auth_risk_score: None,
+            detection_factors: Vec::new(),
+            final_url: None,
+            redirect_chain: Vec::new(),
+            metrics: None,
+            content_format: None,
+            page_count: None,
+            feed_urls: self.extract_feed_links(&document, parsed_url),
+            feed_items: Vec::new(),
+            fetch_attempts: None,
+            block_reason: None,
+            asset_manifest: None,
+            charset: None,
+            etag: None,
+            last_modified: None,
+            revalidated: false,
+            structured_data: Vec::new(),
+            screenshot: None,
+            robots_directives: self.extract_robots_directives(&document),
+            selector_results: None,
+            alternate_languages,
+            provenance: Vec::new(),
+            sections: Vec::new(),
+            title_source,
+            meta_description_source: None,
+            quality: None,
+        }
     }
 
     /// Scrape a URL using the native headless browser (chromiumoxide) for JS-heavy sites.
@@ -432,6 +1378,7 @@ impl RustScraper {
     ) -> Result<(u16, String)> {
         use crate::scraping::browser_manager;
 
+        crate::scraping::domain_policy::check(url).await?;
         crate::host_guard::wait_for_url_host(url).await;
         antibot::apply_request_delay().await;
 
@@ -498,9 +1445,19 @@ impl RustScraper {
         );
 
         // Use the full stealth CDP path (scroll simulation, mouse, stealth injection)
-        let (html, _status) = self.fetch_via_cdp(url, proxy_url).await?;
+        let started = Instant::now();
+        let (html, status) = self.fetch_via_cdp(url, proxy_url.clone()).await?;
         let mut result = self.process_html(&html, url).await?;
         result.warnings.push("native_cdp_rendered".to_string());
+        // Blocked renders fail in `fetch_via_cdp`, so a page here has no block reason.
+        result.provenance.push(provenance::attempt(
+            ScrapeMethod::Cdp,
+            self.browser_user_agent_class(),
+            proxy_url.as_deref(),
+            started.elapsed(),
+            status,
+            None,
+        ));
         Ok(result)
     }
 }
@@ -567,29 +1524,181 @@ impl Default for RustScraper {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        /// Full extraction pipeline runs on this test thread.
+        pub(super) static FULL_EXTRACTIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn fixture_scraper() -> RustScraper {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/http");
+        RustScraper::new().with_fixtures(Some(Fixtures::new(
+            dir,
+            crate::core::fixtures::FixtureMode::Replay,
+        )))
+    }
 
     #[tokio::test]
     async fn test_rust_scraper() {
-        let scraper = RustScraper::new();
+        let scraper = fixture_scraper();
 
-        // Test with a simple HTML page
-        match scraper.scrape_url("https://httpbin.org/html").await {
-            Ok(content) => {
-                assert!(!content.title.is_empty(), "Title should not be empty");
-                assert!(
-                    !content.clean_content.is_empty(),
-                    "Content should not be empty"
-                );
-                assert_eq!(content.status_code, 200, "Status code should be 200");
-                assert!(
-                    content.word_count > 0,
-                    "Word count should be greater than 0"
-                );
-            }
-            Err(e) => {
-                tracing::warn!("Rust scraper test failed: {}", e);
-            }
-        }
+        let content = scraper
+            .scrape_url("https://httpbin.org/html")
+            .await
+            .unwrap();
+        assert_eq!(content.title, "Herman Melville - Moby-Dick");
+        assert!(content
+            .clean_content
+            .starts_with("Availing himself of the mild, summer-cool weather"));
+        assert_eq!(content.status_code, 200);
+        assert_eq!(content.word_count, 251);
+        assert_eq!(content.fetch_attempts, Some(1));
+        let [attempt] = content.provenance.as_slice() else {
+            panic!("one attempt expected: {:?}", content.provenance);
+        };
+        assert_eq!(attempt.method, ScrapeMethod::Static);
+        assert_eq!(attempt.status_code, Some(200));
+        assert!(attempt.selected && attempt.proxy.is_none());
+        assert_eq!(content.charset.as_deref(), Some("UTF-8"));
+    }
+
+    #[tokio::test]
+    async fn fixture_article_metadata_is_extracted() {
+        let scraper = fixture_scraper();
+        let page = scraper
+            .scrape_url("https://blog.example.com/posts/async-runtimes?utm_source=feed")
+            .await
+            .unwrap();
+        assert_eq!(
+            page.title,
+            "Choosing an async runtime for Rust | Example Engineering Blog"
+        );
+        assert_eq!(
+            page.meta_description,
+            "A practical comparison of tokio, smol and glommio for network services."
+        );
+        assert_eq!(
+            page.canonical_url.as_deref(),
+            Some("https://blog.example.com/posts/async-runtimes")
+        );
+        assert_eq!(page.site_name.as_deref(), Some("Example Engineering"));
+        assert_eq!(page.author.as_deref(), Some("Dana Okafor"));
+        assert_eq!(page.published_at.as_deref(), Some("2025-03-05T09:30:00Z"));
+        assert_eq!(
+            page.og_image.as_deref(),
+            Some("https://blog.example.com/img/runtimes-cover.png")
+        );
+        assert_eq!(page.language, "en");
+        assert_eq!(page.headings.len(), 4);
+        assert_eq!(page.code_blocks.len(), 1);
+        assert_eq!(page.code_blocks[0].language.as_deref(), Some("rust"));
+        assert!(page
+            .links
+            .iter()
+            .any(|l| l.url == "https://github.com/smol-rs/smol" && l.nofollow));
+        assert_eq!(page.robots_directives, vec!["max-snippet:120", "noarchive"]);
+        assert!(!page.clean_content.contains("Home"));
+
+        let missing = scraper
+            .scrape_url("https://blog.example.com/unrecorded")
+            .await
+            .unwrap_err();
+        assert!(missing
+            .to_string()
+            .contains("no fixture for https://blog.example.com/unrecorded"));
+    }
+
+    #[tokio::test]
+    async fn redirects_are_followed_and_recorded() {
+        let scraper = fixture_scraper();
+        let page = scraper.scrape_url("https://bit.ly/async-rt").await.unwrap();
+        assert_eq!(page.url, "https://bit.ly/async-rt");
+        assert_eq!(
+            page.final_url.as_deref(),
+            Some("https://medium.com/@dana/async-runtimes-4f2a9c")
+        );
+        assert_eq!(
+            page.redirect_chain,
+            vec![
+                RedirectHop {
+                    url: "https://bit.ly/async-rt".to_string(),
+                    status: 301,
+                    client_redirect: None,
+                },
+                RedirectHop {
+                    url: "https://medium.com/p/4f2a9c".to_string(),
+                    status: 302,
+                    client_redirect: None,
+                },
+            ]
+        );
+        assert_eq!(page.status_code, 200);
+        assert_eq!(page.fetch_attempts, Some(3));
+        assert_eq!(page.domain.as_deref(), Some("medium.com"));
+        assert!(page
+            .links
+            .iter()
+            .any(|l| l.url == "https://medium.com/@dana/benchmarks"));
+
+        let direct = scraper
+            .scrape_url("https://httpbin.org/html")
+            .await
+            .unwrap();
+        assert!(direct.final_url.is_none());
+        assert!(direct.redirect_chain.is_empty());
+
+        let looped = scraper
+            .scrape_url("https://loop.example.com/start")
+            .await
+            .unwrap_err();
+        assert!(looped.to_string().contains("too many redirects"));
+    }
+
+    #[tokio::test]
+    async fn in_page_redirects_are_followed_and_recorded() {
+        let scraper = fixture_scraper();
+        let page = scraper
+            .scrape_url("https://go.example.com/docs")
+            .await
+            .unwrap();
+        assert_eq!(
+            page.final_url.as_deref(),
+            Some("https://docs.example.com/guide")
+        );
+        assert_eq!(
+            page.redirect_chain,
+            vec![
+                RedirectHop {
+                    url: "https://go.example.com/docs".to_string(),
+                    status: 200,
+                    client_redirect: Some(ClientRedirectKind::MetaRefresh),
+                },
+                RedirectHop {
+                    url: "https://go.example.com/landing".to_string(),
+                    status: 200,
+                    client_redirect: Some(ClientRedirectKind::Script),
+                },
+            ]
+        );
+        assert_eq!(page.fetch_attempts, Some(3));
+        assert_eq!(page.title, "Getting started | Example Docs");
+        assert!(page
+            .warnings
+            .contains(&"client_redirect_followed".to_string()));
+
+        let looped = scraper
+            .scrape_url("https://loop.example.com/a")
+            .await
+            .unwrap();
+        assert_eq!(
+            looped.final_url.as_deref(),
+            Some("https://loop.example.com/b")
+        );
+        assert_eq!(looped.redirect_chain.len(), 1);
+        assert!(looped
+            .warnings
+            .contains(&"client_redirect_loop".to_string()));
     }
 
     #[test]
@@ -606,4 +1715,754 @@ mod tests {
         let text = "This is a test with five words";
         assert_eq!(scraper.count_words(text), 7);
     }
+
+    #[test]
+    fn json_ld_is_kept_as_typed_structured_data() {
+        let html = r#"<html><head><title>Widget</title>
+            <script type="application/ld+json">{"@context": "https://schema.org", "@graph": [
+                {"@type": "WebPage", "name": "Widget page"},
+                {"@type": "Product", "name": "Widget", "description": "A sturdy widget.",
+                 "offers": {"@type": "Offer", "price": "19.99", "priceCurrency": "USD"}}
+            ]}</script>
+            <script type="application/ld+json">{"@type": "BreadcrumbList", "itemListElement": []}</script>
+            </head><body><p>Widget</p></body></html>"#;
+        let url = Url::parse("https://shop.example.com/widget").unwrap();
+        let scraper = RustScraper::new();
+        let page = scraper.html_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/html".to_string(),
+            html.to_string(),
+        );
+
+        assert_eq!(page.structured_data.len(), 1);
+        assert_eq!(page.structured_data[0]["@type"], "Product");
+        assert_eq!(page.structured_data[0]["offers"]["price"], "19.99");
+        assert!(page.clean_content.contains("Price: 19.99"));
+
+        let body = "x".repeat(40 * 1024);
+        let html = format!(
+            r#"<script type="application/ld+json">[
+                {{"@type": "Article", "headline": "One", "articleBody": "{body}"}},
+                {{"@type": "Article", "headline": "Two", "articleBody": "{body}"}},
+                {{"@type": "Organization", "name": "Example Co"}}
+            ]</script>"#
+        );
+        let (data, truncated) = scraper.extract_structured_data(&Html::parse_document(&html));
+        assert!(truncated);
+        let kept: Vec<_> = data.iter().map(|v| v["@type"].as_str().unwrap()).collect();
+        assert_eq!(kept, ["Article", "Organization"]);
+    }
+
+    fn title_and_description(url: &str, head: &str, body: &str) -> [(String, Option<String>); 2] {
+        let url = Url::parse(url).unwrap();
+        let page = RustScraper::new().html_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/html".to_string(),
+            format!("<html><head>{head}</head><body>{body}</body></html>"),
+        );
+        [
+            (page.title, page.title_source),
+            (page.meta_description, page.meta_description_source),
+        ]
+    }
+
+    #[test]
+    fn title_fallbacks_follow_priority_order() {
+        let url = "https://news.example.jp/articles/%E6%96%B0%E5%B9%B4-special-report.html";
+        let levels = [
+            "<title>\u{200B}&nbsp;</title>",
+            r#"<meta property="og:title" content="東京の新年特集">"#,
+            r#"<meta name="twitter:title" content="Twitter title">"#,
+            r#"<h1><img src="/logo.png" alt="logo"></h1><h2>Section heading</h2>"#,
+            r#"<script type="application/ld+json">{"@graph": [{"@type": "NewsArticle", "headline": "JSON-LD headline"}]}</script>"#,
+        ];
+        let expected = [
+            ("東京の新年特集", Some("og:title")),
+            ("Twitter title", Some("twitter:title")),
+            ("Section heading", Some("heading")),
+            ("JSON-LD headline", Some("json_ld:headline")),
+            ("新年 special report", Some("url")),
+        ];
+        for (i, (title, source)) in expected.into_iter().enumerate() {
+            let head = levels[..1]
+                .iter()
+                .chain(&levels[i + 1..])
+                .copied()
+                .collect::<String>();
+            let [got, _] = title_and_description(url, &head, "");
+            assert_eq!(
+                got,
+                (title.to_string(), source.map(str::to_string)),
+                "level {}",
+                i
+            );
+        }
+
+        let [got, _] =
+            title_and_description("https://example.com/posts/48213", "<title>Real</title>", "");
+        assert_eq!(got, ("Real".to_string(), None));
+        let [got, _] = title_and_description("https://example.com/posts/48213", "", "");
+        assert_eq!(got, ("Posts".to_string(), Some("url".to_string())));
+        let [got, _] = title_and_description("https://example.com/", "", "");
+        assert_eq!(got, ("example.com".to_string(), Some("url".to_string())));
+    }
+
+    #[test]
+    fn description_fallbacks_follow_priority_order() {
+        let paragraph = "ข่าวเศรษฐกิจวันนี้ ตลาดหุ้นไทยปิดบวกเล็กน้อยจากแรงซื้อกลุ่มพลังงานและธนาคาร นักลงทุนรอติดตามการประชุมคณะกรรมการนโยบายการเงิน";
+        let body = format!(
+            "<nav><p>{}</p></nav><p>Short intro.</p><p>{}</p>",
+            "Home News Markets Sport Weather Video Live Login Register Subscribe Newsletter Contact",
+            paragraph
+        );
+        let levels = [
+            r#"<meta name="description" content="  ">"#,
+            r#"<meta property="og:description" content="OG description">"#,
+            r#"<meta name="twitter:description" content="Twitter description">"#,
+            r#"<script type="application/ld+json">{"@type": "Article", "description": "JSON-LD description"}</script>"#,
+        ];
+        let expected = [
+            ("OG description", Some("og:description")),
+            ("Twitter description", Some("twitter:description")),
+            ("JSON-LD description", Some("json_ld:description")),
+            (paragraph, Some("paragraph")),
+        ];
+        let url = "https://th.example.com/news/1";
+        for (i, (description, source)) in expected.into_iter().enumerate() {
+            let head = levels[..1]
+                .iter()
+                .chain(&levels[i + 1..])
+                .copied()
+                .collect::<String>();
+            let [_, got] = title_and_description(url, &head, &body);
+            assert_eq!(
+                got,
+                (description.to_string(), source.map(str::to_string)),
+                "level {}",
+                i
+            );
+        }
+
+        let [_, got] =
+            title_and_description(url, r#"<meta name="description" content="Real">"#, "");
+        assert_eq!(got, ("Real".to_string(), None));
+        let [_, got] = title_and_description(url, "", "<p>Too short to describe anything.</p>");
+        assert_eq!(got, (String::new(), None));
+        let long = "word ".repeat(100);
+        let [_, (description, _)] = title_and_description(url, "", &format!("<p>{}</p>", long));
+        assert!(description.ends_with("word…"), "{}", description);
+        assert!(description.chars().count() <= 301);
+    }
+
+    #[test]
+    fn hreflang_alternates_resolve_against_the_canonical_url() {
+        let html = r#"<html lang="en"><head>
+            <link rel="canonical" href="https://www.example.com/en/page">
+            <link rel="alternate" hreflang="de" href="../de/page">
+            <link rel="alternate" hreflang="ja" href="/ja/page">
+            <link rel="alternate" hreflang="x-default" href="https://www.example.com/page">
+            <link rel="alternate" hreflang="de" href="../de/page">
+            <link rel="alternate" hreflang="<script>" href="/evil">
+            <link rel="alternate" hreflang="" href="/empty">
+            <link rel="alternate" hreflang="fr" href="javascript:alert(1)">
+            <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+            </head><body><p>Hello</p></body></html>"#;
+        let url = Url::parse("https://example.com/en/page?utm=1").unwrap();
+        let page = RustScraper::new().with_links_only(true).html_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/html".to_string(),
+            html.to_string(),
+        );
+
+        let alternates: Vec<_> = page
+            .alternate_languages
+            .iter()
+            .map(|a| (a.lang.as_str(), a.url.as_str()))
+            .collect();
+        assert_eq!(
+            alternates,
+            [
+                ("de", "https://www.example.com/de/page"),
+                ("ja", "https://www.example.com/ja/page"),
+                ("x-default", "https://www.example.com/page"),
+            ]
+        );
+    }
+
+    #[test]
+    fn links_detail_reports_block_chrome_and_sentence() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/links/citations.html"
+        ));
+        let url = Url::parse("https://example.com/sleep").unwrap();
+        let page = RustScraper::new()
+            .with_links_only(true)
+            .with_links_detail(true)
+            .html_response(
+                url.as_str(),
+                &url,
+                200,
+                "text/html".to_string(),
+                html.to_string(),
+            );
+        let detail = |href: &str| {
+            let link = page.links.iter().find(|l| l.url == href).expect(href);
+            (link.nofollow, link.detail.clone().expect("detail"))
+        };
+
+        // The <article> holds enough links, so the site nav is left out.
+        assert!(!page
+            .links
+            .iter()
+            .any(|l| l.url == "https://example.com/health"));
+        let (_, study) = detail("https://journals.example.org/sleep-1924");
+        assert_eq!(study.block, "p");
+        assert!(!study.in_chrome);
+        assert_eq!(
+            study.context,
+            "The idea goes back to the original study by Jenkins and Dallenbach, who tested two students over several weeks."
+        );
+
+        let (_, meta) = detail("https://review.example.net/meta");
+        assert_eq!(
+            (meta.block.as_str(), meta.context.as_str()),
+            ("li", "A 2019 meta-analysis pooled 41 trials.")
+        );
+        let (nofollow, dataset) = detail("https://data.example.com/sleep");
+        assert!(nofollow && dataset.ugc && !dataset.sponsored);
+
+        let (_, trial) = detail("https://trials.example.org/nct0042");
+        assert_eq!(
+            (trial.block.as_str(), trial.context.as_str()),
+            ("td", "NCT0042")
+        );
+        let (_, lab) = detail("https://lab.example.edu/recall");
+        assert_eq!(
+            (lab.block.as_str(), lab.context.as_str()),
+            ("figcaption", "Data from the recall lab.")
+        );
+
+        for href in [
+            "https://shop.example.com/pillow",
+            "https://example.com/privacy",
+        ] {
+            let (_, footer) = detail(href);
+            assert_eq!(footer.block, "footer");
+            assert!(footer.in_chrome, "{}", href);
+        }
+        assert!(detail("https://shop.example.com/pillow").1.sponsored);
+
+        // Without the option links keep their lightweight shape.
+        let plain = RustScraper::new().with_links_only(true).html_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/html".to_string(),
+            html.to_string(),
+        );
+        assert!(plain.links.iter().all(|l| l.detail.is_none()));
+    }
+
+    #[test]
+    fn link_context_is_cut_around_the_link() {
+        let filler = "word ".repeat(60);
+        let text = format!(
+            "Intro. {}see the original study here {}end. Outro.",
+            filler, filler
+        );
+        let start = text.find("the original study").unwrap();
+        let end = start + "the original study".len();
+        let context = parse::sentence_around(&text, start, end, parse::LINK_CONTEXT_MAX_CHARS);
+        assert!(context.chars().count() <= parse::LINK_CONTEXT_MAX_CHARS);
+        assert!(
+            context.contains("see the original study here"),
+            "{}",
+            context
+        );
+        assert!(!context.contains("Intro") && !context.contains("Outro"));
+
+        assert_eq!(
+            parse::sentence_around("First one. Read it! Last one.", 11, 18, 140),
+            "Read it!"
+        );
+    }
+
+    #[test]
+    fn links_only_skips_content_extraction() {
+        let html = r#"<html><head><title>Docs index</title>
+            <link rel="canonical" href="/docs/">
+            <script type="application/ld+json">{"@type": "Article", "headline": "Docs"}</script>
+            </head><body><main>
+            <h1>Guides</h1>
+            <a href="/docs/install">Install</a> <a href="/docs/config">Configure</a>
+            <a href="https://other.example.org/">Elsewhere</a>
+            <img src="/logo.png" alt="Logo">
+            <pre><code class="language-rust">fn main() {}</code></pre>
+            </main></body></html>"#;
+        let url = Url::parse("https://example.com/docs/index.html").unwrap();
+        let scraper = RustScraper::new().with_links_only(true);
+        FULL_EXTRACTIONS.with(|n| n.set(0));
+
+        let page = scraper.html_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/html".to_string(),
+            html.to_string(),
+        );
+
+        assert_eq!(FULL_EXTRACTIONS.with(Cell::get), 0);
+        assert_eq!(page.title, "Docs index");
+        assert_eq!(
+            page.canonical_url.as_deref(),
+            Some("https://example.com/docs/")
+        );
+        let links: Vec<_> = page.links.iter().map(|l| l.url.as_str()).collect();
+        assert_eq!(
+            links,
+            [
+                "https://example.com/docs/install",
+                "https://example.com/docs/config",
+                "https://other.example.org/"
+            ]
+        );
+        assert_eq!(page.images[0].src, "https://example.com/logo.png");
+        assert_eq!(page.headings[0].text, "Guides");
+        assert!(page.clean_content.is_empty());
+        assert!(page.code_blocks.is_empty() && page.structured_data.is_empty());
+        assert_eq!(page.warnings, ["links_only"]);
+
+        let full = RustScraper::new().html_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/html".to_string(),
+            html.to_string(),
+        );
+        assert_eq!(FULL_EXTRACTIONS.with(Cell::get), 1);
+        assert_eq!(full.links.len(), page.links.len());
+    }
+
+    #[test]
+    fn robots_meta_and_header_directives_are_recorded() {
+        let html = r#"<html><head><title>Archive</title>
+            <meta name="ROBOTS" content="NoIndex, follow">
+            <meta name="googlebot" content="nofollow">
+            </head><body><main><p>Old post.</p>
+            <a href="/a" rel="nofollow ugc">Sponsored</a> <a href="/b">Next</a>
+            </main></body></html>"#;
+        let url = Url::parse("https://example.com/archive").unwrap();
+        let mut page = RustScraper::new().html_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/html".to_string(),
+            html.to_string(),
+        );
+        assert_eq!(page.robots_directives, ["noindex", "follow"]);
+        let nofollow: Vec<_> = page.links.iter().map(|l| l.nofollow).collect();
+        assert_eq!(nofollow, [true, false]);
+
+        add_robots_directives(&mut page, "googlebot: noarchive, none, max-snippet: 20");
+        assert_eq!(
+            page.robots_directives,
+            ["noindex", "follow", "nofollow", "max-snippet: 20"]
+        );
+    }
+
+    #[derive(Clone, Default)]
+    struct LogBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for LogBuffer {
+        type Writer = Self;
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    /// A local HTTP proxy answering one request with `response`; yields the lowercased
+    /// request and the listener. Private targets are refused by default, so tests reach it
+    /// as a proxy for an unresolvable `.invalid` host instead of by its loopback address.
+    fn proxy_once(
+        response: String,
+    ) -> (
+        String,
+        std::thread::JoinHandle<(String, std::net::TcpListener)>,
+    ) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            (
+                String::from_utf8_lossy(&request).to_ascii_lowercase(),
+                listener,
+            )
+        });
+        (proxy_url, server)
+    }
+
+    #[tokio::test]
+    async fn native_fetches_go_through_the_injected_client() {
+        let url = "http://wiki.invalid/wiki/page";
+        let body = "<html><head><title>Wiki</title></head><body><p>Page.</p></body></html>";
+        let (proxy_url, server) = proxy_once(format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        ));
+
+        let mut marker = reqwest::header::HeaderMap::new();
+        marker.insert("x-injected-client", HeaderValue::from_static("yes"));
+        let client = crate::core::http_client::scrape_builder()
+            .proxy(reqwest::Proxy::http(&proxy_url).unwrap())
+            .default_headers(marker)
+            .build()
+            .unwrap();
+        let scraper = RustScraper::new()
+            .with_retry_policy(RetryPolicy::from_env().with_max_attempts(1))
+            .with_client(client);
+        let result = scraper.scrape_url(url).await.unwrap();
+        assert_eq!(result.status_code, 200);
+
+        let (request, _) = server.join().unwrap();
+        assert!(request.contains("x-injected-client: yes"), "{}", request);
+    }
+
+    #[tokio::test]
+    async fn auth_header_is_sent_but_never_logged() {
+        let secret = "s3cret-token-value";
+        let url = "http://wiki.invalid/wiki/page";
+        let body =
+            "<html><head><title>Wiki</title></head><body><p>Internal wiki page.</p></body></html>";
+        let (proxy_url, server) = proxy_once(format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        ));
+
+        let logs = LogBuffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(logs.clone())
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let auth = RequestAuth::Bearer {
+            token: secret.to_string(),
+        };
+        let scraper = RustScraper::new()
+            .with_retry_policy(RetryPolicy::from_env().with_max_attempts(1))
+            .with_proxy(Some(&proxy_url))
+            .unwrap()
+            .with_auth(Some(&auth))
+            .unwrap();
+        let result = scraper.scrape_url(url).await.unwrap();
+        assert_eq!(result.status_code, 200);
+
+        let (request, _) = server.join().unwrap();
+        assert!(request.contains(&format!("authorization: bearer {}", secret)));
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(
+            logs.contains("/wiki/page"),
+            "expected URL log lines: {}",
+            logs
+        );
+        assert!(!logs.contains(secret));
+    }
+
+    #[tokio::test]
+    async fn redirect_into_private_network_is_refused_before_following() {
+        for target in [
+            "http://2130706433:6379/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://[::1]:8080/admin",
+        ] {
+            let (proxy_url, server) = proxy_once(format!(
+                "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                target
+            ));
+            let scraper = RustScraper::new()
+                .with_retry_policy(RetryPolicy::from_env().with_max_attempts(1))
+                .with_proxy(Some(&proxy_url))
+                .unwrap();
+            let err = scraper
+                .scrape_url("http://public.invalid/start")
+                .await
+                .unwrap_err();
+
+            let refused = ScoutError::classify(&err);
+            assert_eq!(
+                refused.kind,
+                crate::core::error::ErrorKind::Blocked,
+                "{target}"
+            );
+            let details = refused.details.expect("domain policy details");
+            assert_eq!(details["reason"], "domain_policy");
+            assert!(
+                details["rule"]
+                    .as_str()
+                    .is_some_and(|rule| rule.starts_with("private_network:")),
+                "{target}: {details}"
+            );
+
+            // The hop was refused without a second request reaching the proxy.
+            let (_, listener) = server.join().unwrap();
+            listener.set_nonblocking(true).unwrap();
+            assert!(listener.accept().is_err(), "{target}");
+        }
+    }
+
+    #[tokio::test]
+    async fn not_modified_skips_extraction_and_keeps_the_cached_result() {
+        let url = "http://docs.invalid/guide";
+        let body = "<html><head><title>Guide</title></head><body><p>Setup steps.</p></body></html>";
+        let (proxy_url, server) = proxy_once(format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nETag: \"v1\"\r\nLast-Modified: Tue, 13 Oct 2026 08:00:00 GMT\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        ));
+        let scraper = RustScraper::new()
+            .with_retry_policy(RetryPolicy::from_env().with_max_attempts(1))
+            .with_proxy(Some(&proxy_url))
+            .unwrap();
+        let cached = scraper.scrape_url(url).await.unwrap();
+        server.join().unwrap();
+        assert_eq!(cached.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(
+            cached.last_modified.as_deref(),
+            Some("Tue, 13 Oct 2026 08:00:00 GMT")
+        );
+
+        let (proxy_url, server) = proxy_once(
+            "HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n".to_string(),
+        );
+        let scraper = RustScraper::new()
+            .with_retry_policy(RetryPolicy::from_env().with_max_attempts(1))
+            .with_proxy(Some(&proxy_url))
+            .unwrap();
+        FULL_EXTRACTIONS.with(|n| n.set(0));
+        let outcome = scraper
+            .scrape_url_revalidating(url, Some(&cached))
+            .await
+            .unwrap();
+        assert!(matches!(outcome, Revalidation::NotModified));
+        assert_eq!(FULL_EXTRACTIONS.with(Cell::get), 0);
+
+        let (request, _) = server.join().unwrap();
+        assert!(request.contains("if-none-match: \"v1\""));
+        assert!(request.contains("if-modified-since: tue, 13 oct 2026 08:00:00 gmt"));
+    }
+
+    #[tokio::test]
+    async fn capped_reads_refuse_oversized_bodies() {
+        let response = |body: &'static str| {
+            reqwest::Response::from(http::Response::builder().body(body).unwrap())
+        };
+        let body = "<html><body>0123456789</body></html>";
+
+        let err = read_body_capped(response(body), 16, "SCRAPE_MAX_RESPONSE_BYTES")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Response too large"), "{err}");
+        assert!(err.contains("SCRAPE_MAX_RESPONSE_BYTES"), "{err}");
+
+        let read = read_body_capped(response(body), body.len(), "SCRAPE_MAX_RESPONSE_BYTES")
+            .await
+            .unwrap();
+        assert_eq!(read, body.as_bytes());
+    }
+
+    #[test]
+    fn raw_html_can_be_dropped_from_content() {
+        let html = "<html><head><title>Big page</title></head><body><main>\
+            <p>Release notes for the latest version of the library.</p></main></body></html>";
+        let url = Url::parse("https://example.com/changelog").unwrap();
+        let page = |scraper: RustScraper| {
+            scraper.html_response(
+                url.as_str(),
+                &url,
+                200,
+                "text/html".to_string(),
+                html.to_string(),
+            )
+        };
+
+        let kept = page(RustScraper::new());
+        let dropped = page(RustScraper::new().with_raw_html(false));
+        assert_eq!(kept.content, html);
+        assert!(dropped.content.is_empty());
+        assert_eq!(dropped.title, "Big page");
+        assert_eq!(dropped.clean_content, kept.clean_content);
+    }
+
+    #[test]
+    fn consent_banners_are_stripped_before_extraction() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/consent/onetrust.html"
+        ));
+        let url = Url::parse("https://news.example.de/rhein-pegel").unwrap();
+        let scraper = RustScraper::new();
+        let article_ending = "disputes over funding between the federal and state authorities";
+        let banner_text = "Strictly Necessary Cookies";
+
+        // Without the cleanup the preference centre competes with the article.
+        let baseline = scraper.extract_clean_content(html, &url);
+        assert!(baseline.contains(banner_text) || !baseline.contains(article_ending));
+
+        let page = scraper.html_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/html".to_string(),
+            html.to_string(),
+        );
+        assert_eq!(page.hydration_status.consent_nodes_removed, 1);
+        assert!(page.clean_content.contains(article_ending));
+        assert!(!page.clean_content.contains(banner_text));
+        assert!(!page.clean_content.contains("We value your privacy"));
+        assert!(page.word_count >= 120);
+        assert!(!page.warnings.iter().any(|w| w == "consent_markup_stripped"));
+    }
+
+    #[test]
+    fn cookie_recipes_survive_consent_cleanup() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/consent/recipe.html"
+        ));
+        let url = Url::parse("https://bakes.example.com/cookie-bars").unwrap();
+        let page = RustScraper::new().html_response(
+            url.as_str(),
+            &url,
+            200,
+            "text/html".to_string(),
+            html.to_string(),
+        );
+
+        assert_eq!(page.hydration_status.consent_nodes_removed, 0);
+        assert!(page.clean_content.contains("cookie dough into a lined tin"));
+        assert!(page.clean_content.contains("airtight tin"));
+    }
+
+    #[test]
+    fn quality_signals_rank_articles_above_stubs_and_link_farms() {
+        let quality = |name: &str, html: &str| {
+            let url = Url::parse(&format!("https://example.com/{}", name)).unwrap();
+            RustScraper::new()
+                .html_response(
+                    url.as_str(),
+                    &url,
+                    200,
+                    "text/html".to_string(),
+                    html.to_string(),
+                )
+                .quality
+                .unwrap()
+        };
+        let technical = quality(
+            "technical",
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/quality/technical.html"
+            )),
+        );
+        let listicle = quality(
+            "listicle",
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/quality/listicle.html"
+            )),
+        );
+        let link_farm = quality(
+            "link_farm",
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/quality/link_farm.html"
+            )),
+        );
+
+        assert!(technical.has_provenance);
+        assert!(technical.reading_level.is_some_and(|grade| grade > 6.0));
+        assert!(technical.link_density < 2.0, "{technical:?}");
+        assert!(!listicle.has_provenance);
+        assert!(link_farm.link_density > 20.0, "{link_farm:?}");
+
+        assert!(technical.composite >= 0.8, "{technical:?}");
+        assert!(listicle.composite < 0.8, "{listicle:?}");
+        assert!(link_farm.composite < 0.5, "{link_farm:?}");
+    }
+
+    #[test]
+    fn framework_main_entities_become_clean_content() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/framework_state/next_product.html"
+        ));
+        let url = Url::parse("https://shop.example.com/products/trail-kettle-1-2l").unwrap();
+        let page = |scraper: RustScraper| {
+            scraper.html_response(
+                url.as_str(),
+                &url,
+                200,
+                "text/html".to_string(),
+                html.to_string(),
+            )
+        };
+
+        let product = page(RustScraper::new());
+        assert!(product.clean_content.starts_with("# Trail Kettle 1.2L"));
+        assert!(product.clean_content.contains("heat-shielded handles"));
+        assert!(!product.clean_content.contains("Cart (0)"));
+        let props = &product.embedded_data_sources[0];
+        assert_eq!(props.source_type, "next_page_props");
+        assert_eq!(props.framework.as_deref(), Some("nextjs"));
+        // The raw blob is still there, and is still the `embedded_state_json`.
+        assert!(product
+            .embedded_state_json
+            .as_ref()
+            .unwrap()
+            .contains("buildId"));
+        assert!(product.embedded_data_sources[1..]
+            .iter()
+            .all(|source| source.framework.is_none()));
+
+        let mut app_state = RustScraper::new();
+        app_state.extract_app_state = true;
+        assert!(!page(app_state)
+            .clean_content
+            .starts_with("# Trail Kettle 1.2L"));
+    }
 }
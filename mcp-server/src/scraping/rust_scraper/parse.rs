@@ -1,11 +1,152 @@
 use super::RustScraper;
-use crate::types::{CodeBlock, Heading, Image, Link};
-use scraper::{Html, Selector};
+use crate::scraping::fragment_route;
+use crate::types::{CodeBlock, Heading, Image, Link, LinkDetail};
+use scraper::{ElementRef, Html, Selector};
 use std::collections::HashSet;
 use tracing::info;
 use url::Url;
 
+/// Longest `LinkDetail::context`, in characters.
+pub const LINK_CONTEXT_MAX_CHARS: usize = 140;
+
+/// Elements a link's `block` names, the nearest enclosing one.
+const LINK_BLOCKS: &[&str] = &[
+    "p",
+    "li",
+    "td",
+    "th",
+    "dd",
+    "dt",
+    "figcaption",
+    "caption",
+    "blockquote",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "pre",
+];
+
+/// Generic containers named instead when no [`LINK_BLOCKS`] element encloses the link.
+const LINK_CONTAINERS: &[&str] = &[
+    "div", "section", "article", "main", "nav", "header", "footer", "aside", "body",
+];
+
+/// Whether an anchor's space-separated `rel` list contains `nofollow`.
+pub fn rel_has_nofollow(rel: &str) -> bool {
+    rel_has(rel, "nofollow")
+}
+
+fn rel_has(rel: &str, value: &str) -> bool {
+    rel.split_ascii_whitespace()
+        .any(|token| token.eq_ignore_ascii_case(value))
+}
+
+/// The sentence of `text` containing the byte range `start..end`, cut to at most
+/// `max_chars` characters around that range when longer.
+pub fn sentence_around(text: &str, start: usize, end: usize, max_chars: usize) -> String {
+    let is_boundary = |i: usize, c: char| {
+        matches!(c, '.' | '!' | '?' | '。' | '！' | '？')
+            && text[i + c.len_utf8()..]
+                .chars()
+                .next()
+                .is_none_or(char::is_whitespace)
+    };
+    let sentence_start = text[..start]
+        .char_indices()
+        .filter(|&(i, c)| is_boundary(i, c))
+        .last()
+        .map(|(i, c)| i + c.len_utf8())
+        .unwrap_or(0);
+    let sentence_end = text[end..]
+        .char_indices()
+        .find(|&(i, c)| is_boundary(end + i, c))
+        .map(|(i, c)| end + i + c.len_utf8())
+        .unwrap_or(text.len());
+    let sentence = &text[sentence_start..sentence_end];
+    let lead = sentence.len() - sentence.trim_start().len();
+    let sentence = sentence.trim();
+    let chars: Vec<char> = sentence.chars().collect();
+    if chars.len() <= max_chars {
+        return sentence.to_string();
+    }
+    // Center the window on the link.
+    let offset = (start - sentence_start)
+        .saturating_sub(lead)
+        .min(sentence.len());
+    let link_start = sentence[..offset].chars().count();
+    let link_chars = text[start..end].chars().count();
+    let pad = max_chars.saturating_sub(link_chars) / 2;
+    let window_end = (link_start.saturating_sub(pad) + max_chars).min(chars.len());
+    let window_start = window_end.saturating_sub(max_chars);
+    chars[window_start..window_end]
+        .iter()
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Directives from a robots meta `content` or `X-Robots-Tag` value, lowercased.
+/// `none` expands to `noindex` + `nofollow`; crawler-scoped groups such as
+/// `googlebot: noindex` are ignored since they do not address us.
+pub fn parse_robots_directives(value: &str) -> Vec<String> {
+    const SCOPED_VALUES: [&str; 4] = [
+        "max-snippet",
+        "max-image-preview",
+        "max-video-preview",
+        "unavailable_after",
+    ];
+    let mut directives: Vec<String> = Vec::new();
+    for token in value.split(',') {
+        let token = token.trim().to_ascii_lowercase();
+        if let Some((name, _)) = token.split_once(':') {
+            if !SCOPED_VALUES.contains(&name.trim()) {
+                continue;
+            }
+        }
+        let expanded = if token == "none" {
+            vec!["noindex".to_string(), "nofollow".to_string()]
+        } else if token.is_empty() {
+            Vec::new()
+        } else {
+            vec![token]
+        };
+        for directive in expanded {
+            if !directives.contains(&directive) {
+                directives.push(directive);
+            }
+        }
+    }
+    directives
+}
+
 impl RustScraper {
+    /// Directives from every `<meta name="robots">` tag in the document.
+    pub(super) fn extract_robots_directives(&self, document: &Html) -> Vec<String> {
+        let Ok(selector) = Selector::parse("meta[name][content]") else {
+            return Vec::new();
+        };
+        let mut directives: Vec<String> = Vec::new();
+        for element in document.select(&selector) {
+            let is_robots = element
+                .value()
+                .attr("name")
+                .is_some_and(|name| name.trim().eq_ignore_ascii_case("robots"));
+            if !is_robots {
+                continue;
+            }
+            for directive in parse_robots_directives(element.value().attr("content").unwrap_or(""))
+            {
+                if !directives.contains(&directive) {
+                    directives.push(directive);
+                }
+            }
+        }
+        directives
+    }
+
     /// Extract headings (h1-h6)
     pub(super) fn extract_headings(&self, document: &Html) -> Vec<Heading> {
         let mut headings = Vec::new();
@@ -87,8 +228,11 @@ impl RustScraper {
         if let Ok(selector) = Selector::parse(selector_str) {
             for element in document.select(&selector) {
                 if let Some(href) = element.value().attr("href") {
-                    // Skip anchor links, javascript, and common non-content patterns
-                    if href.starts_with('#')
+                    // Skip anchor links (but not `#/` routes), javascript, and common
+                    // non-content patterns
+                    if href
+                        .strip_prefix('#')
+                        .is_some_and(|fragment| !fragment_route::is_route(fragment))
                         || href.starts_with("javascript:")
                         || href.starts_with("mailto:")
                     {
@@ -106,9 +250,12 @@ impl RustScraper {
                     // Avoid duplicates
                     if !seen_urls.contains(&absolute_url) {
                         seen_urls.insert(absolute_url.clone());
+                        let detail = self.links_detail.then(|| self.link_detail(&element, &text));
                         links.push(Link {
                             url: absolute_url,
                             text,
+                            nofollow: element.value().attr("rel").is_some_and(rel_has_nofollow),
+                            detail,
                         });
                     }
                 }
@@ -118,6 +265,56 @@ impl RustScraper {
         links
     }
 
+    /// Where `anchor` sits: its enclosing block element, whether it is in page
+    /// chrome (the same test text extraction uses), the sentence around it and its `rel`
+    /// qualifiers.
+    fn link_detail(&self, anchor: &ElementRef, anchor_text: &str) -> LinkDetail {
+        let ancestors: Vec<ElementRef> = anchor.ancestors().filter_map(ElementRef::wrap).collect();
+        let nearest = |names: &[&str]| {
+            ancestors
+                .iter()
+                .find(|el| names.contains(&el.value().name()))
+        };
+        let block = nearest(LINK_BLOCKS)
+            .or_else(|| nearest(LINK_CONTAINERS))
+            .or(ancestors.first());
+        let in_chrome = ancestors.iter().any(|el| self.is_page_chrome(el));
+
+        let anchor_text = anchor_text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let context = match block {
+            Some(block) => {
+                let text = block.text().collect::<String>();
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                let start = if anchor_text.is_empty() {
+                    None
+                } else {
+                    text.find(&anchor_text)
+                };
+                match start {
+                    Some(start) => sentence_around(
+                        &text,
+                        start,
+                        start + anchor_text.len(),
+                        LINK_CONTEXT_MAX_CHARS,
+                    ),
+                    None => sentence_around(&text, 0, 0, LINK_CONTEXT_MAX_CHARS),
+                }
+            }
+            None => anchor_text.chars().take(LINK_CONTEXT_MAX_CHARS).collect(),
+        };
+
+        let rel = anchor.value().attr("rel").unwrap_or("");
+        LinkDetail {
+            block: block
+                .map(|el| el.value().name().to_string())
+                .unwrap_or_default(),
+            in_chrome,
+            context,
+            sponsored: rel_has(rel, "sponsored"),
+            ugc: rel_has(rel, "ugc"),
+        }
+    }
+
     /// Extract images with absolute URLs
     pub(super) fn extract_images(&self, document: &Html, base_url: &Url) -> Vec<Image> {
         let mut images = Vec::new();
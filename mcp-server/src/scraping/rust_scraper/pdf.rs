@@ -0,0 +1,240 @@
+//! PDF responses: detection and pure-Rust text extraction.
+
+use super::{reading_time_minutes, word_equivalents, RustScraper};
+use crate::types::*;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use scraper::Html;
+
+struct PdfExtraction {
+    title: Option<String>,
+    text: String,
+    page_count: usize,
+}
+
+/// A response is treated as PDF when the server says so or the body starts with `%PDF-`.
+pub(super) fn is_pdf(content_type: &str, body: &[u8]) -> bool {
+    content_type
+        .to_ascii_lowercase()
+        .contains("application/pdf")
+        || body.starts_with(b"%PDF-")
+}
+
+/// Content types whose body must be sniffed for PDF magic bytes before text decoding.
+pub(super) fn may_be_pdf(content_type: &str, url: &url::Url) -> bool {
+    let ct = content_type.to_ascii_lowercase();
+    ct.contains("application/pdf")
+        || ct.contains("application/octet-stream")
+        || ct.contains("binary/octet-stream")
+        || url.path().to_ascii_lowercase().ends_with(".pdf")
+}
+
+fn extract(bytes: &[u8]) -> Result<PdfExtraction> {
+    let doc = pdf_extract::Document::load_mem(bytes)
+        .map_err(|e| anyhow!("Failed to parse PDF: {}", e))?;
+    let page_count = doc.get_pages().len();
+    let title = document_title(&doc);
+
+    // pdf-extract can panic on malformed font/encoding tables; keep that inside the scrape.
+    let pages = std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem_by_pages(bytes))
+        .map_err(|_| anyhow!("Failed to extract PDF text: extractor panicked"))?
+        .map_err(|e| anyhow!("Failed to extract PDF text: {}", e))?;
+    let text = pages
+        .iter()
+        .map(|page| normalize_page_text(page))
+        .filter(|page| !page.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    Ok(PdfExtraction {
+        title,
+        text,
+        page_count,
+    })
+}
+
+impl RustScraper {
+    /// Build a `ScrapeResponse` from a PDF body. Headings, links, images and code blocks
+    /// stay empty: the text layer carries no reliable structure for them.
+    pub(super) fn pdf_response(
+        &self,
+        url: &str,
+        parsed_url: &url::Url,
+        status_code: u16,
+        content_type: String,
+        body: &[u8],
+    ) -> Result<ScrapeResponse> {
+        let pdf = extract(body)?;
+        let language = self.detect_language(&Html::parse_document(""), &pdf.text);
+        let word_count = self.count_words_in(&pdf.text, &language);
+        let reading_time_minutes = Some(reading_time_minutes(word_count, &language));
+        let extraction_score = self.calculate_extraction_score(
+            word_equivalents(word_count, &language),
+            &None,
+            &[],
+            &[],
+        );
+        let title = pdf.title.unwrap_or_else(|| {
+            parsed_url
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .filter(|name| !name.is_empty())
+                .unwrap_or("")
+                .to_string()
+        });
+
+        let mut warnings = Vec::new();
+        if word_count == 0 {
+            // Scanned/image-only PDFs have no text layer to extract.
+            warnings.push("pdf_no_text_layer".to_string());
+        }
+
+        Ok(ScrapeResponse {
+            url: url.to_string(),
+            title,
+            content: String::new(),
+            clean_content: pdf.text,
+            embedded_state_json: None,
+            embedded_data_sources: Vec::new(),
+            hydration_status: HydrationStatus::default(),
+            meta_description: String::new(),
+            meta_keywords: String::new(),
+            headings: Vec::new(),
+            links: Vec::new(),
+            images: Vec::new(),
+            timestamp: Utc::now().to_rfc3339(),
+            status_code,
+            content_type,
+            word_count,
+            language,
+            canonical_url: None,
+            site_name: None,
+            author: None,
+            published_at: None,
+            og_title: None,
+            og_description: None,
+            og_image: None,
+            reading_time_minutes,
+            code_blocks: Vec::new(),
+            truncated: false,
+            actual_chars: 0,
+            max_chars_limit: None,
+            extraction_score: Some(extraction_score),
+            warnings,
+            domain: parsed_url.host_str().map(|h| h.to_string()),
+            auth_wall_reason: None,
+            auth_risk_score: None,
+            detection_factors: Vec::new(),
+            final_url: None,
+            redirect_chain: Vec::new(),
+            metrics: None,
+            content_format: Some("pdf".to_string()),
+            page_count: Some(pdf.page_count),
+            feed_urls: Vec::new(),
+            feed_items: Vec::new(),
+            fetch_attempts: None,
+            block_reason: None,
+            asset_manifest: None,
+            charset: None,
+            etag: None,
+            last_modified: None,
+            revalidated: false,
+            structured_data: Vec::new(),
+            screenshot: None,
+            robots_directives: Vec::new(),
+            selector_results: None,
+            alternate_languages: Vec::new(),
+            provenance: Vec::new(),
+            sections: Vec::new(),
+            title_source: None,
+            meta_description_source: None,
+            quality: None,
+        })
+    }
+}
+
+fn document_title(doc: &pdf_extract::Document) -> Option<String> {
+    let info = doc.trailer.get(b"Info").ok()?;
+    let (_, info) = doc.dereference(info).ok()?;
+    let title = info.as_dict().ok()?.get(b"Title").ok()?;
+    let (_, title) = doc.dereference(title).ok()?;
+    pdf_extract::decode_text_string(title)
+        .ok()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// Collapse the extractor's layout whitespace: trim lines and keep at most one blank line.
+fn normalize_page_text(page: &str) -> String {
+    let mut out = Vec::new();
+    let mut blank_run = false;
+    for line in page.lines().map(str::trim) {
+        if line.is_empty() {
+            if !blank_run && !out.is_empty() {
+                out.push(String::new());
+            }
+            blank_run = true;
+        } else {
+            out.push(line.split_whitespace().collect::<Vec<_>>().join(" "));
+            blank_run = false;
+        }
+    }
+    while out.last().is_some_and(|l| l.is_empty()) {
+        out.pop();
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/sample.pdf"
+    ));
+
+    #[test]
+    fn detects_pdf_by_content_type_or_magic_bytes() {
+        assert!(is_pdf("application/pdf", b""));
+        assert!(is_pdf("application/octet-stream", FIXTURE));
+        assert!(!is_pdf("text/html; charset=utf-8", b"<!doctype html>"));
+    }
+
+    #[test]
+    fn extracts_text_title_and_page_count() {
+        let pdf = extract(FIXTURE).expect("fixture should parse");
+        assert_eq!(pdf.title.as_deref(), Some("Sample Fixture Document"));
+        assert_eq!(pdf.page_count, 1);
+        assert!(pdf.text.contains("Cortex Scout PDF fixture"));
+        assert!(pdf.text.contains("text extraction"));
+    }
+
+    #[test]
+    fn pdf_response_has_text_but_no_html_structure() {
+        let scraper = RustScraper::new();
+        let url = url::Url::parse("https://example.com/files/sample.pdf").unwrap();
+        let response = scraper
+            .pdf_response(
+                url.as_str(),
+                &url,
+                200,
+                "application/pdf".to_string(),
+                FIXTURE,
+            )
+            .unwrap();
+
+        assert!(response.word_count > 0);
+        assert_eq!(response.title, "Sample Fixture Document");
+        assert_eq!(response.content_format.as_deref(), Some("pdf"));
+        assert_eq!(response.page_count, Some(1));
+        assert!(response.headings.is_empty());
+        assert!(response.links.is_empty());
+        assert!(response.code_blocks.is_empty());
+    }
+
+    #[test]
+    fn rejects_non_pdf_bytes() {
+        assert!(extract(b"<html>not a pdf</html>").is_err());
+    }
+}
@@ -1,5 +1,23 @@
+use super::words::{count_words, is_dense_script_language, word_equivalents};
 use super::RustScraper;
-use crate::types::{CodeBlock, Heading};
+use crate::types::{CodeBlock, Heading, QualitySignals, ScrapeResponse};
+use regex::Regex;
+use std::sync::LazyLock;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Reading level needs this many words before the sentence statistics mean anything.
+const MIN_READING_LEVEL_WORDS: usize = 30;
+/// Words at which the length component of the composite is full.
+const SUBSTANTIAL_WORDS: f64 = 300.0;
+/// Text-to-markup ratio at which that component is full; article pages sit around 0.1-0.4.
+const GOOD_TEXT_RATIO: f64 = 0.25;
+/// Links per 100 words at which the link component drops to zero.
+const LINK_FARM_DENSITY: f64 = 20.0;
+
+static FENCED_CODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?ms)^\s*(```|~~~).*?^\s*(```|~~~)[^\n]*$").unwrap());
+static MARKDOWN_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!?\[([^\]]*)\]\([^)]*\)").unwrap());
 
 impl RustScraper {
     /// Calculate extraction quality score (Priority 1 fix)
@@ -52,4 +70,153 @@ impl RustScraper {
 
         score.min(1.0)
     }
+
+    /// Quality signals of a finished page; `html_bytes` is the size of the HTML it was
+    /// extracted from.
+    ///
+    /// The composite weighs text-to-markup (0.25), link density (0.3), boilerplate (0.1),
+    /// length up to 300 words (0.25) and an author plus date (0.1).
+    pub(super) fn quality_signals(
+        &self,
+        page: &ScrapeResponse,
+        html_bytes: usize,
+    ) -> QualitySignals {
+        // Link targets are markup, not text: count the words a reader sees.
+        let visible_text = MARKDOWN_LINK.replace_all(&page.clean_content, "$1");
+        let words = word_equivalents(
+            count_words(&visible_text, Some(&page.language)),
+            &page.language,
+        );
+        let text_to_markup_ratio = if html_bytes == 0 {
+            0.0
+        } else {
+            (visible_text.len() as f64 / html_bytes as f64).min(1.0)
+        };
+        let link_density = page.links.len() as f64 * 100.0 / words.max(1) as f64;
+        let boilerplate_ratio = page.hydration_status.noise_reduction_ratio.clamp(0.0, 1.0);
+        let has_provenance = page.author.is_some() && page.published_at.is_some();
+
+        let composite = 0.25 * (text_to_markup_ratio / GOOD_TEXT_RATIO).min(1.0)
+            + 0.3 * (1.0 - (link_density / LINK_FARM_DENSITY).min(1.0))
+            + 0.1 * (1.0 - boilerplate_ratio)
+            + 0.25 * (words as f64 / SUBSTANTIAL_WORDS).min(1.0)
+            + if has_provenance { 0.1 } else { 0.0 };
+
+        QualitySignals {
+            reading_level: reading_level(&page.clean_content, &page.language),
+            text_to_markup_ratio: round3(text_to_markup_ratio),
+            link_density: round3(link_density),
+            boilerplate_ratio: round3(boilerplate_ratio),
+            has_provenance,
+            composite: round3(composite),
+        }
+    }
+}
+
+/// Flesch–Kincaid grade level of markdown `text`, with code blocks and link targets left
+/// out. `None` for dense-script languages, mostly non-Latin text and texts under 30 words,
+/// where syllable counting does not apply or the sample is too small.
+pub(super) fn reading_level(text: &str, language: &str) -> Option<f64> {
+    if is_dense_script_language(language) {
+        return None;
+    }
+    let prose = FENCED_CODE.replace_all(text, "");
+    let prose = MARKDOWN_LINK.replace_all(&prose, "$1");
+
+    let letters = prose.chars().filter(|c| c.is_alphabetic()).count();
+    let latin = prose
+        .chars()
+        .filter(|c| c.is_alphabetic() && (c.is_ascii() || ('\u{C0}'..='\u{24F}').contains(c)))
+        .count();
+    if letters == 0 || (latin as f64) < letters as f64 * 0.8 {
+        return None;
+    }
+
+    let mut sentences = 0usize;
+    let mut words = 0usize;
+    let mut syllables = 0usize;
+    // Headings and list items rarely end in punctuation, so every line closes a sentence.
+    for line in prose.lines() {
+        for sentence in line.unicode_sentences() {
+            let sentence_words: Vec<&str> = sentence
+                .unicode_words()
+                .filter(|w| w.chars().any(char::is_alphabetic))
+                .collect();
+            if sentence_words.is_empty() {
+                continue;
+            }
+            sentences += 1;
+            words += sentence_words.len();
+            syllables += sentence_words
+                .iter()
+                .copied()
+                .map(count_syllables)
+                .sum::<usize>();
+        }
+    }
+    if words < MIN_READING_LEVEL_WORDS {
+        return None;
+    }
+
+    let grade =
+        0.39 * words as f64 / sentences as f64 + 11.8 * syllables as f64 / words as f64 - 15.59;
+    Some((grade.max(0.0) * 10.0).round() / 10.0)
+}
+
+/// Vowel groups, less a silent final `e`, at least one.
+fn count_syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let is_vowel = |c: char| "aeiouyàáâäèéêëìíîïòóôöùúûü".contains(c);
+    let mut groups = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = is_vowel(c);
+        if vowel && !previous_vowel {
+            groups += 1;
+        }
+        previous_vowel = vowel;
+    }
+    if groups > 1 && word.ends_with('e') && !word.ends_with("le") && !word.ends_with("ee") {
+        groups -= 1;
+    }
+    groups.max(1)
+}
+
+fn round3(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn syllables_follow_vowel_groups() {
+        assert_eq!(count_syllables("cat"), 1);
+        assert_eq!(count_syllables("make"), 1);
+        assert_eq!(count_syllables("table"), 2);
+        assert_eq!(count_syllables("concurrency"), 4);
+        assert_eq!(count_syllables("rhythm"), 1);
+    }
+
+    #[test]
+    fn reading_level_separates_plain_and_dense_prose() {
+        let plain = "The cat sat on the mat. The dog ran to the park. We had fun in the sun. \
+                     It was a good day. Then we went home to eat. We like it.";
+        let dense = "Asynchronous cancellation semantics necessitate comprehensive \
+                     consideration of intermediate resource acquisition, particularly \
+                     regarding deterministic deallocation, synchronization primitives \
+                     and heterogeneous execution environments operating concurrently \
+                     across distributed infrastructure. Consequently, implementations require \
+                     meticulous documentation regarding observable behaviour.";
+        let plain_grade = reading_level(plain, "en").unwrap();
+        let dense_grade = reading_level(dense, "en").unwrap();
+        assert!(plain_grade < 3.0, "{plain_grade}");
+        assert!(dense_grade > 16.0, "{dense_grade}");
+
+        assert_eq!(reading_level("Too short to grade.", "en"), None);
+        assert_eq!(reading_level(&"数据".repeat(100), "zh"), None);
+        let code = format!("Intro.\n\n```\n{}\n```\n", "let x = value;\n".repeat(40));
+        assert_eq!(reading_level(&code, "en"), None);
+    }
 }
@@ -0,0 +1,342 @@
+//! JSON and plain-text bodies: API responses, raw `.txt` / `.md` files and the like.
+//!
+//! These are already clean, so they skip `Html::parse_document` and readability. JSON
+//! (declared, or sniffed from a body that parses as an object or array) becomes one fenced
+//! `json` block in `clean_content`, with the raw payload in `embedded_state_json`. Text
+//! and markdown are normalized as they are; headings and fenced code blocks are read from
+//! bodies that are markdown.
+
+use super::github::{fenced_code_blocks, is_markdown_path, markdown_headings};
+use super::{reading_time_minutes, word_equivalents, RustScraper};
+use crate::types::*;
+use chrono::Utc;
+use scraper::Html;
+use url::Url;
+
+/// Cap on the raw payload kept in `embedded_state_json`, as for hydration state.
+const MAX_RAW_JSON_CHARS: usize = 200_000;
+
+/// A body that bypasses the HTML pipeline.
+#[derive(Debug)]
+pub(super) enum TextBody {
+    Json(serde_json::Value),
+    Markdown,
+    Text,
+}
+
+impl TextBody {
+    fn content_format(&self) -> &'static str {
+        match self {
+            TextBody::Json(_) => "json",
+            TextBody::Markdown => "markdown",
+            TextBody::Text => "text",
+        }
+    }
+}
+
+/// What `body` is, when it is JSON (declared or sniffed), markdown or plain text rather
+/// than HTML. A body declared as JSON that does not parse is kept as plain text.
+pub(super) fn detect(content_type: &str, url: &Url, body: &str) -> Option<TextBody> {
+    if let Some(value) = sniff_json(body) {
+        return Some(TextBody::Json(value));
+    }
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    match mime.as_str() {
+        "application/json" | "text/json" => Some(TextBody::Text),
+        mime if mime.ends_with("+json") => Some(TextBody::Text),
+        "text/markdown" | "text/x-markdown" => Some(TextBody::Markdown),
+        "text/plain" if is_markdown_path(url.path()) || looks_like_markdown(body) => {
+            Some(TextBody::Markdown)
+        }
+        "text/plain" => Some(TextBody::Text),
+        _ => None,
+    }
+}
+
+/// An object or array, whatever the content type said. HTML never starts with `{` or `[`.
+fn sniff_json(body: &str) -> Option<serde_json::Value> {
+    let trimmed = body.trim_start_matches('\u{feff}').trim();
+    if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+        return None;
+    }
+    serde_json::from_str::<serde_json::Value>(trimmed)
+        .ok()
+        .filter(|value| value.is_object() || value.is_array())
+}
+
+/// `text/plain` served markdown (READMEs, `llms.txt`): ATX headings plus some other
+/// markdown construct. Headings alone are not enough, since `#` also starts comments in
+/// robots.txt and config files.
+fn looks_like_markdown(body: &str) -> bool {
+    let code_blocks = fenced_code_blocks(body);
+    if markdown_headings(body, &code_blocks).is_empty() {
+        return false;
+    }
+    !code_blocks.is_empty()
+        || body.contains("](")
+        || body.lines().any(|line| {
+            let line = line.trim_start();
+            line.starts_with("- ")
+                || line.starts_with("* ")
+                || line
+                    .split_once(". ")
+                    .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        })
+}
+
+/// Every string value in `value`, for word counting and language detection.
+fn json_strings<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(s) => out.push(s),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| json_strings(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| json_strings(v, out)),
+        _ => {}
+    }
+}
+
+/// The last path segment, or the host for a bare origin.
+fn file_name(url: &Url) -> String {
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty())
+        .or(url.host_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+impl RustScraper {
+    /// Build a `ScrapeResponse` from a JSON, markdown or plain-text body (see [`detect`]).
+    /// Verbatim bodies (JSON, plain text) score 1.0; markdown is scored on its headings and
+    /// code blocks like any article.
+    pub(super) fn text_body_response(
+        &self,
+        url: &str,
+        parsed_url: &Url,
+        status_code: u16,
+        content_type: String,
+        text: String,
+        body: TextBody,
+    ) -> ScrapeResponse {
+        let content_format = body.content_format();
+        let mut warnings = Vec::new();
+        let mut embedded_state_json = None;
+        let mut code_blocks = Vec::new();
+        let mut headings = Vec::new();
+        let (title, clean_content, prose) = match &body {
+            TextBody::Json(value) => {
+                let title = ["title", "name"]
+                    .iter()
+                    .find_map(|key| value.get(key).and_then(|v| v.as_str()))
+                    .map(|title| title.trim().to_string())
+                    .filter(|title| !title.is_empty())
+                    .unwrap_or_else(|| file_name(parsed_url));
+                let pretty = serde_json::to_string_pretty(value).unwrap_or_else(|_| text.clone());
+                let mut strings = Vec::new();
+                json_strings(value, &mut strings);
+                let raw = text.trim();
+                embedded_state_json = Some(if raw.chars().count() > MAX_RAW_JSON_CHARS {
+                    warnings.push("embedded_state_json_truncated".to_string());
+                    raw.chars().take(MAX_RAW_JSON_CHARS).collect()
+                } else {
+                    raw.to_string()
+                });
+                (
+                    title,
+                    format!("```json\n{}\n```", pretty),
+                    strings.join("\n"),
+                )
+            }
+            TextBody::Markdown | TextBody::Text => {
+                let clean_content = self.normalize_markdown_fragments(&text);
+                if matches!(body, TextBody::Markdown) {
+                    code_blocks = fenced_code_blocks(&clean_content);
+                    headings = markdown_headings(&clean_content, &code_blocks);
+                }
+                let title = headings
+                    .iter()
+                    .find(|h| h.level == "h1")
+                    .map(|h| h.text.clone())
+                    .unwrap_or_else(|| file_name(parsed_url));
+                let prose = clean_content.clone();
+                (title, clean_content, prose)
+            }
+        };
+
+        let language = self.detect_language(&Html::parse_document(""), &prose);
+        let word_count = self.count_words_in(&prose, &language);
+        let reading_time_minutes = Some(reading_time_minutes(word_count, &language));
+        let extraction_score = match body {
+            TextBody::Markdown => self.calculate_extraction_score(
+                word_equivalents(word_count, &language),
+                &None,
+                &code_blocks,
+                &headings,
+            ),
+            TextBody::Json(_) | TextBody::Text => 1.0,
+        };
+
+        ScrapeResponse {
+            url: url.to_string(),
+            title,
+            content: if self.raw_html { text } else { String::new() },
+            clean_content,
+            hydration_status: HydrationStatus {
+                json_found: embedded_state_json.is_some(),
+                ..Default::default()
+            },
+            embedded_state_json,
+            embedded_data_sources: Vec::new(),
+            meta_description: String::new(),
+            meta_keywords: String::new(),
+            headings,
+            links: Vec::new(),
+            images: Vec::new(),
+            timestamp: Utc::now().to_rfc3339(),
+            status_code,
+            content_type,
+            word_count,
+            language,
+            canonical_url: None,
+            site_name: None,
+            author: None,
+            published_at: None,
+            og_title: None,
+            og_description: None,
+            og_image: None,
+            reading_time_minutes,
+            code_blocks,
+            truncated: false,
+            actual_chars: 0,
+            max_chars_limit: None,
+            extraction_score: Some(extraction_score),
+            warnings,
+            domain: parsed_url.host_str().map(|h| h.to_string()),
+            auth_wall_reason: None,
+            auth_risk_score: None,
+            detection_factors: Vec::new(),
+            final_url: None,
+            redirect_chain: Vec::new(),
+            metrics: None,
+            content_format: Some(content_format.to_string()),
+            page_count: None,
+            feed_urls: Vec::new(),
+            feed_items: Vec::new(),
+            fetch_attempts: None,
+            block_reason: None,
+            asset_manifest: None,
+            charset: None,
+            etag: None,
+            last_modified: None,
+            revalidated: false,
+            structured_data: Vec::new(),
+            screenshot: None,
+            robots_directives: Vec::new(),
+            selector_results: None,
+            alternate_languages: Vec::new(),
+            provenance: Vec::new(),
+            sections: Vec::new(),
+            title_source: None,
+            meta_description_source: None,
+            quality: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrape(url: &str, content_type: &str, text: &str) -> ScrapeResponse {
+        let url = Url::parse(url).unwrap();
+        let body = detect(content_type, &url, text).expect("not an HTML body");
+        RustScraper::new().text_body_response(
+            url.as_str(),
+            &url,
+            200,
+            content_type.to_string(),
+            text.to_string(),
+            body,
+        )
+    }
+
+    #[test]
+    fn json_api_responses_become_a_fenced_block() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/text_formats/api_response.json"
+        ));
+        let page = scrape(
+            "https://api.example.com/v1/packages/tokio",
+            "application/json; charset=utf-8",
+            text,
+        );
+        assert_eq!(page.content_format.as_deref(), Some("json"));
+        assert_eq!(page.title, "tokio");
+        assert!(page.clean_content.starts_with("```json\n{\n  \""));
+        assert!(page.clean_content.ends_with("\n```"));
+        assert_eq!(page.embedded_state_json.as_deref(), Some(text.trim()));
+        assert!(page.hydration_status.json_found);
+        assert!(page.headings.is_empty() && page.links.is_empty());
+        // Words of the string values, not of keys and punctuation.
+        assert!(
+            page.word_count > 10 && page.word_count < 40,
+            "{}",
+            page.word_count
+        );
+        assert_eq!(page.extraction_score, Some(1.0));
+
+        // Sniffed when the server mislabels it; a broken declared payload stays text.
+        let url = Url::parse("https://api.example.com/v1/ping").unwrap();
+        assert!(matches!(
+            detect("text/html", &url, " [1, 2, 3]"),
+            Some(TextBody::Json(_))
+        ));
+        assert!(detect("text/html", &url, "<html>{}</html>").is_none());
+        assert!(matches!(
+            detect("application/json", &url, "{\"truncated\": "),
+            Some(TextBody::Text)
+        ));
+    }
+
+    #[test]
+    fn raw_readme_keeps_markdown_headings_and_code() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/text_formats/README.md"
+        ));
+        let page = scrape(
+            "https://gitlab.com/demo/widget/-/raw/main/README.md",
+            "text/plain; charset=utf-8",
+            text,
+        );
+        assert_eq!(page.content_format.as_deref(), Some("markdown"));
+        assert_eq!(page.title, "widget");
+        let headings: Vec<_> = page.headings.iter().map(|h| h.text.as_str()).collect();
+        assert_eq!(headings, ["widget", "Install", "Usage", "License"]);
+        assert_eq!(page.code_blocks.len(), 2);
+        assert!(page.clean_content.starts_with("# widget"));
+        assert!(page.word_count > 40, "{}", page.word_count);
+        assert!(page.extraction_score.unwrap() > 0.3);
+    }
+
+    #[test]
+    fn robots_txt_stays_plain_text() {
+        let text = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/text_formats/robots.txt"
+        ));
+        let page = scrape("https://www.example.com/robots.txt", "text/plain", text);
+        assert_eq!(page.content_format.as_deref(), Some("text"));
+        assert_eq!(page.title, "robots.txt");
+        // `#` comments are not headings.
+        assert!(page.headings.is_empty());
+        assert!(page.clean_content.contains("Disallow: /admin/"));
+        assert!(page.word_count > 10);
+        assert_eq!(page.extraction_score, Some(1.0));
+    }
+}
@@ -0,0 +1,113 @@
+//! Word counts and reading time that hold up outside space-separated languages.
+//!
+//! Chinese, Japanese, Thai, Lao, Khmer and Burmese text is not split by spaces, so a
+//! whitespace count sees a whole paragraph as one or two "words". For those languages
+//! every character of the script counts as one unit and reading time uses
+//! [`DENSE_CHARS_PER_MINUTE`]. Everything else is segmented on Unicode word boundaries
+//! (UAX #29), so stray dashes, bullets and emoji no longer count as words.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Average silent reading speed for space-separated languages.
+pub const WORDS_PER_MINUTE: usize = 200;
+/// Average reading speed for CJK and Southeast Asian scripts, in characters.
+pub const DENSE_CHARS_PER_MINUTE: usize = 400;
+
+/// Whether `language` (a `lang` tag like `zh-CN`, or a whatlang code) is written without
+/// spaces between words.
+pub fn is_dense_script_language(language: &str) -> bool {
+    let primary = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    matches!(
+        primary.as_str(),
+        "zh" | "ja" | "th" | "lo" | "km" | "my" | "cmn" | "jpn" | "tha" | "lao" | "khm" | "mya"
+    )
+}
+
+fn is_dense_script_char(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'       // Hiragana, Katakana
+        | '\u{31F0}'..='\u{31FF}'     // Katakana phonetic extensions
+        | '\u{3400}'..='\u{4DBF}'     // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}'     // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}'     // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}'     // Halfwidth Katakana
+        | '\u{20000}'..='\u{2FA1F}'   // CJK extensions B+
+        | '\u{0E00}'..='\u{0EFF}'     // Thai, Lao
+        | '\u{1000}'..='\u{109F}'     // Myanmar
+        | '\u{1780}'..='\u{17FF}'     // Khmer
+    ) && c.is_alphabetic()
+}
+
+/// Words in `text`. For dense-script languages, characters of those scripts count one
+/// each and any Latin runs mixed in are counted as words.
+pub fn count_words(text: &str, language: Option<&str>) -> usize {
+    if !language.is_some_and(is_dense_script_language) {
+        return text.unicode_words().count();
+    }
+    let dense = text.chars().filter(|&c| is_dense_script_char(c)).count();
+    let rest: String = text
+        .chars()
+        .map(|c| if is_dense_script_char(c) { ' ' } else { c })
+        .collect();
+    dense + rest.unicode_words().count()
+}
+
+/// `count` (from [`count_words`]) in English-word equivalents, so fixed word thresholds
+/// mean the same amount of text whatever the script.
+pub fn word_equivalents(count: usize, language: &str) -> usize {
+    if is_dense_script_language(language) {
+        count * WORDS_PER_MINUTE / DENSE_CHARS_PER_MINUTE
+    } else {
+        count
+    }
+}
+
+/// Minutes to read `count` units of `language` text, at least one.
+pub fn reading_time_minutes(count: usize, language: &str) -> u32 {
+    let per_minute = if is_dense_script_language(language) {
+        DENSE_CHARS_PER_MINUTE
+    } else {
+        WORDS_PER_MINUTE
+    };
+    (count.div_ceil(per_minute) as u32).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn punctuation_does_not_count_as_words() {
+        let text = "Rust — a state-of-the-art language • fast, safe & fun 🚀";
+        assert_eq!(text.split_whitespace().count(), 11);
+        assert_eq!(count_words(text, Some("en")), 10);
+        assert_eq!(count_words("don't stop", None), 2);
+    }
+
+    #[test]
+    fn dense_scripts_count_characters() {
+        let zh = "今天天气很好，我们去公园散步吧。";
+        assert_eq!(count_words(zh, Some("zh-CN")), 14);
+        let ja = "東京はRustの勉強会を開きます";
+        assert_eq!(count_words(ja, Some("ja")), 12 + 1);
+        let th = "ภาษาไทยไม่มีการเว้นวรรคระหว่างคำ";
+        assert!(count_words(th, Some("th")) > 20);
+        assert_eq!(th.split_whitespace().count(), 1);
+    }
+
+    #[test]
+    fn reading_time_and_equivalents_follow_the_script() {
+        assert_eq!(reading_time_minutes(0, "en"), 1);
+        assert_eq!(reading_time_minutes(401, "en"), 3);
+        assert_eq!(reading_time_minutes(800, "ja"), 2);
+        assert_eq!(word_equivalents(40, "zh-TW"), 20);
+        assert_eq!(word_equivalents(40, "de"), 40);
+        assert!(!is_dense_script_language("ko"));
+        assert!(is_dense_script_language("THA"));
+    }
+}
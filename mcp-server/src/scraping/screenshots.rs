@@ -0,0 +1,151 @@
+//! PNG captures taken during browser-rendered scrapes (`screenshot` on `web_fetch`).
+//!
+//! Files are named `<sha256>.png` under `CORTEX_SCOUT_SCREENSHOT_DIR` (default
+//! `~/.cortex-scout/screenshots`), so an unchanged page rendered twice is stored once.
+//! Every write trims the directory to the newest `CORTEX_SCOUT_SCREENSHOT_KEEP` files.
+
+use crate::types::PageScreenshot;
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::debug;
+
+/// Screenshots kept on disk when `CORTEX_SCOUT_SCREENSHOT_KEEP` is unset.
+pub const DEFAULT_KEEP: usize = 200;
+
+/// Largest PNG returned inline as base64 when `CORTEX_SCOUT_SCREENSHOT_INLINE_MAX_BYTES`
+/// is unset; bigger captures are returned by path only.
+pub const DEFAULT_INLINE_MAX_BYTES: usize = 1024 * 1024;
+
+fn env_usize(key: &str) -> Option<usize> {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+}
+
+/// `CORTEX_SCOUT_SCREENSHOT_DIR`, else `~/.cortex-scout/screenshots`.
+pub fn screenshot_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("CORTEX_SCOUT_SCREENSHOT_DIR") {
+        let dir = dir.trim();
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    dirs::home_dir().map(|home| home.join(".cortex-scout").join("screenshots"))
+}
+
+pub fn inline_max_bytes() -> usize {
+    env_usize("CORTEX_SCOUT_SCREENSHOT_INLINE_MAX_BYTES").unwrap_or(DEFAULT_INLINE_MAX_BYTES)
+}
+
+/// Store `png` in the screenshot directory and prune old captures.
+pub async fn save(png: &[u8], full_page: bool) -> Result<PageScreenshot> {
+    let dir = screenshot_dir().ok_or_else(|| anyhow!("no home directory for screenshots"))?;
+    let keep = env_usize("CORTEX_SCOUT_SCREENSHOT_KEEP").unwrap_or(DEFAULT_KEEP);
+    save_in(&dir, png, full_page, keep).await
+}
+
+async fn save_in(dir: &Path, png: &[u8], full_page: bool, keep: usize) -> Result<PageScreenshot> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("creating {}", dir.display()))?;
+    let digest = hex::encode(Sha256::digest(png));
+    let path = dir.join(format!("{}.png", &digest[..32]));
+    tokio::fs::write(&path, png)
+        .await
+        .with_context(|| format!("writing {}", path.display()))?;
+
+    match prune(dir, keep).await {
+        Ok(0) => {}
+        Ok(removed) => debug!(
+            "Pruned {} old screenshot(s) from {}",
+            removed,
+            dir.display()
+        ),
+        Err(e) => debug!("Screenshot pruning failed in {}: {}", dir.display(), e),
+    }
+
+    Ok(PageScreenshot {
+        path: path.display().to_string(),
+        bytes: png.len(),
+        full_page,
+        base64: None,
+    })
+}
+
+/// Delete all but the `keep` most recently written PNGs; returns how many were removed.
+async fn prune(dir: &Path, keep: usize) -> Result<usize> {
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    let mut shots: Vec<(SystemTime, PathBuf)> = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("png") {
+            continue;
+        }
+        let modified = entry
+            .metadata()
+            .await
+            .and_then(|m| m.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        shots.push((modified, path));
+    }
+    if shots.len() <= keep {
+        return Ok(0);
+    }
+
+    shots.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    let mut removed = 0;
+    for (_, path) in shots.into_iter().skip(keep) {
+        if tokio::fs::remove_file(&path).await.is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Fill `shot.base64` from its file when it is at most `max_bytes`; returns whether it did.
+pub async fn inline(shot: &mut PageScreenshot, max_bytes: usize) -> Result<bool> {
+    if shot.bytes > max_bytes {
+        return Ok(false);
+    }
+    let png = tokio::fs::read(&shot.path)
+        .await
+        .with_context(|| format!("reading {}", shot.path))?;
+    shot.base64 = Some(base64::engine::general_purpose::STANDARD.encode(png));
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn saves_by_content_hash_and_keeps_only_the_newest() {
+        let dir = std::env::temp_dir().join(format!("cortex-scout-shots-{}", uuid::Uuid::new_v4()));
+
+        let first = save_in(&dir, b"png-1", false, 2).await.unwrap();
+        let again = save_in(&dir, b"png-1", false, 2).await.unwrap();
+        assert_eq!(first.path, again.path);
+        assert_eq!(first.bytes, 5);
+
+        for png in [&b"png-2"[..], b"png-3", b"png-4"] {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            save_in(&dir, png, true, 2).await.unwrap();
+        }
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        assert!(!Path::new(&first.path).exists(), "oldest capture is pruned");
+
+        let mut newest = save_in(&dir, b"png-5", true, 2).await.unwrap();
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        assert!(!inline(&mut newest, 4).await.unwrap());
+        assert!(newest.base64.is_none());
+        assert!(inline(&mut newest, 1024).await.unwrap());
+        assert_eq!(newest.base64.as_deref(), Some("cG5nLTU="));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,402 @@
+//! `clean_content` split into sections along its heading structure, for callers that feed
+//! pages into vector stores and would otherwise re-chunk the flat markdown.
+//!
+//! Each heading (ATX `#` or setext underline, never inside a code fence) opens a section
+//! running to the next heading of the same or a higher level, so a section's body includes
+//! its subsections. Text before the first heading is a section of its own. Pages without
+//! headings are cut into windows of whole paragraphs instead. Code blocks are referenced by
+//! their index in `code_blocks`, located by their first line in the markdown.
+
+use crate::rust_scraper::count_words;
+use crate::types::{CodeBlock, ContentSection};
+
+/// Words per paragraph window when the caller does not say.
+pub const DEFAULT_TARGET_WORDS: usize = 300;
+/// Words repeated from the end of one window at the start of the next.
+pub const DEFAULT_OVERLAP_WORDS: usize = 50;
+/// Bounds on a caller-supplied window size.
+const TARGET_WORDS_RANGE: (usize, usize) = (20, 5000);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionOptions {
+    /// Smallest paragraph window; a window only grows past it by one paragraph.
+    pub target_words: usize,
+    /// Most words of trailing paragraphs a window shares with the next.
+    pub overlap_words: usize,
+}
+
+impl Default for SectionOptions {
+    fn default() -> Self {
+        Self {
+            target_words: DEFAULT_TARGET_WORDS,
+            overlap_words: DEFAULT_OVERLAP_WORDS,
+        }
+    }
+}
+
+impl SectionOptions {
+    /// Caller-supplied sizes, defaulted and clamped; the overlap is at most half a window.
+    pub fn new(target_words: Option<usize>, overlap_words: Option<usize>) -> Self {
+        let (min, max) = TARGET_WORDS_RANGE;
+        let target_words = target_words.map_or(DEFAULT_TARGET_WORDS, |n| n.clamp(min, max));
+        Self {
+            target_words,
+            overlap_words: overlap_words
+                .unwrap_or(DEFAULT_OVERLAP_WORDS)
+                .min(target_words / 2),
+        }
+    }
+}
+
+/// A heading line of the markdown: the byte range it covers (underline included) and its level.
+struct HeadingLine {
+    start: usize,
+    end: usize,
+    level: u8,
+    text: String,
+}
+
+/// A line with its byte offset, and whether it belongs to a fenced code block.
+struct Line<'a> {
+    start: usize,
+    text: &'a str,
+    in_fence: bool,
+}
+
+/// Split `markdown` into sections; word counts use `language` like the page's own.
+pub fn split(
+    markdown: &str,
+    code_blocks: &[CodeBlock],
+    language: Option<&str>,
+    options: SectionOptions,
+) -> Vec<ContentSection> {
+    let lines = lines(markdown);
+    let code_offsets = code_block_offsets(markdown, code_blocks);
+    let section = |heading_path: Vec<String>, level: u8, start: usize, end: usize| {
+        let body = markdown[start..end].trim();
+        (!body.is_empty()).then(|| ContentSection {
+            heading_path,
+            level,
+            content: body.to_string(),
+            word_count: count_words(body, language),
+            code_blocks: code_offsets
+                .iter()
+                .filter(|(_, offset)| (start..end).contains(offset))
+                .map(|(index, _)| *index)
+                .collect(),
+        })
+    };
+
+    let headings = heading_lines(&lines);
+    if headings.is_empty() {
+        return paragraph_windows(markdown, &lines, language, options)
+            .into_iter()
+            .filter_map(|(start, end)| section(Vec::new(), 0, start, end))
+            .collect();
+    }
+
+    let mut sections = Vec::new();
+    sections.extend(section(Vec::new(), 0, 0, headings[0].start));
+    let mut path: Vec<(u8, String)> = Vec::new();
+    for (i, heading) in headings.iter().enumerate() {
+        path.retain(|(level, _)| *level < heading.level);
+        path.push((heading.level, heading.text.clone()));
+        let end = headings[i + 1..]
+            .iter()
+            .find(|next| next.level <= heading.level)
+            .map_or(markdown.len(), |next| next.start);
+        let heading_path = path.iter().map(|(_, text)| text.clone()).collect();
+        sections.extend(section(heading_path, heading.level, heading.end, end));
+    }
+    sections
+}
+
+fn lines(markdown: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut start = 0;
+    for text in markdown.split_inclusive('\n') {
+        let trimmed = text.trim_start();
+        let marker = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker));
+        let in_fence = match (fence, marker) {
+            (None, Some(marker)) => {
+                fence = Some(marker);
+                true
+            }
+            (Some(open), Some(marker)) if open == marker => {
+                fence = None;
+                true
+            }
+            (open, _) => open.is_some(),
+        };
+        lines.push(Line {
+            start,
+            text,
+            in_fence,
+        });
+        start += text.len();
+    }
+    lines
+}
+
+fn heading_lines(lines: &[Line<'_>]) -> Vec<HeadingLine> {
+    let mut headings = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        let text = line.text.trim();
+        if line.in_fence || text.is_empty() {
+            i += 1;
+            continue;
+        }
+        let hashes = text.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&hashes) && (text.len() == hashes || text[hashes..].starts_with(' ')) {
+            let title = text[hashes..].trim().trim_end_matches('#').trim();
+            if !title.is_empty() {
+                headings.push(HeadingLine {
+                    start: line.start,
+                    end: line.start + line.text.len(),
+                    level: hashes as u8,
+                    text: title.to_string(),
+                });
+            }
+            i += 1;
+            continue;
+        }
+        let underline = lines
+            .get(i + 1)
+            .filter(|next| !next.in_fence)
+            .and_then(|next| setext_level(next.text.trim()));
+        match underline {
+            Some(level) => {
+                let next = &lines[i + 1];
+                headings.push(HeadingLine {
+                    start: line.start,
+                    end: next.start + next.text.len(),
+                    level,
+                    text: text.to_string(),
+                });
+                i += 2;
+            }
+            None => i += 1,
+        }
+    }
+    headings
+}
+
+/// Level of a setext underline (`===` for 1, `---` for 2).
+fn setext_level(line: &str) -> Option<u8> {
+    if line.len() < 3 {
+        return None;
+    }
+    if line.chars().all(|c| c == '=') {
+        Some(1)
+    } else if line.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Byte ranges of windows of whole paragraphs (a fenced code block is one paragraph).
+fn paragraph_windows(
+    markdown: &str,
+    lines: &[Line<'_>],
+    language: Option<&str>,
+    options: SectionOptions,
+) -> Vec<(usize, usize)> {
+    // (start, end, words) of each paragraph.
+    let mut paragraphs: Vec<(usize, usize, usize)> = Vec::new();
+    let mut open: Option<usize> = None;
+    for line in lines {
+        let blank = !line.in_fence && line.text.trim().is_empty();
+        match (open, blank) {
+            (None, false) => open = Some(line.start),
+            (Some(start), true) => {
+                paragraphs.push((start, line.start, 0));
+                open = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = open {
+        paragraphs.push((start, markdown.len(), 0));
+    }
+    for (start, end, words) in &mut paragraphs {
+        *words = count_words(&markdown[*start..*end], language);
+    }
+
+    let mut windows = Vec::new();
+    let mut first = 0;
+    while first < paragraphs.len() {
+        let mut last = first;
+        let mut words = paragraphs[first].2;
+        while words < options.target_words && last + 1 < paragraphs.len() {
+            last += 1;
+            words += paragraphs[last].2;
+        }
+        windows.push((paragraphs[first].0, paragraphs[last].1));
+        if last + 1 == paragraphs.len() {
+            break;
+        }
+        // Step back over trailing paragraphs that fit the overlap, but always move forward.
+        let mut next = last + 1;
+        let mut overlap = 0;
+        while next > first + 1 && overlap + paragraphs[next - 1].2 <= options.overlap_words {
+            next -= 1;
+            overlap += paragraphs[next].2;
+        }
+        first = next;
+    }
+    windows
+}
+
+/// Byte offset in `markdown` of each code block that can be found there, by its first
+/// non-blank line, searching forward so repeated snippets resolve in order.
+fn code_block_offsets(markdown: &str, code_blocks: &[CodeBlock]) -> Vec<(usize, usize)> {
+    let mut offsets = Vec::new();
+    let mut cursor = 0;
+    for (index, block) in code_blocks.iter().enumerate() {
+        let Some(first_line) = block.code.lines().map(str::trim).find(|l| !l.is_empty()) else {
+            continue;
+        };
+        if let Some(found) = markdown[cursor..].find(first_line) {
+            offsets.push((index, cursor + found));
+            cursor += found + first_line.len();
+        }
+    }
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(code: &str) -> CodeBlock {
+        CodeBlock {
+            language: Some("rust".to_string()),
+            code: code.to_string(),
+            start_char: None,
+            end_char: None,
+        }
+    }
+
+    fn paths(sections: &[ContentSection]) -> Vec<(Vec<&str>, u8)> {
+        sections
+            .iter()
+            .map(|s| {
+                let path = s.heading_path.iter().map(String::as_str).collect();
+                (path, s.level)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nested_headings_build_paths_and_contain_their_subsections() {
+        let markdown = "Intro before any heading.\n\n\
+            # Guide\n\nOverview text.\n\n\
+            ## Installation\n\nPick one.\n\n\
+            ### From source\n\n```rust\nfn main() {}\n```\n\n\
+            ### From crates.io\n\nRun cargo install.\n\n\
+            ## Usage\n\nCall it.\n";
+        let sections = split(
+            markdown,
+            &[code("fn main() {}")],
+            None,
+            SectionOptions::default(),
+        );
+        assert_eq!(
+            paths(&sections),
+            vec![
+                (vec![], 0),
+                (vec!["Guide"], 1),
+                (vec!["Guide", "Installation"], 2),
+                (vec!["Guide", "Installation", "From source"], 3),
+                (vec!["Guide", "Installation", "From crates.io"], 3),
+                (vec!["Guide", "Usage"], 2),
+            ]
+        );
+        assert_eq!(sections[0].content, "Intro before any heading.");
+        assert_eq!(sections[0].word_count, 4);
+
+        let installation = &sections[2];
+        assert!(installation.content.starts_with("Pick one."));
+        assert!(installation.content.contains("### From crates.io"));
+        assert!(!installation.content.contains("Call it."));
+        assert_eq!(installation.code_blocks, vec![0]);
+        assert_eq!(sections[1].code_blocks, vec![0]);
+        assert_eq!(sections[3].code_blocks, vec![0]);
+        assert!(sections[4].code_blocks.is_empty());
+        assert_eq!(sections[5].content, "Call it.");
+    }
+
+    #[test]
+    fn setext_headings_count_and_empty_sections_are_dropped() {
+        let markdown = "Title\n=====\n\nSubtitle\n--------\n\nBody text here.\n";
+        let sections = split(markdown, &[], None, SectionOptions::default());
+        assert_eq!(
+            paths(&sections),
+            vec![(vec!["Title"], 1), (vec!["Title", "Subtitle"], 2)]
+        );
+        assert_eq!(sections[1].content, "Body text here.");
+    }
+
+    #[test]
+    fn pages_without_headings_become_overlapping_paragraph_windows() {
+        let paragraph = |n: usize| format!("p{} {}", n, "word ".repeat(9).trim_end());
+        let markdown = (0..6).map(paragraph).collect::<Vec<_>>().join("\n\n");
+        let options = SectionOptions {
+            target_words: 25,
+            overlap_words: 10,
+        };
+        let sections = split(&markdown, &[], None, options);
+        let firsts: Vec<&str> = sections
+            .iter()
+            .map(|s| s.content.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(firsts, vec!["p0", "p2", "p4"]);
+        assert!(sections
+            .iter()
+            .all(|s| s.level == 0 && s.heading_path.is_empty()));
+        assert_eq!(sections[0].word_count, 30);
+        assert!(sections[1].content.ends_with(&paragraph(4)));
+        assert_eq!(
+            sections[2].content,
+            format!("{}\n\n{}", paragraph(4), paragraph(5))
+        );
+
+        let no_overlap = split(
+            &markdown,
+            &[],
+            None,
+            SectionOptions {
+                target_words: 25,
+                overlap_words: 0,
+            },
+        );
+        assert_eq!(no_overlap.len(), 2);
+    }
+
+    #[test]
+    fn a_page_that_is_one_code_block_is_one_window() {
+        let body = (0..200)
+            .map(|i| format!("# step {}\nlet x{} = {};\n\n", i, i, i))
+            .collect::<String>();
+        let markdown = format!("```python\n{}```\n", body);
+        let sections = split(
+            &markdown,
+            &[code(&body)],
+            None,
+            SectionOptions {
+                target_words: 20,
+                overlap_words: 5,
+            },
+        );
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].level, 0);
+        assert_eq!(sections[0].content, markdown.trim());
+        assert_eq!(sections[0].code_blocks, vec![0]);
+    }
+}
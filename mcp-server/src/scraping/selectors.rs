@@ -0,0 +1,163 @@
+//! Caller-supplied CSS selectors, evaluated against the fetched HTML.
+//!
+//! Runs on whatever markup the scrape ended up with, so static fetches and CDP renders
+//! report the same shape. A selector that fails to parse is reported under its own name
+//! instead of failing the whole call.
+
+use crate::types::{SelectorMatch, SelectorResult};
+use scraper::{ElementRef, Html, Selector};
+use std::collections::BTreeMap;
+use url::Url;
+
+/// Matches kept per selector unless the caller asks for a different cap.
+pub const DEFAULT_MAX_SELECTOR_MATCHES: usize = 20;
+/// Upper bound on a caller-supplied cap.
+pub const MAX_SELECTOR_MATCHES_LIMIT: usize = 500;
+
+/// Evaluate each named selector; names whose matches were capped are returned separately
+/// so callers can surface a warning.
+pub fn evaluate(
+    html: &str,
+    base_url: &str,
+    selectors: &BTreeMap<String, String>,
+    max_matches: usize,
+) -> (BTreeMap<String, SelectorResult>, Vec<String>) {
+    let document = Html::parse_document(html);
+    let base = Url::parse(base_url).ok();
+    let max_matches = max_matches.clamp(1, MAX_SELECTOR_MATCHES_LIMIT);
+
+    let mut results = BTreeMap::new();
+    let mut truncated = Vec::new();
+    for (name, css) in selectors {
+        let selector = match Selector::parse(css) {
+            Ok(selector) => selector,
+            Err(e) => {
+                results.insert(
+                    name.clone(),
+                    SelectorResult::Error {
+                        error: format!("invalid selector {:?}: {}", css, e),
+                    },
+                );
+                continue;
+            }
+        };
+        let mut matches = Vec::new();
+        for element in document.select(&selector) {
+            if matches.len() == max_matches {
+                truncated.push(name.clone());
+                break;
+            }
+            matches.push(to_match(element, base.as_ref()));
+        }
+        results.insert(name.clone(), SelectorResult::Matches(matches));
+    }
+    (results, truncated)
+}
+
+fn to_match(element: ElementRef<'_>, base: Option<&Url>) -> SelectorMatch {
+    let text = element.text().collect::<String>();
+    let attr = |tag: &str, name: &str| {
+        if element.value().name() != tag {
+            return None;
+        }
+        let raw = element.value().attr(name)?.trim();
+        if raw.is_empty() {
+            return None;
+        }
+        Some(
+            base.and_then(|b| b.join(raw).ok())
+                .map(|u| u.to_string())
+                .unwrap_or_else(|| raw.to_string()),
+        )
+    };
+    SelectorMatch {
+        text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+        href: attr("a", "href"),
+        src: attr("img", "src"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><body>
+        <h1>  Product
+            Title </h1>
+        <span class="price">$19.99</span>
+        <a href="/next?page=2">Next <b>page</b></a>
+        <img src="img/a.png" alt="a"><img src="https://cdn.example.com/b.png">
+        <ul><li>one</li><li>two</li><li>three</li></ul>
+    </body></html>"#;
+
+    fn selectors(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn extracts_text_and_resolved_links() {
+        let (results, truncated) = evaluate(
+            PAGE,
+            "https://shop.example.com/items/42",
+            &selectors(&[
+                ("title", "h1"),
+                ("price", ".price"),
+                ("next", "a"),
+                ("images", "img"),
+                ("missing", ".nope"),
+            ]),
+            DEFAULT_MAX_SELECTOR_MATCHES,
+        );
+        assert!(truncated.is_empty());
+        let text = |name: &str| match &results[name] {
+            SelectorResult::Matches(m) => m.clone(),
+            SelectorResult::Error { error } => panic!("{name}: {error}"),
+        };
+        assert_eq!(text("title")[0].text, "Product Title");
+        assert_eq!(text("price")[0].text, "$19.99");
+        assert_eq!(text("price")[0].href, None);
+        assert_eq!(text("next")[0].text, "Next page");
+        assert_eq!(
+            text("next")[0].href.as_deref(),
+            Some("https://shop.example.com/next?page=2")
+        );
+        let images: Vec<_> = text("images").into_iter().filter_map(|m| m.src).collect();
+        assert_eq!(
+            images,
+            [
+                "https://shop.example.com/items/img/a.png",
+                "https://cdn.example.com/b.png"
+            ]
+        );
+        assert!(text("missing").is_empty());
+    }
+
+    #[test]
+    fn caps_matches_and_reports_invalid_selectors_per_name() {
+        let (results, truncated) = evaluate(
+            PAGE,
+            "https://shop.example.com/",
+            &selectors(&[("items", "li"), ("broken", "li[[")]),
+            2,
+        );
+        assert_eq!(truncated, ["items"]);
+        match &results["items"] {
+            SelectorResult::Matches(m) => {
+                assert_eq!(
+                    m.iter().map(|m| m.text.as_str()).collect::<Vec<_>>(),
+                    ["one", "two"]
+                )
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(
+            matches!(&results["broken"], SelectorResult::Error { error } if error.contains("li[["))
+        );
+        let json = serde_json::to_value(&results).unwrap();
+        assert_eq!(json["items"][0], serde_json::json!({"text": "one"}));
+        assert!(json["broken"]["error"].is_string());
+    }
+}
@@ -22,6 +22,10 @@ pub struct SetupOptions {
     pub ping_target: &'static str,
     pub ping_timeout: Duration,
     pub https_probe_url: &'static str,
+    /// Run only the checks with these ids (`None` runs all of them).
+    pub only: Option<Vec<String>>,
+    /// Check ids to leave out, e.g. `port_conflict` when asked by the running server.
+    pub skip: Vec<String>,
 }
 
 impl Default for SetupOptions {
@@ -32,10 +36,21 @@ impl Default for SetupOptions {
             ping_target: "8.8.8.8",
             ping_timeout: Duration::from_secs(2),
             https_probe_url: "https://example.com",
+            only: None,
+            skip: Vec::new(),
         }
     }
 }
 
+impl SetupOptions {
+    fn wants(&self, id: &str) -> bool {
+        self.only
+            .as_ref()
+            .is_none_or(|only| only.iter().any(|o| o == id))
+            && !self.skip.iter().any(|s| s == id)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum CheckStatus {
@@ -79,6 +94,17 @@ impl SetupReport {
         self.checks.iter().any(|c| c.status.is_fail())
     }
 
+    /// `Fail` if any check failed, else `Warn` if any warned, else `Pass` (skips count as pass).
+    pub fn overall_status(&self) -> CheckStatus {
+        if self.has_failures() {
+            CheckStatus::Fail
+        } else if self.checks.iter().any(|c| c.status == CheckStatus::Warn) {
+            CheckStatus::Warn
+        } else {
+            CheckStatus::Pass
+        }
+    }
+
     pub fn summarize_for_logs(&self) -> String {
         let mut pass = 0;
         let mut warn_count = 0;
@@ -154,21 +180,62 @@ impl fmt::Display for SetupReport {
     }
 }
 
+/// `SetupReport` plus the overall verdict, as served by `GET /setup`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetupStatus {
+    pub status: CheckStatus,
+    /// Same line the server logs at startup (`setup: N pass, N warn, ...`).
+    pub summary: String,
+    /// When the checks ran (RFC 3339); older than the request when served from cache.
+    pub checked_at: String,
+    #[serde(flatten)]
+    pub report: SetupReport,
+}
+
+impl SetupStatus {
+    pub fn from_report(report: SetupReport) -> Self {
+        Self {
+            status: report.overall_status(),
+            summary: report.summarize_for_logs(),
+            checked_at: chrono::Utc::now().to_rfc3339(),
+            report,
+        }
+    }
+}
+
 pub async fn check_all(options: SetupOptions) -> SetupReport {
     let mut report = SetupReport::default();
 
-    report.checks.push(check_chrome_installed());
-    report.checks.push(check_storage_dirs());
-    report
-        .checks
-        .push(check_network_ping(options.ping_target, options.ping_timeout).await);
-    report
-        .checks
-        .push(check_https_tls(options.https_probe_url).await);
-    report.checks.push(check_port_available(options.http_port));
+    if options.wants("chrome_installed") {
+        report.checks.push(check_chrome_installed());
+    }
+    if options.wants("storage_dirs") {
+        report.checks.push(check_storage_dirs());
+    }
+    if options.wants("network_ping") {
+        report
+            .checks
+            .push(check_network_ping(options.ping_target, options.ping_timeout).await);
+    }
+    if options.wants("https_tls") {
+        report
+            .checks
+            .push(check_https_tls(options.https_probe_url).await);
+    }
+    if options.wants("port_conflict") {
+        report.checks.push(check_port_available(options.http_port));
+    }
+    if options.wants("tool_policy") {
+        report.checks.push(check_tool_policy());
+    }
 
-    // OS permissions and environment.
-    report.checks.extend(os::check_permissions(&options).await);
+    // OS permissions and environment (cheap; ids vary by platform, so filter afterwards).
+    report.checks.extend(
+        os::check_permissions(&options)
+            .await
+            .into_iter()
+            .filter(|c| options.wants(&c.id)),
+    );
 
     report
 }
@@ -314,26 +381,51 @@ fn check_storage_dirs() -> SetupCheck {
     }
     let _ = std::fs::remove_file(&probe);
 
+    let mut details = if created.is_empty() {
+        format!("Writable: {} and {}", data.display(), logs.display())
+    } else {
+        format!(
+            "Created and verified writable directories:\n{}",
+            created
+                .into_iter()
+                .map(|d| format!("- {}", d))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    };
+    if let Some(pruned) = prune_stale_crawl_state() {
+        details.push_str(&format!("\n{}", pruned));
+    }
+
     SetupCheck {
         id: "storage_dirs".to_string(),
         title: "Storage access (~/.cortex-scout/*)".to_string(),
         status: CheckStatus::Pass,
-        details: if created.is_empty() {
-            format!("Writable: {} and {}", data.display(), logs.display())
-        } else {
-            format!(
-                "Created and verified writable directories:\n{}",
-                created
-                    .into_iter()
-                    .map(|d| format!("- {}", d))
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            )
-        },
+        details,
         actions: vec![],
     }
 }
 
+/// Resumable crawl state past `CORTEX_SCOUT_CRAWL_MAX_AGE_DAYS` is cleared while the
+/// storage check runs; returns a line for the check details when anything was removed.
+fn prune_stale_crawl_state() -> Option<String> {
+    let max_age = crate::core::config::crawl_state_max_age()?;
+    let root = crate::tools::crawl_state::default_crawl_dir()?;
+    match crate::tools::crawl_state::prune_stale_crawls(&root, max_age) {
+        Ok(0) => None,
+        Ok(removed) => Some(format!(
+            "Removed {} stale crawl state director{} from {}",
+            removed,
+            if removed == 1 { "y" } else { "ies" },
+            root.display()
+        )),
+        Err(e) => {
+            warn!("Failed to prune stale crawl state: {}", e);
+            None
+        }
+    }
+}
+
 async fn check_network_ping(target: &str, timeout: Duration) -> SetupCheck {
     let target_owned = target.to_string();
 
@@ -491,3 +583,103 @@ fn check_port_available(port: u16) -> SetupCheck {
         },
     }
 }
+
+fn check_tool_policy() -> SetupCheck {
+    let fail = |details: String| SetupCheck {
+        id: "tool_policy".to_string(),
+        title: "Tool policy (disabled and aliased tools)".to_string(),
+        status: CheckStatus::Fail,
+        details,
+        actions: vec![ActionRequired {
+            title: "Fix the tool policy".to_string(),
+            steps: vec![
+                "Check CORTEX_SCOUT_TOOLS_DISABLED and CORTEX_SCOUT_TOOL_ALIASES.".to_string(),
+                "Check the file named by CORTEX_SCOUT_TOOLS_CONFIG.".to_string(),
+                "Give every alias a name no other tool uses.".to_string(),
+            ],
+            open_url: None,
+        }],
+    };
+    let registry = match crate::core::tools_registry::ToolRegistry::load_for_deployment() {
+        Ok(registry) => registry,
+        Err(e) => return fail(format!("Invalid tool policy: {:#}", e)),
+    };
+
+    let exposed = registry
+        .public_specs()
+        .into_iter()
+        .map(|spec| spec.public_name)
+        .collect::<Vec<_>>();
+    let disabled = registry.disabled_tools().collect::<Vec<_>>();
+    let mut details = format!("Exposed tools ({}): {}", exposed.len(), exposed.join(", "));
+    if !disabled.is_empty() {
+        details.push_str(&format!("\nDisabled: {}", disabled.join(", ")));
+    }
+    let warnings = registry.policy_warnings();
+    for warning in warnings {
+        details.push_str(&format!("\nIgnored: {}", warning));
+    }
+
+    SetupCheck {
+        id: "tool_policy".to_string(),
+        title: "Tool policy (disabled and aliased tools)".to_string(),
+        status: if warnings.is_empty() {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Warn
+        },
+        details,
+        actions: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(id: &str, status: CheckStatus) -> SetupCheck {
+        SetupCheck {
+            id: id.to_string(),
+            title: id.to_string(),
+            status,
+            details: String::new(),
+            actions: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn filtered_run_only_includes_selected_checks() {
+        let options = SetupOptions {
+            only: Some(vec!["chrome_installed".to_string()]),
+            ..Default::default()
+        };
+        let report = check_all(options).await;
+        let ids: Vec<&str> = report.checks.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["chrome_installed"]);
+
+        let skipping = SetupOptions {
+            skip: vec!["port_conflict".to_string()],
+            ..Default::default()
+        };
+        assert!(skipping.wants("network_ping"));
+        assert!(!skipping.wants("port_conflict"));
+    }
+
+    #[test]
+    fn overall_status_is_the_worst_check() {
+        let mut report = SetupReport {
+            checks: vec![check("a", CheckStatus::Pass), check("b", CheckStatus::Skip)],
+        };
+        assert_eq!(report.overall_status(), CheckStatus::Pass);
+        report.checks.push(check("c", CheckStatus::Warn));
+        assert_eq!(report.overall_status(), CheckStatus::Warn);
+        report.checks.push(check("d", CheckStatus::Fail));
+
+        let status = SetupStatus::from_report(report);
+        assert_eq!(status.status, CheckStatus::Fail);
+        assert_eq!(status.summary, "setup: 1 pass, 1 warn, 1 fail, 1 skip");
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["status"], "fail");
+        assert_eq!(json["checks"].as_array().unwrap().len(), 4);
+    }
+}
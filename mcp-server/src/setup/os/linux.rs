@@ -3,11 +3,11 @@ use crate::setup::{CheckStatus, SetupCheck, SetupOptions, SetupRunMode};
 use std::path::Path;
 
 pub async fn check(options: &SetupOptions) -> Vec<SetupCheck> {
-    let mut checks = Vec::new();
-    checks.push(check_display_env());
-    checks.push(check_dialog_helper(options));
-    checks.push(check_input_devices_access(options));
-    checks
+    vec![
+        check_display_env(),
+        check_dialog_helper(options),
+        check_input_devices_access(options),
+    ]
 }
 
 fn command_exists(cmd: &str) -> bool {
@@ -3,8 +3,10 @@ use crate::types::*;
 use crate::AppState;
 use anyhow::Result;
 use futures::stream::{self, StreamExt};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
 /// Scrape multiple URLs concurrently in batch
@@ -16,25 +18,88 @@ pub async fn scrape_batch(
     max_chars: Option<usize>,
     use_proxy: bool,
     quality_mode: Option<QualityMode>,
+) -> Result<ScrapeBatchResponse> {
+    let options = crate::scrape::ScrapeUrlOptions {
+        use_proxy,
+        quality_mode,
+        ..Default::default()
+    };
+    scrape_batch_with_budget(state, urls, max_concurrent, max_chars, options, None).await
+}
+
+/// Same as [`scrape_batch`], but bounded by a total wall-clock `budget`.
+///
+/// URLs still in flight (or not yet started) when the budget runs out are reported
+/// inline with `failure_reason: "budget_exceeded"`, so callers always get the
+/// partial results instead of waiting on a stalled host. Every URL is scraped with a
+/// copy of `options`.
+pub async fn scrape_batch_with_budget(
+    state: &Arc<AppState>,
+    urls: Vec<String>,
+    max_concurrent: usize,
+    max_chars: Option<usize>,
+    options: crate::scrape::ScrapeUrlOptions,
+    budget: Option<Duration>,
 ) -> Result<ScrapeBatchResponse> {
     let start_time = Instant::now();
     let total_urls = urls.len();
+    // buffer_unordered(0) never polls anything; always make progress.
+    let max_concurrent = max_concurrent.max(1);
+    let deadline = budget.map(|b| tokio::time::Instant::now() + b);
 
     info!(
         "Starting batch scrape of {} URLs (concurrency: {})",
         total_urls, max_concurrent
     );
 
+    // A URL listed twice is scraped once; the repeat points at the first listing.
+    let mut first_listed: HashMap<String, String> = HashMap::new();
+    let mut repeats = Vec::new();
+    let mut to_scrape = Vec::new();
+    for (index, url) in urls.into_iter().enumerate() {
+        match first_listed.entry(batch_target(&url, None)) {
+            Entry::Occupied(first) => repeats.push((index, duplicate_result(url, first.get()))),
+            Entry::Vacant(slot) => {
+                slot.insert(url.clone());
+                to_scrape.push((index, url));
+            }
+        }
+    }
+
     // Use futures stream for concurrent scraping with limited concurrency
-    let results: Vec<ScrapeBatchResult> = stream::iter(urls)
-        .map(|url| {
+    let mut failed_so_far = 0;
+    let mut results: Vec<(usize, ScrapeBatchResult)> = stream::iter(to_scrape)
+        .map(|(index, url)| {
             let state = Arc::clone(state);
+            let options = options.clone();
             async move {
                 let url_start = Instant::now();
 
-                match crate::scrape::scrape_url_with_options(&state, &url, use_proxy, quality_mode)
-                    .await
-                {
+                let scrape = crate::scrape::scrape_url_full(&state, &url, options);
+                let outcome = match deadline {
+                    Some(deadline) => match tokio::time::timeout_at(deadline, scrape).await {
+                        Ok(res) => res,
+                        Err(_) => {
+                            warn!("Batch budget exhausted before {} finished", url);
+                            return (
+                                index,
+                                ScrapeBatchResult {
+                                    url,
+                                    success: false,
+                                    data: None,
+                                    error: Some("batch time budget exceeded".to_string()),
+                                    failure_reason: Some("budget_exceeded".to_string()),
+                                    duplicate_of: None,
+                                    duration_ms: url_start.elapsed().as_millis() as u64,
+                                    duplicates_of: Vec::new(),
+                                },
+                            );
+                        }
+                    },
+                    None => scrape.await,
+                };
+
+                let result = match outcome {
                     Ok(mut data) => {
                         data.actual_chars = data.clean_content.len();
 
@@ -73,14 +138,6 @@ pub async fn scrape_batch(
                             );
                         }
 
-                        // Keep batch JSON focused/clean by default (avoid huge <head>/<script> noise)
-                        // Consumers can still use clean_content, headings, links, images, metadata.
-                        data.content.clear();
-                        crate::content_quality::push_warning_unique(
-                            &mut data.warnings,
-                            "raw_html_omitted_in_batch_output",
-                        );
-
                         ScrapeBatchResult {
                             url,
                             success: !treat_as_failure,
@@ -91,7 +148,9 @@ pub async fn scrape_batch(
                                 None
                             },
                             failure_reason,
+                            duplicate_of: None,
                             duration_ms: url_start.elapsed().as_millis() as u64,
+                            duplicates_of: Vec::new(),
                         }
                     }
                     Err(e) => {
@@ -102,16 +161,52 @@ pub async fn scrape_batch(
                             data: None,
                             error: Some(e.to_string()),
                             failure_reason: Some("scrape_error".to_string()),
+                            duplicate_of: None,
                             duration_ms: url_start.elapsed().as_millis() as u64,
+                            duplicates_of: Vec::new(),
                         }
                     }
-                }
+                };
+                (index, result)
             }
         })
         .buffer_unordered(max_concurrent)
+        .enumerate()
+        .map(|(done, (index, result))| {
+            failed_so_far += usize::from(!result.success);
+            crate::core::progress::report_url(
+                done + 1,
+                failed_so_far,
+                Some(total_urls),
+                &result.url,
+            );
+            (index, result)
+        })
         .collect()
         .await;
 
+    // Different URLs can redirect to the same page (shorteners, tracking links); keep
+    // the page once, under the first URL in input order that reached it.
+    results.sort_by_key(|(index, _)| *index);
+    let mut first_reached: HashMap<String, String> = HashMap::new();
+    for (_, result) in results.iter_mut() {
+        if !result.success {
+            continue;
+        }
+        let target = batch_target(&result.url, result.data.as_ref());
+        match first_reached.entry(target) {
+            Entry::Occupied(first) => {
+                *result = duplicate_result(std::mem::take(&mut result.url), first.get());
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(result.url.clone());
+            }
+        }
+    }
+    results.extend(repeats);
+    results.sort_by_key(|(index, _)| *index);
+    let results: Vec<ScrapeBatchResult> = results.into_iter().map(|(_, r)| r).collect();
+
     let successful = results.iter().filter(|r| r.success).count();
     let failed = results.iter().filter(|r| !r.success).count();
 
@@ -122,6 +217,9 @@ pub async fn scrape_batch(
         failed,
         start_time.elapsed().as_millis()
     );
+    state
+        .metrics
+        .record_tool("scrape_batch", true, start_time.elapsed());
 
     Ok(ScrapeBatchResponse {
         total: total_urls,
@@ -129,5 +227,227 @@ pub async fn scrape_batch(
         failed,
         total_duration_ms: start_time.elapsed().as_millis() as u64,
         results,
+        dropped_low_quality: Vec::new(),
+        duplicates: Vec::new(),
     })
 }
+
+/// Move successful pages whose `quality.composite` is below `min_quality` out of
+/// `results` into `dropped_low_quality`, along with repeats of those pages. Pages
+/// without quality signals (PDFs, feeds, GitHub) are kept.
+pub fn apply_min_quality(response: &mut ScrapeBatchResponse, min_quality: f64) {
+    let mut dropped_urls = Vec::new();
+    response.results.retain(|result| {
+        let composite = result
+            .data
+            .as_ref()
+            .and_then(|data| data.quality.as_ref())
+            .map(|quality| quality.composite);
+        match composite {
+            Some(composite) if result.success && composite < min_quality => {
+                response.dropped_low_quality.push(LowQualityPage {
+                    url: result.url.clone(),
+                    quality: composite,
+                });
+                dropped_urls.push(result.url.clone());
+                false
+            }
+            _ => true,
+        }
+    });
+    response.results.retain(|result| {
+        !result
+            .duplicate_of
+            .as_ref()
+            .is_some_and(|first| dropped_urls.contains(first))
+    });
+    response.successful = response.results.iter().filter(|r| r.success).count();
+}
+
+/// The page a batch entry stands for: its final URL after redirects when known,
+/// otherwise the URL as given, without an anchor fragment.
+fn batch_target(url: &str, data: Option<&ScrapeResponse>) -> String {
+    let target = data
+        .and_then(|d| d.final_url.as_deref())
+        .unwrap_or(url)
+        .trim();
+    match url::Url::parse(target) {
+        Ok(mut parsed) => {
+            crate::scraping::fragment_route::strip_anchor(&mut parsed);
+            parsed.to_string()
+        }
+        Err(_) => target.to_string(),
+    }
+}
+
+fn duplicate_result(url: String, first: &str) -> ScrapeBatchResult {
+    ScrapeBatchResult {
+        url,
+        success: true,
+        data: None,
+        error: None,
+        failure_reason: None,
+        duplicate_of: Some(first.to_string()),
+        duration_ms: 0,
+        duplicates_of: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scraped(url: &str, composite: Option<f64>) -> ScrapeBatchResult {
+        let mut data = ScrapeResponse::for_test(url, "", "");
+        data.quality = composite.map(|composite| QualitySignals {
+            reading_level: None,
+            text_to_markup_ratio: 0.2,
+            link_density: 1.0,
+            boilerplate_ratio: 0.3,
+            has_provenance: false,
+            composite,
+        });
+        ScrapeBatchResult {
+            url: url.to_string(),
+            success: true,
+            data: Some(data),
+            error: None,
+            failure_reason: None,
+            duplicate_of: None,
+            duration_ms: 1,
+            duplicates_of: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn min_quality_drops_thin_pages_and_lists_them() {
+        let mut response = ScrapeBatchResponse {
+            total: 4,
+            successful: 4,
+            failed: 0,
+            total_duration_ms: 10,
+            results: vec![
+                scraped("https://example.com/article", Some(0.9)),
+                scraped("https://example.com/farm", Some(0.2)),
+                scraped("https://example.com/report.pdf", None),
+                duplicate_result(
+                    "https://example.com/farm?ref=1".to_string(),
+                    "https://example.com/farm",
+                ),
+            ],
+            dropped_low_quality: Vec::new(),
+            duplicates: Vec::new(),
+        };
+        apply_min_quality(&mut response, 0.5);
+
+        let kept: Vec<_> = response.results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            kept,
+            [
+                "https://example.com/article",
+                "https://example.com/report.pdf"
+            ]
+        );
+        assert_eq!(response.successful, 2);
+        assert_eq!(
+            response.dropped_low_quality,
+            [LowQualityPage {
+                url: "https://example.com/farm".to_string(),
+                quality: 0.2
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn budget_returns_partial_results_inline() {
+        let state = Arc::new(AppState::new(reqwest::Client::new()));
+        // Hold every outbound permit so each scrape stalls at the connection ceiling.
+        let permits = state.outbound_limit.available_permits() as u32;
+        let _held = state
+            .outbound_limit
+            .clone()
+            .acquire_many_owned(permits)
+            .await
+            .unwrap();
+
+        let urls = vec![
+            "https://example.com/a".to_string(),
+            "https://example.com/b".to_string(),
+        ];
+        let response = scrape_batch_with_budget(
+            &state,
+            urls,
+            4,
+            None,
+            crate::scrape::ScrapeUrlOptions::default(),
+            Some(Duration::from_millis(50)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.total, 2);
+        assert_eq!(response.failed, 2);
+        assert!(response
+            .results
+            .iter()
+            .all(|r| r.failure_reason.as_deref() == Some("budget_exceeded")));
+    }
+
+    #[tokio::test]
+    async fn repeated_urls_are_scraped_once() {
+        let state = Arc::new(AppState::new(reqwest::Client::new()));
+        let permits = state.outbound_limit.available_permits() as u32;
+        let _held = state
+            .outbound_limit
+            .clone()
+            .acquire_many_owned(permits)
+            .await
+            .unwrap();
+
+        let urls = vec![
+            "https://example.com/a".to_string(),
+            "https://example.com/b".to_string(),
+            "https://example.com/a#intro".to_string(),
+        ];
+        let response = scrape_batch_with_budget(
+            &state,
+            urls,
+            4,
+            None,
+            crate::scrape::ScrapeUrlOptions::default(),
+            Some(Duration::from_millis(50)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.total, 3);
+        let urls: Vec<_> = response.results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/a",
+                "https://example.com/b",
+                "https://example.com/a#intro"
+            ]
+        );
+        assert_eq!(
+            response.results[2].duplicate_of.as_deref(),
+            Some("https://example.com/a")
+        );
+        assert!(response.results[2].data.is_none());
+        assert_eq!(response.failed, 2);
+    }
+
+    #[test]
+    fn batch_target_prefers_the_final_url() {
+        let page = ScrapeResponse {
+            final_url: Some("https://medium.com/p/1#top".to_string()),
+            ..ScrapeResponse::for_test("https://bit.ly/x", "", "")
+        };
+        assert_eq!(
+            batch_target("https://bit.ly/x", Some(&page)),
+            "https://medium.com/p/1"
+        );
+        assert_eq!(batch_target("https://bit.ly/x", None), "https://bit.ly/x");
+    }
+}
@@ -1,17 +1,49 @@
+use crate::core::error::ScoutError;
+use crate::rerank::Reranker;
 use crate::rust_scraper::QualityMode;
+use crate::scraping::request_auth::RequestAuth;
+use crate::tools::crawl_graph;
+use crate::tools::crawl_state::{self, CrawlRunStatus, CrawlState, CrawlStore, FrontierEntry};
 use crate::types::*;
 use crate::AppState;
 use anyhow::Result;
 use futures::stream::{self, StreamExt};
-use std::collections::{HashSet, VecDeque};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::io::Read;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 use url::Url;
 
+/// A crawl queue entry: (url, depth, link priority, page it was found on).
+type QueuedUrl = (String, usize, f64, Option<String>);
+
+/// Order in which discovered links are fetched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrawlStrategy {
+    /// Level by level, in discovery order.
+    #[default]
+    Breadth,
+    /// Highest `link_priority` first, regardless of depth.
+    Priority,
+}
+
+impl CrawlStrategy {
+    pub fn parse_str(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "breadth" | "bfs" => Some(Self::Breadth),
+            "priority" => Some(Self::Priority),
+            _ => None,
+        }
+    }
+}
+
 /// Configuration for website crawling
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CrawlConfig {
     pub max_depth: usize,
     pub max_pages: usize,
@@ -21,6 +53,35 @@ pub struct CrawlConfig {
     pub exclude_patterns: Vec<String>,
     pub max_chars_per_page: usize,
     pub quality_mode: String,
+    /// Seed the crawl queue from the site's sitemap (index files are followed).
+    pub use_sitemap: bool,
+    /// Explicit sitemap location; defaults to `<origin>/sitemap.xml`.
+    pub sitemap_url: Option<String>,
+    /// Upper bound on sitemap `<loc>` entries considered.
+    pub max_sitemap_entries: usize,
+    /// Per-domain requests/second for this crawl; `None` uses the server-wide rate.
+    pub domain_rate_limit: Option<f64>,
+    /// Share each site's cookie jar across the crawled pages (see `ScrapeUrlOptions`).
+    pub persist_session: bool,
+    pub strategy: CrawlStrategy,
+    /// Credentials for pages on the start URL's host; never sent to other hosts.
+    /// Not saved with the crawl state.
+    #[serde(skip)]
+    pub auth: Option<RequestAuth>,
+    /// Scrape pages in `links_only` mode: the crawl maps the link graph and pages carry
+    /// no content preview.
+    pub links_only: bool,
+    /// Honor robots meta tags and `X-Robots-Tag`: no link expansion from `nofollow`
+    /// pages or through `rel="nofollow"` anchors, and `noindex` pages are left out of
+    /// the results. Off by default.
+    pub respect_robots_meta: bool,
+    /// Pages included in the response's link `graph`, in crawl order.
+    pub max_graph_nodes: usize,
+    /// Identify pages by their `<link rel="canonical">`: a page whose canonical URL was
+    /// already crawled is left out as a duplicate and not expanded, and AMP links are
+    /// queued as the page they mirror. On by default; turn off for sites whose canonical
+    /// tags are wrong.
+    pub respect_canonical: bool,
 }
 
 impl Default for CrawlConfig {
@@ -61,10 +122,152 @@ impl Default for CrawlConfig {
             ],
             max_chars_per_page: 5000,
             quality_mode: "balanced".to_string(),
+            use_sitemap: false,
+            sitemap_url: None,
+            max_sitemap_entries: 1000,
+            domain_rate_limit: None,
+            persist_session: false,
+            strategy: CrawlStrategy::Breadth,
+            auth: None,
+            links_only: false,
+            respect_robots_meta: false,
+            max_graph_nodes: 500,
+            respect_canonical: true,
         }
     }
 }
 
+/// Continue a saved crawl from its frontier instead of starting over.
+#[derive(Clone, Debug, Default)]
+pub struct CrawlResume {
+    pub crawl_id: String,
+    /// Overrides the saved `max_pages` (pages from earlier runs count towards it).
+    pub max_pages: Option<usize>,
+    /// Credentials are not saved, so a resumed crawl needs them passed again.
+    pub auth: Option<RequestAuth>,
+}
+
+/// Return only crawled pages relevant to a query (`content_filter`). Applied to the
+/// finished response, so every fetched page is still cached and saved with the crawl
+/// state; resuming a completed crawl re-filters its pages without fetching them again.
+#[derive(Clone, Debug)]
+pub struct CrawlContentFilter {
+    pub query: String,
+    pub min_score: f32,
+    pub max_pages_returned: Option<usize>,
+}
+
+/// Filtered-out pages listed in the response summary; the rest are only counted.
+const MAX_FILTERED_OUT_LISTED: usize = 50;
+
+/// Canonical collapses listed in the response; the rest are only counted.
+const MAX_CANONICAL_COLLAPSES_LISTED: usize = 20;
+
+/// Why a fetched page is left out of a crawl's `results`.
+enum LeftOut {
+    Noindex,
+    CanonicalDuplicate(CanonicalCollapse),
+}
+
+/// One fetched page: its result, the links it newly queued, and why it is left out of
+/// `results`, if it is.
+type PageOutcome = (CrawlPageResult, Vec<QueuedUrl>, Option<LeftOut>);
+
+impl CrawlContentFilter {
+    pub const DEFAULT_MIN_SCORE: f32 = 0.3;
+
+    /// Score each successful page's title and content preview with the [`Reranker`] and
+    /// keep those at or above `min_score`, best first, up to `max_pages_returned`.
+    pub fn apply(&self, response: &mut CrawlResponse) {
+        let (succeeded, failed): (Vec<_>, Vec<_>) = std::mem::take(&mut response.results)
+            .into_iter()
+            .partition(|page| page.success);
+        let candidates: Vec<SearchResult> = succeeded
+            .iter()
+            .map(|page| SearchResult {
+                url: page.url.clone(),
+                title: page.title.clone().unwrap_or_default(),
+                content: page.content_preview.clone().unwrap_or_default(),
+                ..Default::default()
+            })
+            .collect();
+        let scores = Reranker::new(&self.query).scores(&candidates);
+
+        let round = |score: f32| (score * 1000.0).round() / 1000.0;
+        let mut scored: Vec<(CrawlPageResult, f32)> = succeeded.into_iter().zip(scores).collect();
+        // Stable: equal scores keep crawl order.
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let pages_matched = scored
+            .iter()
+            .filter(|(_, score)| *score >= self.min_score)
+            .count();
+        let returned = pages_matched.min(self.max_pages_returned.unwrap_or(usize::MAX));
+        let rest = scored.split_off(returned);
+
+        info!(
+            "content_filter '{}': {} of {} pages matched, {} returned",
+            self.query,
+            pages_matched,
+            returned + rest.len(),
+            returned
+        );
+        response.results = scored
+            .into_iter()
+            .map(|(mut page, score)| {
+                page.relevance = Some(round(score));
+                page
+            })
+            .collect();
+        response.content_filter = Some(CrawlFilterSummary {
+            query: self.query.clone(),
+            min_score: self.min_score,
+            pages_matched,
+            pages_returned: returned,
+            filtered_out_unlisted: rest.len().saturating_sub(MAX_FILTERED_OUT_LISTED),
+            filtered_out: rest
+                .into_iter()
+                .take(MAX_FILTERED_OUT_LISTED)
+                .map(|(page, score)| CrawlFilteredPage {
+                    url: page.url,
+                    title: page.title,
+                    score: round(score),
+                })
+                .collect(),
+            failed_pages_omitted: failed.len(),
+        });
+    }
+}
+
+/// Move successful pages whose quality composite is below `min_quality` out of
+/// `results` into `dropped_low_quality`. Like [`CrawlContentFilter::apply`] this runs on
+/// the finished crawl, so the saved state and the graph still cover every page.
+pub fn apply_min_quality(response: &mut CrawlResponse, min_quality: f64) {
+    let (dropped, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut response.results)
+        .into_iter()
+        .partition(|page| page.success && page.quality.is_some_and(|q| q < min_quality));
+    info!(
+        "min_quality {}: dropped {} of {} pages",
+        min_quality,
+        dropped.len(),
+        dropped.len() + kept.len()
+    );
+    response.results = kept;
+    response
+        .dropped_low_quality
+        .extend(dropped.into_iter().map(|page| LowQualityPage {
+            url: page.url,
+            quality: page.quality.unwrap_or_default(),
+        }));
+}
+
+enum CrawlStart {
+    New {
+        start_url: String,
+        config: CrawlConfig,
+    },
+    Resume(CrawlResume),
+}
+
 /// Crawl a website recursively, discovering and scraping pages
 /// Optimized to prevent EOF errors by using chunking for large payloads
 pub async fn crawl_website(
@@ -72,8 +275,175 @@ pub async fn crawl_website(
     start_url: &str,
     config: CrawlConfig,
     use_proxy: bool,
+) -> Result<CrawlResponse> {
+    let start = CrawlStart::New {
+        start_url: start_url.to_string(),
+        config,
+    };
+    timed_crawl(state, start, use_proxy).await
+}
+
+/// Pick up a crawl saved under `resume.crawl_id` with its saved settings.
+pub async fn resume_crawl(
+    state: &Arc<AppState>,
+    resume: CrawlResume,
+    use_proxy: bool,
+) -> Result<CrawlResponse> {
+    timed_crawl(state, CrawlStart::Resume(resume), use_proxy).await
+}
+
+async fn timed_crawl(
+    state: &Arc<AppState>,
+    start: CrawlStart,
+    use_proxy: bool,
+) -> Result<CrawlResponse> {
+    let started = Instant::now();
+    let outcome = run_crawl(state, start, use_proxy).await;
+    state
+        .metrics
+        .record_tool("crawl_website", outcome.is_ok(), started.elapsed());
+    outcome
+}
+
+/// State record, saved pages and store (`None` without a crawl state directory) for a
+/// new or resumed crawl.
+async fn open_crawl(
+    start: CrawlStart,
+) -> Result<(CrawlState, Vec<CrawlPageResult>, Option<CrawlStore>)> {
+    let root = crawl_state::default_crawl_dir();
+    let now = chrono::Utc::now().to_rfc3339();
+    match start {
+        CrawlStart::New { start_url, config } => {
+            let crawl_id = crawl_state::new_crawl_id();
+            let store = match &root {
+                Some(root) => Some(CrawlStore::open(root, &crawl_id)?),
+                None => {
+                    warn!(
+                        "No crawl state directory; crawl {} cannot be resumed",
+                        crawl_id
+                    );
+                    None
+                }
+            };
+            let record = CrawlState {
+                crawl_id,
+                start_url,
+                status: CrawlRunStatus::Running,
+                config,
+                visited: Vec::new(),
+                frontier: Vec::new(),
+                sitemap_seeded: Vec::new(),
+                sitemap_urls_found: 0,
+                pages_excluded_noindex: 0,
+                canonical_duplicates_skipped: 0,
+                canonical_collapses: Vec::new(),
+                max_depth_reached: 0,
+                unique_domains: Vec::new(),
+                pages_done: 0,
+                pages_failed: 0,
+                error: None,
+                created_at: now.clone(),
+                updated_at: now,
+            };
+            Ok((record, Vec::new(), store))
+        }
+        CrawlStart::Resume(resume) => {
+            let root = root.ok_or_else(|| {
+                ScoutError::unavailable(
+                    "No crawl state directory (set CORTEX_SCOUT_CRAWL_DIR or HOME) to resume from",
+                )
+            })?;
+            let store = CrawlStore::open(&root, &resume.crawl_id)?;
+            let mut record = store.load_state().await?;
+            let pages = store.load_pages().await?;
+            crawl_state::reconcile_frontier(&mut record.frontier, &pages);
+            if let Some(max_pages) = resume.max_pages {
+                record.config.max_pages = max_pages;
+            }
+            record.config.auth = resume.auth;
+            record.status = CrawlRunStatus::Running;
+            record.error = None;
+            info!(
+                "Resuming crawl {} of {}: {} pages saved, {} queued",
+                record.crawl_id,
+                record.start_url,
+                pages.len(),
+                record.frontier.len()
+            );
+            Ok((record, pages, Some(store)))
+        }
+    }
+}
+
+/// Write the wave's pages, then the state, so a crash in between only re-queues pages
+/// that [`crawl_state::reconcile_frontier`] drops again. Failures are logged, not fatal:
+/// losing resumability should not fail the crawl itself.
+async fn checkpoint(
+    store: Option<&CrawlStore>,
+    record: &mut CrawlState,
+    progress: &CrawlProgress,
+    new_pages: &[CrawlPageResult],
+) {
+    let Some(store) = store else {
+        return;
+    };
+    progress.snapshot_into(record).await;
+    record.updated_at = chrono::Utc::now().to_rfc3339();
+    if let Err(e) = store.append_pages(new_pages).await {
+        warn!("crawl {}: failed to save pages: {}", record.crawl_id, e);
+    }
+    if let Err(e) = store.save_state(record).await {
+        warn!("crawl {}: failed to save state: {}", record.crawl_id, e);
+    }
+}
+
+/// The crawl's shared bookkeeping, as captured by [`checkpoint`].
+struct CrawlProgress {
+    visited: Arc<Mutex<HashSet<String>>>,
+    results: Arc<Mutex<Vec<CrawlPageResult>>>,
+    unique_domains: Arc<Mutex<HashSet<String>>>,
+    queue: Arc<Mutex<VecDeque<QueuedUrl>>>,
+    max_depth_reached: Arc<Mutex<usize>>,
+}
+
+impl CrawlProgress {
+    async fn snapshot_into(&self, record: &mut CrawlState) {
+        record.visited = self.visited.lock().await.iter().cloned().collect();
+        record.visited.sort();
+        record.frontier = self
+            .queue
+            .lock()
+            .await
+            .iter()
+            .map(|(url, depth, priority, parent)| FrontierEntry {
+                url: url.clone(),
+                depth: *depth,
+                priority: *priority,
+                parent: parent.clone(),
+            })
+            .collect();
+        let results = self.results.lock().await;
+        record.pages_done = results.iter().filter(|r| r.success).count();
+        record.pages_failed = results.len() - record.pages_done;
+        record.unique_domains = self.unique_domains.lock().await.iter().cloned().collect();
+        record.unique_domains.sort();
+        record.max_depth_reached = *self.max_depth_reached.lock().await;
+    }
+}
+
+#[tracing::instrument(skip_all, fields(crawl_id = tracing::field::Empty))]
+async fn run_crawl(
+    state: &Arc<AppState>,
+    start: CrawlStart,
+    use_proxy: bool,
 ) -> Result<CrawlResponse> {
     let start_time = Instant::now();
+    let resuming = matches!(start, CrawlStart::Resume(_));
+    let (mut record, saved_pages, store) = open_crawl(start).await?;
+    tracing::Span::current().record("crawl_id", record.crawl_id.as_str());
+    let config = record.config.clone();
+    let start_url = record.start_url.clone();
+    let start_url = start_url.as_str();
 
     // Parse and validate start URL
     let base_url = Url::parse(start_url)?;
@@ -85,37 +455,119 @@ pub async fn crawl_website(
     );
 
     // Track visited URLs and discovered URLs with their depths
-    let visited: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
-    let results: Arc<Mutex<Vec<CrawlPageResult>>> = Arc::new(Mutex::new(Vec::new()));
-    let unique_domains: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
+    let visited: Arc<Mutex<HashSet<String>>> =
+        Arc::new(Mutex::new(record.visited.iter().cloned().collect()));
+    let results: Arc<Mutex<Vec<CrawlPageResult>>> = Arc::new(Mutex::new(saved_pages));
+    let unique_domains: Arc<Mutex<HashSet<String>>> =
+        Arc::new(Mutex::new(record.unique_domains.iter().cloned().collect()));
 
-    // BFS queue: (url, depth)
-    let queue: Arc<Mutex<VecDeque<(String, usize)>>> = Arc::new(Mutex::new(VecDeque::new()));
+    // Crawl queue. Breadth pops in insertion order; priority re-sorts before each wave.
+    let queue: Arc<Mutex<VecDeque<QueuedUrl>>> = Arc::new(Mutex::new(
+        record
+            .frontier
+            .iter()
+            .map(|entry| {
+                (
+                    entry.url.clone(),
+                    entry.depth,
+                    entry.priority,
+                    entry.parent.clone(),
+                )
+            })
+            .collect(),
+    ));
 
     // Add start URL to queue
-    {
+    if !resuming {
         let mut q = queue.lock().await;
-        q.push_back((start_url.to_string(), 0));
-    }
-    {
+        q.push_back((
+            start_url.to_string(),
+            0,
+            link_priority(start_url, "", &base_domain),
+            None,
+        ));
         let mut v = visited.lock().await;
         v.insert(normalize_url(start_url));
     }
 
-    let max_depth_reached: Arc<Mutex<usize>> = Arc::new(Mutex::new(0));
+    // Sitemap seeding: entries go through the same filters and dedup as discovered links.
+    let mut sitemap_seeded: HashSet<String> = record.sitemap_seeded.iter().cloned().collect();
+    let mut sitemap_urls_found = record.sitemap_urls_found;
+    if config.use_sitemap && !resuming {
+        let sitemap_url = config
+            .sitemap_url
+            .clone()
+            .unwrap_or_else(|| format!("{}/sitemap.xml", base_url.origin().ascii_serialization()));
+        let entries = fetch_sitemap_urls(
+            state,
+            &sitemap_url,
+            config.max_sitemap_entries,
+            config.domain_rate_limit,
+        )
+        .await;
+        sitemap_urls_found = entries.len();
+        info!(
+            "Sitemap {} yielded {} URLs",
+            sitemap_url, sitemap_urls_found
+        );
+
+        let mut q = queue.lock().await;
+        let mut v = visited.lock().await;
+        for entry in entries {
+            let entry = crawl_target(entry, &base_domain, &config);
+            if !should_crawl(&entry, &base_domain, &config) {
+                continue;
+            }
+            let normalized = normalize_url(&entry);
+            if v.insert(normalized.clone()) {
+                sitemap_seeded.insert(normalized);
+                let priority = link_priority(&entry, "", &base_domain);
+                q.push_back((entry, 1, priority, None));
+            }
+        }
+    }
+
+    let max_depth_reached: Arc<Mutex<usize>> = Arc::new(Mutex::new(record.max_depth_reached));
+    let mut pages_excluded_noindex = record.pages_excluded_noindex;
+    let mut canonical_duplicates_skipped = record.canonical_duplicates_skipped;
+    let mut canonical_collapses = std::mem::take(&mut record.canonical_collapses);
+    let mut pages_failed_so_far = results.lock().await.iter().filter(|r| !r.success).count();
+
+    let progress = CrawlProgress {
+        visited: Arc::clone(&visited),
+        results: Arc::clone(&results),
+        unique_domains: Arc::clone(&unique_domains),
+        queue: Arc::clone(&queue),
+        max_depth_reached: Arc::clone(&max_depth_reached),
+    };
+    record.sitemap_seeded = sitemap_seeded.iter().cloned().collect();
+    record.sitemap_seeded.sort();
+    record.sitemap_urls_found = sitemap_urls_found;
+    checkpoint(store.as_ref(), &mut record, &progress, &[]).await;
+    if let Some(store) = &store {
+        crate::core::progress::report(
+            results.lock().await.len() + pages_excluded_noindex,
+            Some(config.max_pages),
+            &format!("crawl_id: {}", store.crawl_id()),
+        );
+    }
 
     // Process queue in waves (BFS by depth level)
     loop {
         // Check if we've reached max pages
-        let current_count = results.lock().await.len();
+        let current_count = results.lock().await.len() + pages_excluded_noindex;
         if current_count >= config.max_pages {
             info!("Reached max_pages limit: {}", config.max_pages);
             break;
         }
 
         // Get batch of URLs to process at current depth
-        let batch: Vec<(String, usize)> = {
+        let batch: Vec<QueuedUrl> = {
             let mut q = queue.lock().await;
+            if config.strategy == CrawlStrategy::Priority {
+                // Stable: equal scores keep discovery order.
+                q.make_contiguous().sort_by(|a, b| b.2.total_cmp(&a.2));
+            }
             let remaining = config.max_pages - current_count;
             let batch_size = remaining.min(config.max_concurrent * 2);
 
@@ -140,8 +592,8 @@ pub async fn crawl_website(
         let config_clone = config.clone();
         let base_domain_clone = base_domain.clone();
 
-        let batch_results: Vec<(CrawlPageResult, Vec<(String, usize)>)> = stream::iter(batch)
-            .map(|(url, depth)| {
+        let batch_results: Vec<PageOutcome> = stream::iter(batch)
+            .map(|(url, depth, priority, parent_url)| {
                 let state = Arc::clone(&state_clone);
                 let config = config_clone.clone();
                 let base_domain = base_domain_clone.clone();
@@ -151,6 +603,7 @@ pub async fn crawl_website(
 
                 async move {
                     let page_start = Instant::now();
+                    let priority = (config.strategy == CrawlStrategy::Priority).then_some(priority);
 
                     // Update max depth reached
                     {
@@ -161,15 +614,8 @@ pub async fn crawl_website(
                     }
 
                     // Scrape the page
-                    let quality_mode = QualityMode::parse_str(&config.quality_mode);
-                    match crate::scrape::scrape_url_with_options(
-                        &state,
-                        &url,
-                        use_proxy,
-                        quality_mode,
-                    )
-                    .await
-                    {
+                    let options = page_options(&config, &url, &base_domain, use_proxy);
+                    match crate::scrape::scrape_url_full(&state, &url, options).await {
                         Ok(data) => {
                             // Auth-wall handling: only abort the crawl when the start URL is
                             // genuinely blocked (no real content).  Pages with substantial
@@ -192,8 +638,48 @@ pub async fn crawl_website(
                                     content_preview: None,
                                     error: Some(format!("NEED_HITL: {} (url: {})", reason, url)),
                                     duration_ms: page_start.elapsed().as_millis() as u64,
+                                    priority,
+                                    parent_url,
+                                    status_code: Some(data.status_code),
+                                    outlinks: Vec::new(),
+                                    relevance: None,
+                                    quality: None,
+                                    duplicates_of: Vec::new(),
                                 };
-                                return (result, vec![]);
+                                return (result, vec![], None);
+                            }
+
+                            // A duplicate of a page already crawled is not expanded: the
+                            // canonical page has the same links. The start page always is.
+                            if config.respect_canonical {
+                                let duplicate_of = {
+                                    let mut visited = visited_ref.lock().await;
+                                    canonical_duplicate(&data, &url, &mut visited)
+                                };
+                                if let Some(canonical_url) = duplicate_of.filter(|_| depth > 0) {
+                                    debug!("{} duplicates canonical {}", url, canonical_url);
+                                    let result = CrawlPageResult {
+                                        url: url.clone(),
+                                        depth,
+                                        success: true,
+                                        title: Some(data.title),
+                                        word_count: Some(data.word_count),
+                                        links_found: Some(data.links.len()),
+                                        content_preview: None,
+                                        error: None,
+                                        duration_ms: page_start.elapsed().as_millis() as u64,
+                                        priority,
+                                        parent_url,
+                                        status_code: Some(data.status_code),
+                                        outlinks: Vec::new(),
+                                        relevance: None,
+                                        quality: None,
+                                        duplicates_of: Vec::new(),
+                                    };
+                                    let collapse = CanonicalCollapse { url, canonical_url };
+                                    let left_out = LeftOut::CanonicalDuplicate(collapse);
+                                    return (result, vec![], Some(left_out));
+                                }
                             }
 
                             // Extract domain
@@ -208,38 +694,50 @@ pub async fn crawl_website(
                                 domains.insert(domain);
                             }
 
-                            // Find new links to crawl (only if not at max depth)
-                            let mut new_urls: Vec<(String, usize)> = Vec::new();
-
-                            if depth < config.max_depth {
-                                for link in &data.links {
-                                    if let Some(absolute_url) = resolve_url(&url, &link.url) {
-                                        let normalized = normalize_url(&absolute_url);
-
-                                        // Check if should crawl this URL
-                                        if should_crawl(&absolute_url, &base_domain, &config) {
-                                            let mut visited = visited_ref.lock().await;
-                                            if !visited.contains(&normalized) {
-                                                visited.insert(normalized);
-                                                new_urls.push((absolute_url, depth + 1));
-                                            }
-                                        }
+                            // Record every in-scope link for the graph; queue the unseen
+                            // ones (only if not at max depth).
+                            let mut new_urls: Vec<QueuedUrl> = Vec::new();
+                            let mut outlinks: Vec<CrawlOutlink> = Vec::new();
+                            let mut link_targets: HashSet<String> = HashSet::new();
+
+                            let noindex = config.respect_robots_meta
+                                && has_robots_directive(&data.robots_directives, "noindex");
+                            for link in followable_links(&data, config.respect_robots_meta) {
+                                let Some(absolute_url) = resolve_url(&url, &link.url) else {
+                                    continue;
+                                };
+                                let absolute_url =
+                                    crawl_target(absolute_url, &base_domain, &config);
+                                if !should_crawl(&absolute_url, &base_domain, &config) {
+                                    continue;
+                                }
+                                let normalized = normalize_url(&absolute_url);
+                                if !link_targets.insert(normalized.clone()) {
+                                    continue;
+                                }
+                                let anchor_text = link.text.trim();
+                                outlinks.push(CrawlOutlink {
+                                    url: absolute_url.clone(),
+                                    anchor_text: (!anchor_text.is_empty())
+                                        .then(|| anchor_text.to_string()),
+                                });
+
+                                if depth < config.max_depth {
+                                    let mut visited = visited_ref.lock().await;
+                                    if visited.insert(normalized) {
+                                        let priority =
+                                            link_priority(&absolute_url, &link.text, &base_domain);
+                                        new_urls.push((
+                                            absolute_url,
+                                            depth + 1,
+                                            priority,
+                                            Some(url.clone()),
+                                        ));
                                     }
                                 }
                             }
 
-                            // Truncate content to avoid large payloads (FIX for EOF errors)
-                            let content_preview =
-                                if data.clean_content.len() > config.max_chars_per_page {
-                                    Some(
-                                        data.clean_content
-                                            .chars()
-                                            .take(config.max_chars_per_page)
-                                            .collect(),
-                                    )
-                                } else {
-                                    Some(data.clean_content.clone())
-                                };
+                            let content_preview = content_preview(&config, &data);
 
                             let result = CrawlPageResult {
                                 url: url.clone(),
@@ -251,9 +749,16 @@ pub async fn crawl_website(
                                 content_preview,
                                 error: None,
                                 duration_ms: page_start.elapsed().as_millis() as u64,
+                                priority,
+                                parent_url,
+                                status_code: Some(data.status_code),
+                                outlinks,
+                                relevance: None,
+                                quality: data.quality.map(|quality| quality.composite),
+                                duplicates_of: Vec::new(),
                             };
 
-                            (result, new_urls)
+                            (result, new_urls, noindex.then_some(LeftOut::Noindex))
                         }
                         Err(e) => {
                             warn!("Failed to crawl {}: {}", url, e);
@@ -267,8 +772,15 @@ pub async fn crawl_website(
                                 content_preview: None,
                                 error: Some(e.to_string()),
                                 duration_ms: page_start.elapsed().as_millis() as u64,
+                                priority,
+                                parent_url,
+                                status_code: None,
+                                outlinks: Vec::new(),
+                                relevance: None,
+                                quality: None,
+                                duplicates_of: Vec::new(),
                             };
-                            (result, vec![])
+                            (result, vec![], None)
                         }
                     }
                 }
@@ -279,30 +791,79 @@ pub async fn crawl_website(
 
         // Process results and add new URLs to queue
         // If the start URL hit an auth-wall, abort immediately (HITL required).
-        for (r, _) in batch_results.iter() {
+        for (r, _, _) in batch_results.iter() {
             if r.depth == 0 {
                 if let Some(err) = r.error.as_deref() {
                     if err.starts_with("NEED_HITL:") {
+                        record.status = CrawlRunStatus::Failed;
+                        record.error = Some(err.to_string());
+                        checkpoint(store.as_ref(), &mut record, &progress, &[]).await;
                         return Err(anyhow::anyhow!(err.to_string()));
                     }
                 }
             }
         }
-        for (result, new_urls) in batch_results {
-            results.lock().await.push(result);
+        let mut wave_pages = Vec::new();
+        for (result, new_urls, left_out) in batch_results {
+            let page_url = result.url.clone();
+            pages_failed_so_far += usize::from(!result.success);
+            let crawled = {
+                let mut results = results.lock().await;
+                match left_out {
+                    Some(LeftOut::Noindex) => {
+                        debug!("Excluding noindex page from crawl results: {}", page_url);
+                        pages_excluded_noindex += 1;
+                    }
+                    // Not counted against `max_pages`.
+                    Some(LeftOut::CanonicalDuplicate(collapse)) => {
+                        canonical_duplicates_skipped += 1;
+                        if canonical_collapses.len() < MAX_CANONICAL_COLLAPSES_LISTED {
+                            canonical_collapses.push(collapse);
+                        }
+                    }
+                    None => {
+                        wave_pages.push(result.clone());
+                        results.push(result);
+                    }
+                }
+                results.len() + pages_excluded_noindex
+            };
+            info!(
+                "Crawled page {}/{}: {}",
+                crawled, config.max_pages, page_url
+            );
 
             let mut q = queue.lock().await;
             for url_depth in new_urls {
                 q.push_back(url_depth);
             }
+            // Pages known so far: done plus queued, up to the page limit.
+            let discovered = (crawled + q.len()).min(config.max_pages);
+            drop(q);
+            crate::core::progress::report_url(
+                crawled,
+                pages_failed_so_far,
+                Some(discovered),
+                &page_url,
+            );
         }
+        record.pages_excluded_noindex = pages_excluded_noindex;
+        record.canonical_duplicates_skipped = canonical_duplicates_skipped;
+        record.canonical_collapses = canonical_collapses.clone();
+        checkpoint(store.as_ref(), &mut record, &progress, &wave_pages).await;
     }
+    record.status = CrawlRunStatus::Completed;
+    checkpoint(store.as_ref(), &mut record, &progress, &[]).await;
 
-    let final_results = results.lock().await.clone();
+    let mut final_results = results.lock().await.clone();
     let pages_crawled = final_results.iter().filter(|r| r.success).count();
     let pages_failed = final_results.iter().filter(|r| !r.success).count();
     let final_max_depth = *max_depth_reached.lock().await;
     let domains: Vec<String> = unique_domains.lock().await.iter().cloned().collect();
+    let pages_from_sitemap = final_results
+        .iter()
+        .filter(|r| sitemap_seeded.contains(&normalize_url(&r.url)))
+        .count();
 
     // Generate sitemap (all successfully crawled URLs)
     let sitemap: Vec<String> = final_results
@@ -311,6 +872,11 @@ pub async fn crawl_website(
         .map(|r| r.url.clone())
         .collect();
 
+    let graph = crawl_graph::build(&final_results, config.max_graph_nodes);
+    for page in &mut final_results {
+        page.outlinks.clear();
+    }
+
     info!(
         "Crawl completed: {} pages crawled, {} failed, max depth {}, {}ms total",
         pages_crawled,
@@ -320,21 +886,475 @@ pub async fn crawl_website(
     );
 
     Ok(CrawlResponse {
+        crawl_id: store.map(|store| store.crawl_id().to_string()),
         start_url: start_url.to_string(),
         pages_crawled,
         pages_failed,
         max_depth_reached: final_max_depth,
         total_duration_ms: start_time.elapsed().as_millis() as u64,
         unique_domains: domains,
+        pages_from_links: final_results.len() - pages_from_sitemap,
+        pages_from_sitemap,
+        sitemap_urls_found,
+        pages_excluded_noindex,
         results: final_results,
         sitemap: Some(sitemap),
+        graph: Some(graph),
+        content_filter: None,
+        plan: None,
+        dropped_low_quality: Vec::new(),
+        duplicates: Vec::new(),
+        canonical_duplicates_skipped,
+        canonical_collapses,
     })
 }
 
-/// Normalize URL for deduplication (remove fragments, trailing slashes, etc.)
-fn normalize_url(url: &str) -> String {
+/// Scrape options for one crawled page; credentials only go to the start URL's host.
+fn page_options(
+    config: &CrawlConfig,
+    url: &str,
+    base_domain: &str,
+    use_proxy: bool,
+) -> crate::scrape::ScrapeUrlOptions {
+    crate::scrape::ScrapeUrlOptions {
+        use_proxy,
+        quality_mode: QualityMode::parse_str(&config.quality_mode),
+        domain_rate_limit: config.domain_rate_limit,
+        persist_session: config.persist_session,
+        auth: config.auth.clone().filter(|_| {
+            Url::parse(url)
+                .ok()
+                .is_some_and(|u| u.host_str() == Some(base_domain))
+        }),
+        links_only: config.links_only,
+        ..Default::default()
+    }
+}
+
+/// The page's content truncated to `max_chars_per_page` to keep payloads small (FIX for
+/// EOF errors); `None` for `links_only` crawls.
+fn content_preview(config: &CrawlConfig, page: &ScrapeResponse) -> Option<String> {
+    if config.links_only {
+        None
+    } else if page.clean_content.len() > config.max_chars_per_page {
+        Some(
+            page.clean_content
+                .chars()
+                .take(config.max_chars_per_page)
+                .collect(),
+        )
+    } else {
+        Some(page.clean_content.clone())
+    }
+}
+
+/// Matched URLs a dry run lists in `sample_urls`.
+const DRY_RUN_SAMPLE_URLS: usize = 50;
+
+/// Sections listed in a dry run's `structure`.
+const DRY_RUN_MAX_SECTIONS: usize = 20;
+
+/// Candidate URLs of a dry run, deduplicated like the crawl's `visited` set.
+#[derive(Default)]
+struct DryRunFrontier {
+    seen: HashSet<String>,
+    /// (url, link priority), in discovery order.
+    matched: Vec<(String, f64)>,
+    excluded_by_rule: BTreeMap<String, usize>,
+}
+
+impl DryRunFrontier {
+    fn consider(&mut self, url: String, priority: f64, exclusion: Option<String>) {
+        if !self.seen.insert(normalize_url(&url)) {
+            return;
+        }
+        match exclusion {
+            Some(rule) => *self.excluded_by_rule.entry(rule).or_default() += 1,
+            None => self.matched.push((url, priority)),
+        }
+    }
+}
+
+/// Estimate a crawl's scope without running it (`dry_run`): fetch only the seed page, and
+/// the sitemap with `use_sitemap`, and run the candidates through the crawl's filters.
+/// The response has the seed as its only result and the estimate in `plan`. Nothing is
+/// saved, so there is no `crawl_id`; the same `start_url` and `config` can be passed to
+/// [`crawl_website`] to proceed.
+pub async fn plan_crawl(
+    state: &Arc<AppState>,
+    start_url: &str,
+    config: CrawlConfig,
+    use_proxy: bool,
+) -> Result<CrawlResponse> {
+    let start_time = Instant::now();
+    let base_url = Url::parse(start_url)?;
+    let base_domain = base_url.host_str().unwrap_or("").to_string();
+    info!(
+        "Planning crawl of {} (max_depth: {}, max_pages: {})",
+        start_url, config.max_depth, config.max_pages
+    );
+
+    let mut frontier = DryRunFrontier::default();
+    frontier.seen.insert(normalize_url(start_url));
+
+    // Sitemap entries are queued ahead of the seed's links, as in `run_crawl`.
+    let mut sitemap_urls_found = 0;
+    if config.use_sitemap {
+        let sitemap_url = config
+            .sitemap_url
+            .clone()
+            .unwrap_or_else(|| format!("{}/sitemap.xml", base_url.origin().ascii_serialization()));
+        let entries = fetch_sitemap_urls(
+            state,
+            &sitemap_url,
+            config.max_sitemap_entries,
+            config.domain_rate_limit,
+        )
+        .await;
+        sitemap_urls_found = entries.len();
+        for entry in entries {
+            let entry = crawl_target(entry, &base_domain, &config);
+            let exclusion = crawl_exclusion(&entry, &base_domain, &config);
+            let priority = link_priority(&entry, "", &base_domain);
+            frontier.consider(entry, priority, exclusion);
+        }
+    }
+
+    let page_start = Instant::now();
+    let options = page_options(&config, start_url, &base_domain, use_proxy);
+    let seed = match crate::scrape::scrape_url_full(state, start_url, options).await {
+        Ok(data) => {
+            let is_auth_walled = data.auth_wall_reason.is_some()
+                || data.warnings.iter().any(|w| w == "content_restricted");
+            if is_auth_walled && data.word_count < 50 {
+                let reason = data.auth_wall_reason.clone().unwrap_or_else(|| {
+                    "Auth-Wall detected (login page returned HTTP 200)".to_string()
+                });
+                return Err(anyhow::anyhow!(
+                    "NEED_HITL: {} (url: {})",
+                    reason,
+                    start_url
+                ));
+            }
+            let followable: HashSet<&str> = followable_links(&data, config.respect_robots_meta)
+                .iter()
+                .map(|link| link.url.as_str())
+                .collect();
+            for link in &data.links {
+                let Some(absolute_url) = resolve_url(start_url, &link.url) else {
+                    continue;
+                };
+                let absolute_url = crawl_target(absolute_url, &base_domain, &config);
+                let exclusion = if !followable.contains(link.url.as_str()) {
+                    Some("nofollow".to_string())
+                } else if config.max_depth == 0 {
+                    Some("max_depth".to_string())
+                } else {
+                    crawl_exclusion(&absolute_url, &base_domain, &config)
+                };
+                let priority = link_priority(&absolute_url, &link.text, &base_domain);
+                frontier.consider(absolute_url, priority, exclusion);
+            }
+            CrawlPageResult {
+                url: start_url.to_string(),
+                depth: 0,
+                success: true,
+                title: Some(data.title.clone()),
+                word_count: Some(data.word_count),
+                links_found: Some(data.links.len()),
+                content_preview: content_preview(&config, &data),
+                error: None,
+                duration_ms: page_start.elapsed().as_millis() as u64,
+                priority: None,
+                parent_url: None,
+                status_code: Some(data.status_code),
+                outlinks: Vec::new(),
+                relevance: None,
+                quality: None,
+                duplicates_of: Vec::new(),
+            }
+        }
+        Err(e) => {
+            warn!("Dry run could not fetch {}: {}", start_url, e);
+            CrawlPageResult {
+                url: start_url.to_string(),
+                depth: 0,
+                success: false,
+                title: None,
+                word_count: None,
+                links_found: None,
+                content_preview: None,
+                error: Some(e.to_string()),
+                duration_ms: page_start.elapsed().as_millis() as u64,
+                priority: None,
+                parent_url: None,
+                status_code: None,
+                outlinks: Vec::new(),
+                relevance: None,
+                quality: None,
+                duplicates_of: Vec::new(),
+            }
+        }
+    };
+
+    let mut matched = frontier.matched;
+    if config.strategy == CrawlStrategy::Priority {
+        // Stable: equal scores keep discovery order.
+        matched.sort_by(|a, b| b.1.total_cmp(&a.1));
+    }
+    let matched: Vec<String> = matched.into_iter().map(|(url, _)| url).collect();
+    let plan = CrawlPlan {
+        estimated_pages: (1 + matched.len()).min(config.max_pages),
+        estimate_is_lower_bound: config.max_depth > 1 && 1 + matched.len() < config.max_pages,
+        candidates_matched: matched.len(),
+        candidates_excluded: frontier.excluded_by_rule.values().sum(),
+        excluded_by_rule: frontier.excluded_by_rule,
+        sample_urls: matched.iter().take(DRY_RUN_SAMPLE_URLS).cloned().collect(),
+        structure: crawl_structure(&matched),
+    };
+    info!(
+        "Crawl plan for {}: ~{} pages, {} candidates matched, {} excluded",
+        start_url, plan.estimated_pages, plan.candidates_matched, plan.candidates_excluded
+    );
+
+    Ok(CrawlResponse {
+        crawl_id: None,
+        start_url: start_url.to_string(),
+        pages_crawled: usize::from(seed.success),
+        pages_failed: usize::from(!seed.success),
+        max_depth_reached: 0,
+        total_duration_ms: start_time.elapsed().as_millis() as u64,
+        unique_domains: if seed.success {
+            vec![base_domain]
+        } else {
+            Vec::new()
+        },
+        sitemap_urls_found,
+        pages_from_sitemap: 0,
+        pages_from_links: 1,
+        pages_excluded_noindex: 0,
+        results: vec![seed],
+        sitemap: None,
+        graph: None,
+        content_filter: None,
+        plan: Some(plan),
+        dropped_low_quality: Vec::new(),
+        duplicates: Vec::new(),
+        canonical_duplicates_skipped: 0,
+        canonical_collapses: Vec::new(),
+    })
+}
+
+/// Path segments that introduce a category/tag style listing.
+const CATEGORY_SEGMENTS: &[&str] = &[
+    "category",
+    "categories",
+    "tag",
+    "tags",
+    "topic",
+    "topics",
+    "archive",
+    "archives",
+];
+
+/// Query parameters that number the pages of a listing.
+const PAGINATION_PARAMS: &[&str] = &["page", "p", "pg", "paged", "offset", "start"];
+
+/// The pagination scheme of `url`, if it is a numbered listing page: a `page`/`p` path
+/// segment followed by a number, or a numeric [`PAGINATION_PARAMS`] query parameter.
+fn pagination_pattern(url: &Url) -> Option<String> {
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segs| segs.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    for pair in segments.windows(2) {
+        let marker = pair[0].to_ascii_lowercase();
+        if matches!(marker.as_str(), "page" | "p") && pair[1].parse::<u32>().is_ok() {
+            return Some(format!("path:/{}/{{n}}", marker));
+        }
+    }
+    url.query_pairs()
+        .find(|(key, value)| {
+            PAGINATION_PARAMS.contains(&key.to_ascii_lowercase().as_str())
+                && value.parse::<u32>().is_ok()
+        })
+        .map(|(key, _)| format!("query:{}", key.to_ascii_lowercase()))
+}
+
+/// Sections, pagination and category listings among a dry run's matched URLs.
+fn crawl_structure(urls: &[String]) -> CrawlStructure {
+    let mut sections: HashMap<String, usize> = HashMap::new();
+    let mut patterns: BTreeMap<String, usize> = BTreeMap::new();
+    let mut structure = CrawlStructure::default();
+
+    for url in urls {
+        let Ok(parsed) = Url::parse(url) else {
+            continue;
+        };
+        let first_segment = parsed
+            .path_segments()
+            .and_then(|mut segs| segs.find(|s| !s.is_empty()))
+            .map(|s| s.to_ascii_lowercase());
+        let prefix = first_segment
+            .as_deref()
+            .map_or_else(|| "/".to_string(), |s| format!("/{}", s));
+        *sections.entry(prefix).or_default() += 1;
+
+        if let Some(pattern) = pagination_pattern(&parsed) {
+            structure.pagination_urls += 1;
+            *patterns.entry(pattern).or_default() += 1;
+        }
+        let in_category = parsed.path_segments().is_some_and(|mut segs| {
+            segs.any(|s| CATEGORY_SEGMENTS.contains(&s.to_ascii_lowercase().as_str()))
+        });
+        if in_category {
+            structure.category_urls += 1;
+        }
+    }
+
+    let mut sections: Vec<CrawlSection> = sections
+        .into_iter()
+        .map(|(prefix, urls)| CrawlSection { prefix, urls })
+        .collect();
+    sections.sort_by(|a, b| b.urls.cmp(&a.urls).then_with(|| a.prefix.cmp(&b.prefix)));
+    sections.truncate(DRY_RUN_MAX_SECTIONS);
+    structure.sections = sections;
+
+    let mut patterns: Vec<(String, usize)> = patterns.into_iter().collect();
+    patterns.sort_by(|a, b| b.1.cmp(&a.1));
+    structure.pagination_patterns = patterns.into_iter().map(|(pattern, _)| pattern).collect();
+    structure
+}
+
+fn has_robots_directive(directives: &[String], directive: &str) -> bool {
+    directives.iter().any(|d| d == directive)
+}
+
+/// Links eligible for the crawl frontier. With `respect_robots_meta`, a `nofollow`
+/// page contributes none and `rel="nofollow"` anchors are skipped.
+fn followable_links(page: &ScrapeResponse, respect_robots_meta: bool) -> Vec<&Link> {
+    if !respect_robots_meta {
+        return page.links.iter().collect();
+    }
+    if has_robots_directive(&page.robots_directives, "nofollow") {
+        return Vec::new();
+    }
+    page.links.iter().filter(|link| !link.nofollow).collect()
+}
+
+/// Sitemap documents followed per crawl (guards against index loops and huge index trees).
+const MAX_SITEMAP_DOCUMENTS: usize = 50;
+
+/// Fetch a sitemap (or sitemap index, recursively) and return up to `max_entries` page URLs.
+async fn fetch_sitemap_urls(
+    state: &Arc<AppState>,
+    sitemap_url: &str,
+    max_entries: usize,
+    domain_rate_limit: Option<f64>,
+) -> Vec<String> {
+    let mut pending: VecDeque<String> = VecDeque::from([sitemap_url.to_string()]);
+    let mut seen_sitemaps: HashSet<String> = HashSet::new();
+    let mut urls: Vec<String> = Vec::new();
+
+    while let Some(next) = pending.pop_front() {
+        if urls.len() >= max_entries || seen_sitemaps.len() >= MAX_SITEMAP_DOCUMENTS {
+            break;
+        }
+        if !seen_sitemaps.insert(next.clone()) {
+            continue;
+        }
+
+        state.domain_limiter.acquire(&next, domain_rate_limit).await;
+        let body = {
+            let _permit = state
+                .outbound_limit
+                .acquire()
+                .await
+                .expect("semaphore closed");
+            match fetch_sitemap_body(&crate::core::http_client::scrape_client(), &next).await {
+                Ok(body) => body,
+                Err(e) => {
+                    warn!("Failed to fetch sitemap {}: {}", next, e);
+                    continue;
+                }
+            }
+        };
+
+        match parse_sitemap(&body) {
+            SitemapDocument::Index(children) => pending.extend(children),
+            SitemapDocument::UrlSet(locs) => {
+                let room = max_entries - urls.len();
+                urls.extend(locs.into_iter().take(room));
+            }
+        }
+    }
+
+    urls
+}
+
+async fn fetch_sitemap_body(client: &reqwest::Client, url: &str) -> Result<String> {
+    let resp = crate::scraping::domain_policy::get_checked(url, |hop| {
+        client.get(hop).timeout(Duration::from_secs(20))
+    })
+    .await?
+    .error_for_status()?;
+    let bytes = resp.bytes().await?;
+    decode_sitemap_bytes(&bytes)
+}
+
+/// `.xml.gz` sitemaps are usually served as opaque gzip files (not Content-Encoding),
+/// so detect the gzip magic bytes and inflate them ourselves.
+fn decode_sitemap_bytes(bytes: &[u8]) -> Result<String> {
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut xml = String::new();
+        flate2::read::GzDecoder::new(bytes).read_to_string(&mut xml)?;
+        Ok(xml)
+    } else {
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum SitemapDocument {
+    Index(Vec<String>),
+    UrlSet(Vec<String>),
+}
+
+fn parse_sitemap(xml: &str) -> SitemapDocument {
+    static LOC_RE: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    let re = LOC_RE.get_or_init(|| {
+        regex::Regex::new(
+            r"(?is)<(?:[a-z0-9]+:)?loc>\s*(?:<!\[CDATA\[)?(.*?)(?:\]\]>)?\s*</(?:[a-z0-9]+:)?loc>",
+        )
+        .expect("valid sitemap loc regex")
+    });
+
+    let locs: Vec<String> = re
+        .captures_iter(xml)
+        .filter_map(|c| c.get(1))
+        .map(|m| {
+            m.as_str()
+                .trim()
+                .replace("&amp;", "&")
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+        })
+        .filter(|loc| loc.starts_with("http://") || loc.starts_with("https://"))
+        .collect();
+
+    if xml.to_ascii_lowercase().contains("<sitemapindex") {
+        SitemapDocument::Index(locs)
+    } else {
+        SitemapDocument::UrlSet(locs)
+    }
+}
+
+/// Normalize URL for deduplication (remove anchor fragments, trailing slashes, etc.)
+pub(crate) fn normalize_url(url: &str) -> String {
     if let Ok(mut parsed) = Url::parse(url) {
-        parsed.set_fragment(None);
+        crate::scraping::fragment_route::strip_anchor(&mut parsed);
         // Strip query strings where every param has an empty value (e.g. "?search=")
         // to prevent crawling duplicate pages that differ only by a vacuous query string.
         if parsed.query().map(|q| q.split('&').all(|kv| {
@@ -354,13 +1374,83 @@ fn normalize_url(url: &str) -> String {
     }
 }
 
+/// The canonical URL `page` declares when it names a page other than the one fetched
+/// and that page is already in `visited`; otherwise the canonical is added to `visited`,
+/// so later pages declaring it count as its duplicates.
+fn canonical_duplicate(
+    page: &ScrapeResponse,
+    url: &str,
+    visited: &mut HashSet<String>,
+) -> Option<String> {
+    let canonical = resolve_url(url, page.canonical_url.as_deref()?)?;
+    let key = normalize_url(&canonical);
+    let fetched = std::iter::once(url).chain(page.final_url.as_deref());
+    if fetched.map(normalize_url).any(|fetched| fetched == key) {
+        return None;
+    }
+    (!visited.insert(key)).then_some(canonical)
+}
+
+/// The non-AMP page an AMP URL mirrors, when the pattern is unambiguous: an `amp.`
+/// subdomain of the crawled host, and/or a single `amp` first or last path segment
+/// with other segments left (a lone `/amp` may well be a real page).
+fn amp_canonical(url: &str, base_domain: &str) -> Option<String> {
+    let mut parsed = Url::parse(url).ok()?;
+    let mut changed = false;
+
+    let host = parsed.host_str().unwrap_or("").to_string();
+    if let Some(stripped) = host.strip_prefix("amp.") {
+        let bare_base = base_domain.strip_prefix("www.").unwrap_or(base_domain);
+        if stripped == base_domain || stripped == bare_base {
+            parsed.set_host(Some(base_domain)).ok()?;
+            changed = true;
+        }
+    }
+
+    let trailing_slash = parsed.path().ends_with('/');
+    let segments: Vec<&str> = parsed.path().split('/').filter(|s| !s.is_empty()).collect();
+    let amp_segments = segments
+        .iter()
+        .filter(|s| s.eq_ignore_ascii_case("amp"))
+        .count();
+    let at_edge = |s: Option<&&str>| s.is_some_and(|s| s.eq_ignore_ascii_case("amp"));
+    if amp_segments == 1
+        && segments.len() > 1
+        && (at_edge(segments.first()) || at_edge(segments.last()))
+    {
+        let kept: Vec<&str> = segments
+            .into_iter()
+            .filter(|s| !s.eq_ignore_ascii_case("amp"))
+            .collect();
+        let mut path = format!("/{}", kept.join("/"));
+        if trailing_slash {
+            path.push('/');
+        }
+        parsed.set_path(&path);
+        changed = true;
+    }
+
+    changed.then(|| parsed.to_string())
+}
+
+/// `url` as the crawl fetches it: its canonical page when it is an AMP URL and the
+/// crawl respects canonicals.
+fn crawl_target(url: String, base_domain: &str, config: &CrawlConfig) -> String {
+    if !config.respect_canonical {
+        return url;
+    }
+    amp_canonical(&url, base_domain).unwrap_or(url)
+}
+
 /// Resolve a potentially relative URL to absolute
 fn resolve_url(base: &str, href: &str) -> Option<String> {
     // Skip javascript:, mailto:, tel:, etc.
     if href.starts_with("javascript:")
         || href.starts_with("mailto:")
         || href.starts_with("tel:")
-        || href.starts_with("#")
+        || href
+            .strip_prefix('#')
+            .is_some_and(|fragment| !crate::scraping::fragment_route::is_route(fragment))
         || href.starts_with("data:")
     {
         return None;
@@ -377,30 +1467,116 @@ fn resolve_url(base: &str, href: &str) -> Option<String> {
     None
 }
 
+/// Paths that rarely hold content worth a crawl slot.
+const NOISE_PATH_MARKERS: &[&str] = &[
+    "/login",
+    "/signin",
+    "/sign-in",
+    "/logout",
+    "/signup",
+    "/register",
+    "/account",
+    "/cart",
+    "/checkout",
+    "/tag/",
+    "/tags/",
+    "/category/",
+    "/author/",
+    "/page/",
+    "/search",
+    "/share",
+];
+
+/// Documentation-ish words not covered by the search reranker's breadcrumb keywords.
+const EXTRA_DOC_KEYWORDS: &[&str] = &["guide", "tutorial", "getting-started", "handbook"];
+
+/// Score a candidate link for `CrawlStrategy::Priority`: higher is fetched sooner.
+///
+/// Same-domain links and documentation-looking paths or anchor texts earn a bonus;
+/// deep paths, query-string-heavy URLs and noise paths (login, cart, tag archives, ...)
+/// are penalised.
+fn link_priority(url: &str, anchor_text: &str, base_domain: &str) -> f64 {
+    let Ok(parsed) = Url::parse(url) else {
+        return f64::MIN;
+    };
+    let host = parsed.host_str().unwrap_or("");
+    let mut score = if host == base_domain {
+        2.0
+    } else if host.ends_with(&format!(".{}", base_domain)) {
+        1.0
+    } else {
+        0.0
+    };
+
+    let segments: Vec<String> = parsed
+        .path_segments()
+        .map(|segs| {
+            segs.filter(|s| !s.is_empty())
+                .map(|s| s.to_ascii_lowercase())
+                .collect()
+        })
+        .unwrap_or_default();
+    score -= 0.5 * segments.len() as f64;
+
+    let mut crumbs = segments;
+    if !anchor_text.trim().is_empty() {
+        crumbs.push(anchor_text.trim().to_ascii_lowercase());
+    }
+    if crate::search::breadcrumbs_have_high_value_keywords(&crumbs)
+        || crumbs
+            .iter()
+            .any(|c| EXTRA_DOC_KEYWORDS.iter().any(|k| c.contains(k)))
+    {
+        score += 3.0;
+    }
+
+    let query_params = parsed.query_pairs().count();
+    score -= (0.5 * query_params as f64).min(2.0);
+
+    let path = parsed.path().to_ascii_lowercase();
+    if NOISE_PATH_MARKERS.iter().any(|m| path.contains(m)) {
+        score -= 4.0;
+    }
+
+    score
+}
+
 /// Check if a URL should be crawled based on configuration
 fn should_crawl(url: &str, base_domain: &str, config: &CrawlConfig) -> bool {
-    let parsed = match Url::parse(url) {
-        Ok(u) => u,
-        Err(_) => return false,
+    crawl_exclusion(url, base_domain, config).is_none()
+}
+
+/// The rule that keeps `url` out of the crawl (as reported in a dry run's
+/// `excluded_by_rule`), or `None` when it is in scope.
+fn crawl_exclusion(url: &str, base_domain: &str, config: &CrawlConfig) -> Option<String> {
+    let Ok(parsed) = Url::parse(url) else {
+        return Some("invalid_url".to_string());
     };
 
     let url_domain = parsed.host_str().unwrap_or("");
 
+    // Links the domain policy refuses are dropped like any other filtered link.
+    if !crate::scraping::domain_policy::allows(url) {
+        return Some("domain_policy".to_string());
+    }
+
     // Check same domain constraint
     if config.same_domain_only {
         // Allow subdomains (e.g., docs.example.com when base is example.com)
         if !url_domain.ends_with(base_domain) && url_domain != base_domain {
-            return false;
+            return Some("other_domain".to_string());
         }
     }
 
     let url_lower = url.to_lowercase();
 
     // Check exclude patterns
-    for pattern in &config.exclude_patterns {
-        if url_lower.contains(&pattern.to_lowercase()) {
-            return false;
-        }
+    if let Some(pattern) = config
+        .exclude_patterns
+        .iter()
+        .find(|pattern| url_lower.contains(&pattern.to_lowercase()))
+    {
+        return Some(format!("exclude:{}", pattern));
     }
 
     // Check include patterns (if specified, URL must match at least one)
@@ -410,9 +1586,354 @@ fn should_crawl(url: &str, base_domain: &str, config: &CrawlConfig) -> bool {
             .iter()
             .any(|p| url_lower.contains(&p.to_lowercase()));
         if !matches_include {
-            return false;
+            return Some("no_include_match".to_string());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn followable_links_honor_robots_meta_only_when_asked() {
+        let link = |url: &str, text: &str, nofollow| Link {
+            url: url.to_string(),
+            text: text.to_string(),
+            nofollow,
+            detail: None,
+        };
+        let mut page = ScrapeResponse {
+            links: vec![
+                link("https://example.com/a", "A", true),
+                link("https://example.com/b", "B", false),
+            ],
+            ..ScrapeResponse::for_test("https://example.com/", "Home", "")
+        };
+
+        assert_eq!(followable_links(&page, false).len(), 2);
+        let followed: Vec<_> = followable_links(&page, true)
+            .iter()
+            .map(|l| l.url.as_str())
+            .collect();
+        assert_eq!(followed, ["https://example.com/b"]);
+
+        page.robots_directives = vec!["noindex".to_string(), "nofollow".to_string()];
+        assert!(followable_links(&page, true).is_empty());
+        assert_eq!(followable_links(&page, false).len(), 2);
+    }
+
+    #[test]
+    fn pages_declaring_a_crawled_canonical_are_duplicates() {
+        let page = |url: &str, canonical: Option<&str>| ScrapeResponse {
+            canonical_url: canonical.map(str::to_string),
+            ..ScrapeResponse::for_test(url, "Shoes", "")
+        };
+        let mut visited: HashSet<String> = ["https://shop.example.com/shoes"]
+            .into_iter()
+            .map(normalize_url)
+            .collect();
+
+        // Self-canonical pages and pages without one are never duplicates.
+        let url = "https://shop.example.com/shoes";
+        assert_eq!(
+            canonical_duplicate(&page(url, Some(url)), url, &mut visited),
+            None
+        );
+        assert_eq!(
+            canonical_duplicate(&page(url, None), url, &mut visited),
+            None
+        );
+
+        let url = "https://shop.example.com/shoes?sort=price";
+        assert_eq!(
+            canonical_duplicate(&page(url, Some("/shoes/")), url, &mut visited),
+            Some("https://shop.example.com/shoes/".to_string())
+        );
+
+        // The first variant of an uncrawled canonical claims it; the next is a duplicate.
+        let first = "https://shop.example.com/boots?sort=price";
+        let second = "https://shop.example.com/boots?utm_source=mail";
+        let canonical = Some("https://shop.example.com/boots");
+        assert_eq!(
+            canonical_duplicate(&page(first, canonical), first, &mut visited),
+            None
+        );
+        assert!(visited.contains(&normalize_url("https://shop.example.com/boots")));
+        assert!(canonical_duplicate(&page(second, canonical), second, &mut visited).is_some());
+    }
+
+    #[test]
+    fn amp_urls_map_to_their_canonical_only_when_unambiguous() {
+        let base = "www.example.com";
+        for (amp, canonical) in [
+            (
+                "https://www.example.com/amp/news/story",
+                "https://www.example.com/news/story",
+            ),
+            (
+                "https://www.example.com/news/story/amp/",
+                "https://www.example.com/news/story/",
+            ),
+            (
+                "https://amp.example.com/news/story",
+                "https://www.example.com/news/story",
+            ),
+            (
+                "https://amp.example.com/amp/story?id=3",
+                "https://www.example.com/story?id=3",
+            ),
+        ] {
+            assert_eq!(
+                amp_canonical(amp, base).as_deref(),
+                Some(canonical),
+                "{}",
+                amp
+            );
+        }
+        for kept in [
+            "https://www.example.com/amp",
+            "https://www.example.com/products/amp/cables",
+            "https://www.example.com/amp/amp",
+            "https://amp.other.com/news/story",
+            "https://www.example.com/amplifiers/story",
+        ] {
+            assert_eq!(amp_canonical(kept, base), None, "{}", kept);
         }
+
+        let config = CrawlConfig {
+            respect_canonical: false,
+            ..CrawlConfig::default()
+        };
+        let amp = "https://amp.example.com/story".to_string();
+        assert_eq!(crawl_target(amp.clone(), base, &config), amp);
+    }
+
+    #[test]
+    fn content_filter_keeps_matching_pages_best_first() {
+        let page = |url: &str, title: &str, preview: &str, success: bool| CrawlPageResult {
+            url: url.to_string(),
+            depth: 1,
+            success,
+            title: Some(title.to_string()),
+            word_count: None,
+            links_found: None,
+            content_preview: Some(preview.to_string()),
+            error: None,
+            duration_ms: 1,
+            priority: None,
+            parent_url: None,
+            status_code: None,
+            outlinks: Vec::new(),
+            relevance: None,
+            quality: None,
+            duplicates_of: Vec::new(),
+        };
+        let crawled = || CrawlResponse {
+            crawl_id: None,
+            start_url: "https://example.com/".to_string(),
+            pages_crawled: 4,
+            pages_failed: 1,
+            max_depth_reached: 1,
+            total_duration_ms: 1,
+            unique_domains: vec![],
+            sitemap_urls_found: 0,
+            pages_from_sitemap: 0,
+            pages_from_links: 5,
+            pages_excluded_noindex: 0,
+            results: vec![
+                page("https://example.com/about", "About us", "Our team", true),
+                page(
+                    "https://example.com/docs/pricing",
+                    "Pricing",
+                    "Plans and billing details",
+                    true,
+                ),
+                page(
+                    "https://example.com/blog",
+                    "Blog",
+                    "Billing changes in 2026",
+                    true,
+                ),
+                page("https://example.com/jobs", "Jobs", "Join us", true),
+                page("https://example.com/broken", "", "", false),
+            ],
+            sitemap: None,
+            graph: None,
+            content_filter: None,
+            plan: None,
+            dropped_low_quality: Vec::new(),
+            duplicates: Vec::new(),
+            canonical_duplicates_skipped: 0,
+            canonical_collapses: Vec::new(),
+        };
+        let filter = |max_pages_returned| CrawlContentFilter {
+            query: "pricing billing".to_string(),
+            min_score: 0.3,
+            max_pages_returned,
+        };
+
+        let mut response = crawled();
+        filter(None).apply(&mut response);
+        let urls: Vec<&str> = response.results.iter().map(|p| p.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/docs/pricing",
+                "https://example.com/blog"
+            ]
+        );
+        assert!(response.results[0].relevance > response.results[1].relevance);
+        let summary = response.content_filter.unwrap();
+        assert_eq!(summary.pages_matched, 2);
+        assert_eq!(summary.failed_pages_omitted, 1);
+        let dropped: Vec<&str> = summary
+            .filtered_out
+            .iter()
+            .map(|p| p.url.as_str())
+            .collect();
+        assert_eq!(
+            dropped,
+            ["https://example.com/about", "https://example.com/jobs"],
+            "ties keep crawl order"
+        );
+
+        // A cap returns the best match and lists the other one first among the rest.
+        let mut capped = crawled();
+        filter(Some(1)).apply(&mut capped);
+        assert_eq!(capped.results.len(), 1);
+        let summary = capped.content_filter.unwrap();
+        assert_eq!((summary.pages_matched, summary.pages_returned), (2, 1));
+        assert_eq!(summary.filtered_out[0].url, "https://example.com/blog");
+
+        // min_quality drops thin pages; failures and pages without signals stay.
+        let mut thin = crawled();
+        thin.results[0].quality = Some(0.2);
+        thin.results[1].quality = Some(0.9);
+        apply_min_quality(&mut thin, 0.5);
+        assert_eq!(thin.results.len(), 4);
+        assert_eq!(
+            thin.dropped_low_quality,
+            [LowQualityPage {
+                url: "https://example.com/about".to_string(),
+                quality: 0.2
+            }]
+        );
+    }
+
+    #[test]
+    fn link_priority_prefers_shallow_docs_over_noise() {
+        let base = "example.com";
+        let docs = link_priority("https://example.com/docs/intro", "Docs", base);
+        let guide = link_priority("https://example.com/blog/x", "Getting started guide", base);
+        let plain = link_priority("https://example.com/blog/2024/05/post", "", base);
+        let tag = link_priority("https://example.com/tag/rust", "rust", base);
+        let login = link_priority("https://example.com/login?next=/docs", "Sign in", base);
+        let offsite = link_priority("https://other.org/docs/intro", "Docs", base);
+        let queried = link_priority("https://example.com/docs/intro?a=1&b=2&c=3", "", base);
+
+        assert!(docs > plain && guide > plain);
+        assert!(plain > tag && plain > login);
+        assert!(docs > offsite);
+        assert!(docs > queried);
+        assert_eq!(
+            CrawlStrategy::parse_str("Priority"),
+            Some(CrawlStrategy::Priority)
+        );
+        assert_eq!(CrawlStrategy::parse_str("depth"), None);
     }
 
-    true
+    #[test]
+    fn crawl_exclusion_names_the_rule_and_structure_groups_listings() {
+        let config = CrawlConfig {
+            include_patterns: vec!["/blog".to_string()],
+            ..Default::default()
+        };
+        let base = "example.com";
+        let rule = |url| crawl_exclusion(url, base, &config);
+        assert_eq!(rule("https://example.com/blog/post"), None);
+        assert_eq!(rule("https://docs.example.com/blog/post"), None);
+        assert_eq!(
+            rule("https://other.org/blog"),
+            Some("other_domain".to_string())
+        );
+        assert_eq!(
+            rule("https://example.com/blog/login"),
+            Some("exclude:/login".to_string())
+        );
+        assert_eq!(
+            rule("https://example.com/about"),
+            Some("no_include_match".to_string())
+        );
+        assert_eq!(rule("not a url"), Some("invalid_url".to_string()));
+        assert!(!should_crawl("https://example.com/about", base, &config));
+
+        let urls: Vec<String> = [
+            "https://example.com/blog/page/2",
+            "https://example.com/blog/page/3",
+            "https://example.com/blog/category/rust",
+            "https://example.com/blog/hello-world",
+            "https://example.com/news?page=4",
+            "https://example.com/",
+        ]
+        .iter()
+        .map(|u| u.to_string())
+        .collect();
+        let structure = crawl_structure(&urls);
+        let sections: Vec<(&str, usize)> = structure
+            .sections
+            .iter()
+            .map(|s| (s.prefix.as_str(), s.urls))
+            .collect();
+        assert_eq!(sections, [("/blog", 4), ("/", 1), ("/news", 1)]);
+        assert_eq!(structure.pagination_urls, 3);
+        assert_eq!(
+            structure.pagination_patterns,
+            ["path:/page/{n}", "query:page"]
+        );
+        assert_eq!(structure.category_urls, 1);
+    }
+
+    #[test]
+    fn parse_sitemap_reads_urlset_entries() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>https://example.com/docs/a</loc></url>
+  <url><loc> https://example.com/docs/b?x=1&amp;y=2 </loc></url>
+  <url><loc><![CDATA[https://example.com/docs/c]]></loc></url>
+</urlset>"#;
+        assert_eq!(
+            parse_sitemap(xml),
+            SitemapDocument::UrlSet(vec![
+                "https://example.com/docs/a".to_string(),
+                "https://example.com/docs/b?x=1&y=2".to_string(),
+                "https://example.com/docs/c".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_sitemap_detects_index_files() {
+        let xml = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>https://example.com/sitemap-1.xml.gz</loc></sitemap>
+</sitemapindex>"#;
+        assert_eq!(
+            parse_sitemap(xml),
+            SitemapDocument::Index(vec!["https://example.com/sitemap-1.xml.gz".to_string()])
+        );
+    }
+
+    #[test]
+    fn decode_sitemap_bytes_inflates_gzip() {
+        let xml = "<urlset><url><loc>https://example.com/</loc></url></urlset>";
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(xml.as_bytes()).unwrap();
+        let gz = encoder.finish().unwrap();
+
+        assert_eq!(decode_sitemap_bytes(&gz).unwrap(), xml);
+        assert_eq!(decode_sitemap_bytes(xml.as_bytes()).unwrap(), xml);
+    }
 }
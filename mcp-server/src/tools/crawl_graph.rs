@@ -0,0 +1,312 @@
+//! The crawl's link graph: crawled pages as nodes, the links between them as edges.
+//!
+//! Built from each page's recorded `outlinks` once the crawl finishes, so resumed crawls
+//! get edges from pages fetched in earlier runs too. [`to_dot`] and [`to_graphml`] write
+//! the same graph for Graphviz and Gephi.
+
+use crate::crawl::normalize_url;
+use crate::types::{CrawlGraph, CrawlGraphEdge, CrawlGraphNode, CrawlPageResult};
+use std::collections::{HashMap, HashSet};
+
+/// Serialization for `output` on the crawl tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    /// The crawl response as JSON, with the graph under `graph`.
+    #[default]
+    Json,
+    Dot,
+    GraphMl,
+}
+
+impl GraphFormat {
+    pub fn parse_str(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "dot" | "graphviz" => Some(Self::Dot),
+            "graphml" => Some(Self::GraphMl),
+            _ => None,
+        }
+    }
+}
+
+/// One node per page in crawl order, up to `max_nodes`. Edges are deduplicated per
+/// (from, to) pair, keep the first anchor text seen, and only join pages that are nodes;
+/// links from a page to itself are dropped.
+pub fn build(pages: &[CrawlPageResult], max_nodes: usize) -> CrawlGraph {
+    let mut node_urls: HashMap<String, String> = HashMap::new();
+    let mut nodes = Vec::new();
+    for page in pages {
+        if nodes.len() >= max_nodes {
+            break;
+        }
+        let key = normalize_url(&page.url);
+        if node_urls.contains_key(&key) {
+            continue;
+        }
+        node_urls.insert(key, page.url.clone());
+        nodes.push(CrawlGraphNode {
+            url: page.url.clone(),
+            title: page.title.clone().filter(|t| !t.is_empty()),
+            status: page.status_code,
+            word_count: page.word_count,
+            depth: page.depth,
+        });
+    }
+    let truncated = pages
+        .iter()
+        .any(|page| !node_urls.contains_key(&normalize_url(&page.url)));
+
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    let mut edges = Vec::new();
+    for page in pages {
+        let Some(from) = node_urls.get(&normalize_url(&page.url)) else {
+            continue;
+        };
+        for link in &page.outlinks {
+            let Some(to) = node_urls.get(&normalize_url(&link.url)) else {
+                continue;
+            };
+            if from == to || !seen.insert((from.clone(), to.clone())) {
+                continue;
+            }
+            edges.push(CrawlGraphEdge {
+                from: from.clone(),
+                to: to.clone(),
+                anchor_text: link.anchor_text.clone(),
+            });
+        }
+    }
+
+    CrawlGraph {
+        nodes,
+        edges,
+        truncated,
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', " ")
+}
+
+/// Graphviz `digraph`: nodes are identified by URL and labelled with their title.
+pub fn to_dot(graph: &CrawlGraph) -> String {
+    let mut out = String::from("digraph crawl {\n");
+    for node in &graph.nodes {
+        let label = node.title.as_deref().unwrap_or(&node.url);
+        out.push_str(&format!(
+            "  \"{}\" [label=\"{}\", depth={}",
+            dot_escape(&node.url),
+            dot_escape(label),
+            node.depth
+        ));
+        if let Some(status) = node.status {
+            out.push_str(&format!(", status={}", status));
+        }
+        if let Some(words) = node.word_count {
+            out.push_str(&format!(", word_count={}", words));
+        }
+        out.push_str("];\n");
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\"",
+            dot_escape(&edge.from),
+            dot_escape(&edge.to)
+        ));
+        if let Some(anchor) = &edge.anchor_text {
+            out.push_str(&format!(" [label=\"{}\"]", dot_escape(anchor)));
+        }
+        out.push_str(";\n");
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// GraphML document with typed `title`, `status`, `word_count`, `depth` node data and
+/// `anchor_text` edge data.
+pub fn to_graphml(graph: &CrawlGraph) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n\
+         \x20 <key id=\"title\" for=\"node\" attr.name=\"title\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"status\" for=\"node\" attr.name=\"status\" attr.type=\"int\"/>\n\
+         \x20 <key id=\"word_count\" for=\"node\" attr.name=\"word_count\" attr.type=\"int\"/>\n\
+         \x20 <key id=\"depth\" for=\"node\" attr.name=\"depth\" attr.type=\"int\"/>\n\
+         \x20 <key id=\"anchor_text\" for=\"edge\" attr.name=\"anchor_text\" attr.type=\"string\"/>\n\
+         \x20 <graph id=\"crawl\" edgedefault=\"directed\">\n",
+    );
+    for node in &graph.nodes {
+        out.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&node.url)));
+        if let Some(title) = &node.title {
+            out.push_str(&format!(
+                "      <data key=\"title\">{}</data>\n",
+                xml_escape(title)
+            ));
+        }
+        if let Some(status) = node.status {
+            out.push_str(&format!("      <data key=\"status\">{}</data>\n", status));
+        }
+        if let Some(words) = node.word_count {
+            out.push_str(&format!(
+                "      <data key=\"word_count\">{}</data>\n",
+                words
+            ));
+        }
+        out.push_str(&format!(
+            "      <data key=\"depth\">{}</data>\n    </node>\n",
+            node.depth
+        ));
+    }
+    for edge in &graph.edges {
+        let endpoints = format!(
+            "source=\"{}\" target=\"{}\"",
+            xml_escape(&edge.from),
+            xml_escape(&edge.to)
+        );
+        match &edge.anchor_text {
+            Some(anchor) => out.push_str(&format!(
+                "    <edge {}>\n      <data key=\"anchor_text\">{}</data>\n    </edge>\n",
+                endpoints,
+                xml_escape(anchor)
+            )),
+            None => out.push_str(&format!("    <edge {}/>\n", endpoints)),
+        }
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::CrawlOutlink;
+
+    fn page(url: &str, depth: usize, links: &[(&str, &str)]) -> CrawlPageResult {
+        CrawlPageResult {
+            url: url.to_string(),
+            depth,
+            success: true,
+            title: Some(format!("Title of {}", url)),
+            word_count: Some(120),
+            links_found: Some(links.len()),
+            content_preview: None,
+            error: None,
+            duration_ms: 5,
+            priority: None,
+            parent_url: None,
+            status_code: Some(200),
+            outlinks: links
+                .iter()
+                .map(|(url, text)| CrawlOutlink {
+                    url: url.to_string(),
+                    anchor_text: (!text.is_empty()).then(|| text.to_string()),
+                })
+                .collect(),
+            relevance: None,
+            quality: None,
+            duplicates_of: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn cycles_and_repeated_links_become_one_edge_each() {
+        let pages = vec![
+            page(
+                "https://example.com/",
+                0,
+                &[
+                    ("https://example.com/docs", "Docs"),
+                    ("https://example.com/docs/#top", "Docs again"),
+                    ("https://example.com/", "Home"),
+                    ("https://example.com/missing", "Not crawled"),
+                ],
+            ),
+            page(
+                "https://example.com/docs",
+                1,
+                &[
+                    ("https://example.com", ""),
+                    ("https://example.com/docs", ""),
+                ],
+            ),
+        ];
+        let graph = build(&pages, 10);
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(!graph.truncated);
+        assert_eq!(
+            graph.edges,
+            [
+                CrawlGraphEdge {
+                    from: "https://example.com/".to_string(),
+                    to: "https://example.com/docs".to_string(),
+                    anchor_text: Some("Docs".to_string()),
+                },
+                CrawlGraphEdge {
+                    from: "https://example.com/docs".to_string(),
+                    to: "https://example.com/".to_string(),
+                    anchor_text: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn node_cap_drops_later_pages_and_their_edges() {
+        let pages = vec![
+            page("https://example.com/", 0, &[("https://example.com/a", "A")]),
+            page(
+                "https://example.com/a",
+                1,
+                &[("https://example.com/b", "B")],
+            ),
+            page("https://example.com/b", 2, &[]),
+        ];
+        let graph = build(&pages, 2);
+
+        assert!(graph.truncated);
+        let urls: Vec<_> = graph.nodes.iter().map(|n| n.url.as_str()).collect();
+        assert_eq!(urls, ["https://example.com/", "https://example.com/a"]);
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn exports_escape_urls_and_labels() {
+        let pages = vec![
+            page(
+                "https://example.com/?a=1&b=\"2\"",
+                0,
+                &[("https://example.com/x", "Say \"hi\" <here>")],
+            ),
+            page("https://example.com/x", 1, &[]),
+        ];
+        let graph = build(&pages, 10);
+
+        let dot = to_dot(&graph);
+        assert!(dot.starts_with("digraph crawl {\n"));
+        assert!(dot.contains(
+            "\"https://example.com/?a=1&b=\\\"2\\\"\" -> \"https://example.com/x\" [label=\"Say \\\"hi\\\" <here>\"];"
+        ));
+
+        let graphml = to_graphml(&graph);
+        assert!(graphml.contains("<node id=\"https://example.com/?a=1&amp;b=&quot;2&quot;\">"));
+        assert!(
+            graphml.contains("<data key=\"anchor_text\">Say &quot;hi&quot; &lt;here&gt;</data>")
+        );
+        assert_eq!(
+            GraphFormat::parse_str("GraphML"),
+            Some(GraphFormat::GraphMl)
+        );
+        assert_eq!(GraphFormat::parse_str("csv"), None);
+    }
+}
@@ -0,0 +1,396 @@
+//! On-disk state for resumable crawls.
+//!
+//! Each crawl gets `<root>/<crawl_id>/` (root: `CORTEX_SCOUT_CRAWL_DIR`, else
+//! `~/.cortex-scout/data/crawls`). `state.json` holds the visited set and the pending
+//! frontier and is rewritten after every wave; `pages.jsonl` gets one [`CrawlPageResult`]
+//! per fetched page as waves complete, so a crawl killed mid-way loses at most the wave
+//! in flight.
+
+use crate::core::error::ScoutError;
+use crate::crawl::CrawlConfig;
+use crate::types::{CanonicalCollapse, CrawlPageResult};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, warn};
+
+const STATE_FILE: &str = "state.json";
+const PAGES_FILE: &str = "pages.jsonl";
+
+/// `CORTEX_SCOUT_CRAWL_DIR`, else `~/.cortex-scout/data/crawls`.
+pub fn default_crawl_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("CORTEX_SCOUT_CRAWL_DIR") {
+        let dir = dir.trim();
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    dirs::home_dir().map(|home| home.join(".cortex-scout").join("data").join("crawls"))
+}
+
+pub fn new_crawl_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Crawl ids name directories, so only plain ASCII letters, digits, `-` and `_` pass.
+pub fn is_valid_crawl_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrawlRunStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A queued URL waiting to be fetched.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrontierEntry {
+    pub url: String,
+    pub depth: usize,
+    pub priority: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlState {
+    pub crawl_id: String,
+    pub start_url: String,
+    pub status: CrawlRunStatus,
+    /// Crawl settings to resume with. Credentials are never written; a resumed crawl
+    /// only sends them when the caller passes `auth` again.
+    pub config: CrawlConfig,
+    /// Normalized URLs already fetched or queued.
+    pub visited: Vec<String>,
+    pub frontier: Vec<FrontierEntry>,
+    #[serde(default)]
+    pub sitemap_seeded: Vec<String>,
+    #[serde(default)]
+    pub sitemap_urls_found: usize,
+    #[serde(default)]
+    pub pages_excluded_noindex: usize,
+    #[serde(default)]
+    pub canonical_duplicates_skipped: usize,
+    #[serde(default)]
+    pub canonical_collapses: Vec<CanonicalCollapse>,
+    #[serde(default)]
+    pub max_depth_reached: usize,
+    #[serde(default)]
+    pub unique_domains: Vec<String>,
+    pub pages_done: usize,
+    pub pages_failed: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// `GET /crawl/{id}/status` body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlStatusReport {
+    pub crawl_id: String,
+    pub start_url: String,
+    pub status: CrawlRunStatus,
+    pub pages_done: usize,
+    pub pages_pending: usize,
+    pub pages_failed: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub updated_at: String,
+}
+
+impl CrawlState {
+    pub fn status_report(&self) -> CrawlStatusReport {
+        CrawlStatusReport {
+            crawl_id: self.crawl_id.clone(),
+            start_url: self.start_url.clone(),
+            status: self.status,
+            pages_done: self.pages_done,
+            pages_pending: self.frontier.len(),
+            pages_failed: self.pages_failed,
+            error: self.error.clone(),
+            updated_at: self.updated_at.clone(),
+        }
+    }
+}
+
+/// One crawl's directory.
+#[derive(Debug, Clone)]
+pub struct CrawlStore {
+    dir: PathBuf,
+    crawl_id: String,
+}
+
+impl CrawlStore {
+    pub fn open(root: &Path, crawl_id: &str) -> Result<Self> {
+        if !is_valid_crawl_id(crawl_id) {
+            return Err(
+                ScoutError::invalid_input(format!("Invalid crawl id '{}'", crawl_id)).into(),
+            );
+        }
+        Ok(Self {
+            dir: root.join(crawl_id),
+            crawl_id: crawl_id.to_string(),
+        })
+    }
+
+    pub fn crawl_id(&self) -> &str {
+        &self.crawl_id
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub async fn load_state(&self) -> Result<CrawlState> {
+        let path = self.dir.join(STATE_FILE);
+        let raw = match tokio::fs::read(&path).await {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(
+                    ScoutError::not_found(format!("Unknown crawl id '{}'", self.crawl_id)).into(),
+                )
+            }
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        serde_json::from_slice(&raw).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    /// Written to a temp file and renamed into place so a crash never leaves half a state.
+    pub async fn save_state(&self, state: &CrawlState) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .with_context(|| format!("creating {}", self.dir.display()))?;
+        let path = self.dir.join(STATE_FILE);
+        let tmp = self.dir.join(format!("{}.tmp", STATE_FILE));
+        tokio::fs::write(&tmp, serde_json::to_vec(state)?)
+            .await
+            .with_context(|| format!("writing {}", tmp.display()))?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .with_context(|| format!("replacing {}", path.display()))?;
+        Ok(())
+    }
+
+    pub async fn append_pages(&self, pages: &[CrawlPageResult]) -> Result<()> {
+        if pages.is_empty() {
+            return Ok(());
+        }
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .with_context(|| format!("creating {}", self.dir.display()))?;
+        let mut lines = Vec::new();
+        for page in pages {
+            serde_json::to_writer(&mut lines, page)?;
+            lines.push(b'\n');
+        }
+        let path = self.dir.join(PAGES_FILE);
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .with_context(|| format!("opening {}", path.display()))?;
+        file.write_all(&lines)
+            .await
+            .with_context(|| format!("appending to {}", path.display()))?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Saved pages in crawl order. A line cut short by a crash is skipped, and a page
+    /// written twice (fetched again after a restart) keeps its latest result.
+    pub async fn load_pages(&self) -> Result<Vec<CrawlPageResult>> {
+        let path = self.dir.join(PAGES_FILE);
+        let raw = match tokio::fs::read_to_string(&path).await {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        let mut pages: Vec<CrawlPageResult> = Vec::new();
+        for line in raw.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<CrawlPageResult>(line) {
+                Ok(page) => {
+                    pages.retain(|p| p.url != page.url);
+                    pages.push(page);
+                }
+                Err(e) => warn!("skipping unreadable page in {}: {}", path.display(), e),
+            }
+        }
+        Ok(pages)
+    }
+}
+
+/// Drop frontier entries that already have a saved page: pages are appended before the
+/// state is rewritten, so a crash in between leaves them in both.
+pub fn reconcile_frontier(frontier: &mut Vec<FrontierEntry>, pages: &[CrawlPageResult]) {
+    let done: HashSet<&str> = pages.iter().map(|p| p.url.as_str()).collect();
+    frontier.retain(|entry| !done.contains(entry.url.as_str()));
+}
+
+pub async fn crawl_status(root: &Path, crawl_id: &str) -> Result<CrawlStatusReport> {
+    let store = CrawlStore::open(root, crawl_id)?;
+    Ok(store.load_state().await?.status_report())
+}
+
+/// Delete crawl directories not written to for longer than `max_age`. Returns how many
+/// were removed.
+pub fn prune_stale_crawls(root: &Path, max_age: Duration) -> Result<usize> {
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", root.display())),
+    };
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        let state_file = dir.join(STATE_FILE);
+        let touched = std::fs::metadata(&state_file)
+            .or_else(|_| std::fs::metadata(&dir))
+            .and_then(|m| m.modified());
+        let Ok(touched) = touched else {
+            continue;
+        };
+        if now.duration_since(touched).unwrap_or_default() <= max_age {
+            continue;
+        }
+        match std::fs::remove_dir_all(&dir) {
+            Ok(()) => {
+                debug!("removed stale crawl state {}", dir.display());
+                removed += 1;
+            }
+            Err(e) => warn!(
+                "failed to remove stale crawl state {}: {}",
+                dir.display(),
+                e
+            ),
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(url: &str, success: bool) -> CrawlPageResult {
+        CrawlPageResult {
+            url: url.to_string(),
+            depth: 1,
+            success,
+            title: None,
+            word_count: None,
+            links_found: None,
+            content_preview: None,
+            error: None,
+            duration_ms: 5,
+            priority: None,
+            parent_url: None,
+            status_code: None,
+            outlinks: Vec::new(),
+            relevance: None,
+            quality: None,
+            duplicates_of: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn state_and_pages_round_trip_and_reconcile() {
+        let root = std::env::temp_dir().join(format!("cortex-scout-crawls-{}", new_crawl_id()));
+        let store = CrawlStore::open(&root, "abc123").unwrap();
+        assert!(CrawlStore::open(&root, "../etc").is_err());
+
+        let err = store.load_state().await.unwrap_err();
+        assert!(err.to_string().contains("Unknown crawl id 'abc123'"));
+
+        let mut state = CrawlState {
+            crawl_id: "abc123".to_string(),
+            start_url: "https://example.com/".to_string(),
+            status: CrawlRunStatus::Running,
+            config: CrawlConfig {
+                max_pages: 500,
+                ..Default::default()
+            },
+            visited: vec!["https://example.com/".to_string()],
+            frontier: vec![
+                FrontierEntry {
+                    url: "https://example.com/a".to_string(),
+                    depth: 1,
+                    priority: 0.5,
+                    parent: None,
+                },
+                FrontierEntry {
+                    url: "https://example.com/b".to_string(),
+                    depth: 1,
+                    priority: 0.25,
+                    parent: None,
+                },
+            ],
+            sitemap_seeded: Vec::new(),
+            sitemap_urls_found: 0,
+            pages_excluded_noindex: 0,
+            canonical_duplicates_skipped: 0,
+            canonical_collapses: Vec::new(),
+            max_depth_reached: 0,
+            unique_domains: vec!["example.com".to_string()],
+            pages_done: 1,
+            pages_failed: 0,
+            error: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        store.save_state(&state).await.unwrap();
+        store
+            .append_pages(&[
+                page("https://example.com/", true),
+                page("https://example.com/a", false),
+            ])
+            .await
+            .unwrap();
+        // A retried page replaces its earlier result.
+        store
+            .append_pages(&[page("https://example.com/a", true)])
+            .await
+            .unwrap();
+
+        let loaded = store.load_state().await.unwrap();
+        assert_eq!(loaded.config.max_pages, 500);
+        assert!(loaded.config.auth.is_none());
+        assert_eq!(loaded.frontier, state.frontier);
+
+        let pages = store.load_pages().await.unwrap();
+        assert_eq!(pages.len(), 2);
+        assert!(pages[1].success);
+
+        reconcile_frontier(&mut state.frontier, &pages);
+        assert_eq!(state.frontier.len(), 1);
+        assert_eq!(state.frontier[0].url, "https://example.com/b");
+
+        let report = crawl_status(&root, "abc123").await.unwrap();
+        assert_eq!(report.pages_pending, 2);
+        assert_eq!(report.status, CrawlRunStatus::Running);
+
+        assert_eq!(
+            prune_stale_crawls(&root, Duration::from_secs(3600)).unwrap(),
+            0
+        );
+        assert_eq!(prune_stale_crawls(&root, Duration::ZERO).unwrap(), 1);
+        assert!(!store.dir().exists());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
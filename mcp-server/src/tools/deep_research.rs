@@ -297,6 +297,25 @@ fn history_bootstrap_query_limit(discovery_budget: usize) -> usize {
     discovery_budget.max(12)
 }
 
+/// Sub-queries searched per run (`DEEP_RESEARCH_QUERY_CAP`, default 6).
+pub(crate) fn query_cap() -> usize {
+    std::env::var("DEEP_RESEARCH_QUERY_CAP")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(6)
+        .max(1)
+}
+
+/// URLs scraped per hop as a multiple of `max_sources`
+/// (`DEEP_RESEARCH_URL_DISCOVERY_MULTIPLIER`, default 2, at most 4).
+pub(crate) fn url_discovery_multiplier() -> usize {
+    std::env::var("DEEP_RESEARCH_URL_DISCOVERY_MULTIPLIER")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(2)
+        .clamp(1, 4)
+}
+
 fn deep_research_hop_timeout() -> Duration {
     Duration::from_secs(
         std::env::var("DEEP_RESEARCH_HOP_TIMEOUT_SECS")
@@ -598,12 +617,7 @@ pub async fn deep_research(
     {
         let mut seen = HashSet::<String>::new();
         hop_queries.retain(|q| seen.insert(normalize_query_for_dedupe(q)));
-        let query_cap = std::env::var("DEEP_RESEARCH_QUERY_CAP")
-            .ok()
-            .and_then(|v| v.parse::<usize>().ok())
-            .unwrap_or(6)
-            .max(1);
-        hop_queries.truncate(query_cap);
+        hop_queries.truncate(query_cap());
     }
 
     all_sub_queries.extend(hop_queries.clone());
@@ -622,20 +636,16 @@ pub async fn deep_research(
         // ── Search phase ─────────────────────────────────────────────────
         let mut candidate_urls: Vec<String> = hop_urls.clone();
         let mut url_via_query: HashMap<String, String> = HashMap::new();
-        let discovery_budget = config.max_sources_per_hop.saturating_mul(
-            std::env::var("DEEP_RESEARCH_URL_DISCOVERY_MULTIPLIER")
-                .ok()
-                .and_then(|v| v.parse::<usize>().ok())
-                .unwrap_or(2)
-                .clamp(1, 4),
-        );
+        let discovery_budget = config
+            .max_sources_per_hop
+            .saturating_mul(url_discovery_multiplier());
 
         if current_depth == 1 && candidate_urls.len() < discovery_budget {
             if let Some(memory) = research_memory.as_ref() {
                 let mut recovered = 0usize;
                 let mut skipped_empty_history = 0usize;
 
-                match memory.find_recent_duplicate(&query, 72).await {
+                match memory.find_recent_duplicate(&query, 72, 0.9).await {
                     Ok(Some((entry, _score))) => {
                         let duplicate_urls = extract_urls_from_logged_search_result(
                             &entry.full_result,
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use regex::Regex;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::info;
@@ -18,6 +19,9 @@ pub struct ExtractStructuredOptions {
     pub quality_mode: Option<String>,
     pub placeholder_word_threshold: Option<usize>,
     pub placeholder_empty_ratio: Option<f64>,
+    /// Named CSS selectors evaluated on the scraped HTML (see `ScrapeUrlOptions::selectors`).
+    pub selectors: BTreeMap<String, String>,
+    pub max_selector_matches: Option<usize>,
 }
 
 /// Extract structured data from a webpage based on schema or prompt
@@ -33,8 +37,18 @@ pub async fn extract_structured(
 
     // First, scrape the page
     let mode = options.quality_mode.as_deref().and_then(QualityMode::parse_str);
-    let scrape_result =
-        crate::scrape::scrape_url_with_options(state, url, options.use_proxy, mode).await?;
+    let scrape_result = crate::scrape::scrape_url_full(
+        state,
+        url,
+        crate::scrape::ScrapeUrlOptions {
+            use_proxy: options.use_proxy,
+            quality_mode: mode,
+            selectors: options.selectors,
+            max_selector_matches: options.max_selector_matches,
+            ..Default::default()
+        },
+    )
+    .await?;
 
     extract_from_scrape(
         &scrape_result,
@@ -47,6 +61,198 @@ pub async fn extract_structured(
     )
 }
 
+/// [`extract_structured`] over many URLs with one shared schema/prompt.
+///
+/// Pages are scraped through [`crate::batch_scrape::scrape_batch_with_budget`] (bounded by
+/// `max_concurrent`, cache-aware, repeated URLs scraped once) and each is run through
+/// [`extract_from_scrape`]. A URL that fails to scrape or extract is reported in its own
+/// result; the rest of the batch carries on.
+pub async fn extract_batch(
+    state: &Arc<AppState>,
+    urls: Vec<String>,
+    max_concurrent: usize,
+    options: ExtractStructuredOptions,
+) -> Result<ExtractBatchResponse> {
+    let start_time = Instant::now();
+    let max_chars = options.max_chars.unwrap_or(10000);
+    let mode = options
+        .quality_mode
+        .as_deref()
+        .and_then(QualityMode::parse_str);
+
+    info!("Extracting structured data from {} URLs", urls.len());
+
+    let batch = crate::batch_scrape::scrape_batch_with_budget(
+        state,
+        urls,
+        max_concurrent,
+        None,
+        crate::scrape::ScrapeUrlOptions {
+            use_proxy: options.use_proxy,
+            quality_mode: mode,
+            selectors: options.selectors,
+            max_selector_matches: options.max_selector_matches,
+            ..Default::default()
+        },
+        None,
+    )
+    .await?;
+
+    let results: Vec<ExtractBatchItem> = batch
+        .results
+        .into_iter()
+        .map(|item| {
+            let mut result = ExtractBatchItem {
+                url: item.url,
+                success: false,
+                confidence: None,
+                extraction: None,
+                error: None,
+                duplicate_of: item.duplicate_of,
+                duration_ms: item.duration_ms,
+            };
+            if result.duplicate_of.is_some() {
+                result.success = true;
+                return result;
+            }
+            let data = match item.data {
+                Some(data) if item.success => data,
+                _ => {
+                    result.error = item
+                        .error
+                        .or(item.failure_reason)
+                        .or_else(|| Some("scrape failed".to_string()));
+                    return result;
+                }
+            };
+            match extract_from_scrape(
+                &data,
+                options.schema.clone(),
+                options.prompt.clone(),
+                options.strict,
+                max_chars,
+                options.placeholder_word_threshold,
+                options.placeholder_empty_ratio,
+            ) {
+                Ok(extraction) => {
+                    result.success = true;
+                    result.confidence = Some(extraction.confidence);
+                    result.extraction = Some(extraction);
+                }
+                Err(e) => result.error = Some(format!("extraction failed: {}", e)),
+            }
+            result
+        })
+        .collect();
+
+    let (rows, field_nulls) = summarize_batch(&results, options.schema.as_deref());
+    let successful = results.iter().filter(|r| r.success).count();
+    Ok(ExtractBatchResponse {
+        total: results.len(),
+        successful,
+        failed: results.len() - successful,
+        total_duration_ms: start_time.elapsed().as_millis() as u64,
+        results,
+        rows,
+        field_nulls,
+    })
+}
+
+/// Table rows (extracted fields plus `_url`) and per-field null counts for a batch.
+/// Fields follow the schema order when there is one, otherwise every key seen; the
+/// roll-up is sorted most-null first, ties keeping that order.
+fn summarize_batch(
+    results: &[ExtractBatchItem],
+    schema: Option<&[ExtractField]>,
+) -> (Vec<serde_json::Value>, Vec<ExtractFieldNulls>) {
+    let extracted: Vec<(&str, &serde_json::Value)> = results
+        .iter()
+        .filter_map(|r| Some((r.url.as_str(), &r.extraction.as_ref()?.extracted_data)))
+        .collect();
+
+    let rows = extracted
+        .iter()
+        .map(|(url, data)| {
+            let mut row = match data {
+                serde_json::Value::Object(fields) => fields.clone(),
+                other => {
+                    let mut map = serde_json::Map::new();
+                    map.insert("value".to_string(), (*other).clone());
+                    map
+                }
+            };
+            row.insert(
+                "_url".to_string(),
+                serde_json::Value::String(url.to_string()),
+            );
+            serde_json::Value::Object(row)
+        })
+        .collect();
+
+    let mut fields: Vec<String> = match schema {
+        Some(schema) => schema.iter().map(|f| f.name.clone()).collect(),
+        None => Vec::new(),
+    };
+    if fields.is_empty() {
+        for (_, data) in &extracted {
+            for key in data.as_object().into_iter().flat_map(|o| o.keys()) {
+                if !fields.contains(key) {
+                    fields.push(key.clone());
+                }
+            }
+        }
+    }
+
+    let is_empty = |v: Option<&serde_json::Value>| match v {
+        None | Some(serde_json::Value::Null) => true,
+        Some(serde_json::Value::String(s)) => s.trim().is_empty(),
+        Some(serde_json::Value::Array(items)) => items.is_empty(),
+        Some(_) => false,
+    };
+    let mut field_nulls: Vec<ExtractFieldNulls> = fields
+        .into_iter()
+        .map(|field| {
+            let null_count = extracted
+                .iter()
+                .filter(|(_, data)| is_empty(data.get(&field)))
+                .count();
+            ExtractFieldNulls {
+                field,
+                null_count,
+                null_ratio: if extracted.is_empty() {
+                    0.0
+                } else {
+                    null_count as f64 / extracted.len() as f64
+                },
+            }
+        })
+        .collect();
+    field_nulls.sort_by(|a, b| b.null_count.cmp(&a.null_count));
+
+    (rows, field_nulls)
+}
+
+/// Condition (a) of the placeholder-page check: fewer than `word_threshold` words, or at
+/// most one non-empty line. Also used by the scraper to decide on browser escalation.
+/// Words are English-word equivalents, so the threshold holds on CJK and Thai pages.
+/// JSON, markdown and plain-text bodies are never sparse: there is nothing left to render.
+pub fn is_sparse_content(scrape_result: &ScrapeResponse, word_threshold: usize) -> bool {
+    if matches!(
+        scrape_result.content_format.as_deref(),
+        Some("json" | "markdown" | "text")
+    ) {
+        return false;
+    }
+    crate::rust_scraper::word_equivalents(scrape_result.word_count, &scrape_result.language)
+        < word_threshold
+        || scrape_result
+            .clean_content
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .count()
+            <= 1
+}
+
 /// Extract structured data from a pre-scraped page.
 ///
 /// When `strict=true` and a schema is provided (or parsed from prompt), the
@@ -93,19 +299,26 @@ pub fn extract_from_scrape(
 
     let allow_metadata_keys = !(strict && schema.is_some());
 
+    let mut provenance: BTreeMap<String, Extracted> = BTreeMap::new();
     if let Some(fields) = &schema {
         for field in fields {
-            let value = extract_field_value(scrape_result, field);
-            let value = if strict {
-                strict_coerce_value(field, value)
-            } else {
-                value
-            };
+            let mut found = extract_field(scrape_result, field);
+            if strict {
+                let coerced = strict_coerce_value(field, found.value.clone());
+                if coerced != found.value {
+                    // The value found did not fit the field's shape and was dropped.
+                    found = Extracted {
+                        value: coerced,
+                        ..Extracted::null()
+                    };
+                }
+            }
 
-            if value.is_null() && field.required.unwrap_or(false) {
+            if found.value.is_null() && field.required.unwrap_or(false) {
                 warnings.push(format!("Required field '{}' not found", field.name));
             }
-            extracted_data.insert(field.name.clone(), value);
+            extracted_data.insert(field.name.clone(), found.value.clone());
+            provenance.insert(field.name.clone(), found);
         }
     } else {
         extracted_data = auto_extract(scrape_result, prompt.as_deref());
@@ -140,43 +353,77 @@ pub fn extract_from_scrape(
 
     // ── Phase 25: Hallucination-Proof Confidence Scoring ─────────────────────────
     //
-    // Score = (NonNull_Ratio × 0.4) + (Source_Grounded_Ratio × 0.4) + (Type_Valid_Ratio × 0.2)
+    // With a schema, every field gets its own confidence in `field_provenance`: the
+    // base confidence of the path that produced it (JSON-LD highest, a regex hit
+    // anywhere on the page lowest, keyword matches fading with distance), halved when
+    // a string value cannot be found in the page (grounding) and halved again when its
+    // JSON type does not match the schema's `field_type`. The overall confidence is
+    // the mean over the schema's fields.
     //
-    // • NonNull_Ratio        = non-null fields / total schema fields
-    // • Source_Grounded_Ratio = string values whose content can be found (exact or
-    //                           fuzzy word overlap) in the raw page text; non-string
-    //                           values are assumed grounded (they come from structure)
-    // • Type_Valid_Ratio     = fields whose JSON type matches schema `field_type`
+    // Without a schema: Score = NonNull_Ratio × 0.4 + 0.6 over the auto-detected keys.
     //
     // A placeholder-page override (confidence → 0.0) fires after the formula when
     // the page is sparse AND almost all scalar fields are null/empty.
 
-    // ── NonNull_Ratio ─────────────────────────────────────────────────────────────
     let schema_field_count = schema.as_ref().map(|s| s.len()).unwrap_or(0);
-    let (non_null_count, total_for_ratio) = if schema_field_count > 0 {
-        let nn = schema
-            .as_ref()
-            .map(|fields| {
-                fields
-                    .iter()
-                    .filter(|f| {
-                        extracted_data
-                            .get(&f.name)
-                            .map(|v| !v.is_null())
-                            .unwrap_or(false)
-                    })
-                    .count()
-            })
-            .unwrap_or(0);
-        (nn, schema_field_count)
+    let grounding_source = build_grounding_source(scrape_result);
+    let mut field_provenance = BTreeMap::new();
+    let mut null_count = 0usize;
+    for field in schema.iter().flatten() {
+        let Some(found) = provenance.remove(&field.name) else {
+            continue;
+        };
+        let mut field_confidence = found.base_confidence();
+        if found.value.is_null() {
+            null_count += 1;
+        } else {
+            // Grounding: only string values are verified against source.
+            // Non-strings (numbers, arrays, booleans) are structurally derived
+            // and are assumed to be grounded.
+            if let Some(s) = found.value.as_str() {
+                if !should_skip_grounding(field, s) && !is_grounded_in_source(s, &grounding_source)
+                {
+                    field_confidence *= 0.5;
+                    warnings.push(format!(
+                        "grounding_fail: '{}' value {:?} not found in source or page metadata",
+                        field.name,
+                        s.chars().take(60).collect::<String>()
+                    ));
+                }
+            }
+            // Type validation.
+            if let Some(expected) = &field.field_type {
+                if !is_type_valid(&found.value, expected) {
+                    field_confidence *= 0.5;
+                    warnings.push(format!(
+                        "type_mismatch: field '{}' expected '{}' but got {}",
+                        field.name,
+                        expected,
+                        json_type_name(&found.value)
+                    ));
+                }
+            }
+        }
+        field_provenance.insert(
+            field.name.clone(),
+            FieldProvenance {
+                source: found.source,
+                location: found.location,
+                distance: found.distance,
+                confidence: (field_confidence * 100.0).round() / 100.0,
+            },
+        );
+    }
+
+    let mut confidence = if !field_provenance.is_empty() {
+        field_provenance.values().map(|p| p.confidence).sum::<f64>() / field_provenance.len() as f64
     } else {
-        let nn = extracted_data.values().filter(|v| !v.is_null()).count();
-        (nn, extracted_data.len().max(1))
+        let non_null = extracted_data.values().filter(|v| !v.is_null()).count();
+        null_count = extracted_data.len().saturating_sub(non_null);
+        (non_null as f64 / extracted_data.len().max(1) as f64) * 0.4 + 0.6
     };
-    let non_null_ratio = non_null_count as f64 / total_for_ratio as f64;
 
     // Null-field summary warning.
-    let null_count = total_for_ratio - non_null_count;
     if null_count > 0 {
         warnings.push(format!(
             "Field not found warning: {} field(s) returned null (hallucination protection active)",
@@ -184,82 +431,14 @@ pub fn extract_from_scrape(
         ));
     }
 
-    // ── Source Grounding + Type Validation ────────────────────────────────────────
-    let grounding_source = build_grounding_source(scrape_result);
-
-    let (grounded_count, grounded_of, type_valid_count, type_checked_count) =
-        if let Some(fields) = &schema {
-            let mut grounded = 0usize;
-            let mut grounded_denom = 0usize;
-            let mut tv = 0usize;
-            let mut tc = 0usize;
-            for field in fields {
-                let val = match extracted_data.get(&field.name) {
-                    Some(v) if !v.is_null() => v,
-                    _ => continue,
-                };
-                // Grounding: only string values are verified against source.
-                // Non-strings (numbers, arrays, booleans) are structurally derived
-                // and are assumed to be grounded.
-                if let Some(s) = val.as_str() {
-                    if should_skip_grounding(field, s) {
-                        grounded += 1;
-                    } else {
-                        grounded_denom += 1;
-                        if is_grounded_in_source(s, &grounding_source) {
-                            grounded += 1;
-                        } else {
-                            warnings.push(format!(
-                                "grounding_fail: '{}' value {:?} not found in source or page metadata",
-                                field.name,
-                                s.chars().take(60).collect::<String>()
-                            ));
-                        }
-                    }
-                }
-                // Type validation.
-                if let Some(expected) = &field.field_type {
-                    tc += 1;
-                    if is_type_valid(val, expected) {
-                        tv += 1;
-                    } else {
-                        warnings.push(format!(
-                            "type_mismatch: field '{}' expected '{}' but got {}",
-                            field.name,
-                            expected,
-                            json_type_name(val)
-                        ));
-                    }
-                }
-            }
-            (grounded, grounded_denom, tv, tc)
-        } else {
-            (0, 0, 0, 0)
-        };
-
-    // No string values to ground-check → assume fully grounded.
-    let grounded_ratio = if grounded_of > 0 {
-        grounded_count as f64 / grounded_of as f64
-    } else {
-        1.0
-    };
-    // No typed fields to validate → full score.
-    let type_valid_ratio = if type_checked_count > 0 {
-        type_valid_count as f64 / type_checked_count as f64
-    } else {
-        1.0
-    };
-
-    let mut confidence =
-        (non_null_ratio * 0.4) + (grounded_ratio * 0.4) + (type_valid_ratio * 0.2);
-
     // ── Placeholder / unrendered page detection ───────────────────────────────────
     //
     // A JS-only page (e.g. crates.io, npm) returns almost no text content.
     // Reporting confidence > 0.0 causes agents to trust empty/wrong extracted fields.
     //
     // Detection requires BOTH of:
-    //   (a) content is sparse: word_count < placeholder_word_threshold (default 10)
+    //   (a) content is sparse: word_count (in English-word equivalents) <
+    //       placeholder_word_threshold (default 10)
     //       OR clean_content has ≤ 1 non-empty line
     //   (b) ≥ placeholder_empty_ratio (default 0.9) of NON-ARRAY schema fields are
     //       null or empty string.
@@ -269,13 +448,7 @@ pub fn extract_from_scrape(
     let word_threshold = placeholder_word_threshold.unwrap_or(10);
     let empty_ratio_threshold = placeholder_empty_ratio.unwrap_or(0.9);
     if schema_field_count > 0 {
-        let sparse_content = scrape_result.word_count < word_threshold
-            || scrape_result
-                .clean_content
-                .lines()
-                .filter(|l| !l.trim().is_empty())
-                .count()
-                <= 1;
+        let sparse_content = is_sparse_content(scrape_result, word_threshold);
 
         // Only tally scalar (non-array) fields. Empty arrays are never a placeholder
         // signal — they are a legitimate "no items found" extraction result.
@@ -309,6 +482,14 @@ pub fn extract_from_scrape(
         }
     }
 
+    warnings.extend(
+        scrape_result
+            .warnings
+            .iter()
+            .filter(|w| w.starts_with("selector_matches_truncated:"))
+            .cloned(),
+    );
+
     let field_count = extracted_data.len();
     let raw_preview: String = scrape_result
         .clean_content
@@ -326,6 +507,8 @@ pub fn extract_from_scrape(
         confidence: confidence.clamp(0.0, 1.0),
         duration_ms: start_time.elapsed().as_millis() as u64,
         warnings,
+        selector_results: scrape_result.selector_results.clone(),
+        field_provenance,
     })
 }
 
@@ -373,41 +556,147 @@ fn strict_coerce_value(field: &ExtractField, value: serde_json::Value) -> serde_
     }
 }
 
+/// A schema field's value and the path that produced it.
+#[derive(Debug)]
+struct Extracted {
+    value: serde_json::Value,
+    source: ExtractSource,
+    location: Option<String>,
+    /// Characters between the field's keyword and the value (keyword proximity only).
+    distance: Option<usize>,
+}
+
+impl Extracted {
+    /// `value` found by `source` at `location`; an empty value is recorded as not found.
+    fn new(value: serde_json::Value, source: ExtractSource, location: impl Into<String>) -> Self {
+        let found = match &value {
+            serde_json::Value::Null => false,
+            serde_json::Value::String(s) => !s.trim().is_empty(),
+            serde_json::Value::Array(items) => !items.is_empty(),
+            _ => true,
+        };
+        if !found {
+            return Self {
+                value,
+                ..Self::null()
+            };
+        }
+        Self {
+            value,
+            source,
+            location: Some(location.into()),
+            distance: None,
+        }
+    }
+
+    fn near_keyword(value: serde_json::Value, distance: usize, excerpt: String) -> Self {
+        Self {
+            distance: Some(distance),
+            ..Self::new(value, ExtractSource::KeywordProximity, excerpt)
+        }
+    }
+
+    fn null() -> Self {
+        Self {
+            value: serde_json::Value::Null,
+            source: ExtractSource::Null,
+            location: None,
+            distance: None,
+        }
+    }
+
+    /// Confidence from the source alone. Keyword matches fade with their distance from
+    /// the keyword across the 500-character search window. A field with nothing found
+    /// keeps a little: the heuristics would rather return null than guess, so null is
+    /// usually right, but it cannot be checked against the page.
+    fn base_confidence(&self) -> f64 {
+        match self.source {
+            ExtractSource::JsonLd => 0.95,
+            ExtractSource::RustdocFastPath => 0.9,
+            ExtractSource::Meta => 0.85,
+            ExtractSource::Heading => 0.7,
+            ExtractSource::KeywordProximity => {
+                let distance = self.distance.unwrap_or(0).min(KEYWORD_WINDOW_CHARS) as f64;
+                0.8 - 0.4 * distance / KEYWORD_WINDOW_CHARS as f64
+            }
+            ExtractSource::FallbackRegex => 0.35,
+            ExtractSource::Null => 0.3,
+        }
+    }
+}
+
+/// How far past a field's keyword the keyword-proximity heuristics look for a value.
+const KEYWORD_WINDOW_CHARS: usize = 500;
+
+/// The first paragraph long enough to be prose, as a fallback summary.
+fn first_paragraph(content: &str) -> serde_json::Value {
+    serde_json::Value::String(
+        content
+            .lines()
+            .find(|l| l.len() > 50)
+            .unwrap_or("")
+            .chars()
+            .take(500)
+            .collect(),
+    )
+}
+
+/// Up to 60 characters of `content` from char `pos`, on one line, as a location hint.
+fn excerpt_at(content: &str, pos: usize) -> String {
+    let line: String = content
+        .chars()
+        .skip(pos)
+        .take_while(|c| *c != '\n')
+        .take(60)
+        .collect();
+    format!("\"{}\"", line.trim())
+}
+
 /// Extract a specific field value based on field definition
-fn extract_field_value(scrape: &ScrapeResponse, field: &ExtractField) -> serde_json::Value {
+fn extract_field(scrape: &ScrapeResponse, field: &ExtractField) -> Extracted {
+    use ExtractSource::{FallbackRegex, Heading, Meta};
     let content = &scrape.clean_content;
     let name_lower = field.name.to_lowercase();
     let desc_lower = field.description.to_lowercase();
 
     if name_lower.contains("title") {
-        return serde_json::Value::String(scrape.title.clone());
+        return Extracted::new(
+            serde_json::Value::String(scrape.title.clone()),
+            Meta,
+            "title",
+        );
     }
 
     if name_lower.contains("summary")
         || (name_lower.contains("description") && name_lower != "meta_description")
     {
         if !scrape.meta_description.is_empty() {
-            return serde_json::Value::String(scrape.meta_description.clone());
+            return Extracted::new(
+                serde_json::Value::String(scrape.meta_description.clone()),
+                Meta,
+                "meta description",
+            );
         }
-        let first_para: String = content
-            .lines()
-            .find(|l| l.len() > 50)
-            .unwrap_or("")
-            .chars()
-            .take(500)
-            .collect();
-        return serde_json::Value::String(first_para);
+        return Extracted::new(first_paragraph(content), FallbackRegex, "first paragraph");
     }
 
     if name_lower.contains("page_type") || desc_lower.contains("page type") {
-        return serde_json::Value::String(infer_page_type(scrape));
+        return Extracted::new(
+            serde_json::Value::String(infer_page_type(scrape)),
+            Meta,
+            "url, title and headings",
+        );
     }
 
     if name_lower.contains("topic") {
         let topics = extract_main_topics(scrape);
         if !topics.is_empty() {
-            return serde_json::Value::Array(
-                topics.into_iter().map(serde_json::Value::String).collect(),
+            return Extracted::new(
+                serde_json::Value::Array(
+                    topics.into_iter().map(serde_json::Value::String).collect(),
+                ),
+                Heading,
+                "h2/h3 headings",
             );
         }
     }
@@ -421,45 +710,69 @@ fn extract_field_value(scrape: &ScrapeResponse, field: &ExtractField) -> serde_j
         if let Some(kind) = rustdoc_kind_from_field(&name_lower, &desc_lower) {
             let symbols = extract_rustdoc_symbols(scrape, kind);
             if !symbols.is_empty() {
-                return serde_json::Value::Array(
-                    symbols.into_iter().map(serde_json::Value::String).collect(),
+                return Extracted::new(
+                    serde_json::Value::Array(
+                        symbols.into_iter().map(serde_json::Value::String).collect(),
+                    ),
+                    ExtractSource::RustdocFastPath,
+                    format!("{} links", rustdoc_kind_token(kind)),
                 );
             }
         }
     }
 
+    // Typed JSON-LD beats the regex heuristics below (e.g. "price" → offers.price).
+    if let Some(found) = structured_data_value(scrape, field) {
+        return found;
+    }
+
     // Try to match based on field name and description
     match name_lower.as_str() {
         // Common field patterns
-        "title" | "name" | "headline" => serde_json::Value::String(scrape.title.clone()),
+        "title" | "name" | "headline" => Extracted::new(
+            serde_json::Value::String(scrape.title.clone()),
+            Meta,
+            "title",
+        ),
         "description" | "summary" | "excerpt" => {
             if !scrape.meta_description.is_empty() {
-                serde_json::Value::String(scrape.meta_description.clone())
+                Extracted::new(
+                    serde_json::Value::String(scrape.meta_description.clone()),
+                    Meta,
+                    "meta description",
+                )
             } else {
-                // First paragraph
-                let first_para: String = content
-                    .lines()
-                    .find(|l| l.len() > 50)
-                    .unwrap_or("")
-                    .chars()
-                    .take(500)
-                    .collect();
-                serde_json::Value::String(first_para)
+                Extracted::new(first_paragraph(content), FallbackRegex, "first paragraph")
             }
         }
-        "author" | "writer" | "by" => scrape
-            .author
-            .clone()
-            .map(serde_json::Value::String)
-            .unwrap_or(serde_json::Value::Null),
-        "date" | "published" | "published_at" | "publish_date" => scrape
-            .published_at
-            .clone()
-            .map(serde_json::Value::String)
-            .unwrap_or_else(|| extract_date_from_content(content)),
+        "author" | "writer" | "by" => match &scrape.author {
+            Some(author) => {
+                Extracted::new(serde_json::Value::String(author.clone()), Meta, "author")
+            }
+            None => Extracted::null(),
+        },
+        "date" | "published" | "published_at" | "publish_date" => match &scrape.published_at {
+            Some(published) => Extracted::new(
+                serde_json::Value::String(published.clone()),
+                Meta,
+                "published_at",
+            ),
+            None => {
+                let date = extract_date_from_content(content);
+                let location = date
+                    .as_str()
+                    .map(|d| format!("\"{}\"", d))
+                    .unwrap_or_default();
+                Extracted::new(date, FallbackRegex, location)
+            }
+        },
         "price" | "cost" | "amount" => extract_price_advanced(content, &field.name),
-        "email" | "emails" => extract_emails(content),
-        "phone" | "telephone" | "phones" => extract_phones(content),
+        "email" | "emails" => {
+            Extracted::new(extract_emails(content), FallbackRegex, "email pattern")
+        }
+        "phone" | "telephone" | "phones" => {
+            Extracted::new(extract_phones(content), FallbackRegex, "phone pattern")
+        }
         "links" | "urls" => {
             let urls: Vec<serde_json::Value> = scrape
                 .links
@@ -467,7 +780,7 @@ fn extract_field_value(scrape: &ScrapeResponse, field: &ExtractField) -> serde_j
                 .take(20)
                 .map(|l| serde_json::Value::String(l.url.clone()))
                 .collect();
-            serde_json::Value::Array(urls)
+            Extracted::new(serde_json::Value::Array(urls), Meta, "page links")
         }
         "headings" | "headers" | "sections" => {
             let headings: Vec<serde_json::Value> = scrape
@@ -475,7 +788,7 @@ fn extract_field_value(scrape: &ScrapeResponse, field: &ExtractField) -> serde_j
                 .iter()
                 .map(|h| serde_json::Value::String(format!("{}: {}", h.level, h.text)))
                 .collect();
-            serde_json::Value::Array(headings)
+            Extracted::new(serde_json::Value::Array(headings), Heading, "all headings")
         }
         "code" | "code_blocks" | "code_snippets" => {
             let blocks: Vec<serde_json::Value> = scrape
@@ -497,7 +810,7 @@ fn extract_field_value(scrape: &ScrapeResponse, field: &ExtractField) -> serde_j
                     serde_json::Value::Object(obj)
                 })
                 .collect();
-            serde_json::Value::Array(blocks)
+            Extracted::new(serde_json::Value::Array(blocks), Meta, "code blocks")
         }
         "images" => {
             let imgs: Vec<serde_json::Value> = scrape
@@ -511,27 +824,29 @@ fn extract_field_value(scrape: &ScrapeResponse, field: &ExtractField) -> serde_j
                     serde_json::Value::Object(obj)
                 })
                 .collect();
-            serde_json::Value::Array(imgs)
+            Extracted::new(serde_json::Value::Array(imgs), Meta, "page images")
         }
         _ => {
             if name_lower.contains("crate") && name_lower.contains("name") {
                 if let Some(value) = extract_crate_name(scrape) {
-                    return serde_json::Value::String(value);
+                    return Extracted::new(
+                        serde_json::Value::String(value),
+                        FallbackRegex,
+                        "\"crate <name>\"",
+                    );
                 }
             }
             if name_lower.contains("purpose") || name_lower.contains("overview") {
                 if !scrape.meta_description.is_empty() {
-                    return serde_json::Value::String(scrape.meta_description.clone());
+                    return Extracted::new(
+                        serde_json::Value::String(scrape.meta_description.clone()),
+                        Meta,
+                        "meta description",
+                    );
                 }
-                let first_para: String = content
-                    .lines()
-                    .find(|l| l.len() > 50)
-                    .unwrap_or("")
-                    .chars()
-                    .take(500)
-                    .collect();
-                if !first_para.is_empty() {
-                    return serde_json::Value::String(first_para);
+                let first_para = first_paragraph(content);
+                if first_para.as_str().is_some_and(|p| !p.is_empty()) {
+                    return Extracted::new(first_para, FallbackRegex, "first paragraph");
                 }
             }
             if name_lower.contains("feature") {
@@ -543,7 +858,11 @@ fn extract_field_value(scrape: &ScrapeResponse, field: &ExtractField) -> serde_j
                     .map(|h| serde_json::Value::String(h.text.clone()))
                     .collect();
                 if !features.is_empty() {
-                    return serde_json::Value::Array(features);
+                    return Extracted::new(
+                        serde_json::Value::Array(features),
+                        Heading,
+                        "h2/h3 headings",
+                    );
                 }
             }
             // ADVANCED HEURISTIC EXTRACTION with hallucination protection
@@ -561,6 +880,138 @@ fn extract_field_value(scrape: &ScrapeResponse, field: &ExtractField) -> serde_j
     }
 }
 
+/// Schema field names whose Schema.org property is spelled differently.
+const JSON_LD_FIELD_ALIASES: &[(&str, &str)] = &[
+    ("salary", "baseSalary"),
+    ("ingredients", "recipeIngredient"),
+    ("instructions", "recipeInstructions"),
+    ("rating", "aggregateRating"),
+    ("currency", "priceCurrency"),
+    ("company", "hiringOrganization"),
+    ("employer", "hiringOrganization"),
+];
+
+/// How deep into nested entities (`offers`, `location`, ...) a property is looked up.
+const JSON_LD_MAX_DEPTH: usize = 3;
+
+/// Value for `field` from the page's JSON-LD `structured_data`, matching the field name
+/// (or its alias) against property names with case, `_` and `-` ignored. Each object's
+/// own properties win over those of nested entities. The location is the top-level
+/// entity's `@type` and the matched property, e.g. `Product.price`.
+fn structured_data_value(scrape: &ScrapeResponse, field: &ExtractField) -> Option<Extracted> {
+    if scrape.structured_data.is_empty() {
+        return None;
+    }
+    let wanted = normalize_json_ld_property(&field.name);
+    let alias = JSON_LD_FIELD_ALIASES
+        .iter()
+        .find(|(name, _)| *name == wanted)
+        .map(|(_, property)| normalize_json_ld_property(property));
+    scrape.structured_data.iter().find_map(|object| {
+        let (property, found) = find_json_ld_property(object, &wanted, alias.as_deref(), 0)?;
+        let value = coerce_json_ld_value(found, field.field_type.as_deref())?;
+        let entity = match object.get("@type") {
+            Some(serde_json::Value::Array(types)) => types.first().and_then(|t| t.as_str()),
+            Some(other) => other.as_str(),
+            None => None,
+        };
+        Some(Extracted::new(
+            value,
+            ExtractSource::JsonLd,
+            format!("{}.{}", entity.unwrap_or("Thing"), property),
+        ))
+    })
+}
+
+fn normalize_json_ld_property(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// The first property named `wanted` (or `alias`), with its name as spelled on the page.
+fn find_json_ld_property<'a>(
+    value: &'a serde_json::Value,
+    wanted: &str,
+    alias: Option<&str>,
+    depth: usize,
+) -> Option<(&'a str, &'a serde_json::Value)> {
+    match value {
+        serde_json::Value::Array(items) => items
+            .iter()
+            .find_map(|item| find_json_ld_property(item, wanted, alias, depth)),
+        serde_json::Value::Object(map) => {
+            let properties = || {
+                map.iter()
+                    .filter(|(key, value)| !key.starts_with('@') && !value.is_null())
+            };
+            let direct = properties()
+                .find(|(key, _)| {
+                    let key = normalize_json_ld_property(key);
+                    key == wanted || Some(key.as_str()) == alias
+                })
+                .map(|(key, value)| (key.as_str(), value));
+            if direct.is_some() || depth >= JSON_LD_MAX_DEPTH {
+                return direct;
+            }
+            properties()
+                .find_map(|(_, value)| find_json_ld_property(value, wanted, alias, depth + 1))
+        }
+        _ => None,
+    }
+}
+
+/// Shape a JSON-LD value for the requested `field_type`. Untyped fields keep objects
+/// whole (a salary range stays a `MonetaryAmount`) except named entities such as an
+/// author or brand, which collapse to their `name`. `None` lets the heuristics run.
+fn coerce_json_ld_value(
+    value: &serde_json::Value,
+    field_type: Option<&str>,
+) -> Option<serde_json::Value> {
+    let entity_name = |value: &serde_json::Value| {
+        value
+            .get("name")
+            .and_then(|name| name.as_str())
+            .map(|name| serde_json::Value::String(name.trim().to_string()))
+    };
+    match field_type.map(|t| t.to_ascii_lowercase()).as_deref() {
+        Some("number" | "integer" | "int" | "float" | "double") => {
+            let scalar = match value {
+                serde_json::Value::Object(map) => ["value", "ratingValue", "price"]
+                    .iter()
+                    .find_map(|key| map.get(*key))?,
+                other => other,
+            };
+            match scalar {
+                serde_json::Value::Number(_) => Some(scalar.clone()),
+                serde_json::Value::String(s) => s
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(serde_json::Value::Number),
+                _ => None,
+            }
+        }
+        Some("array" | "list") => match value {
+            serde_json::Value::Array(_) => Some(value.clone()),
+            other => Some(serde_json::Value::Array(vec![other.clone()])),
+        },
+        Some("object" | "dict" | "map") => value.is_object().then(|| value.clone()),
+        Some("string") => match value {
+            serde_json::Value::String(s) => Some(serde_json::Value::String(s.trim().to_string())),
+            serde_json::Value::Number(n) => Some(serde_json::Value::String(n.to_string())),
+            serde_json::Value::Object(_) => entity_name(value),
+            _ => None,
+        },
+        _ => match value {
+            serde_json::Value::Object(map) if map.contains_key("name") => entity_name(value),
+            other => Some(other.clone()),
+        },
+    }
+}
+
 fn infer_page_type(scrape: &ScrapeResponse) -> String {
     let url = scrape.url.to_ascii_lowercase();
     let title = scrape.title.to_ascii_lowercase();
@@ -1113,7 +1564,7 @@ fn extract_price(content: &str) -> serde_json::Value {
 
 /// ADVANCED: Extract price with heuristic search in tables/lists near keyword
 /// Hallucination protection: returns null if not found within 500 chars
-fn extract_price_advanced(content: &str, keyword: &str) -> serde_json::Value {
+fn extract_price_advanced(content: &str, keyword: &str) -> Extracted {
     let price_re = Regex::new(r"[\$€£¥₹][\s]?[0-9]{1,3}(?:[,.]?[0-9]{3})*(?:[.,][0-9]{2})?|[0-9]{1,3}(?:[,.]?[0-9]{3})*(?:[.,][0-9]{2})?\s?(?:USD|EUR|GBP|JPY|INR)").unwrap();
 
     // First, try to find near keyword
@@ -1122,19 +1573,35 @@ fn extract_price_advanced(content: &str, keyword: &str) -> serde_json::Value {
 
     if let Some(pos) = content_lower.find(&keyword_lower) {
         // Search within 500 chars after keyword (hallucination protection limit)
-        let search_area: String = content.chars().skip(pos).take(500).collect();
+        let search_area: String = content
+            .chars()
+            .skip(pos)
+            .take(KEYWORD_WINDOW_CHARS)
+            .collect();
         if let Some(m) = price_re.find(&search_area) {
-            return serde_json::Value::String(m.as_str().to_string());
+            let distance = search_area[..m.start()]
+                .chars()
+                .count()
+                .saturating_sub(keyword_lower.chars().count());
+            return Extracted::near_keyword(
+                serde_json::Value::String(m.as_str().to_string()),
+                distance,
+                excerpt_at(content, pos),
+            );
         }
     }
 
     // Fallback: Try to find any price in content (first occurrence only)
     if let Some(m) = price_re.find(content) {
-        return serde_json::Value::String(m.as_str().to_string());
+        return Extracted::new(
+            serde_json::Value::String(m.as_str().to_string()),
+            ExtractSource::FallbackRegex,
+            format!("first price on the page: \"{}\"", m.as_str()),
+        );
     }
 
     // HALLUCINATION PROTECTION: Return null if no price found
-    serde_json::Value::Null
+    Extracted::null()
 }
 
 /// Extract dates from content
@@ -1160,29 +1627,37 @@ fn extract_date_from_content(content: &str) -> serde_json::Value {
 
 /// ADVANCED: Extract number near a keyword WITH HALLUCINATION PROTECTION
 /// Returns null if not found within 500 chars of keyword
-fn extract_number_with_hallucination_check(content: &str, keyword: &str) -> serde_json::Value {
+fn extract_number_with_hallucination_check(content: &str, keyword: &str) -> Extracted {
     let content_lower = content.to_lowercase();
     let variants = keyword_variants(keyword);
     if let Some((pos, keyword_len)) = find_keyword_position(&content_lower, &variants) {
         // Look for numbers within 500 chars after keyword (hallucination protection)
-        let search_area: String = content.chars().skip(pos + keyword_len).take(500).collect();
+        let search_area: String = content
+            .chars()
+            .skip(pos + keyword_len)
+            .take(KEYWORD_WINDOW_CHARS)
+            .collect();
         let num_re = Regex::new(r"\d+(?:[.,]\d+)?").unwrap();
         if let Some(m) = num_re.find(&search_area) {
             if let Ok(num) = m.as_str().replace(",", "").parse::<f64>() {
                 if let Some(json_num) = serde_json::Number::from_f64(num) {
-                    return serde_json::Value::Number(json_num);
+                    return Extracted::near_keyword(
+                        serde_json::Value::Number(json_num),
+                        search_area[..m.start()].chars().count(),
+                        excerpt_at(content, pos),
+                    );
                 }
             }
         }
     }
 
     // HALLUCINATION PROTECTION: Return null if not found
-    serde_json::Value::Null
+    Extracted::null()
 }
 
 /// ADVANCED: Extract text near a keyword WITH HALLUCINATION PROTECTION
 /// Returns null if not found within 500 chars of keyword
-fn extract_text_with_hallucination_check(content: &str, keyword: &str) -> serde_json::Value {
+fn extract_text_with_hallucination_check(content: &str, keyword: &str) -> Extracted {
     let content_lower = content.to_lowercase();
     let variants = keyword_variants(keyword);
     if let Some((pos, keyword_len)) = find_keyword_position(&content_lower, &variants) {
@@ -1190,41 +1665,50 @@ fn extract_text_with_hallucination_check(content: &str, keyword: &str) -> serde_
         let after: String = content
             .chars()
             .skip(pos + keyword_len)
-            .take(500)
+            .take(KEYWORD_WINDOW_CHARS)
             .take_while(|c| *c != '\n')
             .collect();
 
         let trimmed = after.trim().trim_start_matches(':').trim();
         if !trimmed.is_empty() && trimmed.len() > 2 {
             // Minimum 3 chars to be valid
-            return serde_json::Value::String(trimmed.to_string());
+            return Extracted::near_keyword(
+                serde_json::Value::String(trimmed.to_string()),
+                0,
+                excerpt_at(content, pos),
+            );
         }
     }
 
     // HALLUCINATION PROTECTION: Return null if not found
-    serde_json::Value::Null
+    Extracted::null()
 }
 
 /// ADVANCED: Extract list near keyword, search in <ul>, <table>, <dl> structures
 /// Hallucination protection: returns null if not found within 500 chars
-fn extract_list_near_keyword_advanced(content: &str, keyword: &str) -> serde_json::Value {
+fn extract_list_near_keyword_advanced(content: &str, keyword: &str) -> Extracted {
     let content_lower = content.to_lowercase();
     let variants = keyword_variants(keyword);
 
     if let Some((pos, keyword_len)) = find_keyword_position(&content_lower, &variants) {
         // Look for bullet points or numbered items within 500 chars (hallucination protection)
-        let search_area: String = content.chars().skip(pos + keyword_len).take(500).collect();
+        let search_area: String = content
+            .chars()
+            .skip(pos + keyword_len)
+            .take(KEYWORD_WINDOW_CHARS)
+            .collect();
+        let is_item = |l: &str| {
+            let trimmed = l.trim();
+            trimmed.starts_with('-')
+                || trimmed.starts_with('•')
+                || trimmed.starts_with('*')
+                || trimmed.starts_with("1.")
+                || trimmed.starts_with("2.")
+                || trimmed.starts_with("3.")
+        };
         let items: Vec<serde_json::Value> = search_area
             .lines()
-            .filter(|l| {
-                let trimmed = l.trim();
-                trimmed.starts_with('-')
-                    || trimmed.starts_with('•')
-                    || trimmed.starts_with('*')
-                    || trimmed.starts_with("1.")
-                    || trimmed.starts_with("2.")
-                    || trimmed.starts_with("3.")
-            })
+            .filter(|l| is_item(l))
             .take(10)
             .map(|l| {
                 let cleaned = l
@@ -1245,12 +1729,21 @@ fn extract_list_near_keyword_advanced(content: &str, keyword: &str) -> serde_jso
             .collect();
 
         if !items.is_empty() {
-            return serde_json::Value::Array(items);
+            let distance = search_area
+                .lines()
+                .take_while(|l| !is_item(l))
+                .map(|l| l.chars().count() + 1)
+                .sum();
+            return Extracted::near_keyword(
+                serde_json::Value::Array(items),
+                distance,
+                excerpt_at(content, pos),
+            );
         }
     }
 
     // HALLUCINATION PROTECTION: Return null if no list found
-    serde_json::Value::Null
+    Extracted::null()
 }
 
 fn keyword_variants(keyword: &str) -> Vec<String> {
@@ -1613,6 +2106,7 @@ mod tests {
                 json_found: false,
                 settle_time_ms: None,
                 noise_reduction_ratio: 0.0,
+                consent_nodes_removed: 0,
             },
             meta_description: String::new(),
             meta_keywords: String::new(),
@@ -1622,6 +2116,8 @@ mod tests {
                 .map(|u| Link {
                     url: u.to_string(),
                     text: String::new(),
+                    nofollow: false,
+                    detail: None,
                 })
                 .collect(),
             images: vec![],
@@ -1649,6 +2145,29 @@ mod tests {
             auth_risk_score: None,
             detection_factors: vec![],
             final_url: None,
+            redirect_chain: Vec::new(),
+            metrics: None,
+            content_format: None,
+            page_count: None,
+            feed_urls: Vec::new(),
+            feed_items: Vec::new(),
+            fetch_attempts: None,
+            block_reason: None,
+            asset_manifest: None,
+            charset: None,
+            etag: None,
+            last_modified: None,
+            revalidated: false,
+            structured_data: Vec::new(),
+            screenshot: None,
+            robots_directives: Vec::new(),
+            selector_results: None,
+            alternate_languages: Vec::new(),
+            provenance: Vec::new(),
+            sections: Vec::new(),
+            title_source: None,
+            meta_description_source: None,
+            quality: None,
         }
     }
 
@@ -1833,6 +2352,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sparse_check_normalizes_dense_script_counts() {
+        let mut scrape = mk_scrape(
+            "https://example.jp/",
+            "読み込み中\n少々お待ちください",
+            vec![],
+        );
+        scrape.language = "ja".to_string();
+        scrape.word_count = 14;
+        assert!(is_sparse_content(&scrape, 10));
+        scrape.word_count = 40;
+        assert!(!is_sparse_content(&scrape, 10));
+        scrape.language = "en".to_string();
+        scrape.word_count = 14;
+        assert!(!is_sparse_content(&scrape, 10));
+    }
+
+    #[test]
+    fn json_and_text_bodies_are_never_placeholders() {
+        let mut scrape = mk_scrape("https://api.example.com/ping", "```json\n{}\n```", vec![]);
+        scrape.word_count = 0;
+        assert!(is_sparse_content(&scrape, 10));
+        scrape.content_format = Some("json".to_string());
+        assert!(!is_sparse_content(&scrape, 10));
+        scrape.content_format = Some("text".to_string());
+        assert!(!is_sparse_content(&scrape, 10));
+    }
+
     #[test]
     fn extract_rustdoc_module_names_simple_relative_url() {
         let clean = "[init](init/index.html)Variable initialization.\n[optim](optim/index.html)Optimizers.";
@@ -1886,4 +2433,189 @@ mod tests {
             result.warnings
         );
     }
+
+    #[test]
+    fn schema_fields_prefer_json_ld_properties() {
+        let mut scrape = mk_scrape(
+            "https://shop.example.com/widget",
+            "Widget. Ships in two days.",
+            vec![],
+        );
+        scrape.structured_data = vec![
+            serde_json::json!({
+                "@type": "Product",
+                "name": "Widget",
+                "brand": {"@type": "Brand", "name": "Acme"},
+                "offers": {"@type": "Offer", "price": "19.99", "priceCurrency": "USD"}
+            }),
+            serde_json::json!({
+                "@type": "JobPosting",
+                "title": "Widget engineer",
+                "baseSalary": {
+                    "@type": "MonetaryAmount",
+                    "currency": "USD",
+                    "value": {"@type": "QuantitativeValue", "minValue": 90000, "maxValue": 120000}
+                }
+            }),
+        ];
+        let field = |name: &str, field_type: Option<&str>| ExtractField {
+            name: name.to_string(),
+            description: String::new(),
+            field_type: field_type.map(str::to_string),
+            required: None,
+        };
+        let schema = vec![
+            field("price", Some("number")),
+            field("price_currency", None),
+            field("brand", None),
+            field("salary", None),
+            field("stock", None),
+        ];
+
+        let result = extract_from_scrape(&scrape, Some(schema), None, true, 500, None, None)
+            .expect("extract_from_scrape should succeed");
+        let data = &result.extracted_data;
+        assert_eq!(data["price"], serde_json::json!(19.99));
+        assert_eq!(data["price_currency"], "USD");
+        assert_eq!(data["brand"], "Acme");
+        assert_eq!(data["salary"]["value"]["maxValue"], 120000);
+        // No JSON-LD property: the heuristics still decide.
+        assert!(data["stock"].is_null());
+    }
+
+    #[test]
+    fn field_provenance_follows_the_path_each_field_took() {
+        let schema = || {
+            vec![
+                ExtractField {
+                    name: "price".to_string(),
+                    description: "product price".to_string(),
+                    field_type: None,
+                    required: None,
+                },
+                ExtractField {
+                    name: "author".to_string(),
+                    description: "author".to_string(),
+                    field_type: None,
+                    required: None,
+                },
+            ]
+        };
+        let extract = |scrape: &ScrapeResponse, strict: bool| {
+            extract_from_scrape(scrape, Some(schema()), None, strict, 500, None, None).unwrap()
+        };
+        let body = "Widget Pro is our best seller and ships worldwide.\nPrice: $49.00 per seat";
+
+        let mut scrape = mk_scrape("https://shop.example.com/widget", body, vec![]);
+        scrape.word_count = 40;
+        scrape.author = Some("Jane Doe".to_string());
+        scrape.structured_data = vec![serde_json::json!({
+            "@type": "Product",
+            "offers": {"@type": "Offer", "price": "45.00"}
+        })];
+        let with_json_ld = extract(&scrape, false);
+        let price = &with_json_ld.field_provenance["price"];
+        assert_eq!(with_json_ld.extracted_data["price"], "45.00");
+        assert_eq!(price.source, ExtractSource::JsonLd);
+        assert_eq!(price.location.as_deref(), Some("Product.price"));
+        assert_eq!(
+            with_json_ld.field_provenance["author"].source,
+            ExtractSource::Meta
+        );
+
+        // Without JSON-LD the price comes from the text right after its keyword.
+        scrape.structured_data.clear();
+        scrape.author = None;
+        let near_keyword = extract(&scrape, false);
+        let price = &near_keyword.field_provenance["price"];
+        assert_eq!(near_keyword.extracted_data["price"], "$49.00");
+        assert_eq!(price.source, ExtractSource::KeywordProximity);
+        assert_eq!(price.distance, Some(2));
+        assert!(price.location.as_deref().unwrap().contains("Price: $49.00"));
+        let author = &near_keyword.field_provenance["author"];
+        assert_eq!(author.source, ExtractSource::Null);
+        assert_eq!(author.location, None);
+
+        // A price far from any keyword is a page-wide regex hit, trusted least.
+        scrape.clean_content = format!("{}\nOnly $49.00 today", "Widget Pro ".repeat(10));
+        let fallback = extract(&scrape, false);
+        let price = &fallback.field_provenance["price"];
+        assert_eq!(fallback.extracted_data["price"], "$49.00");
+        assert_eq!(price.source, ExtractSource::FallbackRegex);
+        assert!(price.confidence < near_keyword.field_provenance["price"].confidence);
+
+        // The overall confidence is the mean of the fields'.
+        assert!(with_json_ld.confidence > near_keyword.confidence);
+        let mean = near_keyword
+            .field_provenance
+            .values()
+            .map(|p| p.confidence)
+            .sum::<f64>()
+            / 2.0;
+        assert!((near_keyword.confidence - mean).abs() < 1e-9);
+
+        // Strict output keeps exactly the schema's keys; provenance stays beside it.
+        let strict = extract(&scrape, true);
+        let mut keys: Vec<&String> = strict.extracted_data.as_object().unwrap().keys().collect();
+        keys.sort();
+        assert_eq!(keys, ["author", "price"]);
+        assert_eq!(strict.field_provenance.len(), 2);
+    }
+
+    #[test]
+    fn batch_summary_builds_rows_and_ranks_null_fields() {
+        let schema = ["name", "price", "rating"]
+            .iter()
+            .map(|name| ExtractField {
+                name: name.to_string(),
+                description: name.to_string(),
+                field_type: None,
+                required: None,
+            })
+            .collect::<Vec<_>>();
+        let item = |url: &str, data: Option<serde_json::Value>| ExtractBatchItem {
+            url: url.to_string(),
+            success: data.is_some(),
+            confidence: data.as_ref().map(|_| 0.8),
+            extraction: data.map(|extracted_data| ExtractResponse {
+                url: url.to_string(),
+                title: String::new(),
+                extracted_data,
+                raw_content_preview: String::new(),
+                extraction_method: "schema_based".to_string(),
+                field_count: 3,
+                confidence: 0.8,
+                duration_ms: 1,
+                warnings: vec![],
+                selector_results: None,
+                field_provenance: BTreeMap::new(),
+            }),
+            error: None,
+            duplicate_of: None,
+            duration_ms: 1,
+        };
+        let results = vec![
+            item(
+                "https://shop.example.com/a",
+                Some(serde_json::json!({"name": "A", "price": "9.99", "rating": null})),
+            ),
+            item(
+                "https://shop.example.com/b",
+                Some(serde_json::json!({"name": "B", "price": "", "rating": null})),
+            ),
+            item("https://shop.example.com/c", None),
+        ];
+
+        let (rows, nulls) = summarize_batch(&results, Some(&schema));
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1]["_url"], "https://shop.example.com/b");
+        assert_eq!(rows[1]["name"], "B");
+        let ranked: Vec<_> = nulls
+            .iter()
+            .map(|n| (n.field.as_str(), n.null_count))
+            .collect();
+        assert_eq!(ranked, [("rating", 2), ("price", 1), ("name", 0)]);
+        assert_eq!(nulls[0].null_ratio, 1.0);
+    }
 }
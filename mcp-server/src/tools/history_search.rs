@@ -0,0 +1,253 @@
+//! Browse, search and purge the semantic memory behind `history_search` and `GET /history`.
+//!
+//! `research_history` answers "is this already cached?" with a strict threshold; this
+//! module is the broader view: every logged search and scrape, optionally narrowed by
+//! kind and time window, ranked by similarity when a query is given and newest-first
+//! otherwise.
+
+use crate::core::config;
+use crate::core::error::ScoutError;
+use crate::history::{EntryType, HistoryEntry, HistoryFilter, MemoryManager};
+use crate::types::*;
+use crate::AppState;
+use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const DEFAULT_LIMIT: usize = 20;
+pub const MAX_LIMIT: usize = 100;
+/// Looser than `research_history`'s 0.60: this is for recall, not cache hits.
+pub const DEFAULT_THRESHOLD: f32 = 0.4;
+/// Rows scanned when listing without a query, before sorting newest-first.
+const LIST_SCAN_CAP: usize = 1000;
+const MEMORY_WAIT: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone)]
+pub struct HistorySearchParams {
+    pub query: String,
+    pub filter: HistoryFilter,
+    pub limit: usize,
+    pub threshold: f32,
+}
+
+impl HistorySearchParams {
+    /// Validate a `GET /history` query (the MCP tool builds the same struct).
+    pub fn from_query(query: &HistoryQuery, now: DateTime<Utc>) -> Result<Self, ScoutError> {
+        let entry_type = match query.kind.as_deref().map(str::trim) {
+            None | Some("") | Some("all") => None,
+            Some(raw) => Some(EntryType::parse_str(raw).ok_or_else(|| {
+                ScoutError::invalid_input(format!(
+                    "Invalid kind '{}': expected 'search' or 'scrape'",
+                    raw
+                ))
+            })?),
+        };
+
+        let since = match (query.since.as_deref(), query.time_range.as_deref()) {
+            (Some(raw), _) => Some(parse_timestamp("since", raw)?),
+            (None, Some(range)) => Some(time_range_start(range, now).ok_or_else(|| {
+                ScoutError::invalid_input(format!(
+                    "Invalid time_range '{}': expected day, week, month or year",
+                    range
+                ))
+            })?),
+            (None, None) => None,
+        };
+        let until = query
+            .until
+            .as_deref()
+            .map(|raw| parse_timestamp("until", raw))
+            .transpose()?;
+        if let (Some(since), Some(until)) = (since, until) {
+            if since > until {
+                return Err(ScoutError::invalid_input("since must not be after until"));
+            }
+        }
+
+        Ok(Self {
+            query: query.q.clone().unwrap_or_default().trim().to_string(),
+            filter: HistoryFilter {
+                entry_type,
+                since,
+                until,
+            },
+            limit: query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+            threshold: query.threshold.unwrap_or(DEFAULT_THRESHOLD).clamp(0.0, 1.0),
+        })
+    }
+}
+
+/// Start of a named window ending at `now`.
+pub fn time_range_start(range: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let span = match range.trim().to_ascii_lowercase().as_str() {
+        "day" => ChronoDuration::days(1),
+        "week" => ChronoDuration::weeks(1),
+        "month" => ChronoDuration::days(30),
+        "year" => ChronoDuration::days(365),
+        _ => return None,
+    };
+    Some(now - span)
+}
+
+fn parse_timestamp(field: &str, raw: &str) -> Result<DateTime<Utc>, ScoutError> {
+    DateTime::parse_from_rfc3339(raw.trim())
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| {
+            ScoutError::invalid_input(format!(
+                "Invalid {} '{}': {} (expected RFC 3339)",
+                field, raw, e
+            ))
+        })
+}
+
+/// The memory manager, or an `unavailable` error that says why there is none.
+pub async fn require_memory(state: &Arc<AppState>) -> Result<Arc<MemoryManager>, ScoutError> {
+    if config::lancedb_uri().is_none() {
+        return Err(ScoutError::unavailable(
+            "Semantic memory is disabled (CORTEX_SCOUT_MEMORY_DISABLED is set or LANCEDB_URI is empty); no history is recorded.",
+        ));
+    }
+    if let Some(memory) = state.get_memory_or_wait(MEMORY_WAIT).await {
+        return Ok(memory);
+    }
+    Err(if state.is_memory_pending() {
+        ScoutError::unavailable("Semantic memory is still initializing; retry shortly.")
+    } else {
+        ScoutError::unavailable("Semantic memory failed to initialize; check the server log.")
+    })
+}
+
+fn summarize(entry: HistoryEntry, similarity: Option<f32>) -> HistoryEntrySummary {
+    HistoryEntrySummary {
+        result_count: entry.result_count(),
+        id: entry.id,
+        kind: entry.entry_type.as_str().to_string(),
+        query: entry.query,
+        timestamp: entry.timestamp.to_rfc3339(),
+        similarity,
+        domain: entry.domain,
+        summary: entry.summary,
+    }
+}
+
+pub async fn search(
+    state: &Arc<AppState>,
+    params: HistorySearchParams,
+) -> Result<HistorySearchResponse> {
+    let memory = require_memory(state).await?;
+
+    let entries: Vec<HistoryEntrySummary> = if params.query.is_empty() {
+        let mut rows = memory
+            .search_history_filtered("", LIST_SCAN_CAP, 0.0, &params.filter)
+            .await?;
+        rows.sort_by_key(|(entry, _)| std::cmp::Reverse(entry.timestamp));
+        rows.truncate(params.limit);
+        rows.into_iter().map(|(e, _)| summarize(e, None)).collect()
+    } else {
+        memory
+            .search_history_filtered(
+                &params.query,
+                params.limit,
+                params.threshold,
+                &params.filter,
+            )
+            .await?
+            .into_iter()
+            .map(|(e, score)| summarize(e, Some(score)))
+            .collect()
+    };
+
+    Ok(HistorySearchResponse {
+        query: params.query,
+        total: entries.len(),
+        entries,
+    })
+}
+
+/// Delete everything recorded more than `older_than_days` days ago (`0` clears all).
+pub async fn purge(state: &Arc<AppState>, older_than_days: u32) -> Result<HistoryPurgeResponse> {
+    let memory = require_memory(state).await?;
+    let cutoff = Utc::now() - ChronoDuration::days(i64::from(older_than_days));
+    let deleted = memory.delete_older_than(cutoff).await?;
+    Ok(HistoryPurgeResponse {
+        older_than_days,
+        cutoff: cutoff.to_rfc3339(),
+        deleted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn params_parse_kind_and_time_window() {
+        let params = HistorySearchParams::from_query(
+            &HistoryQuery {
+                q: Some("  rust async ".into()),
+                kind: Some("Scrape".into()),
+                time_range: Some("week".into()),
+                limit: Some(500),
+                ..Default::default()
+            },
+            now(),
+        )
+        .unwrap();
+        assert_eq!(params.query, "rust async");
+        assert!(matches!(params.filter.entry_type, Some(EntryType::Scrape)));
+        assert_eq!(
+            params.filter.since.unwrap().to_rfc3339(),
+            "2026-03-03T12:00:00+00:00"
+        );
+        assert_eq!(params.limit, MAX_LIMIT);
+        assert_eq!(params.threshold, DEFAULT_THRESHOLD);
+
+        // An explicit `since` wins over `time_range`.
+        let params = HistorySearchParams::from_query(
+            &HistoryQuery {
+                since: Some("2026-01-01T00:00:00Z".into()),
+                time_range: Some("day".into()),
+                ..Default::default()
+            },
+            now(),
+        )
+        .unwrap();
+        assert_eq!(
+            params.filter.since.unwrap().to_rfc3339(),
+            "2026-01-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn params_reject_bad_input() {
+        for query in [
+            HistoryQuery {
+                kind: Some("crawl".into()),
+                ..Default::default()
+            },
+            HistoryQuery {
+                time_range: Some("fortnight".into()),
+                ..Default::default()
+            },
+            HistoryQuery {
+                since: Some("yesterday".into()),
+                ..Default::default()
+            },
+            HistoryQuery {
+                since: Some("2026-03-02T00:00:00Z".into()),
+                until: Some("2026-03-01T00:00:00Z".into()),
+                ..Default::default()
+            },
+        ] {
+            let err = HistorySearchParams::from_query(&query, now()).unwrap_err();
+            assert_eq!(err.kind, crate::core::error::ErrorKind::InvalidInput);
+        }
+    }
+}
@@ -1,6 +1,14 @@
 pub mod batch_scrape;
 pub mod crawl;
+pub mod crawl_graph;
+pub mod crawl_state;
 pub mod deep_research;
 pub mod extract;
+pub mod history_search;
+pub mod monitor;
+pub mod research;
 pub mod scrape;
+pub mod scrape_compare;
+pub mod scrape_diff;
 pub mod search;
+pub mod semantic_dedup;
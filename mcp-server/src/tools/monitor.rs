@@ -0,0 +1,671 @@
+//! Scheduled change monitoring ("monitor" mode).
+//!
+//! `monitor_add` registers a set of URLs with a check interval. A background task
+//! ([`spawn_scheduler`]) wakes every [`TICK`] and re-scrapes the targets that are due, at
+//! most `CORTEX_SCOUT_MONITOR_CONCURRENCY` at a time. Checks scrape with `revalidate`, so
+//! an unchanged page with an `ETag` or `Last-Modified` usually costs a conditional request.
+//! A page whose normalized `clean_content` hashes differently from the last check yields a
+//! [`MonitorEvent`] with the headings added/removed and the word-count delta.
+//!
+//! State lives under `CORTEX_SCOUT_MONITOR_DIR`, else `~/.cortex-scout/data/monitors`.
+//! `monitors.json` holds the definitions and each target's schedule and is rewritten after
+//! every change, so a restart picks the schedule up where it stopped. `events.jsonl` holds
+//! the newest [`MAX_STORED_EVENTS`] change events, and `snapshots/<monitor_id>/` the last
+//! scrape of each target to diff against.
+//!
+//! A target that fails [`FAILURES_BEFORE_PAUSE`] times in a row is paused: its next check
+//! waits twice the interval, doubling with every further failure up to [`MAX_PAUSE`]. One
+//! successful check puts it back on its interval.
+
+use crate::core::error::ScoutError;
+use crate::scrape::{self, ScrapeUrlOptions};
+use crate::scrape_diff;
+use crate::types::Heading;
+use crate::AppState;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
+use tracing::{info, warn};
+
+pub const MIN_INTERVAL_SECS: u64 = 60;
+pub const DEFAULT_INTERVAL_SECS: u64 = 60 * 60;
+pub const MAX_INTERVAL_SECS: u64 = 30 * 24 * 60 * 60;
+pub const MAX_TARGETS: usize = 100;
+pub const DEFAULT_EVENT_LIMIT: usize = 100;
+pub const MAX_STORED_EVENTS: usize = 10_000;
+pub const FAILURES_BEFORE_PAUSE: u32 = 3;
+pub const MAX_PAUSE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// How often the scheduler looks for due targets.
+pub const TICK: Duration = Duration::from_secs(30);
+
+const MONITORS_FILE: &str = "monitors.json";
+const EVENTS_FILE: &str = "events.jsonl";
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// `CORTEX_SCOUT_MONITOR_DIR`, else `~/.cortex-scout/data/monitors`.
+pub fn default_monitor_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("CORTEX_SCOUT_MONITOR_DIR") {
+        let dir = dir.trim();
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    dirs::home_dir().map(|home| home.join(".cortex-scout").join("data").join("monitors"))
+}
+
+/// One watched URL and its schedule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorTarget {
+    pub url: String,
+    /// SHA-256 of the normalized `clean_content` at the last successful check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_checked_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_changed_at: Option<DateTime<Utc>>,
+    pub next_check_at: DateTime<Utc>,
+    #[serde(default)]
+    pub consecutive_failures: u32,
+    /// Set once the target has failed [`FAILURES_BEFORE_PAUSE`] times in a row.
+    #[serde(default)]
+    pub paused: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Monitor {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub interval_secs: u64,
+    #[serde(default)]
+    pub use_proxy: bool,
+    pub created_at: DateTime<Utc>,
+    pub targets: Vec<MonitorTarget>,
+}
+
+/// A change found by a scheduled check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorEvent {
+    pub monitor_id: String,
+    pub url: String,
+    pub detected_at: DateTime<Utc>,
+    pub word_count_before: usize,
+    pub word_count_after: usize,
+    pub word_count_delta: i64,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headings_added: Vec<Heading>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headings_removed: Vec<Heading>,
+}
+
+/// What `monitor_add` asks for; `None` fields take their defaults.
+#[derive(Debug, Clone, Default)]
+pub struct MonitorSpec {
+    pub urls: Vec<String>,
+    pub interval_secs: Option<u64>,
+    pub use_proxy: bool,
+    pub label: Option<String>,
+}
+
+/// Hash a page's text the way [`scrape_diff`] compares it, so whitespace churn from the
+/// markdown normalizer is not a change.
+pub fn content_hash(clean_content: &str) -> String {
+    let digest = Sha256::digest(scrape_diff::normalize_for_diff(clean_content).as_bytes());
+    hex::encode(&digest[..16])
+}
+
+/// Delay before the next check after `failures` consecutive failures: the interval until
+/// the target pauses, then twice the interval, doubling per further failure, capped at
+/// [`MAX_PAUSE`] (or the interval itself when that is longer).
+pub fn retry_delay(interval: Duration, failures: u32) -> Duration {
+    if failures < FAILURES_BEFORE_PAUSE {
+        return interval;
+    }
+    let doublings = (failures - FAILURES_BEFORE_PAUSE + 1).min(16);
+    interval
+        .saturating_mul(2u32.saturating_pow(doublings))
+        .min(MAX_PAUSE.max(interval))
+}
+
+/// Delays never exceed the longest interval; the cap only guards hand-edited state.
+fn chrono_delay(delay: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(delay.min(Duration::from_secs(MAX_INTERVAL_SECS)))
+        .expect("a 30-day delay fits a chrono::Duration")
+}
+
+/// Registered monitors, shared by the tools and the scheduler.
+#[derive(Debug)]
+pub struct Monitors {
+    dir: Option<PathBuf>,
+    monitors: Mutex<Vec<Monitor>>,
+    /// Serializes `events.jsonl` rewrites.
+    events_lock: Mutex<()>,
+    checks: Semaphore,
+}
+
+impl Monitors {
+    /// Monitors stored under `dir` (loaded from it if present); at most `concurrency`
+    /// checks run at once.
+    pub fn new(dir: Option<PathBuf>, concurrency: usize) -> Self {
+        let monitors = dir
+            .as_ref()
+            .and_then(|dir| std::fs::read(dir.join(MONITORS_FILE)).ok())
+            .and_then(|raw| match serde_json::from_slice(&raw) {
+                Ok(monitors) => Some(monitors),
+                Err(e) => {
+                    warn!("ignoring unreadable monitor definitions: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            dir,
+            monitors: Mutex::new(monitors),
+            events_lock: Mutex::new(()),
+            checks: Semaphore::new(concurrency.max(1)),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(
+            default_monitor_dir(),
+            crate::core::config::monitor_concurrency(),
+        )
+    }
+
+    fn dir(&self) -> Result<&Path> {
+        self.dir.as_deref().ok_or_else(|| {
+            ScoutError::unavailable("No monitor directory (set CORTEX_SCOUT_MONITOR_DIR or HOME)")
+                .into()
+        })
+    }
+
+    /// Register `spec`. Every target is due at once; its first check records the baseline.
+    pub async fn add(&self, spec: MonitorSpec, now: DateTime<Utc>) -> Result<Monitor> {
+        self.dir()?;
+        let interval_secs = spec.interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS);
+        if !(MIN_INTERVAL_SECS..=MAX_INTERVAL_SECS).contains(&interval_secs) {
+            return Err(ScoutError::invalid_input(format!(
+                "interval_secs must be between {} and {}",
+                MIN_INTERVAL_SECS, MAX_INTERVAL_SECS
+            ))
+            .into());
+        }
+        let mut urls: Vec<String> = Vec::new();
+        for url in spec.urls {
+            let url = url.trim().to_string();
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(ScoutError::invalid_input(format!(
+                    "Invalid URL '{}': must start with http:// or https://",
+                    url
+                ))
+                .into());
+            }
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        if urls.is_empty() || urls.len() > MAX_TARGETS {
+            return Err(ScoutError::invalid_input(format!(
+                "urls must list between 1 and {} URLs",
+                MAX_TARGETS
+            ))
+            .into());
+        }
+
+        let monitor = Monitor {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            label: spec
+                .label
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty()),
+            interval_secs,
+            use_proxy: spec.use_proxy,
+            created_at: now,
+            targets: urls
+                .into_iter()
+                .map(|url| MonitorTarget {
+                    url,
+                    content_hash: None,
+                    last_checked_at: None,
+                    last_changed_at: None,
+                    next_check_at: now,
+                    consecutive_failures: 0,
+                    paused: false,
+                    last_error: None,
+                })
+                .collect(),
+        };
+        let mut monitors = self.monitors.lock().await;
+        monitors.push(monitor.clone());
+        self.save(&monitors).await?;
+        info!(
+            "monitor {} added: {} target(s) every {}s",
+            monitor.id,
+            monitor.targets.len(),
+            monitor.interval_secs
+        );
+        Ok(monitor)
+    }
+
+    pub async fn list(&self) -> Vec<Monitor> {
+        self.monitors.lock().await.clone()
+    }
+
+    /// Drop a monitor and its snapshots; its events stay queryable. `false` when no
+    /// monitor has that id.
+    pub async fn remove(&self, id: &str) -> Result<bool> {
+        let mut monitors = self.monitors.lock().await;
+        let before = monitors.len();
+        monitors.retain(|m| m.id != id);
+        if monitors.len() == before {
+            return Ok(false);
+        }
+        self.save(&monitors).await?;
+        if let Some(dir) = &self.dir {
+            let snapshots = dir.join(SNAPSHOTS_DIR).join(id);
+            if let Err(e) = tokio::fs::remove_dir_all(&snapshots).await {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("monitor {}: failed to remove snapshots: {}", id, e);
+                }
+            }
+        }
+        info!("monitor {} removed", id);
+        Ok(true)
+    }
+
+    /// Events detected after `since` (all when `None`), optionally for one monitor,
+    /// oldest first and at most `limit`. Pass the last `detected_at` seen as the next
+    /// `since` to page through them.
+    pub async fn events(
+        &self,
+        since: Option<DateTime<Utc>>,
+        monitor_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<MonitorEvent>> {
+        let Some(dir) = &self.dir else {
+            return Ok(Vec::new());
+        };
+        let _guard = self.events_lock.lock().await;
+        Ok(read_events(&dir.join(EVENTS_FILE))
+            .await?
+            .into_iter()
+            .filter(|e| since.map_or(true, |since| e.detected_at > since))
+            .filter(|e| monitor_id.map_or(true, |id| e.monitor_id == id))
+            .take(limit)
+            .collect())
+    }
+
+    /// Append `events`, keeping the newest [`MAX_STORED_EVENTS`].
+    async fn record_events(&self, events: &[MonitorEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let dir = self.dir()?;
+        let path = dir.join(EVENTS_FILE);
+        let _guard = self.events_lock.lock().await;
+        let mut stored = read_events(&path).await?;
+        stored.extend_from_slice(events);
+        let skip = stored.len().saturating_sub(MAX_STORED_EVENTS);
+        let mut body = Vec::new();
+        for event in &stored[skip..] {
+            serde_json::to_writer(&mut body, event)?;
+            body.push(b'\n');
+        }
+        write_atomic(&path, &body).await
+    }
+
+    async fn save(&self, monitors: &[Monitor]) -> Result<()> {
+        let dir = self.dir()?;
+        write_atomic(
+            &dir.join(MONITORS_FILE),
+            &serde_json::to_vec_pretty(monitors)?,
+        )
+        .await
+    }
+
+    /// `(monitor_id, url, use_proxy, content_hash)` of every target due at `now`.
+    async fn due(&self, now: DateTime<Utc>) -> Vec<(String, String, bool, Option<String>)> {
+        self.monitors
+            .lock()
+            .await
+            .iter()
+            .flat_map(|m| {
+                m.targets
+                    .iter()
+                    .filter(|t| t.next_check_at <= now)
+                    .map(|t| {
+                        (
+                            m.id.clone(),
+                            t.url.clone(),
+                            m.use_proxy,
+                            t.content_hash.clone(),
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Record a check's outcome on its target (if the monitor still exists) and persist.
+    async fn finish_checks(
+        &self,
+        outcomes: Vec<(String, String, Result<(String, Option<MonitorEvent>)>)>,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut events = Vec::new();
+        let mut monitors = self.monitors.lock().await;
+        for (id, url, outcome) in outcomes {
+            let Some(monitor) = monitors.iter_mut().find(|m| m.id == id) else {
+                continue;
+            };
+            let interval = Duration::from_secs(monitor.interval_secs);
+            let Some(target) = monitor.targets.iter_mut().find(|t| t.url == url) else {
+                continue;
+            };
+            target.last_checked_at = Some(now);
+            match outcome {
+                Ok((hash, event)) => {
+                    target.content_hash = Some(hash);
+                    target.consecutive_failures = 0;
+                    target.paused = false;
+                    target.last_error = None;
+                    target.next_check_at = now + chrono_delay(interval);
+                    if let Some(event) = event {
+                        target.last_changed_at = Some(now);
+                        events.push(event);
+                    }
+                }
+                Err(e) => {
+                    target.consecutive_failures = target.consecutive_failures.saturating_add(1);
+                    target.paused = target.consecutive_failures >= FAILURES_BEFORE_PAUSE;
+                    target.last_error = Some(e.to_string());
+                    let delay = retry_delay(interval, target.consecutive_failures);
+                    target.next_check_at = now + chrono_delay(delay);
+                    warn!(
+                        "monitor {}: check of {} failed ({} in a row, next in {}s): {}",
+                        id,
+                        url,
+                        target.consecutive_failures,
+                        delay.as_secs(),
+                        e
+                    );
+                }
+            }
+        }
+        self.save(&monitors).await?;
+        drop(monitors);
+        self.record_events(&events).await
+    }
+}
+
+async fn read_events(path: &Path) -> Result<Vec<MonitorEvent>> {
+    let raw = match tokio::fs::read_to_string(path).await {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    // A torn last line from a crash is skipped rather than failing every query.
+    Ok(raw
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+async fn write_atomic(path: &Path, body: &[u8]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("creating {}", dir.display()))?;
+    }
+    let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+    tokio::fs::write(&tmp, body).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+/// Scrape `url` and compare it with the last check. Returns the new content hash and,
+/// when it differs from `previous_hash`, the change event. Block pages and auth walls
+/// count as failures and leave the stored snapshot untouched.
+async fn check_target(
+    state: &Arc<AppState>,
+    snapshot_dir: &Path,
+    monitor_id: &str,
+    url: &str,
+    use_proxy: bool,
+    previous_hash: Option<&str>,
+) -> Result<(String, Option<MonitorEvent>)> {
+    let current = scrape::scrape_url_full(
+        state,
+        url,
+        ScrapeUrlOptions {
+            use_proxy,
+            revalidate: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+    if let Some(err) = scrape::block_error(&current) {
+        return Err(err.into());
+    }
+    if let Some(reason) = current.auth_wall_reason.as_deref() {
+        return Err(
+            ScoutError::blocked(format!("{} is behind an auth wall: {}", url, reason)).into(),
+        );
+    }
+
+    let hash = content_hash(&current.clean_content);
+    if previous_hash == Some(hash.as_str()) {
+        return Ok((hash, None));
+    }
+    let previous = scrape_diff::load_snapshot(snapshot_dir, url).await;
+    scrape_diff::save_snapshot(snapshot_dir, url, &current).await?;
+    // The first check (no previous hash) only records the baseline.
+    let event = previous
+        .filter(|_| previous_hash.is_some())
+        .map(|previous| change_event(monitor_id, url, &previous, &current, Utc::now()));
+    Ok((hash, event))
+}
+
+fn change_event(
+    monitor_id: &str,
+    url: &str,
+    previous: &crate::types::ScrapeResponse,
+    current: &crate::types::ScrapeResponse,
+    detected_at: DateTime<Utc>,
+) -> MonitorEvent {
+    // Only the counts and headings are kept, so the text diff itself can be empty.
+    let diff = scrape_diff::diff_scrapes(previous, current, 0);
+    MonitorEvent {
+        monitor_id: monitor_id.to_string(),
+        url: url.to_string(),
+        detected_at,
+        word_count_before: previous.word_count,
+        word_count_after: current.word_count,
+        word_count_delta: diff.word_count_delta,
+        lines_added: diff.lines_added,
+        lines_removed: diff.lines_removed,
+        headings_added: diff.headings_added,
+        headings_removed: diff.headings_removed,
+    }
+}
+
+/// Check every due target, at most `CORTEX_SCOUT_MONITOR_CONCURRENCY` at a time. Returns
+/// how many were checked.
+pub async fn run_due(state: &Arc<AppState>) -> usize {
+    let monitors = &state.monitors;
+    let due = monitors.due(Utc::now()).await;
+    if due.is_empty() {
+        return 0;
+    }
+    let Ok(dir) = monitors.dir() else {
+        return 0;
+    };
+    let checks = due
+        .into_iter()
+        .map(|(id, url, use_proxy, previous_hash)| async move {
+            let _permit = monitors.checks.acquire().await.expect("semaphore closed");
+            let snapshot_dir = dir.join(SNAPSHOTS_DIR).join(&id);
+            let outcome = check_target(
+                state,
+                &snapshot_dir,
+                &id,
+                &url,
+                use_proxy,
+                previous_hash.as_deref(),
+            )
+            .await;
+            (id, url, outcome)
+        });
+    let outcomes = futures::future::join_all(checks).await;
+    let checked = outcomes.len();
+    if let Err(e) = monitors.finish_checks(outcomes, Utc::now()).await {
+        warn!("monitor: failed to store check results: {}", e);
+    }
+    checked
+}
+
+/// Run [`run_due`] every [`TICK`] for the life of the process. A tick waits for the
+/// previous one's checks, so a slow target is never checked twice at once.
+pub fn spawn_scheduler(state: Arc<AppState>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(TICK);
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tick.tick().await;
+            let checked = run_due(&state).await;
+            if checked > 0 {
+                info!("monitor: checked {} target(s)", checked);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("cortex-scout-monitor-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn spec(urls: &[&str]) -> MonitorSpec {
+        MonitorSpec {
+            urls: urls.iter().map(|u| u.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn failing_targets_pause_with_escalating_backoff() {
+        let hour = Duration::from_secs(60 * 60);
+        assert_eq!(retry_delay(hour, 1), hour);
+        assert_eq!(retry_delay(hour, 2), hour);
+        assert_eq!(retry_delay(hour, 3), hour * 2);
+        assert_eq!(retry_delay(hour, 4), hour * 4);
+        assert_eq!(retry_delay(hour, 40), MAX_PAUSE);
+        let fortnight = MAX_PAUSE * 2;
+        assert_eq!(retry_delay(fortnight, 5), fortnight);
+        assert_eq!(content_hash("a  b\n\n"), content_hash("a b"));
+    }
+
+    #[tokio::test]
+    async fn monitors_survive_a_restart_and_failures_reschedule() {
+        let dir = temp_dir();
+        let now = Utc::now();
+        let monitors = Monitors::new(Some(dir.clone()), 1);
+        assert!(monitors
+            .add(spec(&["ftp://example.com"]), now)
+            .await
+            .is_err());
+        assert!(monitors
+            .add(
+                MonitorSpec {
+                    interval_secs: Some(5),
+                    ..spec(&["https://example.com"])
+                },
+                now
+            )
+            .await
+            .is_err());
+        let added = monitors
+            .add(
+                spec(&["https://example.com/docs", "https://example.com/docs"]),
+                now,
+            )
+            .await
+            .unwrap();
+        assert_eq!(added.targets.len(), 1);
+        assert_eq!(monitors.due(now).await.len(), 1);
+
+        for _ in 0..FAILURES_BEFORE_PAUSE {
+            let outcome = Err(anyhow::anyhow!("connection refused"));
+            monitors
+                .finish_checks(
+                    vec![(added.id.clone(), "https://example.com/docs".into(), outcome)],
+                    now,
+                )
+                .await
+                .unwrap();
+        }
+
+        let reloaded = Monitors::new(Some(dir.clone()), 1);
+        let target = &reloaded.list().await[0].targets[0];
+        assert!(target.paused);
+        assert_eq!(target.consecutive_failures, FAILURES_BEFORE_PAUSE);
+        assert_eq!(
+            target.next_check_at,
+            now + chrono::Duration::seconds(2 * DEFAULT_INTERVAL_SECS as i64)
+        );
+        assert!(reloaded.due(now).await.is_empty());
+
+        assert!(reloaded.remove(&added.id).await.unwrap());
+        assert!(!reloaded.remove(&added.id).await.unwrap());
+        assert!(Monitors::new(Some(dir.clone()), 1).list().await.is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn events_are_filtered_by_time_and_monitor() {
+        let dir = temp_dir();
+        let monitors = Monitors::new(Some(dir.clone()), 1);
+        let t0 = Utc::now();
+        let event = |id: &str, minutes: i64| MonitorEvent {
+            monitor_id: id.to_string(),
+            url: "https://example.com/docs".to_string(),
+            detected_at: t0 + chrono::Duration::minutes(minutes),
+            word_count_before: 10,
+            word_count_after: 12,
+            word_count_delta: 2,
+            lines_added: 1,
+            lines_removed: 0,
+            headings_added: Vec::new(),
+            headings_removed: Vec::new(),
+        };
+        monitors
+            .record_events(&[event("a", 1), event("b", 2)])
+            .await
+            .unwrap();
+        monitors.record_events(&[event("a", 3)]).await.unwrap();
+
+        let all = monitors.events(None, None, 10).await.unwrap();
+        assert_eq!(all.len(), 3);
+        let since = monitors
+            .events(Some(t0 + chrono::Duration::minutes(1)), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(since, vec![event("b", 2), event("a", 3)]);
+        let only_a = monitors.events(None, Some("a"), 1).await.unwrap();
+        assert_eq!(only_a, vec![event("a", 1)]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,409 @@
+//! `research`: search, pick sources, scrape them and optionally extract fields in one
+//! call — the chain agents otherwise run as three or four tool calls — under one
+//! wall-clock budget.
+//!
+//! Search results are reranked against the query and picked best-first with one page per
+//! host, then a second page per host if that leaves fewer than `top_n`; never more. The
+//! picks are scraped concurrently with [`scrape_batch_with_budget`], so a source still
+//! loading when the budget runs out is reported as `budget_exceeded` instead of holding
+//! back the ones that finished. The search step may use at most half of the budget.
+
+use crate::batch_scrape::scrape_batch_with_budget;
+use crate::core::error::ScoutError;
+use crate::extract::extract_from_scrape;
+use crate::extractive::extractive_answer;
+use crate::rerank::Reranker;
+use crate::rust_scraper::QualityMode;
+use crate::scrape::ScrapeUrlOptions;
+use crate::search::search_web_with_params;
+use crate::types::*;
+use crate::AppState;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Most pages taken from one host.
+const MAX_PER_HOST: usize = 2;
+/// Sentences in a source's summary.
+const SUMMARY_SENTENCES: usize = 3;
+/// Summary fallback length when a page has no usable sentence or description.
+const SUMMARY_FALLBACK_CHARS: usize = 300;
+
+#[derive(Debug, Clone)]
+pub struct ResearchOptions {
+    pub top_n: usize,
+    /// Per-page cap on scraped content.
+    pub max_chars: usize,
+    pub quality_mode: Option<QualityMode>,
+    pub use_proxy: bool,
+    /// Fields to extract from every source; `prompt` works as in `extract_fields`.
+    pub schema: Option<Vec<ExtractField>>,
+    pub prompt: Option<String>,
+    pub budget: Duration,
+}
+
+impl Default for ResearchOptions {
+    fn default() -> Self {
+        Self {
+            top_n: 5,
+            max_chars: 5_000,
+            quality_mode: None,
+            use_proxy: false,
+            schema: None,
+            prompt: None,
+            budget: Duration::from_secs(crate::core::config::research_budget_secs()),
+        }
+    }
+}
+
+/// A picked search result with its reranker score and 1-based rank.
+#[derive(Debug, Clone)]
+struct Pick {
+    rank: usize,
+    result: SearchResult,
+    score: f32,
+}
+
+fn host_key(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_ascii_lowercase()))
+        .map(|h| h.trim_start_matches("www.").to_string())
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Pick up to `top_n` of `ranked` (best first): one page per host, then a second per
+/// host if that came up short. Returns the picks in rank order and how many results
+/// ranked above a pick were passed over because their host already had its share.
+fn select_sources(ranked: Vec<(SearchResult, f32)>, top_n: usize) -> (Vec<Pick>, usize) {
+    let ranked: Vec<(SearchResult, f32)> = ranked
+        .into_iter()
+        .filter(|(r, _)| r.url.starts_with("http"))
+        .collect();
+    let mut per_host: HashMap<String, usize> = HashMap::new();
+    let mut picked: Vec<usize> = Vec::new();
+    for limit in 1..=MAX_PER_HOST {
+        for (index, (result, _)) in ranked.iter().enumerate() {
+            if picked.len() >= top_n {
+                break;
+            }
+            if picked.contains(&index) {
+                continue;
+            }
+            let taken = per_host.entry(host_key(&result.url)).or_default();
+            if *taken < limit {
+                *taken += 1;
+                picked.push(index);
+            }
+        }
+    }
+    picked.sort_unstable();
+
+    let skipped = picked
+        .last()
+        .map_or(0, |&last| (0..last).filter(|i| !picked.contains(i)).count());
+    let picks = ranked
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| picked.contains(index))
+        .enumerate()
+        .map(|(position, (_, (result, score)))| Pick {
+            rank: position + 1,
+            result,
+            score,
+        })
+        .collect();
+    (picks, skipped)
+}
+
+fn summarize(query: &str, page: &ScrapeResponse) -> String {
+    if let Some(summary) =
+        extractive_answer(query, std::slice::from_ref(page), SUMMARY_SENTENCES, false)
+    {
+        return summary;
+    }
+    if !page.meta_description.trim().is_empty() {
+        return page.meta_description.trim().to_string();
+    }
+    page.clean_content
+        .chars()
+        .take(SUMMARY_FALLBACK_CHARS)
+        .collect()
+}
+
+/// Turn the batch results for `picks` into sources and failures, both in rank order.
+fn collect(
+    query: &str,
+    picks: &[Pick],
+    batch: ScrapeBatchResponse,
+    options: &ResearchOptions,
+) -> (Vec<ResearchSource>, Vec<ResearchFailure>) {
+    let mut by_url: HashMap<String, ScrapeBatchResult> = batch
+        .results
+        .into_iter()
+        .map(|item| (item.url.clone(), item))
+        .collect();
+    let extracting = options.schema.is_some() || options.prompt.is_some();
+
+    let mut sources = Vec::new();
+    let mut failed = Vec::new();
+    for pick in picks {
+        let url = pick.result.url.clone();
+        let failure = |reason: &str, error: Option<String>| ResearchFailure {
+            rank: pick.rank,
+            url: url.clone(),
+            reason: reason.to_string(),
+            error,
+        };
+        let Some(item) = by_url.remove(&url) else {
+            failed.push(failure("not_scraped", None));
+            continue;
+        };
+        if let Some(first) = item.duplicate_of {
+            failed.push(failure(
+                "duplicate",
+                Some(format!("same page as {}", first)),
+            ));
+            continue;
+        }
+        let Some(page) = item.data.filter(|_| item.success) else {
+            let reason = item.failure_reason.as_deref().unwrap_or("scrape_failed");
+            failed.push(failure(reason, item.error));
+            continue;
+        };
+
+        let mut source = ResearchSource {
+            rank: pick.rank,
+            url: page.url.clone(),
+            title: if page.title.trim().is_empty() {
+                pick.result.title.clone()
+            } else {
+                page.title.clone()
+            },
+            domain: page.domain.clone().or_else(|| pick.result.domain.clone()),
+            score: pick.score,
+            summary: summarize(query, &page),
+            word_count: page.word_count,
+            extraction_score: page.extraction_score,
+            extracted: None,
+            extraction_confidence: None,
+            warnings: Vec::new(),
+        };
+        if extracting {
+            match extract_from_scrape(
+                &page,
+                options.schema.clone(),
+                options.prompt.clone(),
+                true,
+                options.max_chars,
+                None,
+                None,
+            ) {
+                Ok(extraction) => {
+                    source.extracted = Some(extraction.extracted_data);
+                    source.extraction_confidence = Some(extraction.confidence);
+                    source.warnings = extraction.warnings;
+                }
+                Err(e) => source.warnings.push(format!("extraction_failed: {}", e)),
+            }
+        }
+        sources.push(source);
+    }
+    (sources, failed)
+}
+
+/// Search for `query`, scrape the best `top_n` results from distinct hosts and extract
+/// `schema` from each. Fails only when the search itself fails or runs out of time;
+/// sources that cannot be scraped are listed in `failed`.
+pub async fn research(
+    state: &Arc<AppState>,
+    query: &str,
+    options: ResearchOptions,
+) -> Result<ResearchResponse> {
+    let started = Instant::now();
+    let mut warnings = Vec::new();
+
+    let search_budget = options.budget / 2;
+    let search = search_web_with_params(state, query, None);
+    let (results, extras) = match tokio::time::timeout(search_budget, search).await {
+        Ok(outcome) => outcome?,
+        Err(_) => {
+            return Err(ScoutError::timeout(format!(
+                "search did not finish within {} ms of the research budget",
+                search_budget.as_millis()
+            ))
+            .into())
+        }
+    };
+    if !extras.degraded_engines.is_empty() {
+        warnings.push(format!(
+            "search_degraded:{}",
+            extras.degraded_engines.join("|")
+        ));
+    }
+
+    let results_considered = results.len();
+    let scores = Reranker::new(query).scores(&results);
+    let mut ranked: Vec<(SearchResult, f32)> = results.into_iter().zip(scores).collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    let (picks, skipped_same_domain) = select_sources(ranked, options.top_n.max(1));
+    info!(
+        "research: {} of {} results picked for {:?}",
+        picks.len(),
+        results_considered,
+        query
+    );
+
+    let remaining = options.budget.saturating_sub(started.elapsed());
+    let urls: Vec<String> = picks.iter().map(|p| p.result.url.clone()).collect();
+    let scrape_options = ScrapeUrlOptions {
+        use_proxy: options.use_proxy,
+        quality_mode: options.quality_mode,
+        ..Default::default()
+    };
+    let batch = scrape_batch_with_budget(
+        state,
+        urls.clone(),
+        urls.len(),
+        Some(options.max_chars),
+        scrape_options,
+        Some(remaining),
+    )
+    .await?;
+    let (sources, failed) = collect(query, &picks, batch, &options);
+    if sources.is_empty() && !picks.is_empty() {
+        warnings.push("no_source_scraped".to_string());
+    }
+
+    Ok(ResearchResponse {
+        query: query.to_string(),
+        results_considered,
+        skipped_same_domain,
+        sources,
+        failed,
+        budget_ms: options.budget.as_millis() as u64,
+        total_duration_ms: started.elapsed().as_millis() as u64,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str) -> SearchResult {
+        SearchResult {
+            url: url.to_string(),
+            title: format!("Result {}", url),
+            ..Default::default()
+        }
+    }
+
+    fn page(url: &str, clean_content: &str) -> ScrapeResponse {
+        ScrapeResponse::for_test(url, "Tokio tutorial", clean_content)
+    }
+
+    #[test]
+    fn picks_one_page_per_host_before_a_second() {
+        let ranked = vec![
+            (result("https://docs.rs/tokio/a"), 0.9),
+            (result("https://www.docs.rs/tokio/b"), 0.8),
+            (result("https://docs.rs/tokio/c"), 0.7),
+            (result("https://tokio.rs/tutorial"), 0.6),
+            (result("not a url"), 0.5),
+            (result("https://github.com/tokio-rs/tokio"), 0.4),
+        ];
+
+        let (picks, skipped) = select_sources(ranked.clone(), 3);
+        let urls: Vec<&str> = picks.iter().map(|p| p.result.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://docs.rs/tokio/a",
+                "https://tokio.rs/tutorial",
+                "https://github.com/tokio-rs/tokio"
+            ]
+        );
+        assert_eq!(picks.iter().map(|p| p.rank).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(skipped, 2);
+
+        // Short of distinct hosts, a second page per host fills in, but never a third.
+        let (picks, _) = select_sources(ranked, 10);
+        let urls: Vec<&str> = picks.iter().map(|p| p.result.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://docs.rs/tokio/a",
+                "https://www.docs.rs/tokio/b",
+                "https://tokio.rs/tutorial",
+                "https://github.com/tokio-rs/tokio"
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_failures_are_reported_per_source() {
+        let picks: Vec<Pick> = ["https://a.example/ok", "https://b.example/slow"]
+            .iter()
+            .enumerate()
+            .map(|(i, url)| Pick {
+                rank: i + 1,
+                result: result(url),
+                score: 0.5,
+            })
+            .collect();
+        let content = "Tokio is an asynchronous runtime for the Rust programming language. \
+It provides the building blocks needed for writing network applications.";
+        let batch = ScrapeBatchResponse {
+            total: 2,
+            successful: 1,
+            failed: 1,
+            total_duration_ms: 10,
+            results: vec![
+                ScrapeBatchResult {
+                    url: "https://b.example/slow".to_string(),
+                    success: false,
+                    data: None,
+                    error: Some("budget exhausted".to_string()),
+                    failure_reason: Some("budget_exceeded".to_string()),
+                    duplicate_of: None,
+                    duration_ms: 5,
+                    duplicates_of: Vec::new(),
+                },
+                ScrapeBatchResult {
+                    url: "https://a.example/ok".to_string(),
+                    success: true,
+                    data: Some(page("https://a.example/ok", content)),
+                    error: None,
+                    failure_reason: None,
+                    duplicate_of: None,
+                    duration_ms: 5,
+                    duplicates_of: Vec::new(),
+                },
+            ],
+            dropped_low_quality: Vec::new(),
+            duplicates: Vec::new(),
+        };
+        let options = ResearchOptions {
+            schema: Some(vec![ExtractField {
+                name: "title".to_string(),
+                description: "Page title".to_string(),
+                field_type: None,
+                required: None,
+            }]),
+            ..Default::default()
+        };
+
+        let (sources, failed) = collect("tokio async runtime", &picks, batch, &options);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].rank, 1);
+        assert!(sources[0].summary.contains("asynchronous runtime"));
+        let extracted = sources[0].extracted.as_ref().unwrap();
+        assert!(extracted.get("title").is_some());
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].rank, 2);
+        assert_eq!(failed[0].reason, "budget_exceeded");
+    }
+}
@@ -1,13 +1,21 @@
+use crate::core::error::ScoutError;
+use crate::core::retry::RetryPolicy;
 use crate::nlp::semantic_shave;
 use crate::rust_scraper::QualityMode;
-use crate::rust_scraper::RustScraper;
+use crate::rust_scraper::{Revalidation, RustScraper};
+use crate::scraping::assets::{download_assets, AssetOptions};
+use crate::scraping::fragment_route;
+use crate::scraping::pagination;
+use crate::scraping::provenance;
+use crate::scraping::request_auth::RequestAuth;
+use crate::scraping::request_headers::RequestHeaders;
+use crate::scraping::sections::SectionOptions;
 use crate::types::*;
 use crate::AppState;
 use anyhow::{anyhow, Result};
-use backoff::future::retry;
-use backoff::ExponentialBackoffBuilder;
 use select::predicate::Predicate;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet};
 use std::future::Future;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
@@ -27,6 +35,76 @@ pub struct ScrapeUrlOptions {
     pub extract_relevant_sections: bool,
     pub section_limit: Option<usize>,
     pub section_threshold: Option<f32>,
+
+    // Check the origin's robots.txt before any request and refuse disallowed paths.
+    pub respect_robots: bool,
+
+    // Per-domain requests/second for this call; `None` uses the server-wide limiter rate.
+    pub domain_rate_limit: Option<f64>,
+
+    // Fetch the page's advertised RSS/Atom feed into `feed_items`.
+    pub parse_feeds: bool,
+
+    // Total attempts for transient HTTP failures; `None` uses `CORTEX_SCOUT_RETRY_MAX_ATTEMPTS`.
+    pub max_attempts: Option<u32>,
+
+    // Retry a low-quality static result in the browser; `None` uses `CORTEX_SCOUT_AUTO_ESCALATE`.
+    pub auto_escalate: Option<bool>,
+
+    // Download the page's images and attach a manifest (`None` = no downloads).
+    pub download_assets: Option<AssetOptions>,
+
+    // Skip the scrape cache lookup and always fetch; the fresh result is still cached.
+    pub bypass_cache: bool,
+
+    // Treat a cached result as stale: it is served again only when a conditional re-fetch
+    // answers 304, so unchanged pages cost a validator round-trip instead of a full fetch.
+    pub revalidate: bool,
+
+    // Send and store cookies through the site's shared jar (`AppState::cookie_jars`).
+    pub persist_session: bool,
+
+    // Drop the site's jar before fetching.
+    pub clear_session: bool,
+
+    // HTTP Basic/Bearer credentials sent as an `Authorization` header (never logged).
+    pub auth: Option<RequestAuth>,
+
+    // Caller headers and User-Agent that replace the stealth defaults (secret values never logged).
+    pub headers: Option<RequestHeaders>,
+
+    // Static fetch returning only title, canonical URL, headings, links and images.
+    pub links_only: bool,
+
+    // Give each link a `LinkDetail` (enclosing block, page-chrome flag, sentence, `rel`).
+    pub links_detail: bool,
+
+    // Links kept in `links`, in page order (`None` = all).
+    pub max_links: Option<usize>,
+
+    // Render in the browser and save a PNG of the settled page (see `scraping::screenshots`).
+    pub screenshot: bool,
+    pub screenshot_full_page: bool,
+
+    // Also return the PNG as base64 when it fits `CORTEX_SCOUT_SCREENSHOT_INLINE_MAX_BYTES`.
+    pub screenshot_inline: bool,
+
+    // Keep the fetched HTML in `content`; dropped by default to bound memory on huge pages.
+    pub include_raw_html: bool,
+
+    // Named CSS selectors evaluated on the fetched HTML into `selector_results`.
+    pub selectors: BTreeMap<String, String>,
+    // Matches kept per selector; `None` uses `DEFAULT_MAX_SELECTOR_MATCHES`.
+    pub max_selector_matches: Option<usize>,
+
+    // Follow the page's `hreflang` alternate in this language when the page is in another one.
+    pub prefer_language: Option<String>,
+
+    // Split `clean_content` into heading sections (`None` = no sections).
+    pub sections: Option<SectionOptions>,
+
+    // Follow "next page" links and merge up to this many pages (`None` = the page alone).
+    pub follow_pagination: Option<usize>,
 }
 
 pub async fn scrape_url(state: &Arc<AppState>, url: &str) -> Result<ScrapeResponse> {
@@ -59,9 +137,490 @@ pub async fn scrape_url_with_options(
 /// - `extract_app_state`: when `true`, force-return the raw SPA JSON (Next.js/Nuxt/Remix
 ///   `__NEXT_DATA__` etc.) even if it is sparse.  Defaults to `false`, which causes the
 ///   SPA fast-path to fall back to readability when fewer than 100 readable words are found.
+/// - `respect_robots`: when `true`, a path disallowed by robots.txt fails with
+///   [`RobotsError`](crate::scraping::robots::RobotsError) before anything is fetched; a `Crawl-delay` is surfaced as a
+///   `robots_crawl_delay:<secs>s` warning (not enforced).
+/// - `domain_rate_limit`: requests/second for this URL's domain, overriding
+///   `CORTEX_SCOUT_DOMAIN_RATE_LIMIT`; waits over 2s add a `domain_rate_limited:<ms>ms` warning.
+/// - `parse_feeds`: when `true`, the first RSS/Atom feed advertised by the page is fetched
+///   and parsed into `feed_items` (URLs that are feeds themselves are always parsed).
+/// - `max_attempts`: total native fetch attempts for connection errors, timeouts and
+///   retryable statuses (see [`RetryPolicy`]); the count used is reported in `fetch_attempts`.
+/// - `download_assets`: after the scrape (cached or not), download up to `max_assets` images
+///   into the per-domain asset directory and set `asset_manifest` (see [`download_assets`]).
+/// - `auto_escalate`: when an HTML static result is sparse or scores low (see
+///   [`should_auto_escalate`]), re-render it through native CDP within twice the static
+///   stage timeout; an adopted render carries an `escalated_to_browser` warning.
+/// - `bypass_cache`: when `true`, always fetch instead of serving a cached result.
+/// - `revalidate`: when `true`, a cached result is checked with the origin before it is
+///   served: a conditional fetch when the entry has validators, a full fetch otherwise.
+/// - `persist_session`: native fetches send and store cookies through the site's shared
+///   jar, so consent/session cookies carry over to later scrapes of the same site.
+///   Results are cached separately from cookie-less scrapes.
+/// - `clear_session`: drop the site's cookies (memory and disk) before fetching. The page
+///   is fetched fresh, since a cached copy was built with the cookies just dropped.
+/// - `auth`: Basic or Bearer credentials for the native fetch and the CDP navigation.
+///   They are keyed into the cache by fingerprint only, and URL rewrites that would
+///   move the request to another host (e.g. GitHub raw) are skipped so the credentials
+///   stay with the site they were given for.
+/// - `headers`: header values and a User-Agent applied after the stealth defaults, on
+///   native fetches and browser renders. Cached by fingerprint and kept on the requested
+///   host like `auth`; secret-looking values only follow same-origin redirects.
+/// - `links_only`: a single static fetch that keeps title, canonical URL, headings, links
+///   and images and skips content extraction, browser rendering and escalation. The
+///   result has empty `clean_content` and a `links_only` warning; it is cached separately.
+/// - `links_detail`: each link in `links` gets a `detail` with its nearest enclosing block
+///   element (`p`, `li`, `td`, `figcaption`, ... or a generic container), whether it sits
+///   in page chrome that content extraction drops, up to 140 characters of the sentence
+///   around it, and its `sponsored`/`ugc` rel flags. Cached separately from plain scrapes.
+/// - `max_links`: keep the first this many `links`; a `links_truncated:<total>` warning
+///   marks a cut. Applied after the cache.
+/// - `screenshot`: render through the browser even when a static fetch would do, and save
+///   a PNG (viewport, or the whole page with `screenshot_full_page`) taken after the page
+///   settles and the noise filter runs. `screenshot` on the result carries its path, plus
+///   base64 with `screenshot_inline` when under the inline cap. Requires a local browser
+///   and cannot be combined with `links_only`; a scrape that ends up without a capture
+///   (browser render failed, static fallback used) gets a `screenshot_unavailable` warning.
+///   Screenshot scrapes never read the cache.
+/// - `include_raw_html`: keep the fetched HTML in `content`. Without it `content` is empty
+///   and the result carries a `raw_html_omitted` warning; raw-HTML scrapes are cached
+///   separately. Bodies over `SCRAPE_MAX_RESPONSE_BYTES` fail either way.
+/// - `selectors`: named CSS selectors run against the final HTML (static or rendered, cached
+///   or not) into `selector_results`: text plus resolved `href`/`src` for links and images,
+///   at most `max_selector_matches` per name (a `selector_matches_truncated:<name>` warning
+///   marks a cap). An unparsable selector yields an `error` entry for that name only. Cannot
+///   be combined with `links_only`, which keeps no HTML.
+/// - `prefer_language`: when the page's language differs and it advertises an `hreflang`
+///   alternate in the preferred language (exact tag first, then the same primary language),
+///   scrape that alternate instead, one hop at most. The result carries a
+///   `followed_language_alternate:<lang> (from <url>)` warning; if the alternate cannot be
+///   scraped the original page is kept with a `language_alternate_failed:<lang>` warning.
+/// - `sections`: split the returned `clean_content` into `sections` (see
+///   [`sections::split`](crate::scraping::sections::split)): one per heading with its
+///   heading path, or paragraph windows when the page has no headings. Applied after the
+///   cache, so sectioned and plain scrapes share entries.
+/// - `follow_pagination`: up to this many pages (the first included, at most
+///   [`pagination::MAX_PAGES_LIMIT`]) of a series split across "next" links are fetched
+///   one after another with the same options and merged in order (see
+///   [`pagination::merge`]). The series stops at the first page without a next link, at a
+///   link back to a page already merged, at a page repeating an earlier one, or at a
+///   failed fetch (`pagination_page_failed:<url>` warning). Needs the page HTML, so it
+///   cannot be combined with `links_only`.
+///
+/// A URL whose fragment is a client-side route (`#/docs`, `#!/inbox`, see
+/// [`fragment_route`]) is rendered in the browser, which waits for the route's text to
+/// settle; if it ends up served by a static fetch (no browser, render failed) it gets a
+/// `fragment_route_not_rendered` warning. Anchor fragments (`#section-3`) share the cache
+/// entry of the page without them.
+///
+/// A host refused by the [`domain_policy`](crate::scraping::domain_policy) fails with a
+/// `blocked` error before the cache or robots.txt is consulted.
+#[tracing::instrument(skip_all, fields(url = %url))]
 pub async fn scrape_url_full(
     state: &Arc<AppState>,
     url: &str,
+    mut options: ScrapeUrlOptions,
+) -> Result<ScrapeResponse> {
+    crate::scraping::domain_policy::check(url).await?;
+    let asset_options = options.download_assets.take();
+    let section_options = options.sections.take();
+    let max_links = options.max_links.take();
+    let (screenshot, screenshot_inline) = (options.screenshot, options.screenshot_inline);
+    let selectors = std::mem::take(&mut options.selectors);
+    let include_raw_html = options.include_raw_html;
+    options.bypass_cache |= options.clear_session;
+    let max_pages = options
+        .follow_pagination
+        .take()
+        .map(|n| n.clamp(1, pagination::MAX_PAGES_LIMIT));
+    // Selectors and next-page detection run on the HTML, so it is kept until they are done.
+    options.include_raw_html |= !selectors.is_empty() || max_pages.is_some();
+    let prefer_language = options.prefer_language.take();
+    let alternate_options = prefer_language.as_ref().map(|_| options.clone());
+    let pagination_options = max_pages.map(|_| options.clone());
+    let max_selector_matches = options
+        .max_selector_matches
+        .unwrap_or(crate::scraping::selectors::DEFAULT_MAX_SELECTOR_MATCHES);
+    if !selectors.is_empty() && options.links_only {
+        return Err(ScoutError::invalid_input(
+            "selectors need the page HTML and cannot be combined with links_only",
+        )
+        .into());
+    }
+    if max_pages.is_some() && options.links_only {
+        return Err(ScoutError::invalid_input(
+            "follow_pagination needs the page HTML and cannot be combined with links_only",
+        )
+        .into());
+    }
+    if options.clear_session {
+        state.cookie_jars.clear(url);
+    }
+    let persist_session = options.persist_session;
+    let crawl_delay = if options.respect_robots {
+        // Check the host we will actually hit (e.g. raw.githubusercontent.com after rewrite).
+        let target = github_raw_candidates(url)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| url.to_string());
+        state
+            .robots_policy
+            .check(&state.http_client, &target)
+            .await?
+    } else {
+        None
+    };
+
+    let started = Instant::now();
+    let outcome = scrape_url_github_aware(state, url, options).await;
+    state
+        .metrics
+        .record_tool("scrape_url", outcome.is_ok(), started.elapsed());
+    let mut result = outcome?;
+    if let (Some(prefer), Some(alternate_options)) = (prefer_language.as_deref(), alternate_options)
+    {
+        result = follow_language_alternate(state, result, prefer, alternate_options).await;
+    }
+    if let (Some(max_pages), Some(pagination_options)) = (max_pages, pagination_options) {
+        result = follow_pagination(state, result, max_pages, pagination_options).await;
+    }
+    if persist_session {
+        if let Err(e) = state.cookie_jars.save(url) {
+            warn!("Failed to save cookie jar for {}: {}", url, e);
+        }
+    }
+    if let Some(delay) = crawl_delay {
+        crate::content_quality::push_warning_unique(
+            &mut result.warnings,
+            &format!("robots_crawl_delay:{}s", delay),
+        );
+    }
+    if screenshot {
+        attach_screenshot_outcome(&mut result, screenshot_inline).await;
+    }
+    if fragment_route::route(url).is_some() && served_statically(&result) {
+        crate::content_quality::push_warning_unique(
+            &mut result.warnings,
+            "fragment_route_not_rendered",
+        );
+    }
+    if !selectors.is_empty() {
+        let base_url = result
+            .final_url
+            .as_deref()
+            .unwrap_or(&result.url)
+            .to_string();
+        let (matches, truncated) = crate::scraping::selectors::evaluate(
+            &result.content,
+            &base_url,
+            &selectors,
+            max_selector_matches,
+        );
+        for name in truncated {
+            crate::content_quality::push_warning_unique(
+                &mut result.warnings,
+                &format!("selector_matches_truncated:{}", name),
+            );
+        }
+        result.selector_results = Some(matches);
+    }
+    if let Some(max_links) = max_links {
+        let total = result.links.len();
+        if total > max_links {
+            result.links.truncate(max_links);
+            crate::content_quality::push_warning_unique(
+                &mut result.warnings,
+                &format!("links_truncated:{}", total),
+            );
+        }
+    }
+    if let Some(section_options) = section_options {
+        result.sections = crate::scraping::sections::split(
+            &result.clean_content,
+            &result.code_blocks,
+            Some(&result.language),
+            section_options,
+        );
+    }
+    if !include_raw_html {
+        result.content = String::new();
+        crate::content_quality::push_warning_unique(&mut result.warnings, "raw_html_omitted");
+    }
+    if let Some(asset_options) = asset_options {
+        match download_assets(state, &result, &asset_options).await {
+            Ok((manifest_path, manifest)) => {
+                result.asset_manifest = Some(manifest_path.display().to_string());
+                if !manifest.skipped.is_empty() {
+                    crate::content_quality::push_warning_unique(
+                        &mut result.warnings,
+                        &format!("assets_skipped:{}", manifest.skipped.len()),
+                    );
+                }
+            }
+            Err(e) => {
+                warn!("Asset download failed for {}: {}", url, e);
+                crate::content_quality::push_warning_unique(
+                    &mut result.warnings,
+                    &format!("asset_download_failed: {}", e),
+                );
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Scrape the `hreflang` alternate [`preferred_alternate`] picks for `prefer`, in place of
+/// `result`. The alternate is fetched with the same options but its own alternates are not
+/// followed; on any failure `result` is kept with a `language_alternate_failed` warning.
+async fn follow_language_alternate(
+    state: &Arc<AppState>,
+    mut result: ScrapeResponse,
+    prefer: &str,
+    options: ScrapeUrlOptions,
+) -> ScrapeResponse {
+    let Some(alternate) = preferred_alternate(&result, prefer).cloned() else {
+        return result;
+    };
+    info!(
+        "Following {} alternate of {} ({} page): {}",
+        alternate.lang, result.url, result.language, alternate.url
+    );
+    let followed = async {
+        crate::scraping::domain_policy::check(&alternate.url).await?;
+        if options.respect_robots {
+            state
+                .robots_policy
+                .check(&state.http_client, &alternate.url)
+                .await?;
+        }
+        scrape_url_github_aware(state, &alternate.url, options).await
+    }
+    .await;
+    match followed {
+        Ok(mut followed) => {
+            crate::content_quality::push_warning_unique(
+                &mut followed.warnings,
+                &format!(
+                    "followed_language_alternate:{} (from {})",
+                    alternate.lang, result.url
+                ),
+            );
+            followed
+        }
+        Err(e) => {
+            warn!("Language alternate {} failed: {}", alternate.url, e);
+            crate::content_quality::push_warning_unique(
+                &mut result.warnings,
+                &format!("language_alternate_failed:{}", alternate.lang),
+            );
+            result
+        }
+    }
+}
+
+/// Fetch the pages after `result` by their next links, one at a time, and merge them into
+/// it; `max_pages` counts `result` itself.
+async fn follow_pagination(
+    state: &Arc<AppState>,
+    mut result: ScrapeResponse,
+    max_pages: usize,
+    options: ScrapeUrlOptions,
+) -> ScrapeResponse {
+    let mut seen: HashSet<String> = [Some(&result.url), result.final_url.as_ref()]
+        .into_iter()
+        .flatten()
+        .map(|url| pagination::page_key(url))
+        .collect();
+    let mut contents = vec![result.clean_content.clone()];
+    let mut html = result.content.clone();
+    let mut current = result
+        .final_url
+        .clone()
+        .unwrap_or_else(|| result.url.clone());
+    let mut pages = Vec::new();
+    let mut cap_reached = false;
+    while let Some(next) = pagination::next_page_url(&html, &current) {
+        // A next link back to a merged page (page 1, or a cycle) ends the series.
+        if !seen.insert(pagination::page_key(&next)) {
+            break;
+        }
+        if pages.len() + 1 >= max_pages {
+            cap_reached = true;
+            break;
+        }
+        info!(
+            "Following page {} of {}: {}",
+            pages.len() + 2,
+            result.url,
+            next
+        );
+        let fetched = async {
+            crate::scraping::domain_policy::check(&next).await?;
+            if options.respect_robots {
+                state.robots_policy.check(&state.http_client, &next).await?;
+            }
+            scrape_url_github_aware(state, &next, options.clone()).await
+        }
+        .await;
+        let page = match fetched {
+            Ok(page) => page,
+            Err(e) => {
+                warn!("Next page {} failed: {}", next, e);
+                crate::content_quality::push_warning_unique(
+                    &mut result.warnings,
+                    &format!("pagination_page_failed:{}", next),
+                );
+                break;
+            }
+        };
+        // Out-of-range pages often redirect to, or re-serve, an earlier page.
+        let landed = page.final_url.as_deref().unwrap_or(&page.url);
+        if (landed != next && !seen.insert(pagination::page_key(landed)))
+            || contents.contains(&page.clean_content)
+        {
+            break;
+        }
+        contents.push(page.clean_content.clone());
+        html = page.content.clone();
+        current = landed.to_string();
+        pages.push(page);
+    }
+    pagination::merge(&mut result, pages, cap_reached);
+    result
+}
+
+/// The alternate to follow for a `prefer` language tag, if the page is not already in it:
+/// an exact (case-insensitive) `hreflang` match, or else one with the same primary
+/// language when the page's primary language differs. `x-default` and alternates that
+/// point back at the page itself are never picked.
+fn preferred_alternate<'a>(
+    result: &'a ScrapeResponse,
+    prefer: &str,
+) -> Option<&'a AlternateLanguage> {
+    fn normalize(tag: &str) -> String {
+        tag.trim().replace('_', "-").to_ascii_lowercase()
+    }
+    fn primary(tag: &str) -> &str {
+        tag.split('-').next().unwrap_or(tag)
+    }
+
+    let prefer = normalize(prefer);
+    let page_language = normalize(&result.language);
+    if prefer.is_empty() || page_language == prefer {
+        return None;
+    }
+    let is_self = |alternate: &&AlternateLanguage| {
+        alternate.url == result.url
+            || result.final_url.as_deref() == Some(alternate.url.as_str())
+            || result.canonical_url.as_deref() == Some(alternate.url.as_str())
+    };
+    let candidates = || {
+        result
+            .alternate_languages
+            .iter()
+            .filter(|alternate| !alternate.lang.eq_ignore_ascii_case("x-default"))
+    };
+
+    if let Some(exact) = candidates().find(|alternate| normalize(&alternate.lang) == prefer) {
+        return (!is_self(&exact)).then_some(exact);
+    }
+    if primary(&page_language) == primary(&prefer) {
+        return None;
+    }
+    candidates()
+        .filter(|alternate| primary(&normalize(&alternate.lang)) == primary(&prefer))
+        .find(|alternate| !is_self(alternate))
+}
+
+/// Whether the content came from a plain HTTP fetch rather than a browser render.
+fn served_statically(result: &ScrapeResponse) -> bool {
+    result
+        .provenance
+        .iter()
+        .any(|attempt| attempt.selected && attempt.method == ScrapeMethod::Static)
+}
+
+/// Warn when a requested screenshot is missing, and inline it when asked and small enough.
+async fn attach_screenshot_outcome(result: &mut ScrapeResponse, inline: bool) {
+    let Some(shot) = result.screenshot.as_mut() else {
+        crate::content_quality::push_warning_unique(&mut result.warnings, "screenshot_unavailable");
+        return;
+    };
+    if !inline {
+        return;
+    }
+    let max_bytes = crate::scraping::screenshots::inline_max_bytes();
+    match crate::scraping::screenshots::inline(shot, max_bytes).await {
+        Ok(true) => {}
+        Ok(false) => crate::content_quality::push_warning_unique(
+            &mut result.warnings,
+            &format!("screenshot_too_large_to_inline:{}B", shot.bytes),
+        ),
+        Err(e) => {
+            warn!("Failed to inline screenshot {}: {}", shot.path, e);
+            crate::content_quality::push_warning_unique(
+                &mut result.warnings,
+                "screenshot_inline_failed",
+            );
+        }
+    }
+}
+
+/// `blocked` error for a scrape whose final result is still a block page, carrying the
+/// detected reason, URL and upstream status in `details`.
+pub fn block_error(result: &ScrapeResponse) -> Option<ScoutError> {
+    let reason = result.block_reason.as_deref()?;
+    Some(
+        ScoutError::blocked(format!("{} blocked the request: {}", result.url, reason))
+            .with_details(serde_json::json!({
+                "reason": reason,
+                "url": result.url,
+                "status_code": result.status_code,
+            })),
+    )
+}
+
+/// GitHub repo roots and blob pages are scraped from their raw text first (see
+/// [`github_raw_candidates`]); when every candidate 404s or fails, the original URL goes
+/// through the generic path. Scrapes with credentials or custom headers stay on the
+/// requested host, and `links_only`/`screenshot` scrapes want the page itself, so those
+/// skip the raw fetch.
+async fn scrape_url_github_aware(
+    state: &Arc<AppState>,
+    url: &str,
+    options: ScrapeUrlOptions,
+) -> Result<ScrapeResponse> {
+    let candidates = if options.auth.is_none()
+        && options.headers.is_none()
+        && !options.links_only
+        && !options.screenshot
+    {
+        github_raw_candidates(url)
+    } else {
+        Vec::new()
+    };
+    for raw_url in &candidates {
+        match scrape_url_full_inner(state, url, Some(raw_url), options.clone()).await {
+            Ok(result) if result.status_code != 404 => return Ok(result),
+            Ok(_) => info!("GitHub raw candidate {} not found", raw_url),
+            Err(e) => {
+                warn!("GitHub raw candidate {} failed: {}", raw_url, e);
+                break;
+            }
+        }
+    }
+    if !candidates.is_empty() {
+        info!("Falling back to the generic scrape path for {}", url);
+    }
+    scrape_url_full_inner(state, url, None, options).await
+}
+
+/// Scrape `url`, fetching `raw_target` in its place when set. Raw GitHub targets are
+/// plain text, so they skip preflight and browser rendering.
+async fn scrape_url_full_inner(
+    state: &Arc<AppState>,
+    url: &str,
+    raw_target: Option<&str>,
     options: ScrapeUrlOptions,
 ) -> Result<ScrapeResponse> {
     let total_start = Instant::now();
@@ -76,29 +635,68 @@ pub async fn scrape_url_full(
         extract_relevant_sections,
         section_limit,
         section_threshold,
+        respect_robots,
+        domain_rate_limit,
+        parse_feeds,
+        max_attempts,
+        auto_escalate,
+        download_assets: _,
+        bypass_cache,
+        revalidate,
+        persist_session,
+        clear_session: _,
+        auth,
+        headers,
+        links_only,
+        links_detail,
+        max_links: _,
+        screenshot,
+        screenshot_full_page,
+        screenshot_inline: _,
+        include_raw_html,
+        selectors: _,
+        max_selector_matches: _,
+        prefer_language: _,
+        sections: _,
     } = options;
     let query = query.as_deref();
+    let retry_policy = match max_attempts {
+        Some(n) => RetryPolicy::from_env().with_max_attempts(n),
+        None => RetryPolicy::from_env(),
+    };
 
     let requested_url = url;
     info!("Scraping URL: {}", requested_url);
+    if let Some(headers) = &headers {
+        info!("Custom request headers: {:?}", headers);
+    }
 
     // Validate URL
     if !requested_url.starts_with("http://") && !requested_url.starts_with("https://") {
-        return Err(anyhow!("Invalid URL: must start with http:// or https://"));
+        return Err(
+            ScoutError::invalid_input("Invalid URL: must start with http:// or https://").into(),
+        );
+    }
+    if screenshot && links_only {
+        return Err(ScoutError::invalid_input(
+            "screenshot needs a browser render and cannot be combined with links_only",
+        )
+        .into());
+    }
+    if screenshot && !crate::scraping::browser_manager::native_browser_available() {
+        return Err(ScoutError::unavailable(
+            "screenshot needs a local browser; install Brave, Chrome, or Chromium",
+        )
+        .into());
     }
 
-    // 🧬 Smart URL rewrite: transform well-known URL patterns into their cleanest form.
-    // GitHub /blob/ pages → raw.githubusercontent.com returns plain text directly.
-    let rewrite_start = Instant::now();
-    let url_rewritten = rewrite_url_for_clean_content(requested_url);
+    // 🧬 Smart URL rewrite: GitHub pages are fetched as raw text from raw.githubusercontent.com.
     metrics.push_phase(
         "url_rewrite",
-        rewrite_start.elapsed(),
-        url_rewritten
-            .as_ref()
-            .map(|rewritten| format!("rewritten to {}", rewritten)),
+        Duration::ZERO,
+        raw_target.map(|rewritten| format!("rewritten to {}", rewritten)),
     );
-    let url: &str = url_rewritten.as_deref().unwrap_or(requested_url);
+    let url: &str = raw_target.unwrap_or(requested_url);
 
     // ─── Step 0: Smart Auth Cache pre-flight ────────────────────────────────
     // Check whether the auth registry already knows this domain requires auth
@@ -120,39 +718,70 @@ pub async fn scrape_url_full(
 
     // Cache key must include knobs that affect output; otherwise comparisons (and correctness)
     // are broken because a previous scrape can be returned for a different mode.
-    let cache_key = compute_scrape_cache_key(
-        url,
-        ScrapeCacheKeyKnobs {
-            quality_mode,
-            query,
-            strict_relevance,
-            relevance_threshold,
-            extract_app_state,
-            extract_relevant_sections,
-            section_limit,
-            section_threshold,
-        },
-    );
+    let cache_knobs = ScrapeCacheKeyKnobs {
+        quality_mode,
+        query,
+        strict_relevance,
+        relevance_threshold,
+        extract_app_state,
+        extract_relevant_sections,
+        section_limit,
+        section_threshold,
+        parse_feeds,
+        persist_session,
+        auth: auth.as_ref(),
+        headers: headers.as_ref(),
+        links_only,
+        links_detail,
+        screenshot,
+        raw_html: include_raw_html,
+        auto_escalate: auto_escalate.unwrap_or_else(crate::core::config::auto_escalate_enabled),
+    };
+    let cache_key = compute_scrape_cache_key(url, cache_knobs);
 
     // BOSS LEVEL OPTIMIZATION: Check if in rapid testing mode
-    let is_testing = if let Some(memory) = state.get_memory() {
-        memory.is_rapid_testing(url).await.unwrap_or(false)
-    } else {
-        false
-    };
+    let is_testing = state.get_memory().is_some()
+        && state
+            .memory_guard
+            .is_rapid_testing(url)
+            .await
+            .unwrap_or(false);
 
     if is_testing {
         info!("🧪 Rapid testing detected for {}, bypassing cache", url);
     }
 
-    // Check cache (bypass if in testing mode)
+    // Check cache (bypass if in testing mode or asked to). A screenshot is of the page as
+    // it is now, so those scrapes always fetch.
+    // Past its TTL an entry is only served again after a conditional fetch answers 304.
     let cache_lookup_start = Instant::now();
-    if !is_testing {
-        if let Some(cached) = state.scrape_cache.get(&cache_key).await {
-            metrics.push_phase("cache_lookup", cache_lookup_start.elapsed(), Some("cache hit".to_string()));
-            if cached.word_count == 0 || cached.clean_content.trim().is_empty() {
+    let mut revalidate_from: Option<ScrapeResponse> = None;
+    if !is_testing && !bypass_cache && !screenshot {
+        let cached = state.scrape_cache.get(&cache_key).await;
+        let usable = cached.as_ref().is_some_and(|c| {
+            links_only || (c.word_count > 0 && !c.clean_content.trim().is_empty())
+        });
+        let fresh = !revalidate
+            && cached.as_ref().is_some_and(|c| {
+                is_fresh(
+                    c,
+                    Duration::from_secs(crate::core::config::scrape_cache_ttl_secs()),
+                    chrono::Utc::now(),
+                )
+            });
+        state.metrics.record_cache("scrape", usable && fresh);
+        if let Some(cached) = cached {
+            let detail = if fresh { "cache hit" } else { "cache stale" };
+            metrics.push_phase("cache_lookup", cache_lookup_start.elapsed(), Some(detail.to_string()));
+            if !usable {
                 // Invalidate poor/empty cache entries and recompute
                 state.scrape_cache.invalidate(&cache_key).await;
+            } else if !fresh {
+                if can_revalidate(&cached) {
+                    revalidate_from = Some(cached);
+                } else {
+                    state.scrape_cache.invalidate(&cache_key).await;
+                }
             } else {
                 let mut cached = cached;
                 metrics.cache_hit = true;
@@ -161,11 +790,19 @@ pub async fn scrape_url_full(
                 return Ok(cached);
             }
         }
-    } else {
+    } else if is_testing {
         // In testing mode, always invalidate cache
         state.scrape_cache.invalidate(&cache_key).await;
     }
     metrics.push_phase("cache_lookup", cache_lookup_start.elapsed(), Some("cache miss".to_string()));
+    // Raw GitHub files and conditional revalidations are plain static fetches.
+    let native_only = raw_target.is_some() || revalidate_from.is_some();
+
+    // Per-domain pacing happens before taking a global permit so a busy domain
+    // does not starve scrapes of other hosts.
+    let rate_wait = state.domain_limiter.acquire(url, domain_rate_limit).await;
+    metrics.push_phase("domain_rate_wait", rate_wait, None);
+    metrics.rate_limit_wait = rate_wait;
 
     // Concurrency control
     let outbound_wait_start = Instant::now();
@@ -176,9 +813,65 @@ pub async fn scrape_url_full(
         .expect("semaphore closed");
     metrics.push_phase("outbound_wait", outbound_wait_start.elapsed(), None);
 
+    let cookie_jar = if persist_session {
+        state.cookie_jars.jar_for(url)
+    } else {
+        None
+    };
+
+    // Link harvesting: one static fetch, no browser paths. Escalation, the legacy fallback
+    // and query shaping all exist to improve clean content, which this mode never produces.
+    if links_only {
+        metrics.strategy = Some("links_only".to_string());
+        let proxy = match (&state.proxy_manager, use_proxy) {
+            (Some(proxy_manager), true) => proxy_manager.switch_to_best_proxy().await.ok(),
+            _ => None,
+        };
+        let rust_scraper = RustScraper::new()
+            .with_links_only(true)
+            .with_parse_feeds(parse_feeds)
+            .with_links_detail(links_detail)
+            .with_cookie_jar(cookie_jar)
+            .with_retry_policy(retry_policy)
+            .with_metrics(state.metrics.clone())
+            .with_auth(auth.as_ref())
+            .map_err(|e| ScoutError::invalid_input(e.to_string()))?
+            .with_headers(headers.as_ref())
+            .with_proxy(proxy.as_deref())
+            .map_err(|e| ScoutError::internal(e.to_string()))?;
+        let fetch_start = Instant::now();
+        let fetched = run_scrape_stage_with_timeout(
+            "native_http_scrape",
+            rust_scraper.scrape_url_revalidating(url, revalidate_from.as_ref()),
+        )
+        .await?;
+        metrics.push_phase("native_http_scrape", fetch_start.elapsed(), None);
+        let mut result = match fetched {
+            Revalidation::Modified(result) => *result,
+            Revalidation::NotModified => {
+                let cached = revalidate_from.expect("304 only answers a conditional request");
+                return Ok(serve_revalidated(
+                    state,
+                    &cache_key,
+                    cache_knobs,
+                    cached,
+                    &mut metrics,
+                    total_start.elapsed(),
+                )
+                .await);
+            }
+        };
+        attach_scrape_metrics(&mut result, &metrics, total_start.elapsed());
+        if result.block_reason.is_none() {
+            cache_scrape_result(state, &cache_key, cache_knobs, &result).await;
+        }
+        return Ok(result);
+    }
+
     // 🚀 UNIVERSAL CDP STRATEGY: Try native CDP
     let cdp_available = crate::scraping::browser_manager::native_browser_available();
-    let cdp_first = should_try_cdp_first(url, use_proxy, quality_mode, extract_app_state);
+    let cdp_first = screenshot
+        || (!native_only && should_try_cdp_first(url, use_proxy, quality_mode, extract_app_state));
     if cdp_first {
         metrics.strategy = Some("cdp_first".to_string());
     } else {
@@ -189,7 +882,18 @@ pub async fn scrape_url_full(
         info!("🚀 CDP available, attempting universal stealth mode");
 
         let rust_scraper = RustScraper::new_with_quality_mode(quality_mode.map(|m| m.as_str()))
-            .with_extract_app_state(extract_app_state);
+            .with_extract_app_state(extract_app_state)
+            .with_parse_feeds(parse_feeds)
+            .with_links_detail(links_detail)
+            .with_screenshot(screenshot, screenshot_full_page)
+            .with_raw_html(include_raw_html)
+            .with_cookie_jar(cookie_jar.clone())
+            .with_retry_policy(retry_policy.clone())
+            .with_metrics(state.metrics.clone())
+            .with_browser_pool(state.browser_pool.clone())
+            .with_auth(auth.as_ref())
+            .map_err(|e| ScoutError::invalid_input(e.to_string()))?
+            .with_headers(headers.as_ref());
         let cdp_proxy = if use_proxy {
             if let Some(proxy_manager) = &state.proxy_manager {
                 match proxy_manager.switch_to_best_proxy().await {
@@ -213,10 +917,11 @@ pub async fn scrape_url_full(
             rust_scraper.fetch_via_cdp(url, cdp_proxy.clone()),
         )
         .await;
-        metrics.push_phase("cdp_initial_attempt", cdp_attempt_start.elapsed(), None);
+        let cdp_fetch_time = cdp_attempt_start.elapsed();
+        metrics.push_phase("cdp_initial_attempt", cdp_fetch_time, None);
 
         match cdp_result {
-            Ok((html, _status_code)) => {
+            Ok((html, status_code)) => {
                 info!("✅ CDP fetch succeeded, processing HTML");
 
                 // Process HTML into ScrapeResponse
@@ -229,6 +934,14 @@ pub async fn scrape_url_full(
                 {
                     Ok(mut result) => {
                         metrics.push_phase("cdp_process_html", cdp_process_start.elapsed(), None);
+                        result.provenance.push(provenance::attempt(
+                            ScrapeMethod::Cdp,
+                            rust_scraper.browser_user_agent_class(),
+                            cdp_proxy.as_deref(),
+                            cdp_fetch_time,
+                            status_code,
+                            None,
+                        ));
                         // Record proxy success if used
                         if let (Some(proxy_url), Some(manager)) =
                             (cdp_proxy.as_ref(), state.proxy_manager.as_ref())
@@ -297,41 +1010,11 @@ pub async fn scrape_url_full(
                         }
 
                         // Log to history
-                        if let Some(memory) = state.get_memory() {
-                            let history_start = Instant::now();
-                            let summary = format!(
-                                "{} words (CDP stealth), {} code blocks",
-                                result.word_count,
-                                result.code_blocks.len()
-                            );
-                            let domain = url::Url::parse(url)
-                                .ok()
-                                .and_then(|u| u.host_str().map(|s| s.to_string()));
-                            let result_json = serde_json::to_value(&result).unwrap_or_default();
-
-                            if let Err(e) = run_scrape_stage_with_timeout(
-                                "history_log",
-                                memory.log_scrape(
-                                    url.to_string(),
-                                    Some(result.title.clone()),
-                                    summary,
-                                    domain,
-                                    &result_json,
-                                ),
-                            )
-                            .await
-                            {
-                                warn!("Failed to log CDP scrape to history: {}", e);
-                            }
-                            metrics.push_phase("history_log", history_start.elapsed(), None);
-                        }
+                        log_scrape_to_history(state, url, &result, Some("CDP stealth"));
 
                         // Cache and return
                         attach_scrape_metrics(&mut result, &metrics, total_start.elapsed());
-                        state
-                            .scrape_cache
-                            .insert(cache_key.clone(), result.clone())
-                            .await;
+                        cache_scrape_result(state, &cache_key, cache_knobs, &result).await;
                         auth_cache_post_scrape(url, &result, cached_session_active);
                         return Ok(result);
                     }
@@ -341,6 +1024,13 @@ pub async fn scrape_url_full(
                             cdp_process_start.elapsed(),
                             Some(format!("failed: {}", e)),
                         );
+                        metrics.provenance.fail(provenance::failed(
+                            ScrapeMethod::Cdp,
+                            rust_scraper.browser_user_agent_class(),
+                            cdp_proxy.as_deref(),
+                            cdp_fetch_time,
+                            &e,
+                        ));
                         warn!(
                             "❌ CDP HTML processing failed: {}, falling back to standard path",
                             e
@@ -349,6 +1039,13 @@ pub async fn scrape_url_full(
                 }
             }
             Err(e) => {
+                metrics.provenance.fail(provenance::failed(
+                    ScrapeMethod::Cdp,
+                    rust_scraper.browser_user_agent_class(),
+                    cdp_proxy.as_deref(),
+                    cdp_fetch_time,
+                    &e,
+                ));
                 if use_proxy {
                     warn!(
                         "❌ CDP fetch failed: {}, attempting proxy rotation and retry",
@@ -382,16 +1079,34 @@ pub async fn scrape_url_full(
                             )
                             .await
                             {
-                                Ok((html, _)) => {
-                                    metrics.push_phase("cdp_retry_attempt", cdp_retry_start.elapsed(), None);
+                                Ok((html, retry_status)) => {
+                                    let cdp_retry_time = cdp_retry_start.elapsed();
+                                    metrics.push_phase("cdp_retry_attempt", cdp_retry_time, None);
                                     let cdp_retry_process_start = Instant::now();
-                                    if let Ok(mut result) = run_scrape_stage_with_timeout(
+                                    let processed = run_scrape_stage_with_timeout(
                                         "cdp_retry_process_html",
                                         rust_scraper.process_html(&html, url),
                                     )
-                                    .await
-                                    {
+                                    .await;
+                                    if let Err(e) = &processed {
+                                        metrics.provenance.fail(provenance::failed(
+                                            ScrapeMethod::Cdp,
+                                            rust_scraper.browser_user_agent_class(),
+                                            Some(&new_proxy_url),
+                                            cdp_retry_time,
+                                            e,
+                                        ));
+                                    }
+                                    if let Ok(mut result) = processed {
                                         metrics.push_phase("cdp_retry_process_html", cdp_retry_process_start.elapsed(), None);
+                                        result.provenance.push(provenance::attempt(
+                                            ScrapeMethod::Cdp,
+                                            rust_scraper.browser_user_agent_class(),
+                                            Some(&new_proxy_url),
+                                            cdp_retry_time,
+                                            retry_status,
+                                            None,
+                                        ));
                                         let _ = proxy_manager
                                             .record_proxy_result(&new_proxy_url, true, None)
                                             .await;
@@ -435,10 +1150,7 @@ pub async fn scrape_url_full(
 
                                         // Note: retry path uses the same cache key.
                                         attach_scrape_metrics(&mut result, &metrics, total_start.elapsed());
-                                        state
-                                            .scrape_cache
-                                            .insert(cache_key.clone(), result.clone())
-                                            .await;
+                                        cache_scrape_result(state, &cache_key, cache_knobs, &result).await;
                                         auth_cache_post_scrape(url, &result, cached_session_active);
                                         return Ok(result);
                                     }
@@ -449,6 +1161,13 @@ pub async fn scrape_url_full(
                                         cdp_retry_start.elapsed(),
                                         Some(format!("failed: {}", e2)),
                                     );
+                                    metrics.provenance.fail(provenance::failed(
+                                        ScrapeMethod::Cdp,
+                                        rust_scraper.browser_user_agent_class(),
+                                        Some(&new_proxy_url),
+                                        cdp_retry_start.elapsed(),
+                                        &e2,
+                                    ));
                                     warn!("❌ CDP retry also failed: {}, falling back to standard path", e2);
                                     let _ = proxy_manager
                                         .record_proxy_result(&new_proxy_url, false, None)
@@ -468,9 +1187,6 @@ pub async fn scrape_url_full(
         }
     }
 
-    let rust_scraper = RustScraper::new_with_quality_mode(quality_mode.map(|m| m.as_str()))
-        .with_extract_app_state(extract_app_state);
-    let url_owned = url.to_string();
     let mut force_browserless = false;
     let mut forced_proxy: Option<String> = None;
 
@@ -478,7 +1194,7 @@ pub async fn scrape_url_full(
         if let Some(proxy_manager) = &state.proxy_manager {
             match proxy_manager.switch_to_best_proxy().await {
                 Ok(proxy_url) => {
-                    force_browserless = true;
+                    force_browserless = !native_only;
                     forced_proxy = Some(proxy_url);
                 }
                 Err(e) => {
@@ -490,8 +1206,27 @@ pub async fn scrape_url_full(
         }
     }
 
+    // The native client shares the forced proxy so preflight and the static fallback
+    // leave through the same exit as the browser.
+    let rust_scraper = RustScraper::new_with_quality_mode(quality_mode.map(|m| m.as_str()))
+        .with_extract_app_state(extract_app_state)
+        .with_parse_feeds(parse_feeds)
+        .with_links_detail(links_detail)
+        .with_screenshot(screenshot, screenshot_full_page)
+        .with_raw_html(include_raw_html)
+        .with_cookie_jar(cookie_jar)
+        .with_retry_policy(retry_policy)
+        .with_metrics(state.metrics.clone())
+        .with_browser_pool(state.browser_pool.clone())
+        .with_auth(auth.as_ref())
+        .map_err(|e| ScoutError::invalid_input(e.to_string()))?
+        .with_headers(headers.as_ref())
+        .with_proxy(forced_proxy.as_deref())
+        .map_err(|e| ScoutError::internal(e.to_string()))?;
+    let url_owned = url.to_string();
+
     // Pre-flight checker: quick native probe to detect blocks before full scrape
-    if should_run_preflight(url, use_proxy, quality_mode) {
+    if !native_only && should_run_preflight(url, use_proxy, quality_mode) {
         let preflight_start = Instant::now();
         if let Ok(preflight) = run_scrape_stage_with_timeout(
             "preflight_check",
@@ -518,7 +1253,7 @@ pub async fn scrape_url_full(
 
     if use_proxy && !crate::scraping::browser_manager::native_browser_available() {
         warn!(
-            "use_proxy requested but no browser found; proxy mode requires a browser (install Brave/Chrome/Chromium)"
+            "use_proxy requested but no browser found; using the native scraper through the proxy (install Brave/Chrome/Chromium for browser proxy mode)"
         );
     }
 
@@ -551,7 +1286,8 @@ pub async fn scrape_url_full(
         metrics.push_phase("forced_cdp_attempt", forced_cdp_start.elapsed(), None);
 
         match initial_result {
-            Ok(result) => {
+            Ok(mut result) => {
+                metrics.provenance.record(&mut result);
                 if let (Some(proxy_url), Some(manager)) =
                     (forced_proxy.as_ref(), state.proxy_manager.as_ref())
                 {
@@ -591,6 +1327,7 @@ pub async fn scrape_url_full(
                                 });
 
                                 // Retry with proxy
+                                let proxy_retry_start = Instant::now();
                                 match run_scrape_stage_with_timeout(
                                     "forced_cdp_attempt",
                                     rust_scraper.scrape_with_browserless_advanced_with_proxy(
@@ -623,38 +1360,12 @@ pub async fn scrape_url_full(
                                                 .await;
 
                                             // Auto-log to history
-                                            if let Some(memory) = state.get_memory() {
-                                                let summary = format!(
-                                                    "{} words (proxy), {} code blocks",
-                                                    proxy_result.word_count,
-                                                    proxy_result.code_blocks.len()
-                                                );
-                                                let domain =
-                                                    url::Url::parse(url).ok().and_then(|u| {
-                                                        u.host_str().map(|s| s.to_string())
-                                                    });
-                                                let result_json =
-                                                    serde_json::to_value(&proxy_result)
-                                                        .unwrap_or_default();
-
-                                                if let Err(e) = run_scrape_stage_with_timeout(
-                                                    "history_log",
-                                                    memory.log_scrape(
-                                                        url.to_string(),
-                                                        Some(proxy_result.title.clone()),
-                                                        summary,
-                                                        domain,
-                                                        &result_json,
-                                                    ),
-                                                )
-                                                .await
-                                                {
-                                                    tracing::warn!(
-                                                        "Failed to log scrape to history: {}",
-                                                        e
-                                                    );
-                                                }
-                                            }
+                                            log_scrape_to_history(
+                                                state,
+                                                url,
+                                                &proxy_result,
+                                                Some("proxy"),
+                                            );
 
                                             auth_cache_post_scrape(
                                                 url,
@@ -665,6 +1376,8 @@ pub async fn scrape_url_full(
                                         } else {
                                             warn!("⚠️ PROXY RETRY NO IMPROVEMENT: {} words vs {} before", 
                                                   proxy_result.word_count, result.word_count);
+                                            let mut proxy_result = proxy_result;
+                                            metrics.provenance.discard(&mut proxy_result);
                                             // Record proxy failure
                                             let _ = proxy_manager
                                                 .record_proxy_result(&proxy_url, false, None)
@@ -673,6 +1386,13 @@ pub async fn scrape_url_full(
                                     }
                                     Err(e) => {
                                         warn!("❌ PROXY RETRY FAILED: {}", e);
+                                        metrics.provenance.fail(provenance::failed(
+                                            ScrapeMethod::Cdp,
+                                            rust_scraper.browser_user_agent_class(),
+                                            Some(&proxy_url),
+                                            proxy_retry_start.elapsed(),
+                                            &e,
+                                        ));
                                         // Record proxy failure
                                         let _ = proxy_manager
                                             .record_proxy_result(&proxy_url, false, None)
@@ -688,7 +1408,6 @@ pub async fn scrape_url_full(
                 }
 
                 // Cache and return original result (if proxy retry didn't succeed)
-                let mut result = result;
                 attach_scrape_metrics(&mut result, &metrics, total_start.elapsed());
                 state
                     .scrape_cache
@@ -696,32 +1415,7 @@ pub async fn scrape_url_full(
                     .await;
 
                 // Auto-log to history
-                if let Some(memory) = state.get_memory() {
-                    let summary = format!(
-                        "{} words, {} code blocks",
-                        result.word_count,
-                        result.code_blocks.len()
-                    );
-                    let domain = url::Url::parse(url)
-                        .ok()
-                        .and_then(|u| u.host_str().map(|s| s.to_string()));
-                    let result_json = serde_json::to_value(&result).unwrap_or_default();
-
-                    if let Err(e) = run_scrape_stage_with_timeout(
-                        "history_log",
-                        memory.log_scrape(
-                            url.to_string(),
-                            Some(result.title.clone()),
-                            summary,
-                            domain,
-                            &result_json,
-                        ),
-                    )
-                    .await
-                    {
-                        tracing::warn!("Failed to log scrape to history: {}", e);
-                    }
-                }
+                log_scrape_to_history(state, url, &result, None);
 
                 auth_cache_post_scrape(url, &result, cached_session_active);
                 return Ok(result);
@@ -732,6 +1426,13 @@ pub async fn scrape_url_full(
                     forced_cdp_start.elapsed(),
                     Some(format!("failed: {}", e)),
                 );
+                metrics.provenance.fail(provenance::failed(
+                    ScrapeMethod::Cdp,
+                    rust_scraper.browser_user_agent_class(),
+                    forced_proxy.as_deref(),
+                    forced_cdp_start.elapsed(),
+                    &e,
+                ));
                 if let (Some(proxy_url), Some(manager)) =
                     (forced_proxy.as_ref(), state.proxy_manager.as_ref())
                 {
@@ -745,62 +1446,104 @@ pub async fn scrape_url_full(
         }
     }
 
-    // Only use Rust-native scraper with retries
+    // Rust-native scraper; transient HTTP failures are retried inside `scrape_url`
+    // according to the retry policy.
     let native_scrape_start = Instant::now();
-    let mut result = run_scrape_stage_with_timeout(
+    let fetched = run_scrape_stage_with_timeout(
         "native_http_scrape",
-        retry(
-            ExponentialBackoffBuilder::new()
-                .with_initial_interval(std::time::Duration::from_millis(200))
-                .with_max_interval(std::time::Duration::from_secs(2))
-                .with_max_elapsed_time(Some(std::time::Duration::from_secs(6)))
-                .build(),
-            || async {
-                match rust_scraper.scrape_url(&url_owned).await {
-                    Ok(r) => Ok(r),
-                    Err(e) => {
-                        // Treat network/temporary HTML parse errors as transient
-                        Err(backoff::Error::transient(anyhow!("{}", e)))
-                    }
-                }
-            },
-        ),
+        rust_scraper.scrape_url_revalidating(&url_owned, revalidate_from.as_ref()),
     )
     .await?;
     metrics.push_phase("native_http_scrape", native_scrape_start.elapsed(), None);
-
-    // PHASE 3: Adaptive native-CDP fallback for low-quality extractions
-    let should_use_native_cdp = (result.extraction_score.map(|s| s < 0.35).unwrap_or(false)
-        || result.word_count < 50)
-        && !result.warnings.contains(&"native_cdp_rendered".to_string());
+    let mut result = match fetched {
+        Revalidation::Modified(result) => *result,
+        Revalidation::NotModified => {
+            let cached = revalidate_from.expect("304 only answers a conditional request");
+            return Ok(serve_revalidated(
+                state,
+                &cache_key,
+                cache_knobs,
+                cached,
+                &mut metrics,
+                total_start.elapsed(),
+            )
+            .await);
+        }
+    };
+    metrics.provenance.record(&mut result);
+
+    // PHASE 3: Adaptive native-CDP escalation for low-quality extractions
+    let should_use_native_cdp = auto_escalate
+        .unwrap_or_else(crate::core::config::auto_escalate_enabled)
+        && should_auto_escalate(
+            &result,
+            crate::core::config::auto_escalate_min_score(),
+            crate::core::config::auto_escalate_min_words(),
+        );
+    // Static pass + escalation together get at most twice the static stage timeout.
+    let escalation_budget = escalation_time_budget(native_scrape_start.elapsed());
+    // Whether a scrape escalates cannot be told from its arguments, so the browser budget
+    // is charged here, to the caller the call was admitted for, rather than at dispatch.
+    let browser_budget = if should_use_native_cdp
+        && escalation_budget.is_some()
+        && crate::scraping::browser_manager::native_browser_available()
+    {
+        state.budgets.charge(
+            &crate::core::budget::current_caller(),
+            crate::core::budget::Cost {
+                browser: 1,
+                ..Default::default()
+            },
+        )
+    } else {
+        Ok(())
+    };
 
     if should_use_native_cdp {
-        if crate::scraping::browser_manager::native_browser_available() {
+        if let (Some(escalation_timeout), Ok(())) = (
+            escalation_budget
+                .filter(|_| crate::scraping::browser_manager::native_browser_available()),
+            &browser_budget,
+        ) {
             info!(
-                "Low quality extraction (score: {:.2}, words: {}), attempting native CDP fallback",
+                "Low quality extraction (score: {:.2}, words: {}), escalating to native CDP",
                 result.extraction_score.unwrap_or(0.0),
                 result.word_count
             );
 
             let cdp_fallback_start = Instant::now();
-            match run_scrape_stage_with_timeout(
+            match run_scrape_stage_with_budget(
                 "native_cdp_fallback",
+                escalation_timeout,
                 rust_scraper.scrape_with_browserless(&url_owned),
             )
             .await
             {
-                Ok(cdp_result) => {
+                Ok(mut cdp_result) => {
                     metrics.push_phase("native_cdp_fallback", cdp_fallback_start.elapsed(), None);
                     if cdp_result.word_count > result.word_count + 20 {
+                        metrics.provenance.record(&mut cdp_result);
                         info!(
                             "✨ Native CDP improved extraction: {} → {} words",
                             result.word_count, cdp_result.word_count
                         );
+                        let static_warnings = std::mem::take(&mut result.warnings);
                         result = cdp_result;
+                        for warning in static_warnings {
+                            crate::content_quality::push_warning_unique(
+                                &mut result.warnings,
+                                &warning,
+                            );
+                        }
+                        crate::content_quality::push_warning_unique(
+                            &mut result.warnings,
+                            "escalated_to_browser",
+                        );
                     } else {
                         info!(
                             "Native CDP didn't improve extraction significantly, keeping original"
                         );
+                        metrics.provenance.discard(&mut cdp_result);
                     }
                 }
                 Err(e) => {
@@ -809,10 +1552,28 @@ pub async fn scrape_url_full(
                         cdp_fallback_start.elapsed(),
                         Some(format!("failed: {}", e)),
                     );
+                    metrics.provenance.fail(provenance::failed(
+                        ScrapeMethod::Cdp,
+                        rust_scraper.browser_user_agent_class(),
+                        None,
+                        cdp_fallback_start.elapsed(),
+                        &e,
+                    ));
                     info!("Native CDP fallback failed: {}, using static result", e);
                     result.warnings.push("cdp_fallback_failed".to_string());
                 }
             }
+        } else if let Err(e) = &browser_budget {
+            info!("Not escalating {} to the browser: {}", url, e);
+            crate::content_quality::push_warning_unique(
+                &mut result.warnings,
+                "escalation_skipped:browser_budget",
+            );
+        } else if escalation_budget.is_none() {
+            crate::content_quality::push_warning_unique(
+                &mut result.warnings,
+                "escalation_skipped:time_budget",
+            );
         } else {
             result.warnings.push("low_quality_extraction".to_string());
             result.warnings.push(
@@ -868,6 +1629,33 @@ pub async fn scrape_url_full(
                     extract_relevant_sections,
                     section_limit,
                     section_threshold,
+                    respect_robots,
+                    domain_rate_limit,
+                    parse_feeds,
+                    max_attempts,
+                    auto_escalate,
+                    download_assets: None,
+                    bypass_cache,
+                    revalidate,
+                    persist_session,
+                    clear_session: false,
+                    auth: auth.clone(),
+                    headers: headers.clone(),
+                    links_only,
+                    links_detail,
+                    // Applied to the pivot result by the outer `scrape_url_full`.
+                    max_links: None,
+                    screenshot,
+                    screenshot_full_page,
+                    screenshot_inline: false,
+                    include_raw_html,
+                    // Applied to the pivot result by the outer `scrape_url_full`.
+                    selectors: BTreeMap::new(),
+                    max_selector_matches: None,
+                    prefer_language: None,
+                    sections: None,
+                    // Followed from the pivot result by the outer `scrape_url_full`.
+                    follow_pagination: None,
                 };
                 if let Ok(pivot_result) =
                     Box::pin(scrape_url_full(state, &pivot_url, pivot_options)).await
@@ -951,55 +1739,27 @@ pub async fn scrape_url_full(
     if !(result.auth_wall_reason.is_some()
         || result.warnings.iter().any(|w| w == "content_restricted"))
     {
-        state
-            .scrape_cache
-            .insert(cache_key.clone(), result.clone())
-            .await;
+        cache_scrape_result(state, &cache_key, cache_knobs, &result).await;
     }
 
     // Auto-log to history if memory is enabled (Phase 1)
-    if let Some(memory) = state.get_memory() {
-        let history_start = Instant::now();
-        let summary = format!(
-            "{} words, {} code blocks",
-            result.word_count,
-            result.code_blocks.len()
-        );
-
-        // Extract domain from URL
-        let domain = url::Url::parse(url)
-            .ok()
-            .and_then(|u| u.host_str().map(|s| s.to_string()));
-
-        let result_json = serde_json::to_value(&result).unwrap_or_default();
-
-        if let Err(e) = run_scrape_stage_with_timeout(
-            "history_log",
-            memory.log_scrape(
-                url.to_string(),
-                Some(result.title.clone()),
-                summary,
-                domain,
-                &result_json,
-            ),
-        )
-        .await
-        {
-            tracing::warn!("Failed to log scrape to history: {}", e);
-        }
-        metrics.push_phase("history_log", history_start.elapsed(), None);
-    }
+    log_scrape_to_history(state, url, &result, None);
 
     attach_scrape_metrics(&mut result, &metrics, total_start.elapsed());
 
     Ok(result)
 }
 
+/// Per-domain pacing delays longer than this are surfaced as a warning.
+const DOMAIN_RATE_WARN_AFTER: Duration = Duration::from_secs(2);
+
 #[derive(Default)]
 struct ScrapeMetricsBuilder {
     strategy: Option<String>,
     cache_hit: bool,
     phases: Vec<ToolExecutionPhase>,
+    rate_limit_wait: Duration,
+    provenance: provenance::Trail,
 }
 
 impl ScrapeMetricsBuilder {
@@ -1018,6 +1778,12 @@ fn attach_scrape_metrics(
     metrics: &ScrapeMetricsBuilder,
     total_duration: Duration,
 ) {
+    if metrics.rate_limit_wait > DOMAIN_RATE_WARN_AFTER {
+        crate::content_quality::push_warning_unique(
+            &mut result.warnings,
+            &format!("domain_rate_limited:{}ms", metrics.rate_limit_wait.as_millis()),
+        );
+    }
     result.metrics = Some(ToolExecutionMetrics {
         total_duration_ms: total_duration.as_millis() as u64,
         total_duration_seconds: total_duration.as_secs_f64(),
@@ -1025,6 +1791,7 @@ fn attach_scrape_metrics(
         cache_hit: metrics.cache_hit,
         phases: metrics.phases.clone(),
     });
+    metrics.provenance.attach(result);
 }
 
 async fn run_scrape_stage_with_timeout<T, F>(stage_name: &str, future: F) -> Result<T>
@@ -1032,6 +1799,21 @@ where
     F: Future<Output = Result<T>>,
 {
     let timeout = Duration::from_secs(crate::core::config::scrape_stage_timeout_secs(stage_name));
+    run_scrape_stage_with_budget(stage_name, timeout, future).await
+}
+
+/// Like [`run_scrape_stage_with_timeout`], but never longer than `budget`.
+async fn run_scrape_stage_with_budget<T, F>(
+    stage_name: &str,
+    budget: Duration,
+    future: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let stage_timeout =
+        Duration::from_secs(crate::core::config::scrape_stage_timeout_secs(stage_name));
+    let timeout = stage_timeout.min(budget);
     match tokio::time::timeout(timeout, future).await {
         Ok(result) => result,
         Err(_) => Err(anyhow!(
@@ -1042,13 +1824,42 @@ where
     }
 }
 
+/// Escalation criteria for a static result: HTML only (not a body handled natively, such
+/// as sniffed JSON), not already browser-rendered, and either scored below `min_score`,
+/// shorter than `min_words`, or a near-empty placeholder (the same sparse-content check
+/// `extract_structured` uses).
+pub fn should_auto_escalate(result: &ScrapeResponse, min_score: f64, min_words: usize) -> bool {
+    let content_type = result.content_type.to_ascii_lowercase();
+    let is_html = (content_type.is_empty() || content_type.contains("html"))
+        && result.content_format.is_none();
+    if !is_html || result.warnings.iter().any(|w| w == "native_cdp_rendered") {
+        return false;
+    }
+    result
+        .extraction_score
+        .is_some_and(|score| score < min_score)
+        || crate::extract::is_sparse_content(result, min_words)
+}
+
+/// Time left for a browser escalation after a static pass that took `static_elapsed`,
+/// out of twice the static stage timeout. `None` when too little is left to render.
+fn escalation_time_budget(static_elapsed: Duration) -> Option<Duration> {
+    let static_timeout = Duration::from_secs(crate::core::config::scrape_stage_timeout_secs(
+        "native_http_scrape",
+    ));
+    (static_timeout * 2)
+        .checked_sub(static_elapsed)
+        .filter(|left| *left >= Duration::from_secs(3))
+}
+
 fn should_try_cdp_first(
     url: &str,
     use_proxy: bool,
     quality_mode: Option<QualityMode>,
     extract_app_state: bool,
 ) -> bool {
-    if use_proxy || extract_app_state {
+    // A static fetch of a fragment route only ever returns the app shell.
+    if use_proxy || extract_app_state || fragment_route::route(url).is_some() {
         return true;
     }
 
@@ -1108,6 +1919,162 @@ fn github_pivot_plain_url(url: &str) -> Option<String> {
     Some(u2.to_string())
 }
 
+/// Queue `result` for the scrape history when memory is enabled. `via` names the path that
+/// produced it (e.g. "proxy") in the entry's summary.
+fn log_scrape_to_history(state: &AppState, url: &str, result: &ScrapeResponse, via: Option<&str>) {
+    if state.get_memory().is_none() {
+        return;
+    }
+    let summary = match via {
+        Some(via) => format!(
+            "{} words ({}), {} code blocks",
+            result.word_count,
+            via,
+            result.code_blocks.len()
+        ),
+        None => format!(
+            "{} words, {} code blocks",
+            result.word_count,
+            result.code_blocks.len()
+        ),
+    };
+    let domain = url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|s| s.to_string()));
+    state.memory_guard.log_scrape(
+        url.to_string(),
+        Some(result.title.clone()),
+        summary,
+        domain,
+        serde_json::to_value(result).unwrap_or_default(),
+    );
+}
+
+/// Cache `result` under `cache_key` and, when the fetch was redirected, under its final
+/// URL's key too, so the same page reached by another link is served from the cache.
+async fn cache_scrape_result(
+    state: &Arc<AppState>,
+    cache_key: &str,
+    knobs: ScrapeCacheKeyKnobs<'_>,
+    result: &ScrapeResponse,
+) {
+    if let Some(final_url) = result.final_url.as_deref().filter(|u| *u != result.url) {
+        let final_key = compute_scrape_cache_key(final_url, knobs);
+        state.scrape_cache.insert(final_key, result.clone()).await;
+    }
+    state
+        .scrape_cache
+        .insert(cache_key.to_string(), result.clone())
+        .await;
+}
+
+/// Whether a cached scrape taken at its `timestamp` is still within `ttl` at `now`.
+/// Unparseable timestamps count as stale.
+fn is_fresh(cached: &ScrapeResponse, ttl: Duration, now: chrono::DateTime<chrono::Utc>) -> bool {
+    match chrono::DateTime::parse_from_rfc3339(&cached.timestamp) {
+        // A timestamp ahead of `now` (clock skew) has a negative age and is fresh.
+        Ok(taken) => (now - taken.with_timezone(&chrono::Utc))
+            .to_std()
+            .map_or(true, |age| age <= ttl),
+        Err(_) => false,
+    }
+}
+
+/// Stale entries can be revalidated when the origin gave us a validator and the content
+/// came from the static fetch; a browser-rendered page may differ from what a 304 vouches for.
+fn can_revalidate(cached: &ScrapeResponse) -> bool {
+    (cached.etag.is_some() || cached.last_modified.is_some())
+        && !cached.warnings.iter().any(|w| w == "native_cdp_rendered")
+}
+
+/// The origin answered 304: renew the entry's timestamp in the cache and return it marked
+/// as revalidated.
+async fn serve_revalidated(
+    state: &Arc<AppState>,
+    cache_key: &str,
+    knobs: ScrapeCacheKeyKnobs<'_>,
+    mut cached: ScrapeResponse,
+    metrics: &mut ScrapeMetricsBuilder,
+    total_duration: Duration,
+) -> ScrapeResponse {
+    cached.timestamp = chrono::Utc::now().to_rfc3339();
+    cache_scrape_result(state, cache_key, knobs, &cached).await;
+    cached.revalidated = true;
+    metrics.cache_hit = true;
+    metrics.strategy = Some("revalidated".to_string());
+    attach_scrape_metrics(&mut cached, metrics, total_duration);
+    cached
+}
+
+/// Approximate heap size of a cached scrape, used as its weight against
+/// `SCRAPE_CACHE_MAX_BYTES`. Counts the text-bearing fields plus a flat allowance for the
+/// struct and its small metadata strings.
+pub(crate) fn cached_scrape_weight(key: &str, result: &ScrapeResponse) -> u32 {
+    const BASE_BYTES: usize = 2048;
+    let bytes = BASE_BYTES
+        + key.len()
+        + result.content.len()
+        + result.clean_content.len()
+        + result.embedded_state_json.as_ref().map_or(0, String::len)
+        + result
+            .embedded_data_sources
+            .iter()
+            .map(|s| s.content.len())
+            .sum::<usize>()
+        + result.headings.iter().map(|h| h.text.len()).sum::<usize>()
+        + result
+            .links
+            .iter()
+            .map(|l| l.url.len() + l.text.len())
+            .sum::<usize>()
+        + result
+            .images
+            .iter()
+            .map(|i| i.src.len() + i.alt.len() + i.title.len())
+            .sum::<usize>()
+        + result
+            .code_blocks
+            .iter()
+            .map(|c| c.code.len())
+            .sum::<usize>()
+        + result
+            .structured_data
+            .iter()
+            .map(|v| v.to_string().len())
+            .sum::<usize>();
+    u32::try_from(bytes).unwrap_or(u32::MAX)
+}
+
+/// Cache key a default-options scrape of `url` is stored under.
+pub(crate) fn default_scrape_cache_key(url: &str) -> String {
+    let url = github_raw_candidates(url)
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| url.to_string());
+    compute_scrape_cache_key(
+        &url,
+        ScrapeCacheKeyKnobs {
+            quality_mode: None,
+            query: None,
+            strict_relevance: false,
+            relevance_threshold: None,
+            extract_app_state: false,
+            extract_relevant_sections: false,
+            section_limit: None,
+            section_threshold: None,
+            parse_feeds: false,
+            persist_session: false,
+            auth: None,
+            headers: None,
+            links_only: false,
+            links_detail: false,
+            screenshot: false,
+            raw_html: false,
+            auto_escalate: crate::core::config::auto_escalate_enabled(),
+        },
+    )
+}
+
 #[derive(Clone, Copy, Debug)]
 struct ScrapeCacheKeyKnobs<'a> {
     quality_mode: Option<QualityMode>,
@@ -1118,6 +2085,16 @@ struct ScrapeCacheKeyKnobs<'a> {
     extract_relevant_sections: bool,
     section_limit: Option<usize>,
     section_threshold: Option<f32>,
+    parse_feeds: bool,
+    persist_session: bool,
+    auth: Option<&'a RequestAuth>,
+    headers: Option<&'a RequestHeaders>,
+    links_only: bool,
+    links_detail: bool,
+    screenshot: bool,
+    raw_html: bool,
+    /// Effective `auto_escalate`: a thin static result is only final when it is off.
+    auto_escalate: bool,
 }
 
 fn compute_scrape_cache_key(url: &str, knobs: ScrapeCacheKeyKnobs<'_>) -> String {
@@ -1130,6 +2107,15 @@ fn compute_scrape_cache_key(url: &str, knobs: ScrapeCacheKeyKnobs<'_>) -> String
         extract_relevant_sections,
         section_limit,
         section_threshold,
+        parse_feeds,
+        persist_session,
+        auth,
+        headers,
+        links_only,
+        links_detail,
+        screenshot,
+        raw_html,
+        auto_escalate,
     } = knobs;
     let ns = if crate::core::config::neurosiphon_enabled() {
         1
@@ -1139,7 +2125,36 @@ fn compute_scrape_cache_key(url: &str, knobs: ScrapeCacheKeyKnobs<'_>) -> String
     let qm = quality_mode.unwrap_or(QualityMode::Balanced).as_str();
     let eas = if extract_app_state { 1 } else { 0 };
     let ers = if extract_relevant_sections { 1 } else { 0 };
+    // `#section-3` is the same page; `#/docs` is not.
+    let url = fragment_route::without_anchor(url);
     let mut key = format!("{}|qm={}|ns={}|eas={}|ers={}", url, qm, ns, eas, ers);
+    if parse_feeds {
+        key.push_str("|pf=1");
+    }
+    if persist_session {
+        key.push_str("|ps=1");
+    }
+    if links_only {
+        key.push_str("|lo=1");
+    }
+    if links_detail {
+        key.push_str("|ld=1");
+    }
+    if screenshot {
+        key.push_str("|shot=1");
+    }
+    if raw_html {
+        key.push_str("|raw=1");
+    }
+    if !auto_escalate {
+        key.push_str("|ae=0");
+    }
+    if let Some(auth) = auth {
+        key.push_str(&format!("|auth={}", auth.fingerprint()));
+    }
+    if let Some(headers) = headers {
+        key.push_str(&format!("|hdr={}", headers.fingerprint()));
+    }
     if strict_relevance {
         let threshold = relevance_threshold.unwrap_or(semantic_shave::DEFAULT_RELEVANCE_THRESHOLD);
         key.push_str(&format!("|sr=1|t={:.3}", threshold));
@@ -1445,12 +2460,14 @@ async fn apply_semantic_shaving_if_enabled(
 // Fallback scraper using direct HTTP request (legacy simple mode) -- optional; keeping for troubleshooting
 pub async fn scrape_url_fallback(state: &Arc<AppState>, url: &str) -> Result<ScrapeResponse> {
     info!("Using fallback scraper for: {}", url);
+    const USER_AGENT: &str = "Mozilla/5.0 (compatible; SearchScrape/1.0)";
 
     // Make direct HTTP request
+    let started = Instant::now();
     let response = state
         .http_client
         .get(url)
-        .header("User-Agent", "Mozilla/5.0 (compatible; SearchScrape/1.0)")
+        .header("User-Agent", USER_AGENT)
         .send()
         .await
         .map_err(|e| anyhow!("Failed to fetch URL: {}", e))?;
@@ -1463,10 +2480,7 @@ pub async fn scrape_url_fallback(state: &Arc<AppState>, url: &str) -> Result<Scr
         .unwrap_or("text/html")
         .to_string();
 
-    let html = response
-        .text()
-        .await
-        .map_err(|e| anyhow!("Failed to read response body: {}", e))?;
+    let html = crate::rust_scraper::read_html_capped(response).await?;
 
     let document = select::document::Document::from(html.as_str());
 
@@ -1520,6 +2534,10 @@ pub async fn scrape_url_fallback(state: &Arc<AppState>, url: &str) -> Result<Scr
             n.attr("href").map(|href| Link {
                 url: href.to_string(),
                 text: n.text(),
+                nofollow: n
+                    .attr("rel")
+                    .is_some_and(crate::rust_scraper::rel_has_nofollow),
+                detail: None,
             })
         })
         .collect();
@@ -1575,59 +2593,91 @@ pub async fn scrape_url_fallback(state: &Arc<AppState>, url: &str) -> Result<Scr
         auth_risk_score: None,
         detection_factors: Vec::new(),
         final_url: None,
+        redirect_chain: Vec::new(),
         metrics: None,
+        content_format: None,
+        page_count: None,
+        feed_urls: Vec::new(),
+        feed_items: Vec::new(),
+        fetch_attempts: None,
+        block_reason: None,
+        asset_manifest: None,
+        charset: None,
+        etag: None,
+        last_modified: None,
+        revalidated: false,
+        structured_data: Vec::new(),
+        screenshot: None,
+        robots_directives: Vec::new(),
+        selector_results: None,
+        alternate_languages: Vec::new(),
+        provenance: vec![provenance::attempt(
+            ScrapeMethod::Static,
+            provenance::user_agent_class(USER_AGENT),
+            None,
+            started.elapsed(),
+            status_code,
+            None,
+        )],
+        sections: Vec::new(),
+        title_source: None,
+        meta_description_source: None,
+        quality: None,
     };
 
     info!("Fallback scraper extracted {} words", result.word_count);
     Ok(result)
 }
 
-/// Rewrite certain URL patterns into variants that produce cleaner content for the pipeline.
+/// Raw-text URLs to try, in order, before scraping a GitHub page through the generic path.
+///
+/// - `github.com/{owner}/{repo}/blob/{ref}/{path}` → `raw.githubusercontent.com/{owner}/{repo}/{ref}/{path}`.
+///   Blob pages are React SPAs; the raw URL returns plain source text directly.
+/// - `github.com/{owner}/{repo}` → the repo's `README.md` on `main`, then on `master`.
+///   Repo roots are SPAs too; the raw README is the stable text entry point.
 ///
-/// Currently handles:
-/// - `github.com/{owner}/{repo}/blob/{ref}/{path}` → `raw.githubusercontent.com/{owner}/{repo}/{ref}/{path}`
-///   GitHub blob pages are React SPAs; the raw URL returns plain source text directly.
-/// - `github.com/{owner}/{repo}` → `raw.githubusercontent.com/{owner}/{repo}/HEAD/README.md`
-///   Repo root pages are also SPAs; rewriting to the raw README gives a stable text entry point.
-fn rewrite_url_for_clean_content(url: &str) -> Option<String> {
-    // GitHub file blob viewer pages
-    if url.contains("github.com/")
-        && url.contains("/blob/")
-        && !url.contains("raw.githubusercontent.com")
+/// Anything else yields no candidates.
+fn github_raw_candidates(url: &str) -> Vec<String> {
+    let Ok(parsed) = url::Url::parse(url) else {
+        return Vec::new();
+    };
+    if !parsed
+        .host_str()
+        .is_some_and(|host| host.eq_ignore_ascii_case("github.com"))
     {
-        if let Some(blob_idx) = url.find("/blob/") {
-            let prefix = &url[..blob_idx]; // "https://github.com/owner/repo"
-            let after_blob = &url[blob_idx + "/blob".len()..]; // "/main/README.md"
-            if let Some(gh_idx) = prefix.find("github.com") {
-                let scheme_prefix = &prefix[..gh_idx]; // "https://"
-                let repo_path = &prefix[(gh_idx + "github.com".len())..]; // "/owner/repo"
-                let raw_url = format!(
-                    "{}raw.githubusercontent.com{}{}",
-                    scheme_prefix, repo_path, after_blob
-                );
-                info!("🔀 GitHub blob → raw URL: {}", raw_url);
-                return Some(raw_url);
-            }
-        }
+        return Vec::new();
     }
-
-    if url.contains("github.com") && !url.contains("raw.githubusercontent.com") {
-        if let Ok(parsed) = url::Url::parse(url) {
-            if parsed.host_str() == Some("github.com") {
-                let segments: Vec<&str> = parsed.path_segments()?.collect();
-                if segments.len() == 2 && segments.iter().all(|segment| !segment.is_empty()) {
-                    let raw_url = format!(
-                        "https://raw.githubusercontent.com/{}/{}/HEAD/README.md",
-                        segments[0], segments[1]
-                    );
-                    info!("🔀 GitHub repo root → raw README URL: {}", raw_url);
-                    return Some(raw_url);
-                }
-            }
+    let segments: Vec<&str> = parsed
+        .path_segments()
+        .map(|segments| segments.collect())
+        .unwrap_or_default();
+    match segments.as_slice() {
+        [owner, repo, "blob", rest @ ..]
+            if !owner.is_empty() && !repo.is_empty() && rest.len() >= 2 =>
+        {
+            let raw_url = format!(
+                "https://raw.githubusercontent.com/{}/{}/{}",
+                owner,
+                repo,
+                rest.join("/")
+            );
+            info!("🔀 GitHub blob → raw URL: {}", raw_url);
+            vec![raw_url]
+        }
+        [owner, repo] | [owner, repo, ""] if !owner.is_empty() && !repo.is_empty() => {
+            info!("🔀 GitHub repo root → raw README ({}/{})", owner, repo);
+            ["main", "master"]
+                .iter()
+                .map(|branch| {
+                    format!(
+                        "https://raw.githubusercontent.com/{}/{}/{}/README.md",
+                        owner, repo, branch
+                    )
+                })
+                .collect()
         }
+        _ => Vec::new(),
     }
-
-    None
 }
 
 // BOSS LEVEL OPTIMIZATION: Domain detection helpers
@@ -1697,6 +2747,7 @@ mod tests {
                 json_found: false,
                 settle_time_ms: None,
                 noise_reduction_ratio: 0.0,
+                consent_nodes_removed: 0,
             },
             meta_description: String::new(),
             meta_keywords: String::new(),
@@ -1727,9 +2778,244 @@ mod tests {
             auth_risk_score: None,
             detection_factors: Vec::new(),
             final_url: None,
+            redirect_chain: Vec::new(),
+            metrics: None,
+            content_format: None,
+            page_count: None,
+            feed_urls: Vec::new(),
+            feed_items: Vec::new(),
+            fetch_attempts: None,
+            block_reason: None,
+            asset_manifest: None,
+            charset: None,
+            etag: None,
+            last_modified: None,
+            revalidated: false,
+            structured_data: Vec::new(),
+            screenshot: None,
+            robots_directives: Vec::new(),
+            selector_results: None,
+            alternate_languages: Vec::new(),
+            provenance: Vec::new(),
+            sections: Vec::new(),
+            title_source: None,
+            meta_description_source: None,
+            quality: None,
         }
     }
 
+    #[test]
+    fn preferred_alternate_matches_exact_then_primary_language() {
+        let alternate = |lang: &str, url: &str| AlternateLanguage {
+            lang: lang.to_string(),
+            url: url.to_string(),
+        };
+        let mut page = mk_response("Hello");
+        page.url = "https://example.com/en/page".to_string();
+        page.language = "en-US".to_string();
+        page.alternate_languages = vec![
+            alternate("x-default", "https://example.com/page"),
+            alternate("en", "https://example.com/en/page"),
+            alternate("de-DE", "https://example.com/de/page"),
+            alternate("pt-PT", "https://example.com/pt/page"),
+            alternate("pt-BR", "https://example.com/br/page"),
+        ];
+
+        let pick = |prefer| preferred_alternate(&page, prefer).map(|a| a.url.as_str());
+        assert_eq!(pick("de"), Some("https://example.com/de/page"));
+        assert_eq!(pick("pt_br"), Some("https://example.com/br/page"));
+        assert_eq!(pick("pt"), Some("https://example.com/pt/page"));
+        assert_eq!(pick("EN"), None, "the page itself is the en alternate");
+        assert_eq!(pick("en-GB"), None, "same primary language, no exact match");
+        assert_eq!(pick("ja"), None);
+        assert_eq!(pick("x-default"), None);
+    }
+
+    #[tokio::test]
+    async fn invalid_url_maps_to_invalid_input() {
+        let state = Arc::new(AppState::new(reqwest::Client::new()));
+        let err = scrape_url_full(
+            &state,
+            "ftp://example.com/file",
+            ScrapeUrlOptions::default(),
+        )
+        .await
+        .unwrap_err();
+        let (status, axum::Json(body)) = ScoutError::classify(&err).into_http();
+        assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            serde_json::to_value(&body).unwrap()["code"],
+            "invalid_input"
+        );
+    }
+
+    #[tokio::test]
+    async fn screenshot_cannot_combine_with_links_only() {
+        let state = Arc::new(AppState::new(reqwest::Client::new()));
+        let options = ScrapeUrlOptions {
+            links_only: true,
+            screenshot: true,
+            ..Default::default()
+        };
+        let err = scrape_url_full(&state, "https://example.com/", options)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            ScoutError::classify(&err).kind,
+            crate::core::error::ErrorKind::InvalidInput
+        );
+    }
+
+    #[tokio::test]
+    async fn screenshot_outcome_flags_missing_and_oversized_captures() {
+        let mut page = mk_response("rendered");
+        attach_screenshot_outcome(&mut page, true).await;
+        assert!(page.warnings.iter().any(|w| w == "screenshot_unavailable"));
+
+        let mut page = mk_response("rendered");
+        page.screenshot = Some(PageScreenshot {
+            path: "/nonexistent/shot.png".to_string(),
+            bytes: usize::MAX,
+            full_page: false,
+            base64: None,
+        });
+        attach_screenshot_outcome(&mut page, true).await;
+        assert!(page.screenshot.as_ref().unwrap().base64.is_none());
+        assert!(page
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("screenshot_too_large_to_inline:")));
+    }
+
+    #[test]
+    fn stale_entries_revalidate_only_with_validators_and_static_content() {
+        let now = chrono::Utc::now();
+        let ttl = Duration::from_secs(60);
+        let mut cached = mk_response("Body");
+        cached.timestamp = (now - chrono::Duration::seconds(30)).to_rfc3339();
+        assert!(is_fresh(&cached, ttl, now));
+        cached.timestamp = (now - chrono::Duration::seconds(90)).to_rfc3339();
+        assert!(!is_fresh(&cached, ttl, now));
+        cached.timestamp = String::new();
+        assert!(!is_fresh(&cached, ttl, now));
+
+        assert!(!can_revalidate(&cached));
+        cached.etag = Some("\"v1\"".to_string());
+        assert!(can_revalidate(&cached));
+        cached.warnings.push("native_cdp_rendered".to_string());
+        assert!(!can_revalidate(&cached));
+    }
+
+    #[test]
+    fn block_page_maps_to_blocked_error() {
+        assert!(block_error(&mk_response("plenty of real content")).is_none());
+
+        let mut blocked = mk_response("Please complete the captcha");
+        blocked.status_code = 403;
+        blocked.block_reason = Some("Captcha".to_string());
+        let (status, axum::Json(body)) = block_error(&blocked).unwrap().into_http();
+        assert_eq!(status, axum::http::StatusCode::FORBIDDEN);
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["code"], "blocked");
+        assert_eq!(json["details"]["reason"], "Captcha");
+        assert_eq!(json["details"]["status_code"], 403);
+    }
+
+    #[test]
+    fn auto_escalation_only_for_weak_html() {
+        let article = (0..120)
+            .map(|i| format!("word{}", i))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut good = mk_response(&format!("{}\n\n{}", article, article));
+        good.extraction_score = Some(0.8);
+        assert!(!should_auto_escalate(&good, 0.35, 50));
+
+        let mut low_score = good.clone();
+        low_score.extraction_score = Some(0.2);
+        assert!(should_auto_escalate(&low_score, 0.35, 50));
+
+        let shell = mk_response("Loading...\nPlease enable JavaScript");
+        assert!(should_auto_escalate(&shell, 0.35, 50));
+
+        let mut pdf = shell.clone();
+        pdf.content_type = "application/pdf".to_string();
+        assert!(!should_auto_escalate(&pdf, 0.35, 50));
+
+        let mut rendered = shell.clone();
+        rendered.warnings.push("native_cdp_rendered".to_string());
+        assert!(!should_auto_escalate(&rendered, 0.35, 50));
+
+        let mut sniffed_json = shell.clone();
+        sniffed_json.content_format = Some("json".to_string());
+        assert!(!should_auto_escalate(&sniffed_json, 0.35, 50));
+    }
+
+    #[test]
+    fn escalation_budget_is_twice_the_static_timeout() {
+        let static_timeout = crate::core::config::scrape_stage_timeout_secs("native_http_scrape");
+        assert_eq!(
+            escalation_time_budget(Duration::from_secs(1)),
+            Some(Duration::from_secs(static_timeout * 2 - 1))
+        );
+        assert_eq!(
+            escalation_time_budget(Duration::from_secs(static_timeout * 2)),
+            None
+        );
+    }
+
+    #[test]
+    fn provenance_lists_every_pass_and_marks_the_kept_one() {
+        let pass = |method, proxy: Option<&str>| {
+            let mut page = mk_response("content");
+            page.provenance.push(provenance::attempt(
+                method,
+                UserAgentClass::Desktop,
+                proxy,
+                Duration::from_millis(10),
+                200,
+                None,
+            ));
+            page
+        };
+        let mut metrics = ScrapeMetricsBuilder::default();
+
+        // Static pass, a failed render, then a render that was no better than static.
+        let mut result = pass(ScrapeMethod::Static, None);
+        metrics.provenance.record(&mut result);
+        metrics.provenance.fail(provenance::failed(
+            ScrapeMethod::Cdp,
+            UserAgentClass::Desktop,
+            Some("http://user:pw@proxy.example:3128"),
+            Duration::from_millis(5),
+            &anyhow!("CDP bypass failed: Captcha"),
+        ));
+        metrics
+            .provenance
+            .discard(&mut pass(ScrapeMethod::Cdp, None));
+        attach_scrape_metrics(&mut result, &metrics, Duration::from_millis(20));
+
+        let trail: Vec<_> = result
+            .provenance
+            .iter()
+            .map(|a| (a.method, a.proxy.as_deref(), a.error.is_some(), a.selected))
+            .collect();
+        assert_eq!(
+            trail,
+            vec![
+                (ScrapeMethod::Static, None, false, true),
+                (ScrapeMethod::Cdp, Some("proxy.example:3128"), true, false),
+                (ScrapeMethod::Cdp, None, false, false),
+            ]
+        );
+
+        // A render that wins takes the selection.
+        let mut rendered = pass(ScrapeMethod::Cdp, None);
+        attach_scrape_metrics(&mut rendered, &metrics, Duration::from_millis(30));
+        let selected: Vec<_> = rendered.provenance.iter().map(|a| a.selected).collect();
+        assert_eq!(selected, vec![false, false, false, true]);
+    }
+
     #[tokio::test]
     async fn test_scrape_url_fallback() {
         let state = Arc::new(AppState::new(reqwest::Client::new()));
@@ -1857,36 +3143,105 @@ Observability tooling such as Prometheus and Grafana helps track key metrics.
     #[test]
     fn test_rewrite_github_blob_url_rewrites_blob_pages() {
         let input = "https://github.com/microsoft/vscode/blob/main/README.md";
-        let result = rewrite_url_for_clean_content(input);
         assert_eq!(
-            result.as_deref(),
-            Some("https://raw.githubusercontent.com/microsoft/vscode/main/README.md")
+            github_raw_candidates(input),
+            ["https://raw.githubusercontent.com/microsoft/vscode/main/README.md"]
         );
     }
 
     #[test]
     fn test_rewrite_github_blob_url_nested_path() {
         let input = "https://github.com/user/repo/blob/feature/my-branch/src/main.rs";
-        let result = rewrite_url_for_clean_content(input);
         assert_eq!(
-            result.as_deref(),
-            Some("https://raw.githubusercontent.com/user/repo/feature/my-branch/src/main.rs")
+            github_raw_candidates(input),
+            ["https://raw.githubusercontent.com/user/repo/feature/my-branch/src/main.rs"]
         );
     }
 
     #[test]
     fn test_rewrite_github_blob_url_ignores_non_blob() {
-        // Repo root now rewrites to raw README instead of returning None
+        // Repo roots try the README on main, then master
         assert_eq!(
-            rewrite_url_for_clean_content("https://github.com/user/repo").as_deref(),
-            Some("https://raw.githubusercontent.com/user/repo/HEAD/README.md")
+            github_raw_candidates("https://github.com/user/repo"),
+            [
+                "https://raw.githubusercontent.com/user/repo/main/README.md",
+                "https://raw.githubusercontent.com/user/repo/master/README.md",
+            ]
+        );
+        assert_eq!(
+            github_raw_candidates("https://github.com/user/repo/").len(),
+            2
         );
         // Sub-paths other than blob and repo-root should NOT be rewritten
-        assert!(rewrite_url_for_clean_content("https://github.com/user/repo/issues/1").is_none());
-        assert!(rewrite_url_for_clean_content("https://docs.python.org/3/").is_none());
-        assert!(rewrite_url_for_clean_content(
-            "https://raw.githubusercontent.com/user/repo/main/f.rs"
-        )
-        .is_none());
+        assert!(github_raw_candidates("https://github.com/user/repo/issues/1").is_empty());
+        assert!(github_raw_candidates("https://github.com/user/repo/blob/main").is_empty());
+        assert!(github_raw_candidates("https://docs.python.org/3/").is_empty());
+        assert!(
+            github_raw_candidates("https://raw.githubusercontent.com/user/repo/main/f.rs")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn cache_key_separates_escalating_and_static_only_scrapes() {
+        let knobs = |auto_escalate| ScrapeCacheKeyKnobs {
+            quality_mode: None,
+            query: None,
+            strict_relevance: false,
+            relevance_threshold: None,
+            extract_app_state: false,
+            extract_relevant_sections: false,
+            section_limit: None,
+            section_threshold: None,
+            parse_feeds: false,
+            persist_session: false,
+            auth: None,
+            headers: None,
+            links_only: false,
+            links_detail: false,
+            screenshot: false,
+            raw_html: false,
+            auto_escalate,
+        };
+        let url = "https://example.com/app";
+        let escalating = compute_scrape_cache_key(url, knobs(true));
+        let static_only = compute_scrape_cache_key(url, knobs(false));
+        assert_ne!(escalating, static_only);
+        assert!(static_only.ends_with("|ae=0"));
+        // Keys of escalating scrapes, the default, are unchanged.
+        assert!(!escalating.contains("|ae="));
+    }
+
+    #[test]
+    fn fragment_routes_are_their_own_pages_and_render_in_the_browser() {
+        let key = default_scrape_cache_key;
+        assert_eq!(
+            key("https://docs.example.com/guide#section-3"),
+            key("https://docs.example.com/guide")
+        );
+        assert_ne!(
+            key("https://app.example.com/#/docs/getting-started"),
+            key("https://app.example.com/")
+        );
+        assert_ne!(
+            key("https://app.example.com/#!/docs"),
+            key("https://app.example.com/#!/pricing")
+        );
+
+        let cdp_first = |url| should_try_cdp_first(url, false, None, false);
+        assert!(cdp_first("https://app.example.com/#/docs/getting-started"));
+        assert!(cdp_first("https://mail.example.com/#!/inbox"));
+        assert!(!cdp_first("https://docs.example.com/guide#section-3"));
+        assert!(!cdp_first("https://example.com/search#q=rust&page=2"));
+    }
+
+    #[test]
+    fn cache_weight_grows_with_result_size() {
+        let small = mk_response("short page");
+        let mut large = mk_response("short page");
+        large.content = "x".repeat(1_000_000);
+        let small_weight = cached_scrape_weight("key", &small);
+        assert!(small_weight > 0);
+        assert!(cached_scrape_weight("key", &large) >= small_weight + 1_000_000);
     }
 }
@@ -0,0 +1,186 @@
+//! `scrape_compare`: run one fetched page through every [`QualityMode`] side by side.
+//!
+//! The page is fetched once (with its raw HTML kept) and the same markup is handed to
+//! [`RustScraper::process_html`] under each mode concurrently, so the comparison isolates
+//! the extraction pipeline from fetch variance. `high` differs from `balanced` only in
+//! how it fetches (browser first), so over shared HTML the two extract alike.
+
+use crate::core::error::ScoutError;
+use crate::rust_scraper::{QualityMode, RustScraper};
+use crate::scrape::{self, ScrapeUrlOptions};
+use crate::types::*;
+use crate::AppState;
+use anyhow::{anyhow, Result};
+use futures::future::join_all;
+use std::sync::Arc;
+
+/// Modes compared, in report order.
+pub const COMPARED_MODES: [QualityMode; 3] = [
+    QualityMode::Balanced,
+    QualityMode::Aggressive,
+    QualityMode::High,
+];
+
+const PREVIEW_CHARS: usize = 500;
+
+#[derive(Debug, Clone, Default)]
+pub struct ScrapeCompareOptions {
+    pub use_proxy: bool,
+}
+
+fn summarize(mode: QualityMode, page: &ScrapeResponse) -> QualityModeSummary {
+    QualityModeSummary {
+        quality_mode: mode.as_str().to_string(),
+        word_count: page.word_count,
+        extraction_score: page.extraction_score,
+        noise_reduction_ratio: page.hydration_status.noise_reduction_ratio,
+        headings: page.headings.len(),
+        preview: page.clean_content.chars().take(PREVIEW_CHARS).collect(),
+    }
+}
+
+/// Extract `html` (served at `url`) under every mode in [`COMPARED_MODES`].
+pub async fn compare_html(html: &str, url: &str) -> Result<Vec<QualityModeSummary>> {
+    let html: Arc<str> = Arc::from(html);
+    let runs = COMPARED_MODES.iter().map(|&mode| {
+        let (html, url) = (Arc::clone(&html), url.to_string());
+        tokio::spawn(async move {
+            let scraper =
+                RustScraper::new_with_quality_mode(Some(mode.as_str())).with_raw_html(false);
+            let page = scraper.process_html(&html, &url).await?;
+            Ok::<_, anyhow::Error>(summarize(mode, &page))
+        })
+    });
+    join_all(runs)
+        .await
+        .into_iter()
+        .map(|joined| joined.map_err(|e| anyhow!("quality mode extraction panicked: {}", e))?)
+        .collect()
+}
+
+/// The mode with the best `extraction_score`; on a tie the earlier mode is kept.
+pub fn recommend(modes: &[QualityModeSummary]) -> Option<&str> {
+    let score = |m: &QualityModeSummary| m.extraction_score.unwrap_or(0.0);
+    modes
+        .iter()
+        .reduce(|best, m| if score(m) > score(best) { m } else { best })
+        .map(|m| m.quality_mode.as_str())
+}
+
+/// Fetch `url` once and compare its extraction across quality modes. Block pages fail
+/// with `blocked`; responses that are not HTML (PDFs, feeds) fail with `invalid_input`.
+pub async fn scrape_compare(
+    state: &Arc<AppState>,
+    url: &str,
+    options: ScrapeCompareOptions,
+) -> Result<ScrapeCompareResponse> {
+    let page = scrape::scrape_url_full(
+        state,
+        url,
+        ScrapeUrlOptions {
+            use_proxy: options.use_proxy,
+            include_raw_html: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+    if let Some(err) = scrape::block_error(&page) {
+        return Err(err.into());
+    }
+    if !page.content_type.to_ascii_lowercase().contains("html") || page.content.is_empty() {
+        return Err(ScoutError::invalid_input(format!(
+            "{} is not an HTML page ({}); quality modes only apply to HTML extraction",
+            page.url, page.content_type
+        ))
+        .into());
+    }
+
+    let base_url = page.final_url.as_deref().unwrap_or(&page.url);
+    let modes = compare_html(&page.content, base_url).await?;
+    let recommended_mode = recommend(&modes)
+        .unwrap_or(QualityMode::Balanced.as_str())
+        .to_string();
+    let mut warnings = Vec::new();
+    if page.auth_wall_reason.is_some() {
+        warnings.push("auth_wall_detected".to_string());
+    }
+
+    Ok(ScrapeCompareResponse {
+        url: page.url.clone(),
+        final_url: page.final_url.clone(),
+        modes,
+        recommended_mode,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r#"<html><head><title>Borrowing in Rust</title></head><body>
+        <nav><a href="/">Home</a> | <a href="/blog">Blog</a> | <a href="/about">About</a></nav>
+        <article>
+          <h1>Borrowing in Rust</h1>
+          <p>References let a function use a value without taking ownership of it. The borrow
+          checker makes sure every reference stays valid for as long as it is used, which rules
+          out dangling pointers at compile time.</p>
+          <h2>Mutable references</h2>
+          <p>Only one mutable reference to a value may exist at a time. This restriction is what
+          prevents data races: two threads can never write to the same memory without
+          synchronisation, and the compiler enforces it without any runtime cost.</p>
+          <h2>Lifetimes</h2>
+          <p>Lifetimes name the scope a reference is valid for. Most of the time they are
+          inferred, and explicit annotations are only needed when the compiler cannot work out
+          how the lifetimes of several references relate to each other.</p>
+        </article>
+        <footer>Share | Tweet | Subscribe | Cookie settings</footer>
+    </body></html>"#;
+
+    #[tokio::test]
+    async fn compares_every_mode_over_the_same_html() {
+        let modes = compare_html(ARTICLE, "https://blog.example.com/borrowing")
+            .await
+            .unwrap();
+        let names: Vec<_> = modes.iter().map(|m| m.quality_mode.as_str()).collect();
+        assert_eq!(names, ["balanced", "aggressive", "high"]);
+        for mode in &modes {
+            assert!(mode.word_count > 50, "{mode:?}");
+            assert!(mode.headings >= 3, "{mode:?}");
+            assert!(mode.preview.chars().count() <= PREVIEW_CHARS);
+            assert!(
+                mode.preview.starts_with("References let a function"),
+                "{mode:?}"
+            );
+        }
+        // `high` only changes the fetch strategy.
+        assert_eq!(modes[0].word_count, modes[2].word_count);
+        assert_eq!(modes[0].preview, modes[2].preview);
+        assert!(modes[1].word_count <= modes[0].word_count);
+    }
+
+    #[test]
+    fn recommendation_prefers_the_best_score_then_the_earlier_mode() {
+        let mode = |name: &str, score: Option<f64>| QualityModeSummary {
+            quality_mode: name.to_string(),
+            word_count: 100,
+            extraction_score: score,
+            noise_reduction_ratio: 0.0,
+            headings: 0,
+            preview: String::new(),
+        };
+        let tied = [
+            mode("balanced", Some(0.8)),
+            mode("aggressive", Some(0.8)),
+            mode("high", None),
+        ];
+        assert_eq!(recommend(&tied), Some("balanced"));
+        let aggressive_wins = [
+            mode("balanced", Some(0.6)),
+            mode("aggressive", Some(0.9)),
+            mode("high", Some(0.6)),
+        ];
+        assert_eq!(recommend(&aggressive_wins), Some("aggressive"));
+        assert_eq!(recommend(&[]), None);
+    }
+}
@@ -0,0 +1,409 @@
+//! `scrape_diff`: re-scrape a URL and report what changed since the last known version.
+//!
+//! The previous version is the newest of three sources: the on-disk snapshot written by
+//! the last diff, the in-process scrape cache, and the scrape logged to semantic memory.
+//! Every successful diff writes the fresh scrape back as the snapshot, so repeated calls
+//! report changes since the previous call.
+//!
+//! Text is compared line by line after collapsing runs of whitespace and dropping blank
+//! lines, so re-wrapping and spacing churn from the markdown normalizer is not a change.
+
+use crate::core::error::ScoutError;
+use crate::history::EntryType;
+use crate::scrape::{self, ScrapeUrlOptions};
+use crate::types::*;
+use crate::AppState;
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+pub const DEFAULT_MAX_DIFF_CHARS: usize = 8_000;
+const DIFF_CONTEXT_LINES: usize = 2;
+
+#[derive(Debug, Clone, Default)]
+pub struct ScrapeDiffOptions {
+    pub use_proxy: bool,
+    /// Cap on `text_diff`; `None` uses [`DEFAULT_MAX_DIFF_CHARS`].
+    pub max_diff_chars: Option<usize>,
+    /// Snapshot directory; `None` uses [`default_snapshot_dir`].
+    pub snapshot_dir: Option<PathBuf>,
+}
+
+/// `CORTEX_SCOUT_SNAPSHOT_DIR`, else `~/.cortex-scout/snapshots`.
+pub fn default_snapshot_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("CORTEX_SCOUT_SNAPSHOT_DIR") {
+        let dir = dir.trim();
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    dirs::home_dir().map(|home| home.join(".cortex-scout").join("snapshots"))
+}
+
+/// URL identity for diffing: tracking params, fragment and a trailing slash are ignored.
+fn diff_url_key(url: &str) -> String {
+    let key = crate::search::normalize_url_key(url);
+    // Dropping every tracking param can leave a bare `?` behind.
+    let key = key.strip_suffix('?').unwrap_or(&key);
+    key.strip_suffix('/').unwrap_or(key).to_string()
+}
+
+fn snapshot_path(dir: &Path, url: &str) -> PathBuf {
+    let digest = hex::encode(Sha256::digest(diff_url_key(url).as_bytes()));
+    dir.join(format!("{}.json", &digest[..32]))
+}
+
+pub async fn load_snapshot(dir: &Path, url: &str) -> Option<ScrapeResponse> {
+    let raw = tokio::fs::read(snapshot_path(dir, url)).await.ok()?;
+    serde_json::from_slice(&raw).ok()
+}
+
+pub async fn save_snapshot(dir: &Path, url: &str, page: &ScrapeResponse) -> Result<PathBuf> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("creating {}", dir.display()))?;
+    let path = snapshot_path(dir, url);
+    let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+    tokio::fs::write(&tmp, serde_json::to_vec(page)?).await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    Ok(path)
+}
+
+async fn memory_previous(state: &Arc<AppState>, url: &str) -> Option<ScrapeResponse> {
+    let memory = state.get_memory()?;
+    let wanted = diff_url_key(url);
+    let mut hits = memory
+        .search_history(url, 5, 0.9, Some(EntryType::Scrape))
+        .await
+        .ok()?;
+    hits.sort_by_key(|(entry, _)| std::cmp::Reverse(entry.timestamp));
+    // Entries chunked for size no longer deserialize and are skipped.
+    hits.into_iter()
+        .filter(|(entry, _)| diff_url_key(&entry.query) == wanted)
+        .find_map(|(entry, _)| serde_json::from_value(entry.full_result).ok())
+}
+
+fn parse_timestamp(page: &ScrapeResponse) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(&page.timestamp).ok()
+}
+
+async fn previous_version(
+    state: &Arc<AppState>,
+    url: &str,
+    snapshot_dir: Option<&Path>,
+) -> Option<(&'static str, ScrapeResponse)> {
+    let mut candidates = Vec::new();
+    if let Some(dir) = snapshot_dir {
+        if let Some(page) = load_snapshot(dir, url).await {
+            candidates.push(("snapshot", page));
+        }
+    }
+    let cache_key = scrape::default_scrape_cache_key(url);
+    if let Some(page) = state.scrape_cache.get(&cache_key).await {
+        candidates.push(("cache", page));
+    }
+    if let Some(page) = memory_previous(state, url).await {
+        candidates.push(("memory", page));
+    }
+    // Unparseable timestamps sort oldest; ties keep the earlier (more durable) source.
+    candidates
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, page)| parse_timestamp(page))
+}
+
+/// One line per non-blank input line with inner whitespace collapsed to single spaces.
+pub fn normalize_for_diff(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines() {
+        let mut words = line.split_whitespace().peekable();
+        if words.peek().is_none() {
+            continue;
+        }
+        for (i, word) in words.enumerate() {
+            if i > 0 {
+                out.push(' ');
+            }
+            out.push_str(word);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn heading_key(heading: &Heading) -> (String, String) {
+    (
+        heading.level.to_ascii_lowercase(),
+        heading
+            .text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Headings of `from` missing in `other`, in page order, each reported once.
+fn headings_missing(from: &[Heading], other: &[Heading]) -> Vec<Heading> {
+    let present: HashSet<_> = other.iter().map(heading_key).collect();
+    let mut seen = HashSet::new();
+    from.iter()
+        .filter(|h| {
+            let key = heading_key(h);
+            !present.contains(&key) && seen.insert(key)
+        })
+        .cloned()
+        .collect()
+}
+
+fn field_change(before: &Option<String>, after: &Option<String>) -> Option<FieldChange> {
+    let norm = |v: &Option<String>| {
+        v.as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let (before, after) = (norm(before), norm(after));
+    (before != after).then_some(FieldChange { before, after })
+}
+
+fn truncate_chars(text: &mut String, max_chars: usize) -> bool {
+    match text.char_indices().nth(max_chars) {
+        Some((byte_idx, _)) => {
+            text.truncate(byte_idx);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Compare two scrapes of the same page. `previous_source` is left unset.
+pub fn diff_scrapes(
+    previous: &ScrapeResponse,
+    current: &ScrapeResponse,
+    max_diff_chars: usize,
+) -> ScrapeDiffResponse {
+    let before = normalize_for_diff(&previous.clean_content);
+    let after = normalize_for_diff(&current.clean_content);
+    let diff = TextDiff::from_lines(&before, &after);
+
+    let (mut lines_added, mut lines_removed) = (0, 0);
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => lines_added += 1,
+            ChangeTag::Delete => lines_removed += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+
+    let (text_diff, diff_truncated) = if lines_added + lines_removed == 0 {
+        (None, false)
+    } else {
+        let mut unified = diff
+            .unified_diff()
+            .context_radius(DIFF_CONTEXT_LINES)
+            .header("previous", "current")
+            .to_string();
+        let truncated = truncate_chars(&mut unified, max_diff_chars);
+        (Some(unified), truncated)
+    };
+
+    let headings_added = headings_missing(&current.headings, &previous.headings);
+    let headings_removed = headings_missing(&previous.headings, &current.headings);
+    let published_at_change = field_change(&previous.published_at, &current.published_at);
+    let canonical_url_change = field_change(&previous.canonical_url, &current.canonical_url);
+
+    let changed = text_diff.is_some()
+        || !headings_added.is_empty()
+        || !headings_removed.is_empty()
+        || published_at_change.is_some()
+        || canonical_url_change.is_some();
+
+    ScrapeDiffResponse {
+        url: current.url.clone(),
+        status: if changed { "changed" } else { "unchanged" }.to_string(),
+        previous_source: None,
+        previous_timestamp: Some(previous.timestamp.clone()),
+        current_timestamp: current.timestamp.clone(),
+        word_count_before: Some(previous.word_count),
+        word_count_after: current.word_count,
+        word_count_delta: current.word_count as i64 - previous.word_count as i64,
+        headings_added,
+        headings_removed,
+        published_at_change,
+        canonical_url_change,
+        lines_added,
+        lines_removed,
+        text_diff,
+        diff_truncated,
+        warnings: Vec::new(),
+    }
+}
+
+/// Fetch `url` fresh (bypassing the scrape cache), diff it against the previous
+/// version and store it as the new snapshot. Without a previous version the result has
+/// status `baseline_created`. Block pages and auth walls fail with `blocked` and leave
+/// the stored snapshot untouched.
+pub async fn scrape_diff(
+    state: &Arc<AppState>,
+    url: &str,
+    options: ScrapeDiffOptions,
+) -> Result<ScrapeDiffResponse> {
+    let snapshot_dir = options.snapshot_dir.or_else(default_snapshot_dir);
+    let previous = previous_version(state, url, snapshot_dir.as_deref()).await;
+
+    let current = scrape::scrape_url_full(
+        state,
+        url,
+        ScrapeUrlOptions {
+            use_proxy: options.use_proxy,
+            bypass_cache: true,
+            ..Default::default()
+        },
+    )
+    .await?;
+    if let Some(err) = scrape::block_error(&current) {
+        return Err(err.into());
+    }
+    if let Some(reason) = current.auth_wall_reason.as_deref() {
+        return Err(ScoutError::blocked(format!(
+            "{} is behind an auth wall: {}",
+            current.url, reason
+        ))
+        .into());
+    }
+
+    let mut response = match previous {
+        Some((source, previous)) => {
+            let mut diff = diff_scrapes(
+                &previous,
+                &current,
+                options.max_diff_chars.unwrap_or(DEFAULT_MAX_DIFF_CHARS),
+            );
+            diff.previous_source = Some(source.to_string());
+            diff
+        }
+        None => ScrapeDiffResponse {
+            url: current.url.clone(),
+            status: "baseline_created".to_string(),
+            previous_source: None,
+            previous_timestamp: None,
+            current_timestamp: current.timestamp.clone(),
+            word_count_before: None,
+            word_count_after: current.word_count,
+            word_count_delta: 0,
+            headings_added: Vec::new(),
+            headings_removed: Vec::new(),
+            published_at_change: None,
+            canonical_url_change: None,
+            lines_added: 0,
+            lines_removed: 0,
+            text_diff: None,
+            diff_truncated: false,
+            warnings: Vec::new(),
+        },
+    };
+
+    match snapshot_dir {
+        Some(dir) => match save_snapshot(&dir, url, &current).await {
+            Ok(path) => info!("scrape_diff: stored snapshot {}", path.display()),
+            Err(e) => {
+                warn!("scrape_diff: failed to store snapshot for {}: {}", url, e);
+                response
+                    .warnings
+                    .push(format!("snapshot_store_failed: {}", e));
+            }
+        },
+        None => response
+            .warnings
+            .push("snapshot_store_unavailable".to_string()),
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(clean_content: &str, headings: &[(&str, &str)]) -> ScrapeResponse {
+        ScrapeResponse {
+            headings: headings
+                .iter()
+                .map(|(level, text)| Heading {
+                    level: level.to_string(),
+                    text: text.to_string(),
+                })
+                .collect(),
+            ..ScrapeResponse::for_test("https://example.com/pricing", "Pricing", clean_content)
+        }
+    }
+
+    #[test]
+    fn whitespace_churn_is_not_a_change() {
+        let before = page("# Pricing\n\nPro   plan: $10\n", &[("h1", "Pricing")]);
+        let after = page("# Pricing\nPro plan: $10   \n\n\n", &[("H1", " Pricing ")]);
+        let diff = diff_scrapes(&before, &after, DEFAULT_MAX_DIFF_CHARS);
+        assert_eq!(diff.status, "unchanged");
+        assert_eq!(diff.text_diff, None);
+        assert_eq!((diff.lines_added, diff.lines_removed), (0, 0));
+    }
+
+    #[test]
+    fn reports_text_heading_and_field_changes() {
+        let mut before = page(
+            "# Pricing\nPro plan: $10\n## Legacy\nOld tier",
+            &[("h1", "Pricing"), ("h2", "Legacy")],
+        );
+        before.canonical_url = Some("https://example.com/pricing".to_string());
+        let mut after = page(
+            "# Pricing\nPro plan: $12\n## Enterprise\nContact sales",
+            &[("h1", "Pricing"), ("h2", "Enterprise")],
+        );
+        after.canonical_url = Some("https://example.com/plans".to_string());
+
+        let diff = diff_scrapes(&before, &after, DEFAULT_MAX_DIFF_CHARS);
+        assert_eq!(diff.status, "changed");
+        assert_eq!((diff.lines_added, diff.lines_removed), (3, 3));
+        let text = diff.text_diff.unwrap();
+        assert!(text.contains("-Pro plan: $10\n"));
+        assert!(text.contains("+Pro plan: $12\n"));
+        assert_eq!(diff.headings_added[0].text, "Enterprise");
+        assert_eq!(diff.headings_removed[0].text, "Legacy");
+        assert_eq!(
+            diff.canonical_url_change.unwrap().after.as_deref(),
+            Some("https://example.com/plans")
+        );
+        assert_eq!(diff.published_at_change, None);
+        assert_eq!(diff.word_count_delta, 0);
+
+        let capped = diff_scrapes(&before, &after, 20);
+        assert!(capped.diff_truncated);
+        assert_eq!(capped.text_diff.unwrap().chars().count(), 20);
+    }
+
+    #[tokio::test]
+    async fn snapshots_round_trip_under_normalized_url() {
+        let dir = std::env::temp_dir().join(format!("cortex-scout-snap-{}", uuid::Uuid::new_v4()));
+        let stored = page("hello", &[]);
+        save_snapshot(
+            &dir,
+            "https://example.com/pricing/?utm_source=x#top",
+            &stored,
+        )
+        .await
+        .unwrap();
+        let loaded = load_snapshot(&dir, "https://example.com/pricing")
+            .await
+            .unwrap();
+        assert_eq!(loaded.clean_content, "hello");
+        assert!(load_snapshot(&dir, "https://example.com/other")
+            .await
+            .is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
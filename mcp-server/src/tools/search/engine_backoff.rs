@@ -0,0 +1,255 @@
+//! Per-engine rate-limit cool-downs that outlive a single search.
+//!
+//! When a SERP comes back as HTTP 429 or a captcha / unusual-traffic page, the engine is
+//! put in a cool-down. The length is the response's `Retry-After` when it had one.
+//! Otherwise it is 60s, doubling with each consecutive block, capped at an hour.
+//! `run_engine` skips a cooling engine without sending a request, so the next query does
+//! not extend the ban. A success halves the block streak instead of clearing it, so an
+//! engine that was blocked repeatedly starts from a longer cool-down if it blocks again.
+//! With `CORTEX_SCOUT_ENGINE_BACKOFF_PERSIST=1` the state is also written to disk and
+//! reloaded on start (see [`crate::core::config::engine_backoff_file`]).
+//!
+//! This is separate from the short in-process cooldowns in
+//! [`super::InternalSearchService`] and the block-rate bench in [`super::engine_stats`].
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+pub const BASE_COOLDOWN: Duration = Duration::from_secs(60);
+pub const MAX_COOLDOWN: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct BackoffEntry {
+    /// Consecutive rate-limit blocks; halved on each success.
+    streak: u32,
+    /// Unix seconds at which the cool-down ends.
+    until: u64,
+    reason: String,
+}
+
+#[derive(Debug)]
+pub struct EngineBackoff {
+    path: Option<PathBuf>,
+    entries: Mutex<BTreeMap<String, BackoffEntry>>,
+}
+
+impl Default for EngineBackoff {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl EngineBackoff {
+    /// Backoff state kept in memory, and in `path` when given (loaded from it if present).
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_ref()
+            .and_then(|p| std::fs::read(p).ok())
+            .and_then(|raw| match serde_json::from_slice(&raw) {
+                Ok(entries) => Some(entries),
+                Err(e) => {
+                    warn!("ignoring unreadable engine backoff state: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(crate::core::config::engine_backoff_file())
+    }
+
+    /// Whether a block `reason` is a rate limit that warrants a cool-down. Includes
+    /// `simulated_block`, so `SEARCH_SIMULATE_BLOCK` drives the same path.
+    pub fn is_rate_limit(reason: &str) -> bool {
+        ["http_429", "captcha", "unusual_traffic", "simulated_block"]
+            .iter()
+            .any(|needle| reason.contains(needle))
+    }
+
+    /// Cool-down length for the `streak`-th consecutive block.
+    fn cooldown_for(streak: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| {
+                BASE_COOLDOWN.saturating_mul(2u32.saturating_pow(streak.saturating_sub(1).min(6)))
+            })
+            .min(MAX_COOLDOWN)
+    }
+
+    /// Start a cool-down after a blocked request. Returns its length, or `None` when
+    /// `reason` is not a rate limit.
+    pub fn record_block(
+        &self,
+        engine: &str,
+        reason: &str,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        self.record_block_at(engine, reason, retry_after, now_secs())
+    }
+
+    fn record_block_at(
+        &self,
+        engine: &str,
+        reason: &str,
+        retry_after: Option<Duration>,
+        now: u64,
+    ) -> Option<Duration> {
+        if !Self::is_rate_limit(reason) {
+            return None;
+        }
+        let mut entries = self.entries.lock().expect("engine backoff mutex poisoned");
+        let entry = entries.entry(engine.to_string()).or_default();
+        entry.streak = entry.streak.saturating_add(1);
+        let cooldown = Self::cooldown_for(entry.streak, retry_after);
+        entry.until = entry.until.max(now + cooldown.as_secs());
+        entry.reason = reason.to_string();
+        warn!(
+            "engine '{}' cooling down for {}s ({}, streak {})",
+            engine,
+            cooldown.as_secs(),
+            reason,
+            entry.streak
+        );
+        self.save(&entries);
+        Some(cooldown)
+    }
+
+    /// Decay the backoff after a successful request: the streak is halved and any
+    /// remaining cool-down is dropped.
+    pub fn record_success(&self, engine: &str) {
+        let mut entries = self.entries.lock().expect("engine backoff mutex poisoned");
+        let Some(entry) = entries.get_mut(engine) else {
+            return;
+        };
+        entry.streak /= 2;
+        entry.until = 0;
+        if entry.streak == 0 {
+            entries.remove(engine);
+            info!("engine '{}' backoff cleared", engine);
+        }
+        self.save(&entries);
+    }
+
+    /// Time left on `engine`'s cool-down, if it is cooling down.
+    pub fn remaining(&self, engine: &str) -> Option<Duration> {
+        self.remaining_at(engine, now_secs())
+    }
+
+    fn remaining_at(&self, engine: &str, now: u64) -> Option<Duration> {
+        let entries = self.entries.lock().expect("engine backoff mutex poisoned");
+        let until = entries.get(engine)?.until;
+        (until > now).then(|| Duration::from_secs(until - now))
+    }
+
+    /// Write the state to disk; a no-op unless persistence is enabled. Failures are
+    /// logged rather than surfaced, since the in-memory state still applies.
+    fn save(&self, entries: &BTreeMap<String, BackoffEntry>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let write = || -> std::io::Result<()> {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let tmp = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+            std::fs::write(&tmp, serde_json::to_vec_pretty(entries)?)?;
+            std::fs::rename(&tmp, path)
+        };
+        if let Err(e) = write() {
+            warn!(
+                "failed to save engine backoff state to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limits_back_off_exponentially_and_successes_decay() {
+        let backoff = EngineBackoff::default();
+        let now = 1_000_000;
+
+        assert_eq!(
+            backoff.record_block_at("google", "http_403", None, now),
+            None
+        );
+        assert_eq!(backoff.remaining_at("google", now), None);
+
+        let lengths: Vec<u64> = (0..8)
+            .map(|_| {
+                backoff
+                    .record_block_at("google", "http_429", None, now)
+                    .unwrap()
+                    .as_secs()
+            })
+            .collect();
+        assert_eq!(lengths, [60, 120, 240, 480, 960, 1920, 3600, 3600]);
+        assert_eq!(
+            backoff.remaining_at("google", now + 100),
+            Some(Duration::from_secs(3500))
+        );
+        assert_eq!(backoff.remaining_at("bing", now), None);
+
+        // The streak of 8 halves to 4, so the next block is treated as the fifth in a row.
+        backoff.record_success("google");
+        assert_eq!(backoff.remaining_at("google", now), None);
+        assert_eq!(
+            backoff.record_block_at("google", "captcha", None, now),
+            Some(Duration::from_secs(960))
+        );
+
+        // Retry-After wins over the exponential schedule, still capped at an hour.
+        assert_eq!(
+            backoff.record_block_at("bing", "http_429", Some(Duration::from_secs(90)), now),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            backoff.record_block_at("brave", "http_429", Some(Duration::from_secs(86_400)), now),
+            Some(MAX_COOLDOWN)
+        );
+    }
+
+    #[test]
+    fn state_survives_a_restart_when_persisted() {
+        let path = std::env::temp_dir()
+            .join(format!("cortex-scout-backoff-{}", uuid::Uuid::new_v4()))
+            .join("engine_backoff.json");
+
+        let backoff = EngineBackoff::new(Some(path.clone()));
+        backoff.record_block(
+            "google",
+            "http_429; cdp:captcha",
+            Some(Duration::from_secs(1800)),
+        );
+
+        let reloaded = EngineBackoff::new(Some(path.clone()));
+        let remaining = reloaded.remaining("google").unwrap();
+        assert!(remaining > Duration::from_secs(1790) && remaining <= Duration::from_secs(1800));
+
+        reloaded.record_success("google");
+        assert!(EngineBackoff::new(Some(path.clone()))
+            .remaining("google")
+            .is_none());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
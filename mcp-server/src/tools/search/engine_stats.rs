@@ -0,0 +1,364 @@
+//! Rolling per-engine request statistics and automatic benching.
+//!
+//! Every SERP request made by `run_engine` is recorded here. When more than
+//! `CORTEX_SCOUT_ENGINE_BENCH_THRESHOLD` (default 0.6) of an engine's last
+//! `CORTEX_SCOUT_ENGINE_BENCH_WINDOW` (default 10) requests were blocked, the engine is
+//! benched for `CORTEX_SCOUT_ENGINE_BENCH_SECS` (default 300) and engine selection skips
+//! it rather than spending the per-query timeout on a SERP that will block again.
+//! This sits on top of the short per-failure cooldowns in [`super::InternalSearchService`],
+//! which react to streaks rather than to a sustained block rate.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+pub const DEFAULT_BENCH_WINDOW: usize = 10;
+pub const DEFAULT_BENCH_THRESHOLD: f64 = 0.6;
+pub const DEFAULT_BENCH_SECS: u64 = 300;
+
+/// When an engine is benched and for how long.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct BenchPolicy {
+    /// Most recent requests the block rate is computed over.
+    pub window: usize,
+    /// Block rate (0.0–1.0) above which the engine is benched.
+    pub threshold: f64,
+    pub bench_secs: u64,
+}
+
+impl Default for BenchPolicy {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_BENCH_WINDOW,
+            threshold: DEFAULT_BENCH_THRESHOLD,
+            bench_secs: DEFAULT_BENCH_SECS,
+        }
+    }
+}
+
+impl BenchPolicy {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            window: std::env::var("CORTEX_SCOUT_ENGINE_BENCH_WINDOW")
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.window),
+            threshold: std::env::var("CORTEX_SCOUT_ENGINE_BENCH_THRESHOLD")
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|t| (0.0..=1.0).contains(t))
+                .unwrap_or(defaults.threshold),
+            bench_secs: std::env::var("CORTEX_SCOUT_ENGINE_BENCH_SECS")
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .unwrap_or(defaults.bench_secs),
+        }
+    }
+}
+
+/// How a single engine request ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineOutcome {
+    Success,
+    /// The SERP was a block page or rate-limit response; `reason` as detected.
+    Blocked {
+        reason: String,
+    },
+    Timeout,
+    Failed,
+}
+
+#[derive(Debug, Default)]
+struct EngineRecord {
+    requests: u64,
+    successes: u64,
+    timeouts: u64,
+    failures: u64,
+    blocks: BTreeMap<String, u64>,
+    total_latency: Duration,
+    last_success_timestamp: Option<u64>,
+    /// Whether each of the last `window` requests was blocked, oldest first.
+    recent: VecDeque<bool>,
+    benched_until: Option<Instant>,
+    bench_reason: Option<String>,
+}
+
+impl EngineRecord {
+    fn recent_block_rate(&self) -> Option<f64> {
+        if self.recent.is_empty() {
+            return None;
+        }
+        let blocked = self.recent.iter().filter(|b| **b).count();
+        Some(blocked as f64 / self.recent.len() as f64)
+    }
+
+    fn bench_remaining(&self, now: Instant) -> Option<Duration> {
+        self.benched_until
+            .filter(|until| *until > now)
+            .map(|until| until.duration_since(now))
+    }
+}
+
+/// Health of one engine; an entry of [`EngineStatusReport`].
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineStatus {
+    pub engine: String,
+    pub requests: u64,
+    pub successes: u64,
+    pub timeouts: u64,
+    pub failures: u64,
+    /// Blocked requests by detected reason (e.g. `http_429`, `captcha`).
+    pub blocks: BTreeMap<String, u64>,
+    pub avg_latency_ms: Option<u64>,
+    pub last_success_timestamp: Option<u64>,
+    /// Share of blocked requests in the current window; `None` before the first request.
+    pub recent_block_rate: Option<f64>,
+    pub recent_requests: usize,
+    pub benched: bool,
+    pub bench_remaining_secs: Option<u64>,
+    pub bench_reason: Option<String>,
+}
+
+/// Payload of `GET /engines/status` and the `engine_status` MCP tool.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineStatusReport {
+    pub bench_policy: BenchPolicy,
+    pub benched_engines: Vec<String>,
+    pub engines: Vec<EngineStatus>,
+}
+
+#[derive(Debug)]
+pub struct EngineStats {
+    policy: BenchPolicy,
+    engines: Mutex<BTreeMap<String, EngineRecord>>,
+}
+
+impl Default for EngineStats {
+    fn default() -> Self {
+        Self::new(BenchPolicy::default())
+    }
+}
+
+impl EngineStats {
+    pub fn new(policy: BenchPolicy) -> Self {
+        Self {
+            policy,
+            engines: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(BenchPolicy::from_env())
+    }
+
+    /// Record one request. Returns the bench duration when this request benched the engine.
+    pub fn record(
+        &self,
+        engine: &str,
+        outcome: &EngineOutcome,
+        elapsed: Duration,
+    ) -> Option<Duration> {
+        let mut engines = self.engines.lock().expect("engine stats mutex poisoned");
+        let entry = engines.entry(engine.to_string()).or_default();
+        entry.requests += 1;
+        entry.total_latency += elapsed;
+        match outcome {
+            EngineOutcome::Success => {
+                entry.successes += 1;
+                entry.last_success_timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .ok()
+                    .map(|d| d.as_secs());
+            }
+            EngineOutcome::Blocked { reason } => {
+                *entry.blocks.entry(reason.clone()).or_default() += 1;
+            }
+            EngineOutcome::Timeout => entry.timeouts += 1,
+            EngineOutcome::Failed => entry.failures += 1,
+        }
+
+        entry
+            .recent
+            .push_back(matches!(outcome, EngineOutcome::Blocked { .. }));
+        while entry.recent.len() > self.policy.window {
+            entry.recent.pop_front();
+        }
+
+        let now = Instant::now();
+        if self.policy.bench_secs == 0
+            || entry.bench_remaining(now).is_some()
+            || entry.recent.len() < self.policy.window
+        {
+            return None;
+        }
+        let rate = entry.recent_block_rate().unwrap_or(0.0);
+        if rate <= self.policy.threshold {
+            return None;
+        }
+
+        let duration = Duration::from_secs(self.policy.bench_secs);
+        let reason = format!(
+            "block_rate:{:.0}% of last {}",
+            rate * 100.0,
+            entry.recent.len()
+        );
+        warn!(
+            "engine '{}' benched for {}s ({})",
+            engine,
+            duration.as_secs(),
+            reason
+        );
+        entry.benched_until = Some(now + duration);
+        entry.bench_reason = Some(reason);
+        // Start the next window fresh so the engine is judged on post-bench traffic.
+        entry.recent.clear();
+        Some(duration)
+    }
+
+    /// Time left on `engine`'s bench and why it was benched, if it is benched.
+    pub fn bench_remaining(&self, engine: &str) -> Option<(Duration, String)> {
+        let engines = self.engines.lock().expect("engine stats mutex poisoned");
+        let entry = engines.get(engine)?;
+        let remaining = entry.bench_remaining(Instant::now())?;
+        Some((
+            remaining,
+            entry
+                .bench_reason
+                .clone()
+                .unwrap_or_else(|| "block_rate".to_string()),
+        ))
+    }
+
+    /// Lift the bench on `engine` (or every engine) and clear its block-rate window.
+    /// Returns the engines that were benched.
+    pub fn reset(&self, engine: Option<&str>) -> Vec<String> {
+        let now = Instant::now();
+        let mut engines = self.engines.lock().expect("engine stats mutex poisoned");
+        let mut released = Vec::new();
+        for (name, entry) in engines.iter_mut() {
+            if engine.is_some_and(|target| target != name) {
+                continue;
+            }
+            if entry.bench_remaining(now).is_some() {
+                released.push(name.clone());
+            }
+            entry.benched_until = None;
+            entry.bench_reason = None;
+            entry.recent.clear();
+        }
+        if !released.is_empty() {
+            info!("engine bench lifted: {}", released.join(", "));
+        }
+        released
+    }
+
+    /// Statistics for every engine in `known` plus any other engine seen so far.
+    pub fn report(&self, known: &[&str]) -> EngineStatusReport {
+        let now = Instant::now();
+        let engines = self.engines.lock().expect("engine stats mutex poisoned");
+        let mut names: Vec<String> = known.iter().map(|name| name.to_string()).collect();
+        names.extend(
+            engines
+                .keys()
+                .filter(|name| !known.contains(&name.as_str()))
+                .cloned(),
+        );
+
+        let empty = EngineRecord::default();
+        let statuses: Vec<EngineStatus> = names
+            .into_iter()
+            .map(|engine| {
+                let entry = engines.get(&engine).unwrap_or(&empty);
+                let remaining = entry.bench_remaining(now);
+                EngineStatus {
+                    requests: entry.requests,
+                    successes: entry.successes,
+                    timeouts: entry.timeouts,
+                    failures: entry.failures,
+                    blocks: entry.blocks.clone(),
+                    avg_latency_ms: (entry.requests > 0).then(|| {
+                        (entry.total_latency.as_millis() / u128::from(entry.requests)) as u64
+                    }),
+                    last_success_timestamp: entry.last_success_timestamp,
+                    recent_block_rate: entry.recent_block_rate(),
+                    recent_requests: entry.recent.len(),
+                    benched: remaining.is_some(),
+                    bench_remaining_secs: remaining.map(|d| d.as_secs().max(1)),
+                    bench_reason: remaining.and(entry.bench_reason.clone()),
+                    engine,
+                }
+            })
+            .collect();
+
+        EngineStatusReport {
+            bench_policy: self.policy,
+            benched_engines: statuses
+                .iter()
+                .filter(|s| s.benched)
+                .map(|s| s.engine.clone())
+                .collect(),
+            engines: statuses,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocked() -> EngineOutcome {
+        EngineOutcome::Blocked {
+            reason: "http_429".to_string(),
+        }
+    }
+
+    #[test]
+    fn benches_after_block_rate_exceeds_threshold_and_reset_lifts_it() {
+        let stats = EngineStats::new(BenchPolicy {
+            window: 4,
+            threshold: 0.5,
+            bench_secs: 60,
+        });
+        let ms = Duration::from_millis(100);
+
+        assert!(stats
+            .record("google", &EngineOutcome::Success, ms)
+            .is_none());
+        assert!(stats.record("google", &blocked(), ms).is_none());
+        assert!(stats.record("google", &blocked(), ms).is_none());
+        // 2 of 4 blocked is not above the threshold.
+        assert!(stats
+            .record("google", &EngineOutcome::Timeout, ms)
+            .is_none());
+        assert!(stats.bench_remaining("google").is_none());
+
+        // Window slides to [blocked, blocked, timeout, blocked]: 75%.
+        assert_eq!(
+            stats.record("google", &blocked(), ms),
+            Some(Duration::from_secs(60))
+        );
+        let (remaining, reason) = stats.bench_remaining("google").unwrap();
+        assert!(remaining <= Duration::from_secs(60));
+        assert_eq!(reason, "block_rate:75% of last 4");
+
+        let report = stats.report(&["google", "bing"]);
+        assert_eq!(report.benched_engines, vec!["google".to_string()]);
+        let google = &report.engines[0];
+        assert_eq!(google.requests, 5);
+        assert_eq!(google.blocks.get("http_429"), Some(&3));
+        assert_eq!(google.avg_latency_ms, Some(100));
+        assert!(google.last_success_timestamp.is_some());
+        assert_eq!(report.engines[1].engine, "bing");
+        assert_eq!(report.engines[1].requests, 0);
+
+        assert_eq!(stats.reset(Some("bing")), Vec::<String>::new());
+        assert!(stats.bench_remaining("google").is_some());
+        assert_eq!(stats.reset(None), vec!["google".to_string()]);
+        assert!(stats.bench_remaining("google").is_none());
+        assert!(stats.report(&[]).benched_engines.is_empty());
+    }
+}
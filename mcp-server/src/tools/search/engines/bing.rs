@@ -1,8 +1,65 @@
+use crate::tools::search::{Operator, TimeRange};
 use crate::types::SearchResult;
 use base64::Engine as _;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
 
-use super::{fetch_serp_html, EngineError};
+use super::{clamp_pageno, EngineError, SearchEngine, SerpExtras};
+
+pub struct Bing;
+
+impl SearchEngine for Bing {
+    fn name(&self) -> &'static str {
+        "bing"
+    }
+
+    fn build_url(
+        &self,
+        query: &str,
+        _max_results: usize,
+        pageno: u32,
+        language: Option<&str>,
+    ) -> Result<reqwest::Url, EngineError> {
+        build_search_url(query, pageno, language)
+    }
+
+    fn parse_results(&self, html: &str, max_results: usize) -> Vec<SearchResult> {
+        parse_results(html, max_results)
+    }
+
+    fn parse_extras(&self, html: &str) -> SerpExtras {
+        parse_extras(html)
+    }
+
+    fn time_range_param(&self, range: TimeRange) -> Option<(&'static str, String)> {
+        Some((
+            "filters",
+            time_filter(range, chrono::Utc::now().date_naive()),
+        ))
+    }
+
+    fn operators(&self) -> &'static [Operator] {
+        &[
+            Operator::Site,
+            Operator::ExcludeSite,
+            Operator::Filetype,
+            Operator::Intitle,
+        ]
+    }
+}
+
+/// Bing's `filters` value: `ez1`..`ez3` are the last day/week/month; a year needs an
+/// explicit `ez5_<from>_<to>` range counted in days since the Unix epoch.
+pub fn time_filter(range: TimeRange, today: chrono::NaiveDate) -> String {
+    match range {
+        TimeRange::Day => r#"ex1:"ez1""#.to_string(),
+        TimeRange::Week => r#"ex1:"ez2""#.to_string(),
+        TimeRange::Month => r#"ex1:"ez3""#.to_string(),
+        TimeRange::Year => {
+            let to = (today - chrono::DateTime::UNIX_EPOCH.date_naive()).num_days();
+            format!(r#"ex1:"ez5_{}_{}""#, to - range.days(), to)
+        }
+    }
+}
 
 fn normalize_bing_href(href: &str) -> Option<String> {
     let href = href.trim();
@@ -48,80 +105,284 @@ fn normalize_bing_href(href: &str) -> Option<String> {
     Some(href.to_string())
 }
 
+fn collapse_ws(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn text_of(el: ElementRef<'_>) -> String {
+    collapse_ws(&el.text().collect::<Vec<_>>().join(" "))
+}
+
+fn bing_result(
+    url: String,
+    title: String,
+    content: String,
+    published_at: Option<String>,
+    rich_snippet: Option<String>,
+) -> SearchResult {
+    let breadcrumbs = crate::tools::search::breadcrumbs_from_url(&url);
+    let (domain, source_type) = crate::tools::search::classify_search_result(&url);
+    SearchResult {
+        url,
+        title,
+        content,
+        engine: Some("bing".to_string()),
+        engine_source: Some("bing".to_string()),
+        engine_sources: vec!["bing".to_string()],
+        score: None,
+        published_at,
+        breadcrumbs,
+        rich_snippet,
+        top_answer: None,
+        domain,
+        source_type: Some(source_type),
+        detected_language: None,
+        title_highlights: None,
+        content_highlights: None,
+    }
+}
+
+/// Organic `li.b_algo` blocks plus the cards of the news and video carousels, in page
+/// order. Cards in the `#b_context` sidebar and URLs already listed are skipped.
 pub fn parse_results(html: &str, max_results: usize) -> Vec<SearchResult> {
     let doc = Html::parse_document(html);
-    let sel_item = Selector::parse("li.b_algo").unwrap();
-    let sel_link = Selector::parse("h2 a").unwrap();
-    let sel_snip = Selector::parse("div.b_caption p").unwrap();
-    let sel_fact = Selector::parse("div.b_factrow, div.b_vlist2col").unwrap();
+    let sel_item = Selector::parse("li.b_algo, div.news-card, div.mc_vtvc").unwrap();
 
-    let mut out = Vec::new();
+    let mut out: Vec<SearchResult> = Vec::new();
     for item in doc.select(&sel_item) {
         if out.len() >= max_results {
             break;
         }
-        let link = match item.select(&sel_link).next() {
-            Some(l) => l,
-            None => continue,
-        };
-        let href_raw = link.value().attr("href").unwrap_or("").to_string();
-        let Some(href) = normalize_bing_href(&href_raw) else {
+        let in_sidebar = item
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|a| a.value().id() == Some("b_context"));
+        if in_sidebar {
             continue;
+        }
+        let has_class = |class: &str| item.value().classes().any(|c| c == class);
+        let parsed = if has_class("b_algo") {
+            parse_organic(item)
+        } else if has_class("news-card") {
+            parse_news_card(item)
+        } else {
+            parse_video_card(item)
         };
-        let title = link.text().collect::<Vec<_>>().join(" ");
-        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
-        let snippet_raw = item
-            .select(&sel_snip)
-            .next()
-            .map(|p| p.text().collect::<Vec<_>>().join(" "))
-            .unwrap_or_default();
-        let snippet_raw = snippet_raw.split_whitespace().collect::<Vec<_>>().join(" ");
-        let (published_prefix, snippet) = crate::tools::search::split_date_prefix(&snippet_raw);
-
-        let published_at = published_prefix
-            .or_else(|| crate::tools::search::extract_published_at_from_text(&snippet_raw));
-        let breadcrumbs = crate::tools::search::breadcrumbs_from_url(&href);
-        let rich_snippet = item
-            .select(&sel_fact)
-            .next()
-            .map(|n| n.text().collect::<Vec<_>>().join(" "))
-            .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
-            .filter(|s| !s.is_empty());
-
-        let (domain, source_type) = crate::tools::search::classify_search_result(&href);
-        out.push(SearchResult {
-            url: href,
-            title,
-            content: snippet,
-            engine: Some("bing".to_string()),
-            engine_source: Some("bing".to_string()),
-            engine_sources: vec!["bing".to_string()],
-            score: None,
-            published_at,
-            breadcrumbs,
-            rich_snippet,
-            top_answer: None,
-            domain,
-            source_type: Some(source_type),
-        });
+        if let Some(result) = parsed {
+            if !out.iter().any(|r| r.url == result.url) {
+                out.push(result);
+            }
+        }
     }
 
     out
 }
 
-pub async fn search(
-    client: &reqwest::Client,
+fn parse_organic(item: ElementRef<'_>) -> Option<SearchResult> {
+    let sel_link = Selector::parse("h2 a").unwrap();
+    let sel_snip = Selector::parse("div.b_caption p").unwrap();
+    let sel_fact = Selector::parse("div.b_factrow, div.b_vlist2col").unwrap();
+
+    let link = item.select(&sel_link).next()?;
+    let href = normalize_bing_href(link.value().attr("href").unwrap_or(""))?;
+    let title = text_of(link);
+    let snippet_raw = item
+        .select(&sel_snip)
+        .next()
+        .map(text_of)
+        .unwrap_or_default();
+    let (published_prefix, snippet) = crate::tools::search::split_date_prefix(&snippet_raw);
+
+    let published_at = published_prefix
+        .or_else(|| crate::tools::search::extract_published_at_from_text(&snippet_raw));
+    let rich_snippet = item
+        .select(&sel_fact)
+        .next()
+        .map(text_of)
+        .filter(|s| !s.is_empty());
+
+    Some(bing_result(
+        href,
+        title,
+        snippet,
+        published_at,
+        rich_snippet,
+    ))
+}
+
+/// A news-carousel card: `data-url`/`data-title`/`data-author` on the card, the age in
+/// the `.source` line's `aria-label`. The outlet and age become the rich snippet.
+fn parse_news_card(card: ElementRef<'_>) -> Option<SearchResult> {
+    let sel_title = Selector::parse("a.title").unwrap();
+    let sel_snip = Selector::parse(".snippet").unwrap();
+    let sel_source = Selector::parse(".source .cite, .source span[title]").unwrap();
+    let sel_time = Selector::parse(".source span[aria-label]").unwrap();
+
+    let link = card.select(&sel_title).next();
+    let href = card
+        .value()
+        .attr("data-url")
+        .or_else(|| link.and_then(|l| l.value().attr("href")))
+        .and_then(normalize_bing_href)?;
+    let title = card
+        .value()
+        .attr("data-title")
+        .map(collapse_ws)
+        .or_else(|| link.map(text_of))
+        .filter(|t| !t.is_empty())?;
+    let snippet_raw = card
+        .select(&sel_snip)
+        .next()
+        .map(|s| {
+            s.value()
+                .attr("title")
+                .map(collapse_ws)
+                .unwrap_or_else(|| text_of(s))
+        })
+        .unwrap_or_default();
+    let outlet = card
+        .value()
+        .attr("data-author")
+        .map(collapse_ws)
+        .or_else(|| card.select(&sel_source).next().map(text_of))
+        .filter(|s| !s.is_empty());
+    let time = card
+        .select(&sel_time)
+        .next()
+        .and_then(|t| t.value().attr("aria-label"))
+        .map(collapse_ws);
+
+    let (published_at, snippet) =
+        crate::tools::search::engines::card_published_at(&snippet_raw, time.as_deref());
+    let rich_snippet = [outlet, time]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" · ");
+    Some(bing_result(
+        href,
+        title,
+        snippet,
+        published_at,
+        Some(rich_snippet).filter(|s| !s.is_empty()),
+    ))
+}
+
+/// A video-carousel card. Its link goes to Bing's own player, so the watch page comes
+/// from the `ourl` attribute; the meta rows carry views, upload date, host and channel.
+fn parse_video_card(card: ElementRef<'_>) -> Option<SearchResult> {
+    let sel_ourl = Selector::parse("[ourl]").unwrap();
+    let sel_link = Selector::parse("a[href]").unwrap();
+    let sel_title = Selector::parse(".mc_vtvc_title").unwrap();
+    let sel_meta = Selector::parse(".mc_vtvc_meta_row span").unwrap();
+    let sel_duration = Selector::parse(".mc_bc_rc").unwrap();
+
+    let href = card
+        .value()
+        .attr("ourl")
+        .or_else(|| card.select(&sel_ourl).next()?.value().attr("ourl"))
+        .or_else(|| card.select(&sel_link).next()?.value().attr("href"))
+        .and_then(normalize_bing_href)?;
+    let title = card
+        .select(&sel_title)
+        .next()
+        .map(|t| {
+            t.value()
+                .attr("title")
+                .map(collapse_ws)
+                .unwrap_or_else(|| text_of(t))
+        })
+        .filter(|t| !t.is_empty())?;
+    let meta: Vec<String> = card
+        .select(&sel_meta)
+        .map(text_of)
+        .filter(|m| !m.is_empty())
+        .collect();
+    let now = chrono::Utc::now();
+    let time = meta.iter().find(|m| {
+        crate::tools::search::extract_published_at_from_text(m).is_some()
+            || crate::tools::search::published_at_from_age(m, now).is_some()
+    });
+    let (published_at, _) =
+        crate::tools::search::engines::card_published_at("", time.map(String::as_str));
+    let duration = card
+        .select(&sel_duration)
+        .next()
+        .map(text_of)
+        .filter(|d| !d.is_empty())
+        .map(|d| format!("Video · {}", d));
+
+    let mut result = bing_result(href, title, meta.join(" · "), published_at, duration);
+    result.source_type = Some("video".to_string());
+    Some(result)
+}
+
+/// The `#b_context` entity pane (title, subtitle, description and its source link),
+/// reported as an answer instead of a result.
+pub fn parse_extras(html: &str) -> SerpExtras {
+    let doc = Html::parse_document(html);
+    let sel_entity = Selector::parse("#b_context .b_entityTP").unwrap();
+    let sel_title = Selector::parse(".b_entityTitle").unwrap();
+    let sel_subtitle = Selector::parse(".b_entitySubTitle").unwrap();
+    let sel_desc = Selector::parse(".b_snippet, .b_paractl").unwrap();
+    let sel_source = Selector::parse("a[href]").unwrap();
+
+    let mut extras = SerpExtras::default();
+    for entity in doc.select(&sel_entity) {
+        let Some(desc) = entity.select(&sel_desc).next() else {
+            continue;
+        };
+        let mut text = text_of(desc);
+        // The description ends with its attribution link ("Wikipedia").
+        let source = desc.select(&sel_source).last();
+        if let Some(source) = source {
+            let label = text_of(source);
+            if let Some(stripped) = text.strip_suffix(label.as_str()) {
+                text = stripped.trim_end().to_string();
+            }
+        }
+        let title = entity.select(&sel_title).next().map(text_of);
+        let Some(title) = title.filter(|t| !t.is_empty()) else {
+            continue;
+        };
+        if text.is_empty() {
+            continue;
+        }
+        let heading = match entity.select(&sel_subtitle).next().map(text_of) {
+            Some(subtitle) if !subtitle.is_empty() => format!("{} ({})", title, subtitle),
+            _ => title,
+        };
+        let mut answer = format!("{}: {}", heading, text);
+        if let Some(href) = source
+            .and_then(|s| s.value().attr("href"))
+            .and_then(normalize_bing_href)
+        {
+            answer.push_str(&format!(" ({})", href));
+        }
+        extras.answers.push(answer);
+    }
+
+    extras
+}
+
+pub fn build_search_url(
     query: &str,
-    max_results: usize,
-) -> Result<Vec<SearchResult>, EngineError> {
+    pageno: u32,
+    language: Option<&str>,
+) -> Result<reqwest::Url, EngineError> {
     let mut url = reqwest::Url::parse("https://www.bing.com/search")
         .map_err(|e| EngineError::Fatal(e.to_string()))?;
     {
         let mut qp = url.query_pairs_mut();
         qp.append_pair("q", query);
+        // Bing's `first` is the 1-based index of the first result (10 per page).
+        let page_index = clamp_pageno(pageno) - 1;
+        if page_index > 0 {
+            qp.append_pair("first", &(page_index * 10 + 1).to_string());
+        }
+        if let Some(language) = language {
+            qp.append_pair("setlang", language.trim());
+        }
     }
-
-    let (_status, body) = fetch_serp_html(client, url, "bing").await?;
-
-    Ok(parse_results(&body, max_results))
+    Ok(url)
 }
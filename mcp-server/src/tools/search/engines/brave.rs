@@ -1,7 +1,58 @@
+use crate::tools::search::{Operator, TimeRange};
 use crate::types::SearchResult;
 use scraper::{ElementRef, Html, Selector};
+use std::time::Duration;
 
-use super::{fetch_serp_html, EngineError};
+use super::{clamp_pageno, EngineError, SearchEngine, SerpExtras};
+
+pub struct Brave;
+
+impl SearchEngine for Brave {
+    fn name(&self) -> &'static str {
+        "brave"
+    }
+
+    fn build_url(
+        &self,
+        query: &str,
+        _max_results: usize,
+        pageno: u32,
+        _language: Option<&str>,
+    ) -> Result<reqwest::Url, EngineError> {
+        build_search_url(query, pageno)
+    }
+
+    fn parse_results(&self, html: &str, max_results: usize) -> Vec<SearchResult> {
+        parse_results(html, max_results)
+    }
+
+    fn parse_extras(&self, html: &str) -> SerpExtras {
+        parse_extras(html)
+    }
+
+    fn time_range_param(&self, range: TimeRange) -> Option<(&'static str, String)> {
+        let tf = match range {
+            TimeRange::Day => "pd",
+            TimeRange::Week => "pw",
+            TimeRange::Month => "pm",
+            TimeRange::Year => "py",
+        };
+        Some(("tf", tf.to_string()))
+    }
+
+    fn operators(&self) -> &'static [Operator] {
+        &[
+            Operator::Site,
+            Operator::ExcludeSite,
+            Operator::Filetype,
+            Operator::Intitle,
+        ]
+    }
+
+    fn default_timeout(&self) -> Duration {
+        Duration::from_millis(3_500)
+    }
+}
 
 fn normalize_href(href: &str) -> Option<String> {
     let href = href.trim();
@@ -37,81 +88,213 @@ fn extract_snippet(container: &ElementRef<'_>) -> String {
     String::new()
 }
 
+fn collapse_ws(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn text_of(el: ElementRef<'_>) -> String {
+    collapse_ws(&el.text().collect::<Vec<_>>().join(" "))
+}
+
+fn brave_result(
+    url: String,
+    title: String,
+    content: String,
+    published_at: Option<String>,
+    rich_snippet: Option<String>,
+) -> SearchResult {
+    let breadcrumbs = crate::tools::search::breadcrumbs_from_url(&url);
+    let (domain, source_type) = crate::tools::search::classify_search_result(&url);
+    SearchResult {
+        url,
+        title,
+        content,
+        engine: Some("brave".to_string()),
+        engine_source: Some("brave".to_string()),
+        engine_sources: vec!["brave".to_string()],
+        score: None,
+        published_at,
+        breadcrumbs,
+        rich_snippet,
+        top_answer: None,
+        domain,
+        source_type: Some(source_type),
+        detected_language: None,
+        title_highlights: None,
+        content_highlights: None,
+    }
+}
+
+/// The `data-type` of the news or video carousel an anchor sits in, if any.
+fn carousel_kind(a: &ElementRef<'_>) -> Option<&'static str> {
+    a.ancestors()
+        .filter_map(ElementRef::wrap)
+        .find_map(|el| match el.value().attr("data-type") {
+            Some("news") => Some("news"),
+            Some("videos") => Some("videos"),
+            _ => None,
+        })
+}
+
 pub fn parse_results(html: &str, max_results: usize) -> Vec<SearchResult> {
     let doc = Html::parse_document(html);
 
-    // Brave SERP markup changes; prefer semantic patterns: anchors wrapping h3 under main.
+    // Brave SERP markup changes; prefer semantic patterns: anchors wrapping h3 under main,
+    // plus the cards of the news and video carousels, in page order.
     let main_sel = Selector::parse("main").unwrap();
     let a_sel = Selector::parse("a").unwrap();
     let h3_sel = Selector::parse("h3").unwrap();
 
-    let mut out = Vec::new();
+    let mut out: Vec<SearchResult> = Vec::new();
     let Some(main) = doc.select(&main_sel).next() else {
         return out;
     };
 
-    // Attempt 1: anchors containing h3.
     for a in main.select(&a_sel) {
         if out.len() >= max_results {
             break;
         }
-        if a.select(&h3_sel).next().is_none() {
-            continue;
+        let parsed = match carousel_kind(&a) {
+            Some(kind) => parse_card(a, kind),
+            None if a.select(&h3_sel).next().is_some() => parse_organic(a, &h3_sel),
+            None => None,
+        };
+        if let Some(result) = parsed {
+            if !out.iter().any(|r| r.url == result.url) {
+                out.push(result);
+            }
         }
+    }
+
+    out
+}
+
+fn parse_organic(a: ElementRef<'_>, h3_sel: &Selector) -> Option<SearchResult> {
+    let url = normalize_href(a.value().attr("href").unwrap_or(""))?;
+    let title = a.select(h3_sel).next().map(text_of).unwrap_or_default();
+    if title.is_empty() {
+        return None;
+    }
+
+    // Snippet: use parent container heuristics.
+    let snippet_raw = extract_snippet(&a);
+    let (published_prefix, snippet) = crate::tools::search::split_date_prefix(&snippet_raw);
+    let published_at = published_prefix
+        .or_else(|| crate::tools::search::extract_published_at_from_text(&snippet_raw));
 
-        let href = a.value().attr("href").unwrap_or("");
-        let Some(url) = normalize_href(href) else {
+    Some(brave_result(url, title, snippet, published_at, None))
+}
+
+/// A carousel card: `.title`, the outlet in `.netloc` (videos add `.creator` and
+/// `.duration`) and the age in `.age`. Outlet, creator and duration become the rich
+/// snippet; video cards are typed `video`.
+fn parse_card(a: ElementRef<'_>, kind: &str) -> Option<SearchResult> {
+    let sel_title = Selector::parse(".title").unwrap();
+    let sel_desc = Selector::parse(".description").unwrap();
+    let sel_age = Selector::parse(".age, time").unwrap();
+
+    let url = normalize_href(a.value().attr("href").unwrap_or(""))?;
+    let title = a
+        .select(&sel_title)
+        .next()
+        .map(|t| {
+            t.value()
+                .attr("title")
+                .map(collapse_ws)
+                .unwrap_or_else(|| text_of(t))
+        })
+        .filter(|t| !t.is_empty())?;
+    let snippet_raw = a.select(&sel_desc).next().map(text_of).unwrap_or_default();
+    // `datetime` is RFC 3339; its date part is what the date extractors recognise.
+    let age = a
+        .select(&sel_age)
+        .next()
+        .map(|t| match t.value().attr("datetime") {
+            Some(dt) => dt.trim().split('T').next().unwrap_or_default().to_string(),
+            None => text_of(t),
+        });
+    let meta = [".netloc", ".creator", ".duration"]
+        .into_iter()
+        .filter_map(|css| a.select(&Selector::parse(css).unwrap()).next())
+        .map(text_of)
+        .filter(|m| !m.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ");
+
+    let (published_at, snippet) =
+        crate::tools::search::engines::card_published_at(&snippet_raw, age.as_deref());
+    let content = if snippet.is_empty() {
+        meta.clone()
+    } else {
+        snippet
+    };
+    let mut result = brave_result(
+        url,
+        title,
+        content,
+        published_at,
+        Some(meta).filter(|m| !m.is_empty()),
+    );
+    if kind == "videos" {
+        result.source_type = Some("video".to_string());
+    }
+    Some(result)
+}
+
+/// The sidebar infobox (title, subtitle, description and its attribution link),
+/// reported as an answer instead of a result.
+pub fn parse_extras(html: &str) -> SerpExtras {
+    let doc = Html::parse_document(html);
+    let sel_infobox = Selector::parse(".infobox").unwrap();
+    let sel_title = Selector::parse(".infobox-title").unwrap();
+    let sel_subtitle = Selector::parse(".infobox-subtitle").unwrap();
+    let sel_desc = Selector::parse(".infobox-description").unwrap();
+    let sel_source = Selector::parse("a.infobox-attribution").unwrap();
+
+    let mut extras = SerpExtras::default();
+    for infobox in doc.select(&sel_infobox) {
+        let title = infobox.select(&sel_title).next().map(text_of);
+        let Some(title) = title.filter(|t| !t.is_empty()) else {
             continue;
         };
-
-        let title = a
-            .select(&h3_sel)
+        let text = infobox
+            .select(&sel_desc)
             .next()
-            .map(|h| h.text().collect::<Vec<_>>().join(" "))
+            .map(text_of)
             .unwrap_or_default();
-        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
-        if title.is_empty() {
+        if text.is_empty() {
             continue;
         }
-
-        // Snippet: use parent container heuristics.
-        let snippet_raw = extract_snippet(&a);
-        let (published_prefix, snippet) = crate::tools::search::split_date_prefix(&snippet_raw);
-        let published_at = published_prefix
-            .or_else(|| crate::tools::search::extract_published_at_from_text(&snippet_raw));
-        let breadcrumbs = crate::tools::search::breadcrumbs_from_url(&url);
-        let (domain, source_type) = crate::tools::search::classify_search_result(&url);
-
-        out.push(SearchResult {
-            url,
-            title,
-            content: snippet,
-            engine: Some("brave".to_string()),
-            engine_source: Some("brave".to_string()),
-            engine_sources: vec!["brave".to_string()],
-            score: None,
-            published_at,
-            breadcrumbs,
-            rich_snippet: None,
-            top_answer: None,
-            domain,
-            source_type: Some(source_type),
-        });
+        let heading = match infobox.select(&sel_subtitle).next().map(text_of) {
+            Some(subtitle) if !subtitle.is_empty() => format!("{} ({})", title, subtitle),
+            _ => title,
+        };
+        let mut answer = format!("{}: {}", heading, text);
+        if let Some(href) = infobox
+            .select(&sel_source)
+            .next()
+            .and_then(|s| s.value().attr("href"))
+            .and_then(normalize_href)
+        {
+            answer.push_str(&format!(" ({})", href));
+        }
+        extras.answers.push(answer);
     }
 
-    out
+    extras
 }
 
-pub async fn search(
-    client: &reqwest::Client,
-    query: &str,
-    max_results: usize,
-) -> Result<Vec<SearchResult>, EngineError> {
+/// Brave has no stable URL parameter for result language; it only goes out as
+/// `Accept-Language`.
+pub fn build_search_url(query: &str, pageno: u32) -> Result<reqwest::Url, EngineError> {
     let mut url = reqwest::Url::parse("https://search.brave.com/search")
         .map_err(|e| EngineError::Fatal(e.to_string()))?;
     url.query_pairs_mut().append_pair("q", query);
-
-    let (_status, body) = fetch_serp_html(client, url, "brave").await?;
-
-    Ok(parse_results(&body, max_results))
+    // Brave's `offset` counts pages, not results.
+    let page_index = clamp_pageno(pageno) - 1;
+    if page_index > 0 {
+        url.query_pairs_mut()
+            .append_pair("offset", &page_index.to_string());
+    }
+    Ok(url)
 }
@@ -1,7 +1,63 @@
+use crate::tools::search::language::{primary_subtag, region_subtag};
+use crate::tools::search::{Operator, TimeRange};
 use crate::types::SearchResult;
 use scraper::{Html, Selector};
+use std::time::Duration;
 
-use super::{fetch_serp_html, EngineError};
+use super::{clamp_pageno, EngineError, SearchEngine, SerpExtras};
+
+pub struct DuckDuckGo;
+
+impl SearchEngine for DuckDuckGo {
+    fn name(&self) -> &'static str {
+        "duckduckgo"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ddg"]
+    }
+
+    fn build_url(
+        &self,
+        query: &str,
+        _max_results: usize,
+        pageno: u32,
+        language: Option<&str>,
+    ) -> Result<reqwest::Url, EngineError> {
+        build_search_url(query, pageno, language)
+    }
+
+    fn parse_results(&self, html: &str, max_results: usize) -> Vec<SearchResult> {
+        parse_results(html, max_results)
+    }
+
+    fn parse_extras(&self, html: &str) -> SerpExtras {
+        parse_extras(html)
+    }
+
+    fn time_range_param(&self, range: TimeRange) -> Option<(&'static str, String)> {
+        let df = match range {
+            TimeRange::Day => "d",
+            TimeRange::Week => "w",
+            TimeRange::Month => "m",
+            TimeRange::Year => "y",
+        };
+        Some(("df", df.to_string()))
+    }
+
+    fn operators(&self) -> &'static [Operator] {
+        &[
+            Operator::Site,
+            Operator::ExcludeSite,
+            Operator::Filetype,
+            Operator::Intitle,
+        ]
+    }
+
+    fn default_timeout(&self) -> Duration {
+        Duration::from_millis(4_500)
+    }
+}
 
 fn normalize_ddg_href(href: &str) -> Option<String> {
     let href = href.trim();
@@ -87,22 +143,105 @@ pub fn parse_results(html: &str, max_results: usize) -> Vec<SearchResult> {
             top_answer: None,
             domain,
             source_type: Some(source_type),
+            detected_language: None,
+            title_highlights: None,
+            content_highlights: None,
         });
     }
 
     out
 }
 
-pub async fn search(
-    client: &reqwest::Client,
+fn collapse_ws(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The zero-click box (definitions, conversions, Wikipedia abstracts), related searches
+/// and the "Including results for" / "Did you mean" spelling link.
+pub fn parse_extras(html: &str) -> SerpExtras {
+    let doc = Html::parse_document(html);
+    let sel_zci = Selector::parse("div.zci").unwrap();
+    let sel_heading = Selector::parse(".zci__heading").unwrap();
+    let sel_body = Selector::parse(".zci__result").unwrap();
+    let sel_source = Selector::parse(".zci__more-at").unwrap();
+    let sel_related = Selector::parse(".related-searches a").unwrap();
+    let sel_spelling = Selector::parse("#did_you_mean a").unwrap();
+
+    let mut extras = SerpExtras::default();
+    for zci in doc.select(&sel_zci) {
+        let Some(body) = zci.select(&sel_body).next() else {
+            continue;
+        };
+        let mut text = collapse_ws(&body.text().collect::<Vec<_>>().join(" "));
+        // The "More at Wikipedia" link closes the result; keep it out of the answer text.
+        let source = body.select(&sel_source).next();
+        if let Some(source) = source {
+            let label = collapse_ws(&source.text().collect::<Vec<_>>().join(" "));
+            if let Some(stripped) = text.strip_suffix(label.as_str()) {
+                text = stripped.trim_end().to_string();
+            }
+        }
+        if text.is_empty() {
+            continue;
+        }
+        let heading = zci
+            .select(&sel_heading)
+            .next()
+            .map(|h| collapse_ws(&h.text().collect::<Vec<_>>().join(" ")))
+            .filter(|h| !h.is_empty());
+        let mut answer = match heading {
+            Some(heading) => format!("{}: {}", heading, text),
+            None => text,
+        };
+        if let Some(href) = source
+            .and_then(|s| s.value().attr("href"))
+            .and_then(normalize_ddg_href)
+        {
+            answer.push_str(&format!(" ({})", href));
+        }
+        extras.answers.push(answer);
+    }
+
+    for link in doc.select(&sel_related) {
+        let text = collapse_ws(&link.text().collect::<Vec<_>>().join(" "));
+        if !text.is_empty() && !extras.suggestions.contains(&text) {
+            extras.suggestions.push(text);
+        }
+    }
+
+    // The first link is the corrected query; "Search only for" links back to the original.
+    if let Some(link) = doc.select(&sel_spelling).next() {
+        let text = collapse_ws(&link.text().collect::<Vec<_>>().join(" "));
+        if !text.is_empty() {
+            extras.corrections.push(text);
+        }
+    }
+
+    extras
+}
+
+pub fn build_search_url(
     query: &str,
-    max_results: usize,
-) -> Result<Vec<SearchResult>, EngineError> {
+    pageno: u32,
+    language: Option<&str>,
+) -> Result<reqwest::Url, EngineError> {
     let mut url = reqwest::Url::parse("https://duckduckgo.com/html/")
         .map_err(|e| EngineError::Fatal(e.to_string()))?;
     url.query_pairs_mut().append_pair("q", query);
-
-    let (_status, body) = fetch_serp_html(client, url, "duckduckgo").await?;
-
-    Ok(parse_results(&body, max_results))
+    // The HTML endpoint serves 30 results on the first page and 50 per page after that.
+    let page_index = clamp_pageno(pageno) - 1;
+    if page_index > 0 {
+        let offset = 30 + (page_index - 1) * 50;
+        url.query_pairs_mut()
+            .append_pair("s", &offset.to_string())
+            .append_pair("dc", &(offset + 1).to_string());
+    }
+    // `kl` is a region-language pair (`de-de`, `us-en`), so it needs a regional tag.
+    if let Some(language) = language {
+        if let Some(region) = region_subtag(language) {
+            let kl = format!("{}-{}", region, primary_subtag(language));
+            url.query_pairs_mut().append_pair("kl", &kl);
+        }
+    }
+    Ok(url)
 }
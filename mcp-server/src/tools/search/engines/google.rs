@@ -1,8 +1,52 @@
+use crate::tools::search::language::primary_subtag;
+use crate::tools::search::{Operator, TimeRange};
 use crate::types::SearchResult;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use scraper::{ElementRef, Html, Selector};
 
-use super::{fetch_serp_html, EngineError};
+use super::{clamp_pageno, EngineError, SearchEngine};
+
+pub struct Google;
+
+impl SearchEngine for Google {
+    fn name(&self) -> &'static str {
+        "google"
+    }
+
+    fn build_url(
+        &self,
+        query: &str,
+        max_results: usize,
+        pageno: u32,
+        language: Option<&str>,
+    ) -> Result<reqwest::Url, EngineError> {
+        build_search_url(query, max_results, pageno, language)
+    }
+
+    fn parse_results(&self, html: &str, max_results: usize) -> Vec<SearchResult> {
+        parse_results(html, max_results)
+    }
+
+    fn time_range_param(&self, range: TimeRange) -> Option<(&'static str, String)> {
+        let unit = match range {
+            TimeRange::Day => "d",
+            TimeRange::Week => "w",
+            TimeRange::Month => "m",
+            TimeRange::Year => "y",
+        };
+        Some(("tbs", format!("qdr:{}", unit)))
+    }
+
+    fn operators(&self) -> &'static [Operator] {
+        &[
+            Operator::Site,
+            Operator::ExcludeSite,
+            Operator::Filetype,
+            Operator::Intitle,
+            Operator::DateRange,
+        ]
+    }
+}
 
 fn normalize_google_href(href: &str) -> Option<String> {
     if href.is_empty() {
@@ -149,6 +193,9 @@ pub fn parse_results(html: &str, max_results: usize) -> Vec<SearchResult> {
                 top_answer: top_answer_for_this,
                 domain,
                 source_type: Some(source_type),
+                detected_language: None,
+                title_highlights: None,
+                content_highlights: None,
             });
         }
 
@@ -160,21 +207,29 @@ pub fn parse_results(html: &str, max_results: usize) -> Vec<SearchResult> {
     out
 }
 
-pub async fn search(
-    client: &reqwest::Client,
+pub fn build_search_url(
     query: &str,
     max_results: usize,
-) -> Result<Vec<SearchResult>, EngineError> {
+    pageno: u32,
+    language: Option<&str>,
+) -> Result<reqwest::Url, EngineError> {
     // Use a conservative, widely supported endpoint.
     let encoded = utf8_percent_encode(query, NON_ALPHANUMERIC).to_string();
-    let url = reqwest::Url::parse(&format!(
-        "https://www.google.com/search?q={}&hl=en&num={}",
+    let num = max_results.clamp(5, 10);
+    let hl = language.map(|l| utf8_percent_encode(l.trim(), NON_ALPHANUMERIC).to_string());
+    let mut raw = format!(
+        "https://www.google.com/search?q={}&hl={}&num={}",
         encoded,
-        max_results.clamp(5, 10)
-    ))
-    .map_err(|e| EngineError::Fatal(e.to_string()))?;
-
-    let (_status, body) = fetch_serp_html(client, url, "google").await?;
-
-    Ok(parse_results(&body, max_results))
+        hl.as_deref().unwrap_or("en"),
+        num
+    );
+    // `hl` only sets the UI language; `lr` restricts results to documents in it.
+    if let Some(language) = language {
+        raw.push_str(&format!("&lr=lang_{}", primary_subtag(language)));
+    }
+    let page_index = clamp_pageno(pageno) - 1;
+    if page_index > 0 {
+        raw.push_str(&format!("&start={}", page_index as usize * num));
+    }
+    reqwest::Url::parse(&raw).map_err(|e| EngineError::Fatal(e.to_string()))
 }
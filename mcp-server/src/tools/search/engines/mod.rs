@@ -2,30 +2,205 @@ pub mod bing;
 pub mod brave;
 pub mod duckduckgo;
 pub mod google;
+pub mod mojeek;
 
-use anyhow::Result;
+use crate::core::fixtures::{header_pairs, Fixtures, RecordedResponse};
+use crate::core::retry::{parse_retry_after, RetryPolicy};
+use crate::tools::search::language::primary_subtag;
+use crate::tools::search::{Operator, TimeRange};
+use crate::types::SearchResult;
 use reqwest::StatusCode;
-use tracing::warn;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, warn};
 
+/// An HTML search engine. Each engine lives in its own module as a unit struct and is
+/// listed once in [`REGISTRY`]; the search service, the tier-2 non-robot fallback and
+/// engine-list validation all go through this trait.
+pub trait SearchEngine: Send + Sync {
+    /// Canonical lowercase name used in `SEARCH_ENGINES`, env keys and result attribution.
+    fn name(&self) -> &'static str;
+
+    /// Other accepted spellings in engine lists (e.g. `ddg`).
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// SERP URL for a 1-based `pageno`; `language` is a tag such as `de` or `pt-BR`.
+    fn build_url(
+        &self,
+        query: &str,
+        max_results: usize,
+        pageno: u32,
+        language: Option<&str>,
+    ) -> Result<reqwest::Url, EngineError>;
+
+    fn parse_results(&self, html: &str, max_results: usize) -> Vec<SearchResult>;
+
+    /// Instant answers, related searches and spelling corrections shown beside the
+    /// organic results; engines that don't parse them return nothing.
+    fn parse_extras(&self, _html: &str) -> SerpExtras {
+        SerpExtras::default()
+    }
+
+    /// Query parameter restricting results to `range`, or `None` when the engine has no
+    /// date filter and runs unfiltered.
+    fn time_range_param(&self, _range: TimeRange) -> Option<(&'static str, String)> {
+        None
+    }
+
+    /// Query operators the engine honors; the others are left out of its query (see
+    /// `operators::render`).
+    fn operators(&self) -> &'static [Operator] {
+        &[Operator::Site]
+    }
+
+    /// Built-in timeout, used when `SEARCH_ENGINE_TIMEOUT_MS_<NAME>` is unset.
+    fn default_timeout(&self) -> Duration {
+        base_engine_timeout()
+    }
+}
+
+/// `SEARCH_ENGINE_TIMEOUT_MS`, default 2.5s: the timeout of engines without their own.
+pub fn base_engine_timeout() -> Duration {
+    Duration::from_millis(
+        std::env::var("SEARCH_ENGINE_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(2_500),
+    )
+}
+
+/// What a SERP says about the query itself rather than its results.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerpExtras {
+    pub answers: Vec<String>,
+    pub suggestions: Vec<String>,
+    pub corrections: Vec<String>,
+}
+
+/// Split a news or video card's date out of its snippet the way organic results do,
+/// falling back to the card's printed time (`Mar 3, 2025`, `2 days ago`, `5h`).
+pub(crate) fn card_published_at(snippet: &str, time: Option<&str>) -> (Option<String>, String) {
+    let (prefix, content) = crate::tools::search::split_date_prefix(snippet);
+    let time = time.map(str::trim).filter(|t| !t.is_empty());
+    let published_at = prefix
+        .or_else(|| time.and_then(crate::tools::search::extract_published_at_from_text))
+        .or_else(|| {
+            time.and_then(|t| crate::tools::search::published_at_from_age(t, chrono::Utc::now()))
+        })
+        .or_else(|| crate::tools::search::extract_published_at_from_text(snippet));
+    (published_at, content)
+}
+
+/// Every engine the service can run, in default priority order.
+pub static REGISTRY: &[&dyn SearchEngine] = &[
+    &google::Google,
+    &bing::Bing,
+    &duckduckgo::DuckDuckGo,
+    &brave::Brave,
+    &mojeek::Mojeek,
+];
+
+/// Resolve a name or alias (case-insensitive) to its registered engine.
+pub fn lookup(name: &str) -> Option<&'static dyn SearchEngine> {
+    let name = name.trim().to_ascii_lowercase();
+    REGISTRY
+        .iter()
+        .copied()
+        .find(|engine| engine.name() == name || engine.aliases().contains(&name.as_str()))
+}
+
+/// Add `engine`'s date filter for `range` to `url`; `false` if it has none.
+pub fn apply_time_range(
+    engine: &dyn SearchEngine,
+    url: &mut reqwest::Url,
+    range: TimeRange,
+) -> bool {
+    match engine.time_range_param(range) {
+        Some((key, value)) => {
+            url.query_pairs_mut().append_pair(key, &value);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Fetch one SERP page through [`fetch_serp_html`] and parse its results and extras.
+pub async fn search(
+    engine: &dyn SearchEngine,
+    client: &reqwest::Client,
+    query: &str,
+    max_results: usize,
+    pageno: u32,
+    language: Option<&str>,
+    time_range: Option<TimeRange>,
+) -> Result<(Vec<SearchResult>, SerpExtras), EngineError> {
+    let mut url = engine.build_url(query, max_results, pageno, language)?;
+    if let Some(range) = time_range {
+        apply_time_range(engine, &mut url, range);
+    }
+
+    let (_status, body) = fetch_serp_html(client, url, engine.name(), language).await?;
+
+    Ok((
+        engine.parse_results(&body, max_results),
+        engine.parse_extras(&body),
+    ))
+}
+
+/// `attempts` counts the HTTP requests made for the SERP fetch, retries included.
+/// `retry_after` is the blocked response's `Retry-After`, when it carried one.
 #[derive(Debug)]
 pub enum EngineError {
-    Blocked { reason: String },
-    Transient(String),
+    Blocked {
+        reason: String,
+        attempts: u32,
+        retry_after: Option<Duration>,
+    },
+    Transient {
+        message: String,
+        attempts: u32,
+    },
     Fatal(String),
 }
 
+impl EngineError {
+    pub fn attempts(&self) -> u32 {
+        match self {
+            EngineError::Blocked { attempts, .. } | EngineError::Transient { attempts, .. } => {
+                *attempts
+            }
+            EngineError::Fatal(_) => 0,
+        }
+    }
+}
+
 impl std::fmt::Display for EngineError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            EngineError::Blocked { reason } => write!(f, "blocked: {}", reason),
-            EngineError::Transient(e) => write!(f, "transient: {}", e),
-            EngineError::Fatal(e) => write!(f, "fatal: {}", e),
+            EngineError::Blocked { reason, .. } => write!(f, "blocked: {}", reason)?,
+            EngineError::Transient { message, .. } => write!(f, "transient: {}", message)?,
+            EngineError::Fatal(e) => return write!(f, "fatal: {}", e),
+        }
+        match self.attempts() {
+            n if n > 1 => write!(f, " (after {} attempts)", n),
+            _ => Ok(()),
         }
     }
 }
 
 impl std::error::Error for EngineError {}
 
+/// Deepest SERP page we will request; anything beyond is clamped rather than
+/// producing offsets the engines reject or silently ignore.
+pub const MAX_PAGENO: u32 = 50;
+
+/// Normalize a 1-based page number into `1..=MAX_PAGENO`.
+pub fn clamp_pageno(pageno: u32) -> u32 {
+    pageno.clamp(1, MAX_PAGENO)
+}
+
 pub fn detect_block_reason(status: StatusCode, body: &str) -> Option<String> {
     if status == StatusCode::TOO_MANY_REQUESTS {
         return Some("http_429".to_string());
@@ -90,14 +265,18 @@ fn env_truthy(key: &str, default: bool) -> bool {
     }
 }
 
-fn cdp_fallback_enabled() -> bool {
+/// Whether the browser fallback for blocked SERPs is switched on (the default), whether or
+/// not a browser is installed.
+pub fn cdp_fallback_configured() -> bool {
     // Preferred name (native CDP). Keep the legacy env var as an alias for backwards compatibility.
-    let enabled = match std::env::var("SEARCH_CDP_FALLBACK") {
+    match std::env::var("SEARCH_CDP_FALLBACK") {
         Ok(_) => env_truthy("SEARCH_CDP_FALLBACK", true),
         Err(_) => env_truthy("SEARCH_BROWSERLESS_FALLBACK", true),
-    };
+    }
+}
 
-    enabled && crate::scraping::browser_manager::native_browser_available()
+fn cdp_fallback_enabled() -> bool {
+    cdp_fallback_configured() && crate::scraping::browser_manager::native_browser_available()
 }
 
 fn should_simulate_block(engine: &str) -> bool {
@@ -121,25 +300,31 @@ pub async fn fetch_serp_html(
     client: &reqwest::Client,
     url: reqwest::Url,
     engine: &'static str,
+    language: Option<&str>,
 ) -> Result<(StatusCode, String), EngineError> {
     if should_simulate_block(engine) {
         warn!("Simulating blocked engine: {}", engine);
         if !cdp_fallback_enabled() {
             return Err(EngineError::Blocked {
                 reason: "simulated_block".to_string(),
+                attempts: 0,
+                retry_after: None,
             });
         }
     }
 
-    let direct = fetch_html(client, url.clone(), engine)
-        .await
-        .map_err(|e| EngineError::Transient(e.to_string()))?;
+    // Retries share the engine's timeout slice with parsing and any CDP fallback.
+    let deadline = Instant::now() + super::engine_timeout(engine);
+    let (status, body, attempts, retry_after) =
+        fetch_html(client, url.clone(), engine, language, deadline).await?;
 
-    let direct_block = detect_block_reason(direct.0, &direct.1)
+    let direct_block = detect_block_reason(status, &body)
         .or_else(|| should_simulate_block(engine).then_some("simulated_block".to_string()));
 
     if let Some(reason) = direct_block {
-        if cdp_fallback_enabled() {
+        // A replayed block page is replayed as blocked; the browser would go to the network.
+        let replaying = Fixtures::from_env().is_some_and(|f| f.replays());
+        if cdp_fallback_enabled() && !replaying {
             warn!(
                 "Engine {} looks blocked ({}); trying native CDP fallback",
                 engine, reason
@@ -148,48 +333,669 @@ pub async fn fetch_serp_html(
             let (status_u16, html) = scraper
                 .fetch_html_with_browserless(url.as_str(), None)
                 .await
-                .map_err(|e| EngineError::Transient(e.to_string()))?;
+                .map_err(|e| EngineError::Transient {
+                    message: e.to_string(),
+                    attempts: attempts + 1,
+                })?;
 
             let status = StatusCode::from_u16(status_u16).unwrap_or(StatusCode::OK);
             if let Some(reason2) = detect_block_reason(status, &html) {
                 return Err(EngineError::Blocked {
                     reason: format!("{}; cdp:{}", reason, reason2),
+                    attempts: attempts + 1,
+                    retry_after,
                 });
             }
 
             return Ok((status, html));
         }
 
-        return Err(EngineError::Blocked { reason });
+        return Err(EngineError::Blocked {
+            reason,
+            attempts,
+            retry_after,
+        });
     }
 
-    Ok(direct)
+    Ok((status, body))
 }
 
+/// Fetch a SERP, retrying transient failures (and 429/503 answers) per
+/// [`RetryPolicy::from_env`] while the next attempt can still start before `deadline`.
+/// A requested `language` replaces `SEARCH_ACCEPT_LANGUAGE` as the `Accept-Language` header.
+/// Returns the final status, body, number of attempts and the response's `Retry-After`.
 pub async fn fetch_html(
     client: &reqwest::Client,
     url: reqwest::Url,
     engine: &str,
-) -> Result<(StatusCode, String)> {
+    language: Option<&str>,
+    deadline: Instant,
+) -> Result<(StatusCode, String, u32, Option<Duration>), EngineError> {
+    let fixtures = Fixtures::from_env();
+    if let Some(fixtures) = fixtures.as_ref().filter(|f| f.replays()) {
+        let replayed = fixtures.load(url.as_str()).and_then(|recorded| {
+            let retry_after = recorded
+                .headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("retry-after"))
+                .and_then(|(_, value)| parse_retry_after(value, SystemTime::now()));
+            Ok((recorded.status, recorded.body_text()?, retry_after))
+        });
+        let (status, body, retry_after) =
+            replayed.map_err(|e| EngineError::Fatal(e.to_string()))?;
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+        return Ok((status, body, 1, retry_after));
+    }
+
+    crate::scraping::domain_policy::check(url.as_str())
+        .await
+        .map_err(|e| EngineError::Fatal(e.to_string()))?;
     crate::host_guard::wait_for_search_engine(engine).await;
 
-    let user_agent = crate::antibot::get_random_user_agent();
-    let mut req = client
-        .get(url)
-        .header("User-Agent", user_agent)
-        .header("Accept", "text/html,application/xhtml+xml")
-        .header(
-            "Accept-Language",
-            std::env::var("SEARCH_ACCEPT_LANGUAGE").unwrap_or_else(|_| "en-US,en;q=0.9".into()),
-        );
+    let accept_language = match language {
+        Some(language) => format!("{},{};q=0.9", language.trim(), primary_subtag(language)),
+        None => std::env::var("SEARCH_ACCEPT_LANGUAGE").unwrap_or_else(|_| "en-US,en;q=0.9".into()),
+    };
+    let outcome = RetryPolicy::from_env()
+        .send(Some(deadline), || {
+            crate::core::http_client::note_request();
+            let mut req = client
+                .get(url.clone())
+                .header("User-Agent", crate::antibot::get_random_user_agent())
+                .header("Accept", "text/html,application/xhtml+xml")
+                .header("Accept-Language", accept_language.as_str());
 
-    for (k, v) in crate::antibot::get_stealth_headers() {
-        req = req.header(k, v);
-    }
+            for (k, v) in crate::antibot::get_stealth_headers() {
+                req = req.header(k, v);
+            }
+            req
+        })
+        .await;
 
-    let resp = req.send().await?;
+    let attempts = outcome.attempts;
+    let resp = outcome.result.map_err(|e| EngineError::Transient {
+        message: e.to_string(),
+        attempts,
+    })?;
+    if attempts > 1 {
+        debug!("engine {} answered after {} attempts", engine, attempts);
+    }
 
     let status = resp.status();
+    let headers = header_pairs(resp.headers());
+    let retry_after = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| parse_retry_after(v, SystemTime::now()));
     let body = resp.text().await.unwrap_or_default();
-    Ok((status, body))
+    if let Some(fixtures) = &fixtures {
+        let recorded =
+            RecordedResponse::new(url.as_str(), status.as_u16(), headers, body.as_bytes());
+        if let Err(e) = fixtures.save(&recorded) {
+            warn!("failed to record fixture for {}: {}", url, e);
+        }
+    }
+    Ok((status, body, attempts, retry_after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(url: &reqwest::Url, key: &str) -> Option<String> {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    }
+
+    #[test]
+    fn google_url_uses_start_offset() {
+        let p1 = google::build_search_url("rust", 10, 1, None).unwrap();
+        let p2 = google::build_search_url("rust", 10, 2, None).unwrap();
+        let p5 = google::build_search_url("rust", 10, 5, None).unwrap();
+        assert_eq!(param(&p1, "start"), None);
+        assert_eq!(param(&p2, "start").as_deref(), Some("10"));
+        assert_eq!(param(&p5, "start").as_deref(), Some("40"));
+    }
+
+    #[test]
+    fn bing_url_uses_first_offset() {
+        let p1 = bing::build_search_url("rust", 1, None).unwrap();
+        let p2 = bing::build_search_url("rust", 2, None).unwrap();
+        let p5 = bing::build_search_url("rust", 5, None).unwrap();
+        assert_eq!(param(&p1, "first"), None);
+        assert_eq!(param(&p2, "first").as_deref(), Some("11"));
+        assert_eq!(param(&p5, "first").as_deref(), Some("41"));
+    }
+
+    #[test]
+    fn brave_url_uses_page_offset() {
+        let p1 = brave::build_search_url("rust", 1).unwrap();
+        let p2 = brave::build_search_url("rust", 2).unwrap();
+        let p5 = brave::build_search_url("rust", 5).unwrap();
+        assert_eq!(param(&p1, "offset"), None);
+        assert_eq!(param(&p2, "offset").as_deref(), Some("1"));
+        assert_eq!(param(&p5, "offset").as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn duckduckgo_url_uses_s_offset() {
+        let p1 = duckduckgo::build_search_url("rust", 1, None).unwrap();
+        let p2 = duckduckgo::build_search_url("rust", 2, None).unwrap();
+        let p5 = duckduckgo::build_search_url("rust", 5, None).unwrap();
+        assert_eq!(param(&p1, "s"), None);
+        assert_eq!(param(&p2, "s").as_deref(), Some("30"));
+        assert_eq!(param(&p5, "s").as_deref(), Some("180"));
+    }
+
+    #[test]
+    fn mojeek_url_and_results() {
+        let p1 = mojeek::build_search_url("rust", 1, Some("de-AT")).unwrap();
+        let p3 = mojeek::build_search_url("rust", 3, None).unwrap();
+        assert_eq!(param(&p1, "s"), None);
+        assert_eq!(param(&p1, "lb").as_deref(), Some("de"));
+        assert_eq!(param(&p3, "s").as_deref(), Some("21"));
+
+        let html = r#"<ul class="results-standard">
+            <li class="r1">
+              <a class="ob" href="https://www.rust-lang.org/"><p class="i">www.rust-lang.org</p></a>
+              <h2><a class="title" href="https://www.rust-lang.org/">Rust  Programming Language</a></h2>
+              <p class="s">A language empowering everyone to build reliable and efficient software.</p>
+            </li>
+            <li class="r2"><h2><a class="title" href="/relative">Skipped</a></h2></li>
+        </ul>"#;
+        let results = mojeek::parse_results(html, 10);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].title, "Rust Programming Language");
+        assert!(results[0].content.starts_with("A language empowering"));
+        assert_eq!(results[0].engine.as_deref(), Some("mojeek"));
+    }
+
+    /// (url, title, content, published_at) of each parsed result.
+    fn summarize(results: &[SearchResult]) -> Vec<(&str, &str, &str, Option<&str>)> {
+        results
+            .iter()
+            .map(|r| {
+                (
+                    r.url.as_str(),
+                    r.title.as_str(),
+                    r.content.as_str(),
+                    r.published_at.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn google_fixture_parses_organic_results_and_top_answer() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/google.html"
+        ));
+        let results = google::parse_results(html, 10);
+        assert_eq!(
+            summarize(&results),
+            vec![
+                (
+                    "https://tokio.rs/tokio/tutorial",
+                    "Tutorial | Tokio - An asynchronous Rust runtime",
+                    "Tokio is an asynchronous runtime for the Rust programming language. It provides the building blocks needed for writing network applications.",
+                    None,
+                ),
+                (
+                    "https://docs.rs/async-std/latest/async_std/",
+                    "async_std - Rust",
+                    "Async version of the Rust standard library with familiar APIs.",
+                    Some("Jan 10, 2024"),
+                ),
+                (
+                    "https://github.com/smol-rs/smol",
+                    "smol-rs/smol: A small and fast async runtime for Rust",
+                    "A small and fast async runtime. This crate simply re-exports other smaller async crates.",
+                    None,
+                ),
+            ]
+        );
+        assert!(results[0]
+            .top_answer
+            .as_deref()
+            .is_some_and(|a| a.starts_with("Tokio is an event-driven")));
+        assert!(results[1].top_answer.is_none());
+        assert_eq!(
+            results[0].breadcrumbs,
+            vec!["tokio.rs", "tokio", "tutorial"]
+        );
+        assert_eq!(results[1].source_type.as_deref(), Some("docs"));
+        assert_eq!(results[2].source_type.as_deref(), Some("repo"));
+        assert_eq!(google::parse_results(html, 1).len(), 1);
+    }
+
+    #[test]
+    fn bing_fixture_decodes_click_links_and_fact_rows() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/bing.html"
+        ));
+        let results = bing::parse_results(html, 10);
+        assert_eq!(
+            summarize(&results),
+            vec![
+                (
+                    "https://tokio.rs/blog",
+                    "Blog | Tokio - An asynchronous Rust runtime",
+                    "Tokio is an asynchronous runtime for the Rust programming language. It provides the building blocks needed for writing networking applications.",
+                    None,
+                ),
+                (
+                    "https://rust-lang.github.io/async-book/",
+                    "Asynchronous Programming in Rust",
+                    "Getting started with async/await in Rust, futures, executors and tasks.",
+                    Some("2024-03-02"),
+                ),
+                (
+                    "https://stackoverflow.com/questions/60561573/which-async-runtime",
+                    "Which async runtime should I use in Rust?",
+                    "Compare tokio, async-std and smol for a web service.",
+                    None,
+                ),
+            ]
+        );
+        assert_eq!(
+            results[0].rich_snippet.as_deref(),
+            Some("Latest version: 1.49 License: MIT")
+        );
+        assert_eq!(results[2].source_type.as_deref(), Some("qa"));
+        assert!(results.iter().all(|r| r.engine.as_deref() == Some("bing")));
+    }
+
+    #[test]
+    fn duckduckgo_fixture_unwraps_redirect_links() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/duckduckgo.html"
+        ));
+        let results = duckduckgo::parse_results(html, 10);
+        assert_eq!(
+            summarize(&results),
+            vec![
+                (
+                    "https://tokio.rs/tokio/tutorial",
+                    "Tutorial | Tokio - An asynchronous Rust runtime",
+                    "Tokio is an asynchronous runtime for the Rust programming language.",
+                    None,
+                ),
+                (
+                    "https://blog.example.dev/posts/async-runtimes",
+                    "Comparing async runtimes",
+                    "Benchmarks of tokio, smol and glommio under load.",
+                    Some("Mar 5, 2025"),
+                ),
+            ]
+        );
+        assert_eq!(results[1].source_type.as_deref(), Some("blog"));
+        assert_eq!(duckduckgo::parse_extras(html), SerpExtras::default());
+    }
+
+    #[test]
+    fn duckduckgo_definition_fixture_yields_answer_and_related_searches() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/duckduckgo_definition.html"
+        ));
+        let extras = lookup("duckduckgo").unwrap().parse_extras(html);
+        assert_eq!(
+            extras.answers,
+            ["Serendipity: noun The occurrence and development of events by chance in a happy or beneficial way. (https://en.wiktionary.org/wiki/serendipity)"]
+        );
+        assert_eq!(
+            extras.suggestions,
+            ["serendipity synonym", "serendipity origin"]
+        );
+        assert!(extras.corrections.is_empty());
+        assert_eq!(duckduckgo::parse_results(html, 10).len(), 1);
+    }
+
+    #[test]
+    fn duckduckgo_conversion_fixture_yields_answer_and_correction() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/duckduckgo_conversion.html"
+        ));
+        let extras = duckduckgo::parse_extras(html);
+        assert_eq!(extras.answers, ["10 miles = 16.09344 kilometers"]);
+        assert_eq!(extras.corrections, ["10 miles in km"]);
+        assert!(extras.suggestions.is_empty());
+        // Engines without an extras parser report nothing.
+        assert_eq!(
+            lookup("mojeek").unwrap().parse_extras(html),
+            SerpExtras::default()
+        );
+    }
+
+    #[test]
+    fn brave_fixture_reads_anchors_under_main_only() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/brave.html"
+        ));
+        let results = brave::parse_results(html, 10);
+        assert_eq!(
+            summarize(&results),
+            vec![
+                (
+                    "https://tokio.rs/",
+                    "Tokio - An asynchronous Rust runtime",
+                    "Tokio is an asynchronous runtime for the Rust programming language, built for reliable network applications.",
+                    None,
+                ),
+                (
+                    "https://crates.io/crates/smol",
+                    "smol - crates.io: Rust Package Registry",
+                    "A small and fast async runtime for Rust.",
+                    Some("2025-01-20"),
+                ),
+            ]
+        );
+    }
+
+    /// `published_at` for a card printed as `age` before now.
+    fn aged(age: chrono::Duration) -> String {
+        (chrono::Utc::now() - age).format("%Y-%m-%d").to_string()
+    }
+
+    #[test]
+    fn bing_news_fixture_reads_carousel_cards_and_entity_pane() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/bing_news.html"
+        ));
+        let results = bing::parse_results(html, 10);
+        let two_days_ago = aged(chrono::Duration::days(2));
+        assert_eq!(
+            summarize(&results),
+            vec![
+                (
+                    "https://www.phoronix.com/news/Tokio-1.50-Released",
+                    "Tokio 1.50 Released With io_uring File Operations",
+                    "The Tokio async runtime for Rust is out with version 1.50, bringing io_uring backed file operations on Linux.",
+                    Some(two_days_ago.as_str()),
+                ),
+                (
+                    "https://www.theregister.com/2025/03/04/rust_async_runtime/",
+                    "Rust's async runtime story keeps getting better",
+                    "Maintainers say the new release closes the gap with blocking file APIs.",
+                    Some("Mar 4, 2025"),
+                ),
+                (
+                    "https://github.com/tokio-rs/tokio/releases/tag/tokio-1.50.0",
+                    "Release Tokio v1.50.0 · tokio-rs/tokio · GitHub",
+                    "1.50.0 (March 3rd, 2025). Added: fs: io_uring support for File::open and read.",
+                    None,
+                ),
+            ]
+        );
+        assert_eq!(
+            results[0].rich_snippet.as_deref(),
+            Some("Phoronix · 2 days ago")
+        );
+        assert!(results.iter().all(|r| {
+            r.engine_source.as_deref() == Some("bing") && r.engine_sources == ["bing"]
+        }));
+        assert_eq!(
+            bing::parse_extras(html).answers,
+            ["Tokio (Software library): Tokio is an event-driven, non-blocking I/O platform for writing asynchronous applications with the Rust programming language. (https://en.wikipedia.org/wiki/Tokio_(software))"]
+        );
+        assert_eq!(bing::parse_results(html, 1).len(), 1);
+    }
+
+    #[test]
+    fn bing_video_fixture_maps_cards_to_watch_pages() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/bing_videos.html"
+        ));
+        let results = bing::parse_results(html, 10);
+        let three_weeks_ago = aged(chrono::Duration::weeks(3));
+        assert_eq!(
+            summarize(&results),
+            vec![
+                (
+                    "https://tokio.rs/tokio/tutorial",
+                    "Tutorial | Tokio - An asynchronous Rust runtime",
+                    "Tokio is an asynchronous runtime for the Rust programming language.",
+                    None,
+                ),
+                (
+                    "https://www.youtube.com/watch?v=o2ob8zkeq2s",
+                    "Decrusting the tokio crate",
+                    "152K views · Mar 3, 2025 · YouTube · Jon Gjengset",
+                    Some("Mar 3, 2025"),
+                ),
+                (
+                    "https://www.infoq.com/presentations/async-rust-production/",
+                    "Async Rust in production",
+                    "8.1K views · 3 weeks ago · InfoQ · Carl Lerche",
+                    Some(three_weeks_ago.as_str()),
+                ),
+            ]
+        );
+        assert_ne!(results[0].source_type.as_deref(), Some("video"));
+        assert!(results[1..]
+            .iter()
+            .all(|r| r.source_type.as_deref() == Some("video")));
+        assert_eq!(results[1].rich_snippet.as_deref(), Some("Video · 1:45:03"));
+        assert_eq!(bing::parse_extras(html), SerpExtras::default());
+    }
+
+    #[test]
+    fn brave_news_fixture_reads_carousel_cards() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/brave_news.html"
+        ));
+        let results = brave::parse_results(html, 10);
+        let five_hours_ago = aged(chrono::Duration::hours(5));
+        assert_eq!(
+            summarize(&results),
+            vec![
+                (
+                    "https://www.phoronix.com/news/Tokio-1.50-Released",
+                    "Tokio 1.50 Released With io_uring File Operations",
+                    "Phoronix",
+                    Some(five_hours_ago.as_str()),
+                ),
+                (
+                    "https://www.theregister.com/2025/03/04/rust_async_runtime/",
+                    "Rust's async runtime story keeps getting better",
+                    "Maintainers say the new release closes the gap with blocking file APIs.",
+                    Some("2025-03-04"),
+                ),
+                (
+                    "https://github.com/tokio-rs/tokio/releases/tag/tokio-1.50.0",
+                    "Release Tokio v1.50.0 · tokio-rs/tokio",
+                    "Added: fs: io_uring support for File::open and read.",
+                    None,
+                ),
+            ]
+        );
+        assert_eq!(results[1].rich_snippet.as_deref(), Some("The Register"));
+        assert!(results.iter().all(|r| {
+            r.engine_source.as_deref() == Some("brave") && r.engine_sources == ["brave"]
+        }));
+        assert_eq!(brave::parse_extras(html), SerpExtras::default());
+    }
+
+    #[test]
+    fn brave_video_fixture_reads_cards_and_infobox() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/brave_videos.html"
+        ));
+        let results = brave::parse_results(html, 10);
+        let three_weeks_ago = aged(chrono::Duration::weeks(3));
+        assert_eq!(
+            summarize(&results),
+            vec![
+                (
+                    "https://www.youtube.com/watch?v=o2ob8zkeq2s",
+                    "Decrusting the tokio crate",
+                    "YouTube · Jon Gjengset · 1:45:03",
+                    Some("Mar 3, 2025"),
+                ),
+                (
+                    "https://www.infoq.com/presentations/async-rust-production/",
+                    "Async Rust in production",
+                    "InfoQ · Carl Lerche · 32:10",
+                    Some(three_weeks_ago.as_str()),
+                ),
+                (
+                    "https://tokio.rs/tokio/tutorial",
+                    "Tutorial | Tokio - An asynchronous Rust runtime",
+                    "Tokio is an asynchronous runtime for the Rust programming language.",
+                    None,
+                ),
+            ]
+        );
+        assert!(results[..2]
+            .iter()
+            .all(|r| r.source_type.as_deref() == Some("video")));
+        assert_ne!(results[2].source_type.as_deref(), Some("video"));
+        assert_eq!(
+            lookup("brave").unwrap().parse_extras(html).answers,
+            ["Tokio (Rust library): Tokio is an event-driven, non-blocking I/O platform for writing asynchronous applications with the Rust programming language. (https://en.wikipedia.org/wiki/Tokio_(software))"]
+        );
+    }
+
+    #[test]
+    fn mojeek_fixture_parses_standard_results() {
+        let html = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/serp/mojeek.html"
+        ));
+        let results = mojeek::parse_results(html, 10);
+        assert_eq!(
+            summarize(&results),
+            vec![
+                (
+                    "https://tokio.rs/",
+                    "Tokio - An asynchronous Rust runtime",
+                    "Tokio is an asynchronous runtime for the Rust programming language.",
+                    None,
+                ),
+                (
+                    "https://www.reddit.com/r/rust/comments/xyz/async_runtimes/",
+                    "Which async runtime do you use? : r/rust",
+                    "Most of the ecosystem targets tokio, but smol is fine for small tools.",
+                    None,
+                ),
+            ]
+        );
+        assert_eq!(results[1].source_type.as_deref(), Some("blog"));
+    }
+
+    #[test]
+    fn absurd_page_numbers_are_clamped() {
+        assert_eq!(clamp_pageno(0), 1);
+        assert_eq!(clamp_pageno(10_000), MAX_PAGENO);
+
+        let far = bing::build_search_url("rust", 10_000, None).unwrap();
+        let max = bing::build_search_url("rust", MAX_PAGENO, None).unwrap();
+        assert_eq!(param(&far, "first"), param(&max, "first"));
+
+        let google = google::build_search_url("rust", 10, u32::MAX, None).unwrap();
+        assert_eq!(param(&google, "start").as_deref(), Some("490"));
+    }
+
+    #[test]
+    fn language_is_passed_to_engines() {
+        let google_default = google::build_search_url("rust", 10, 1, None).unwrap();
+        assert_eq!(param(&google_default, "hl").as_deref(), Some("en"));
+        assert_eq!(param(&google_default, "lr"), None);
+        let google = google::build_search_url("rust", 10, 1, Some("pt-BR")).unwrap();
+        assert_eq!(param(&google, "hl").as_deref(), Some("pt-BR"));
+        assert_eq!(param(&google, "lr").as_deref(), Some("lang_pt"));
+
+        let bing = bing::build_search_url("rust", 1, Some("de")).unwrap();
+        assert_eq!(param(&bing, "setlang").as_deref(), Some("de"));
+
+        let ddg = duckduckgo::build_search_url("rust", 1, Some("en-US")).unwrap();
+        assert_eq!(param(&ddg, "kl").as_deref(), Some("us-en"));
+        let ddg_bare = duckduckgo::build_search_url("rust", 1, Some("en")).unwrap();
+        assert_eq!(param(&ddg_bare, "kl"), None);
+    }
+
+    #[test]
+    fn registry_resolves_names_and_aliases() {
+        assert_eq!(lookup("DDG").map(|e| e.name()), Some("duckduckgo"));
+        assert_eq!(lookup(" mojeek ").map(|e| e.name()), Some("mojeek"));
+        assert!(lookup("altavista").is_none());
+
+        let mut names: Vec<_> = REGISTRY.iter().map(|e| e.name()).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), REGISTRY.len(), "engine names must be unique");
+
+        let url = lookup("google")
+            .unwrap()
+            .build_url("rust", 10, 2, None)
+            .unwrap();
+        assert_eq!(param(&url, "start").as_deref(), Some("10"));
+    }
+
+    #[test]
+    fn time_range_reaches_engine_urls() {
+        let filtered = |name: &str, range: TimeRange| {
+            let engine = lookup(name).unwrap();
+            let mut url = engine.build_url("rust", 10, 1, None).unwrap();
+            assert!(apply_time_range(engine, &mut url, range), "{}", name);
+            url
+        };
+        assert_eq!(
+            param(&filtered("google", TimeRange::Week), "tbs").as_deref(),
+            Some("qdr:w")
+        );
+        assert_eq!(
+            param(&filtered("bing", TimeRange::Week), "filters").as_deref(),
+            Some(r#"ex1:"ez2""#)
+        );
+        assert_eq!(
+            param(&filtered("brave", TimeRange::Day), "tf").as_deref(),
+            Some("pd")
+        );
+        assert_eq!(
+            param(&filtered("ddg", TimeRange::Month), "df").as_deref(),
+            Some("m")
+        );
+
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert_eq!(
+            bing::time_filter(TimeRange::Year, today),
+            r#"ex1:"ez5_19358_19723""#
+        );
+
+        let mojeek = lookup("mojeek").unwrap();
+        let mut url = mojeek.build_url("rust", 10, 1, None).unwrap();
+        let before = url.clone();
+        assert!(!apply_time_range(mojeek, &mut url, TimeRange::Year));
+        assert_eq!(url, before);
+    }
+
+    #[test]
+    fn engine_error_reports_retries() {
+        let once = EngineError::Transient {
+            message: "connection reset".to_string(),
+            attempts: 1,
+        };
+        assert_eq!(once.to_string(), "transient: connection reset");
+
+        let retried = EngineError::Blocked {
+            reason: "http_503".to_string(),
+            attempts: 3,
+            retry_after: None,
+        };
+        assert_eq!(retried.attempts(), 3);
+        assert_eq!(retried.to_string(), "blocked: http_503 (after 3 attempts)");
+    }
 }
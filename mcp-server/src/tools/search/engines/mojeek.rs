@@ -0,0 +1,115 @@
+use crate::tools::search::language::primary_subtag;
+use crate::types::SearchResult;
+use scraper::{Html, Selector};
+use std::time::Duration;
+
+use super::{clamp_pageno, EngineError, SearchEngine};
+
+/// Mojeek runs its own crawler and index, so its results do not overlap with the
+/// Bing-backed engines, and its plain HTML SERP rarely challenges automated clients.
+pub struct Mojeek;
+
+impl SearchEngine for Mojeek {
+    fn name(&self) -> &'static str {
+        "mojeek"
+    }
+
+    fn build_url(
+        &self,
+        query: &str,
+        _max_results: usize,
+        pageno: u32,
+        language: Option<&str>,
+    ) -> Result<reqwest::Url, EngineError> {
+        build_search_url(query, pageno, language)
+    }
+
+    fn parse_results(&self, html: &str, max_results: usize) -> Vec<SearchResult> {
+        parse_results(html, max_results)
+    }
+
+    fn default_timeout(&self) -> Duration {
+        Duration::from_millis(3_500)
+    }
+}
+
+pub fn parse_results(html: &str, max_results: usize) -> Vec<SearchResult> {
+    let doc = Html::parse_document(html);
+    let sel_item = Selector::parse("ul.results-standard > li").unwrap();
+    let sel_link = Selector::parse("h2 a.title, h2 a").unwrap();
+    let sel_snip = Selector::parse("p.s").unwrap();
+
+    let mut out = Vec::new();
+    for item in doc.select(&sel_item) {
+        if out.len() >= max_results {
+            break;
+        }
+        let Some(link) = item.select(&sel_link).next() else {
+            continue;
+        };
+        let href = link.value().attr("href").unwrap_or("").trim().to_string();
+        if !(href.starts_with("http://") || href.starts_with("https://")) {
+            continue;
+        }
+        let title = link.text().collect::<Vec<_>>().join(" ");
+        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        if title.is_empty() {
+            continue;
+        }
+        let snippet_raw = item
+            .select(&sel_snip)
+            .next()
+            .map(|p| p.text().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+        let snippet_raw = snippet_raw.split_whitespace().collect::<Vec<_>>().join(" ");
+        let (published_prefix, snippet) = crate::tools::search::split_date_prefix(&snippet_raw);
+
+        let published_at = published_prefix
+            .or_else(|| crate::tools::search::extract_published_at_from_text(&snippet_raw));
+        let breadcrumbs = crate::tools::search::breadcrumbs_from_url(&href);
+        let (domain, source_type) = crate::tools::search::classify_search_result(&href);
+        out.push(SearchResult {
+            url: href,
+            title,
+            content: snippet,
+            engine: Some("mojeek".to_string()),
+            engine_source: Some("mojeek".to_string()),
+            engine_sources: vec!["mojeek".to_string()],
+            score: None,
+            published_at,
+            breadcrumbs,
+            rich_snippet: None,
+            top_answer: None,
+            domain,
+            source_type: Some(source_type),
+            detected_language: None,
+            title_highlights: None,
+            content_highlights: None,
+        });
+    }
+
+    out
+}
+
+pub fn build_search_url(
+    query: &str,
+    pageno: u32,
+    language: Option<&str>,
+) -> Result<reqwest::Url, EngineError> {
+    let mut url = reqwest::Url::parse("https://www.mojeek.com/search")
+        .map_err(|e| EngineError::Fatal(e.to_string()))?;
+    {
+        let mut qp = url.query_pairs_mut();
+        qp.append_pair("q", query);
+        // `s` is the 1-based index of the first result (10 per page).
+        let page_index = clamp_pageno(pageno) - 1;
+        if page_index > 0 {
+            qp.append_pair("s", &(page_index * 10 + 1).to_string());
+        }
+        // `lb` biases ranking towards a language rather than filtering by it.
+        if let Some(language) = language {
+            qp.append_pair("lb", &primary_subtag(language));
+        }
+    }
+    Ok(url)
+}
@@ -0,0 +1,349 @@
+//! Caller-supplied domain and URL filters for search results.
+//!
+//! `include_domains` keeps only results on the listed hosts, `exclude_domains` drops
+//! results on them and `exclude_url_patterns` drops URLs matching any of the regexes.
+//! A domain entry `example.com` matches that host with or without `www.`;
+//! `*.example.com` also matches every subdomain. The `filetype` and published-date
+//! window set by query operators (see `operators`) are enforced here too, for engines
+//! that ignored them. The filters run on the merged list after dedup and before
+//! reranking, so `max_results` is filled from usable results.
+
+use super::SearchParamOverrides;
+use crate::core::error::ScoutError;
+use crate::scraping::domain_policy::HostPattern;
+use crate::types::SearchResult;
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::Serialize;
+use url::Url;
+
+/// How many merged results each filter removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FilteredCounts {
+    /// Results on a host outside `include_domains`.
+    pub not_included: usize,
+    pub excluded_domains: usize,
+    pub excluded_patterns: usize,
+    /// Results whose URL does not end in the `filetype` extension.
+    pub wrong_filetype: usize,
+    /// Dated results outside the `after:` / `before:` window.
+    pub outside_date_window: usize,
+}
+
+impl FilteredCounts {
+    pub fn total(&self) -> usize {
+        self.not_included
+            + self.excluded_domains
+            + self.excluded_patterns
+            + self.wrong_filetype
+            + self.outside_date_window
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ResultFilters {
+    include: Vec<HostPattern>,
+    exclude: Vec<HostPattern>,
+    url_patterns: Vec<Regex>,
+    filetype: Option<String>,
+    published_after: Option<NaiveDate>,
+    published_before: Option<NaiveDate>,
+}
+
+fn parse_domain(entry: &str) -> Result<HostPattern, ScoutError> {
+    let trimmed = entry.trim();
+    // Tolerate a pasted URL: keep only its host.
+    let host = match Url::parse(trimmed) {
+        Ok(url) if trimmed.contains("://") => url.host_str().unwrap_or_default().to_string(),
+        _ => trimmed.to_string(),
+    };
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    HostPattern::parse(host)
+        .ok_or_else(|| ScoutError::invalid_input(format!("Invalid domain filter '{}'", entry)))
+}
+
+/// A result's host, lowercased and without a leading `www.`.
+fn result_host(url: &str) -> Option<String> {
+    let host = Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+    let host = host.trim_end_matches('.');
+    Some(host.strip_prefix("www.").unwrap_or(host).to_string())
+}
+
+impl ResultFilters {
+    /// `None` when no filter is set; an unparsable domain or regex is `invalid_input`.
+    pub fn new(
+        include_domains: &[String],
+        exclude_domains: &[String],
+        exclude_url_patterns: &[String],
+    ) -> Result<Option<Self>, ScoutError> {
+        let non_empty = |list: &[String]| -> Vec<String> {
+            list.iter()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+        let include = non_empty(include_domains);
+        let exclude = non_empty(exclude_domains);
+        let patterns = non_empty(exclude_url_patterns);
+        if include.is_empty() && exclude.is_empty() && patterns.is_empty() {
+            return Ok(None);
+        }
+
+        let url_patterns = patterns
+            .iter()
+            .map(|raw| {
+                Regex::new(raw).map_err(|e| {
+                    ScoutError::invalid_input(format!(
+                        "Invalid exclude_url_patterns entry '{}': {}",
+                        raw, e
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Self {
+            include: include
+                .iter()
+                .map(|d| parse_domain(d))
+                .collect::<Result<_, _>>()?,
+            exclude: exclude
+                .iter()
+                .map(|d| parse_domain(d))
+                .collect::<Result<_, _>>()?,
+            url_patterns,
+            ..Default::default()
+        }))
+    }
+
+    pub fn from_overrides(overrides: &SearchParamOverrides) -> Result<Option<Self>, ScoutError> {
+        let filters = Self::new(
+            &overrides.include_domains,
+            &overrides.exclude_domains,
+            &overrides.exclude_url_patterns,
+        )?;
+        let filetype = overrides
+            .filetype
+            .as_deref()
+            .map(|ext| ext.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|ext| !ext.is_empty());
+        if filetype.is_none()
+            && overrides.published_after.is_none()
+            && overrides.published_before.is_none()
+        {
+            return Ok(filters);
+        }
+        Ok(Some(Self {
+            filetype,
+            published_after: overrides.published_after,
+            published_before: overrides.published_before,
+            ..filters.unwrap_or_default()
+        }))
+    }
+
+    fn wrong_filetype(&self, url: &str) -> bool {
+        let Some(ext) = &self.filetype else {
+            return false;
+        };
+        let path = Url::parse(url)
+            .map(|u| u.path().to_ascii_lowercase())
+            .unwrap_or_default();
+        !path.ends_with(&format!(".{}", ext))
+    }
+
+    fn outside_date_window(&self, published_at: Option<&str>) -> bool {
+        let Some(date) = published_at.and_then(super::parse_published_date) else {
+            return false;
+        };
+        self.published_after.is_some_and(|after| date < after)
+            || self.published_before.is_some_and(|before| date >= before)
+    }
+
+    /// Drop filtered results, keeping the order of the rest. A result is counted once,
+    /// under the first filter that removes it (include, exclude, patterns, filetype,
+    /// then dates).
+    pub fn apply(&self, results: Vec<SearchResult>) -> (Vec<SearchResult>, FilteredCounts) {
+        let mut counts = FilteredCounts::default();
+        let kept = results
+            .into_iter()
+            .filter(|result| {
+                let host = result_host(&result.url);
+                let on = |patterns: &[HostPattern]| {
+                    host.as_deref()
+                        .is_some_and(|h| patterns.iter().any(|p| p.matches(h)))
+                };
+                if !self.include.is_empty() && !on(&self.include) {
+                    counts.not_included += 1;
+                    false
+                } else if on(&self.exclude) {
+                    counts.excluded_domains += 1;
+                    false
+                } else if self.url_patterns.iter().any(|re| re.is_match(&result.url)) {
+                    counts.excluded_patterns += 1;
+                    false
+                } else if self.wrong_filetype(&result.url) {
+                    counts.wrong_filetype += 1;
+                    false
+                } else if self.outside_date_window(result.published_at.as_deref()) {
+                    counts.outside_date_window += 1;
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect();
+        (kept, counts)
+    }
+}
+
+/// The `site:` operator worth adding to the engine query when `include_domains` names a
+/// single domain, so engines spend their result slots on it.
+pub fn site_operator(include_domains: &[String]) -> Option<String> {
+    let mut domains = include_domains.iter().filter(|d| !d.trim().is_empty());
+    let only = domains.next()?;
+    if domains.next().is_some() {
+        return None;
+    }
+    let pattern = parse_domain(only).ok()?;
+    Some(format!("site:{}", pattern.host()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str) -> SearchResult {
+        SearchResult {
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn urls(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.url.as_str()).collect()
+    }
+
+    #[test]
+    fn domain_filters_match_www_and_wildcard_subdomains() {
+        let filters = ResultFilters::new(
+            &[],
+            &["nytimes.com".to_string(), "*.contentfarm.io".to_string()],
+            &[],
+        )
+        .unwrap()
+        .unwrap();
+        let (kept, counts) = filters.apply(vec![
+            result("https://www.nytimes.com/2026/01/article.html"),
+            result("https://cooking.nytimes.com/recipes/1"),
+            result("https://a.b.contentfarm.io/post"),
+            result("https://contentfarm.io/"),
+            result("https://docs.rs/regex"),
+        ]);
+        assert_eq!(
+            urls(&kept),
+            [
+                "https://cooking.nytimes.com/recipes/1",
+                "https://docs.rs/regex"
+            ],
+            "a bare domain does not cover other subdomains"
+        );
+        assert_eq!(counts.excluded_domains, 3);
+        assert_eq!(counts.total(), 3);
+    }
+
+    #[test]
+    fn include_exclude_and_patterns_are_counted_separately() {
+        let filters = ResultFilters::new(
+            &[
+                "*.rust-lang.org".to_string(),
+                "https://docs.rs/".to_string(),
+            ],
+            &["users.rust-lang.org".to_string()],
+            &[r"/print\.html$".to_string()],
+        )
+        .unwrap()
+        .unwrap();
+        let (kept, counts) = filters.apply(vec![
+            result("https://doc.rust-lang.org/book/ch01.html"),
+            result("https://doc.rust-lang.org/book/print.html"),
+            result("https://users.rust-lang.org/t/1"),
+            result("https://docs.rs/tokio"),
+            result("https://medium.com/@someone/rust"),
+            result("not a url"),
+        ]);
+        assert_eq!(
+            urls(&kept),
+            [
+                "https://doc.rust-lang.org/book/ch01.html",
+                "https://docs.rs/tokio"
+            ]
+        );
+        assert_eq!(
+            counts,
+            FilteredCounts {
+                not_included: 2,
+                excluded_domains: 1,
+                excluded_patterns: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn filetype_and_date_window_filter_what_engines_ignored() {
+        let date = |raw: &str| NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok();
+        let filters = ResultFilters::from_overrides(&SearchParamOverrides {
+            filetype: Some("PDF".to_string()),
+            published_after: date("2023-01-01"),
+            published_before: date("2024-01-01"),
+            ..Default::default()
+        })
+        .unwrap()
+        .unwrap();
+        let dated = |url: &str, published_at: Option<&str>| SearchResult {
+            published_at: published_at.map(str::to_string),
+            ..result(url)
+        };
+        let (kept, counts) = filters.apply(vec![
+            dated(
+                "https://example.com/paper.PDF?download=1",
+                Some("2023-05-02"),
+            ),
+            dated("https://example.com/undated.pdf", None),
+            dated("https://example.com/paper.html", Some("2023-05-02")),
+            dated("https://example.com/old.pdf", Some("2022-12-31")),
+            dated("https://example.com/new.pdf", Some("2024-01-01")),
+        ]);
+        assert_eq!(
+            urls(&kept),
+            [
+                "https://example.com/paper.PDF?download=1",
+                "https://example.com/undated.pdf"
+            ]
+        );
+        assert_eq!(counts.wrong_filetype, 1);
+        assert_eq!(counts.outside_date_window, 2);
+        assert_eq!(counts.total(), 3);
+    }
+
+    #[test]
+    fn invalid_filters_and_site_operator() {
+        assert!(ResultFilters::new(&[" ".to_string()], &[], &[])
+            .unwrap()
+            .is_none());
+        let err = ResultFilters::new(&[], &[], &["(unclosed".to_string()]).unwrap_err();
+        assert_eq!(err.kind, crate::core::error::ErrorKind::InvalidInput);
+        assert!(ResultFilters::new(&["*.".to_string()], &[], &[]).is_err());
+
+        assert_eq!(
+            site_operator(&["*.python.org".to_string()]).as_deref(),
+            Some("site:python.org")
+        );
+        assert_eq!(
+            site_operator(&["www.github.com".to_string()]).as_deref(),
+            Some("site:github.com")
+        );
+        assert_eq!(
+            site_operator(&["a.com".to_string(), "b.com".to_string()]),
+            None
+        );
+    }
+}
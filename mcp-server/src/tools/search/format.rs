@@ -0,0 +1,168 @@
+//! Alternative renderings of a search result list for downstream tooling.
+//!
+//! `json` is the normal `SearchResponse` body. `opensearch` follows the OpenSearch
+//! Suggestions extension: `[query, [titles], [snippets], [urls]]`. `csv` is RFC 4180
+//! with a `title,url,snippet,engine,score` header. Formatting runs after the search
+//! has finished and never changes which results are returned.
+
+use crate::types::{SearchResponse, SearchResult};
+
+const CSV_HEADER: &str = "title,url,snippet,engine,score";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchOutputFormat {
+    #[default]
+    Json,
+    OpenSearch,
+    Csv,
+}
+
+impl SearchOutputFormat {
+    pub fn parse_str(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "opensearch" => Some(Self::OpenSearch),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::OpenSearch => "opensearch",
+            Self::Csv => "csv",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::OpenSearch => "application/x-suggestions+json",
+            Self::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn render(self, query: &str, results: &[SearchResult]) -> String {
+        match self {
+            Self::Json => serde_json::to_string_pretty(&SearchResponse {
+                results: results.to_vec(),
+            })
+            .unwrap_or_else(|e| format!(r#"{{"error": "Serialization failed: {}"}}"#, e)),
+            Self::OpenSearch => render_opensearch(query, results),
+            Self::Csv => render_csv(results),
+        }
+    }
+}
+
+fn render_opensearch(query: &str, results: &[SearchResult]) -> String {
+    let titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+    let snippets: Vec<&str> = results.iter().map(|r| r.content.as_str()).collect();
+    let urls: Vec<&str> = results.iter().map(|r| r.url.as_str()).collect();
+    serde_json::json!([query, titles, snippets, urls]).to_string()
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_csv(results: &[SearchResult]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push_str("\r\n");
+    for result in results {
+        let engine = result
+            .engine
+            .as_deref()
+            .or(result.engine_source.as_deref())
+            .unwrap_or("");
+        let score = result.score.map(|s| s.to_string()).unwrap_or_default();
+        let row = [
+            result.title.as_str(),
+            result.url.as_str(),
+            result.content.as_str(),
+            engine,
+            score.as_str(),
+        ]
+        .map(csv_field)
+        .join(",");
+        out.push_str(&row);
+        out.push_str("\r\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(title: &str, url: &str, content: &str, score: Option<f64>) -> SearchResult {
+        SearchResult {
+            url: url.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            engine: Some("bing".to_string()),
+            score,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_known_formats() {
+        assert_eq!(
+            SearchOutputFormat::parse_str(" CSV "),
+            Some(SearchOutputFormat::Csv)
+        );
+        assert_eq!(
+            SearchOutputFormat::parse_str("opensearch"),
+            Some(SearchOutputFormat::OpenSearch)
+        );
+        assert_eq!(SearchOutputFormat::parse_str("xml"), None);
+        assert_eq!(SearchOutputFormat::default().as_str(), "json");
+    }
+
+    #[test]
+    fn csv_escapes_commas_quotes_and_newlines() {
+        let results = vec![
+            result("Plain", "https://a.example/", "simple", Some(0.5)),
+            result(
+                "Rust, \"the\" book",
+                "https://b.example/?q=1,2",
+                "line one\nline two",
+                None,
+            ),
+        ];
+        let csv = SearchOutputFormat::Csv.render("q", &results);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "Plain,https://a.example/,simple,bing,0.5");
+        assert_eq!(
+            lines[2],
+            "\"Rust, \"\"the\"\" book\",\"https://b.example/?q=1,2\",\"line one\nline two\",bing,"
+        );
+        assert_eq!(lines[3], "");
+    }
+
+    #[test]
+    fn opensearch_emits_parallel_arrays() {
+        let results = vec![
+            result("A", "https://a.example/", "first", None),
+            result("B", "https://b.example/", "second", None),
+        ];
+        let body = SearchOutputFormat::OpenSearch.render("rust", &results);
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([
+                "rust",
+                ["A", "B"],
+                ["first", "second"],
+                ["https://a.example/", "https://b.example/"]
+            ])
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Query-term highlight offsets for `search_web` results (`highlights: true`).
+//!
+//! Terms come from the original query plus the rewritten and spell-corrected forms the
+//! `QueryRewriter` produced, tokenized the same way the reranker does. Search operators
+//! (`site:`, `filetype:`, `-excluded`) are not terms. Matching is whole-word and
+//! case-insensitive; spans are byte offsets into the untouched `title` / `content`, so
+//! they always fall on char boundaries even where lowercasing changes a word's length.
+
+use crate::query_rewriter::QueryRewriteResult;
+use crate::rerank::tokenize;
+use crate::types::{HighlightSpan, SearchResult};
+use std::collections::HashSet;
+
+/// Lowercased terms of `query` and of its rewritten / corrected forms.
+pub fn highlight_terms(query: &str, rewrite: Option<&QueryRewriteResult>) -> HashSet<String> {
+    let mut queries = vec![query];
+    if let Some(rewrite) = rewrite {
+        queries.extend(rewrite.rewritten.as_deref());
+        queries.extend(rewrite.corrected.as_deref());
+    }
+    queries
+        .into_iter()
+        .flat_map(|q| q.split_whitespace())
+        .filter(|word| !word.starts_with('-') && !word.contains(':'))
+        .flat_map(tokenize)
+        .collect()
+}
+
+/// Byte ranges of the words in `text` that are one of `terms`, in order.
+pub fn highlight_spans(text: &str, terms: &HashSet<String>) -> Vec<HighlightSpan> {
+    let mut spans = Vec::new();
+    if terms.is_empty() {
+        return spans;
+    }
+    let mut word_start: Option<usize> = None;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (c.is_alphanumeric(), word_start) {
+            (true, None) => word_start = Some(i),
+            (false, Some(start)) => {
+                if terms.contains(&text[start..i].to_lowercase()) {
+                    spans.push(HighlightSpan { start, end: i });
+                }
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+/// Fill `title_highlights` / `content_highlights` on every result; results without a
+/// match get empty lists.
+pub fn annotate_highlights(results: &mut [SearchResult], terms: &HashSet<String>) {
+    for result in results {
+        result.title_highlights = Some(highlight_spans(&result.title, terms));
+        result.content_highlights = Some(highlight_spans(&result.content, terms));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(query: &str) -> HashSet<String> {
+        highlight_terms(query, None)
+    }
+
+    fn slices<'a>(text: &'a str, spans: &[HighlightSpan]) -> Vec<&'a str> {
+        spans.iter().map(|s| &text[s.start..s.end]).collect()
+    }
+
+    #[test]
+    fn spans_are_case_insensitive_whole_words() {
+        let text = "Tokio runtime: the TOKIO scheduler, not tokiofoo";
+        let spans = highlight_spans(text, &terms("tokio scheduler"));
+        assert_eq!(slices(text, &spans), vec!["Tokio", "TOKIO", "scheduler"]);
+        assert_eq!(spans[0], HighlightSpan { start: 0, end: 5 });
+    }
+
+    #[test]
+    fn offsets_land_on_char_boundaries_in_non_ascii_text() {
+        // "İ" lowercases to two chars, so lowercased offsets would drift.
+        let text = "İSTANBUL — Straße café: Über straße";
+        let spans = highlight_spans(text, &terms("istanbul straße über"));
+        for span in &spans {
+            assert!(text.is_char_boundary(span.start) && text.is_char_boundary(span.end));
+        }
+        assert_eq!(slices(text, &spans), vec!["Straße", "Über", "straße"]);
+
+        let text = "東京 guide: 東京 タワー";
+        let spans = highlight_spans(text, &terms("東京"));
+        assert_eq!(slices(text, &spans), vec!["東京", "東京"]);
+        assert_eq!(spans[1].start, "東京 guide: ".len());
+    }
+
+    #[test]
+    fn rewritten_and_corrected_terms_count_but_operators_do_not() {
+        let rewrite = QueryRewriteResult {
+            original: "kubernets ingres".to_string(),
+            rewritten: Some("kubernets ingres docs site:kubernetes.io -helm".to_string()),
+            corrected: Some("kubernetes ingress".to_string()),
+            suggestions: Vec::new(),
+            detected_keywords: Vec::new(),
+            is_developer_query: true,
+        };
+        let terms = highlight_terms(&rewrite.original, Some(&rewrite));
+        for term in ["kubernets", "kubernetes", "ingress", "docs"] {
+            assert!(terms.contains(term), "missing {}", term);
+        }
+        assert!(!terms.contains("site") && !terms.contains("helm"));
+    }
+
+    #[test]
+    fn results_without_matches_get_empty_lists() {
+        let mut results = vec![SearchResult {
+            title: "Nothing here".to_string(),
+            content: "At all".to_string(),
+            ..Default::default()
+        }];
+        annotate_highlights(&mut results, &terms("tokio"));
+        assert_eq!(results[0].title_highlights, Some(Vec::new()));
+        assert_eq!(results[0].content_highlights, Some(Vec::new()));
+        let json = serde_json::to_value(&results[0]).unwrap();
+        assert_eq!(json["title_highlights"], serde_json::json!([]));
+    }
+}
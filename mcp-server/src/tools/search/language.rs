@@ -0,0 +1,215 @@
+//! Result language handling for `SearchParamOverrides.language`.
+//!
+//! Engines are asked for the language up front; afterwards each result's title + snippet
+//! is run through `whatlang`, and results confidently detected as another language are
+//! dropped (`filter`) or moved behind the matching ones (`demote`). Results whose language
+//! cannot be told from a short snippet are always kept in place.
+
+use crate::types::SearchResult;
+use whatlang::Lang;
+
+/// Snippets shorter than this are too small for a trustworthy guess.
+const MIN_DETECTION_CHARS: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LanguageFilterMode {
+    /// Drop results detected as a different language.
+    Filter,
+    /// Keep them, ordered after every matching or undetermined result.
+    #[default]
+    Demote,
+}
+
+impl LanguageFilterMode {
+    pub fn parse_str(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "filter" => Some(Self::Filter),
+            "demote" => Some(Self::Demote),
+            _ => None,
+        }
+    }
+
+    /// `CORTEX_SCOUT_SEARCH_LANGUAGE_MODE` (`filter` | `demote`), defaulting to `demote`.
+    pub fn from_env() -> Self {
+        std::env::var("CORTEX_SCOUT_SEARCH_LANGUAGE_MODE")
+            .ok()
+            .and_then(|v| Self::parse_str(&v))
+            .unwrap_or_default()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Filter => "filter",
+            Self::Demote => "demote",
+        }
+    }
+}
+
+/// Primary subtag of a language tag, lowercased: `"pt-BR"` → `"pt"`, `"de_DE"` → `"de"`.
+pub fn primary_subtag(language: &str) -> String {
+    language
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Region subtag, if any: `"pt-BR"` → `Some("br")`.
+pub fn region_subtag(language: &str) -> Option<String> {
+    language
+        .trim()
+        .split(['-', '_'])
+        .nth(1)
+        .filter(|r| r.len() == 2 && r.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(|r| r.to_ascii_lowercase())
+}
+
+/// ISO 639-1 code for the languages whatlang knows that have one; others keep their
+/// ISO 639-3 code so they can still be compared with a 3-letter request.
+fn language_code(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Eng => "en",
+        Lang::Spa => "es",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Ita => "it",
+        Lang::Por => "pt",
+        Lang::Rus => "ru",
+        Lang::Ukr => "uk",
+        Lang::Pol => "pl",
+        Lang::Nld => "nl",
+        Lang::Swe => "sv",
+        Lang::Dan => "da",
+        Lang::Nob => "no",
+        Lang::Fin => "fi",
+        Lang::Ces => "cs",
+        Lang::Tur => "tr",
+        Lang::Ell => "el",
+        Lang::Heb => "he",
+        Lang::Ara => "ar",
+        Lang::Hin => "hi",
+        Lang::Tha => "th",
+        Lang::Vie => "vi",
+        Lang::Ind => "id",
+        Lang::Jpn => "ja",
+        Lang::Kor => "ko",
+        Lang::Cmn => "zh",
+        other => other.code(),
+    }
+}
+
+/// Best-effort language of a result from its title and snippet; `None` when the text is
+/// too short or whatlang is not confident.
+pub fn detect_result_language(result: &SearchResult) -> Option<String> {
+    let text = format!("{} {}", result.title, result.content);
+    if text.trim().chars().count() < MIN_DETECTION_CHARS {
+        return None;
+    }
+    let info = whatlang::detect(&text)?;
+    info.is_reliable()
+        .then(|| language_code(info.lang()).to_string())
+}
+
+/// Fill `detected_language` on every result.
+pub fn annotate_languages(results: &mut [SearchResult]) {
+    for result in results.iter_mut() {
+        result.detected_language = detect_result_language(result);
+    }
+}
+
+fn mismatches(result: &SearchResult, wanted: &str) -> bool {
+    result
+        .detected_language
+        .as_deref()
+        .is_some_and(|detected| detected != wanted)
+}
+
+/// Apply `mode` for the requested `language` to results already carrying
+/// `detected_language`. Relative order inside each group is preserved.
+pub fn apply_language_preference(
+    results: Vec<SearchResult>,
+    language: &str,
+    mode: LanguageFilterMode,
+) -> Vec<SearchResult> {
+    let wanted = primary_subtag(language);
+    if wanted.is_empty() {
+        return results;
+    }
+    let (matching, other): (Vec<_>, Vec<_>) =
+        results.into_iter().partition(|r| !mismatches(r, &wanted));
+    match mode {
+        LanguageFilterMode::Filter => matching,
+        LanguageFilterMode::Demote => matching.into_iter().chain(other).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str, title: &str, content: &str) -> SearchResult {
+        SearchResult {
+            url: url.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn sample() -> Vec<SearchResult> {
+        let mut results = vec![
+            result(
+                "https://example.com/en",
+                "How to configure the async runtime",
+                "This guide explains how the scheduler distributes work across threads in production.",
+            ),
+            result(
+                "https://example.de/de",
+                "Wie man die asynchrone Laufzeit konfiguriert",
+                "Diese Anleitung erklärt, wie der Planer die Arbeit auf mehrere Threads verteilt und warum das wichtig ist.",
+            ),
+            result("https://example.com/short", "Docs", ""),
+        ];
+        annotate_languages(&mut results);
+        results
+    }
+
+    #[test]
+    fn detects_title_and_snippet_language() {
+        let results = sample();
+        assert_eq!(results[0].detected_language.as_deref(), Some("en"));
+        assert_eq!(results[1].detected_language.as_deref(), Some("de"));
+        assert_eq!(results[2].detected_language, None);
+    }
+
+    #[test]
+    fn filter_drops_and_demote_reorders() {
+        let filtered = apply_language_preference(sample(), "de-DE", LanguageFilterMode::Filter);
+        let urls: Vec<_> = filtered.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, ["https://example.de/de", "https://example.com/short"]);
+
+        let demoted = apply_language_preference(sample(), "de", LanguageFilterMode::Demote);
+        let urls: Vec<_> = demoted.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.de/de",
+                "https://example.com/short",
+                "https://example.com/en"
+            ]
+        );
+    }
+
+    #[test]
+    fn language_tag_parts() {
+        assert_eq!(primary_subtag(" pt-BR "), "pt");
+        assert_eq!(region_subtag("pt-BR").as_deref(), Some("br"));
+        assert_eq!(region_subtag("de"), None);
+        assert_eq!(
+            LanguageFilterMode::parse_str("FILTER"),
+            Some(LanguageFilterMode::Filter)
+        );
+        assert_eq!(LanguageFilterMode::parse_str("drop"), None);
+    }
+}
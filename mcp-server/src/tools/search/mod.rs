@@ -1,11 +1,25 @@
+pub mod engine_backoff;
+pub mod engine_stats;
 pub mod engines;
+mod filters;
+pub mod format;
+mod highlight;
+mod language;
+mod operators;
+mod reuse;
 mod service;
+pub mod shape;
+mod time_range;
 
+use crate::core::error::ScoutError;
 use crate::query_rewriter::{QueryRewriteResult, QueryRewriter};
 use crate::rerank::Reranker;
 use crate::types::*;
 use crate::AppState;
-use anyhow::{anyhow, Result};
+use anyhow::Result;
+use engine_backoff::EngineBackoff;
+use engine_stats::{EngineOutcome, EngineStats};
+use engines::SerpExtras;
 use futures::future::join_all;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -16,17 +30,48 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+pub use filters::FilteredCounts;
+pub use format::SearchOutputFormat;
+pub use highlight::{annotate_highlights, highlight_terms};
+pub use language::LanguageFilterMode;
+pub use operators::Operator;
+pub use reuse::ReuseRecent;
 pub use service::{SearchExecutionOutcome, SearchService};
+pub use time_range::TimeRange;
+
+/// Engines queried when neither the request nor `SEARCH_ENGINES` names any.
+pub const DEFAULT_SEARCH_ENGINES: &str = "google,bing,duckduckgo,brave";
 
 #[derive(Debug, Default, Clone)]
 pub struct SearchParamOverrides {
     pub engines: Option<String>,    // comma-separated list
     pub categories: Option<String>, // comma-separated list
     pub language: Option<String>,   // e.g., "en" or "en-US"
+    /// What to do with results detected in another `language` (default: env, then demote).
+    pub language_mode: Option<LanguageFilterMode>,
     pub safesearch: Option<u8>,     // 0,1,2
     pub time_range: Option<String>, // e.g., day, week, month, year
     pub pageno: Option<u32>,        // 1..N
+    pub max_results: Option<usize>, // cap on the final merged (post-rerank) list
     pub disable_recovery: bool,
+    /// When a recent near-identical search in history is served instead (default: auto).
+    pub reuse_recent: Option<ReuseRecent>,
+    /// Keep only results on these domains (`*.example.com` includes subdomains).
+    pub include_domains: Vec<String>,
+    pub exclude_domains: Vec<String>,
+    /// Regexes matched against each result URL.
+    pub exclude_url_patterns: Vec<String>,
+    /// Trim each result's `content` to this many chars after reranking (default: as given).
+    pub snippet_max_chars: Option<usize>,
+    /// Keep only results whose URL path ends in this extension (`filetype:` operator).
+    pub filetype: Option<String>,
+    /// Words or phrases the result titles should contain (`intitle:` operator). Passed
+    /// to engines; results are not filtered on it.
+    pub intitle: Vec<String>,
+    /// Keep only results published on or after `published_after` and before
+    /// `published_before` (`after:` / `before:` operators). Undated results are kept.
+    pub published_after: Option<chrono::NaiveDate>,
+    pub published_before: Option<chrono::NaiveDate>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -34,17 +79,57 @@ pub struct SearchExtras {
     pub answers: Vec<String>,
     pub suggestions: Vec<String>,
     pub corrections: Vec<String>,
+    /// One `engine: cause` entry per engine that returned nothing, e.g.
+    /// `google: timeout_2500ms` or `duckduckgo: blocked:captcha`.
     pub unresponsive_engines: Vec<String>,
+    /// Engines that answered (directly or via the browser fallback) in the best pass;
+    /// 0 when the results came from a cache or history.
+    pub engines_succeeded: usize,
     pub degraded_engines: Vec<String>,
     pub skipped_engines: Vec<String>,
+    /// Engines whose SERP request carried the `time_range` date filter.
+    pub time_filtered_engines: Vec<String>,
     pub query_rewrite: Option<QueryRewriteResult>,
     pub duplicate_warning: Option<String>,
+    /// The results are a recent search's, served from history without querying engines.
+    pub from_history: bool,
+    /// Age of the history entry the results came from.
+    pub history_age_secs: Option<u64>,
+    /// Results the include/exclude filters removed from this call's merged list.
+    pub filtered_out: FilteredCounts,
+    /// Results dropped from the end of the response to honour `max_response_bytes`.
+    pub truncated_results: usize,
+}
+
+impl SearchExtras {
+    /// A one-line summary of engine failures for text output, or `None` when every
+    /// engine that ran answered.
+    pub fn engine_diagnostics(&self) -> Option<String> {
+        if self.unresponsive_engines.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "{} engine(s) answered; {}",
+            self.engines_succeeded,
+            self.unresponsive_engines.join(", ")
+        ))
+    }
+}
+
+/// A cached search: the final results and the engines' own answers/suggestions/corrections.
+/// Rewrite hints and telemetry are per call and not cached.
+#[derive(Debug, Default, Clone)]
+pub struct CachedSearch {
+    pub results: Vec<SearchResult>,
+    pub extras: SerpExtras,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct SharedSearchCacheEntry {
     cached_at_ms: i64,
     results: Vec<SearchResult>,
+    #[serde(default)]
+    extras: SerpExtras,
 }
 
 struct SharedSearchLeaderLock {
@@ -71,15 +156,46 @@ enum EngineRunStatus {
     Success,
     Recovered { reason: String },
     Blocked { reason: String },
-    Timeout,
+    Timeout { after: Duration },
     Failed { reason: String },
+    CoolingDown { remaining: Duration },
+}
+
+impl EngineRunStatus {
+    fn succeeded(&self) -> bool {
+        matches!(self, Self::Success | Self::Recovered { .. })
+    }
+
+    /// Why the engine returned nothing, as listed in `unresponsive_engines`.
+    fn failure_cause(&self) -> Option<String> {
+        match self {
+            Self::Success | Self::Recovered { .. } => None,
+            Self::Blocked { reason } => Some(format!("blocked:{}", reason)),
+            Self::Timeout { after } => Some(format!("timeout_{}ms", after.as_millis())),
+            Self::Failed { reason } => Some(format!("failed:{}", reason)),
+            Self::CoolingDown { remaining } => {
+                Some(format!("cooling_down_{}s", remaining.as_secs().max(1)))
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct EngineRunOutput {
     engine: String,
     results: Vec<SearchResult>,
+    extras: SerpExtras,
     status: EngineRunStatus,
+    /// The SERP request carried the engine's date filter.
+    time_filtered: bool,
+}
+
+/// Per-search SERP options forwarded to every engine run.
+#[derive(Debug, Clone, Copy)]
+struct SerpParams<'a> {
+    pageno: u32,
+    language: Option<&'a str>,
+    time_range: Option<TimeRange>,
 }
 
 pub struct InternalSearchService {
@@ -101,16 +217,41 @@ impl InternalSearchService {
         }
     }
 
-    fn parse_engine_list(engines: Option<String>) -> Vec<String> {
-        engines
-            .unwrap_or_else(|| {
-                std::env::var("SEARCH_ENGINES")
-                    .unwrap_or_else(|_| "google,bing,duckduckgo,brave".to_string())
-            })
+    /// Resolve a comma-separated engine list against [`engines::REGISTRY`], returning the
+    /// canonical names (deduplicated, aliases such as `ddg` folded) and the unknown entries.
+    fn parse_engine_list(engines: Option<String>) -> (Vec<String>, Vec<String>) {
+        let raw = engines.unwrap_or_else(|| {
+            std::env::var("SEARCH_ENGINES")
+                .unwrap_or_else(|_| DEFAULT_SEARCH_ENGINES.to_string())
+        });
+        let mut known: Vec<String> = Vec::new();
+        let mut unknown = Vec::new();
+        for entry in raw
             .split(',')
             .map(|s| s.trim().to_ascii_lowercase())
             .filter(|s| !s.is_empty())
-            .collect()
+        {
+            match engines::lookup(&entry) {
+                Some(engine) => {
+                    if !known.iter().any(|name| name == engine.name()) {
+                        known.push(engine.name().to_string());
+                    }
+                }
+                None => {
+                    warn!(
+                        "unknown search engine '{}' ignored (known: {})",
+                        entry,
+                        engines::REGISTRY
+                            .iter()
+                            .map(|e| e.name())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                    unknown.push(entry);
+                }
+            }
+        }
+        (known, unknown)
     }
 
     fn max_engines_per_query(strict_requested: bool, requested_len: usize) -> usize {
@@ -168,6 +309,8 @@ impl InternalSearchService {
         &self,
         requested: &[String],
         strict_requested: bool,
+        stats: &EngineStats,
+        backoff: &EngineBackoff,
     ) -> (Vec<String>, Vec<String>) {
         if requested.is_empty() {
             return (Vec::new(), Vec::new());
@@ -192,8 +335,29 @@ impl InternalSearchService {
         let mut active = Vec::new();
         let mut skipped = Vec::new();
         let mut fallback_probe: Option<(String, Duration, String)> = None;
+        // Rate-limited engines don't use up the budget; they are still returned (after the
+        // active ones) so `run_engine` reports them as cooling down without a request.
+        let mut rate_limited = Vec::new();
 
         for engine in ordered {
+            if backoff.remaining(&engine).is_some() {
+                rate_limited.push(engine);
+                continue;
+            }
+            if let Some((remaining, reason)) = stats.bench_remaining(&engine) {
+                skipped.push(format!(
+                    "{}(benched:{}s after {})",
+                    engine,
+                    remaining.as_secs().max(1),
+                    reason
+                ));
+                match &fallback_probe {
+                    Some((_, best_remaining, _)) if *best_remaining <= remaining => {}
+                    _ => fallback_probe = Some((engine.clone(), remaining, reason)),
+                }
+                continue;
+            }
+
             let Some(state) = health.get(&engine) else {
                 active.push(engine);
                 if active.len() >= budget {
@@ -234,6 +398,7 @@ impl InternalSearchService {
                 active.push(engine);
             }
         }
+        active.extend(rate_limited);
 
         (active, skipped)
     }
@@ -242,13 +407,22 @@ impl InternalSearchService {
         &self,
         requested: &[String],
         attempted: &HashSet<String>,
+        stats: &EngineStats,
+        backoff: &EngineBackoff,
     ) -> Option<String> {
         let now = Instant::now();
         let health = self.engine_health.lock().expect("engine health mutex poisoned");
         let mut fallback_probe: Option<(String, Duration)> = None;
 
         for engine in requested {
-            if attempted.contains(engine) {
+            if attempted.contains(engine) || backoff.remaining(engine).is_some() {
+                continue;
+            }
+            if let Some((remaining, _)) = stats.bench_remaining(engine) {
+                match &fallback_probe {
+                    Some((_, best_remaining)) if *best_remaining <= remaining => {}
+                    _ => fallback_probe = Some((engine.clone(), remaining)),
+                }
                 continue;
             }
 
@@ -310,7 +484,7 @@ impl InternalSearchService {
                 entry.cooldown_until = Some(now + backoff);
                 entry.last_issue = Some(format!("blocked:{}", reason));
             }
-            EngineRunStatus::Timeout => {
+            EngineRunStatus::Timeout { .. } => {
                 entry.timeout_streak = entry.timeout_streak.saturating_add(1);
                 entry.failure_streak = 0;
                 let backoff = Self::exp_backoff(
@@ -331,6 +505,7 @@ impl InternalSearchService {
                 entry.cooldown_until = Some(now + backoff);
                 entry.last_issue = Some(format!("failed:{}", reason));
             }
+            EngineRunStatus::CoolingDown { .. } => {}
         }
     }
 
@@ -381,6 +556,18 @@ impl InternalSearchService {
         };
 
         for run in runs {
+            push_unique(&mut extras.answers, &run.extras.answers);
+            push_unique(&mut extras.suggestions, &run.extras.suggestions);
+            push_unique(&mut extras.corrections, &run.extras.corrections);
+            if run.time_filtered && !extras.time_filtered_engines.contains(&run.engine) {
+                extras.time_filtered_engines.push(run.engine.clone());
+            }
+            if let Some(cause) = run.status.failure_cause() {
+                let entry = format!("{}: {}", run.engine, cause);
+                if !extras.unresponsive_engines.contains(&entry) {
+                    extras.unresponsive_engines.push(entry);
+                }
+            }
             match &run.status {
                 EngineRunStatus::Success => {}
                 EngineRunStatus::Recovered { reason } => {
@@ -389,39 +576,65 @@ impl InternalSearchService {
                         .push(format!("{}(recovered_via_fallback:{})", run.engine, reason));
                 }
                 EngineRunStatus::Blocked { reason } => {
-                    extras.unresponsive_engines.push(run.engine.clone());
                     extras
                         .degraded_engines
                         .push(format!("{}(blocked:{})", run.engine, reason));
                 }
-                EngineRunStatus::Timeout => {
-                    extras.unresponsive_engines.push(run.engine.clone());
+                EngineRunStatus::Timeout { .. } => {
                     extras
                         .degraded_engines
                         .push(format!("{}(timeout)", run.engine));
                 }
                 EngineRunStatus::Failed { reason } => {
-                    extras.unresponsive_engines.push(run.engine.clone());
                     extras
                         .degraded_engines
                         .push(format!("{}(failed:{})", run.engine, reason));
                 }
+                EngineRunStatus::CoolingDown { .. } => {}
             }
         }
+        let succeeded: HashSet<&str> = runs
+            .iter()
+            .filter(|run| run.status.succeeded())
+            .map(|run| run.engine.as_str())
+            .collect();
+        extras.engines_succeeded = succeeded.len();
 
         extras
     }
 
+    /// The error for a search in which no engine answered, carrying the per-engine
+    /// causes so callers can tell "nothing found" from "nothing reachable".
+    fn no_engine_answered(extras: &SearchExtras) -> ScoutError {
+        let message = if extras.unresponsive_engines.is_empty() {
+            format!(
+                "No search engine available: {}",
+                extras.skipped_engines.join(", ")
+            )
+        } else {
+            format!(
+                "No search engine answered: {}",
+                extras.unresponsive_engines.join(", ")
+            )
+        };
+        ScoutError::upstream_unavailable(message).with_details(serde_json::json!({
+            "engines_succeeded": 0,
+            "unresponsive_engines": extras.unresponsive_engines,
+            "skipped_engines": extras.skipped_engines,
+        }))
+    }
+
     async fn sync_host_guard(&self, run: &EngineRunOutput) {
         match &run.status {
             EngineRunStatus::Success => crate::host_guard::note_search_engine_success(&run.engine).await,
             EngineRunStatus::Recovered { reason } | EngineRunStatus::Blocked { reason } => {
                 crate::host_guard::note_search_engine_blocked(&run.engine, reason).await
             }
-            EngineRunStatus::Timeout => crate::host_guard::note_search_engine_timeout(&run.engine).await,
+            EngineRunStatus::Timeout { .. } => crate::host_guard::note_search_engine_timeout(&run.engine).await,
             EngineRunStatus::Failed { reason } => {
                 crate::host_guard::note_search_engine_failure(&run.engine, reason).await
             }
+            EngineRunStatus::CoolingDown { .. } => {}
         }
     }
 
@@ -431,22 +644,48 @@ impl InternalSearchService {
         engine: &str,
         query: &str,
         max_results: usize,
+        serp: SerpParams<'_>,
     ) -> EngineRunOutput {
         let client = &state.http_client;
         let timeout = engine_timeout(engine);
+        let started = Instant::now();
+        let time_filtered = serp.time_range.is_some_and(|range| {
+            engines::lookup(engine).is_some_and(|adapter| adapter.time_range_param(range).is_some())
+        });
+
+        if let Some(remaining) = state.engine_backoff.remaining(engine) {
+            debug!(
+                "engine '{}' skipped: cooling down for another {}s",
+                engine,
+                remaining.as_secs()
+            );
+            return EngineRunOutput {
+                engine: engine.to_string(),
+                results: Vec::new(),
+                extras: SerpExtras::default(),
+                status: EngineRunStatus::CoolingDown { remaining },
+                time_filtered: false,
+            };
+        }
 
         let fut = async {
-            match engine {
-                "duckduckgo" | "ddg" => {
-                    engines::duckduckgo::search(client, query, max_results).await
-                }
-                "bing" => engines::bing::search(client, query, max_results).await,
-                "google" => engines::google::search(client, query, max_results).await,
-                "brave" => engines::brave::search(client, query, max_results).await,
-                other => {
-                    debug!("unknown search engine requested: {}", other);
-                    Ok(Vec::new())
+            match engines::lookup(engine) {
+                Some(adapter) => {
+                    engines::search(
+                        adapter,
+                        client,
+                        query,
+                        max_results,
+                        serp.pageno,
+                        serp.language,
+                        serp.time_range,
+                    )
+                    .await
                 }
+                None => Err(engines::EngineError::Fatal(format!(
+                    "unknown search engine: {}",
+                    engine
+                ))),
             }
         };
 
@@ -458,35 +697,87 @@ impl InternalSearchService {
                     engine,
                     timeout.as_millis()
                 );
+                state
+                    .metrics
+                    .record_engine(engine, "timeout", started.elapsed());
+                state
+                    .engine_stats
+                    .record(engine, &EngineOutcome::Timeout, started.elapsed());
                 return EngineRunOutput {
                     engine: engine.to_string(),
                     results: Vec::new(),
-                    status: EngineRunStatus::Timeout,
+                    extras: SerpExtras::default(),
+                    status: EngineRunStatus::Timeout { after: timeout },
+                    time_filtered,
                 };
             }
         };
 
+        let outcome = match &res {
+            Ok(_) => "success",
+            Err(engines::EngineError::Blocked { .. }) => "blocked",
+            Err(engines::EngineError::Transient { .. }) => "transient",
+            Err(engines::EngineError::Fatal(_)) => "fatal",
+        };
+        state
+            .metrics
+            .record_engine(engine, outcome, started.elapsed());
+        let stats_outcome = match &res {
+            Ok(_) => EngineOutcome::Success,
+            Err(engines::EngineError::Blocked { reason, .. }) => EngineOutcome::Blocked {
+                reason: reason.clone(),
+            },
+            Err(_) => EngineOutcome::Failed,
+        };
+        state
+            .engine_stats
+            .record(engine, &stats_outcome, started.elapsed());
+        match &res {
+            Ok(_) => state.engine_backoff.record_success(engine),
+            Err(engines::EngineError::Blocked {
+                reason,
+                retry_after,
+                ..
+            }) => {
+                state
+                    .engine_backoff
+                    .record_block(engine, reason, *retry_after);
+            }
+            Err(_) => {}
+        }
+
         match res {
-            Ok(v) => EngineRunOutput {
+            Ok((results, extras)) => EngineRunOutput {
                 engine: engine.to_string(),
-                results: v,
+                results,
+                extras,
                 status: EngineRunStatus::Success,
+                time_filtered,
             },
-            Err(engines::EngineError::Blocked { reason }) => {
-                warn!("engine '{}' blocked: {}", engine, reason);
+            Err(engines::EngineError::Blocked {
+                reason, attempts, ..
+            }) => {
+                warn!(
+                    "engine '{}' blocked: {} ({} attempt(s))",
+                    engine, reason, attempts
+                );
                 let fallback = self
-                    .tier2_non_robot_fallback(state, engine, query, max_results)
+                    .tier2_non_robot_fallback(state, engine, query, max_results, serp)
                     .await;
                 match fallback {
                     Some(results) if !results.is_empty() => EngineRunOutput {
                         engine: engine.to_string(),
                         results,
+                        extras: SerpExtras::default(),
                         status: EngineRunStatus::Recovered { reason },
+                        time_filtered,
                     },
                     _ => EngineRunOutput {
                         engine: engine.to_string(),
                         results: Vec::new(),
+                        extras: SerpExtras::default(),
                         status: EngineRunStatus::Blocked { reason },
+                        time_filtered,
                     },
                 }
             }
@@ -495,9 +786,11 @@ impl InternalSearchService {
                 EngineRunOutput {
                     engine: engine.to_string(),
                     results: Vec::new(),
+                    extras: SerpExtras::default(),
                     status: EngineRunStatus::Failed {
                         reason: e.to_string(),
                     },
+                    time_filtered,
                 }
             }
         }
@@ -510,6 +803,7 @@ impl InternalSearchService {
         engine: &str,
         query: &str,
         max_results: usize,
+        serp: SerpParams<'_>,
     ) -> Option<Vec<SearchResult>> {
         use crate::features::non_robot_search::{execute_non_robot_search, NonRobotSearchConfig};
         use crate::rust_scraper::QualityMode;
@@ -522,32 +816,13 @@ impl InternalSearchService {
             return None;
         }
 
-        let url = match engine {
-            "duckduckgo" | "ddg" => {
-                let mut u = reqwest::Url::parse("https://duckduckgo.com/html/").ok()?;
-                u.query_pairs_mut().append_pair("q", query);
-                u
-            }
-            "bing" => {
-                let mut u = reqwest::Url::parse("https://www.bing.com/search").ok()?;
-                u.query_pairs_mut().append_pair("q", query);
-                u
-            }
-            "google" => {
-                let mut u = reqwest::Url::parse("https://www.google.com/search").ok()?;
-                u.query_pairs_mut().append_pair("q", query);
-                u.query_pairs_mut().append_pair("hl", "en");
-                u.query_pairs_mut()
-                    .append_pair("num", &max_results.clamp(5, 10).to_string());
-                u
-            }
-            "brave" => {
-                let mut u = reqwest::Url::parse("https://search.brave.com/search").ok()?;
-                u.query_pairs_mut().append_pair("q", query);
-                u
-            }
-            _ => return None,
-        };
+        let adapter = engines::lookup(engine)?;
+        let mut url = adapter
+            .build_url(query, max_results, serp.pageno, serp.language)
+            .ok()?;
+        if let Some(range) = serp.time_range {
+            engines::apply_time_range(adapter, &mut url, range);
+        }
 
         let cfg = NonRobotSearchConfig {
             url: url.to_string(),
@@ -561,18 +836,15 @@ impl InternalSearchService {
             wait_for_selector: None,
             keep_open: false,
             instruction_message: None,
+            headless_test: crate::core::config::non_robot_headless_test(),
+            session_ttl: state.shadow_config.non_robot_search.resolve_session_ttl(),
+            close_session: false,
         };
 
         match execute_non_robot_search(state, cfg).await {
             Ok(scraped) => {
                 let html = scraped.content;
-                let parsed = match engine {
-                    "duckduckgo" | "ddg" => engines::duckduckgo::parse_results(&html, max_results),
-                    "bing" => engines::bing::parse_results(&html, max_results),
-                    "google" => engines::google::parse_results(&html, max_results),
-                    "brave" => engines::brave::parse_results(&html, max_results),
-                    _ => Vec::new(),
-                };
+                let parsed = adapter.parse_results(&html, max_results);
                 if parsed.is_empty() {
                     warn!(
                         "tier2 fallback got HTML but parsed 0 results for engine '{}'",
@@ -595,6 +867,7 @@ impl InternalSearchService {
         _engine: &str,
         _query: &str,
         _max_results: usize,
+        _serp: SerpParams<'_>,
     ) -> Option<Vec<SearchResult>> {
         None
     }
@@ -612,37 +885,68 @@ impl SearchService for InternalSearchService {
         let explicit_engines = engines_override.is_some();
 
         // Context-based forcing (roughly equivalent to the legacy external search engine forcing).
+        // A search already scoped to sites is left alone; `include_domains` and the other
+        // operators are added per engine (see `operators::render`).
         let query_lower = query.to_lowercase();
         let mut effective_query = query.to_string();
-        if engines_override.is_none()
+        let site_scoped = query_lower.contains("site:")
+            || overrides
+                .as_ref()
+                .is_some_and(|o| !o.include_domains.is_empty());
+        let forcing = engines_override.is_none() && !site_scoped;
+        if forcing
             && (query_lower.contains("github")
                 || query_lower.contains("repo")
                 || query_lower.contains("repository"))
         {
             effective_query = format!("{} site:github.com", query);
-        } else if engines_override.is_none()
+        } else if forcing
             && (query_lower.contains("stackoverflow") || query_lower.contains("stack overflow"))
         {
             effective_query = format!("{} site:stackoverflow.com", query);
         }
 
-        let engine_list = Self::parse_engine_list(engines_override.take());
-        let (selected_engines, mut skipped_engines) =
-            self.select_engines(&engine_list, explicit_engines);
+        let (engine_list, unknown_engines) = Self::parse_engine_list(engines_override.take());
+        let (selected_engines, mut skipped_engines) = self.select_engines(
+            &engine_list,
+            explicit_engines,
+            &state.engine_stats,
+            &state.engine_backoff,
+        );
+        skipped_engines.extend(
+            unknown_engines
+                .iter()
+                .map(|name| format!("{}(unknown engine)", name)),
+        );
         let max_results = std::env::var("SEARCH_MAX_RESULTS_PER_ENGINE")
             .ok()
             .and_then(|v| v.parse::<usize>().ok())
             .unwrap_or(10);
+        let pageno = overrides.as_ref().and_then(|o| o.pageno).unwrap_or(1);
+        let language = overrides.as_ref().and_then(|o| o.language.clone());
+        let time_range = overrides
+            .as_ref()
+            .and_then(|o| o.time_range.as_deref())
+            .and_then(TimeRange::parse_str);
+        let serp = SerpParams {
+            pageno,
+            language: language.as_deref(),
+            time_range,
+        };
 
         // Run the healthiest engines in parallel with a light stagger to reduce burstiness.
         let stagger_ms = Self::search_engine_stagger_ms();
+        let engine_query = |engine: &str, query: &str| match engines::lookup(engine) {
+            Some(adapter) => operators::render(adapter, query, overrides.as_ref()),
+            None => query.to_string(),
+        };
         let engine_futs = selected_engines.iter().enumerate().map(|(index, engine)| {
-            let effective_query = effective_query.clone();
+            let engine_query = engine_query(engine.as_str(), &effective_query);
             async move {
                 if index > 0 && stagger_ms > 0 {
                     tokio::time::sleep(Duration::from_millis(stagger_ms * index as u64)).await;
                 }
-                self.run_engine(state, engine.as_str(), &effective_query, max_results)
+                self.run_engine(state, engine.as_str(), &engine_query, max_results, serp)
                     .await
             }
         });
@@ -658,13 +962,24 @@ impl SearchService for InternalSearchService {
 
         if results.is_empty() {
             let attempted: HashSet<String> = selected_engines.iter().cloned().collect();
-            if let Some(rescue_engine) = self.select_rescue_engine(&engine_list, &attempted) {
+            if let Some(rescue_engine) = self.select_rescue_engine(
+                &engine_list,
+                &attempted,
+                &state.engine_stats,
+                &state.engine_backoff,
+            ) {
                 debug!(
                     "primary search returned 0 results; probing rescue engine '{}'",
                     rescue_engine
                 );
                 let rescue_run = self
-                    .run_engine(state, rescue_engine.as_str(), &effective_query, max_results)
+                    .run_engine(
+                        state,
+                        rescue_engine.as_str(),
+                        &engine_query(rescue_engine.as_str(), &effective_query),
+                        max_results,
+                        serp,
+                    )
                     .await;
                 self.update_engine_health(&rescue_run.engine, &rescue_run.status);
                 self.sync_host_guard(&rescue_run).await;
@@ -674,23 +989,28 @@ impl SearchService for InternalSearchService {
         }
 
         // Community expansion is expensive and higher-risk. Only use it when the query
-        // explicitly asks for community discussion or primary results are too sparse.
-        if Self::should_run_community_expansion(&effective_query, results.len()) {
+        // explicitly asks for community discussion or primary results are too sparse,
+        // and never on a search already scoped to sites.
+        if !site_scoped && Self::should_run_community_expansion(&effective_query, results.len()) {
             let community_query = format!(
                 "{} (site:reddit.com OR site:news.ycombinator.com)",
                 effective_query
             );
 
-            let (community_engines, community_skipped) =
-                self.select_engines(&engine_list, explicit_engines);
+            let (community_engines, community_skipped) = self.select_engines(
+                &engine_list,
+                explicit_engines,
+                &state.engine_stats,
+                &state.engine_backoff,
+            );
             skipped_engines.extend(community_skipped);
             let community_futs = community_engines.iter().enumerate().map(|(index, engine)| {
-                let community_query = community_query.clone();
+                let community_query = engine_query(engine.as_str(), &community_query);
                 async move {
                     if index > 0 && stagger_ms > 0 {
                         tokio::time::sleep(Duration::from_millis(stagger_ms * index as u64)).await;
                     }
-                    self.run_engine(state, engine.as_str(), &community_query, max_results)
+                    self.run_engine(state, engine.as_str(), &community_query, max_results, serp)
                         .await
                 }
             });
@@ -703,14 +1023,28 @@ impl SearchService for InternalSearchService {
             engine_runs.extend(community_runs);
         }
 
+        let extras = Self::extras_from_runs(&engine_runs, skipped_engines);
+        if extras.engines_succeeded == 0 {
+            return Err(Self::no_engine_answered(&extras).into());
+        }
+
         Ok(SearchExecutionOutcome {
-            results: dedup_and_score_results(results, query),
-            extras: Self::extras_from_runs(&engine_runs, skipped_engines),
+            results: dedup_and_score_results(results, query, time_range),
+            extras,
         })
     }
 }
 
-fn dedup_and_score_results(results: Vec<SearchResult>, query: &str) -> Vec<SearchResult> {
+/// Share of its score a result keeps when its `published_at` predates the requested
+/// `time_range` (engine-side date filters are not always honored).
+const STALE_RESULT_FACTOR: f64 = 0.5;
+
+fn dedup_and_score_results(
+    results: Vec<SearchResult>,
+    query: &str,
+    time_range: Option<TimeRange>,
+) -> Vec<SearchResult> {
+    let today = chrono::Utc::now().date_naive();
     #[derive(Default)]
     struct Acc {
         result: SearchResult,
@@ -819,7 +1153,17 @@ fn dedup_and_score_results(results: Vec<SearchResult>, query: &str) -> Vec<Searc
             }
 
             let recency_bonus = recency_bonus(&acc.result.published_at);
-            let base = 1.0 * domain_weight + corroboration_bonus + recency_bonus;
+            let mut base = 1.0 * domain_weight + corroboration_bonus + recency_bonus;
+            let published = acc
+                .result
+                .published_at
+                .as_deref()
+                .and_then(parse_published_date);
+            if let (Some(range), Some(date)) = (time_range, published) {
+                if range.excludes(date, today) {
+                    base *= STALE_RESULT_FACTOR;
+                }
+            }
             acc.result.score = Some(base);
 
             acc.result.engine_source = if engine_count == 1 {
@@ -853,22 +1197,29 @@ fn dedup_and_score_results(results: Vec<SearchResult>, query: &str) -> Vec<Searc
 }
 
 fn engine_timeout(engine: &str) -> Duration {
-    let base_default_ms = std::env::var("SEARCH_ENGINE_TIMEOUT_MS")
-        .ok()
-        .and_then(|v| v.parse::<u64>().ok())
-        .unwrap_or(2_500);
+    let adapter = engines::lookup(engine);
 
     // Built-in per-engine defaults (can be overridden by env).
-    let builtin_ms = match engine {
-        "duckduckgo" | "ddg" => 4_500,
-        "brave" => 3_500,
-        _ => base_default_ms,
-    };
-
-    let key = format!("SEARCH_ENGINE_TIMEOUT_MS_{}", engine.to_ascii_uppercase());
-    let ms = std::env::var(key)
-        .ok()
-        .and_then(|v| v.parse::<u64>().ok())
+    let builtin_ms = adapter
+        .map(|a| a.default_timeout())
+        .unwrap_or_else(engines::base_engine_timeout)
+        .as_millis() as u64;
+
+    // `SEARCH_ENGINE_TIMEOUT_MS_<NAME>`, also accepted under an alias (`..._DDG`).
+    let names = std::iter::once(engine).chain(
+        adapter
+            .into_iter()
+            .flat_map(|a| std::iter::once(a.name()).chain(a.aliases().iter().copied())),
+    );
+    let ms = names
+        .filter_map(|name| {
+            std::env::var(format!(
+                "SEARCH_ENGINE_TIMEOUT_MS_{}",
+                name.to_ascii_uppercase()
+            ))
+            .ok()
+        })
+        .find_map(|v| v.parse::<u64>().ok())
         .unwrap_or(builtin_ms);
 
     Duration::from_millis(ms.max(250))
@@ -960,7 +1311,31 @@ pub(crate) fn extract_published_at_from_text(text: &str) -> Option<String> {
     None
 }
 
-fn breadcrumbs_have_high_value_keywords(breadcrumbs: &[String]) -> bool {
+/// Carousels print ages rather than dates: a compact `5h` / `2d` / `3w` / `4mo`, or a
+/// spelled-out `3 hours ago`. Resolved against `now` to a `YYYY-MM-DD` date so recency
+/// scoring can use it.
+pub(crate) fn published_at_from_age(
+    text: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<String> {
+    let t = text.trim();
+    let compact = regex::Regex::new(r"(?i)^(\d{1,3})\s*([a-z]+)$").ok()?;
+    let spelled = regex::Regex::new(r"(?i)\b(\d{1,3})\s+([a-z]+)\s+ago\b").ok()?;
+    let cap = compact.captures(t).or_else(|| spelled.captures(t))?;
+    let n: i64 = cap[1].parse().ok()?;
+    let age = match cap[2].to_ascii_lowercase().as_str() {
+        "m" | "min" | "mins" | "minute" | "minutes" => chrono::Duration::minutes(n),
+        "h" | "hr" | "hrs" | "hour" | "hours" => chrono::Duration::hours(n),
+        "d" | "day" | "days" => chrono::Duration::days(n),
+        "w" | "wk" | "wks" | "week" | "weeks" => chrono::Duration::weeks(n),
+        "mo" | "month" | "months" => chrono::Duration::days(30 * n),
+        "y" | "yr" | "yrs" | "year" | "years" => chrono::Duration::days(365 * n),
+        _ => return None,
+    };
+    Some((now - age).format("%Y-%m-%d").to_string())
+}
+
+pub(crate) fn breadcrumbs_have_high_value_keywords(breadcrumbs: &[String]) -> bool {
     let needles = [
         "docs",
         "documentation",
@@ -976,22 +1351,23 @@ fn breadcrumbs_have_high_value_keywords(breadcrumbs: &[String]) -> bool {
     })
 }
 
+/// Date of a result's `published_at`, for the few formats engines and snippets use.
+fn parse_published_date(s: &str) -> Option<chrono::NaiveDate> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|dt| dt.date_naive())
+        .or_else(|| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+        .or_else(|| chrono::NaiveDate::parse_from_str(s, "%b %d, %Y").ok())
+        .or_else(|| chrono::NaiveDate::parse_from_str(s, "%B %d, %Y").ok())
+}
+
 fn recency_bonus(published_at: &Option<String>) -> f64 {
     let Some(s) = published_at.as_ref() else {
         return 0.0;
     };
 
-    // Parse a few common formats.
     let now = chrono::Utc::now().date_naive();
-
-    let parsed = chrono::DateTime::parse_from_rfc3339(s)
-        .ok()
-        .map(|dt| dt.date_naive())
-        .or_else(|| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
-        .or_else(|| chrono::NaiveDate::parse_from_str(s, "%b %d, %Y").ok())
-        .or_else(|| chrono::NaiveDate::parse_from_str(s, "%B %d, %Y").ok());
-
-    let Some(date) = parsed else {
+    let Some(date) = parse_published_date(s) else {
         return 0.0;
     };
 
@@ -1008,13 +1384,14 @@ fn recency_bonus(published_at: &Option<String>) -> f64 {
     }
 }
 
-fn normalize_url_key(url: &str) -> String {
+pub(crate) fn normalize_url_key(url: &str) -> String {
     let trimmed = url.trim();
     let Ok(mut parsed) = url::Url::parse(trimmed) else {
         return trimmed.to_string();
     };
 
-    parsed.set_fragment(None);
+    // A routing fragment (`#/docs`) names a different document; an anchor does not.
+    crate::scraping::fragment_route::strip_anchor(&mut parsed);
 
     // Drop high-noise tracking params (common across engines / social referrers).
     if parsed.query().is_some() {
@@ -1169,20 +1546,66 @@ pub async fn search_web(
     search_web_with_params(state, query, None).await
 }
 
+#[tracing::instrument(skip_all, fields(query = %query))]
 pub async fn search_web_with_params(
     state: &Arc<AppState>,
     query: &str,
     overrides: Option<SearchParamOverrides>,
+) -> Result<(Vec<SearchResult>, SearchExtras)> {
+    let started = Instant::now();
+    let snippet_max_chars = overrides.as_ref().and_then(|ov| ov.snippet_max_chars);
+    let mut outcome = run_search(state, query, overrides).await;
+    state
+        .metrics
+        .record_tool("search_web", outcome.is_ok(), started.elapsed());
+    // After reranking and caching, so both see the full snippets.
+    if let (Ok((results, _)), Some(max_chars)) = (outcome.as_mut(), snippet_max_chars) {
+        shape::trim_snippets(results, max_chars);
+    }
+    outcome
+}
+
+async fn run_search(
+    state: &Arc<AppState>,
+    query: &str,
+    overrides: Option<SearchParamOverrides>,
 ) -> Result<(Vec<SearchResult>, SearchExtras)> {
     info!("Searching for: {}", query);
+    let (terms, overrides) = operators::extract(query, overrides);
+    let query = terms.as_str();
+
+    let max_results = overrides.as_ref().and_then(|ov| ov.max_results);
+    if max_results == Some(0) {
+        debug!("max_results=0 requested; skipping engine fan-out");
+        return Ok((Vec::new(), SearchExtras::default()));
+    }
 
     let neurosiphon = crate::core::config::neurosiphon_enabled();
+    let result_filters = match overrides.as_ref() {
+        Some(ov) => filters::ResultFilters::from_overrides(ov)?,
+        None => None,
+    };
 
-    // Phase 2: Check for recent duplicates if memory enabled
+    // Phase 2: Check for recent duplicates if memory enabled, and serve them when
+    // `reuse_recent` allows.
+    let reuse_mode = overrides
+        .as_ref()
+        .and_then(|ov| ov.reuse_recent)
+        .unwrap_or_default();
     let mut duplicate_warning = None;
-    if neurosiphon {
-        if let Some(memory) = state.get_memory_or_wait(Duration::from_secs(3)).await {
-            match memory.find_recent_duplicate(query, 6).await {
+    let mut stale_history_entry = None;
+    if neurosiphon || reuse_mode == ReuseRecent::Always {
+        if state
+            .get_memory_or_wait(Duration::from_secs(3))
+            .await
+            .is_some()
+        {
+            let min_similarity = crate::core::config::search_history_reuse_min_similarity();
+            match state
+                .memory_guard
+                .find_recent_duplicate(query, 6, min_similarity.min(0.9))
+                .await
+            {
                 Ok(Some((entry, score))) => {
                     let time_ago = chrono::Utc::now().signed_duration_since(entry.timestamp);
                     let hours = time_ago.num_hours();
@@ -1206,21 +1629,66 @@ pub async fn search_web_with_params(
                         "Duplicate search detected: {} ({} ago)",
                         entry.query, time_str
                     );
+
+                    let age = time_ago.to_std().unwrap_or_default();
+                    let narrowed = overrides.as_ref().is_some_and(narrows_search);
+                    let max_age = Duration::from_secs(
+                        crate::core::config::search_history_reuse_max_age_mins() * 60,
+                    );
+                    if reuse::should_reuse(
+                        reuse_mode,
+                        score,
+                        age,
+                        min_similarity,
+                        max_age,
+                        narrowed,
+                    ) {
+                        match reuse::results_from_history(&entry.full_result) {
+                            Some(results) => {
+                                info!(
+                                    "Serving '{}' from history entry {} ({})",
+                                    query, entry.id, time_str
+                                );
+                                state.metrics.record_cache("search_history", true);
+                                let (results, filtered_out) = match &result_filters {
+                                    Some(f) => f.apply(results),
+                                    None => (results, FilteredCounts::default()),
+                                };
+                                let extras = SearchExtras {
+                                    duplicate_warning,
+                                    from_history: true,
+                                    history_age_secs: Some(age.as_secs()),
+                                    filtered_out,
+                                    ..Default::default()
+                                };
+                                return Ok((truncate_results(results, max_results), extras));
+                            }
+                            None => {
+                                warn!(
+                                    "History entry {} no longer parses as search results; searching live",
+                                    entry.id
+                                );
+                                stale_history_entry = Some(entry.id.clone());
+                            }
+                        }
+                    }
                 }
                 Ok(None) => {}
-                Err(e) => warn!("Failed to check for duplicates: {}", e),
+                Err(e) => debug!("Skipped duplicate check: {}", e),
             }
         }
     }
 
     // Phase 2: Query rewriting for developer queries
     let rewrite_result = if neurosiphon {
-        let rewriter = QueryRewriter::new();
+        let rewriter = QueryRewriter::new()
+            .with_spell_correction(crate::core::config::spell_correction_enabled());
         rewriter.rewrite_query(query)
     } else {
         QueryRewriteResult {
             original: query.to_string(),
             rewritten: None,
+            corrected: None,
             suggestions: Vec::new(),
             detected_keywords: Vec::new(),
             is_developer_query: false,
@@ -1238,51 +1706,28 @@ pub async fn search_web_with_params(
         query.to_string()
     };
 
-    let cache_key = if let Some(ref ov) = overrides {
-        format!(
-            "q={}|eng={}|cat={}|lang={}|safe={}|time={}|page={}|recover={}|ns={}",
-            query,
-            ov.engines.clone().unwrap_or_default(),
-            ov.categories.clone().unwrap_or_default(),
-            ov.language.clone().unwrap_or_default(),
-            ov.safesearch.map(|v| v.to_string()).unwrap_or_default(),
-            ov.time_range.clone().unwrap_or_default(),
-            ov.pageno
-                .map(|v| v.to_string())
-                .unwrap_or_else(|| "1".into()),
-            if ov.disable_recovery { 0 } else { 1 },
-            if neurosiphon { 1 } else { 0 }
-        )
-    } else {
-        format!("q={}|default|ns={}", query, if neurosiphon { 1 } else { 0 })
-    };
+    let cache_key = search_cache_key(query, overrides.as_ref(), neurosiphon);
 
     let disable_recovery = overrides
         .as_ref()
         .map(|ov| ov.disable_recovery)
         .unwrap_or(false);
 
-    if let Some(cached) = state.search_cache.get(&cache_key).await {
+    let cached = state.search_cache.get(&cache_key).await;
+    state.metrics.record_cache("search", cached.is_some());
+    if let Some(cached) = cached {
         debug!("search cache hit for query");
-        let cached_extras = SearchExtras {
-            suggestions: rewrite_result.suggestions.clone(),
-            query_rewrite: Some(rewrite_result),
-            duplicate_warning,
-            ..Default::default()
-        };
-        return Ok((cached, cached_extras));
+        let cached_extras =
+            with_rewrite_hints(cached.extras.into(), rewrite_result, duplicate_warning);
+        return Ok((cached.results, cached_extras));
     }
 
     if let Some(shared) = read_shared_search_cache(&cache_key).await {
         debug!("shared search cache hit for query");
         state.search_cache.insert(cache_key.clone(), shared.clone()).await;
-        let cached_extras = SearchExtras {
-            suggestions: rewrite_result.suggestions.clone(),
-            query_rewrite: Some(rewrite_result),
-            duplicate_warning,
-            ..Default::default()
-        };
-        return Ok((shared, cached_extras));
+        let cached_extras =
+            with_rewrite_hints(shared.extras.into(), rewrite_result, duplicate_warning);
+        return Ok((shared.results, cached_extras));
     }
 
     let _shared_search_lock = if shared_search_cache_enabled() {
@@ -1292,13 +1737,9 @@ pub async fn search_web_with_params(
                 if let Some(shared) = wait_for_shared_search_result(&cache_key).await {
                     debug!("shared search cache filled by another process");
                     state.search_cache.insert(cache_key.clone(), shared.clone()).await;
-                    let cached_extras = SearchExtras {
-                        suggestions: rewrite_result.suggestions.clone(),
-                        query_rewrite: Some(rewrite_result),
-                        duplicate_warning,
-                        ..Default::default()
-                    };
-                    return Ok((shared, cached_extras));
+                    let cached_extras =
+                        with_rewrite_hints(shared.extras.into(), rewrite_result, duplicate_warning);
+                    return Ok((shared.results, cached_extras));
                 }
                 try_acquire_shared_search_leader(&cache_key)
             }
@@ -1317,7 +1758,33 @@ pub async fn search_web_with_params(
         .search_service
         .search(state, &effective_query, overrides.clone())
         .await
-        .map_err(|e| anyhow!("internal search failed: {}", e))?;
+        .map_err(|e| e.context("internal search failed"))?;
+
+    let spell_retry_min = crate::core::config::spell_retry_min_results();
+    if let Some(corrected) = rewrite_result.corrected.as_ref() {
+        if !disable_recovery && search_outcome.results.len() < spell_retry_min {
+            info!(
+                "search: '{}' returned {} result(s); also searching corrected '{}'",
+                query,
+                search_outcome.results.len(),
+                corrected
+            );
+            match state
+                .search_service
+                .search(state, corrected, overrides.clone())
+                .await
+            {
+                Ok(corrected_outcome) => {
+                    merge_search_extras(&mut search_outcome.extras, corrected_outcome.extras);
+                    // Corrected results lead; the original ones stay as a tail.
+                    let original_results =
+                        std::mem::replace(&mut search_outcome.results, corrected_outcome.results);
+                    search_outcome.results.extend(original_results);
+                }
+                Err(e) => warn!("search for corrected query '{}' failed: {}", corrected, e),
+            }
+        }
+    }
 
     if !disable_recovery && search_outcome.results.is_empty() {
         let recovery_rewrite = if rewrite_result.is_developer_query {
@@ -1367,13 +1834,12 @@ pub async fn search_web_with_params(
         }
     }
 
-    // Internal engines don't provide external-backend "extras"; keep only rewrite+dup warning.
-    let mut extras = SearchExtras {
-        suggestions: rewrite_result.suggestions.clone(),
-        query_rewrite: Some(rewrite_result),
-        duplicate_warning,
-        ..search_outcome.extras
+    let engine_extras = SerpExtras {
+        answers: search_outcome.extras.answers.clone(),
+        suggestions: search_outcome.extras.suggestions.clone(),
+        corrections: search_outcome.extras.corrections.clone(),
     };
+    let mut extras = with_rewrite_hints(search_outcome.extras, rewrite_result, duplicate_warning);
     extras.unresponsive_engines.sort();
     extras.unresponsive_engines.dedup();
     extras.degraded_engines.sort();
@@ -1381,6 +1847,26 @@ pub async fn search_web_with_params(
     extras.skipped_engines.sort();
     extras.skipped_engines.dedup();
 
+    // Caller filters run before reranking so the top-N is filled from usable results.
+    if let Some(filters) = &result_filters {
+        let (kept, filtered_out) = filters.apply(results);
+        if filtered_out.total() > 0 {
+            debug!("search filters removed {} result(s)", filtered_out.total());
+        }
+        results = kept;
+        extras.filtered_out = filtered_out;
+    }
+
+    // Detect languages before reranking so `detected_language` is visible to the reranker;
+    // filtering/demotion runs afterwards so reranking cannot undo it.
+    let requested_language = overrides
+        .as_ref()
+        .and_then(|ov| ov.language.clone())
+        .filter(|lang| !lang.trim().is_empty());
+    if requested_language.is_some() {
+        language::annotate_languages(&mut results);
+    }
+
     // Enhanced semantic reranking with keyword boosting (NeuroSiphon mode)
     let final_results = if neurosiphon {
         let reranker = Reranker::new(query);
@@ -1395,33 +1881,136 @@ pub async fn search_web_with_params(
     } else {
         results
     };
+    let final_results = match requested_language.as_deref() {
+        Some(lang) => {
+            let mode = overrides
+                .as_ref()
+                .and_then(|ov| ov.language_mode)
+                .unwrap_or_else(LanguageFilterMode::from_env);
+            let before = final_results.len();
+            let kept = language::apply_language_preference(final_results, lang, mode);
+            debug!(
+                "language preference '{}' ({}): kept {}/{} results",
+                lang,
+                mode.as_str(),
+                kept.len(),
+                before
+            );
+            kept
+        }
+        None => final_results,
+    };
+    let final_results = truncate_results(final_results, max_results);
 
     let cacheable = !(final_results.is_empty()
         && (!extras.degraded_engines.is_empty() || !extras.skipped_engines.is_empty()));
     if cacheable {
-        state
-            .search_cache
-            .insert(cache_key.clone(), final_results.clone())
-            .await;
-        write_shared_search_cache(&cache_key, &final_results).await;
+        let cached = CachedSearch {
+            results: final_results.clone(),
+            extras: engine_extras,
+        };
+        write_shared_search_cache(&cache_key, &cached).await;
+        state.search_cache.insert(cache_key.clone(), cached).await;
     } else {
         debug!("skipping cache for empty degraded search result set");
     }
 
-    if let Some(memory) = state.get_memory() {
-        let result_json = serde_json::to_value(&final_results).unwrap_or_default();
-
-        if let Err(e) = memory
-            .log_search(query.to_string(), &result_json, final_results.len())
-            .await
-        {
-            warn!("Failed to log search to history: {}", e);
-        }
+    if state.get_memory().is_some() {
+        // Queued; the fresh entry replaces the one whose results could not be reused.
+        state.memory_guard.log_search(
+            query.to_string(),
+            serde_json::to_value(&final_results).unwrap_or_default(),
+            final_results.len(),
+            stale_history_entry,
+        );
     }
 
     Ok((final_results, extras))
 }
 
+/// Whether `overrides` restrict the search in a way a history entry does not record.
+fn narrows_search(overrides: &SearchParamOverrides) -> bool {
+    let set = |v: &Option<String>| v.as_deref().is_some_and(|s| !s.trim().is_empty());
+    set(&overrides.engines)
+        || set(&overrides.categories)
+        || set(&overrides.language)
+        || set(&overrides.time_range)
+        || overrides.safesearch.is_some()
+        || overrides.pageno.is_some_and(|page| page > 1)
+        || !overrides.include_domains.is_empty()
+        || !overrides.exclude_domains.is_empty()
+        || !overrides.exclude_url_patterns.is_empty()
+        || overrides.filetype.is_some()
+        || !overrides.intitle.is_empty()
+        || overrides.published_after.is_some()
+        || overrides.published_before.is_some()
+}
+
+fn search_cache_key(
+    query: &str,
+    overrides: Option<&SearchParamOverrides>,
+    neurosiphon: bool,
+) -> String {
+    if let Some(ov) = overrides {
+        format!(
+            "q={}|eng={}|cat={}|lang={}|safe={}|time={}|page={}|max={}|recover={}|inc={}|exc={}|pat={}|ft={}|title={}|dates={}..{}|ns={}",
+            query,
+            ov.engines.clone().unwrap_or_default(),
+            ov.categories.clone().unwrap_or_default(),
+            search_cache_language(ov),
+            ov.safesearch.map(|v| v.to_string()).unwrap_or_default(),
+            ov.time_range.clone().unwrap_or_default(),
+            ov.pageno
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "1".into()),
+            ov.max_results
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "all".into()),
+            if ov.disable_recovery { 0 } else { 1 },
+            ov.include_domains.join(","),
+            ov.exclude_domains.join(","),
+            // Regexes may contain commas.
+            ov.exclude_url_patterns.join("\u{1f}"),
+            ov.filetype.clone().unwrap_or_default(),
+            ov.intitle.join("\u{1f}"),
+            ov.published_after.map(|d| d.to_string()).unwrap_or_default(),
+            ov.published_before.map(|d| d.to_string()).unwrap_or_default(),
+            if neurosiphon { 1 } else { 0 }
+        )
+    } else {
+        format!("q={}|default|ns={}", query, if neurosiphon { 1 } else { 0 })
+    }
+}
+
+/// Language segment of the cache key; the filter mode only matters when a language is set.
+fn search_cache_language(ov: &SearchParamOverrides) -> String {
+    match ov
+        .language
+        .as_deref()
+        .filter(|lang| !lang.trim().is_empty())
+    {
+        Some(lang) => format!(
+            "{}:{}",
+            lang,
+            ov.language_mode
+                .unwrap_or_else(LanguageFilterMode::from_env)
+                .as_str()
+        ),
+        None => String::new(),
+    }
+}
+
+/// Cap the merged result list after reranking so the limit applies to the best results.
+fn truncate_results(
+    mut results: Vec<SearchResult>,
+    max_results: Option<usize>,
+) -> Vec<SearchResult> {
+    if let Some(max) = max_results {
+        results.truncate(max);
+    }
+    results
+}
+
 fn zero_result_recovery_queries(
     original_query: &str,
     effective_query: &str,
@@ -1447,15 +2036,59 @@ fn zero_result_recovery_queries(
     candidates
 }
 
+impl From<SerpExtras> for SearchExtras {
+    fn from(serp: SerpExtras) -> Self {
+        SearchExtras {
+            answers: serp.answers,
+            suggestions: serp.suggestions,
+            corrections: serp.corrections,
+            ..Default::default()
+        }
+    }
+}
+
+/// Append `items` to `target`, skipping case-insensitive repeats.
+fn push_unique(target: &mut Vec<String>, items: &[String]) {
+    for item in items {
+        if !target.iter().any(|t| t.eq_ignore_ascii_case(item)) {
+            target.push(item.clone());
+        }
+    }
+}
+
+/// Add the per-call rewrite hints to the engines' extras: the rewriter's suggestions and
+/// correction lead, and engine ones that repeat them are dropped.
+fn with_rewrite_hints(
+    engine: SearchExtras,
+    rewrite: QueryRewriteResult,
+    duplicate_warning: Option<String>,
+) -> SearchExtras {
+    let mut suggestions = rewrite.suggestions.clone();
+    push_unique(&mut suggestions, &engine.suggestions);
+    let mut corrections: Vec<String> = rewrite.corrected.iter().cloned().collect();
+    push_unique(&mut corrections, &engine.corrections);
+    SearchExtras {
+        suggestions,
+        corrections,
+        query_rewrite: Some(rewrite),
+        duplicate_warning,
+        ..engine
+    }
+}
+
 fn merge_search_extras(target: &mut SearchExtras, addition: SearchExtras) {
-    target.answers.extend(addition.answers);
-    target.suggestions.extend(addition.suggestions);
-    target.corrections.extend(addition.corrections);
+    push_unique(&mut target.answers, &addition.answers);
+    push_unique(&mut target.suggestions, &addition.suggestions);
+    push_unique(&mut target.corrections, &addition.corrections);
     target
         .unresponsive_engines
         .extend(addition.unresponsive_engines);
+    target.engines_succeeded = target.engines_succeeded.max(addition.engines_succeeded);
     target.degraded_engines.extend(addition.degraded_engines);
     target.skipped_engines.extend(addition.skipped_engines);
+    target
+        .time_filtered_engines
+        .extend(addition.time_filtered_engines);
 
     if target.query_rewrite.is_none() {
         target.query_rewrite = addition.query_rewrite;
@@ -1556,7 +2189,7 @@ fn try_acquire_shared_search_leader(cache_key: &str) -> Option<SharedSearchLeade
     None
 }
 
-async fn read_shared_search_cache(cache_key: &str) -> Option<Vec<SearchResult>> {
+async fn read_shared_search_cache(cache_key: &str) -> Option<CachedSearch> {
     if !shared_search_cache_enabled() {
         return None;
     }
@@ -1572,10 +2205,13 @@ async fn read_shared_search_cache(cache_key: &str) -> Option<Vec<SearchResult>>
         return None;
     }
 
-    Some(entry.results)
+    Some(CachedSearch {
+        results: entry.results,
+        extras: entry.extras,
+    })
 }
 
-async fn wait_for_shared_search_result(cache_key: &str) -> Option<Vec<SearchResult>> {
+async fn wait_for_shared_search_result(cache_key: &str) -> Option<CachedSearch> {
     let deadline = Instant::now() + Duration::from_secs(shared_search_lock_wait_secs());
     let lock_path = shared_search_lock_path(cache_key);
     while Instant::now() < deadline {
@@ -1603,8 +2239,8 @@ async fn wait_for_shared_search_result(cache_key: &str) -> Option<Vec<SearchResu
     None
 }
 
-async fn write_shared_search_cache(cache_key: &str, results: &[SearchResult]) {
-    if !shared_search_cache_enabled() || results.is_empty() {
+async fn write_shared_search_cache(cache_key: &str, cached: &CachedSearch) {
+    if !shared_search_cache_enabled() || cached.results.is_empty() {
         return;
     }
 
@@ -1617,7 +2253,8 @@ async fn write_shared_search_cache(cache_key: &str, results: &[SearchResult]) {
 
     let entry = SharedSearchCacheEntry {
         cached_at_ms: chrono::Utc::now().timestamp_millis(),
-        results: results.to_vec(),
+        results: cached.results.clone(),
+        extras: cached.extras.clone(),
     };
     let Ok(bytes) = serde_json::to_vec(&entry) else {
         return;
@@ -1779,13 +2416,126 @@ mod tests {
             "duckduckgo".to_string(),
             "brave".to_string(),
         ];
-        let (selected, skipped) = service.select_engines(&requested, false);
+        let (selected, skipped) = service.select_engines(
+            &requested,
+            false,
+            &EngineStats::default(),
+            &EngineBackoff::default(),
+        );
 
         assert!(!selected.iter().any(|engine| engine == "google"));
         assert!(selected.iter().any(|engine| engine == "bing"));
         assert!(skipped.iter().any(|entry| entry.contains("google(cooldown")));
     }
 
+    #[test]
+    fn select_engines_skips_benched_engines_unless_all_are_benched() {
+        let service = InternalSearchService::new();
+        let stats = EngineStats::new(engine_stats::BenchPolicy {
+            window: 2,
+            threshold: 0.5,
+            bench_secs: 60,
+        });
+        let blocked = EngineOutcome::Blocked {
+            reason: "captcha".to_string(),
+        };
+        for _ in 0..2 {
+            stats.record("bing", &blocked, Duration::from_millis(50));
+        }
+
+        let requested = vec!["bing".to_string(), "brave".to_string()];
+        let (selected, skipped) =
+            service.select_engines(&requested, true, &stats, &EngineBackoff::default());
+        assert_eq!(selected, vec!["brave".to_string()]);
+        assert!(skipped[0].starts_with("bing(benched:"));
+
+        let only_bing = vec!["bing".to_string()];
+        let (selected, _) =
+            service.select_engines(&only_bing, true, &stats, &EngineBackoff::default());
+        assert_eq!(
+            selected, only_bing,
+            "a fully benched list still probes one engine"
+        );
+    }
+
+    #[test]
+    fn rate_limited_engines_are_reported_last_and_never_rescue() {
+        let service = InternalSearchService::new();
+        let backoff = EngineBackoff::default();
+        backoff.record_block("google", "http_429", None);
+
+        let requested = vec!["google".to_string(), "bing".to_string()];
+        let (selected, skipped) =
+            service.select_engines(&requested, true, &EngineStats::default(), &backoff);
+        assert_eq!(selected, vec!["bing".to_string(), "google".to_string()]);
+        assert!(skipped.is_empty());
+
+        let attempted: HashSet<String> = ["bing".to_string()].into_iter().collect();
+        assert_eq!(
+            service.select_rescue_engine(&requested, &attempted, &EngineStats::default(), &backoff),
+            None
+        );
+        assert_eq!(
+            EngineRunStatus::CoolingDown {
+                remaining: Duration::from_secs(60)
+            }
+            .failure_cause()
+            .as_deref(),
+            Some("cooling_down_60s")
+        );
+    }
+
+    #[test]
+    fn engine_list_is_validated_against_registry() {
+        let (known, unknown) = InternalSearchService::parse_engine_list(Some(
+            "Google, ddg,duckduckgo,altavista,,mojeek".to_string(),
+        ));
+        assert_eq!(known, ["google", "duckduckgo", "mojeek"]);
+        assert_eq!(unknown, ["altavista"]);
+    }
+
+    #[test]
+    fn engine_extras_follow_rewrite_hints_without_repeats() {
+        let runs = [
+            EngineRunOutput {
+                engine: "duckduckgo".to_string(),
+                results: Vec::new(),
+                extras: SerpExtras {
+                    answers: vec!["10 miles = 16.09344 kilometers".to_string()],
+                    suggestions: vec!["miles to km".to_string()],
+                    corrections: vec!["10 Miles in km".to_string()],
+                },
+                status: EngineRunStatus::Success,
+                time_filtered: false,
+            },
+            EngineRunOutput {
+                engine: "google".to_string(),
+                results: Vec::new(),
+                extras: SerpExtras::default(),
+                status: EngineRunStatus::Success,
+                time_filtered: false,
+            },
+        ];
+        let engine = InternalSearchService::extras_from_runs(&runs, Vec::new());
+        let rewrite = QueryRewriteResult {
+            original: "10 mils in km".to_string(),
+            rewritten: None,
+            corrected: Some("10 miles in km".to_string()),
+            suggestions: vec!["10 miles in kilometers".to_string()],
+            detected_keywords: Vec::new(),
+            is_developer_query: false,
+        };
+
+        let extras = with_rewrite_hints(engine, rewrite, None);
+        assert_eq!(extras.answers, ["10 miles = 16.09344 kilometers"]);
+        assert_eq!(extras.corrections, ["10 miles in km"]);
+        assert_eq!(
+            extras.suggestions,
+            ["10 miles in kilometers", "miles to km"]
+        );
+        assert!(extras.query_rewrite.is_some());
+    }
+
     #[test]
     fn blocked_status_generates_degraded_telemetry() {
         let extras = InternalSearchService::extras_from_runs(
@@ -1793,22 +2543,28 @@ mod tests {
                 EngineRunOutput {
                     engine: "google".to_string(),
                     results: Vec::new(),
+                    extras: SerpExtras::default(),
                     status: EngineRunStatus::Blocked {
                         reason: "http_429".to_string(),
                     },
+                    time_filtered: true,
                 },
                 EngineRunOutput {
                     engine: "bing".to_string(),
                     results: Vec::new(),
+                    extras: SerpExtras::default(),
                     status: EngineRunStatus::Recovered {
                         reason: "cloudflare".to_string(),
                     },
+                    time_filtered: false,
                 },
             ],
             vec!["duckduckgo(cooldown:30s after timeout)".to_string()],
         );
 
-        assert_eq!(extras.unresponsive_engines, vec!["google".to_string()]);
+        assert_eq!(extras.unresponsive_engines, ["google: blocked:http_429"]);
+        assert_eq!(extras.engines_succeeded, 1, "a recovered engine answered");
+        assert_eq!(extras.time_filtered_engines, vec!["google".to_string()]);
         assert!(extras
             .degraded_engines
             .iter()
@@ -1819,4 +2575,142 @@ mod tests {
             .any(|entry| entry == "bing(recovered_via_fallback:cloudflare)"));
         assert_eq!(extras.skipped_engines.len(), 1);
     }
+
+    #[test]
+    fn search_without_any_answering_engine_is_an_upstream_error() {
+        let failed = |engine: &str, status| EngineRunOutput {
+            engine: engine.to_string(),
+            results: Vec::new(),
+            extras: SerpExtras::default(),
+            status,
+            time_filtered: false,
+        };
+        let extras = InternalSearchService::extras_from_runs(
+            &[
+                failed(
+                    "google",
+                    EngineRunStatus::Timeout {
+                        after: Duration::from_millis(2500),
+                    },
+                ),
+                failed(
+                    "duckduckgo",
+                    EngineRunStatus::Blocked {
+                        reason: "captcha".to_string(),
+                    },
+                ),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(extras.engines_succeeded, 0);
+        assert_eq!(
+            extras.unresponsive_engines,
+            ["google: timeout_2500ms", "duckduckgo: blocked:captcha"]
+        );
+
+        let err = InternalSearchService::no_engine_answered(&extras);
+        assert_eq!(err.kind, crate::core::error::ErrorKind::UpstreamUnavailable);
+        assert!(err.message.contains("google: timeout_2500ms"));
+        // The kind survives the context `run_search` adds.
+        let wrapped = anyhow::Error::from(err).context("internal search failed");
+        assert_eq!(
+            ScoutError::classify(&wrapped).kind,
+            crate::core::error::ErrorKind::UpstreamUnavailable
+        );
+    }
+
+    #[test]
+    fn cache_key_distinguishes_max_results() {
+        let five = SearchParamOverrides {
+            max_results: Some(5),
+            ..Default::default()
+        };
+        let thirty = SearchParamOverrides {
+            max_results: Some(30),
+            ..Default::default()
+        };
+        let unbounded = SearchParamOverrides::default();
+
+        let k5 = search_cache_key("rust async", Some(&five), true);
+        let k30 = search_cache_key("rust async", Some(&thirty), true);
+        let kall = search_cache_key("rust async", Some(&unbounded), true);
+        assert_ne!(k5, k30);
+        assert_ne!(k5, kall);
+        assert!(k5.contains("|max=5|"));
+    }
+
+    #[test]
+    fn carousel_ages_resolve_to_dates() {
+        let now = chrono::DateTime::parse_from_rfc3339("2025-03-10T08:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let age = |text: &str| published_at_from_age(text, now);
+        assert_eq!(age("5h").as_deref(), Some("2025-03-10"));
+        assert_eq!(age("10h").as_deref(), Some("2025-03-09"));
+        assert_eq!(age("2d").as_deref(), Some("2025-03-08"));
+        assert_eq!(age("3 days ago").as_deref(), Some("2025-03-07"));
+        assert_eq!(age("1 week ago").as_deref(), Some("2025-03-03"));
+        assert_eq!(age("2mo").as_deref(), Some("2025-01-09"));
+        // Counts and durations are not ages.
+        assert_eq!(age("152K views"), None);
+        assert_eq!(age("19:42"), None);
+        assert_eq!(age("12 items"), None);
+    }
+
+    #[test]
+    fn results_older_than_time_range_are_demoted() {
+        let today = chrono::Utc::now().date_naive();
+        let published = |days: i64| (today - chrono::Duration::days(days)).to_string();
+        let results = vec![
+            SearchResult {
+                url: "https://example.com/old".to_string(),
+                engine: Some("bing".to_string()),
+                published_at: Some(published(400)),
+                ..Default::default()
+            },
+            SearchResult {
+                url: "https://example.com/fresh".to_string(),
+                engine: Some("bing".to_string()),
+                published_at: Some(published(2)),
+                ..Default::default()
+            },
+            SearchResult {
+                url: "https://example.com/undated".to_string(),
+                engine: Some("bing".to_string()),
+                ..Default::default()
+            },
+        ];
+        let score = |scored: &[SearchResult], url: &str| {
+            scored
+                .iter()
+                .find(|r| r.url.ends_with(url))
+                .and_then(|r| r.score)
+                .unwrap()
+        };
+
+        let unfiltered = dedup_and_score_results(results.clone(), "rust", None);
+        let weekly = dedup_and_score_results(results, "rust", Some(TimeRange::Week));
+        assert_eq!(
+            score(&weekly, "/old"),
+            score(&unfiltered, "/old") * STALE_RESULT_FACTOR
+        );
+        assert_eq!(score(&weekly, "/fresh"), score(&unfiltered, "/fresh"));
+        assert_eq!(score(&weekly, "/undated"), score(&unfiltered, "/undated"));
+        assert!(score(&weekly, "/old") < score(&weekly, "/undated"));
+    }
+
+    #[test]
+    fn truncate_results_caps_merged_list() {
+        let results: Vec<SearchResult> = (0..8)
+            .map(|i| SearchResult {
+                url: format!("https://example.com/{}", i),
+                ..Default::default()
+            })
+            .collect();
+
+        assert_eq!(truncate_results(results.clone(), Some(3)).len(), 3);
+        assert_eq!(truncate_results(results.clone(), Some(0)).len(), 0);
+        assert_eq!(truncate_results(results.clone(), Some(50)).len(), 8);
+        assert_eq!(truncate_results(results, None).len(), 8);
+    }
 }
@@ -0,0 +1,344 @@
+//! Search operators typed into the query: `site:`, `-site:`, `filetype:` (or `ext:`),
+//! `intitle:`, `after:` and `before:`.
+//!
+//! `run_search` lifts them out of the query into `SearchParamOverrides` before anything
+//! else sees it, so query rewriting, history lookups, the cache key and reranking work on
+//! the plain terms. Each engine then gets back only the operators it understands (see
+//! `SearchEngine::operators`), and the result filters enforce the domain, file type and
+//! date constraints on every engine's results, including the ones that ignored them.
+
+use super::{filters, SearchParamOverrides};
+use crate::tools::search::engines::SearchEngine;
+use chrono::NaiveDate;
+
+/// An operator an engine accepts in its query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Site,
+    ExcludeSite,
+    Filetype,
+    Intitle,
+    /// `after:` / `before:` with a `YYYY-MM-DD` date.
+    DateRange,
+}
+
+/// Operators lifted out of a query.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct QueryOperators {
+    /// Domain filter entries: `site:python.org` becomes `*.python.org`, which (like the
+    /// engines' own `site:`) covers subdomains.
+    pub sites: Vec<String>,
+    pub excluded_sites: Vec<String>,
+    /// Lowercase extension without the dot.
+    pub filetype: Option<String>,
+    pub intitle: Vec<String>,
+    /// Inclusive lower bound; `after:2023` is 2023-01-01.
+    pub after: Option<NaiveDate>,
+    /// Exclusive upper bound; `before:2023-06` is 2023-06-01.
+    pub before: Option<NaiveDate>,
+}
+
+impl QueryOperators {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `exclude_domains` entries sent to engines as `-site:`; the rest are only filtered.
+const MAX_RENDERED_EXCLUSIONS: usize = 5;
+
+/// Split `query` into its plain terms and operators. Quoted phrases are kept whole, so
+/// `"error: cannot find"` stays a phrase, and an operator whose value does not parse
+/// (`site:` alone, `after:yesterday`) stays in the terms as typed.
+pub fn parse(query: &str) -> (String, QueryOperators) {
+    let mut terms = Vec::new();
+    let mut operators = QueryOperators::default();
+    for token in tokenize(query) {
+        if !lift(&token, &mut operators) {
+            terms.push(token);
+        }
+    }
+    (terms.join(" "), operators)
+}
+
+/// Lift the operators in `query` into `overrides`: `site:` into `include_domains`,
+/// `-site:` into `exclude_domains`, the rest into their own fields. Returns the plain
+/// terms. A query that is nothing but operators is left as typed.
+pub fn extract(
+    query: &str,
+    overrides: Option<SearchParamOverrides>,
+) -> (String, Option<SearchParamOverrides>) {
+    let (terms, operators) = parse(query);
+    if operators.is_empty() || terms.is_empty() {
+        return (query.to_string(), overrides);
+    }
+    let mut ov = overrides.unwrap_or_default();
+    ov.include_domains.extend(operators.sites);
+    ov.exclude_domains.extend(operators.excluded_sites);
+    if operators.filetype.is_some() {
+        ov.filetype = operators.filetype;
+    }
+    ov.intitle.extend(operators.intitle);
+    // Both bounds keep the narrower of the two windows.
+    ov.published_after = ov.published_after.max(operators.after);
+    ov.published_before = match (ov.published_before, operators.before) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    (terms, Some(ov))
+}
+
+/// `query` as sent to `engine`: the plain terms plus the operators in `overrides` that
+/// the engine understands. An `intitle:` term it can't take goes along as a plain term;
+/// other unsupported operators are left to the result filters.
+pub fn render(
+    engine: &dyn SearchEngine,
+    query: &str,
+    overrides: Option<&SearchParamOverrides>,
+) -> String {
+    let Some(ov) = overrides else {
+        return query.to_string();
+    };
+    let supports = |operator: Operator| engine.operators().contains(&operator);
+    let mut parts = vec![query.to_string()];
+    if supports(Operator::Site) {
+        parts.extend(filters::site_operator(&ov.include_domains));
+    }
+    if supports(Operator::ExcludeSite) {
+        parts.extend(
+            ov.exclude_domains
+                .iter()
+                .filter_map(|domain| filters::site_operator(std::slice::from_ref(domain)))
+                .take(MAX_RENDERED_EXCLUSIONS)
+                .map(|site| format!("-{}", site)),
+        );
+    }
+    if let Some(filetype) = ov
+        .filetype
+        .as_deref()
+        .filter(|_| supports(Operator::Filetype))
+    {
+        parts.push(format!("filetype:{}", filetype));
+    }
+    for title in &ov.intitle {
+        if supports(Operator::Intitle) {
+            parts.push(format!("intitle:{}", phrase(title)));
+        } else {
+            parts.push(phrase(title));
+        }
+    }
+    if supports(Operator::DateRange) {
+        parts.extend(ov.published_after.map(|d| format!("after:{}", d)));
+        parts.extend(ov.published_before.map(|d| format!("before:{}", d)));
+    }
+    parts.retain(|part| !part.is_empty());
+    parts.join(" ")
+}
+
+/// Whitespace-separated tokens; whitespace inside double quotes does not split, and the
+/// quotes stay in the token.
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        if c == '"' {
+            quoted = !quoted;
+            current.push(c);
+        } else if c.is_whitespace() && !quoted {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Record `token` in `operators` if it is an operator with a usable value.
+fn lift(token: &str, operators: &mut QueryOperators) -> bool {
+    let Some((name, value)) = token.split_once(':') else {
+        return false;
+    };
+    // A colon inside a phrase (`"error: cannot find"`) is not an operator.
+    if name.contains('"') {
+        return false;
+    }
+    let value = value.trim_matches('"').trim();
+    if value.is_empty() {
+        return false;
+    }
+    match name.to_ascii_lowercase().as_str() {
+        "site" | "-site" => {
+            let Some(domain) = site_domain(value) else {
+                return false;
+            };
+            if name.starts_with('-') {
+                operators.excluded_sites.push(domain);
+            } else {
+                operators.sites.push(domain);
+            }
+        }
+        "filetype" | "ext" => {
+            let extension = value.trim_start_matches('.').to_ascii_lowercase();
+            if extension.is_empty()
+                || extension.len() > 8
+                || !extension.chars().all(|c| c.is_ascii_alphanumeric())
+            {
+                return false;
+            }
+            operators.filetype = Some(extension);
+        }
+        "intitle" => operators.intitle.push(value.to_string()),
+        "after" => match date_bound(value) {
+            Some(date) => operators.after = Some(date),
+            None => return false,
+        },
+        "before" => match date_bound(value) {
+            Some(date) => operators.before = Some(date),
+            None => return false,
+        },
+        _ => return false,
+    }
+    true
+}
+
+/// `site:` value as a domain filter entry covering subdomains, or `None` when it is
+/// not a bare domain (`site:docs.python.org/3` stays in the query).
+fn site_domain(value: &str) -> Option<String> {
+    let host = value.trim_end_matches('.').to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let valid = host.contains('.')
+        && !host.starts_with('.')
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    valid.then(|| format!("*.{}", host))
+}
+
+/// `YYYY`, `YYYY-MM` or `YYYY-MM-DD`, as the first day it names.
+fn date_bound(value: &str) -> Option<NaiveDate> {
+    let parts: Vec<&str> = value.split('-').collect();
+    let number = |i: usize| parts.get(i).and_then(|p| p.parse::<u32>().ok());
+    if parts.len() > 3 || parts[0].len() != 4 {
+        return None;
+    }
+    let year = i32::try_from(number(0)?).ok()?;
+    let month = if parts.len() > 1 { number(1)? } else { 1 };
+    let day = if parts.len() > 2 { number(2)? } else { 1 };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+fn phrase(text: &str) -> String {
+    if text.contains(char::is_whitespace) {
+        format!("\"{}\"", text)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::search::engines;
+
+    fn date(raw: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(raw, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn operators_are_lifted_out_of_the_query() {
+        let (terms, operators) = parse(
+            "asyncio tutorial site:docs.python.org -site:www.w3schools.com FILETYPE:pdf \
+             intitle:\"event loop\" after:2023 before:2024-06",
+        );
+        assert_eq!(terms, "asyncio tutorial");
+        assert_eq!(
+            operators,
+            QueryOperators {
+                sites: vec!["*.docs.python.org".to_string()],
+                excluded_sites: vec!["*.w3schools.com".to_string()],
+                filetype: Some("pdf".to_string()),
+                intitle: vec!["event loop".to_string()],
+                after: Some(date("2023-01-01")),
+                before: Some(date("2024-06-01")),
+            }
+        );
+    }
+
+    #[test]
+    fn colons_in_phrases_and_unparsable_values_stay_in_the_query() {
+        for query in [
+            "\"error: cannot find\" rustc",
+            "rustc \"error: cannot find value\" E0425",
+            "\"see site:example.com\" quote",
+            "note: unrelated colon",
+            "http://example.com/path",
+            "site: empty",
+            "site:docs.python.org/3 asyncio",
+            "released after:yesterday",
+            "filetype:not-an-ext report",
+        ] {
+            let (terms, operators) = parse(query);
+            assert_eq!(terms, query, "{}", query);
+            assert!(operators.is_empty(), "{}", query);
+        }
+
+        // A phrase survives next to real operators.
+        let (terms, operators) = parse("\"error: cannot find\" site:users.rust-lang.org");
+        assert_eq!(terms, "\"error: cannot find\"");
+        assert_eq!(operators.sites, ["*.users.rust-lang.org"]);
+    }
+
+    #[test]
+    fn extract_fills_overrides_and_keeps_operator_only_queries() {
+        let caller = SearchParamOverrides {
+            include_domains: vec!["python.org".to_string()],
+            published_before: Some(date("2024-01-01")),
+            ..Default::default()
+        };
+        let (terms, ov) = extract("pep 8 site:peps.python.org before:2025", Some(caller));
+        let ov = ov.unwrap();
+        assert_eq!(terms, "pep 8");
+        assert_eq!(ov.include_domains, ["python.org", "*.peps.python.org"]);
+        assert_eq!(ov.published_before, Some(date("2024-01-01")));
+
+        let (terms, ov) = extract("site:example.com", None);
+        assert_eq!(terms, "site:example.com");
+        assert!(ov.is_none());
+    }
+
+    #[test]
+    fn each_engine_gets_only_the_operators_it_understands() {
+        let ov = SearchParamOverrides {
+            include_domains: vec!["*.docs.python.org".to_string()],
+            exclude_domains: vec!["*.w3schools.com".to_string()],
+            filetype: Some("pdf".to_string()),
+            intitle: vec!["event loop".to_string()],
+            published_after: Some(date("2023-01-01")),
+            ..Default::default()
+        };
+        let render_for = |name: &str| render(engines::lookup(name).unwrap(), "asyncio", Some(&ov));
+        assert_eq!(
+            render_for("google"),
+            "asyncio site:docs.python.org -site:w3schools.com filetype:pdf \
+             intitle:\"event loop\" after:2023-01-01"
+        );
+        assert_eq!(
+            render_for("bing"),
+            "asyncio site:docs.python.org -site:w3schools.com filetype:pdf intitle:\"event loop\""
+        );
+        assert_eq!(
+            render_for("mojeek"),
+            "asyncio site:docs.python.org \"event loop\""
+        );
+        assert_eq!(
+            render(engines::lookup("google").unwrap(), "asyncio", None),
+            "asyncio"
+        );
+    }
+}
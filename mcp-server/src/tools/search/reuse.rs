@@ -0,0 +1,160 @@
+//! Serving a repeated search from semantic memory instead of the engines.
+//!
+//! `web_search` logs every result list to history. When a new query is close enough to
+//! one logged recently, the logged results are returned as they were (`from_history`),
+//! saving a full engine fan-out. `reuse_recent` picks when that happens.
+
+use crate::types::SearchResult;
+use serde_json::Value;
+use std::time::Duration;
+
+/// The `reuse_recent` search argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReuseRecent {
+    /// Reuse a match younger than the configured max age, unless the call narrows the
+    /// search (engines, categories, language, time range or a later page) in a way
+    /// history does not record.
+    #[default]
+    Auto,
+    /// Reuse any match found in the lookback window.
+    Always,
+    /// Always search live; a match only produces `duplicate_warning`.
+    Never,
+}
+
+impl ReuseRecent {
+    pub fn parse_str(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "always" => Some(Self::Always),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Always => "always",
+            Self::Never => "never",
+        }
+    }
+}
+
+/// Whether a history match with `similarity`, logged `age` ago, should be served.
+/// Both bounds are inclusive.
+pub fn should_reuse(
+    mode: ReuseRecent,
+    similarity: f32,
+    age: Duration,
+    min_similarity: f32,
+    max_age: Duration,
+    narrowed: bool,
+) -> bool {
+    if similarity < min_similarity {
+        return false;
+    }
+    match mode {
+        ReuseRecent::Never => false,
+        ReuseRecent::Always => true,
+        ReuseRecent::Auto => !narrowed && age <= max_age,
+    }
+}
+
+/// The results a history entry stored, or `None` when they no longer parse (an older
+/// format, or a list truncated by history's size cap).
+pub fn results_from_history(full_result: &Value) -> Option<Vec<SearchResult>> {
+    serde_json::from_value(full_result.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const MIN: f32 = 0.9;
+    const MAX_AGE: Duration = Duration::from_secs(30 * 60);
+
+    #[test]
+    fn thresholds_are_inclusive() {
+        let auto = ReuseRecent::Auto;
+        assert!(should_reuse(auto, 0.9, MAX_AGE, MIN, MAX_AGE, false));
+        assert!(!should_reuse(auto, 0.8999, MAX_AGE, MIN, MAX_AGE, false));
+        assert!(!should_reuse(
+            auto,
+            1.0,
+            MAX_AGE + Duration::from_secs(1),
+            MIN,
+            MAX_AGE,
+            false
+        ));
+        assert!(!should_reuse(auto, 1.0, Duration::ZERO, MIN, MAX_AGE, true));
+    }
+
+    #[test]
+    fn always_ignores_age_and_narrowing_but_not_similarity() {
+        let old = Duration::from_secs(5 * 60 * 60);
+        assert!(should_reuse(
+            ReuseRecent::Always,
+            0.95,
+            old,
+            MIN,
+            MAX_AGE,
+            true
+        ));
+        assert!(!should_reuse(
+            ReuseRecent::Always,
+            0.5,
+            old,
+            MIN,
+            MAX_AGE,
+            true
+        ));
+        assert!(!should_reuse(
+            ReuseRecent::Never,
+            1.0,
+            Duration::ZERO,
+            MIN,
+            MAX_AGE,
+            false
+        ));
+        assert_eq!(
+            ReuseRecent::parse_str(" ALWAYS "),
+            Some(ReuseRecent::Always)
+        );
+        assert_eq!(ReuseRecent::parse_str("sometimes"), None);
+    }
+
+    #[test]
+    fn logged_results_round_trip() {
+        let results = vec![SearchResult {
+            url: "https://docs.rs/tokio".to_string(),
+            title: "tokio - Rust".to_string(),
+            content: "An event-driven, non-blocking I/O platform.".to_string(),
+            engine: Some("duckduckgo".to_string()),
+            engine_source: Some("duckduckgo".to_string()),
+            engine_sources: vec!["duckduckgo".to_string(), "bing".to_string()],
+            score: Some(0.87),
+            published_at: Some("2026-02-01".to_string()),
+            breadcrumbs: vec!["docs.rs".to_string(), "tokio".to_string()],
+            rich_snippet: None,
+            top_answer: Some("Tokio is an asynchronous runtime.".to_string()),
+            domain: Some("docs.rs".to_string()),
+            source_type: Some("docs".to_string()),
+            detected_language: Some("en".to_string()),
+            title_highlights: None,
+            content_highlights: None,
+        }];
+        // As `log_search` stores them.
+        let logged = serde_json::to_value(&results).unwrap();
+        let restored = results_from_history(&logged).expect("round trip");
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            logged,
+            "nothing lost in the round trip"
+        );
+
+        let capped = json!({ "content": "[{\"url\": \"https://docs.rs", "_truncated": {} });
+        assert!(results_from_history(&capped).is_none());
+    }
+}
@@ -0,0 +1,206 @@
+//! Response shaping for `search_web`: shorter snippets, a field whitelist and a byte cap,
+//! so large result lists fit a client's message limit.
+//!
+//! Snippets are trimmed after reranking (and after the search cache), so scoring and the
+//! cached entry always see the engines' full text. The field whitelist and the byte cap
+//! apply to the serialized response only. With none of them set the output is unchanged.
+
+use super::SearchOutputFormat;
+use crate::core::error::ScoutError;
+use crate::types::SearchResult;
+use serde_json::{Map, Value};
+
+/// Fields left out of a serialized result unless set.
+const OPTIONAL_FIELDS: &[&str] = &[
+    "detected_language",
+    "title_highlights",
+    "content_highlights",
+];
+
+/// Cut each result's `content` to at most `max_chars` characters, at a char boundary.
+pub fn trim_snippets(results: &mut [SearchResult], max_chars: usize) {
+    for result in results {
+        if let Some((cut, _)) = result.content.char_indices().nth(max_chars) {
+            result.content.truncate(cut);
+            result.content.truncate(result.content.trim_end().len());
+        }
+    }
+}
+
+/// The caller's `include_fields`, checked against the fields a result serializes to.
+pub fn parse_include_fields(fields: &[String]) -> Result<Vec<String>, ScoutError> {
+    let known = known_fields();
+    let mut parsed = Vec::new();
+    for field in fields.iter().map(|f| f.trim()).filter(|f| !f.is_empty()) {
+        if !known.iter().any(|k| k == field) {
+            return Err(ScoutError::invalid_input(format!(
+                "Unknown include_fields entry '{}': expected one of {}",
+                field,
+                known.join(", ")
+            )));
+        }
+        if !parsed.iter().any(|p| p == field) {
+            parsed.push(field.to_string());
+        }
+    }
+    Ok(parsed)
+}
+
+fn known_fields() -> Vec<String> {
+    let mut fields: Vec<String> = match serde_json::to_value(SearchResult::default()) {
+        Ok(Value::Object(map)) => map.keys().cloned().collect(),
+        _ => Vec::new(),
+    };
+    fields.extend(OPTIONAL_FIELDS.iter().map(|f| f.to_string()));
+    fields
+}
+
+/// `results` as JSON objects keeping only `include_fields` (all fields when empty).
+pub fn project(results: &[SearchResult], include_fields: &[String]) -> Vec<Value> {
+    results
+        .iter()
+        .map(|result| {
+            let value = serde_json::to_value(result).unwrap_or(Value::Null);
+            match value {
+                Value::Object(map) if !include_fields.is_empty() => Value::Object(
+                    map.into_iter()
+                        .filter(|(key, _)| include_fields.contains(key))
+                        .collect::<Map<_, _>>(),
+                ),
+                value => value,
+            }
+        })
+        .collect()
+}
+
+/// The JSON response body: `results` projected to `include_fields`, plus
+/// `truncated_results` when results were dropped for the byte cap.
+pub fn json_body(
+    results: &[SearchResult],
+    include_fields: &[String],
+    truncated_results: usize,
+) -> Value {
+    let mut body = serde_json::json!({ "results": project(results, include_fields) });
+    if truncated_results > 0 {
+        body["truncated_results"] = Value::from(truncated_results);
+    }
+    body
+}
+
+/// `format`'s usual rendering, except that shaped JSON goes through [`json_body`].
+pub fn render(
+    format: SearchOutputFormat,
+    query: &str,
+    results: &[SearchResult],
+    include_fields: &[String],
+    truncated_results: usize,
+) -> String {
+    match format {
+        SearchOutputFormat::Json if !include_fields.is_empty() || truncated_results > 0 => {
+            serde_json::to_string_pretty(&json_body(results, include_fields, truncated_results))
+                .unwrap_or_default()
+        }
+        _ => format.render(query, results),
+    }
+}
+
+/// Drop results from the end until `render` (given the kept results and how many were
+/// dropped) fits in `max_bytes`. Returns the rendering and the number dropped; when even
+/// an empty list does not fit, that rendering is returned anyway.
+pub fn fit_to_bytes(
+    results: &mut Vec<SearchResult>,
+    max_bytes: usize,
+    render: impl Fn(&[SearchResult], usize) -> String,
+) -> (String, usize) {
+    let total = results.len();
+    loop {
+        let dropped = total - results.len();
+        let rendered = render(results, dropped);
+        if rendered.len() <= max_bytes || results.is_empty() {
+            return (rendered, dropped);
+        }
+        results.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(n: usize, content: &str) -> SearchResult {
+        SearchResult {
+            url: format!("https://example.com/{}", n),
+            title: format!("Result {}", n),
+            content: content.to_string(),
+            engine: Some("bing".to_string()),
+            score: Some(1.0 / n as f64),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn snippets_are_trimmed_on_char_boundaries() {
+        let mut results = vec![
+            result(1, "Zürich café — naïve façade ☕ and more words after"),
+            result(2, "short"),
+        ];
+        trim_snippets(&mut results, 14);
+        assert_eq!(results[0].content, "Zürich café —");
+        assert_eq!(results[1].content, "short");
+
+        let mut emoji = vec![result(3, "🦀🦀🦀🦀")];
+        trim_snippets(&mut emoji, 3);
+        assert_eq!(emoji[0].content, "🦀🦀🦀");
+    }
+
+    #[test]
+    fn include_fields_whitelist_the_serialized_keys() {
+        let fields = parse_include_fields(&[
+            "title".to_string(),
+            " url ".to_string(),
+            "score".to_string(),
+            "title".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(fields, ["title", "url", "score"]);
+        let projected = project(&[result(1, "snippet")], &fields);
+        assert_eq!(
+            projected[0],
+            serde_json::json!({"title": "Result 1", "url": "https://example.com/1", "score": 1.0})
+        );
+        // Optional fields are accepted even though a bare result omits them.
+        assert!(parse_include_fields(&["content_highlights".to_string()]).is_ok());
+        let err = parse_include_fields(&["snippet".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("'snippet'"), "{}", err);
+        // No whitelist keeps every field.
+        assert_eq!(
+            project(&[result(1, "snippet")], &[])[0],
+            serde_json::to_value(result(1, "snippet")).unwrap()
+        );
+    }
+
+    #[test]
+    fn byte_cap_drops_trailing_results_and_counts_them() {
+        let mut results: Vec<_> = (1..=40).map(|n| result(n, &"x".repeat(500))).collect();
+        let json = |kept: &[SearchResult], dropped: usize| {
+            render(SearchOutputFormat::Json, "q", kept, &[], dropped)
+        };
+        let (body, dropped) = fit_to_bytes(&mut results, 4_000, json);
+        assert!(body.len() <= 4_000, "{}", body.len());
+        assert_eq!(results.len() + dropped, 40);
+        assert!(!results.is_empty() && dropped > 0);
+        assert_eq!(
+            results.last().unwrap().url,
+            format!("https://example.com/{}", results.len())
+        );
+        let parsed: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["truncated_results"], dropped);
+        assert_eq!(parsed["results"].as_array().unwrap().len(), results.len());
+
+        // Everything fits: nothing dropped, and the body is the unshaped one.
+        let mut few = vec![result(1, "tiny")];
+        let (body, dropped) = fit_to_bytes(&mut few, 4_000, json);
+        assert_eq!((few.len(), dropped), (1, 0));
+        assert_eq!(body, SearchOutputFormat::Json.render("q", &few));
+    }
+}
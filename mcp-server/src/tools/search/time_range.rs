@@ -0,0 +1,52 @@
+//! Recency windows for `SearchParamOverrides.time_range`.
+//!
+//! Engines with a date filter translate the window into their own URL parameter (see
+//! `SearchEngine::time_range_param`); the others run unfiltered. Engine-side filtering
+//! is best-effort, so scoring also demotes results whose `published_at` predates the
+//! window.
+
+use chrono::NaiveDate;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeRange {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl TimeRange {
+    pub fn parse_str(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            "year" => Some(Self::Year),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+        }
+    }
+
+    /// Window length in days, matching `history_search::time_range_start`.
+    pub fn days(self) -> i64 {
+        match self {
+            Self::Day => 1,
+            Self::Week => 7,
+            Self::Month => 30,
+            Self::Year => 365,
+        }
+    }
+
+    /// Whether something published on `date` is older than the window ending `today`.
+    pub fn excludes(self, date: NaiveDate, today: NaiveDate) -> bool {
+        (today - date).num_days() > self.days()
+    }
+}
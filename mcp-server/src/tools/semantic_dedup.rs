@@ -0,0 +1,508 @@
+//! Semantic deduplication of a batch or crawl (`semantic_dedup`).
+//!
+//! Print views, tracking-tagged variants and mirrored category pages have different URLs,
+//! so URL normalization keeps them all. Here each successful page's title and opening text
+//! are embedded with the semantic memory's Model2Vec model, and a page at least
+//! `threshold`-similar to an earlier one is collapsed into it: the earlier page lists it in
+//! `duplicates_of` and the response lists it in `duplicates`. With the `history` scope a
+//! page can also collapse into a past scrape from LanceDB.
+//!
+//! Like `min_quality` and `content_filter` this runs on the finished response, so every
+//! page is still cached and saved with the crawl state.
+
+use crate::core::error::ScoutError;
+use crate::history::MemoryManager;
+use crate::nlp::semantic_shave::cosine_similarity;
+use crate::types::*;
+use crate::AppState;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+/// Characters of `clean_content` embedded after the title.
+const EMBED_CHARS: usize = 1000;
+
+/// Past scrapes fetched per page; the best one not in the job is used.
+const HISTORY_CANDIDATES: usize = 5;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DedupScope {
+    /// Pages are compared with the other pages of the same batch or crawl.
+    #[default]
+    Job,
+    /// Also with past scrapes in semantic memory.
+    History,
+}
+
+impl DedupScope {
+    pub fn parse_str(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "job" => Some(DedupScope::Job),
+            "history" => Some(DedupScope::History),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SemanticDedup {
+    pub threshold: f32,
+    pub scope: DedupScope,
+}
+
+impl SemanticDedup {
+    pub const DEFAULT_THRESHOLD: f32 = 0.9;
+
+    /// From the `dedup_threshold` (0-1], default [`Self::DEFAULT_THRESHOLD`]) and
+    /// `dedup_scope` (`job` or `history`) arguments.
+    pub fn new(threshold: Option<f64>, scope: Option<&str>) -> Result<Self, ScoutError> {
+        let threshold = match threshold {
+            None => Self::DEFAULT_THRESHOLD,
+            Some(t) if t > 0.0 && t <= 1.0 => t as f32,
+            Some(_) => {
+                return Err(ScoutError::invalid_input(
+                    "dedup_threshold must be greater than 0 and at most 1",
+                ))
+            }
+        };
+        let scope = match scope {
+            None => DedupScope::Job,
+            Some(raw) => DedupScope::parse_str(raw).ok_or_else(|| {
+                ScoutError::invalid_input(format!(
+                    "Unknown dedup_scope '{}': expected job or history",
+                    raw
+                ))
+            })?,
+        };
+        Ok(Self { threshold, scope })
+    }
+
+    /// Collapse near-identical successful pages of a batch. Repeats of a collapsed page
+    /// (same page reached twice) go with it.
+    pub async fn apply_to_batch(
+        &self,
+        memory: &MemoryManager,
+        response: &mut ScrapeBatchResponse,
+    ) -> Result<()> {
+        let pages: Vec<(String, String)> = response
+            .results
+            .iter()
+            .filter(|result| result.success)
+            .filter_map(|result| {
+                let data = result.data.as_ref()?;
+                Some((
+                    result.url.clone(),
+                    page_text(&data.title, &data.clean_content),
+                ))
+            })
+            .collect();
+        let duplicates = self.find(memory, &pages).await?;
+        collapse_batch(response, duplicates);
+        Ok(())
+    }
+
+    /// Collapse near-identical successful pages of a crawl, compared on their content
+    /// preview.
+    pub async fn apply_to_crawl(
+        &self,
+        memory: &MemoryManager,
+        response: &mut CrawlResponse,
+    ) -> Result<()> {
+        let pages: Vec<(String, String)> = response
+            .results
+            .iter()
+            .filter(|page| page.success)
+            .map(|page| {
+                let title = page.title.as_deref().unwrap_or("");
+                let preview = page.content_preview.as_deref().unwrap_or("");
+                (page.url.clone(), page_text(title, preview))
+            })
+            .collect();
+        let duplicates = self.find(memory, &pages).await?;
+        collapse_crawl(response, duplicates);
+        Ok(())
+    }
+
+    /// The pages (url, text) that collapse into another one.
+    async fn find(
+        &self,
+        memory: &MemoryManager,
+        pages: &[(String, String)],
+    ) -> Result<Vec<SemanticDuplicate>> {
+        let texts = pages.iter().map(|(_, text)| text.clone()).collect();
+        let embeddings = memory.embed_texts(texts).await?;
+        let urls: Vec<String> = pages.iter().map(|(url, _)| url.clone()).collect();
+        let mut plan = job_duplicates(&urls, &embeddings, self.threshold);
+
+        if self.scope == DedupScope::History {
+            // The job's own pages are in history too, logged as they were scraped.
+            let job_urls: HashSet<&str> = urls.iter().map(String::as_str).collect();
+            for (index, url) in urls.iter().enumerate() {
+                if plan[index].is_some() {
+                    continue;
+                }
+                let past = memory
+                    .nearest_scrapes(&embeddings[index], HISTORY_CANDIDATES)
+                    .await?
+                    .into_iter()
+                    .find(|(entry, similarity)| {
+                        *similarity >= self.threshold && !job_urls.contains(entry.query.as_str())
+                    });
+                if let Some((entry, similarity)) = past {
+                    collapse_into_history(&mut plan, index, url, &entry.query, similarity);
+                }
+            }
+        }
+
+        let duplicates: Vec<SemanticDuplicate> = plan.into_iter().flatten().collect();
+        info!(
+            "semantic_dedup (threshold {}): collapsed {} of {} pages",
+            self.threshold,
+            duplicates.len(),
+            pages.len()
+        );
+        Ok(duplicates)
+    }
+}
+
+/// The semantic memory `semantic_dedup` embeds with, or an error when the server runs
+/// without it. Checked before the batch or crawl starts.
+pub async fn memory(state: &AppState) -> Result<Arc<MemoryManager>, ScoutError> {
+    state
+        .get_memory_or_wait(Duration::from_secs(8))
+        .await
+        .ok_or_else(|| {
+            ScoutError::unavailable(
+                "semantic_dedup needs semantic memory (LanceDB), which is not configured on this server",
+            )
+        })
+}
+
+/// Title plus the first [`EMBED_CHARS`] characters of the content.
+fn page_text(title: &str, content: &str) -> String {
+    let opening: String = content.chars().take(EMBED_CHARS).collect();
+    format!("{}\n{}", title.trim(), opening.trim())
+}
+
+fn round(similarity: f32) -> f32 {
+    (similarity * 1000.0).round() / 1000.0
+}
+
+/// For each page, the earlier page it repeats. Pages are taken in order and compared with
+/// the ones kept so far; a page joins the most similar at or above `threshold`.
+fn job_duplicates(
+    urls: &[String],
+    embeddings: &[Vec<f32>],
+    threshold: f32,
+) -> Vec<Option<SemanticDuplicate>> {
+    let mut kept: Vec<usize> = Vec::new();
+    embeddings
+        .iter()
+        .enumerate()
+        .map(|(index, embedding)| {
+            let best = kept
+                .iter()
+                .map(|&k| (k, cosine_similarity(&embeddings[k], embedding)))
+                .filter(|(_, similarity)| *similarity >= threshold)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            match best {
+                Some((k, similarity)) => Some(SemanticDuplicate {
+                    url: urls[index].clone(),
+                    duplicate_of: urls[k].clone(),
+                    similarity: round(similarity),
+                    from_history: false,
+                }),
+                None => {
+                    kept.push(index);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Collapse the kept page `plan[index]` into the past scrape `past_url`, along with the
+/// pages already collapsed into it.
+fn collapse_into_history(
+    plan: &mut [Option<SemanticDuplicate>],
+    index: usize,
+    url: &str,
+    past_url: &str,
+    similarity: f32,
+) {
+    for duplicate in plan.iter_mut().flatten() {
+        if duplicate.duplicate_of == url {
+            duplicate.duplicate_of = past_url.to_string();
+            duplicate.from_history = true;
+        }
+    }
+    plan[index] = Some(SemanticDuplicate {
+        url: url.to_string(),
+        duplicate_of: past_url.to_string(),
+        similarity: round(similarity),
+        from_history: true,
+    });
+}
+
+/// URLs collapsed into each page of the job.
+fn collapsed_into(duplicates: &[SemanticDuplicate]) -> HashMap<&str, Vec<String>> {
+    let mut into: HashMap<&str, Vec<String>> = HashMap::new();
+    for duplicate in duplicates.iter().filter(|d| !d.from_history) {
+        into.entry(duplicate.duplicate_of.as_str())
+            .or_default()
+            .push(duplicate.url.clone());
+    }
+    into
+}
+
+fn collapse_batch(response: &mut ScrapeBatchResponse, duplicates: Vec<SemanticDuplicate>) {
+    let by_url: HashMap<&str, &SemanticDuplicate> =
+        duplicates.iter().map(|d| (d.url.as_str(), d)).collect();
+    let mut listed = Vec::new();
+    response.results.retain(|result| {
+        // A repeat carries no data and stands for the page it points at.
+        let page = match &result.data {
+            Some(_) if result.success => Some(result.url.as_str()),
+            Some(_) => None,
+            None => result.duplicate_of.as_deref(),
+        };
+        match page.and_then(|page| by_url.get(page)) {
+            Some(duplicate) => {
+                listed.push(SemanticDuplicate {
+                    url: result.url.clone(),
+                    ..(*duplicate).clone()
+                });
+                false
+            }
+            None => true,
+        }
+    });
+    let mut into = collapsed_into(&listed);
+    for result in response.results.iter_mut().filter(|r| r.data.is_some()) {
+        if let Some(urls) = into.remove(result.url.as_str()) {
+            result.duplicates_of = urls;
+        }
+    }
+    response.successful = response.results.iter().filter(|r| r.success).count();
+    response.duplicates.extend(listed);
+}
+
+fn collapse_crawl(response: &mut CrawlResponse, duplicates: Vec<SemanticDuplicate>) {
+    let collapsed: HashSet<&str> = duplicates.iter().map(|d| d.url.as_str()).collect();
+    response
+        .results
+        .retain(|page| !(page.success && collapsed.contains(page.url.as_str())));
+    let mut into = collapsed_into(&duplicates);
+    for page in response.results.iter_mut() {
+        if let Some(urls) = into.remove(page.url.as_str()) {
+            page.duplicates_of = urls;
+        }
+    }
+    response.duplicates.extend(duplicates);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic stand-in for the model: a bag of words hashed into 64 buckets, so
+    /// shared vocabulary is what makes two pages similar.
+    fn stub_embed(text: &str) -> Vec<f32> {
+        let mut buckets = vec![0.0; 64];
+        for word in text
+            .to_ascii_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            let hash: usize = word.bytes().map(usize::from).sum();
+            buckets[hash % 64] += 1.0;
+        }
+        buckets
+    }
+
+    fn plan_for(pages: &[(&str, &str, &str)], threshold: f32) -> Vec<Option<SemanticDuplicate>> {
+        let urls: Vec<String> = pages.iter().map(|(url, _, _)| url.to_string()).collect();
+        let embeddings: Vec<Vec<f32>> = pages
+            .iter()
+            .map(|(_, title, content)| stub_embed(&page_text(title, content)))
+            .collect();
+        job_duplicates(&urls, &embeddings, threshold)
+    }
+
+    const ARTICLE: &str = "Rust ownership rules: each value has a single owner, \
+        and the value is dropped when the owner goes out of scope.";
+    const OTHER: &str = "Quarterly pricing update for the enterprise billing plans \
+        with new invoicing options.";
+
+    fn batch_page(url: &str, title: &str, content: &str) -> ScrapeBatchResult {
+        let data = ScrapeResponse::for_test(url, title, content);
+        ScrapeBatchResult {
+            url: url.to_string(),
+            success: true,
+            data: Some(data),
+            error: None,
+            failure_reason: None,
+            duplicate_of: None,
+            duration_ms: 1,
+            duplicates_of: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn near_identical_pages_collapse_into_the_first() {
+        let pages = [
+            ("https://example.com/ownership", "Ownership", ARTICLE),
+            ("https://example.com/pricing", "Pricing", OTHER),
+            (
+                "https://example.com/ownership?print=1",
+                "Ownership",
+                ARTICLE,
+            ),
+            (
+                "https://mirror.example.org/rust/ownership",
+                "Ownership - Mirror",
+                ARTICLE,
+            ),
+        ];
+        let plan = plan_for(&pages, 0.95);
+        assert!(plan[0].is_none() && plan[1].is_none());
+        let print = plan[2].as_ref().unwrap();
+        assert_eq!(print.duplicate_of, "https://example.com/ownership");
+        assert_eq!(print.similarity, 1.0);
+        assert!(!print.from_history);
+        let mirror = plan[3].as_ref().unwrap();
+        assert_eq!(mirror.duplicate_of, "https://example.com/ownership");
+        assert!(mirror.similarity >= 0.95 && mirror.similarity < 1.0);
+
+        // A strict threshold keeps the retitled mirror.
+        assert!(plan_for(&pages, 0.9999)[3].is_none());
+
+        let mut response = ScrapeBatchResponse {
+            total: 5,
+            successful: 5,
+            failed: 0,
+            total_duration_ms: 10,
+            results: pages
+                .iter()
+                .map(|(url, title, content)| batch_page(url, title, content))
+                .chain([ScrapeBatchResult {
+                    url: "https://example.com/ownership?print=1#top".to_string(),
+                    success: true,
+                    data: None,
+                    error: None,
+                    failure_reason: None,
+                    duplicate_of: Some("https://example.com/ownership?print=1".to_string()),
+                    duration_ms: 0,
+                    duplicates_of: Vec::new(),
+                }])
+                .collect(),
+            dropped_low_quality: Vec::new(),
+            duplicates: Vec::new(),
+        };
+        collapse_batch(&mut response, plan.into_iter().flatten().collect());
+
+        let kept: Vec<_> = response.results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            kept,
+            [
+                "https://example.com/ownership",
+                "https://example.com/pricing"
+            ]
+        );
+        assert_eq!(
+            response.results[0].duplicates_of,
+            [
+                "https://example.com/ownership?print=1",
+                "https://mirror.example.org/rust/ownership",
+                "https://example.com/ownership?print=1#top"
+            ]
+        );
+        assert!(response.results[1].duplicates_of.is_empty());
+        assert_eq!(response.successful, 2);
+        assert_eq!(response.duplicates.len(), 3);
+        assert_eq!(
+            response.duplicates[2].duplicate_of,
+            "https://example.com/ownership"
+        );
+    }
+
+    #[test]
+    fn a_page_seen_before_collapses_with_its_group_into_history() {
+        let pages = [
+            ("https://example.com/ownership", "Ownership", ARTICLE),
+            (
+                "https://example.com/ownership?utm_source=x",
+                "Ownership",
+                ARTICLE,
+            ),
+            ("https://example.com/pricing", "Pricing", OTHER),
+        ];
+        let mut plan = plan_for(&pages, 0.9);
+        collapse_into_history(
+            &mut plan,
+            0,
+            "https://example.com/ownership",
+            "https://old.example.com/ownership",
+            0.97,
+        );
+        let duplicates: Vec<_> = plan.into_iter().flatten().collect();
+        assert_eq!(duplicates.len(), 2);
+        assert!(duplicates
+            .iter()
+            .all(|d| d.from_history && d.duplicate_of == "https://old.example.com/ownership"));
+
+        let page = |url: &str| CrawlPageResult {
+            url: url.to_string(),
+            depth: 1,
+            success: true,
+            title: None,
+            word_count: None,
+            links_found: None,
+            content_preview: None,
+            error: None,
+            duration_ms: 1,
+            priority: None,
+            parent_url: None,
+            status_code: Some(200),
+            outlinks: Vec::new(),
+            relevance: None,
+            quality: None,
+            duplicates_of: Vec::new(),
+        };
+        let mut response: CrawlResponse = serde_json::from_value(serde_json::json!({
+            "start_url": "https://example.com/", "pages_crawled": 3, "pages_failed": 0,
+            "max_depth_reached": 1, "total_duration_ms": 10, "unique_domains": [],
+            "results": []
+        }))
+        .unwrap();
+        response.results = pages.iter().map(|(url, _, _)| page(url)).collect();
+        collapse_crawl(&mut response, duplicates);
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(response.results[0].url, "https://example.com/pricing");
+        assert!(response.results[0].duplicates_of.is_empty());
+        assert_eq!(response.duplicates.len(), 2);
+    }
+
+    #[test]
+    fn arguments_are_validated() {
+        let dedup = SemanticDedup::new(None, None).unwrap();
+        assert_eq!(dedup.threshold, SemanticDedup::DEFAULT_THRESHOLD);
+        assert_eq!(dedup.scope, DedupScope::Job);
+        let dedup = SemanticDedup::new(Some(0.8), Some("History")).unwrap();
+        assert_eq!(dedup.scope, DedupScope::History);
+        assert!(SemanticDedup::new(Some(0.0), None).is_err());
+        assert!(SemanticDedup::new(Some(1.5), None).is_err());
+        let err = SemanticDedup::new(None, Some("site")).unwrap_err();
+        assert!(err.to_string().contains("'site'"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn rejected_without_semantic_memory() {
+        let state = AppState::new(reqwest::Client::new());
+        state.mark_memory_failed();
+        let err = memory(&state).await.unwrap_err();
+        assert!(err.to_string().contains("semantic memory"), "{}", err);
+    }
+}